@@ -0,0 +1,187 @@
+//! Per-host accounts from the `[auth]` config
+//!
+//! Maps each host to a named account and each account to where its token
+//! comes from, so one machine can use a personal token for github.com and a
+//! work token for a GitHub Enterprise instance.
+
+use crate::config::AuthConfig;
+use crate::error::{Error, Result};
+use crate::hooks::shell;
+use std::collections::HashMap;
+use std::env;
+use std::process::Stdio;
+use std::sync::{Arc, LazyLock, Mutex};
+use tokio::sync::OnceCell;
+use tracing::debug;
+
+/// Tokens printed by `token_command`s, keyed by command
+///
+/// Secret managers can be slow or prompt for unlocking, so each command runs
+/// at most once per process. Concurrent lookups of the same command wait on
+/// its cell rather than running it again.
+static COMMAND_TOKENS: LazyLock<Mutex<HashMap<String, Arc<OnceCell<String>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Token of the account configured for `host`, if one is
+///
+/// Returns `Ok(None)` when no account is mapped to the host, so the usual
+/// sources apply. An account that yields no token is an error rather than a
+/// fallback, so ryu never acts as the wrong identity.
+pub async fn account_token(auth: &AuthConfig, host: &str) -> Result<Option<String>> {
+    let Some(name) = auth.hosts.get(host) else {
+        return Ok(None);
+    };
+    let account = auth.accounts.get(name).ok_or_else(|| {
+        Error::Config(format!(
+            "auth.hosts maps {host} to account '{name}', which isn't defined in [auth.accounts]"
+        ))
+    })?;
+
+    if let Some(command) = &account.token_command {
+        return command_token(command)
+            .await
+            .map(Some)
+            .map_err(|e| Error::Auth(format!("Account '{name}' for {host}: {e}")));
+    }
+    let Some(var) = &account.token_env else {
+        return Err(Error::Config(format!(
+            "account '{name}' has no token source; set auth.accounts.{name}.token_command \
+             or token_env"
+        )));
+    };
+    match env::var(var) {
+        Ok(token) if !token.trim().is_empty() => Ok(Some(token.trim().to_string())),
+        _ => Err(Error::Auth(format!(
+            "Account '{name}' for {host} reads its token from {var}, which isn't set"
+        ))),
+    }
+}
+
+/// Token printed by `command`, running it only the first time it's asked for
+///
+/// A failed run isn't cached, so the next lookup tries again.
+async fn command_token(command: &str) -> Result<String> {
+    let cell = {
+        let mut cells = COMMAND_TOKENS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        Arc::clone(cells.entry(command.to_string()).or_default())
+    };
+    cell.get_or_try_init(|| run_token_command(command))
+        .await
+        .cloned()
+}
+
+async fn run_token_command(command: &str) -> Result<String> {
+    debug!(command, "running token command");
+    // stderr is left attached so secret managers can prompt
+    let output = shell(command)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .await
+        .map_err(|e| Error::Auth(format!("token command failed to start: {e}")))?;
+    if !output.status.success() {
+        return Err(Error::Auth(format!(
+            "token command exited with {}",
+            output.status
+        )));
+    }
+
+    let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if token.is_empty() {
+        return Err(Error::Auth("token command printed no token".to_string()));
+    }
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AccountConfig;
+
+    fn auth_config(account: AccountConfig) -> AuthConfig {
+        let mut auth = AuthConfig::default();
+        auth.hosts.insert("ghe.corp.com".to_string(), "work".to_string());
+        auth.accounts.insert("work".to_string(), account);
+        auth
+    }
+
+    fn env_account(var: &str) -> AccountConfig {
+        AccountConfig {
+            token_env: Some(var.to_string()),
+            ..AccountConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unmapped_host_has_no_account() {
+        let auth = auth_config(env_account("RYU_TEST_UNSET_WORK_TOKEN"));
+        assert_eq!(account_token(&auth, "github.com").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_missing_account_token_is_an_error() {
+        let auth = auth_config(env_account("RYU_TEST_UNSET_WORK_TOKEN"));
+        let err = account_token(&auth, "ghe.corp.com").await.unwrap_err();
+        assert!(matches!(err, Error::Auth(ref msg) if msg.contains("RYU_TEST_UNSET_WORK_TOKEN")));
+    }
+
+    #[tokio::test]
+    async fn test_undefined_account() {
+        let mut auth = auth_config(env_account("RYU_TEST_UNSET_WORK_TOKEN"));
+        auth.accounts.clear();
+        let err = account_token(&auth, "ghe.corp.com").await.unwrap_err();
+        assert!(matches!(err, Error::Config(ref msg) if msg.contains("'work'")));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_token_command_runs_once() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let log = temp.path().join("runs");
+        let auth = auth_config(AccountConfig {
+            token_command: Some(format!("echo run >> {} && echo ' tok-123 '", log.display())),
+            ..AccountConfig::default()
+        });
+
+        for _ in 0..2 {
+            let token = account_token(&auth, "ghe.corp.com").await.unwrap();
+            assert_eq!(token.as_deref(), Some("tok-123"));
+        }
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "run\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_concurrent_lookups_run_token_command_once() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let log = temp.path().join("runs");
+        let auth = auth_config(AccountConfig {
+            token_command: Some(format!(
+                "echo run >> {} && sleep 0.2 && echo tok-456",
+                log.display()
+            )),
+            ..AccountConfig::default()
+        });
+
+        let (first, second) = tokio::join!(
+            account_token(&auth, "ghe.corp.com"),
+            account_token(&auth, "ghe.corp.com")
+        );
+        assert_eq!(first.unwrap().as_deref(), Some("tok-456"));
+        assert_eq!(second.unwrap().as_deref(), Some("tok-456"));
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "run\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_failing_token_command() {
+        let auth = auth_config(AccountConfig {
+            token_command: Some("exit 3".to_string()),
+            ..AccountConfig::default()
+        });
+        let err = account_token(&auth, "ghe.corp.com").await.unwrap_err();
+        assert!(matches!(err, Error::Auth(ref msg) if msg.contains("exited")));
+    }
+}
@@ -0,0 +1,162 @@
+//! Hosts and tokens from the gh and glab config files
+//!
+//! `gh auth login --hostname` and `glab auth login --hostname` record each
+//! instance they're logged in to, so a GitHub Enterprise or self-managed
+//! GitLab host found there is known without setting `GH_HOST` or
+//! `GITLAB_HOST`. Tokens are only in the files when the CLI wasn't able to
+//! use the system keyring.
+
+use crate::config::HostPlatform;
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Hosts in gh's `hosts.yml`, with their tokens if stored there
+pub fn parse_gh_hosts(content: &str) -> BTreeMap<String, Option<String>> {
+    let Ok(Value::Mapping(hosts)) = serde_yaml::from_str::<Value>(content) else {
+        return BTreeMap::new();
+    };
+    hosts
+        .iter()
+        .filter_map(|(host, entry)| {
+            let token = entry.get("oauth_token").and_then(token_str);
+            Some((host.as_str()?.to_string(), token))
+        })
+        .collect()
+}
+
+/// Hosts in glab's `config.yml`, with their tokens if stored there
+pub fn parse_glab_hosts(content: &str) -> BTreeMap<String, Option<String>> {
+    // glab tags some values `!!null` even when they're set (`token: !!null
+    // glpat-...`), which isn't valid YAML for a string, so drop the tag
+    let content = content.replace("!!null", "");
+    let Ok(config) = serde_yaml::from_str::<Value>(&content) else {
+        return BTreeMap::new();
+    };
+    let Some(Value::Mapping(hosts)) = config.get("hosts") else {
+        return BTreeMap::new();
+    };
+    hosts
+        .iter()
+        .filter_map(|(host, entry)| {
+            let token = entry.get("token").and_then(token_str);
+            Some((host.as_str()?.to_string(), token))
+        })
+        .collect()
+}
+
+/// Token for `host` in gh's `hosts.yml`
+pub fn gh_config_token(host: &str) -> Option<String> {
+    let content = fs::read_to_string(gh_config_dir()?.join("hosts.yml")).ok()?;
+    parse_gh_hosts(&content).remove(host).flatten()
+}
+
+/// Token for `host` in glab's `config.yml`
+pub fn glab_config_token(host: &str) -> Option<String> {
+    glab_config_paths()
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .and_then(|content| parse_glab_hosts(&content).remove(host).flatten())
+}
+
+/// Self-hosted instances gh and glab are logged in to, for platform detection
+///
+/// The public github.com and gitlab.com are left out; they're recognized
+/// anyway.
+pub fn cli_config_hosts() -> BTreeMap<String, HostPlatform> {
+    let mut hosts = BTreeMap::new();
+    let glab = glab_config_paths()
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok());
+    if let Some(content) = glab {
+        for host in parse_glab_hosts(&content).into_keys() {
+            hosts.insert(host, HostPlatform::GitLab);
+        }
+    }
+    let gh = gh_config_dir().and_then(|dir| fs::read_to_string(dir.join("hosts.yml")).ok());
+    if let Some(content) = gh {
+        for host in parse_gh_hosts(&content).into_keys() {
+            hosts.insert(host, HostPlatform::GitHub);
+        }
+    }
+    hosts.remove("github.com");
+    hosts.remove("gitlab.com");
+    hosts
+}
+
+/// A token value, skipping the empty placeholders left when the token is in
+/// the keyring
+fn token_str(value: &Value) -> Option<String> {
+    value
+        .as_str()
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(String::from)
+}
+
+/// gh's config directory: `GH_CONFIG_DIR`, else `~/.config/gh`
+/// (`%AppData%\GitHub CLI` on Windows)
+fn gh_config_dir() -> Option<PathBuf> {
+    if let Ok(dir) = env::var("GH_CONFIG_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    if let Ok(dir) = env::var("XDG_CONFIG_HOME") {
+        return Some(Path::new(&dir).join("gh"));
+    }
+    if cfg!(windows) {
+        return dirs::config_dir().map(|dir| dir.join("GitHub CLI"));
+    }
+    dirs::home_dir().map(|home| home.join(".config").join("gh"))
+}
+
+/// Where glab's `config.yml` may be: `GLAB_CONFIG_DIR`, else
+/// `~/.config/glab-cli`, else the OS config directory
+fn glab_config_paths() -> Vec<PathBuf> {
+    if let Ok(dir) = env::var("GLAB_CONFIG_DIR") {
+        return vec![Path::new(&dir).join("config.yml")];
+    }
+    let xdg = env::var("XDG_CONFIG_HOME")
+        .ok()
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")));
+    [xdg, dirs::config_dir()]
+        .into_iter()
+        .flatten()
+        .map(|dir| dir.join("glab-cli").join("config.yml"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gh_hosts() {
+        let hosts = parse_gh_hosts(
+            "github.com:\n    user: octocat\n    git_protocol: https\n\
+             ghe.corp.com:\n    oauth_token: gho_work\n    user: octocat-work\n",
+        );
+
+        assert_eq!(hosts["github.com"], None);
+        assert_eq!(hosts["ghe.corp.com"].as_deref(), Some("gho_work"));
+    }
+
+    #[test]
+    fn test_parse_glab_hosts() {
+        let hosts = parse_glab_hosts(
+            "git_protocol: ssh\nhosts:\n    gitlab.com:\n        token: !!null\n\
+             \x20   git.corp.com:\n        token: !!null glpat-work\n        api_protocol: https\n",
+        );
+
+        assert_eq!(hosts["gitlab.com"], None);
+        assert_eq!(hosts["git.corp.com"].as_deref(), Some("glpat-work"));
+    }
+
+    #[test]
+    fn test_unparseable_config_has_no_hosts() {
+        assert!(parse_gh_hosts("not: [valid").is_empty());
+        assert!(parse_glab_hosts("hosts: 3").is_empty());
+    }
+}
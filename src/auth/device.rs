@@ -0,0 +1,261 @@
+//! OAuth device authorization flow (RFC 8628)
+//!
+//! Lets `ryu auth <platform> login` get a token without the vendor CLIs: the
+//! user enters a short code on the platform's site while ryu polls for the
+//! token. GitHub and GitLab both implement the flow for OAuth apps that have
+//! it enabled. GitLab's tokens expire after two hours and come with a refresh
+//! token, so the whole [`OAuthToken`] is kept to renew them.
+
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::debug;
+
+/// Grant type for polling the token endpoint
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Code the user enters to authorize ryu
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceCode {
+    /// Code ryu polls the token endpoint with
+    pub device_code: String,
+    /// Code the user types in at `verification_uri`
+    pub user_code: String,
+    /// Page where the user enters the code
+    pub verification_uri: String,
+    /// Seconds until the codes expire
+    pub expires_in: u64,
+    /// Minimum seconds between polls
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+}
+
+const fn default_interval() -> u64 {
+    5
+}
+
+/// Renew tokens this long before they expire, so they don't lapse mid-command
+const REFRESH_MARGIN_SECS: i64 = 5 * 60;
+
+/// Token endpoint response: a token, or why there isn't one yet
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// Access token from the device flow, with what's needed to renew it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuthToken {
+    /// Token sent with API requests
+    pub access_token: String,
+    /// Token that gets a new access token once this one expires
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// When the access token expires (None = it doesn't)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// OAuth app the token was issued to, needed to refresh it
+    pub client_id: String,
+}
+
+impl OAuthToken {
+    /// Parse a token saved with [`OAuthToken::to_stored`]
+    ///
+    /// Returns None for anything else, e.g. a personal access token.
+    pub fn from_stored(stored: &str) -> Option<Self> {
+        serde_json::from_str(stored).ok()
+    }
+
+    /// Serialize the token for the system keyring
+    pub fn to_stored(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Whether the access token expires within a few minutes
+    pub fn expires_soon(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| {
+            expires_at - chrono::Duration::seconds(REFRESH_MARGIN_SECS) <= Utc::now()
+        })
+    }
+}
+
+/// Device flow against one platform's OAuth endpoints
+#[derive(Debug, Clone)]
+pub struct DeviceFlow {
+    client: Client,
+    code_url: String,
+    token_url: String,
+    client_id: String,
+    scope: &'static str,
+}
+
+impl DeviceFlow {
+    /// Device flow for GitHub at `base_url` (e.g. `https://github.com`)
+    ///
+    /// Asks for the `repo` scope.
+    pub fn github(base_url: &str, client_id: &str) -> Result<Self> {
+        let base_url = base_url.trim_end_matches('/');
+        Self::new(
+            format!("{base_url}/login/device/code"),
+            format!("{base_url}/login/oauth/access_token"),
+            client_id,
+            "repo",
+        )
+    }
+
+    /// Device flow for GitLab at `base_url` (e.g. `https://gitlab.com`)
+    ///
+    /// Asks for the `api` scope.
+    pub fn gitlab(base_url: &str, client_id: &str) -> Result<Self> {
+        let base_url = base_url.trim_end_matches('/');
+        Self::new(
+            format!("{base_url}/oauth/authorize_device"),
+            format!("{base_url}/oauth/token"),
+            client_id,
+            "api",
+        )
+    }
+
+    fn new(
+        code_url: String,
+        token_url: String,
+        client_id: &str,
+        scope: &'static str,
+    ) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent("jj-ryu")
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| Error::Auth(format!("failed to create HTTP client: {e}")))?;
+        Ok(Self {
+            client,
+            code_url,
+            token_url,
+            client_id: client_id.to_string(),
+            scope,
+        })
+    }
+
+    /// Start the flow: get the code the user has to enter
+    pub async fn request_code(&self) -> Result<DeviceCode> {
+        debug!(url = %self.code_url, "requesting device code");
+        self.client
+            .post(&self.code_url)
+            .header("Accept", "application/json")
+            .form(&[("client_id", self.client_id.as_str()), ("scope", self.scope)])
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(|e| Error::Auth(format!("Failed to start device login: {e}")))?
+            .json()
+            .await
+            .map_err(|e| Error::Auth(format!("Unexpected device code response: {e}")))
+    }
+
+    /// Poll until the user authorizes ryu, returning the token
+    ///
+    /// Fails if the user denies access or the code expires first.
+    pub async fn poll_token(&self, code: &DeviceCode) -> Result<OAuthToken> {
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(code.expires_in);
+        let mut interval = Duration::from_secs(code.interval);
+
+        loop {
+            tokio::time::sleep(interval).await;
+            if tokio::time::Instant::now() >= deadline {
+                return Err(Error::Auth("Device login expired; run it again".to_string()));
+            }
+
+            // Errors come back as 200 on GitHub and 400 on GitLab, so the
+            // status isn't checked
+            let response: TokenResponse = self
+                .client
+                .post(&self.token_url)
+                .header("Accept", "application/json")
+                .form(&[
+                    ("client_id", self.client_id.as_str()),
+                    ("device_code", code.device_code.as_str()),
+                    ("grant_type", DEVICE_CODE_GRANT),
+                ])
+                .send()
+                .await?
+                .json()
+                .await
+                .map_err(|e| Error::Auth(format!("Unexpected token response: {e}")))?;
+
+            if let Some(token) = self.token_from(&response) {
+                return Ok(token);
+            }
+            match response.error.as_deref() {
+                Some("authorization_pending") => {}
+                Some("slow_down") => interval += Duration::from_secs(5),
+                Some("access_denied") => {
+                    return Err(Error::Auth("Device login was denied".to_string()));
+                }
+                Some("expired_token") => {
+                    return Err(Error::Auth("Device login expired; run it again".to_string()));
+                }
+                error => {
+                    let reason = response
+                        .error_description
+                        .or_else(|| error.map(String::from))
+                        .unwrap_or_else(|| "no token in response".to_string());
+                    return Err(Error::Auth(format!("Device login failed: {reason}")));
+                }
+            }
+        }
+    }
+
+    /// Exchange `token`'s refresh token for a new token
+    ///
+    /// The platform may rotate the refresh token too, so the returned token
+    /// replaces the old one entirely.
+    pub async fn refresh(&self, token: &OAuthToken) -> Result<OAuthToken> {
+        let Some(refresh_token) = &token.refresh_token else {
+            return Err(Error::Auth(
+                "Login expired and can't be renewed; log in again".to_string(),
+            ));
+        };
+
+        debug!(url = %self.token_url, "refreshing OAuth token");
+        let response: TokenResponse = self
+            .client
+            .post(&self.token_url)
+            .header("Accept", "application/json")
+            .form(&[
+                ("client_id", self.client_id.as_str()),
+                ("refresh_token", refresh_token.as_str()),
+                ("grant_type", "refresh_token"),
+            ])
+            .send()
+            .await?
+            .json()
+            .await
+            .map_err(|e| Error::Auth(format!("Unexpected token response: {e}")))?;
+
+        self.token_from(&response).ok_or_else(|| {
+            let reason = response
+                .error_description
+                .or(response.error)
+                .unwrap_or_else(|| "no token in response".to_string());
+            Error::Auth(format!("Failed to renew login ({reason}); log in again"))
+        })
+    }
+
+    /// The token in a token endpoint response, if it has one
+    fn token_from(&self, response: &TokenResponse) -> Option<OAuthToken> {
+        Some(OAuthToken {
+            access_token: response.access_token.clone()?,
+            refresh_token: response.refresh_token.clone(),
+            expires_at: response
+                .expires_in
+                .map(|secs| Utc::now() + chrono::Duration::seconds(secs)),
+            client_id: self.client_id.clone(),
+        })
+    }
+}
@@ -0,0 +1,138 @@
+//! Gerrit authentication
+//!
+//! Gerrit's REST API uses HTTP basic auth with the account's username and
+//! its generated HTTP password (Settings → HTTP Credentials).
+
+use crate::auth::AuthSource;
+use crate::error::{Error, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use std::env;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::debug;
+
+/// Prefix Gerrit puts before every JSON response to prevent XSSI
+pub const GERRIT_JSON_PREFIX: &str = ")]}'";
+
+/// Gerrit authentication configuration
+#[derive(Debug, Clone)]
+pub struct GerritAuthConfig {
+    /// Account username
+    pub username: String,
+    /// HTTP password
+    pub password: String,
+    /// Where the credentials were obtained from
+    pub source: AuthSource,
+    /// Gerrit host (e.g., "review.example.org")
+    pub host: String,
+}
+
+/// Get Gerrit authentication
+///
+/// Priority:
+/// 1. `GERRIT_USERNAME` and `GERRIT_PASSWORD` environment variables
+/// 2. git credential helpers (`git credential fill` for `https://{host}`)
+pub async fn get_gerrit_auth(host: Option<&str>) -> Result<GerritAuthConfig> {
+    let host = host
+        .map(String::from)
+        .or_else(|| env::var("GERRIT_HOST").ok())
+        .ok_or_else(|| {
+            Error::Auth("No Gerrit host known. Set GERRIT_HOST to your Gerrit hostname".to_string())
+        })?;
+
+    debug!(host = %host, "checking Gerrit env vars");
+    if let (Ok(username), Ok(password)) = (env::var("GERRIT_USERNAME"), env::var("GERRIT_PASSWORD"))
+    {
+        debug!("obtained Gerrit credentials from env vars");
+        return Ok(GerritAuthConfig {
+            username,
+            password,
+            source: AuthSource::EnvVar,
+            host,
+        });
+    }
+
+    debug!("Gerrit env vars not set, asking git credential helpers");
+    if let Some((username, password)) = get_git_credential(&host).await {
+        debug!("obtained Gerrit credentials from git credential helper");
+        return Ok(GerritAuthConfig {
+            username,
+            password,
+            source: AuthSource::Cli,
+            host,
+        });
+    }
+
+    debug!("no Gerrit authentication found");
+    Err(Error::Auth(
+        "No Gerrit authentication found. Set GERRIT_USERNAME and GERRIT_PASSWORD (your HTTP password)"
+            .to_string(),
+    ))
+}
+
+/// Ask git's credential helpers for `https://{host}`, without prompting
+async fn get_git_credential(host: &str) -> Option<(String, String)> {
+    let mut child = Command::new("git")
+        .args(["credential", "fill"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    let mut stdin = child.stdin.take()?;
+    stdin
+        .write_all(format!("protocol=https\nhost={host}\n\n").as_bytes())
+        .await
+        .ok()?;
+    drop(stdin);
+
+    let output = child.wait_with_output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let field = |name: &str| {
+        text.lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix('='))
+            .map(String::from)
+    };
+    Some((field("username")?, field("password")?))
+}
+
+#[derive(Deserialize)]
+struct GerritAccount {
+    username: Option<String>,
+    name: Option<String>,
+}
+
+/// Test Gerrit authentication
+pub async fn test_gerrit_auth(config: &GerritAuthConfig) -> Result<String> {
+    let url = format!("https://{}/a/accounts/self", config.host);
+
+    let client = Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| Error::GerritApi(format!("failed to create HTTP client: {e}")))?;
+
+    let text = client
+        .get(&url)
+        .basic_auth(&config.username, Some(&config.password))
+        .send()
+        .await?
+        .error_for_status()
+        .map_err(|e| Error::Auth(format!("Invalid credentials: {e}")))?
+        .text()
+        .await?;
+
+    let account: GerritAccount =
+        serde_json::from_str(text.trim_start_matches(GERRIT_JSON_PREFIX))?;
+    Ok(account
+        .username
+        .or(account.name)
+        .unwrap_or_else(|| config.username.clone()))
+}
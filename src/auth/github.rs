@@ -1,7 +1,12 @@
 //! GitHub authentication
 
-use crate::auth::AuthSource;
+use crate::auth::{
+    AuthSource, TokenStatus, account_token, gh_config_token, header_u64, keyring_token,
+};
+use crate::config::AuthConfig;
 use crate::error::{Error, Result};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
 use std::env;
 use tokio::process::Command;
 use tracing::debug;
@@ -15,22 +20,47 @@ pub struct GitHubAuthConfig {
     pub source: AuthSource,
 }
 
+/// Classic token scopes that allow managing PRs
+const REPO_SCOPES: &[&str] = &["repo", "public_repo"];
+
 /// Get GitHub authentication
 ///
-/// Priority:
-/// 1. gh CLI (`gh auth token`)
-/// 2. `GITHUB_TOKEN` environment variable
-/// 3. `GH_TOKEN` environment variable
+/// Priority, for `GH_HOST` (default `github.com`):
+/// 1. System keyring
+/// 2. gh CLI (`gh auth token`), or the token in gh's `hosts.yml`
+/// 3. `GITHUB_TOKEN` environment variable
+/// 4. `GH_TOKEN` environment variable
 pub async fn get_github_auth() -> Result<GitHubAuthConfig> {
-    // Try gh CLI first
-    debug!("attempting to get GitHub token via gh CLI");
-    if let Some(token) = get_gh_cli_token().await {
+    let host = env::var("GH_HOST").unwrap_or_else(|_| "github.com".to_string());
+    github_auth(&host).await
+}
+
+/// [`get_github_auth`] for a given host
+async fn github_auth(host: &str) -> Result<GitHubAuthConfig> {
+    if let Some(token) = keyring_token(host).await {
+        debug!(host = %host, "obtained GitHub token from system keyring");
+        return Ok(GitHubAuthConfig {
+            token,
+            source: AuthSource::Keyring,
+        });
+    }
+
+    // Try gh CLI next
+    debug!(host, "attempting to get GitHub token via gh CLI");
+    if let Some(token) = get_gh_cli_token(host).await {
         debug!("obtained GitHub token from gh CLI");
         return Ok(GitHubAuthConfig {
             token,
             source: AuthSource::Cli,
         });
     }
+    if let Some(token) = gh_config_token(host) {
+        debug!("obtained GitHub token from gh hosts.yml");
+        return Ok(GitHubAuthConfig {
+            token,
+            source: AuthSource::Cli,
+        });
+    }
 
     // Try environment variables
     debug!("gh CLI token not available, checking env vars");
@@ -56,13 +86,33 @@ pub async fn get_github_auth() -> Result<GitHubAuthConfig> {
     ))
 }
 
-async fn get_gh_cli_token() -> Option<String> {
+/// Get GitHub authentication for `host`, honoring `[auth]` accounts
+///
+/// If the config maps the host (default `github.com`) to an account, that
+/// account's token is used; otherwise the sources of [`get_github_auth`] are
+/// tried for the host.
+pub async fn get_github_auth_for_host(
+    host: Option<&str>,
+    auth: &AuthConfig,
+) -> Result<GitHubAuthConfig> {
+    let host = host.unwrap_or("github.com");
+    if let Some(token) = account_token(auth, host).await? {
+        debug!(host, "obtained GitHub token from configured account");
+        return Ok(GitHubAuthConfig {
+            token,
+            source: AuthSource::Account,
+        });
+    }
+    github_auth(host).await
+}
+
+async fn get_gh_cli_token(host: &str) -> Option<String> {
     // Check gh is available
     Command::new("gh").arg("--version").output().await.ok()?;
 
     // Check authenticated
     let status = Command::new("gh")
-        .args(["auth", "status"])
+        .args(["auth", "status", "--hostname", host])
         .output()
         .await
         .ok()?;
@@ -73,7 +123,7 @@ async fn get_gh_cli_token() -> Option<String> {
 
     // Get token
     let output = Command::new("gh")
-        .args(["auth", "token"])
+        .args(["auth", "token", "--hostname", host])
         .output()
         .await
         .ok()?;
@@ -101,3 +151,108 @@ pub async fn test_github_auth(config: &GitHubAuthConfig) -> Result<String> {
 
     Ok(user.login)
 }
+
+/// Check that a GitHub token can manage PRs, before it's used for real
+///
+/// `api_url` is the REST API root (`https://api.github.com`, or
+/// `https://<host>/api/v3` on GitHub Enterprise). Classic tokens report their
+/// scopes, which must include `repo` (or `public_repo`). Fine-grained and app
+/// tokens don't; they pass, and missing permissions surface as API errors.
+pub async fn validate_github_token(token: &str, api_url: &str) -> Result<()> {
+    let client = Client::builder()
+        .user_agent("jj-ryu")
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| Error::GitHubApi(format!("failed to create HTTP client: {e}")))?;
+
+    let response = client
+        .get(format!("{api_url}/user"))
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?;
+
+    if response.status() == StatusCode::UNAUTHORIZED {
+        return Err(Error::Auth(
+            "GitHub rejected the token. Run `gh auth login` or set a valid GITHUB_TOKEN"
+                .to_string(),
+        ));
+    }
+
+    let Some(scopes) = response
+        .headers()
+        .get("x-oauth-scopes")
+        .and_then(|v| v.to_str().ok())
+    else {
+        debug!("token reports no scopes, skipping scope check");
+        return Ok(());
+    };
+
+    let scopes: Vec<&str> = scopes
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    if scopes.iter().any(|scope| REPO_SCOPES.contains(scope)) {
+        return Ok(());
+    }
+
+    let granted = if scopes.is_empty() {
+        "none".to_string()
+    } else {
+        scopes.join(", ")
+    };
+    Err(Error::Auth(format!(
+        "GitHub token is missing the `repo` scope (it has: {granted}). \
+         Run `gh auth refresh -s repo` or create a token with the `repo` scope"
+    )))
+}
+
+#[derive(Deserialize)]
+struct GitHubUser {
+    login: String,
+}
+
+/// Look up who a GitHub token belongs to, its scopes and its rate limit
+///
+/// `api_url` is the REST API root, as for [`validate_github_token`]. Scopes
+/// are None for fine-grained and app tokens, which don't report them.
+pub async fn github_token_status(token: &str, api_url: &str) -> Result<TokenStatus> {
+    let client = Client::builder()
+        .user_agent("jj-ryu")
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| Error::GitHubApi(format!("failed to create HTTP client: {e}")))?;
+
+    let response = client
+        .get(format!("{api_url}/user"))
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?
+        .error_for_status()
+        .map_err(|e| Error::Auth(format!("Invalid token: {e}")))?;
+
+    let headers = response.headers();
+    let scopes = headers
+        .get("x-oauth-scopes")
+        .and_then(|v| v.to_str().ok())
+        .map(|scopes| {
+            scopes
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect()
+        });
+    let rate_limit_remaining = header_u64(headers, "x-ratelimit-remaining");
+    let rate_limit = header_u64(headers, "x-ratelimit-limit");
+    let user: GitHubUser = response.json().await?;
+
+    Ok(TokenStatus {
+        login: user.login,
+        scopes,
+        rate_limit_remaining,
+        rate_limit,
+    })
+}
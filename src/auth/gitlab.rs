@@ -1,18 +1,52 @@
 //! GitLab authentication
 
-use crate::auth::AuthSource;
+use crate::auth::{
+    AuthSource, DeviceFlow, OAuthToken, TokenStatus, account_token, glab_config_token, header_u64,
+    keyring_token, store_keyring_token,
+};
+use crate::config::AuthConfig;
 use crate::error::{Error, Result};
-use reqwest::Client;
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
+use reqwest::{Client, StatusCode};
 use serde::Deserialize;
 use std::env;
 use tokio::process::Command;
 use tracing::debug;
 
+/// How a GitLab token is sent with API requests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GitLabTokenKind {
+    /// Personal, project or group access token, sent as `PRIVATE-TOKEN`
+    #[default]
+    Personal,
+    /// OAuth access token (`ryu auth gitlab login`), sent as a bearer token
+    OAuth,
+}
+
+impl GitLabTokenKind {
+    /// Headers that authenticate a request with `token`
+    pub fn headers(self, token: &str) -> HeaderMap {
+        let (name, value) = match self {
+            Self::Personal => ("PRIVATE-TOKEN", token.to_string()),
+            Self::OAuth => (AUTHORIZATION.as_str(), format!("Bearer {token}")),
+        };
+
+        let mut headers = HeaderMap::new();
+        if let Ok(mut value) = HeaderValue::from_str(&value) {
+            value.set_sensitive(true);
+            headers.insert(name, value);
+        }
+        headers
+    }
+}
+
 /// GitLab authentication configuration
 #[derive(Debug, Clone)]
 pub struct GitLabAuthConfig {
     /// Authentication token
     pub token: String,
+    /// How the token is sent
+    pub kind: GitLabTokenKind,
     /// Where the token was obtained from
     pub source: AuthSource,
     /// GitLab host (e.g., "gitlab.com")
@@ -22,21 +56,47 @@ pub struct GitLabAuthConfig {
 /// Get GitLab authentication
 ///
 /// Priority:
-/// 1. glab CLI (`glab auth token`)
-/// 2. `GITLAB_TOKEN` environment variable
-/// 3. `GL_TOKEN` environment variable
+/// 1. System keyring, for the host. Expiring logins from `ryu auth gitlab
+///    login` are renewed with their refresh token
+/// 2. glab CLI (`glab auth token`), or the token in glab's `config.yml`
+/// 3. `GITLAB_TOKEN` environment variable
+/// 4. `GL_TOKEN` environment variable
 pub async fn get_gitlab_auth(host: Option<&str>) -> Result<GitLabAuthConfig> {
     let host = host
         .map(String::from)
         .or_else(|| env::var("GITLAB_HOST").ok())
         .unwrap_or_else(|| "gitlab.com".to_string());
 
-    // Try glab CLI first
+    if let Some(token) = keyring_token(&host).await {
+        debug!(host = %host, "obtained GitLab token from system keyring");
+        let (token, kind) = match OAuthToken::from_stored(&token) {
+            Some(oauth) => (fresh_oauth_token(&host, oauth).await?, GitLabTokenKind::OAuth),
+            None => (token, GitLabTokenKind::Personal),
+        };
+        return Ok(GitLabAuthConfig {
+            token,
+            kind,
+            source: AuthSource::Keyring,
+            host,
+        });
+    }
+
+    // Try glab CLI next
     debug!(host = %host, "attempting to get GitLab token via glab CLI");
     if let Some(token) = get_glab_cli_token(&host).await {
         debug!("obtained GitLab token from glab CLI");
         return Ok(GitLabAuthConfig {
             token,
+            kind: GitLabTokenKind::Personal,
+            source: AuthSource::Cli,
+            host,
+        });
+    }
+    if let Some(token) = glab_config_token(&host) {
+        debug!("obtained GitLab token from glab config.yml");
+        return Ok(GitLabAuthConfig {
+            token,
+            kind: GitLabTokenKind::Personal,
             source: AuthSource::Cli,
             host,
         });
@@ -48,6 +108,7 @@ pub async fn get_gitlab_auth(host: Option<&str>) -> Result<GitLabAuthConfig> {
         debug!("obtained GitLab token from GITLAB_TOKEN env var");
         return Ok(GitLabAuthConfig {
             token,
+            kind: GitLabTokenKind::Personal,
             source: AuthSource::EnvVar,
             host,
         });
@@ -57,6 +118,7 @@ pub async fn get_gitlab_auth(host: Option<&str>) -> Result<GitLabAuthConfig> {
         debug!("obtained GitLab token from GL_TOKEN env var");
         return Ok(GitLabAuthConfig {
             token,
+            kind: GitLabTokenKind::Personal,
             source: AuthSource::EnvVar,
             host,
         });
@@ -68,6 +130,52 @@ pub async fn get_gitlab_auth(host: Option<&str>) -> Result<GitLabAuthConfig> {
     ))
 }
 
+/// Get GitLab authentication for `host`, honoring `[auth]` accounts
+///
+/// If the config maps the host to an account, that account's token is used;
+/// otherwise this is [`get_gitlab_auth`].
+pub async fn get_gitlab_auth_for_host(
+    host: Option<&str>,
+    auth: &AuthConfig,
+) -> Result<GitLabAuthConfig> {
+    let host = host
+        .map(String::from)
+        .or_else(|| env::var("GITLAB_HOST").ok())
+        .unwrap_or_else(|| "gitlab.com".to_string());
+    if let Some(token) = account_token(auth, &host).await? {
+        debug!(host = %host, "obtained GitLab token from configured account");
+        return Ok(GitLabAuthConfig {
+            token,
+            kind: GitLabTokenKind::Personal,
+            source: AuthSource::Account,
+            host,
+        });
+    }
+    get_gitlab_auth(Some(&host)).await
+}
+
+/// The access token of a `ryu auth gitlab login`, renewed if it's expiring
+///
+/// A renewed token is stored back in the keyring, since GitLab rotates the
+/// refresh token along with it.
+async fn fresh_oauth_token(host: &str, token: OAuthToken) -> Result<String> {
+    if !token.expires_soon() {
+        return Ok(token.access_token);
+    }
+
+    debug!(host, "GitLab login expiring, refreshing it");
+    let flow = DeviceFlow::gitlab(&format!("https://{host}"), &token.client_id)?;
+    let token = flow.refresh(&token).await.map_err(|e| {
+        Error::Auth(format!(
+            "{e}. Run `ryu auth gitlab login --host {host}` to log in again"
+        ))
+    })?;
+    if let Err(e) = store_keyring_token(host, &token.to_stored()).await {
+        debug!(host, error = %e, "failed to store the renewed GitLab login");
+    }
+    Ok(token.access_token)
+}
+
 async fn get_glab_cli_token(host: &str) -> Option<String> {
     // Check glab is available
     Command::new("glab").arg("--version").output().await.ok()?;
@@ -114,7 +222,7 @@ pub async fn test_gitlab_auth(config: &GitLabAuthConfig) -> Result<String> {
 
     let user: GitLabUser = client
         .get(&url)
-        .header("PRIVATE-TOKEN", &config.token)
+        .headers(config.kind.headers(&config.token))
         .send()
         .await?
         .error_for_status()
@@ -124,3 +232,116 @@ pub async fn test_gitlab_auth(config: &GitLabAuthConfig) -> Result<String> {
 
     Ok(user.username)
 }
+
+#[derive(Deserialize)]
+struct TokenInfo {
+    scopes: Vec<String>,
+}
+
+/// Check that a GitLab token can manage MRs, before it's used for real
+///
+/// `api_url` includes the `/api/v4` prefix. Personal, project and group
+/// access tokens must have the `api` scope. OAuth tokens can't be inspected;
+/// they pass, and missing scopes surface as API errors.
+pub async fn validate_gitlab_token(
+    token: &str,
+    kind: GitLabTokenKind,
+    api_url: &str,
+) -> Result<()> {
+    if kind == GitLabTokenKind::OAuth {
+        return Ok(());
+    }
+
+    let client = Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| Error::GitLabApi(format!("failed to create HTTP client: {e}")))?;
+
+    let response = client
+        .get(format!("{api_url}/personal_access_tokens/self"))
+        .header("PRIVATE-TOKEN", token)
+        .send()
+        .await?;
+
+    if response.status() == StatusCode::UNAUTHORIZED {
+        return Err(Error::Auth(
+            "GitLab rejected the token. Run `glab auth login` or set a valid GITLAB_TOKEN"
+                .to_string(),
+        ));
+    }
+    if !response.status().is_success() {
+        debug!(status = %response.status(), "token can't be inspected, skipping scope check");
+        return Ok(());
+    }
+
+    let info: TokenInfo = response.json().await?;
+    if info.scopes.iter().any(|scope| scope == "api") {
+        return Ok(());
+    }
+
+    let granted = if info.scopes.is_empty() {
+        "none".to_string()
+    } else {
+        info.scopes.join(", ")
+    };
+    Err(Error::Auth(format!(
+        "GitLab token is missing the `api` scope (it has: {granted}). \
+         Create a token with the `api` scope"
+    )))
+}
+
+/// Look up who a GitLab token belongs to, its scopes and its rate limit
+///
+/// `api_url` includes the `/api/v4` prefix. Scopes are None for tokens that
+/// can't be inspected (e.g. OAuth tokens), and the rate limit is None on
+/// instances that don't report it.
+pub async fn gitlab_token_status(
+    token: &str,
+    kind: GitLabTokenKind,
+    api_url: &str,
+) -> Result<TokenStatus> {
+    let client = Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| Error::GitLabApi(format!("failed to create HTTP client: {e}")))?;
+
+    let response = client
+        .get(format!("{api_url}/user"))
+        .headers(kind.headers(token))
+        .send()
+        .await?
+        .error_for_status()
+        .map_err(|e| Error::Auth(format!("Invalid token: {e}")))?;
+
+    let rate_limit_remaining = header_u64(response.headers(), "ratelimit-remaining");
+    let rate_limit = header_u64(response.headers(), "ratelimit-limit");
+    let user: GitLabUser = response.json().await?;
+
+    if kind == GitLabTokenKind::OAuth {
+        return Ok(TokenStatus {
+            login: user.username,
+            scopes: None,
+            rate_limit_remaining,
+            rate_limit,
+        });
+    }
+
+    let response = client
+        .get(format!("{api_url}/personal_access_tokens/self"))
+        .header("PRIVATE-TOKEN", token)
+        .send()
+        .await?;
+    let scopes = if response.status().is_success() {
+        Some(response.json::<TokenInfo>().await?.scopes)
+    } else {
+        debug!(status = %response.status(), "token can't be inspected, no scopes");
+        None
+    };
+
+    Ok(TokenStatus {
+        login: user.username,
+        scopes,
+        rate_limit_remaining,
+        rate_limit,
+    })
+}
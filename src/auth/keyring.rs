@@ -0,0 +1,59 @@
+//! Tokens stored in the system keyring
+//!
+//! The macOS Keychain, the Secret Service on Linux, or the Windows Credential
+//! Manager. ryu keeps one entry per host under the `jj-ryu` service.
+
+use crate::error::{Error, Result};
+
+/// Keyring service ryu's tokens are stored under
+pub const KEYRING_SERVICE: &str = "jj-ryu";
+
+/// Token stored in the system keyring for `host`, if any
+///
+/// A missing or locked keyring counts as no token, so auth falls through to
+/// the next source.
+#[cfg(feature = "keyring")]
+pub async fn keyring_token(host: &str) -> Option<String> {
+    let host = host.to_string();
+    // Keyring backends block, and the Secret Service one runs its own executor
+    tokio::task::spawn_blocking(move || {
+        let entry = ::keyring::Entry::new(KEYRING_SERVICE, &host).ok()?;
+        match entry.get_password() {
+            Ok(token) => Some(token.trim().to_string()).filter(|t| !t.is_empty()),
+            Err(::keyring::Error::NoEntry) => None,
+            Err(e) => {
+                tracing::debug!(%host, error = %e, "keyring lookup failed");
+                None
+            }
+        }
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Token stored in the system keyring for `host` (built without keyring support)
+#[cfg(not(feature = "keyring"))]
+pub async fn keyring_token(_host: &str) -> Option<String> {
+    None
+}
+
+/// Store `token` in the system keyring for `host`, replacing any existing one
+#[cfg(feature = "keyring")]
+pub async fn store_keyring_token(host: &str, token: &str) -> Result<()> {
+    let (host, token) = (host.to_string(), token.to_string());
+    tokio::task::spawn_blocking(move || {
+        ::keyring::Entry::new(KEYRING_SERVICE, &host).and_then(|entry| entry.set_password(&token))
+    })
+    .await
+    .map_err(|e| Error::Internal(format!("keyring task failed: {e}")))?
+    .map_err(|e| Error::Auth(format!("Failed to store the token in the system keyring: {e}")))
+}
+
+/// Store `token` in the system keyring for `host` (built without keyring support)
+#[cfg(not(feature = "keyring"))]
+pub async fn store_keyring_token(_host: &str, _token: &str) -> Result<()> {
+    Err(Error::Unsupported(
+        "ryu was built without keyring support (the `keyring` feature)".to_string(),
+    ))
+}
@@ -1,18 +1,59 @@
-//! Authentication for GitHub and GitLab
+//! Authentication for GitHub, GitLab and Gerrit
 //!
-//! Supports CLI-based auth (gh, glab) and environment variables.
+//! Supports the system keyring, CLI-based auth (gh, glab, git credential
+//! helpers) and environment variables.
 
+mod accounts;
+mod cli_config;
+mod device;
+mod gerrit;
 mod github;
 mod gitlab;
+mod keyring;
 
-pub use github::{GitHubAuthConfig, get_github_auth, test_github_auth};
-pub use gitlab::{GitLabAuthConfig, get_gitlab_auth, test_gitlab_auth};
+pub use accounts::account_token;
+pub use cli_config::{
+    cli_config_hosts, gh_config_token, glab_config_token, parse_gh_hosts, parse_glab_hosts,
+};
+pub use device::{DeviceCode, DeviceFlow, OAuthToken};
+pub use gerrit::{GERRIT_JSON_PREFIX, GerritAuthConfig, get_gerrit_auth, test_gerrit_auth};
+pub use github::{
+    GitHubAuthConfig, get_github_auth, get_github_auth_for_host, github_token_status,
+    test_github_auth, validate_github_token,
+};
+pub use gitlab::{
+    GitLabAuthConfig, GitLabTokenKind, get_gitlab_auth, get_gitlab_auth_for_host,
+    gitlab_token_status, test_gitlab_auth, validate_gitlab_token,
+};
+pub use keyring::{KEYRING_SERVICE, keyring_token, store_keyring_token};
 
 /// Source of authentication token
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AuthSource {
-    /// Token from CLI tool (gh or glab)
+    /// Token from the system keyring (`ryu auth <platform> store`)
+    Keyring,
+    /// Token of the account the `[auth]` config maps the host to
+    Account,
+    /// Token from CLI tool (gh, glab, or a git credential helper)
     Cli,
     /// Token from environment variable
     EnvVar,
 }
+
+/// What the platform reports about a token (`ryu auth status`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenStatus {
+    /// Login of the user the token authenticates as
+    pub login: String,
+    /// Scopes granted to the token; None if the token type doesn't report them
+    pub scopes: Option<Vec<String>>,
+    /// API requests left in the current rate limit window
+    pub rate_limit_remaining: Option<u64>,
+    /// API requests allowed per rate limit window
+    pub rate_limit: Option<u64>,
+}
+
+/// Read a numeric response header, if present
+fn header_u64(headers: &reqwest::header::HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
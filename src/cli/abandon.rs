@@ -0,0 +1,195 @@
+//! `ryu abandon` command - close a stack's PRs and clean up its bookmarks
+
+use crate::cli::context::CommandContext;
+use crate::cli::prompt::confirm;
+use crate::cli::style::{Stylize, arrow, check, cross};
+use anstream::println;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::graph::build_change_graph;
+use jj_ryu::platform::{PlatformService, close_prs};
+use jj_ryu::submit::analyze_submission;
+use jj_ryu::tracking::{save_pr_cache, save_tracking};
+use jj_ryu::types::Bookmark;
+use std::path::Path;
+
+/// Options for the abandon command
+#[derive(Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct AbandonOptions {
+    /// Also delete the bookmarks' branches on the remote
+    pub delete_remote: bool,
+    /// Abandon only the named bookmark, moving the PR above it onto its base
+    pub only: bool,
+    /// Only list what would be abandoned
+    pub dry_run: bool,
+    /// Don't ask for confirmation
+    pub yes: bool,
+}
+
+/// Run the abandon command
+///
+/// Closes the PR of `bookmark` and of every bookmark above it in the stack
+/// (the whole stack if `bookmark` is None), then untracks and deletes the
+/// local bookmarks. With `only`, just `bookmark` goes and the PR above it is
+/// retargeted onto its base. The commits themselves are left alone.
+///
+/// A PR that fails to close keeps its bookmark; the others are still
+/// abandoned, and the failures are reported at the end.
+#[allow(clippy::too_many_lines)]
+pub async fn run_abandon(
+    path: &Path,
+    bookmark: Option<&str>,
+    remote: Option<&str>,
+    options: AbandonOptions,
+) -> Result<()> {
+    let mut ctx = CommandContext::new(path, remote).await?;
+    let graph = build_change_graph(&ctx.workspace)?;
+
+    if graph.stack.is_none() {
+        println!("{}", "No stack found between trunk and working copy.".muted());
+        return Ok(());
+    }
+    let analysis = analyze_submission(&graph, None)?;
+
+    let bookmarks: Vec<&Bookmark> = analysis.segments.iter().map(|s| &s.bookmark).collect();
+    let start = match bookmark {
+        Some(name) => bookmarks
+            .iter()
+            .position(|b| b.name == name)
+            .ok_or_else(|| Error::BookmarkNotFound(name.to_string()))?,
+        None => 0,
+    };
+
+    let end = if options.only {
+        start + 1
+    } else {
+        bookmarks.len()
+    };
+    // Top of the stack first, so no PR is left based on a closed one
+    let targets: Vec<&Bookmark> = bookmarks[start..end].iter().rev().copied().collect();
+    // With --only, the bookmark above takes over the abandoned one's base
+    let retarget = match bookmarks.get(end) {
+        Some(above) if options.only => Some((
+            above.name.clone(),
+            start.checked_sub(1).map_or_else(
+                || ctx.stack_base(&analysis),
+                |below| bookmarks[below].name.clone(),
+            ),
+        )),
+        _ => None,
+    };
+
+    println!(
+        "{}",
+        if options.dry_run {
+            "Would abandon:"
+        } else {
+            "Abandoning:"
+        }
+    );
+    for bookmark in &targets {
+        println!("  {}", bookmark.name.accent());
+    }
+    if let Some((above, base)) = &retarget {
+        println!("  {} PR of {} moves onto {base}", arrow(), above.accent());
+    }
+    if options.dry_run {
+        return Ok(());
+    }
+    if !confirm("Close these PRs and delete their bookmarks?", options.yes)? {
+        println!("{}", "Aborted".muted());
+        return Ok(());
+    }
+
+    // Record where the repo is before closing and deleting, for 'ryu undo'
+    let mut undo = ctx.begin_undo("abandon")?;
+    let mut errors = Vec::new();
+
+    // Before the close, so the PR above never points at a closed base.
+    // Nothing is closed if that fails.
+    if let Some((above, base)) = &retarget
+        && let Some((number, previous)) = retarget_pr(ctx.platform.as_ref(), above, base).await?
+    {
+        println!("{} Moved PR #{number} onto {base}", check());
+        if let Some(undo) = &mut undo {
+            undo.record_base_change(number, &previous);
+            ctx.save_undo(Some(undo));
+        }
+    }
+
+    let names: Vec<String> = targets.iter().map(|b| b.name.clone()).collect();
+    let closed = close_prs(ctx.platform.as_ref(), &names).await;
+
+    for (bookmark, (_, result)) in targets.iter().zip(closed) {
+        let name = bookmark.name.as_str();
+
+        match result {
+            Ok(Some(number)) => {
+                println!("{} Closed PR #{number} ({})", check(), name.accent());
+                if let Some(undo) = &mut undo {
+                    undo.closed_prs.push(number);
+                    ctx.save_undo(Some(undo));
+                }
+            }
+            Ok(None) => println!("  {} {}", name.accent(), "(no open PR)".muted()),
+            Err(e) => {
+                // Keep the bookmark, so a rerun can find the PR again
+                println!("{} {}: {e}", cross(), name.accent());
+                errors.push(format!("Failed to close the PR of {name}: {e}"));
+                continue;
+            }
+        }
+
+        if options.delete_remote && bookmark.has_remote {
+            let remote = ctx
+                .tracking
+                .get(name)
+                .and_then(|t| t.remote.clone())
+                .unwrap_or_else(|| ctx.remote_name.clone());
+            let previous = ctx
+                .workspace
+                .get_remote_bookmark(name, &remote)?
+                .map(|b| b.commit_id);
+            match ctx.workspace.restore_remote_bookmark(name, &remote, None) {
+                Ok(()) => {
+                    println!("{} Deleted {name} on {remote}", check());
+                    if let Some(undo) = &mut undo {
+                        undo.record_push(name, &remote, previous);
+                        ctx.save_undo(Some(undo));
+                    }
+                }
+                Err(e) => println!(
+                    "{}",
+                    format!("⚠️  Failed to delete {name} on {remote}: {e}").warn()
+                ),
+            }
+        }
+
+        ctx.pr_cache.remove(name);
+        ctx.tracking.untrack(name);
+        // Ignore errors - the bookmark may already be gone
+        let _ = ctx.workspace.delete_bookmark(name);
+    }
+
+    save_tracking(&ctx.workspace_root, &ctx.tracking)?;
+    // Best effort - the cache is rebuilt by the next submit
+    let _ = save_pr_cache(&ctx.workspace_root, &ctx.pr_cache);
+
+    if !errors.is_empty() {
+        return Err(Error::Platform(errors.join("; ")));
+    }
+    Ok(())
+}
+
+/// Point the open PR of `bookmark` at `base`, with its previous base
+async fn retarget_pr(
+    platform: &dyn PlatformService,
+    bookmark: &str,
+    base: &str,
+) -> Result<Option<(u64, String)>> {
+    let Some(pr) = platform.find_existing_pr(bookmark).await? else {
+        return Ok(None);
+    };
+    platform.update_pr_base(pr.number, base).await?;
+    Ok(Some((pr.number, pr.base_ref)))
+}
@@ -0,0 +1,165 @@
+//! `ryu adopt` command - take over PRs that were opened outside ryu
+
+use crate::cli::context::CommandContext;
+use crate::cli::style::{Stylize, arrow, check};
+use anstream::println;
+use chrono::Utc;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::graph::build_change_graph;
+use jj_ryu::submit::analyze_submission;
+use jj_ryu::tracking::{TrackedBookmark, save_pr_cache, save_tracking};
+use jj_ryu::types::{PrState, PullRequest};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Options for the adopt command
+#[derive(Debug, Clone, Default)]
+pub struct AdoptOptions {
+    /// Only report what would be adopted
+    pub dry_run: bool,
+}
+
+/// Run the adopt command
+///
+/// Finds the open PRs whose head branch is a local bookmark (only PR `pr` if
+/// given), tracks the bookmarks and records the PRs in the PR cache, so the
+/// next `ryu submit` updates them instead of opening new ones.
+///
+/// PRs of bookmarks in the current stack are then rebased onto the bookmark
+/// below them. The bottom PR keeps its base unless it names another bookmark
+/// of the stack; a base other than the default branch becomes the base the
+/// stack is tracked with.
+#[allow(clippy::too_many_lines)]
+pub async fn run_adopt(
+    path: &Path,
+    pr: Option<u64>,
+    remote: Option<&str>,
+    options: AdoptOptions,
+) -> Result<()> {
+    let mut ctx = CommandContext::new(path, remote).await?;
+
+    let prs: Vec<PullRequest> = if let Some(number) = pr {
+        let details = ctx.platform.get_pr_details(number).await?;
+        if details.state != PrState::Open {
+            return Err(Error::InvalidArgument(format!(
+                "PR #{number} is {}",
+                details.state
+            )));
+        }
+        if ctx.workspace.get_local_bookmark(&details.head_ref)?.is_none() {
+            return Err(Error::BookmarkNotFound(details.head_ref));
+        }
+        let pr = ctx
+            .platform
+            .find_existing_pr(&details.head_ref)
+            .await?
+            .filter(|pr| pr.number == number)
+            .ok_or_else(|| Error::Platform(format!("PR #{number} not found")))?;
+        vec![pr]
+    } else {
+        let names: Vec<String> = ctx
+            .workspace
+            .local_bookmarks()?
+            .into_iter()
+            .map(|b| b.name)
+            .filter(|name| *name != ctx.default_branch)
+            .collect();
+        let mut found = ctx.platform.find_existing_prs(&names).await?;
+        names.iter().filter_map(|name| found.remove(name)).collect()
+    };
+
+    if prs.is_empty() {
+        println!("{}", "No open PRs found for local bookmarks".muted());
+        return Ok(());
+    }
+
+    // Bases the current stack calls for, bottom to top
+    let graph = build_change_graph(&ctx.workspace)?;
+    let stack: Vec<String> = if graph.stack.is_some() {
+        analyze_submission(&graph, None)?
+            .segments
+            .into_iter()
+            .map(|s| s.bookmark.name)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let by_head: HashMap<&str, &PullRequest> =
+        prs.iter().map(|pr| (pr.head_ref.as_str(), pr)).collect();
+    let stack_base = ctx
+        .tracking
+        .stack_base(stack.iter().map(String::as_str))
+        .map(String::from)
+        .or_else(|| {
+            let bottom = by_head.get(stack.first()?.as_str())?;
+            (bottom.base_ref != ctx.default_branch && !stack.contains(&bottom.base_ref))
+                .then(|| bottom.base_ref.clone())
+        });
+    let mut expected_bases: HashMap<&str, String> = HashMap::new();
+    for (i, name) in stack.iter().enumerate() {
+        let base = if i == 0 {
+            stack_base.clone().unwrap_or_else(|| ctx.default_branch.clone())
+        } else {
+            stack[i - 1].clone()
+        };
+        expected_bases.insert(name.as_str(), base);
+    }
+
+    for pr in &prs {
+        let name = pr.head_ref.as_str();
+        let new_base = expected_bases
+            .get(name)
+            .filter(|base| **base != pr.base_ref);
+        let base_note = new_base.map_or_else(String::new, |base| {
+            format!(" ({} {} {base})", pr.base_ref, arrow())
+        });
+
+        if options.dry_run {
+            println!(
+                "  {} {}{base_note}",
+                name.accent(),
+                format!("#{}", pr.number).emphasis()
+            );
+            continue;
+        }
+
+        let pr = match new_base {
+            Some(base) => ctx.platform.update_pr_base(pr.number, base).await?,
+            None => pr.clone(),
+        };
+
+        if !ctx.tracking.is_tracked(name) {
+            let change_id = ctx
+                .workspace
+                .get_change_id(name)?
+                .ok_or_else(|| Error::BookmarkNotFound(name.to_string()))?;
+            ctx.tracking.track(TrackedBookmark {
+                name: name.to_string(),
+                change_id,
+                remote: remote.map(String::from),
+                base: stack_base
+                    .clone()
+                    .filter(|_| expected_bases.contains_key(name)),
+                tracked_at: Utc::now(),
+            });
+        }
+        ctx.pr_cache.upsert(name, &pr, &ctx.remote_name);
+        println!(
+            "{} Adopted {} {}{base_note}",
+            check(),
+            name.accent(),
+            format!("#{}", pr.number).emphasis()
+        );
+    }
+
+    if options.dry_run {
+        println!();
+        println!("{}", "Dry run: nothing was changed".muted());
+        return Ok(());
+    }
+
+    save_tracking(&ctx.workspace_root, &ctx.tracking)?;
+    save_pr_cache(&ctx.workspace_root, &ctx.pr_cache)?;
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+//! `ryu install-jj-aliases` command - make ryu available as jj subcommands
+
+use crate::cli::style::{Stylize, check, cross};
+use anstream::eprintln;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::repo::{
+    AliasReport, JjWorkspace, RYU_ALIASES, install_aliases, repo_jj_config_path, uninstall_aliases,
+    user_jj_config_path,
+};
+use std::path::Path;
+
+/// Options for the install-jj-aliases command
+#[derive(Debug, Clone, Default)]
+pub struct AliasOptions {
+    /// Write to the repo's jj config instead of the user config
+    pub repo: bool,
+    /// Overwrite existing aliases with the same names
+    pub force: bool,
+    /// Remove the aliases instead of installing them
+    pub uninstall: bool,
+}
+
+/// Run the install-jj-aliases command
+pub fn run_install_jj_aliases(path: &Path, options: &AliasOptions) -> Result<()> {
+    let config_path = if options.repo {
+        repo_jj_config_path(JjWorkspace::open(path)?.workspace_root())
+    } else {
+        user_jj_config_path()
+            .ok_or_else(|| Error::Config("could not determine jj config directory".to_string()))?
+    };
+
+    if options.uninstall {
+        let report = uninstall_aliases(&config_path)?;
+        if report.changed.is_empty() {
+            eprintln!("{}", "No ryu aliases installed".muted());
+        } else {
+            eprintln!("Removed aliases from {}:", config_path.display());
+            for name in &report.changed {
+                eprintln!("  {} jj {}", check(), name.accent());
+            }
+        }
+        print_conflicts(&report, "left in place");
+        return Ok(());
+    }
+
+    let report = install_aliases(&config_path, options.force)?;
+    eprintln!("Aliases in {}:", config_path.display());
+    for (name, args) in RYU_ALIASES {
+        if report.conflicting.iter().any(|c| c == name) {
+            continue;
+        }
+        let command = format!("ryu {}", args.join(" "));
+        eprintln!(
+            "  {} jj {} {}",
+            check(),
+            name.accent(),
+            format!("→ {}", command.trim_end()).muted()
+        );
+    }
+    print_conflicts(&report, "already defined (use --force to overwrite)");
+
+    Ok(())
+}
+
+fn print_conflicts(report: &AliasReport, reason: &str) {
+    for name in &report.conflicting {
+        eprintln!(
+            "  {} jj {} {}",
+            cross(),
+            name.accent(),
+            reason.muted()
+        );
+    }
+}
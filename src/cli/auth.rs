@@ -1,11 +1,23 @@
 //! Auth command - test and manage authentication
 
 use crate::cli::style::{Stylize, check, spinner_style};
+use crate::cli::web::open_in_browser;
 use anstream::println;
+use dialoguer::Password;
 use indicatif::ProgressBar;
-use jj_ryu::auth::{get_github_auth, get_gitlab_auth, test_github_auth, test_gitlab_auth};
-use jj_ryu::error::Result;
+use jj_ryu::auth::{
+    DeviceFlow, GitLabTokenKind, TokenStatus, cli_config_hosts, get_gerrit_auth, get_github_auth,
+    get_github_auth_for_host, get_gitlab_auth, get_gitlab_auth_for_host, github_token_status,
+    gitlab_token_status, store_keyring_token, test_gerrit_auth, test_github_auth,
+    test_gitlab_auth, validate_github_token, validate_gitlab_token,
+};
+use jj_ryu::config::load_config;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::platform::parse_repo_info_with_hosts;
+use jj_ryu::repo::{JjWorkspace, select_remote};
 use jj_ryu::types::Platform;
+use std::env;
+use std::path::Path;
 use std::time::Duration;
 
 /// Run the auth test command
@@ -19,6 +31,7 @@ pub async fn run_auth_test(platform: Platform) -> Result<()> {
 
             let config = get_github_auth().await?;
             let username = test_github_auth(&config).await?;
+            validate_github_token(&config.token, "https://api.github.com").await?;
 
             spinner.finish_and_clear();
             println!("{} Authenticated as: {}", check(), username.accent());
@@ -32,12 +45,33 @@ pub async fn run_auth_test(platform: Platform) -> Result<()> {
 
             let config = get_gitlab_auth(None).await?;
             let username = test_gitlab_auth(&config).await?;
+            let api_url = format!("https://{}/api/v4", config.host);
+            validate_gitlab_token(&config.token, config.kind, &api_url).await?;
 
             spinner.finish_and_clear();
             println!("{} Authenticated as: {}", check(), username.accent());
             println!("  {} {:?}", "Token source:".muted(), config.source);
             println!("  {} {}", "Host:".muted(), config.host);
         }
+        Platform::Gerrit => {
+            let spinner = ProgressBar::new_spinner();
+            spinner.set_style(spinner_style());
+            spinner.set_message("Testing Gerrit authentication...");
+            spinner.enable_steady_tick(Duration::from_millis(80));
+
+            let config = get_gerrit_auth(None).await?;
+            let username = test_gerrit_auth(&config).await?;
+
+            spinner.finish_and_clear();
+            println!("{} Authenticated as: {}", check(), username.accent());
+            println!("  {} {:?}", "Credential source:".muted(), config.source);
+            println!("  {} {}", "Host:".muted(), config.host);
+        }
+        Platform::Custom(name) => {
+            return Err(Error::Unsupported(format!(
+                "Authentication for {name} is handled by its platform backend"
+            )));
+        }
     }
     Ok(())
 }
@@ -59,6 +93,9 @@ pub fn run_auth_setup(platform: Platform) {
                 "GH_TOKEN".accent()
             );
             println!();
+            println!("{}", "Option 3: System keyring".emphasis());
+            println!("  Run: {}", "ryu auth github store".accent());
+            println!();
             println!("{}", "For GitHub Enterprise:".muted());
             println!("  {}", "Set GH_HOST to your instance hostname".muted());
         }
@@ -79,10 +116,237 @@ pub fn run_auth_setup(platform: Platform) {
                 "GL_TOKEN".accent()
             );
             println!();
+            println!("{}", "Option 3: System keyring".emphasis());
+            println!("  Run: {}", "ryu auth gitlab store".accent());
+            println!();
             println!("{}", "For self-hosted GitLab:".muted());
             println!("  {}", "Set GITLAB_HOST to your instance hostname".muted());
         }
+        Platform::Gerrit => {
+            println!("{}", "Gerrit Authentication Setup".emphasis());
+            println!();
+            println!("{}", "Generate an HTTP password".emphasis());
+            println!("  In Gerrit: {}", "Settings → HTTP Credentials".accent());
+            println!();
+            println!("{}", "Option 1: Environment variables".emphasis());
+            println!(
+                "  Set {} and {}",
+                "GERRIT_USERNAME".accent(),
+                "GERRIT_PASSWORD".accent()
+            );
+            println!();
+            println!("{}", "Option 2: git credential helper".emphasis());
+            println!("  Store the credentials for {}", "https://<gerrit host>".accent());
+            println!();
+            println!("{}", "Set GERRIT_HOST to your Gerrit hostname".muted());
+        }
+        Platform::Custom(name) => {
+            println!("{}", format!("Authentication for {name} is handled by its platform backend").muted());
+        }
+    }
+}
+
+/// Run the auth store command
+///
+/// Prompts for a token, checks it against the platform, and saves it in the
+/// system keyring for the host (see [`keyring_host`]).
+pub async fn run_auth_store(platform: Platform, host: Option<&str>) -> Result<()> {
+    let (name, host) = keyring_host(&platform, host)?;
+
+    let token = Password::new()
+        .with_prompt(format!("{name} token for {host}"))
+        .interact()
+        .map_err(|e| Error::Internal(format!("Failed to read token: {e}")))?;
+    let token = token.trim();
+
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(spinner_style());
+    spinner.set_message(format!("Checking {name} token..."));
+    spinner.enable_steady_tick(Duration::from_millis(80));
+
+    let checked = match platform {
+        Platform::GitHub if host == "github.com" => {
+            validate_github_token(token, "https://api.github.com").await
+        }
+        Platform::GitHub => validate_github_token(token, &format!("https://{host}/api/v3")).await,
+        _ => {
+            let api_url = format!("https://{host}/api/v4");
+            validate_gitlab_token(token, GitLabTokenKind::Personal, &api_url).await
+        }
+    };
+    spinner.finish_and_clear();
+    checked?;
+
+    store_keyring_token(&host, token).await?;
+    println!(
+        "{} Stored {name} token for {} in the system keyring",
+        check(),
+        host.accent()
+    );
+    Ok(())
+}
+
+/// Run the auth login command
+///
+/// Logs in with the OAuth device flow: shows a code, opens the page to enter
+/// it on, waits for the user to authorize ryu, then stores the token in the
+/// system keyring. GitLab logins are stored with their refresh token, since
+/// the access token expires after two hours. Needs the client ID of an OAuth
+/// app with the device flow enabled, from `--client-id` or the `[auth]`
+/// config table.
+pub async fn run_auth_login(
+    path: &Path,
+    platform: Platform,
+    host: Option<&str>,
+    client_id: Option<&str>,
+) -> Result<()> {
+    let (name, host) = keyring_host(&platform, host)?;
+    let config = load_config(path).unwrap_or_default();
+    let (configured_id, key) = match platform {
+        Platform::GitHub => (config.auth.github_client_id, "github_client_id"),
+        _ => (config.auth.gitlab_client_id, "gitlab_client_id"),
+    };
+    let client_id = client_id.map(String::from).or(configured_id).ok_or_else(|| {
+        Error::Auth(format!(
+            "No OAuth app configured for {name} login. Pass --client-id or set \
+             auth.{key} in the ryu config"
+        ))
+    })?;
+
+    let base_url = format!("https://{host}");
+    let flow = match platform {
+        Platform::GitHub => DeviceFlow::github(&base_url, &client_id)?,
+        _ => DeviceFlow::gitlab(&base_url, &client_id)?,
+    };
+    let code = flow.request_code().await?;
+
+    println!(
+        "Enter the code {} at {}",
+        code.user_code.emphasis(),
+        code.verification_uri.accent()
+    );
+    if !open_in_browser(&code.verification_uri) {
+        println!("{}", "Open the page in your browser to continue.".muted());
     }
+
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(spinner_style());
+    spinner.set_message("Waiting for authorization...");
+    spinner.enable_steady_tick(Duration::from_millis(80));
+    let token = flow.poll_token(&code).await;
+    spinner.finish_and_clear();
+    let token = token?;
+
+    // GitLab's tokens expire, so keep the refresh token along with them
+    let stored = match platform {
+        Platform::GitHub => token.access_token,
+        _ => token.to_stored(),
+    };
+    store_keyring_token(&host, &stored).await?;
+    println!(
+        "{} Logged in to {name} at {}; the token is stored in the system keyring",
+        check(),
+        host.accent()
+    );
+    Ok(())
+}
+
+/// Run the auth status command
+///
+/// Detects the platform from the repository's remote, then shows who the
+/// token authenticates as, where it came from, its scopes and how much of the
+/// API rate limit is left.
+pub async fn run_auth_status(path: &Path, remote: Option<&str>) -> Result<()> {
+    let workspace = JjWorkspace::open(path)?;
+    let config = load_config(workspace.workspace_root())?;
+    let mut hosts = cli_config_hosts();
+    hosts.extend(config.hosts.clone());
+
+    let remotes = workspace.git_remotes()?;
+    let remote_name = select_remote(&remotes, remote.or(config.remote.as_deref()))?;
+    let remote_info = remotes
+        .iter()
+        .find(|r| r.name == remote_name)
+        .ok_or_else(|| Error::RemoteNotFound(remote_name.clone()))?;
+    let platform_config = parse_repo_info_with_hosts(&remote_info.url, &hosts)?;
+    let host = platform_config.host.as_deref();
+
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(spinner_style());
+    spinner.set_message("Checking authentication...");
+    spinner.enable_steady_tick(Duration::from_millis(80));
+
+    let (name, host, source, status) = match platform_config.platform {
+        Platform::GitHub => {
+            let auth = get_github_auth_for_host(host, &config.auth).await?;
+            let api_url = host.map_or_else(
+                || "https://api.github.com".to_string(),
+                |h| format!("https://{h}/api/v3"),
+            );
+            let status = github_token_status(&auth.token, &api_url).await?;
+            let host = host.unwrap_or("github.com").to_string();
+            ("GitHub", host, format!("{:?}", auth.source), status)
+        }
+        Platform::GitLab => {
+            let auth = get_gitlab_auth_for_host(host, &config.auth).await?;
+            let api_url = format!("https://{}/api/v4", auth.host);
+            let status = gitlab_token_status(&auth.token, auth.kind, &api_url).await?;
+            ("GitLab", auth.host, format!("{:?}", auth.source), status)
+        }
+        Platform::Gerrit => {
+            let auth = get_gerrit_auth(host).await?;
+            let login = test_gerrit_auth(&auth).await?;
+            let status = TokenStatus {
+                login,
+                ..TokenStatus::default()
+            };
+            ("Gerrit", auth.host, format!("{:?}", auth.source), status)
+        }
+        Platform::Custom(name) => {
+            spinner.finish_and_clear();
+            return Err(Error::Unsupported(format!(
+                "Authentication for {name} is handled by its platform backend"
+            )));
+        }
+    };
+    spinner.finish_and_clear();
+
+    println!("{} Authenticated as: {}", check(), status.login.accent());
+    println!("  {} {name} ({host})", "Platform:".muted());
+    println!("  {} {source}", "Token source:".muted());
+    let scopes = match &status.scopes {
+        Some(scopes) if scopes.is_empty() => "none".to_string(),
+        Some(scopes) => scopes.join(", "),
+        None => "not reported".to_string(),
+    };
+    println!("  {} {scopes}", "Scopes:".muted());
+    let rate_limit = match (status.rate_limit_remaining, status.rate_limit) {
+        (Some(remaining), Some(limit)) => format!("{remaining} of {limit} requests left"),
+        (Some(remaining), None) => format!("{remaining} requests left"),
+        _ => "not reported".to_string(),
+    };
+    println!("  {} {rate_limit}", "Rate limit:".muted());
+    Ok(())
+}
+
+/// Platform name and host whose keyring entry the auth commands use
+///
+/// The host defaults to `GH_HOST`/`GITLAB_HOST`, then the public instance.
+fn keyring_host(platform: &Platform, host: Option<&str>) -> Result<(&'static str, String)> {
+    let (name, env_var, default_host) = match platform {
+        Platform::GitHub => ("GitHub", "GH_HOST", "github.com"),
+        Platform::GitLab => ("GitLab", "GITLAB_HOST", "gitlab.com"),
+        Platform::Gerrit | Platform::Custom(_) => {
+            return Err(Error::Unsupported(
+                "Only GitHub and GitLab tokens can be stored in the keyring".to_string(),
+            ));
+        }
+    };
+    let host = host
+        .map(String::from)
+        .or_else(|| env::var(env_var).ok())
+        .unwrap_or_else(|| default_host.to_string());
+    Ok((name, host))
 }
 
 /// Wrapper for auth commands
@@ -0,0 +1,214 @@
+//! `ryu backport` command - mirror a stack onto a release branch
+
+use crate::cli::context::CommandContext;
+use crate::cli::CliProgress;
+use crate::cli::style::{CHECK, Stylize, arrow, check, cross, spinner_style};
+use anstream::{eprintln, println};
+use futures::stream::{self, StreamExt};
+use indicatif::ProgressBar;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::graph::build_change_graph;
+use jj_ryu::submit::{
+    SubmissionAnalysis, analyze_submission, apply_config_to_plan, backport_bookmark_name,
+    capture_rollback_snapshot, create_backport_stack, create_submission_plan_onto,
+    execute_submission, link_backport_prs, record_undo,
+};
+use jj_ryu::tracking::{TrackedBookmark, save_pr_cache, save_tracking};
+use jj_ryu::types::PullRequest;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// Options for the backport command
+#[derive(Debug, Clone, Default)]
+pub struct BackportOptions {
+    /// Backport all bookmarks in `trunk()`..@ (ignore tracking)
+    pub all: bool,
+    /// Create the backport PRs as drafts
+    pub draft: bool,
+    /// Show what would be backported without making changes
+    pub dry_run: bool,
+}
+
+/// Run the backport command
+///
+/// Duplicates the stack (up to `bookmark`, if given) onto `target`, creates
+/// `backport/<target>/<bookmark>` bookmarks, and submits them as a stack of
+/// PRs against `target` that link back to the original PRs. The new
+/// bookmarks are tracked with `target` as their base.
+#[allow(clippy::too_many_lines)]
+pub async fn run_backport(
+    path: &Path,
+    target: &str,
+    bookmark: Option<&str>,
+    remote: Option<&str>,
+    options: BackportOptions,
+) -> Result<()> {
+    let mut ctx = CommandContext::new(path, remote).await?;
+
+    let tracked_names: Vec<String> = ctx.tracked_names().into_iter().map(String::from).collect();
+    if tracked_names.is_empty() && !options.all {
+        return Err(Error::Tracking(
+            "No bookmarks tracked. Run 'ryu track' first, or use 'ryu backport --all' to backport all bookmarks.".to_string()
+        ));
+    }
+
+    if !options.dry_run {
+        let spinner = ProgressBar::new_spinner();
+        spinner.set_style(spinner_style());
+        spinner.set_message(format!("Fetching from {}...", ctx.remote_name.emphasis()));
+        spinner.enable_steady_tick(Duration::from_millis(80));
+
+        ctx.workspace.git_fetch(&ctx.remote_name)?;
+
+        spinner.finish_with_message(format!(
+            "{} Fetched from {}",
+            check(),
+            ctx.remote_name.emphasis()
+        ));
+    }
+
+    let graph = build_change_graph(&ctx.workspace)?;
+    if graph.stack.is_none() {
+        println!("{}", "No stack to backport".muted());
+        return Ok(());
+    }
+    if let Some(bm) = bookmark
+        && !graph.bookmarks.contains_key(bm)
+    {
+        return Err(Error::BookmarkNotFound(bm.to_string()));
+    }
+
+    let mut analysis = analyze_submission(&graph, bookmark)?;
+    if !options.all {
+        analysis
+            .segments
+            .retain(|s| tracked_names.contains(&s.bookmark.name));
+        if analysis.segments.is_empty() {
+            return Err(Error::Tracking(
+                "No tracked bookmarks in stack. Use 'ryu track' to track bookmarks, or 'ryu backport --all'.".to_string()
+            ));
+        }
+    }
+
+    println!(
+        "{} {} onto {}",
+        "Backporting".emphasis(),
+        format!("{} bookmark(s)", analysis.segments.len()).accent(),
+        format!("{target}@{}", ctx.remote_name).emphasis()
+    );
+    for segment in &analysis.segments {
+        println!(
+            "  {} {} {}",
+            segment.bookmark.name.accent(),
+            arrow(),
+            backport_bookmark_name(target, &segment.bookmark.name)
+        );
+    }
+
+    if options.dry_run {
+        println!();
+        println!("{}", "Dry run complete".muted());
+        return Ok(());
+    }
+
+    // PRs to link back to, keyed by backport bookmark
+    let platform = ctx.platform.as_ref();
+    let originals: HashMap<String, PullRequest> =
+        stream::iter(analysis.segments.iter().map(|s| s.bookmark.name.clone()))
+            .map(|name| async move {
+                let pr = platform.find_existing_pr(&name).await.ok().flatten();
+                (backport_bookmark_name(target, &name), pr)
+            })
+            .buffered(platform.max_concurrency())
+            .filter_map(|(name, pr)| async move { pr.map(|pr| (name, pr)) })
+            .collect()
+            .await;
+
+    // Record where the repo is before creating the backport stack, for 'ryu undo'
+    let mut undo = ctx.begin_undo("backport")?;
+
+    let segments = create_backport_stack(
+        &mut ctx.workspace,
+        &analysis.segments,
+        target,
+        &ctx.remote_name,
+    )?;
+    let backport = SubmissionAnalysis {
+        target_bookmark: segments
+            .last()
+            .map(|s| s.bookmark.name.clone())
+            .unwrap_or_default(),
+        segments,
+    };
+
+    let mut plan = create_submission_plan_onto(
+        &backport,
+        ctx.platform.as_ref(),
+        &ctx.remote_name,
+        target,
+        &ctx.default_branch,
+    )
+    .await?;
+    link_backport_prs(&mut plan, target, &originals);
+    apply_config_to_plan(&mut plan, &ctx.config.submit, &ctx.pr_cache, options.draft)?;
+
+    let snapshot = capture_rollback_snapshot(&plan, &ctx.workspace)?;
+    let progress = CliProgress::compact();
+    let result = execute_submission(
+        &plan,
+        &mut ctx.workspace,
+        ctx.platform.as_ref(),
+        &progress,
+        false,
+    )
+    .await?;
+    if let Some(undo) = &mut undo {
+        record_undo(undo, &plan, &snapshot, &result);
+        ctx.save_undo(Some(undo));
+    }
+
+    // Track the mirrored stack against the release branch
+    for segment in &backport.segments {
+        let mut tracked = TrackedBookmark::new(
+            segment.bookmark.name.clone(),
+            segment.bookmark.change_id.clone(),
+        );
+        tracked.base = Some(target.to_string());
+        ctx.tracking.track(tracked);
+    }
+    save_tracking(&ctx.workspace_root, &ctx.tracking)?;
+    for pr in result.created_prs.iter().chain(result.updated_prs.iter()) {
+        ctx.pr_cache.upsert(&pr.head_ref, pr, &ctx.remote_name);
+    }
+    // Best effort - the cache is rebuilt by the next submit
+    let _ = save_pr_cache(&ctx.workspace_root, &ctx.pr_cache);
+
+    println!();
+    if result.success {
+        println!(
+            "{} {} PR(s) against {}",
+            format!("{CHECK} Backported:").success(),
+            result.created_prs.len().accent(),
+            target.emphasis()
+        );
+        for pr in &result.created_prs {
+            println!("  {} {}", pr.head_ref.accent(), pr.html_url.muted());
+        }
+    } else {
+        eprintln!("{} Backport failed", cross());
+        for err in &result.errors {
+            eprintln!("  {}", err.error());
+        }
+        eprintln!(
+            "{}",
+            format!(
+                "The backport bookmarks are tracked; run 'jj new {}' and 'ryu submit' to retry.",
+                backport.target_bookmark
+            )
+            .muted()
+        );
+    }
+
+    Ok(())
+}
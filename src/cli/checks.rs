@@ -0,0 +1,134 @@
+//! `ryu checks` command - show CI results for each PR in the stack
+
+use crate::cli::context::CommandContext;
+use crate::cli::style::{Stream, Stylize, check, cross, hyperlink_url};
+use anstream::println;
+use futures::stream::{self, StreamExt};
+use jj_ryu::error::{Error, Result};
+use jj_ryu::graph::build_change_graph;
+use jj_ryu::platform::PlatformService;
+use jj_ryu::submit::analyze_submission;
+use jj_ryu::types::{CheckInfo, CheckState, PullRequest};
+use std::path::Path;
+use std::time::Duration;
+
+/// A bookmark with its PR and the PR's checks (None = no PR)
+type BookmarkChecks = (String, Option<(PullRequest, Vec<CheckInfo>)>);
+
+/// Options for the checks command
+#[derive(Debug, Clone, Copy)]
+pub struct ChecksOptions {
+    /// Refresh until no check is pending
+    pub watch: bool,
+    /// Time between refreshes with `watch`
+    pub interval: Duration,
+}
+
+/// Run the checks command
+///
+/// Lists the CI checks of every PR in the stack, trunk first, with their
+/// state and a link to their details. With `watch`, the list is refreshed
+/// until every check has completed. Fails if any check failed, so it can
+/// gate scripts.
+pub async fn run_checks(path: &Path, remote: Option<&str>, options: ChecksOptions) -> Result<()> {
+    let ctx = CommandContext::new(path, remote).await?;
+    let graph = build_change_graph(&ctx.workspace)?;
+
+    if graph.stack.is_none() {
+        println!(
+            "{}",
+            "No stack found between trunk and working copy.".muted()
+        );
+        return Ok(());
+    }
+    let names: Vec<String> = analyze_submission(&graph, None)?
+        .segments
+        .into_iter()
+        .map(|s| s.bookmark.name)
+        .collect();
+
+    loop {
+        let fetched = fetch_checks(ctx.platform.as_ref(), &names).await?;
+        print_checks(&fetched);
+
+        let all_checks = fetched
+            .iter()
+            .filter_map(|(_, pr)| pr.as_ref())
+            .flat_map(|(_, c)| c);
+        let pending = all_checks
+            .clone()
+            .filter(|c| c.state == CheckState::Pending)
+            .count();
+        let failed = all_checks
+            .filter(|c| c.state == CheckState::Failure)
+            .count();
+
+        if options.watch && pending > 0 {
+            println!();
+            println!(
+                "{}",
+                format!(
+                    "{pending} pending; refreshing in {}s...",
+                    options.interval.as_secs()
+                )
+                .muted()
+            );
+            tokio::time::sleep(options.interval).await;
+            println!();
+            continue;
+        }
+
+        if failed > 0 {
+            return Err(Error::Platform(format!("{failed} check(s) failed")));
+        }
+        return Ok(());
+    }
+}
+
+/// Look up the PR and checks of each bookmark
+async fn fetch_checks(
+    platform: &dyn PlatformService,
+    names: &[String],
+) -> Result<Vec<BookmarkChecks>> {
+    stream::iter(names.iter().cloned())
+        .map(|name| async move {
+            let Ok(Some(pr)) = platform.find_existing_pr(&name).await else {
+                return Ok((name, None));
+            };
+            let checks = platform.list_checks(&pr.head_ref).await?;
+            Ok((name, Some((pr, checks))))
+        })
+        .buffered(platform.max_concurrency())
+        .collect::<Vec<Result<_>>>()
+        .await
+        .into_iter()
+        .collect()
+}
+
+/// Print each PR's checks, one line each
+fn print_checks(fetched: &[BookmarkChecks]) {
+    for (name, pr) in fetched {
+        let Some((pr, checks)) = pr else {
+            println!("{} {}", name.accent(), "(no PR)".muted());
+            continue;
+        };
+        println!("{} {}", name.accent(), format!("#{}", pr.number).muted());
+
+        if checks.is_empty() {
+            println!("  {}", "No checks reported".muted());
+        }
+        for info in checks {
+            let marker = match info.state {
+                CheckState::Success | CheckState::Skipped => check().to_string(),
+                CheckState::Failure => cross().to_string(),
+                CheckState::Pending => "…".warn().to_string(),
+            };
+            let url = info
+                .url
+                .as_deref()
+                .map(|url| format!(" {}", hyperlink_url(Stream::Stdout, url)))
+                .unwrap_or_default();
+            println!("  {marker} {} {}{url}", info.name, info.state.muted());
+        }
+    }
+}
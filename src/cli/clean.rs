@@ -0,0 +1,204 @@
+//! `ryu clean` command - remove bookmarks whose PRs are merged or closed
+
+use crate::cli::context::CommandContext;
+use crate::cli::prompt::confirm;
+use crate::cli::style::{Stylize, check, cross, spinner_style};
+use anstream::println;
+use indicatif::ProgressBar;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::platform::get_prs_details;
+use jj_ryu::tracking::{save_pr_cache, save_tracking};
+use jj_ryu::types::PrState;
+use std::path::Path;
+use std::time::Duration;
+
+/// Options for the clean command
+#[derive(Debug, Clone, Default)]
+pub struct CleanOptions {
+    /// Also abandon the bookmarks' changes that are empty
+    pub abandon: bool,
+    /// Only report what would be cleaned up
+    pub dry_run: bool,
+    /// Don't ask for confirmation
+    pub yes: bool,
+}
+
+/// A tracked bookmark whose PR is done with
+struct Finished {
+    name: String,
+    number: u64,
+    state: PrState,
+}
+
+/// Run the clean command
+///
+/// Fetches, then looks up the PR of every tracked bookmark. Bookmarks whose
+/// PR was merged or closed are deleted locally, untracked and removed from the
+/// PR cache. With `--abandon`, the empty changes (typically because they landed
+/// on trunk) of each bookmark's own segment are abandoned too.
+///
+/// A bookmark whose PR can't be checked, or whose changes can't be abandoned,
+/// is left in place; the others are still cleaned up and the failures are
+/// reported at the end.
+///
+/// PRs are found by the number in the PR cache, so a merged PR is only
+/// recognized if ryu has seen it; bookmarks missing from the cache are
+/// checked for a closed PR.
+#[allow(clippy::future_not_send)]
+pub async fn run_clean(path: &Path, remote: Option<&str>, options: CleanOptions) -> Result<()> {
+    let mut ctx = CommandContext::new(path, remote).await?;
+
+    let names: Vec<String> = ctx
+        .tracking
+        .tracked_names()
+        .into_iter()
+        .map(String::from)
+        .collect();
+    if names.is_empty() {
+        println!("{}", "No bookmarks currently tracked".muted());
+        return Ok(());
+    }
+
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(spinner_style());
+    spinner.set_message(format!("Fetching from {}...", ctx.remote_name.emphasis()));
+    spinner.enable_steady_tick(Duration::from_millis(80));
+    let mut remotes = vec![ctx.remote_name.clone()];
+    for remote in ctx.tracking.push_remotes().into_values() {
+        if !remotes.contains(&remote) {
+            remotes.push(remote);
+        }
+    }
+    for remote in &remotes {
+        ctx.workspace.git_fetch(remote)?;
+    }
+
+    spinner.set_message("Checking PRs...");
+    let (finished, mut errors) = find_finished(&ctx, names).await;
+    spinner.finish_and_clear();
+
+    for error in &errors {
+        println!("{} {error}", cross());
+    }
+    if finished.is_empty() {
+        println!("{}", "Nothing to clean up".muted());
+        return into_result(&errors);
+    }
+
+    println!(
+        "{}",
+        if options.dry_run {
+            "Would clean up:"
+        } else {
+            "Cleaning up:"
+        }
+    );
+    for bookmark in &finished {
+        println!(
+            "  {} {}",
+            bookmark.name.accent(),
+            format!("(PR #{} {})", bookmark.number, bookmark.state).muted()
+        );
+    }
+    if options.dry_run {
+        return into_result(&errors);
+    }
+    if !confirm("Delete these bookmarks?", options.yes)? {
+        println!("{}", "Aborted".muted());
+        return Ok(());
+    }
+
+    // Record where the repo is before deleting bookmarks, for 'ryu undo'
+    ctx.begin_undo("clean")?;
+
+    for bookmark in &finished {
+        let name = bookmark.name.as_str();
+        // Before the bookmark goes, while it still names the changes
+        if options.abandon {
+            match ctx.workspace.abandon_empty_segment("trunk()", name) {
+                Ok(0) => {}
+                Ok(abandoned) => {
+                    println!(
+                        "{} Abandoned {abandoned} empty change(s) of {name}",
+                        check()
+                    );
+                }
+                Err(e) => {
+                    println!("{} {}: {e}", cross(), name.accent());
+                    errors.push(format!("Failed to abandon the changes of {name}: {e}"));
+                    continue;
+                }
+            }
+        }
+
+        ctx.pr_cache.remove(name);
+        ctx.tracking.untrack(name);
+        // Ignore errors - the bookmark may already be gone
+        let _ = ctx.workspace.delete_bookmark(name);
+        println!(
+            "{} Removed {} {}",
+            check(),
+            name.accent(),
+            format!("(PR #{} {})", bookmark.number, bookmark.state).muted()
+        );
+    }
+
+    save_tracking(&ctx.workspace_root, &ctx.tracking)?;
+    // Best effort - the cache is rebuilt by the next submit
+    let _ = save_pr_cache(&ctx.workspace_root, &ctx.pr_cache);
+
+    into_result(&errors)
+}
+
+/// Fail with the collected `errors`, if any
+fn into_result(errors: &[String]) -> Result<()> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Platform(errors.join("; ")))
+    }
+}
+
+/// Bookmarks among `names` whose PR was merged or closed, and the lookups
+/// that failed
+#[allow(clippy::future_not_send)]
+async fn find_finished(ctx: &CommandContext, names: Vec<String>) -> (Vec<Finished>, Vec<String>) {
+    let mut finished = Vec::new();
+    let mut errors = Vec::new();
+    let (cached, uncached): (Vec<String>, Vec<String>) = names
+        .into_iter()
+        .partition(|name| ctx.pr_cache.get(name).is_some());
+    let numbers: Vec<u64> = cached
+        .iter()
+        .filter_map(|name| ctx.pr_cache.get(name).map(|c| c.number))
+        .collect();
+    let details = get_prs_details(ctx.platform.as_ref(), &numbers).await;
+    for (name, (number, details)) in cached.into_iter().zip(details) {
+        match details {
+            Ok(details) if details.state != PrState::Open => finished.push(Finished {
+                name,
+                number,
+                state: details.state,
+            }),
+            Ok(_) => {}
+            Err(e) => errors.push(format!("Failed to check PR #{number} of {name}: {e}")),
+        }
+    }
+    for name in uncached {
+        let closed = match ctx.platform.find_existing_pr(&name).await {
+            Ok(Some(_)) => Ok(None),
+            Ok(None) => ctx.platform.find_closed_pr(&name).await,
+            Err(e) => Err(e),
+        };
+        match closed {
+            Ok(Some(pr)) => finished.push(Finished {
+                name,
+                number: pr.number,
+                state: PrState::Closed,
+            }),
+            Ok(None) => {}
+            Err(e) => errors.push(format!("Failed to check the PR of {name}: {e}")),
+        }
+    }
+    (finished, errors)
+}
@@ -0,0 +1,159 @@
+//! `ryu comment` command - post a comment on a bookmark's PR
+
+use crate::cli::context::CommandContext;
+use crate::cli::style::{Stylize, check};
+use anstream::println;
+use dialoguer::Editor;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::graph::build_change_graph;
+use jj_ryu::platform::PlatformService;
+use jj_ryu::repo::JjWorkspace;
+use jj_ryu::submit::analyze_submission;
+use jj_ryu::tracking::{PendingOperation, load_op_queue, save_op_queue};
+use jj_ryu::types::PullRequest;
+use std::io::{self, IsTerminal, Read};
+use std::path::Path;
+
+/// Run the comment command
+///
+/// Posts `message` on the PR of `bookmark` (the bookmark the working copy is
+/// on if None). Without a message, or with `-`, the comment is read from
+/// stdin, or written in $EDITOR when stdin is a terminal. With `offline`,
+/// the comment is queued for `ryu flush` instead.
+pub async fn run_comment(
+    path: &Path,
+    bookmark: Option<&str>,
+    message: Option<&str>,
+    remote: Option<&str>,
+    offline: bool,
+) -> Result<()> {
+    if offline {
+        return queue_comment(path, bookmark, message, remote);
+    }
+
+    let ctx = CommandContext::new(path, remote).await?;
+    let name = resolve_bookmark(&ctx.workspace, bookmark)?;
+
+    let pr = find_open_pr(ctx.platform.as_ref(), &name).await?;
+
+    let body = match message {
+        Some(message) if message != "-" => message.to_string(),
+        _ => read_message()?,
+    };
+    let body = body.trim();
+    if body.is_empty() {
+        println!("{}", "Empty comment, nothing posted".muted());
+        return Ok(());
+    }
+
+    post_comment(ctx.platform.as_ref(), &pr, &name, body).await
+}
+
+/// The open PR of bookmark `name`
+pub async fn find_open_pr(platform: &dyn PlatformService, name: &str) -> Result<PullRequest> {
+    platform
+        .find_existing_pr(name)
+        .await?
+        .ok_or_else(|| Error::Platform(format!("no open PR for bookmark '{name}'")))
+}
+
+/// Post `body` on `pr`, the PR of bookmark `name`
+///
+/// Shared with `ryu flush`, which replays comments queued with `--offline`.
+pub async fn post_comment(
+    platform: &dyn PlatformService,
+    pr: &PullRequest,
+    name: &str,
+    body: &str,
+) -> Result<()> {
+    platform.create_pr_comment(pr.number, body).await?;
+    println!(
+        "{} Commented on PR #{} ({})",
+        check(),
+        pr.number,
+        name.accent()
+    );
+    Ok(())
+}
+
+/// Record a comment in the offline queue without contacting the remote
+///
+/// Only local validation runs here: the bookmark must exist, and the comment
+/// must not be empty.
+fn queue_comment(
+    path: &Path,
+    bookmark: Option<&str>,
+    message: Option<&str>,
+    remote: Option<&str>,
+) -> Result<()> {
+    let workspace = JjWorkspace::open(path)?;
+    let workspace_root = workspace.workspace_root().to_path_buf();
+
+    let name = resolve_bookmark(&workspace, bookmark)?;
+    if workspace.get_local_bookmark(&name)?.is_none() {
+        return Err(Error::BookmarkNotFound(name));
+    }
+
+    let body = match message {
+        Some(message) if message != "-" => message.to_string(),
+        _ => read_message()?,
+    };
+    let body = body.trim();
+    if body.is_empty() {
+        println!("{}", "Empty comment, nothing queued".muted());
+        return Ok(());
+    }
+
+    let mut queue = load_op_queue(&workspace_root)?;
+    let operation = PendingOperation::Comment {
+        bookmark: name,
+        body: body.to_string(),
+        remote: remote.map(String::from),
+    };
+    println!(
+        "{} Queued {} ({} pending)",
+        check(),
+        operation.describe().emphasis(),
+        (queue.len() + 1).accent()
+    );
+    queue.push(operation);
+    save_op_queue(&workspace_root, &queue)?;
+
+    println!(
+        "{}",
+        "Run 'ryu flush' when back online to post it.".muted()
+    );
+    Ok(())
+}
+
+/// The bookmark to comment on: `bookmark`, or the one the working copy is on
+fn resolve_bookmark(workspace: &JjWorkspace, bookmark: Option<&str>) -> Result<String> {
+    if let Some(name) = bookmark {
+        return Ok(name.to_string());
+    }
+
+    let graph = build_change_graph(workspace)?;
+    if graph.stack.is_none() {
+        return Err(Error::InvalidArgument(
+            "No bookmark found between trunk and working copy; pass --bookmark".to_string(),
+        ));
+    }
+    Ok(analyze_submission(&graph, None)?.target_bookmark)
+}
+
+/// Read the comment from stdin, or from $EDITOR if stdin is a terminal
+fn read_message() -> Result<String> {
+    if io::stdin().is_terminal() {
+        let edited = Editor::new()
+            .require_save(true)
+            .edit("")
+            .map_err(|e| Error::Internal(format!("Failed to run editor: {e}")))?;
+        return Ok(edited.unwrap_or_default());
+    }
+
+    let mut message = String::new();
+    io::stdin()
+        .read_to_string(&mut message)
+        .map_err(|e| Error::Internal(format!("Failed to read stdin: {e}")))?;
+    Ok(message)
+}
@@ -0,0 +1,70 @@
+//! `ryu config` command - read and write configuration
+
+use crate::cli::style::{Stylize, check};
+use anstream::println;
+use jj_ryu::config::{
+    config_entries, env_var_name, get_config_value, load_config, parse_config_value,
+    repo_config_path, set_config_value, user_config_path,
+};
+use jj_ryu::error::{Error, Result};
+use jj_ryu::repo::JjWorkspace;
+use std::path::Path;
+
+/// Run `ryu config get`: print the effective value of `key`
+///
+/// Fails if the key is unset, so scripts can tell it apart from an empty value.
+pub fn run_config_get(path: &Path, key: &str) -> Result<()> {
+    let workspace = JjWorkspace::open(path)?;
+    let config = load_config(workspace.workspace_root())?;
+
+    let value = get_config_value(&config, key)?
+        .ok_or_else(|| Error::Config(format!("{key} is not set")))?;
+    match value {
+        serde_json::Value::String(value) => println!("{value}"),
+        value => println!("{value}"),
+    }
+    Ok(())
+}
+
+/// Run `ryu config set`: save `key` in the repo config, or the user config
+/// with `user`
+///
+/// An environment variable for the key still overrides the saved value,
+/// which is pointed out.
+pub fn run_config_set(path: &Path, key: &str, value: &str, user: bool) -> Result<()> {
+    let config_path = if user {
+        user_config_path()
+            .ok_or_else(|| Error::Config("no user config directory on this system".to_string()))?
+    } else {
+        let workspace = JjWorkspace::open(path)?;
+        repo_config_path(workspace.workspace_root())
+    };
+
+    set_config_value(&config_path, key, parse_config_value(key, value)?)?;
+    println!(
+        "{} Set {} in {}",
+        check(),
+        key.accent(),
+        config_path.display()
+    );
+
+    let env_var = env_var_name(key);
+    if std::env::var_os(&env_var).is_some() {
+        println!(
+            "{}",
+            format!("{env_var} is set and overrides this value").warn()
+        );
+    }
+    Ok(())
+}
+
+/// Run `ryu config list`: print every setting that has a value
+pub fn run_config_list(path: &Path) -> Result<()> {
+    let workspace = JjWorkspace::open(path)?;
+    let config = load_config(workspace.workspace_root())?;
+
+    for (key, value) in config_entries(&config) {
+        println!("{} = {value}", key.accent());
+    }
+    Ok(())
+}
@@ -2,10 +2,27 @@
 //!
 //! Extracts common setup code shared by submit, sync, and merge commands.
 
+use crate::cli::output::is_json;
+use crate::cli::style::Stylize;
+use anstream::eprintln;
+use jj_ryu::auth::cli_config_hosts;
+use jj_ryu::config::{load_config, RyuConfig};
 use jj_ryu::error::{Error, Result};
-use jj_ryu::platform::{create_platform_service, parse_repo_info, PlatformService};
+use jj_ryu::hooks::{run_hook, HookEvent, HookPayload};
+use jj_ryu::platform::{
+    create_platform_service_with_auth, parse_repo_info_with_hosts, simulated_state_path,
+    PlatformService, RetryingPlatformService, SimulatedPlatformService, ThrottledPlatformService,
+};
+use jj_ryu::types::{Platform, PlatformConfig};
 use jj_ryu::repo::{select_remote, JjWorkspace};
-use jj_ryu::tracking::{load_pr_cache, load_tracking, PrCache, TrackingState};
+use jj_ryu::submit::{SubmissionAnalysis, SubmissionPlan, SubmissionResult, record_descriptions};
+use jj_ryu::tracking::{
+    load_pr_cache, load_tracking, load_undo_record, save_pr_cache, save_undo_record, PrCache, TrackingState,
+    UndoRecord,
+};
+use jj_ryu::webhooks::{post_summary, ChatSummary};
+use serde::Serialize;
+use std::cell::Cell;
 use std::path::{Path, PathBuf};
 
 /// Shared context for CLI commands that interact with the platform
@@ -34,6 +51,14 @@ pub struct CommandContext {
     pub remote_name: String,
     /// Default branch name (e.g., "main")
     pub default_branch: String,
+    /// User and repo configuration
+    pub config: RyuConfig,
+    /// Running with `--simulate`: the platform is simulated, the remote isn't
+    /// contacted, and no local ryu state, hooks or webhooks are touched
+    pub simulated: bool,
+    /// Set by [`Self::begin_undo`], so the operation the command leaves the
+    /// repo at is recorded when the context is dropped
+    undo_finisher: UndoFinisher,
 }
 
 impl CommandContext {
@@ -43,44 +68,107 @@ impl CommandContext {
     /// - Open workspace
     /// - Load tracking state
     /// - Load PR cache
+    /// - Load configuration
     /// - Select and validate remote
     /// - Detect platform and create service
     /// - Get default branch
     pub async fn new(path: &Path, remote: Option<&str>) -> Result<Self> {
+        Self::open(path, remote, false).await
+    }
+
+    /// Create a context for `--simulate`
+    ///
+    /// Uses the simulated platform (with PRs kept in
+    /// `.jj/repo/ryu/simulated.toml`) and skips fetches and pushes. A
+    /// repository without remotes is simulated as having `origin`.
+    pub async fn simulated(path: &Path, remote: Option<&str>) -> Result<Self> {
+        Self::open(path, remote, true).await
+    }
+
+    async fn open(path: &Path, remote: Option<&str>, simulated: bool) -> Result<Self> {
         // Open workspace
-        let workspace = JjWorkspace::open(path)?;
+        let mut workspace = JjWorkspace::open(path)?;
         let workspace_root = workspace.workspace_root().to_path_buf();
 
-        // Load tracking and PR cache
+        // Load tracking and PR cache (simulated PRs aren't cached)
         let tracking = load_tracking(&workspace_root)?;
-        let pr_cache = load_pr_cache(&workspace_root)?;
+        let pr_cache = if simulated {
+            PrCache::new()
+        } else {
+            load_pr_cache(&workspace_root)?
+        };
+        let config = load_config(&workspace_root)?;
+        let remote = remote.or(config.remote.as_deref());
+        // Hosts gh and glab are logged in to, overridden by the `[hosts]` config
+        let mut hosts = cli_config_hosts();
+        hosts.extend(config.hosts.clone());
 
         // Get remotes and select one
         let remotes = workspace.git_remotes()?;
-        let remote_name = select_remote(&remotes, remote)?;
+        let (remote_name, platform): (String, Box<dyn PlatformService>) = if simulated {
+            workspace.simulate_remote();
 
-        // Detect platform from remote URL
-        let remote_info = remotes
-            .iter()
-            .find(|r| r.name == remote_name)
-            .ok_or_else(|| Error::RemoteNotFound(remote_name.clone()))?;
+            // A repository without remotes is simulated as having `origin`
+            let (remote_name, url) = if remotes.is_empty() && remote.is_none() {
+                ("origin".to_string(), None)
+            } else {
+                let remote_name = select_remote(&remotes, remote)?;
+                let url = remotes
+                    .iter()
+                    .find(|r| r.name == remote_name)
+                    .map(|r| r.url.clone());
+                (remote_name, url)
+            };
+            // Only PR-based platforms are simulated
+            let platform_config = url
+                .and_then(|url| parse_repo_info_with_hosts(&url, &hosts).ok())
+                .filter(|c| matches!(c.platform, Platform::GitHub | Platform::GitLab))
+                .unwrap_or_else(|| simulated_config(&workspace_root));
+            let platform = SimulatedPlatformService::open(
+                platform_config,
+                simulated_state_path(&workspace_root),
+            )?;
+            (remote_name, Box::new(platform))
+        } else {
+            let remote_name = select_remote(&remotes, remote)?;
 
-        let platform_config = parse_repo_info(&remote_info.url)?;
+            // Detect platform from remote URL
+            let remote_info = remotes
+                .iter()
+                .find(|r| r.name == remote_name)
+                .ok_or_else(|| Error::RemoteNotFound(remote_name.clone()))?;
 
-        // Create platform service
-        let platform = create_platform_service(&platform_config).await?;
+            let platform_config = parse_repo_info_with_hosts(&remote_info.url, &hosts)?;
+
+            // Create platform service, throttled and retried per `http.*` config.
+            // Retries wrap the throttle so each attempt waits for a request slot.
+            let platform = RetryingPlatformService::new(
+                ThrottledPlatformService::new(
+                    create_platform_service_with_auth(&platform_config, &config.auth).await?,
+                    &config.http,
+                ),
+                &config.http,
+            );
+            (remote_name, Box::new(platform))
+        };
 
         // Get default branch
         let default_branch = workspace.default_branch()?;
 
         Ok(Self {
             workspace,
-            workspace_root,
+            workspace_root: workspace_root.clone(),
             tracking,
             pr_cache,
             platform,
             remote_name,
             default_branch,
+            config,
+            simulated,
+            undo_finisher: UndoFinisher {
+                workspace_root,
+                started: Cell::new(false),
+            },
         })
     }
 
@@ -94,4 +182,167 @@ impl CommandContext {
     pub fn tracked_names(&self) -> Vec<&str> {
         self.tracking.tracked_names()
     }
+
+    /// Branch the analyzed stack targets
+    ///
+    /// This is the base declared with `ryu track --base`, or the default
+    /// branch if the stack's tracked bookmarks don't declare one.
+    pub fn stack_base(&self, analysis: &SubmissionAnalysis) -> String {
+        let names = analysis.segments.iter().map(|s| s.bookmark.name.as_str());
+        self.tracking
+            .stack_base(names)
+            .unwrap_or(&self.default_branch)
+            .to_string()
+    }
+
+    /// Cache the PRs a successful submission touched
+    ///
+    /// Stores the plan's existing PRs and the ones the run created or
+    /// updated, along with the commit descriptions they were described from
+    /// (see [`record_descriptions`]). Best effort, and skipped when simulated.
+    pub fn save_submitted_prs(&mut self, plan: &SubmissionPlan, result: &SubmissionResult) {
+        if self.simulated || !result.success {
+            return;
+        }
+        for (bookmark, pr) in &plan.existing_prs {
+            if self.pr_cache.get(bookmark).is_none() {
+                self.pr_cache.upsert(bookmark, pr, &self.remote_name);
+            }
+        }
+        for pr in result.created_prs.iter().chain(&result.updated_prs) {
+            self.pr_cache.upsert(&pr.head_ref, pr, &self.remote_name);
+        }
+        record_descriptions(plan, result, &mut self.pr_cache);
+        // The cache is rebuilt by the next submit if this fails
+        let _ = save_pr_cache(&self.workspace_root, &self.pr_cache);
+    }
+
+    /// Record where the repo is before `command` changes it, for `ryu undo`
+    ///
+    /// Saves the current jj operation and tracking state, replacing the
+    /// previous command's record. Returns None when simulated, since nothing
+    /// real changes.
+    pub fn begin_undo(&self, command: &str) -> Result<Option<UndoRecord>> {
+        if self.simulated {
+            return Ok(None);
+        }
+        let record = UndoRecord::new(
+            command,
+            self.workspace.current_operation_id()?,
+            self.remote_name.clone(),
+            self.tracking.clone(),
+        );
+        save_undo_record(&self.workspace_root, &record)?;
+        self.undo_finisher.started.set(true);
+        Ok(Some(record))
+    }
+
+    /// Save the platform changes added to an undo record
+    ///
+    /// Best effort - the changes have already been made, and the jj
+    /// operation they started from was saved by [`Self::begin_undo`].
+    pub fn save_undo(&self, record: Option<&UndoRecord>) {
+        if let Some(record) = record
+            && let Err(e) = save_undo_record(&self.workspace_root, record)
+        {
+            eprintln!("{}", format!("⚠️  Failed to save undo record: {e}").warn());
+        }
+    }
+
+    /// Run the hook configured for `event`, if any
+    ///
+    /// The operation has already happened, so a failing hook is reported as
+    /// a warning rather than an error. Hooks don't run when simulated, and
+    /// under `--json` their output goes to stderr so it can't corrupt the
+    /// JSON document.
+    ///
+    /// The returned future doesn't borrow the context, which isn't `Sync`.
+    pub fn run_hook<'a, T: Serialize + Sync>(
+        &self,
+        event: HookEvent,
+        result: &'a T,
+    ) -> impl Future<Output = ()> + Send + 'a {
+        let command = event
+            .command(&self.config.hooks)
+            .filter(|_| !self.simulated)
+            .map(String::from);
+        let remote = self.remote_name.clone();
+        let workspace_root = self.workspace_root.clone();
+        let json = is_json();
+
+        async move {
+            let Some(command) = command else {
+                return;
+            };
+            let payload = HookPayload {
+                event,
+                remote: &remote,
+                result,
+            };
+            if let Err(e) = run_hook(&command, &payload, &workspace_root, json).await {
+                eprintln!("{}", format!("⚠️  {e}").warn());
+            }
+        }
+    }
+
+    /// Repository as `owner/repo`, for chat summaries
+    pub fn repo_name(&self) -> String {
+        let config = self.platform.config();
+        format!("{}/{}", config.owner, config.repo)
+    }
+
+    /// Post `summary` to the configured chat webhooks, if any
+    ///
+    /// Like hooks, failures are reported as warnings, and nothing is posted
+    /// when simulated.
+    pub fn post_webhooks(&self, summary: ChatSummary) -> impl Future<Output = ()> + Send + 'static {
+        let webhooks = self.config.webhooks.clone();
+        let simulated = self.simulated;
+
+        async move {
+            if simulated {
+                return;
+            }
+            for e in post_summary(&webhooks, &summary).await {
+                eprintln!("{}", format!("⚠️  {e}").warn());
+            }
+        }
+    }
+}
+
+/// Records the operation a command left the repo at when dropped, so
+/// `ryu undo` can tell whether anything changed the repo since
+struct UndoFinisher {
+    workspace_root: PathBuf,
+    started: Cell<bool>,
+}
+
+impl Drop for UndoFinisher {
+    fn drop(&mut self) {
+        if !self.started.get() {
+            return;
+        }
+        // Best effort - without it, undo asks before restoring
+        if let Ok(Some(mut record)) = load_undo_record(&self.workspace_root)
+            && let Ok(operation_id) =
+                JjWorkspace::open(&self.workspace_root).and_then(|ws| ws.current_operation_id())
+        {
+            record.finished_operation_id = Some(operation_id);
+            let _ = save_undo_record(&self.workspace_root, &record);
+        }
+    }
+}
+
+/// Platform config for a simulated repository whose remote isn't a
+/// recognized GitHub or GitLab remote
+fn simulated_config(workspace_root: &Path) -> PlatformConfig {
+    let repo = workspace_root
+        .file_name()
+        .map_or_else(|| "repo".to_string(), |name| name.to_string_lossy().into_owned());
+    PlatformConfig {
+        platform: Platform::GitHub,
+        owner: "simulated".to_string(),
+        repo,
+        host: None,
+    }
 }
@@ -0,0 +1,44 @@
+//! `ryu draft` command - convert a PR back to a draft
+
+use crate::cli::context::CommandContext;
+use crate::cli::style::{Stylize, check};
+use anstream::println;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::tracking::save_pr_cache;
+use std::path::Path;
+
+/// Run the draft command
+///
+/// Marks the open PR for `bookmark` as a draft, the reverse of
+/// `ryu submit --publish`. A PR that's already a draft is left alone.
+pub async fn run_draft(path: &Path, bookmark: &str, remote: Option<&str>) -> Result<()> {
+    let mut ctx = CommandContext::new(path, remote).await?;
+
+    let pr = ctx
+        .platform
+        .find_existing_pr(bookmark)
+        .await?
+        .ok_or_else(|| Error::Platform(format!("no open PR for bookmark '{bookmark}'")))?;
+
+    if pr.is_draft {
+        println!(
+            "{}",
+            format!("PR #{} ({bookmark}) is already a draft", pr.number).muted()
+        );
+        return Ok(());
+    }
+
+    let pr = ctx.platform.unpublish_pr(pr.number).await?;
+    println!(
+        "{} Converted PR #{} ({}) to draft",
+        check(),
+        pr.number,
+        bookmark.accent()
+    );
+
+    ctx.pr_cache.upsert(bookmark, &pr, &ctx.remote_name);
+    // Best effort - the PR is already a draft
+    let _ = save_pr_cache(&ctx.workspace_root, &ctx.pr_cache);
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! `ryu export` command - print the stack as JSON
+
+use crate::cli::context::CommandContext;
+use anstream::println;
+use futures::stream::{self, StreamExt};
+use jj_ryu::error::Result;
+use jj_ryu::graph::{ExportedPr, build_change_graph, export_stack};
+use jj_ryu::platform::PlatformService;
+use jj_ryu::repo::{JjWorkspace, select_remote};
+use jj_ryu::tracking::{PrCache, load_pr_cache, load_tracking};
+use jj_ryu::types::ChangeGraph;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Options for the export command
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Use the local PR cache instead of querying the platform
+    pub cached: bool,
+    /// Write to this file instead of stdout
+    pub output: Option<PathBuf>,
+}
+
+/// Run the export command
+///
+/// PR details come from the platform unless `cached` is set, in which case
+/// only the number and URL recorded in the PR cache are exported.
+pub async fn run_export(path: &Path, remote: Option<&str>, options: ExportOptions) -> Result<()> {
+    let export = if options.cached {
+        let workspace = JjWorkspace::open(path)?;
+        let workspace_root = workspace.workspace_root().to_path_buf();
+        let tracking = load_tracking(&workspace_root)?;
+        let pr_cache = load_pr_cache(&workspace_root)?;
+        let remote = select_remote(&workspace.git_remotes()?, remote).ok();
+        let graph = build_change_graph(&workspace)?;
+
+        let prs = cached_prs(&graph, &pr_cache, remote.as_deref());
+        export_stack(
+            &graph,
+            &workspace.default_branch()?,
+            remote.as_deref(),
+            &prs,
+            &tracking.tracked_names(),
+        )
+    } else {
+        let ctx = CommandContext::new(path, remote).await?;
+        let graph = build_change_graph(&ctx.workspace)?;
+
+        let cached = cached_prs(&graph, &ctx.pr_cache, Some(&ctx.remote_name));
+        let prs = fetch_prs(&graph, ctx.platform.as_ref(), cached).await;
+        export_stack(
+            &graph,
+            &ctx.default_branch,
+            Some(&ctx.remote_name),
+            &prs,
+            &ctx.tracked_names(),
+        )
+    };
+
+    let json = serde_json::to_string_pretty(&export)?;
+    match options.output {
+        Some(output) => fs::write(output, format!("{json}\n"))?,
+        None => println!("{json}"),
+    }
+
+    Ok(())
+}
+
+/// PRs for the stack's bookmarks as recorded in the PR cache
+fn cached_prs(
+    graph: &ChangeGraph,
+    pr_cache: &PrCache,
+    remote: Option<&str>,
+) -> HashMap<String, ExportedPr> {
+    graph
+        .bookmarks
+        .keys()
+        .filter_map(|name| {
+            let cached = pr_cache.get(name)?;
+            if remote.is_some_and(|remote| remote != cached.remote) {
+                return None;
+            }
+            Some((
+                name.clone(),
+                ExportedPr {
+                    number: cached.number,
+                    url: cached.url.clone(),
+                    head: name.clone(),
+                    base: None,
+                    title: None,
+                    state: None,
+                    is_draft: None,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Look up each bookmark's PR on the platform
+///
+/// Cached PR numbers are used when known so closed and merged PRs are still
+/// found. Lookups that fail fall back to the cached entry.
+async fn fetch_prs(
+    graph: &ChangeGraph,
+    platform: &dyn PlatformService,
+    mut cached: HashMap<String, ExportedPr>,
+) -> HashMap<String, ExportedPr> {
+    let names: Vec<(String, Option<u64>)> = graph
+        .bookmarks
+        .keys()
+        .map(|name| (name.clone(), cached.get(name).map(|pr| pr.number)))
+        .collect();
+
+    let fetched: Vec<(String, Option<ExportedPr>)> = stream::iter(names)
+        .map(|(name, cached_number)| async move {
+            let number = match cached_number {
+                Some(number) => Some(number),
+                None => platform
+                    .find_existing_pr(&name)
+                    .await
+                    .ok()
+                    .flatten()
+                    .map(|pr| pr.number),
+            };
+            let details = match number {
+                Some(number) => platform.get_pr_details(number).await.ok(),
+                None => None,
+            };
+            (name, details.map(ExportedPr::from))
+        })
+        .buffered(platform.max_concurrency())
+        .collect()
+        .await;
+
+    for (name, pr) in fetched {
+        if let Some(pr) = pr {
+            cached.insert(name, pr);
+        }
+    }
+    cached
+}
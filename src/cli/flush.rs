@@ -0,0 +1,138 @@
+//! `ryu flush` command - replay operations queued while offline
+
+use crate::cli::comment::{find_open_pr, post_comment};
+use crate::cli::context::CommandContext;
+use crate::cli::style::{Stylize, arrow, check, cross};
+use crate::cli::submit::{SubmitOptions, SubmitScope, submit_stack};
+use anstream::{eprintln, println};
+use jj_ryu::error::{Error, Result};
+use jj_ryu::repo::JjWorkspace;
+use jj_ryu::tracking::{PendingOperation, load_op_queue, save_op_queue};
+use std::path::Path;
+
+/// Options for the flush command
+#[derive(Debug, Clone, Default)]
+pub struct FlushOptions {
+    /// List queued operations without performing them
+    pub dry_run: bool,
+}
+
+/// Run the flush command
+///
+/// Operations are replayed oldest first. Each one is removed from the queue
+/// as soon as it succeeds, so a failure leaves only the remaining operations
+/// queued for the next flush.
+pub async fn run_flush(path: &Path, options: FlushOptions) -> Result<()> {
+    let workspace = JjWorkspace::open(path)?;
+    let workspace_root = workspace.workspace_root().to_path_buf();
+    drop(workspace);
+
+    let mut queue = load_op_queue(&workspace_root)?;
+
+    if queue.is_empty() {
+        println!("{}", "No queued operations".muted());
+        return Ok(());
+    }
+
+    if options.dry_run {
+        println!("{} queued operation(s):", queue.len().accent());
+        for queued in &queue.operations {
+            println!(
+                "  {} {} {}",
+                arrow(),
+                queued.operation.describe(),
+                format!("(queued {})", queued.queued_at.format("%Y-%m-%d %H:%M")).muted()
+            );
+        }
+        return Ok(());
+    }
+
+    while let Some(queued) = queue.operations.first().cloned() {
+        let description = queued.operation.describe();
+        println!("{} {}", arrow(), description.emphasis());
+
+        let outcome = replay(path, &queued.operation).await;
+        match outcome {
+            Ok(()) => {
+                queue.pop_front();
+                save_op_queue(&workspace_root, &queue)?;
+            }
+            Err(e) => {
+                eprintln!("{} Failed to {description}: {e}", cross());
+                eprintln!(
+                    "{}",
+                    format!("{} operation(s) remain queued", queue.len()).muted()
+                );
+                return Err(e);
+            }
+        }
+    }
+
+    println!();
+    println!("{} All queued operations completed", check());
+    Ok(())
+}
+
+/// Perform a single queued operation
+async fn replay(path: &Path, operation: &PendingOperation) -> Result<()> {
+    match operation {
+        PendingOperation::Submit {
+            bookmark,
+            remote,
+            upto,
+            only,
+            stack,
+            update_only,
+            draft,
+            publish,
+            all,
+            labels,
+            assignees,
+            milestone,
+            stack_in_body,
+            no_stack_comment,
+            reopen,
+        } => {
+            let scope = if upto.is_some() {
+                SubmitScope::Upto
+            } else if *only {
+                SubmitScope::Only
+            } else if *stack {
+                SubmitScope::Stack
+            } else {
+                SubmitScope::Default
+            };
+
+            let options = SubmitOptions {
+                scope,
+                upto_bookmark: upto.as_deref(),
+                update_only: *update_only,
+                draft: *draft,
+                publish: *publish,
+                all: *all,
+                labels: labels.clone(),
+                assignees: assignees.clone(),
+                milestone: milestone.clone(),
+                stack_in_body: *stack_in_body,
+                no_stack_comment: *no_stack_comment,
+                reopen: *reopen,
+                ..SubmitOptions::default()
+            };
+
+            if submit_stack(path, bookmark.as_deref(), remote.as_deref(), options).await? {
+                Ok(())
+            } else {
+                Err(Error::Platform("submission did not complete".to_string()))
+            }
+        }
+        PendingOperation::Comment {
+            bookmark,
+            body,
+            remote,
+        } => {
+            let ctx = CommandContext::new(path, remote.as_deref()).await?;
+            let pr = find_open_pr(ctx.platform.as_ref(), bookmark).await?;
+            post_comment(ctx.platform.as_ref(), &pr, bookmark, body).await
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Submitting a stack to Gerrit, one change per commit
+
+use crate::cli::context::CommandContext;
+use crate::cli::style::{CHECK, Stylize, arrow, bullet};
+use crate::cli::submit::{SubmitOptions, submit_labels};
+use anstream::println;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::submit::{
+    GerritSubmissionPlan, SubmissionAnalysis, create_gerrit_plan, execute_gerrit_submission,
+};
+use jj_ryu::tracking::save_pr_cache;
+
+/// Submit the analyzed stack to Gerrit, returning whether it succeeded
+///
+/// `--draft` pushes the changes as work in progress and `--publish` marks
+/// them ready for review. Labels become hashtags.
+pub async fn submit_gerrit(
+    ctx: &mut CommandContext,
+    analysis: &SubmissionAnalysis,
+    options: &SubmitOptions<'_>,
+) -> Result<bool> {
+    if options.single_pr || options.atomic || options.resume || options.select {
+        return Err(Error::Unsupported(
+            "--single-pr, --atomic, --continue and --select are not supported with Gerrit"
+                .to_string(),
+        ));
+    }
+    if !options.assignees.is_empty() {
+        return Err(Error::Unsupported(
+            "Gerrit changes have no assignees; add reviewers instead".to_string(),
+        ));
+    }
+    if options.milestone.is_some() {
+        return Err(Error::Unsupported(
+            "Gerrit has no milestones; use --label to add hashtags instead".to_string(),
+        ));
+    }
+
+    let target_branch = ctx.stack_base(analysis);
+    let mut plan =
+        create_gerrit_plan(analysis, ctx.platform.as_ref(), &ctx.remote_name, &target_branch)
+            .await?;
+    plan.wip = options.draft;
+    plan.ready = options.publish;
+    plan.hashtags = submit_labels(ctx, options);
+
+    if options.update_only {
+        plan.changes.truncate(
+            plan.changes
+                .iter()
+                .rposition(|c| c.existing.is_some())
+                .map_or(0, |i| i + 1),
+        );
+    }
+    if plan.changes.is_empty() {
+        println!("{}", "No changes to submit".muted());
+        return Ok(true);
+    }
+
+    print_gerrit_plan(&plan);
+    if options.dry_run {
+        return Ok(true);
+    }
+
+    println!();
+    let result = execute_gerrit_submission(&plan, &mut ctx.workspace, ctx.platform.as_ref()).await?;
+    if !result.pushed {
+        println!("{}", "Gerrit already has every change; nothing to update".muted());
+    }
+
+    let mut missing = 0;
+    for (change, pr) in &result.changes {
+        let Some(pr) = pr else {
+            missing += 1;
+            println!("  {} {} {}", bullet(), change.title, "(not found after push)".warn());
+            continue;
+        };
+        println!("  {} {} {}", bullet(), change.title, pr.html_url.muted());
+        ctx.pr_cache
+            .upsert_change(&change.bookmark, &change.change_id, pr, &ctx.remote_name);
+    }
+
+    // The change at the tip of each segment stands in for the bookmark's PR
+    for segment in &analysis.segments {
+        let tip = result
+            .changes
+            .iter()
+            .rev()
+            .find(|(change, _)| change.bookmark == segment.bookmark.name);
+        if let Some((_, Some(pr))) = tip {
+            ctx.pr_cache.upsert(&segment.bookmark.name, pr, &ctx.remote_name);
+        }
+    }
+    // Best effort - don't fail submit if cache write fails
+    let _ = save_pr_cache(&ctx.workspace_root, &ctx.pr_cache);
+
+    println!();
+    println!(
+        "{} {} change{} for {}",
+        format!("{CHECK} Submitted").success(),
+        result.changes.len().accent(),
+        if result.changes.len() == 1 { "" } else { "s" },
+        target_branch.accent()
+    );
+    Ok(missing == 0)
+}
+
+/// Print the commits that will be pushed and what Gerrit will do with them
+fn print_gerrit_plan(plan: &GerritSubmissionPlan) {
+    println!(
+        "{} Pushing {} commit{} to {} ({} new change{})",
+        arrow(),
+        plan.changes.len().accent(),
+        if plan.changes.len() == 1 { "" } else { "s" },
+        plan.push_target().emphasis(),
+        plan.count_new(),
+        if plan.count_new() == 1 { "" } else { "s" }
+    );
+    for change in &plan.changes {
+        let status = change.existing.as_ref().map_or_else(
+            || "new change".to_string(),
+            |pr| format!("new patch set for change {}", pr.number),
+        );
+        let trailer = if change.new_description.is_some() {
+            ", adds Change-Id"
+        } else {
+            ""
+        };
+        println!(
+            "  {} {} {}",
+            bullet(),
+            change.title,
+            format!("({status}{trailer})").muted()
+        );
+    }
+}
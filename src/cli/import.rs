@@ -0,0 +1,267 @@
+//! `ryu import` command - adopt stacks managed by other tools
+
+use crate::cli::context::CommandContext;
+use crate::cli::style::{Stylize, arrow, check};
+use anstream::eprintln;
+use chrono::Utc;
+use jj_ryu::error::Result;
+use jj_ryu::import::{
+    CommitStackStyle, ImportedBranch, StackCommit, read_commit_stack, read_graphite_branches,
+    stack_commits_to_branches,
+};
+use jj_ryu::platform::PlatformService;
+use jj_ryu::repo::{JjWorkspace, select_remote};
+use jj_ryu::tracking::{
+    TrackedBookmark, load_pr_cache, load_tracking, save_pr_cache, save_tracking,
+};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Tool to import stacks from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportSource {
+    /// Graphite (`gt`) branch metadata
+    Graphite,
+    /// spr-style commits with `Pull Request:` trailers
+    Spr,
+    /// git-branchless-style stacks of bookmarked commits
+    Branchless,
+}
+
+impl ImportSource {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Graphite => "Graphite",
+            Self::Spr => "spr",
+            Self::Branchless => "git-branchless",
+        }
+    }
+}
+
+/// Options for the import command
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Show what would be imported without changing anything
+    pub dry_run: bool,
+}
+
+/// Run the import command
+///
+/// Each branch the source tool manages becomes a tracked bookmark, importing
+/// it from git first if jj doesn't know it yet. Open PRs the tool recorded
+/// are added to the PR cache so the next submit updates them instead of
+/// opening new ones.
+///
+/// spr commits without a bookmark get one named after their PR's head
+/// branch, which is looked up on the platform.
+pub async fn run_import(path: &Path, source: ImportSource, options: ImportOptions) -> Result<()> {
+    let (mut workspace, branches) = read_branches(path, source, options.dry_run).await?;
+    let workspace_root = workspace.workspace_root().to_path_buf();
+
+    if branches.is_empty() {
+        eprintln!(
+            "{}",
+            format!("No {} branches found", source.name()).muted()
+        );
+        return Ok(());
+    }
+
+    // Commit-based stacks are read from jj itself, so only Graphite can name
+    // branches jj hasn't imported yet
+    let missing: Vec<String> = if source == ImportSource::Graphite {
+        let local: HashSet<String> = workspace
+            .local_bookmarks()?
+            .into_iter()
+            .map(|b| b.name)
+            .collect();
+        branches
+            .iter()
+            .filter(|b| !local.contains(&b.name))
+            .map(|b| b.name.clone())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    if options.dry_run {
+        print_dry_run(source, &branches, &missing);
+        return Ok(());
+    }
+
+    if !missing.is_empty() {
+        workspace.import_git_branches(&missing)?;
+    }
+
+    let trunk = workspace.default_branch()?;
+    let remote = select_remote(&workspace.git_remotes()?, None).ok();
+    let mut tracking = load_tracking(&workspace_root)?;
+    let mut pr_cache = load_pr_cache(&workspace_root)?;
+
+    let mut imported = Vec::new();
+    for branch in &branches {
+        let Some(change_id) = workspace.get_change_id(&branch.name)? else {
+            eprintln!(
+                "{}",
+                format!("Skipping {}: no such branch in this repository", branch.name).warn()
+            );
+            continue;
+        };
+
+        if let Some(parent) = branch.parent.as_deref()
+            && parent != trunk
+            && !is_ancestor(&workspace, parent, &branch.name)
+        {
+            eprintln!(
+                "{}",
+                format!(
+                    "Warning: {} is not on top of {parent}; run 'ryu sync' or rebase it",
+                    branch.name
+                )
+                .warn()
+            );
+        }
+
+        if !tracking.is_tracked(&branch.name) {
+            tracking.track(TrackedBookmark {
+                name: branch.name.clone(),
+                change_id,
+                remote: None,
+                base: None,
+                tracked_at: Utc::now(),
+            });
+        }
+
+        let pr = branch.open_pull_request();
+        if let (Some(pr), Some(remote)) = (&pr, &remote) {
+            pr_cache.upsert(&branch.name, pr, remote);
+        }
+        imported.push((branch, pr.map(|pr| pr.number)));
+    }
+
+    save_tracking(&workspace_root, &tracking)?;
+    save_pr_cache(&workspace_root, &pr_cache)?;
+
+    eprintln!(
+        "Imported {} bookmark(s) from {}:",
+        imported.len(),
+        source.name()
+    );
+    for (branch, pr_number) in imported {
+        let pr = pr_number.map_or_else(String::new, |n| format!(" {}", format!("#{n}").muted()));
+        eprintln!("  {} {}{pr}", check(), branch.name.accent());
+    }
+
+    Ok(())
+}
+
+/// Open the workspace and read the branches `source` manages
+async fn read_branches(
+    path: &Path,
+    source: ImportSource,
+    dry_run: bool,
+) -> Result<(JjWorkspace, Vec<ImportedBranch>)> {
+    Ok(match source {
+        ImportSource::Graphite => {
+            let workspace = JjWorkspace::open(path)?;
+            let branches = read_graphite_branches(&workspace)?;
+            (workspace, branches)
+        }
+        ImportSource::Spr => {
+            let mut ctx = CommandContext::new(path, None).await?;
+            let mut commits = read_commit_stack(&ctx.workspace, CommitStackStyle::Spr)?;
+            let names = resolve_spr_prs(
+                &mut ctx.workspace,
+                ctx.platform.as_ref(),
+                &mut commits,
+                dry_run,
+            )
+            .await?;
+            let branches = stack_commits_to_branches(&commits, &names);
+            (ctx.workspace, branches)
+        }
+        ImportSource::Branchless => {
+            let workspace = JjWorkspace::open(path)?;
+            let commits = read_commit_stack(&workspace, CommitStackStyle::Branchless)?;
+            let branches = stack_commits_to_branches(&commits, &HashMap::new());
+            (workspace, branches)
+        }
+    })
+}
+
+/// Fill in spr PRs from the platform and name commits after their PR's head
+///
+/// Returns the names chosen for commits without a bookmark, keyed by commit
+/// ID. Unless `dry_run` is set, a bookmark is created for each of them.
+async fn resolve_spr_prs(
+    workspace: &mut JjWorkspace,
+    platform: &dyn PlatformService,
+    commits: &mut [StackCommit],
+    dry_run: bool,
+) -> Result<HashMap<String, String>> {
+    let mut names = HashMap::new();
+    for commit in commits.iter_mut() {
+        let Some(pr) = commit.pr.as_mut() else {
+            continue;
+        };
+        let details = match platform.get_pr_details(pr.number).await {
+            Ok(details) => details,
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    format!("Skipping PR #{}: {e}", pr.number).warn()
+                );
+                continue;
+            }
+        };
+
+        pr.url = Some(details.html_url);
+        pr.title = Some(details.title);
+        pr.base = Some(details.base_ref);
+        pr.state = Some(details.state);
+        pr.is_draft = details.is_draft;
+
+        if commit.bookmark.is_none() {
+            if !dry_run {
+                workspace.set_bookmark(&details.head_ref, &commit.commit_id)?;
+            }
+            names.insert(commit.commit_id.clone(), details.head_ref);
+        }
+    }
+    Ok(names)
+}
+
+/// Check whether `parent` is an ancestor of `child`
+fn is_ancestor(workspace: &JjWorkspace, parent: &str, child: &str) -> bool {
+    workspace
+        .resolve_revset(&format!(r#""{parent}" & ::"{child}""#))
+        .is_ok_and(|entries| !entries.is_empty())
+}
+
+fn print_dry_run(source: ImportSource, branches: &[ImportedBranch], missing: &[String]) {
+    eprintln!(
+        "Would import {} bookmark(s) from {}:",
+        branches.len(),
+        source.name()
+    );
+    for branch in branches {
+        let parent = branch.parent.as_deref().unwrap_or("trunk");
+        let mut notes = Vec::new();
+        if missing.contains(&branch.name) {
+            notes.push("from git".to_string());
+        }
+        if let Some(pr) = branch.open_pull_request() {
+            notes.push(format!("PR #{}", pr.number));
+        }
+        let notes = if notes.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", format!("({})", notes.join(", ")).muted())
+        };
+        eprintln!(
+            "  {} {} {}{notes}",
+            branch.name.accent(),
+            arrow(),
+            parent
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! `ryu init` command - interactive setup of a repository's ryu config
+
+use crate::cli::style::{Stylize, check, cross, spinner_style};
+use anstream::println;
+use dialoguer::{Confirm, Select};
+use indicatif::ProgressBar;
+use jj_ryu::auth::cli_config_hosts;
+use jj_ryu::config::{HostPlatform, load_config, repo_config_path, set_config_value};
+use jj_ryu::error::{Error, Result};
+use jj_ryu::platform::{
+    create_platform_service_with_auth, extract_hostname, parse_repo_info_with_hosts,
+};
+use jj_ryu::repo::{JjWorkspace, select_remote};
+use jj_ryu::types::{MergeMethod, Platform};
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::time::Duration;
+
+/// Platforms a self-hosted instance can run, as offered by the wizard
+const HOST_PLATFORMS: [(&str, HostPlatform); 3] = [
+    ("GitHub Enterprise", HostPlatform::GitHub),
+    ("GitLab", HostPlatform::GitLab),
+    ("Gerrit", HostPlatform::Gerrit),
+];
+
+/// Merge methods offered by the wizard
+const MERGE_METHODS: [MergeMethod; 3] =
+    [MergeMethod::Squash, MergeMethod::Merge, MergeMethod::Rebase];
+
+/// Run the init command
+///
+/// Detects the remote and platform ryu will use, checks that authentication
+/// works, and asks for submit and merge defaults. Answers are saved to the
+/// repo config (`.jj/repo/ryu/config.toml`), with the current config values
+/// as the suggested answers.
+#[allow(clippy::too_many_lines)]
+pub async fn run_init(path: &Path) -> Result<()> {
+    if !io::stdin().is_terminal() {
+        return Err(Error::InvalidArgument(
+            "ryu init is interactive; run it in a terminal".to_string(),
+        ));
+    }
+
+    let workspace = JjWorkspace::open(path)?;
+    let workspace_root = workspace.workspace_root().to_path_buf();
+    let config = load_config(&workspace_root)?;
+    let config_path = repo_config_path(&workspace_root);
+
+    // Remote
+    let remotes = workspace.git_remotes()?;
+    let remote_name = select_remote(&remotes, config.remote.as_deref())?;
+    let url = remotes
+        .iter()
+        .find(|r| r.name == remote_name)
+        .map(|r| r.url.clone())
+        .ok_or_else(|| Error::RemoteNotFound(remote_name.clone()))?;
+    println!("{} Remote: {} ({})", check(), remote_name.accent(), url.muted());
+
+    // Platform, asking which one runs on hosts ryu doesn't recognize
+    let mut hosts = cli_config_hosts();
+    hosts.extend(config.hosts.clone());
+    let platform_config = match parse_repo_info_with_hosts(&url, &hosts) {
+        Err(Error::NoSupportedRemotes) => {
+            let host = extract_hostname(&url)
+                .ok_or_else(|| Error::Parse(format!("cannot parse remote URL: {url}")))?;
+            let names: Vec<&str> = HOST_PLATFORMS.iter().map(|(name, _)| *name).collect();
+            let index = Select::new()
+                .with_prompt(format!("Which platform runs on {host}?"))
+                .items(&names)
+                .default(0)
+                .interact()
+                .map_err(prompt_error)?;
+            let platform = HOST_PLATFORMS[index].1;
+            let value = toml::Value::try_from(platform)
+                .map_err(|e| Error::Internal(format!("failed to encode platform: {e}")))?;
+            set_config_value(&config_path, &format!("hosts.\"{host}\""), value)?;
+            hosts.insert(host, platform);
+            parse_repo_info_with_hosts(&url, &hosts)?
+        }
+        result => result?,
+    };
+    let platform = platform_config.platform;
+    let location = platform_config
+        .host
+        .as_ref()
+        .map_or_else(String::new, |host| format!(" at {host}"));
+    println!(
+        "{} Platform: {platform}{location} ({}/{})",
+        check(),
+        platform_config.owner,
+        platform_config.repo
+    );
+
+    // Authentication
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(spinner_style());
+    spinner.set_message(format!("Checking {platform} authentication..."));
+    spinner.enable_steady_tick(Duration::from_millis(80));
+    let auth = create_platform_service_with_auth(&platform_config, &config.auth).await;
+    spinner.finish_and_clear();
+    match auth {
+        Ok(_) => println!("{} Authentication works", check()),
+        Err(e) => {
+            println!("{} Authentication failed: {e}", cross());
+            let command = match platform {
+                Platform::GitHub => Some("ryu auth github setup"),
+                Platform::GitLab => Some("ryu auth gitlab setup"),
+                Platform::Gerrit => Some("ryu auth gerrit setup"),
+                Platform::Custom(_) => None,
+            };
+            if let Some(command) = command {
+                println!("  {} {}", "To set it up, see:".muted(), command.accent());
+            }
+        }
+    }
+
+    // Defaults. Gerrit changes aren't drafts, merged by ryu or commented on.
+    if platform != Platform::Gerrit {
+        println!();
+        let draft = Confirm::new()
+            .with_prompt("Create new PRs as drafts?")
+            .default(config.submit.draft)
+            .interact()
+            .map_err(prompt_error)?;
+
+        let methods: Vec<String> = MERGE_METHODS.iter().map(ToString::to_string).collect();
+        let index = Select::new()
+            .with_prompt("How should ryu merge PRs?")
+            .items(&methods)
+            .default(
+                MERGE_METHODS
+                    .iter()
+                    .position(|m| *m == config.merge.method)
+                    .unwrap_or(0),
+            )
+            .interact()
+            .map_err(prompt_error)?;
+        let method = MERGE_METHODS[index];
+
+        let index = Select::new()
+            .with_prompt("Where should the stack overview go?")
+            .items(&["A comment on each PR", "Each PR's description"])
+            .default(usize::from(config.submit.stack_in_body))
+            .interact()
+            .map_err(prompt_error)?;
+        let stack_in_body = index == 1;
+
+        set_config_value(&config_path, "submit.draft", toml::Value::Boolean(draft))?;
+        set_config_value(
+            &config_path,
+            "merge.method",
+            toml::Value::String(method.to_string()),
+        )?;
+        set_config_value(
+            &config_path,
+            "submit.stack_in_body",
+            toml::Value::Boolean(stack_in_body),
+        )?;
+    }
+
+    println!();
+    println!(
+        "{} Saved settings to {}",
+        check(),
+        config_path.display().to_string().accent()
+    );
+    println!(
+        "Next, track your stack with {} and submit it with {}",
+        "ryu track --all".accent(),
+        "ryu submit".accent()
+    );
+    Ok(())
+}
+
+/// Error for a prompt that couldn't be answered (for `map_err`)
+#[allow(clippy::needless_pass_by_value)]
+fn prompt_error(e: dialoguer::Error) -> Error {
+    Error::Internal(format!("Failed to read answer: {e}"))
+}
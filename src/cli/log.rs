@@ -0,0 +1,202 @@
+//! `ryu log` command - graph of the stack annotated with each bookmark's PR
+
+use crate::cli::context::CommandContext;
+use crate::cli::style::{self, Stylize, arrow, pipe};
+use anstream::println;
+use futures::stream::{self, StreamExt};
+use jj_ryu::error::Result;
+use jj_ryu::graph::build_change_graph;
+use jj_ryu::repo::JjWorkspace;
+use jj_ryu::submit::analyze_submission;
+use jj_ryu::tracking::{load_pr_cache, load_tracking, save_pr_cache};
+use jj_ryu::types::PullRequest;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Options for the log command
+#[derive(Debug, Clone, Default)]
+pub struct LogOptions {
+    /// Only show PRs from the local cache, without contacting the platform
+    pub offline: bool,
+}
+
+/// A bookmark's PR as shown in the log
+struct PrLine {
+    number: u64,
+    title: String,
+    base: String,
+}
+
+/// Run the log command
+///
+/// Prints the stack from the working copy down to trunk, one line per
+/// change, with each bookmark annotated with its PR's number, title and base.
+/// PRs are looked up on the platform, falling back to the PR cache when it
+/// can't be reached (or with `--offline`). Bookmarks without a PR show the
+/// base `ryu submit` would give them.
+#[allow(clippy::too_many_lines)]
+pub async fn run_log(path: &Path, remote: Option<&str>, options: LogOptions) -> Result<()> {
+    let ctx = if options.offline {
+        None
+    } else {
+        match CommandContext::new(path, remote).await {
+            Ok(ctx) => Some(ctx),
+            Err(e) => {
+                println!(
+                    "{}",
+                    format!("Couldn't reach the platform ({e}); showing cached PRs").muted()
+                );
+                None
+            }
+        }
+    };
+    let (workspace, tracking, mut pr_cache, platform) = if let Some(ctx) = ctx {
+        (
+            ctx.workspace,
+            ctx.tracking,
+            ctx.pr_cache,
+            Some((ctx.platform, ctx.remote_name)),
+        )
+    } else {
+        let workspace = JjWorkspace::open(path)?;
+        let workspace_root = workspace.workspace_root().to_path_buf();
+        let tracking = load_tracking(&workspace_root).unwrap_or_default();
+        let pr_cache = load_pr_cache(&workspace_root).unwrap_or_default();
+        (workspace, tracking, pr_cache, None)
+    };
+
+    let graph = build_change_graph(&workspace)?;
+    let Some(stack) = &graph.stack else {
+        println!("{}", "No stack found between trunk and working copy.".muted());
+        return Ok(());
+    };
+    let analysis = analyze_submission(&graph, None)?;
+    let default_branch = workspace.default_branch()?;
+    let trunk = tracking
+        .stack_base(analysis.segments.iter().map(|s| s.bookmark.name.as_str()))
+        .unwrap_or(&default_branch)
+        .to_string();
+
+    let names: Vec<String> = stack
+        .segments
+        .iter()
+        .flat_map(|s| s.bookmarks.iter().map(|b| b.name.clone()))
+        .collect();
+
+    // Live PRs, with the cache refreshed from them. A failed lookup falls
+    // back to the cache; a bookmark the platform has no PR for shows none.
+    let mut live: HashMap<String, Option<PullRequest>> = HashMap::new();
+    if let Some((platform, remote_name)) = &platform {
+        let platform = platform.as_ref();
+        let fetched: Vec<(String, Result<Option<PullRequest>>)> = stream::iter(names.iter())
+            .map(|name| async move { (name.clone(), platform.find_existing_pr(name).await) })
+            .buffered(platform.max_concurrency())
+            .collect()
+            .await;
+        for (name, pr) in fetched {
+            let Ok(pr) = pr else { continue };
+            if let Some(pr) = &pr {
+                pr_cache.upsert(&name, pr, remote_name);
+            }
+            live.insert(name, pr);
+        }
+        // Best effort - the cache is only used to show PRs offline
+        let _ = save_pr_cache(workspace.workspace_root(), &pr_cache);
+    }
+
+    let pr_line = |name: &str| -> Option<PrLine> {
+        live.get(name).map_or_else(
+            || {
+                pr_cache.get(name).map(|pr| PrLine {
+                    number: pr.number,
+                    title: pr.title.clone(),
+                    base: pr.base.clone(),
+                })
+            },
+            |pr| {
+                pr.as_ref().map(|pr| PrLine {
+                    number: pr.number,
+                    title: pr.title.clone(),
+                    base: pr.base_ref.clone(),
+                })
+            },
+        )
+    };
+
+    // Newest first, like `jj log`
+    for (i, segment) in stack.segments.iter().enumerate().rev() {
+        let planned_base = if i == 0 {
+            trunk.clone()
+        } else {
+            analysis.segments[i - 1].bookmark.name.clone()
+        };
+
+        for (j, change) in segment.changes.iter().enumerate() {
+            let marker = if change.is_working_copy {
+                style::CURRENT
+            } else {
+                style::BULLET
+            };
+            let change_short = &change.change_id[..8.min(change.change_id.len())];
+            let commit_short = &change.commit_id[..8.min(change.commit_id.len())];
+            let desc = if change.description_first_line.is_empty() {
+                "(no description)"
+            } else {
+                &change.description_first_line
+            };
+            println!(
+                "{marker}  {} {} {desc}",
+                change_short.muted(),
+                commit_short.muted()
+            );
+
+            // Bookmarks point at the segment's tip, its newest change
+            if j == 0 {
+                for bookmark in &segment.bookmarks {
+                    let name = bookmark.name.as_str();
+                    let name_display = if tracking.is_tracked(name) {
+                        name.accent()
+                    } else {
+                        name.muted()
+                    };
+                    match pr_line(name) {
+                        Some(pr) => {
+                            let title = if pr.title.is_empty() {
+                                String::new()
+                            } else {
+                                format!(" {}", pr.title)
+                            };
+                            let base = if pr.base.is_empty() {
+                                String::new()
+                            } else {
+                                format!(" {} {}", arrow(), pr.base)
+                            };
+                            println!(
+                                "{}  {name_display} {}{title}{base}",
+                                pipe(),
+                                format!("#{}", pr.number).emphasis()
+                            );
+                        }
+                        None => println!(
+                            "{}  {name_display} {}",
+                            pipe(),
+                            format!("(no PR) {} {planned_base}", style::ARROW).muted()
+                        ),
+                    }
+                }
+            }
+            println!("{}", pipe());
+        }
+    }
+    println!("{}  {}", "◆".muted(), trunk.muted());
+
+    if platform.is_none() {
+        println!();
+        println!(
+            "{}",
+            "PRs are from the local cache and may be out of date.".muted()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+//! Debug logging for `--verbose` and `log.file`
+//!
+//! The `tracing::debug!` output of the library (API calls, fetches and
+//! pushes, retries, with timings) is off by default. `-v` sends ryu's debug
+//! output to stderr and `-vv` adds ryu's trace output and the debug output of
+//! its dependencies. With `log.file` (or `RYU_LOG_FILE`) set, the log is
+//! appended to that file instead, so stdout and stderr stay clean.
+
+use jj_ryu::config::load_config;
+use jj_ryu::error::{Error, Result};
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::Level;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt;
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Crates whose output `-v` turns on: the library and the binary
+const RYU_TARGETS: [&str; 2] = ["jj_ryu", "ryu"];
+
+/// Start logging at `verbosity` (the number of `-v` flags)
+///
+/// Does nothing without `-v` unless a log file is configured, which then
+/// gets ryu's debug output.
+pub fn init_logging(path: &Path, verbosity: u8) -> Result<()> {
+    // Best effort: a broken config is reported by the command itself
+    let file = load_config(path).ok().and_then(|config| config.log.file);
+    if file.is_none() && verbosity == 0 {
+        return Ok(());
+    }
+
+    let (level, others) = if verbosity >= 2 {
+        (Level::TRACE, LevelFilter::DEBUG)
+    } else {
+        (Level::DEBUG, LevelFilter::WARN)
+    };
+    let filter = RYU_TARGETS
+        .iter()
+        .fold(Targets::new(), |targets, target| {
+            targets.with_target(*target, level)
+        })
+        .with_default(others);
+
+    let subscriber = fmt().with_max_level(LevelFilter::TRACE);
+    let result = if let Some(file) = file {
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&file)
+            .map_err(|e| {
+                Error::Config(format!("failed to open log file {}: {e}", file.display()))
+            })?;
+        tracing::subscriber::set_global_default(
+            subscriber
+                .with_writer(Mutex::new(log))
+                .finish()
+                .with(filter),
+        )
+    } else {
+        tracing::subscriber::set_global_default(
+            subscriber
+                .with_writer(std::io::stderr)
+                .finish()
+                .with(filter),
+        )
+    };
+    result.map_err(|e| Error::Internal(format!("failed to start logging: {e}")))
+}
@@ -1,31 +1,87 @@
 //! Merge command - merge approved PRs in the stack
 
 use crate::cli::context::CommandContext;
-use crate::cli::style::{Stylize, check, spinner_style};
+use crate::cli::style::{Stylize, arrow, check, cross, spinner_style};
 use crate::cli::CliProgress;
-use anstream::println;
-use dialoguer::Confirm;
+use crate::cli::notify::notify;
+use crate::cli::output::{emit_json, emit_stopped, println};
+use crate::cli::restack::pause_on_conflicts;
+use crate::cli::submit::print_simulation_notice;
+use dialoguer::{Confirm, Editor};
+use futures::stream::{self, StreamExt};
 use indicatif::ProgressBar;
 use jj_ryu::error::{Error, Result};
 use jj_ryu::graph::build_change_graph;
+use jj_ryu::hooks::HookEvent;
+use jj_ryu::platform::PlatformService;
 use jj_ryu::merge::{
-    create_merge_plan, execute_merge, MergeConfidence, MergeExecutionResult, MergePlan,
-    MergePlanOptions, MergeStep, PrInfo,
+    commit_trailers, compose_squash_message, create_auto_merge_plan, create_merge_plan,
+    execute_merge, parse_squash_message, MergeConfidence, MergeExecutionResult, MergePlan,
+    MergePlanOptions, MergeStep, PrInfo, SquashMessage,
 };
-use jj_ryu::submit::{analyze_submission, create_submission_plan, execute_submission};
-use jj_ryu::tracking::{save_pr_cache, save_tracking};
-use jj_ryu::types::NarrowedBookmarkSegment;
+use jj_ryu::submit::{
+    SubmissionAnalysis, analyze_submission, apply_config_to_plan, capture_rollback_snapshot,
+    create_submission_plan_onto, execute_submission, publish_bottom_draft, record_undo,
+};
+use jj_ryu::tracking::{save_pr_cache, save_tracking, UndoRecord};
+use jj_ryu::types::{NarrowedBookmarkSegment, Platform, PullRequestDetails};
+use jj_ryu::webhooks::ChatSummary;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::path::Path;
 use std::time::Duration;
 
+/// `ryu merge --json` document
+#[derive(Serialize)]
+struct MergeReport<'a> {
+    command: &'static str,
+    dry_run: bool,
+    /// Auto-merge was enabled instead of merging
+    auto: bool,
+    success: bool,
+    plan: &'a MergePlan,
+    /// None if nothing was merged
+    result: Option<&'a MergeExecutionResult>,
+}
+
+impl<'a> MergeReport<'a> {
+    const fn new(plan: &'a MergePlan, options: &MergeOptions) -> Self {
+        Self {
+            command: "merge",
+            dry_run: options.dry_run,
+            auto: options.auto,
+            success: true,
+            plan,
+            result: None,
+        }
+    }
+}
+
 /// Options for the merge command
 #[derive(Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct MergeOptions {
     /// Dry run - show what would be merged without making changes
     pub dry_run: bool,
     /// Preview plan and prompt for confirmation before executing
     pub confirm: bool,
+    /// Send a desktop notification when merging finishes or is blocked
+    pub notify: bool,
+    /// Update PR branches that are behind their base before merging
+    pub update_branch: bool,
+    /// Edit each squash commit message in `$EDITOR` before merging
+    pub edit_message: bool,
+    /// Merge on the simulated platform and leave the repo untouched
+    pub simulate: bool,
+    /// Enable auto-merge instead of merging now
+    pub auto: bool,
+    /// How long to wait for GitHub to finish computing mergeability before
+    /// planning with an unknown status
+    pub wait_mergeable: Option<Duration>,
+    /// Merge up to and including this bookmark
+    pub target: Option<String>,
+    /// Merge at most this many PRs
+    pub limit: Option<usize>,
 }
 
 /// Run the merge command
@@ -35,7 +91,23 @@ pub async fn run_merge(path: &Path, remote: Option<&str>, options: MergeOptions)
     // Phase 1: GATHER - Collect all data upfront
     // =========================================================================
 
-    let mut ctx = CommandContext::new(path, remote).await?;
+    if options.limit == Some(0) {
+        return Err(Error::InvalidArgument(
+            "--limit must be at least 1".to_string(),
+        ));
+    }
+
+    let mut ctx = if options.simulate {
+        print_simulation_notice();
+        CommandContext::simulated(path, remote).await?
+    } else {
+        CommandContext::new(path, remote).await?
+    };
+    if ctx.platform.config().platform == Platform::Gerrit {
+        return Err(Error::Unsupported(
+            "ryu merge is not supported with Gerrit; submit changes from the Gerrit web UI".to_string(),
+        ));
+    }
 
     // Check tracking
     // Collect into owned strings to avoid borrow checker issues with later mutations
@@ -51,11 +123,13 @@ pub async fn run_merge(path: &Path, remote: Option<&str>, options: MergeOptions)
 
     if graph.stack.is_none() {
         println!("{}", "No stack found between trunk and working copy.".muted());
+        emit_stopped("merge", true, "No stack found between trunk and working copy")?;
         return Ok(());
     }
 
     // Get stack analysis (reuse existing infrastructure)
     let analysis = analyze_submission(&graph, None)?;
+    let base = ctx.stack_base(&analysis);
 
     // Filter to tracked bookmarks
     let tracked_segments: Vec<&NarrowedBookmarkSegment> = analysis
@@ -66,29 +140,55 @@ pub async fn run_merge(path: &Path, remote: Option<&str>, options: MergeOptions)
 
     if tracked_segments.is_empty() {
         println!("{}", "No tracked bookmarks in stack.".muted());
+        emit_stopped("merge", true, "No tracked bookmarks in stack")?;
         return Ok(());
     }
 
+    if let Some(target) = &options.target
+        && !tracked_segments.iter().any(|s| &s.bookmark.name == target)
+    {
+        return Err(if graph.bookmarks.contains_key(target) {
+            Error::Tracking(format!(
+                "Bookmark '{target}' is not tracked. Run 'ryu track {target}' first."
+            ))
+        } else {
+            Error::BookmarkNotFound(target.clone())
+        });
+    }
+
     // Batch fetch all PR info (details + readiness)
     println!(
         "{}",
         format!("Checking {} tracked bookmark(s)...", tracked_segments.len()).muted()
     );
-    let pr_info_map = fetch_all_pr_info(&tracked_segments, &ctx).await?;
+    let pr_info_map = fetch_all_pr_info(
+        &tracked_segments,
+        ctx.platform.as_ref(),
+        options.wait_mergeable,
+    )
+    .await?;
 
     if pr_info_map.is_empty() {
         println!("{}", "No PRs found for tracked bookmarks.".muted());
+        emit_stopped("merge", true, "No PRs found for tracked bookmarks")?;
         return Ok(());
     }
 
+    if options.auto {
+        return enable_auto_merge(&ctx, &analysis, &pr_info_map, &base, &options).await;
+    }
+
     // =========================================================================
     // Phase 2: PLAN - Pure function, easily testable
     // =========================================================================
 
     let plan_options = MergePlanOptions {
-        target_bookmark: None, // Merge all consecutive mergeable PRs
+        target_bookmark: options.target.clone(),
+        limit: options.limit,
+        update_branch: options.update_branch,
+        method: ctx.config.merge.method,
     };
-    let merge_plan = create_merge_plan(&analysis, &pr_info_map, &plan_options, &ctx.default_branch);
+    let mut merge_plan = create_merge_plan(&analysis, &pr_info_map, &plan_options, &base);
 
     // =========================================================================
     // Phase 3: EXECUTE - Effectful operations
@@ -97,6 +197,7 @@ pub async fn run_merge(path: &Path, remote: Option<&str>, options: MergeOptions)
     // Dry run - just report
     if options.dry_run {
         report_merge_dry_run(&merge_plan);
+        emit_json(&MergeReport::new(&merge_plan, &options))?;
         return Ok(());
     }
 
@@ -104,6 +205,10 @@ pub async fn run_merge(path: &Path, remote: Option<&str>, options: MergeOptions)
     if merge_plan.is_empty() {
         println!("{}", "No PRs are ready to merge.".muted());
         print_blocking_summary(&merge_plan);
+        if options.notify {
+            notify_blocked(&merge_plan);
+        }
+        emit_json(&MergeReport::new(&merge_plan, &options))?;
         return Ok(());
     }
 
@@ -122,6 +227,11 @@ pub async fn run_merge(path: &Path, remote: Option<&str>, options: MergeOptions)
         println!();
     }
 
+    if options.edit_message && !edit_merge_messages(&mut merge_plan, &analysis)? {
+        println!("{}", "Aborted".muted());
+        return Ok(());
+    }
+
     // Execute merges
     println!(
         "{} {}",
@@ -129,11 +239,45 @@ pub async fn run_merge(path: &Path, remote: Option<&str>, options: MergeOptions)
         format!("{} PR(s)...", merge_plan.merge_count()).accent()
     );
 
+    // Record where the repo is before merging, for 'ryu undo'
+    let mut undo = ctx.begin_undo("merge")?;
+
     let progress = CliProgress::compact();
     let merge_result = execute_merge(&merge_plan, ctx.platform.as_ref(), &progress).await?;
+    emit_json(&MergeReport {
+        success: merge_result.is_success(),
+        result: Some(&merge_result),
+        ..MergeReport::new(&merge_plan, &options)
+    })?;
+    if let Some(undo) = &mut undo {
+        undo.merged_bookmarks.clone_from(&merge_result.merged_bookmarks);
+        ctx.save_undo(Some(undo));
+    }
+
+    if merge_result.has_merges() {
+        ctx.run_hook(HookEvent::PostMerge, &merge_result).await;
+        let summary = ChatSummary::merge(
+            ctx.workspace.user_name(),
+            &analysis.target_bookmark,
+            &ctx.repo_name(),
+            &merge_result,
+            &pr_info_map,
+        );
+        ctx.post_webhooks(summary).await;
+    }
+    if options.notify {
+        notify_merge_result(&merge_result);
+    }
 
     // Post-merge cleanup and sync
-    if merge_result.bottom_merged() {
+    if ctx.simulated {
+        // Nothing was merged for real, so keep the local bookmarks and stack
+        print_merge_summary(&merge_result);
+        println!(
+            "{}",
+            "Simulated merge: local bookmarks and tracking were left as they are".muted()
+        );
+    } else if merge_result.bottom_merged() {
         // Clean up merged bookmarks
         for bookmark in &merge_result.merged_bookmarks {
             ctx.pr_cache.remove(bookmark);
@@ -161,7 +305,15 @@ pub async fn run_merge(path: &Path, remote: Option<&str>, options: MergeOptions)
         }
 
         // Post-merge sync: fetch, rebase, re-submit
-        post_merge_sync(&mut ctx, &merge_plan, &merge_result).await?;
+        post_merge_sync(
+            &mut ctx,
+            &merge_plan,
+            &merge_result,
+            &base,
+            options.notify,
+            undo.as_mut(),
+        )
+        .await?;
     } else {
         // Print summary without sync
         print_merge_summary(&merge_result);
@@ -170,47 +322,235 @@ pub async fn run_merge(path: &Path, remote: Option<&str>, options: MergeOptions)
     Ok(())
 }
 
-/// Fetch all PR info upfront (details + readiness)
+/// Enable auto-merge on the PRs that can land on the base branch by
+/// themselves (`ryu merge --auto`)
 #[allow(clippy::future_not_send)]
-async fn fetch_all_pr_info(
-    segments: &[&NarrowedBookmarkSegment],
+async fn enable_auto_merge(
     ctx: &CommandContext,
-) -> Result<HashMap<String, PrInfo>> {
-    let mut result = HashMap::new();
+    analysis: &SubmissionAnalysis,
+    pr_info_map: &HashMap<String, PrInfo>,
+    base: &str,
+    options: &MergeOptions,
+) -> Result<()> {
+    let plan = create_auto_merge_plan(analysis, pr_info_map, base, ctx.config.merge.method);
+
+    if options.dry_run || options.confirm {
+        print_auto_merge_plan(&plan);
+    }
+    if options.dry_run {
+        return emit_json(&MergeReport::new(&plan, options));
+    }
+    if plan.is_empty() {
+        println!("{}", "No PRs are ready for auto-merge.".muted());
+        print_blocking_summary(&plan);
+        return emit_json(&MergeReport::new(&plan, options));
+    }
+    if options.confirm
+        && !Confirm::new()
+            .with_prompt("Enable auto-merge?")
+            .default(true)
+            .interact()
+            .map_err(|e| Error::Internal(format!("Failed to read confirmation: {e}")))?
+    {
+        println!("{}", "Aborted".muted());
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for step in &plan.steps {
+        let MergeStep::Merge {
+            pr_number,
+            pr_title,
+            method,
+            ..
+        } = step
+        else {
+            continue;
+        };
+        match ctx.platform.enable_auto_merge(*pr_number, *method).await {
+            Ok(()) => println!("{} Auto-merge enabled for PR #{pr_number}: {pr_title}", check()),
+            Err(e) => {
+                failed += 1;
+                println!("{} PR #{pr_number}: {e}", cross());
+            }
+        }
+    }
+
+    if plan.steps.iter().any(|s| matches!(s, MergeStep::Skip { .. })) {
+        println!();
+        println!("{}", "Not enabled:".muted());
+        print_blocking_summary(&plan);
+    }
+
+    emit_json(&MergeReport {
+        success: failed == 0,
+        ..MergeReport::new(&plan, options)
+    })?;
+    if failed > 0 {
+        return Err(Error::Platform(format!(
+            "failed to enable auto-merge on {failed} PR(s)"
+        )));
+    }
+    Ok(())
+}
 
-    for segment in segments {
-        let bookmark_name = &segment.bookmark.name;
+/// Print which PRs `ryu merge --auto` will enable auto-merge on
+fn print_auto_merge_plan(plan: &MergePlan) {
+    println!("{}:", "Auto-merge plan".emphasis());
+    println!();
+    for step in &plan.steps {
+        match step {
+            MergeStep::Merge {
+                pr_number,
+                pr_title,
+                confidence,
+                ..
+            } => {
+                println!("  {} PR #{pr_number}: {pr_title}", "✓ Would enable".success());
+                if let MergeConfidence::Uncertain(reason) = confidence {
+                    println!("    {}", reason.muted());
+                }
+            }
+            MergeStep::Skip {
+                bookmark,
+                pr_number,
+                reasons,
+            } => {
+                println!("  {} PR #{pr_number} ({bookmark})", "✗ Would skip".warn());
+                for reason in reasons {
+                    println!("    - {}", reason.muted());
+                }
+            }
+            MergeStep::RetargetBase { .. } | MergeStep::UpdateBranch { .. } => {}
+        }
+    }
+    println!();
+}
 
-        // Find existing PR
-        let Some(existing) = ctx.platform.find_existing_pr(bookmark_name).await? else {
+/// Let the user edit each squash commit message in `$EDITOR`
+///
+/// The message is pre-filled from the PR title and body plus trailers from
+/// the PR's commits. Returns false if a message was left empty, which aborts
+/// the merge.
+fn edit_merge_messages(plan: &mut MergePlan, analysis: &SubmissionAnalysis) -> Result<bool> {
+    for step in &mut plan.steps {
+        let MergeStep::Merge {
+            bookmark,
+            pr_number,
+            pr_title,
+            pr_body,
+            ..
+        } = step
+        else {
             continue;
         };
 
-        // Fetch details and readiness
-        let details = ctx.platform.get_pr_details(existing.number).await?;
-        let readiness = ctx.platform.check_merge_readiness(existing.number).await?;
+        let trailers = analysis
+            .segments
+            .iter()
+            .find(|s| s.bookmark.name == *bookmark)
+            .map(|s| commit_trailers(&s.changes))
+            .unwrap_or_default();
+        let message = SquashMessage {
+            title: pr_title.clone(),
+            body: pr_body.clone(),
+        };
+        let text = compose_squash_message(&message, *pr_number, bookmark, &trailers);
+
+        let edited = Editor::new()
+            .require_save(false)
+            .edit(&text)
+            .map_err(|e| Error::Internal(format!("Failed to run editor: {e}")))?;
+        let Some(edited) = edited.as_deref().and_then(parse_squash_message) else {
+            return Ok(false);
+        };
+        *pr_title = edited.title;
+        *pr_body = edited.body;
+    }
+    Ok(true)
+}
 
-        result.insert(
-            bookmark_name.clone(),
-            PrInfo {
+/// Fetch all PR info upfront (details + readiness)
+///
+/// Each bookmark's lookups are independent, so they run concurrently up to
+/// the platform's `max_concurrency`.
+pub async fn fetch_all_pr_info(
+    segments: &[&NarrowedBookmarkSegment],
+    platform: &dyn PlatformService,
+    wait_mergeable: Option<Duration>,
+) -> Result<HashMap<String, PrInfo>> {
+    let fetched: Vec<Result<Option<PrInfo>>> = stream::iter(segments)
+        .map(|segment| async move {
+            let bookmark_name = &segment.bookmark.name;
+
+            // Find existing PR
+            let Some(existing) = platform.find_existing_pr(bookmark_name).await? else {
+                return Ok(None);
+            };
+
+            // Fetch details and readiness
+            let details = match wait_mergeable {
+                Some(timeout) => fetch_resolved_details(platform, existing.number, timeout).await?,
+                None => platform.get_pr_details(existing.number).await?,
+            };
+            let readiness = platform.check_merge_readiness(&details).await?;
+
+            Ok(Some(PrInfo {
                 bookmark: bookmark_name.clone(),
                 details,
                 readiness,
-            },
-        );
+            }))
+        })
+        .buffered(platform.max_concurrency())
+        .collect()
+        .await;
+
+    let mut result = HashMap::new();
+    for info in fetched {
+        if let Some(info) = info? {
+            result.insert(info.bookmark.clone(), info);
+        }
     }
 
     Ok(result)
 }
 
+/// Fetch PR details, polling while the platform is still computing mergeability
+///
+/// GitHub reports `mergeable: null` until a background job has checked the
+/// PR for conflicts, which usually takes a few seconds. Polls with backoff
+/// until it resolves or `timeout` passes, then returns the last details.
+async fn fetch_resolved_details(
+    platform: &dyn PlatformService,
+    pr_number: u64,
+    timeout: Duration,
+) -> Result<PullRequestDetails> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut delay = Duration::from_secs(1);
+
+    loop {
+        let details = platform.get_pr_details(pr_number).await?;
+        let now = tokio::time::Instant::now();
+        if details.mergeable.is_some() || now >= deadline {
+            return Ok(details);
+        }
+
+        tokio::time::sleep(delay.min(deadline - now)).await;
+        delay = (delay * 2).min(Duration::from_secs(8));
+    }
+}
+
 /// Post-merge sync: fetch, rebase remaining stack, re-submit
 ///
 /// Only called when bottom-most PR merged successfully (trunk changed).
-#[allow(clippy::future_not_send)]
+#[allow(clippy::future_not_send, clippy::too_many_lines)]
 async fn post_merge_sync(
     ctx: &mut CommandContext,
     plan: &MergePlan,
     merge_result: &MergeExecutionResult,
+    base: &str,
+    notify_conflicts: bool,
+    undo: Option<&mut UndoRecord>,
 ) -> Result<()> {
     // Fetch to get new main
     let spinner = ProgressBar::new_spinner();
@@ -232,11 +572,18 @@ async fn post_merge_sync(
     // Rebase remaining stack if there's a target
     if let Some(ref next_bookmark) = plan.rebase_target {
         println!(
-            "🔄 Rebasing {} onto trunk...",
-            next_bookmark.accent()
+            "🔄 Rebasing {} onto {}...",
+            next_bookmark.accent(),
+            base.emphasis()
         );
 
-        if let Err(e) = ctx.workspace.rebase_bookmark_onto_trunk(next_bookmark) {
+        let rebased = if base == ctx.default_branch {
+            ctx.workspace.rebase_bookmark_onto_trunk(next_bookmark)
+        } else {
+            ctx.workspace
+                .rebase_bookmark_onto_remote(next_bookmark, base, &ctx.remote_name)
+        };
+        if let Err(e) = rebased {
             // Rebase failure - warn but don't fail the command
             println!(
                 "{}",
@@ -246,6 +593,12 @@ async fn post_merge_sync(
                 "{}",
                 "   Run 'jj rebase' manually to fix.".muted()
             );
+            if notify_conflicts {
+                notify(
+                    "ryu: rebase failed",
+                    &format!("Rebasing {next_bookmark} onto {base} failed: {e}"),
+                );
+            }
         } else {
             // Re-submit to update PR bases
             println!("📤 Updating remaining PRs...");
@@ -262,18 +615,46 @@ async fn post_merge_sync(
                 .segments
                 .retain(|s| tracked_names.contains(&s.bookmark.name));
 
+            // Conflicted commits mustn't be pushed; 'ryu sync --continue' takes over
+            if pause_on_conflicts(ctx, &filtered_analysis.segments, base)? {
+                if notify_conflicts {
+                    notify(
+                        "ryu: rebase left conflicts",
+                        &format!("Resolve them, then run 'ryu sync --continue' ({next_bookmark})"),
+                    );
+                }
+                print_merge_summary(merge_result);
+                return Ok(());
+            }
+
             if !filtered_analysis.segments.is_empty() {
                 // Create submission plan and execute
-                let submit_plan = create_submission_plan(
+                let mut submit_plan = create_submission_plan_onto(
                     &filtered_analysis,
                     ctx.platform.as_ref(),
                     &ctx.remote_name,
+                    base,
                     &ctx.default_branch,
                 )
                 .await?;
+                submit_plan.push_remotes = ctx.tracking.push_remotes();
+                apply_config_to_plan(
+                    &mut submit_plan,
+                    &ctx.config.submit,
+                    &ctx.pr_cache,
+                    ctx.config.submit.draft,
+                )?;
+
+                // The new bottom of the stack is ready for review
+                if ctx.config.drafts.auto_publish
+                    && let Some(number) = publish_bottom_draft(&mut submit_plan)
+                {
+                    println!("{} Publishing draft #{number}", arrow());
+                }
 
+                let snapshot = capture_rollback_snapshot(&submit_plan, &ctx.workspace)?;
                 let progress = CliProgress::compact();
-                if let Err(e) = execute_submission(
+                match execute_submission(
                     &submit_plan,
                     &mut ctx.workspace,
                     ctx.platform.as_ref(),
@@ -282,15 +663,24 @@ async fn post_merge_sync(
                 )
                 .await
                 {
-                    // Soft failure - merge succeeded, just PR updates failed
-                    println!(
-                        "{}",
-                        format!("⚠️  Failed to update remaining PRs: {e}").warn()
-                    );
-                    println!(
-                        "{}",
-                        "   Run 'ryu submit' to complete the update.".muted()
-                    );
+                    Ok(result) => {
+                        if let Some(undo) = undo {
+                            record_undo(undo, &submit_plan, &snapshot, &result);
+                            ctx.save_undo(Some(undo));
+                        }
+                        ctx.save_submitted_prs(&submit_plan, &result);
+                    }
+                    Err(e) => {
+                        // Soft failure - merge succeeded, just PR updates failed
+                        println!(
+                            "{}",
+                            format!("⚠️  Failed to update remaining PRs: {e}").warn()
+                        );
+                        println!(
+                            "{}",
+                            "   Run 'ryu submit' to complete the update.".muted()
+                        );
+                    }
                 }
             }
         }
@@ -340,6 +730,33 @@ fn print_merge_summary(merge_result: &MergeExecutionResult) {
     }
 }
 
+/// Notify that merges landed and/or one failed
+fn notify_merge_result(merge_result: &MergeExecutionResult) {
+    if let Some(ref failed) = merge_result.failed_bookmark {
+        let reason = merge_result.error_message.as_deref().unwrap_or("merge failed");
+        notify("ryu: merge failed", &format!("{failed}: {reason}"));
+    } else if merge_result.has_merges() {
+        notify(
+            "ryu: merge complete",
+            &format!("Merged {}", merge_result.merged_bookmarks.join(", ")),
+        );
+    }
+}
+
+/// Notify which PR is blocking the stack, and why
+fn notify_blocked(plan: &MergePlan) {
+    let blocker = plan.steps.iter().find_map(|step| match step {
+        MergeStep::Skip {
+            pr_number, reasons, ..
+        } => Some(format!("PR #{pr_number}: {}", reasons.join(", "))),
+        _ => None,
+    });
+    notify(
+        "ryu: merge blocked",
+        blocker.as_deref().unwrap_or("No PRs are ready to merge"),
+    );
+}
+
 /// Report what would be merged (dry run)
 fn report_merge_dry_run(plan: &MergePlan) {
     println!("{}:", "Merge plan".emphasis());
@@ -396,6 +813,17 @@ fn report_merge_dry_run(plan: &MergePlan) {
                     new_base.accent()
                 );
             }
+            MergeStep::UpdateBranch {
+                bookmark,
+                pr_number,
+            } => {
+                println!(
+                    "  {} PR #{} ({}) from its base",
+                    "⬆ Would update".accent(),
+                    pr_number,
+                    bookmark
+                );
+            }
             MergeStep::Skip {
                 bookmark,
                 pr_number,
@@ -438,3 +866,47 @@ fn print_blocking_summary(plan: &MergePlan) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Scripts parse this document; renaming a field breaks them
+    #[test]
+    fn test_merge_report_fields() {
+        let plan = MergePlan {
+            steps: vec![],
+            bookmarks_to_clear: vec!["feat-a".to_string()],
+            rebase_target: None,
+            has_actionable: true,
+            trunk_branch: "main".to_string(),
+        };
+        let result = MergeExecutionResult::default();
+        let mut report = MergeReport::new(
+            &plan,
+            &MergeOptions {
+                auto: true,
+                ..MergeOptions::default()
+            },
+        );
+        report.result = Some(&result);
+
+        let json = serde_json::to_value(&report).unwrap();
+        let mut keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            ["auto", "command", "dry_run", "plan", "result", "success"]
+        );
+        assert_eq!(json["command"], "merge");
+        assert_eq!(json["auto"], true);
+        assert_eq!(json["dry_run"], false);
+        assert_eq!(json["plan"]["trunk_branch"], "main");
+        assert!(json["result"].is_object());
+    }
+}
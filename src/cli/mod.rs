@@ -2,22 +2,83 @@
 //!
 //! Command implementations for the `ryu` binary.
 
+mod abandon;
+mod adopt;
+mod aliases;
 mod analyze;
 mod auth;
+mod backport;
+mod checks;
+mod clean;
+mod comment;
+mod config;
 mod context;
+mod draft;
+mod export;
+mod flush;
+mod gerrit;
+mod import;
+mod init;
+mod log;
+mod logging;
 mod merge;
+mod navigate;
+mod notify;
+mod output;
 mod progress;
+mod prompt;
+mod rename;
+mod restack;
+mod reviews;
+#[cfg(feature = "self-update")]
+mod self_update;
+mod send_email;
+mod split;
 pub mod style;
 mod submit;
 mod sync;
 mod track;
+#[cfg(feature = "tui")]
+mod tui;
+mod undo;
 mod untrack;
+mod wait;
+mod web;
 
+pub use abandon::{AbandonOptions, run_abandon};
+pub use adopt::{AdoptOptions, run_adopt};
+pub use aliases::{AliasOptions, run_install_jj_aliases};
 pub use analyze::run_analyze;
-pub use auth::run_auth;
+pub use auth::{run_auth, run_auth_login, run_auth_status, run_auth_store};
+pub use backport::{BackportOptions, run_backport};
+pub use checks::{ChecksOptions, run_checks};
+pub use clean::{CleanOptions, run_clean};
+pub use comment::run_comment;
+pub use config::{run_config_get, run_config_list, run_config_set};
+pub use draft::run_draft;
+pub use export::{ExportOptions, run_export};
+pub use flush::{FlushOptions, run_flush};
+pub use init::run_init;
+pub use import::{ImportOptions, ImportSource, run_import};
+pub use log::{LogOptions, run_log};
+pub use logging::init_logging;
 pub use merge::{MergeOptions, run_merge};
-pub use progress::CliProgress;
+pub use navigate::{Direction, NavigateOptions, run_navigate};
+pub use output::set_json;
+pub use progress::{CliProgress, JournalingProgress};
+pub use rename::{RenameOptions, run_rename};
+pub use restack::{RestackOptions, run_restack};
+pub use reviews::run_reviews;
+#[cfg(feature = "self-update")]
+pub use self_update::{SelfUpdateOptions, check_for_update, run_self_update};
+pub use send_email::{SendEmailOptions, run_send_email};
+pub use split::{SplitOptions, run_split};
 pub use submit::{SubmitOptions, SubmitScope, run_submit};
-pub use sync::{SyncOptions, run_sync};
+pub use sync::{DivergenceChoice, SyncOptions, run_sync};
 pub use track::{TrackOptions, run_track};
+#[cfg(feature = "tui")]
+pub use tui::run_ui;
+pub use undo::{UndoOptions, run_undo};
 pub use untrack::{UntrackOptions, run_untrack};
+pub use wait::{WaitOptions, run_wait};
+pub use web::{WebOptions, run_web};
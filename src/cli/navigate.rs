@@ -0,0 +1,120 @@
+//! `ryu up`, `ryu down`, `ryu top` and `ryu bottom` - move around the stack
+
+use crate::cli::style::{Stylize, check};
+use anstream::println;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::graph::build_change_graph_to;
+use jj_ryu::repo::JjWorkspace;
+use jj_ryu::submit::analyze_submission;
+use std::path::Path;
+use std::process::Command;
+
+/// Where to move in the stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// This many bookmarks further from trunk
+    Up(usize),
+    /// This many bookmarks closer to trunk
+    Down(usize),
+    /// The top bookmark of the stack
+    Top,
+    /// The bookmark closest to trunk
+    Bottom,
+}
+
+/// Options for the navigation commands
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NavigateOptions {
+    /// Start a new change on top of the bookmark (`jj new`) instead of
+    /// editing it (`jj edit`)
+    pub new: bool,
+}
+
+/// Run a navigation command
+///
+/// Moves the working copy to another bookmark of the stack it's in, including
+/// the part of the stack above it. An undescribed working-copy change without
+/// a bookmark (as left by `jj new`) counts as being on its parent.
+pub fn run_navigate(path: &Path, direction: Direction, options: NavigateOptions) -> Result<()> {
+    let workspace = JjWorkspace::open(path)?;
+
+    let wc = workspace
+        .resolve_revset("@")?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::Workspace("no working-copy commit".to_string()))?;
+    let scratch = wc.description.trim().is_empty() && wc.local_bookmarks.is_empty();
+    let position = if scratch { "@-" } else { "@" };
+    let position_id = workspace
+        .resolve_revset(position)?
+        .into_iter()
+        .next()
+        .map(|entry| entry.commit_id);
+
+    // Extend the stack up to its top, which must be unambiguous
+    let heads = workspace.resolve_revset(&format!("heads(({position}):: ~ @)"))?;
+    let head = match heads.as_slice() {
+        [] => position.to_string(),
+        [head] => head.commit_id.clone(),
+        _ => {
+            return Err(Error::InvalidArgument(
+                "The stack branches above the working copy; use 'jj edit' to pick a branch"
+                    .to_string(),
+            ));
+        }
+    };
+
+    let graph = build_change_graph_to(&workspace, &head)?;
+    if graph.stack.is_none() {
+        return Err(Error::InvalidArgument(
+            "No stack found around the working copy".to_string(),
+        ));
+    }
+    let segments = analyze_submission(&graph, None)?.segments;
+    let last = segments.len() - 1;
+
+    // Index of the segment the working copy is in; None below the bottom one
+    let current = segments.iter().position(|s| {
+        s.changes
+            .iter()
+            .any(|c| Some(&c.commit_id) == position_id.as_ref())
+    });
+
+    let target = match (direction, current) {
+        (Direction::Up(_), Some(i)) if i == last => {
+            return Err(Error::InvalidArgument(
+                "Already at the top of the stack".to_string(),
+            ));
+        }
+        (Direction::Up(steps), Some(i)) => (i + steps).min(last),
+        (Direction::Up(steps), None) => steps.saturating_sub(1).min(last),
+        (Direction::Down(_), Some(0) | None) => {
+            return Err(Error::InvalidArgument(
+                "Already at the bottom of the stack".to_string(),
+            ));
+        }
+        (Direction::Down(steps), Some(i)) => i.saturating_sub(steps),
+        (Direction::Top, _) => last,
+        (Direction::Bottom, _) => 0,
+    };
+    let bookmark = &segments[target].bookmark;
+
+    let command = if options.new { "new" } else { "edit" };
+    let status = Command::new("jj")
+        .args([command, &bookmark.change_id])
+        .current_dir(workspace.workspace_root())
+        .status()
+        .map_err(|e| Error::Workspace(format!("Failed to run jj: {e}")))?;
+    if !status.success() {
+        return Err(Error::Workspace(format!("jj {command} failed")));
+    }
+
+    println!(
+        "{} Now on {} ({}/{})",
+        check(),
+        bookmark.name.accent(),
+        target + 1,
+        segments.len()
+    );
+    Ok(())
+}
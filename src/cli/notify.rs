@@ -0,0 +1,21 @@
+//! Desktop notifications for commands run with `--notify`
+//!
+//! Only available with the `notifications` feature. Notifications are best
+//! effort: if no notification service is running they're silently skipped.
+
+/// Show a desktop notification
+#[cfg(feature = "notifications")]
+pub fn notify(summary: &str, body: &str) {
+    if let Err(e) = notify_rust::Notification::new()
+        .appname("ryu")
+        .summary(summary)
+        .body(body)
+        .show()
+    {
+        tracing::debug!(error = %e, "failed to show desktop notification");
+    }
+}
+
+/// Show a desktop notification (no-op: built without `notifications`)
+#[cfg(not(feature = "notifications"))]
+pub const fn notify(_summary: &str, _body: &str) {}
@@ -0,0 +1,185 @@
+//! Machine-readable output for `--json`
+//!
+//! With `--json`, commands that support it print a single JSON document on
+//! stdout instead of their styled text. Modules whose text must give way to
+//! the document import [`println`] and [`print`] from here instead of from
+//! `anstream`; warnings and errors still go to stderr.
+
+use jj_ryu::error::Result;
+use jj_ryu::submit::{SubmissionPlan, SubmissionResult};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether `--json` was passed
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// Switch to JSON output for the rest of the process
+pub fn set_json(enabled: bool) {
+    JSON.store(enabled, Ordering::Relaxed);
+}
+
+/// Check if JSON output was requested
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// `anstream::println!` that prints nothing under `--json`
+macro_rules! println {
+    ($($arg:tt)*) => {
+        if !$crate::cli::output::is_json() {
+            ::anstream::println!($($arg)*);
+        }
+    };
+}
+
+/// `anstream::print!` that prints nothing under `--json`
+macro_rules! print {
+    ($($arg:tt)*) => {
+        if !$crate::cli::output::is_json() {
+            ::anstream::print!($($arg)*);
+        }
+    };
+}
+
+pub(crate) use {print, println};
+
+/// Print `report` as the command's JSON document, if JSON output was requested
+pub fn emit_json<T: Serialize>(report: &T) -> Result<()> {
+    if is_json() {
+        ::anstream::println!("{}", serde_json::to_string_pretty(report)?);
+    }
+    Ok(())
+}
+
+/// JSON document for `submit` and `sync`: the plan and what running it did
+#[derive(Serialize)]
+pub struct SubmissionReport<'a> {
+    /// Command that ran
+    pub command: &'a str,
+    /// Whether this was a dry run
+    pub dry_run: bool,
+    /// Whether every step succeeded
+    pub success: bool,
+    /// Bookmark at the top of the submitted stack
+    pub target_bookmark: &'a str,
+    /// Plan that was executed
+    pub plan: &'a SubmissionPlan,
+    /// Outcome of executing the plan
+    pub result: &'a SubmissionResult,
+}
+
+/// JSON document for a command that stopped before running a plan
+#[derive(Serialize)]
+struct Stopped<'a> {
+    command: &'a str,
+    success: bool,
+    message: &'a str,
+}
+
+/// Print why `command` stopped early, if JSON output was requested
+///
+/// `success` is false when the stop needs the user's attention, e.g. a sync
+/// paused on conflicts.
+pub fn emit_stopped(command: &str, success: bool, message: &str) -> Result<()> {
+    emit_json(&Stopped {
+        command,
+        success,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jj_ryu::types::StackCommentMode;
+    use std::collections::HashMap;
+
+    /// Top-level keys of `value`, sorted
+    fn keys(value: &serde_json::Value) -> Vec<&str> {
+        let mut keys: Vec<&str> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    fn empty_plan() -> SubmissionPlan {
+        SubmissionPlan {
+            segments: vec![],
+            constraints: vec![],
+            execution_steps: vec![],
+            existing_prs: HashMap::new(),
+            remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
+            default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
+        }
+    }
+
+    // Scripts parse these documents; renaming a field breaks them
+    #[test]
+    fn test_submission_report_fields() {
+        let plan = empty_plan();
+        let result = SubmissionResult::new();
+        let report = SubmissionReport {
+            command: "submit",
+            dry_run: true,
+            success: true,
+            target_bookmark: "feat-b",
+            plan: &plan,
+            result: &result,
+        };
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            keys(&json),
+            [
+                "command",
+                "dry_run",
+                "plan",
+                "result",
+                "success",
+                "target_bookmark"
+            ]
+        );
+        assert_eq!(json["command"], "submit");
+        assert_eq!(json["target_bookmark"], "feat-b");
+        assert_eq!(json["plan"]["remote"], "origin");
+        assert_eq!(
+            keys(&json["result"]),
+            [
+                "created_prs",
+                "described_prs",
+                "errors",
+                "pushed_bookmarks",
+                "success",
+                "updated_prs"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stopped_fields() {
+        let stopped = Stopped {
+            command: "sync",
+            success: false,
+            message: "paused on conflicts",
+        };
+
+        let json = serde_json::to_value(&stopped).unwrap();
+        assert_eq!(keys(&json), ["command", "message", "success"]);
+        assert_eq!(json["command"], "sync");
+        assert_eq!(json["success"], false);
+        assert_eq!(json["message"], "paused on conflicts");
+    }
+}
@@ -1,12 +1,16 @@
 //! Shared CLI progress callback with styled output and spinners
 
+use crate::cli::output::{print, println};
 use crate::cli::style::{Stream, Stylize, check, cross, hyperlink_url};
-use anstream::{eprintln, print, println};
+use anstream::eprintln;
 use async_trait::async_trait;
 use jj_ryu::error::Error;
 use jj_ryu::submit::{Phase, ProgressCallback, PushStatus};
+use jj_ryu::tracking::{SubmissionJournal, save_journal};
 use jj_ryu::types::PullRequest;
 use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
 
 /// CLI progress callback that prints to stdout with styled output
 ///
@@ -142,3 +146,114 @@ impl ProgressCallback for CliProgress {
         }
     }
 }
+
+/// Progress callback that journals each created PR before passing it on
+///
+/// Keeps the submission journal current while a plan runs, so a submission
+/// that fails or is killed partway can still be resumed with `--continue`.
+pub struct JournalingProgress<'a> {
+    inner: &'a dyn ProgressCallback,
+    workspace_root: &'a Path,
+    journal: Mutex<SubmissionJournal>,
+}
+
+impl<'a> JournalingProgress<'a> {
+    /// Save `journal` now, and again after every PR created through `inner`
+    pub fn start(
+        inner: &'a dyn ProgressCallback,
+        workspace_root: &'a Path,
+        journal: SubmissionJournal,
+    ) -> jj_ryu::error::Result<Self> {
+        save_journal(workspace_root, &journal)?;
+        Ok(Self {
+            inner,
+            workspace_root,
+            journal: Mutex::new(journal),
+        })
+    }
+
+    /// The journal as of the last created PR
+    pub fn into_journal(self) -> SubmissionJournal {
+        self.journal
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[async_trait]
+impl ProgressCallback for JournalingProgress<'_> {
+    async fn on_phase(&self, phase: Phase) {
+        self.inner.on_phase(phase).await;
+    }
+
+    async fn on_bookmark_push(&self, bookmark: &str, status: PushStatus) {
+        self.inner.on_bookmark_push(bookmark, status).await;
+    }
+
+    async fn on_pr_created(&self, bookmark: &str, pr: &PullRequest) {
+        let snapshot = {
+            let mut journal = self
+                .journal
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            journal.record_created(std::slice::from_ref(pr));
+            journal.clone()
+        };
+        if let Err(e) = save_journal(self.workspace_root, &snapshot) {
+            eprintln!(
+                "{}",
+                format!("⚠️  Failed to save submit journal: {e}").warn()
+            );
+        }
+        self.inner.on_pr_created(bookmark, pr).await;
+    }
+
+    async fn on_pr_updated(&self, bookmark: &str, pr: &PullRequest) {
+        self.inner.on_pr_updated(bookmark, pr).await;
+    }
+
+    async fn on_error(&self, err: &Error) {
+        self.inner.on_error(err).await;
+    }
+
+    async fn on_message(&self, message: &str) {
+        self.inner.on_message(message).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jj_ryu::submit::NoopProgress;
+    use jj_ryu::tracking::load_journal;
+    use tempfile::TempDir;
+
+    fn pr(number: u64, head: &str) -> PullRequest {
+        PullRequest {
+            number,
+            html_url: format!("https://github.com/o/r/pull/{number}"),
+            base_ref: "main".to_string(),
+            head_ref: head.to_string(),
+            title: head.to_string(),
+            node_id: None,
+            is_draft: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_journal_is_saved_as_prs_are_created() {
+        let temp = TempDir::new().unwrap();
+        let journal = SubmissionJournal::new(None, "origin".to_string());
+        let progress = JournalingProgress::start(&NoopProgress, temp.path(), journal).unwrap();
+        assert!(load_journal(temp.path()).unwrap().is_some());
+
+        progress.on_pr_created("feat-a", &pr(1, "feat-a")).await;
+        progress.on_pr_created("feat-b", &pr(2, "feat-b")).await;
+
+        // On disk before execution returns, so a crash can still be resumed
+        let saved = load_journal(temp.path()).unwrap().unwrap();
+        let numbers: Vec<u64> = saved.created_prs.iter().map(|pr| pr.number).collect();
+        assert_eq!(numbers, [1, 2]);
+        assert_eq!(progress.into_journal().created_prs.len(), 2);
+    }
+}
@@ -0,0 +1,25 @@
+//! Confirmation before commands that close PRs or delete bookmarks
+
+use dialoguer::Confirm;
+use jj_ryu::error::{Error, Result};
+use std::io::{self, IsTerminal};
+
+/// Ask `prompt`, unless `yes` (from `--yes`) already answered it
+///
+/// Without a terminal to ask on, the command refuses to go ahead rather than
+/// assume consent.
+pub fn confirm(prompt: &str, yes: bool) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    if !io::stdin().is_terminal() {
+        return Err(Error::InvalidArgument(
+            "confirmation needs a terminal; pass --yes to skip it".to_string(),
+        ));
+    }
+    Confirm::new()
+        .with_prompt(prompt)
+        .default(false)
+        .interact()
+        .map_err(|e| Error::Internal(format!("Failed to read confirmation: {e}")))
+}
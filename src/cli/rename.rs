@@ -0,0 +1,184 @@
+//! `ryu rename` command - rename a bookmark and move its PR to the new branch
+
+use crate::cli::context::CommandContext;
+use crate::cli::style::{Stylize, arrow, check};
+use anstream::println;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::graph::build_change_graph;
+use jj_ryu::submit::{analyze_submission, pr_sigil};
+use jj_ryu::tracking::{TrackedBookmark, save_pr_cache, save_tracking};
+use std::path::Path;
+
+/// Options for the rename command
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenameOptions {
+    /// Only show what would be done
+    pub dry_run: bool,
+}
+
+/// Run the rename command
+///
+/// Renames the local bookmark `old` to `new` and carries its tracking entry
+/// over. If the old branch was pushed, the new one is pushed and the old one
+/// deleted on the remote.
+///
+/// A PR's head branch can't be changed, so an open PR for `old` is replaced:
+/// a PR with the same title, description and draft state is opened for
+/// `new`, PRs based on `old` are retargeted to `new`, and the old PR is
+/// closed with a comment linking to its replacement.
+#[allow(clippy::too_many_lines)]
+pub async fn run_rename(
+    path: &Path,
+    old: &str,
+    new: &str,
+    remote: Option<&str>,
+    options: RenameOptions,
+) -> Result<()> {
+    let mut ctx = CommandContext::new(path, remote).await?;
+
+    let bookmark = ctx
+        .workspace
+        .get_local_bookmark(old)?
+        .ok_or_else(|| Error::BookmarkNotFound(old.to_string()))?;
+    if ctx.workspace.get_local_bookmark(new)?.is_some() {
+        return Err(Error::InvalidArgument(format!(
+            "Bookmark '{new}' already exists"
+        )));
+    }
+
+    let remote = ctx
+        .tracking
+        .get(old)
+        .and_then(|t| t.remote.clone())
+        .unwrap_or_else(|| ctx.remote_name.clone());
+    let pr = ctx.platform.find_existing_pr(old).await?;
+
+    // PRs of the stack that are based on the old branch
+    let graph = build_change_graph(&ctx.workspace)?;
+    let stack: Vec<String> = if graph.stack.is_some() {
+        analyze_submission(&graph, None)?
+            .segments
+            .into_iter()
+            .map(|s| s.bookmark.name)
+            .filter(|name| name != old)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let dependents: Vec<_> = ctx
+        .platform
+        .find_existing_prs(&stack)
+        .await?
+        .into_values()
+        .filter(|pr| pr.base_ref == old)
+        .collect();
+
+    if options.dry_run {
+        println!("  {} {} {}", old.accent(), arrow(), new.accent());
+        if let Some(pr) = &pr {
+            println!("  Replace PR #{} with a PR for {new}", pr.number);
+        }
+        for pr in &dependents {
+            println!("  Retarget PR #{} ({}) to {new}", pr.number, pr.head_ref);
+        }
+        println!();
+        println!("{}", "Dry run: nothing was changed".muted());
+        return Ok(());
+    }
+
+    // Rename the local bookmark and its tracking entry
+    ctx.workspace.set_bookmark(new, &bookmark.commit_id)?;
+    ctx.workspace.delete_bookmark(old)?;
+    if let Some(tracked) = ctx.tracking.get(old).cloned() {
+        ctx.tracking.untrack(old);
+        ctx.tracking.track(TrackedBookmark {
+            name: new.to_string(),
+            ..tracked
+        });
+        save_tracking(&ctx.workspace_root, &ctx.tracking)?;
+    }
+    println!(
+        "{} Renamed {} {} {}",
+        check(),
+        old.accent(),
+        arrow(),
+        new.accent()
+    );
+
+    if !bookmark.has_remote && pr.is_none() {
+        return Ok(());
+    }
+    ctx.workspace.git_push(new, &remote)?;
+    println!("{} Pushed {new} to {remote}", check());
+
+    if let Some(old_pr) = pr {
+        let sigil = pr_sigil(ctx.platform.config().platform);
+        let details = ctx.platform.get_pr_details(old_pr.number).await?;
+        let body = details.body.unwrap_or_default();
+        let new_pr = ctx
+            .platform
+            .create_pr_with_options(
+                new,
+                &old_pr.base_ref,
+                &details.title,
+                Some(&body),
+                details.is_draft,
+            )
+            .await?;
+        println!(
+            "{} Opened PR #{} for {}",
+            check(),
+            new_pr.number,
+            new.accent()
+        );
+
+        for pr in &dependents {
+            ctx.platform.update_pr_base(pr.number, new).await?;
+            println!(
+                "{} Retargeted PR #{} ({}) to {new}",
+                check(),
+                pr.number,
+                pr.head_ref.accent()
+            );
+        }
+
+        ctx.platform
+            .create_pr_comment(
+                old_pr.number,
+                &format!(
+                    "The branch `{old}` was renamed to `{new}`; this PR continues in {sigil}{}.",
+                    new_pr.number
+                ),
+            )
+            .await?;
+        ctx.platform.close_pr(old_pr.number).await?;
+        println!(
+            "{} Closed PR #{} in favor of #{}",
+            check(),
+            old_pr.number,
+            new_pr.number
+        );
+
+        ctx.pr_cache.remove(old);
+        ctx.pr_cache.upsert(new, &new_pr, &remote);
+        // Best effort - the cache is rebuilt by the next submit
+        let _ = save_pr_cache(&ctx.workspace_root, &ctx.pr_cache);
+    }
+
+    if bookmark.has_remote {
+        match ctx.workspace.restore_remote_bookmark(old, &remote, None) {
+            Ok(()) => println!("{} Deleted {old} on {remote}", check()),
+            Err(e) => println!(
+                "{}",
+                format!("⚠️  Failed to delete {old} on {remote}: {e}").warn()
+            ),
+        }
+    }
+
+    println!();
+    println!(
+        "Run {} to update the stack comments",
+        "ryu submit".accent()
+    );
+    Ok(())
+}
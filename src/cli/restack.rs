@@ -0,0 +1,288 @@
+//! `ryu restack` command, and pausing on conflicts left by restacking until
+//! `ryu sync --continue`
+
+use crate::cli::CliProgress;
+use crate::cli::context::CommandContext;
+use crate::cli::output::println;
+use crate::cli::style::{CHECK, Stylize, arrow, check, spinner_style};
+use indicatif::ProgressBar;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::graph::build_change_graph;
+use jj_ryu::repo::{StackConflict, create_resolution_changes, find_stack_conflicts};
+use jj_ryu::submit::{
+    analyze_submission, apply_config_to_plan, capture_rollback_snapshot,
+    create_submission_plan_onto, execute_submission, record_undo,
+};
+use jj_ryu::tracking::{RestackState, load_restack_state, save_restack_state};
+use jj_ryu::types::{NarrowedBookmarkSegment, Platform};
+use std::path::Path;
+use std::time::Duration;
+
+/// Options for the restack command
+#[derive(Debug, Clone, Default)]
+pub struct RestackOptions {
+    /// Only rebase locally, without pushing or updating PRs
+    pub no_submit: bool,
+}
+
+/// Run the restack command
+///
+/// Fetches, rebases the tracked stack onto the latest trunk (or the branch
+/// the stack targets), and submits it again so the PRs and their bases match
+/// the rebased stack. Conflicts pause the restack the same way they pause a
+/// sync, to be resumed with `ryu sync --continue`.
+#[allow(clippy::too_many_lines)]
+pub async fn run_restack(path: &Path, remote: Option<&str>, options: RestackOptions) -> Result<()> {
+    let mut ctx = CommandContext::new(path, remote).await?;
+
+    if load_restack_state(&ctx.workspace_root)?.is_some() {
+        return Err(Error::InvalidArgument(
+            "A sync is paused on conflicts. Resolve them and run 'ryu sync --continue'.".to_string(),
+        ));
+    }
+    let tracked_names: Vec<String> = ctx.tracked_names().into_iter().map(String::from).collect();
+    if tracked_names.is_empty() {
+        return Err(Error::Tracking(
+            "No bookmarks tracked. Run 'ryu track' first.".to_string(),
+        ));
+    }
+
+    // Record where the repo is before fetching and rebasing, for 'ryu undo'
+    let mut undo = ctx.begin_undo("restack")?;
+
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(spinner_style());
+    spinner.set_message(format!("Fetching from {}...", ctx.remote_name.emphasis()));
+    spinner.enable_steady_tick(Duration::from_millis(80));
+    let mut remotes = vec![ctx.remote_name.clone()];
+    for remote in ctx.tracking.push_remotes().into_values() {
+        if !remotes.contains(&remote) {
+            remotes.push(remote);
+        }
+    }
+    for remote in &remotes {
+        ctx.workspace.git_fetch(remote)?;
+    }
+    spinner.finish_with_message(format!(
+        "{} Fetched from {}",
+        check(),
+        remotes.join(", ").emphasis()
+    ));
+
+    let graph = build_change_graph(&ctx.workspace)?;
+    if graph.stack.is_none() {
+        println!("{}", "No stack to restack".muted());
+        return Ok(());
+    }
+    let mut analysis = analyze_submission(&graph, None)?;
+    analysis
+        .segments
+        .retain(|s| tracked_names.contains(&s.bookmark.name));
+    let Some(bottom) = analysis.segments.first().map(|s| s.bookmark.name.clone()) else {
+        return Err(Error::Tracking(
+            "No tracked bookmarks in stack. Use 'ryu track' to track bookmarks.".to_string(),
+        ));
+    };
+
+    // Rebase from the root of the stack, so untracked changes below the
+    // bottom bookmark move along
+    let base = ctx.stack_base(&analysis);
+    let base_revset = if base == ctx.default_branch {
+        "trunk()".to_string()
+    } else {
+        format!(
+            "remote_bookmarks(exact:{base:?}, exact:{:?})",
+            ctx.remote_name
+        )
+    };
+    if ctx.workspace.is_ancestor(&base_revset, &format!("{bottom:?}"))? {
+        println!("{} Stack is already on top of {}", check(), base.emphasis());
+    } else {
+        println!("🔄 Rebasing {} onto {}...", bottom.accent(), base.emphasis());
+        let root = format!("roots({base_revset}..{bottom:?})");
+        if base == ctx.default_branch {
+            ctx.workspace.rebase_bookmark_onto_trunk(&root)?;
+        } else {
+            ctx.workspace
+                .rebase_bookmark_onto_remote(&root, &base, &ctx.remote_name)?;
+        }
+    }
+
+    // Commit IDs changed, so look at the stack again
+    let graph = build_change_graph(&ctx.workspace)?;
+    let mut analysis = analyze_submission(&graph, None)?;
+    analysis
+        .segments
+        .retain(|s| tracked_names.contains(&s.bookmark.name));
+    if pause_on_conflicts(&mut ctx, &analysis.segments, &base)? {
+        return Ok(());
+    }
+    for segment in &analysis.segments {
+        println!("  {} {}", check(), segment.bookmark.name.accent());
+    }
+
+    if options.no_submit {
+        println!();
+        println!(
+            "{}",
+            "Run 'ryu submit' to push the stack and update its PRs".muted()
+        );
+        return Ok(());
+    }
+    if ctx.platform.config().platform == Platform::Gerrit {
+        println!();
+        println!(
+            "{}",
+            "Run 'ryu submit' to upload the rebased changes as new patch sets".muted()
+        );
+        return Ok(());
+    }
+
+    println!("📤 Updating PRs...");
+    let mut plan = create_submission_plan_onto(
+        &analysis,
+        ctx.platform.as_ref(),
+        &ctx.remote_name,
+        &base,
+        &ctx.default_branch,
+    )
+    .await?;
+    plan.push_remotes = ctx.tracking.push_remotes();
+    apply_config_to_plan(
+        &mut plan,
+        &ctx.config.submit,
+        &ctx.pr_cache,
+        ctx.config.submit.draft,
+    )?;
+
+    let snapshot = capture_rollback_snapshot(&plan, &ctx.workspace)?;
+    let progress = CliProgress::compact();
+    let result = execute_submission(
+        &plan,
+        &mut ctx.workspace,
+        ctx.platform.as_ref(),
+        &progress,
+        false,
+    )
+    .await?;
+    if let Some(undo) = &mut undo {
+        record_undo(undo, &plan, &snapshot, &result);
+        ctx.save_undo(Some(undo));
+    }
+    ctx.save_submitted_prs(&plan, &result);
+
+    println!();
+    println!(
+        "{} {} pushed, {} created, {} updated",
+        format!("{CHECK} Restack complete:").success(),
+        result.pushed_bookmarks.len().accent(),
+        result.created_prs.len().accent(),
+        result.updated_prs.len().accent()
+    );
+
+    Ok(())
+}
+
+/// Stop before pushing if rebasing left conflicts in the stack
+///
+/// Creates a resolution change per conflicted segment, records the paused
+/// sync for `ryu sync --continue`, and prints what to do. Returns whether
+/// the caller should stop.
+pub fn pause_on_conflicts(
+    ctx: &mut CommandContext,
+    segments: &[NarrowedBookmarkSegment],
+    base: &str,
+) -> Result<bool> {
+    let mut conflicts = find_stack_conflicts(&ctx.workspace, segments)?;
+    if conflicts.is_empty() {
+        return Ok(false);
+    }
+
+    create_resolution_changes(&mut ctx.workspace, &mut conflicts)?;
+    let state = RestackState::new(
+        ctx.remote_name.clone(),
+        base.to_string(),
+        conflicts.iter().map(|c| c.bookmark.clone()).collect(),
+    );
+    save_restack_state(&ctx.workspace_root, &state)?;
+
+    print_checklist(ctx, segments, &conflicts, base);
+    Ok(true)
+}
+
+/// Print the conflicted files and the steps to resolve them
+fn print_checklist(
+    ctx: &CommandContext,
+    segments: &[NarrowedBookmarkSegment],
+    conflicts: &[StackConflict],
+    base: &str,
+) {
+    println!();
+    println!(
+        "{}",
+        format!(
+            "⚠️  Rebasing onto {base} left conflicts in {} bookmark(s). Nothing was pushed.",
+            conflicts.len()
+        )
+        .warn()
+    );
+
+    for conflict in conflicts {
+        println!();
+        println!(
+            "  {} {}",
+            conflict.bookmark.accent(),
+            affected_prs(ctx, segments, &conflict.bookmark).muted()
+        );
+        for change in &conflict.changes {
+            println!(
+                "    {} {}",
+                short_id(&change.commit_id).emphasis(),
+                change.description_first_line
+            );
+            for path in &change.paths {
+                println!("      {}", path.warn());
+            }
+        }
+        if let Some(resolution) = &conflict.resolution_change {
+            let id = short_id(resolution);
+            println!("    Resolve in change {}:", id.emphasis());
+            println!("      1. jj edit {id}");
+            println!("      2. Fix the files above (or run 'jj resolve')");
+            println!("      3. jj squash");
+        }
+    }
+
+    println!();
+    if let Some(top) = segments.last() {
+        println!(
+            "{} Then run 'jj new {}' to return to the top of the stack",
+            arrow(),
+            top.bookmark.name
+        );
+    }
+    println!(
+        "{} Run 'ryu sync --continue' to push the stack and update its PRs",
+        arrow()
+    );
+}
+
+/// PRs that wait on `bookmark`'s conflicts: its own and those stacked on it
+fn affected_prs(ctx: &CommandContext, segments: &[NarrowedBookmarkSegment], bookmark: &str) -> String {
+    let numbers: Vec<String> = segments
+        .iter()
+        .skip_while(|s| s.bookmark.name != bookmark)
+        .filter_map(|s| ctx.pr_cache.get(&s.bookmark.name))
+        .map(|pr| format!("#{}", pr.number))
+        .collect();
+    if numbers.is_empty() {
+        String::new()
+    } else {
+        format!("(affects PR {})", numbers.join(", "))
+    }
+}
+
+/// Abbreviated commit ID, as accepted by jj commands
+fn short_id(commit_id: &str) -> &str {
+    &commit_id[..12.min(commit_id.len())]
+}
@@ -0,0 +1,101 @@
+//! `ryu reviews` command - show review state for each PR in the stack
+
+use crate::cli::context::CommandContext;
+use crate::cli::style::{Stylize, check, cross};
+use anstream::println;
+use futures::stream::{self, StreamExt};
+use jj_ryu::error::Result;
+use jj_ryu::graph::build_change_graph;
+use jj_ryu::platform::latest_reviews;
+use jj_ryu::submit::analyze_submission;
+use jj_ryu::types::{PrReview, PullRequest, ReviewState, ReviewThread};
+use std::path::Path;
+
+/// A bookmark with its PR, reviews and unresolved threads (None = no PR)
+type BookmarkReviews = (
+    String,
+    Option<(PullRequest, Vec<PrReview>, Vec<ReviewThread>)>,
+);
+
+/// Run the reviews command
+///
+/// Lists each reviewer's current verdict (their latest approval or change
+/// request) and the unresolved review threads for every PR in the stack,
+/// trunk first.
+pub async fn run_reviews(path: &Path, remote: Option<&str>) -> Result<()> {
+    let ctx = CommandContext::new(path, remote).await?;
+    let graph = build_change_graph(&ctx.workspace)?;
+
+    if graph.stack.is_none() {
+        println!("{}", "No stack found between trunk and working copy.".muted());
+        return Ok(());
+    }
+    let analysis = analyze_submission(&graph, None)?;
+
+    let platform = ctx.platform.as_ref();
+    let fetched: Vec<BookmarkReviews> =
+        stream::iter(analysis.segments.iter().map(|s| s.bookmark.name.clone()))
+            .map(|name| async move {
+                let Ok(Some(pr)) = platform.find_existing_pr(&name).await else {
+                    return Ok((name, None));
+                };
+                let reviews = platform.list_reviews(pr.number).await?;
+                let mut threads = platform.list_review_threads(pr.number).await?;
+                threads.retain(|thread| !thread.is_resolved);
+                Ok((name, Some((pr, reviews, threads))))
+            })
+            .buffered(platform.max_concurrency())
+            .collect::<Vec<Result<_>>>()
+            .await
+            .into_iter()
+            .collect::<Result<_>>()?;
+
+    for (name, pr) in fetched {
+        let Some((pr, reviews, threads)) = pr else {
+            println!("{} {}", name.accent(), "(no PR)".muted());
+            continue;
+        };
+        println!("{} {}", name.accent(), format!("#{}", pr.number).muted());
+
+        let latest = latest_reviews(&reviews);
+        if latest.is_empty() {
+            println!("  {}", "No reviews yet".muted());
+        }
+        for review in latest {
+            let marker = if review.state == ReviewState::Approved {
+                check()
+            } else {
+                cross()
+            };
+            println!("  {marker} {} {}", review.reviewer, review.state.muted());
+        }
+        print_unresolved_threads(&threads);
+    }
+
+    Ok(())
+}
+
+/// Print a PR's unresolved review threads, one line each
+fn print_unresolved_threads(threads: &[ReviewThread]) {
+    if threads.is_empty() {
+        return;
+    }
+    let noun = if threads.len() == 1 {
+        "thread"
+    } else {
+        "threads"
+    };
+    println!(
+        "  {}",
+        format!("{} unresolved {noun}", threads.len()).warn()
+    );
+    for thread in threads {
+        let location = match (&thread.path, thread.line) {
+            (Some(path), Some(line)) => format!("{path}:{line}"),
+            (Some(path), None) => path.clone(),
+            (None, _) => "(general)".to_string(),
+        };
+        let summary = thread.body.lines().next().unwrap_or_default();
+        println!("    {} {}: {summary}", location.muted(), thread.author);
+    }
+}
@@ -0,0 +1,615 @@
+//! `ryu self-update` command and the new-version check
+//!
+//! Releases are the GitHub releases of the ryu repository: one archive per
+//! platform (`ryu-<platform>.tar.gz`, or `.zip` on Windows) plus
+//! `checksums-sha256.txt`. The archive is checked against its checksum before
+//! the running executable is replaced. Once a release key is built into ryu,
+//! the checksums must also carry a valid minisign signature,
+//! `checksums-sha256.txt.minisig`.
+
+use crate::cli::style::{Stylize, arrow, check};
+use anstream::{eprintln, println};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use blake2::Blake2b512;
+use chrono::{DateTime, Duration, Utc};
+use jj_ryu::config::load_config;
+use jj_ryu::error::{Error, Result};
+use ring::signature::{ED25519, UnparsedPublicKey};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{Cursor, IsTerminal, Read, Write as _};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// GitHub API URL listing ryu's releases
+const RELEASES_URL: &str = "https://api.github.com/repos/dmmulroy/jj-ryu/releases?per_page=20";
+
+/// Release asset with the SHA-256 of every archive
+const CHECKSUMS_ASSET: &str = "checksums-sha256.txt";
+
+/// Release asset with the minisign signature of [`CHECKSUMS_ASSET`]
+const SIGNATURE_ASSET: &str = "checksums-sha256.txt.minisig";
+
+/// Public minisign key that release checksums are signed with
+///
+/// None until the maintainers generate the release key pair and store its
+/// secret half as the `MINISIGN_SECRET_KEY` repository secret; until then
+/// releases aren't signed and only their checksums are verified.
+const RELEASE_PUBLIC_KEY: Option<&str> = None;
+
+/// How long a new-version check result is reused
+const CHECK_INTERVAL_HOURS: i64 = 24;
+
+/// Timeout for the passive new-version check
+const CHECK_TIMEOUT_SECS: u64 = 2;
+
+/// Options for the self-update command
+#[derive(Debug, Clone, Default)]
+pub struct SelfUpdateOptions {
+    /// Only report whether a newer release exists
+    pub check: bool,
+    /// Reinstall even if up to date, or if installed by a package manager
+    pub force: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Cached result of the last new-version check
+#[derive(Debug, Serialize, Deserialize)]
+struct VersionCheck {
+    checked_at: DateTime<Utc>,
+    latest: String,
+}
+
+/// Run the self-update command
+pub async fn run_self_update(options: SelfUpdateOptions) -> Result<()> {
+    let current = current_version()?;
+    let client = http_client(None)?;
+    let releases = fetch_releases(&client).await?;
+    let Some((latest, release)) = select_latest(&releases, &current) else {
+        return Err(Error::SelfUpdate("no releases found".to_string()));
+    };
+
+    if latest <= current && !options.force {
+        println!("{} ryu {current} is up to date", check());
+        return Ok(());
+    }
+    if options.check {
+        println!(
+            "{} ryu {} is available (installed: {current})",
+            arrow(),
+            latest.to_string().accent()
+        );
+        println!("  Run 'ryu self-update' to install it");
+        return Ok(());
+    }
+
+    let exe = std::env::current_exe()
+        .map_err(|e| Error::SelfUpdate(format!("failed to locate ryu executable: {e}")))?;
+    if !options.force
+        && let Some(hint) = package_manager_hint(&exe)
+    {
+        return Err(Error::SelfUpdate(format!(
+            "ryu was installed by {hint} (use --force to replace it anyway)"
+        )));
+    }
+
+    let platform = platform()
+        .ok_or_else(|| Error::SelfUpdate("no prebuilt binaries for this platform".to_string()))?;
+    let archive_name = asset_name(platform);
+    let archive_url = asset_url(release, &archive_name)?;
+    let checksums_url = asset_url(release, CHECKSUMS_ASSET)?;
+
+    println!("{} Downloading ryu {latest} ({archive_name})", arrow());
+    let archive = download(&client, archive_url).await?;
+    let checksums = download(&client, checksums_url).await?;
+    if let Some(public_key) = RELEASE_PUBLIC_KEY {
+        let signature_url = asset_url(release, SIGNATURE_ASSET)?;
+        let signature =
+            String::from_utf8_lossy(&download(&client, signature_url).await?).into_owned();
+        verify_signature(&checksums, &signature, public_key)?;
+    }
+    let checksums = String::from_utf8_lossy(&checksums).into_owned();
+
+    let expected = parse_checksum(&checksums, &archive_name).ok_or_else(|| {
+        Error::SelfUpdate(format!("{CHECKSUMS_ASSET} has no entry for {archive_name}"))
+    })?;
+    let actual = sha256_hex(&archive);
+    if !actual.eq_ignore_ascii_case(&expected) {
+        return Err(Error::SelfUpdate(format!(
+            "checksum mismatch for {archive_name}: expected {expected}, got {actual}"
+        )));
+    }
+
+    let binary = extract_binary(&archive, is_windows_platform(platform))?;
+    replace_executable(&exe, &binary)?;
+    save_version_check(&latest);
+
+    println!(
+        "{} Updated ryu {current} {} {}",
+        check(),
+        arrow(),
+        latest.to_string().success()
+    );
+    Ok(())
+}
+
+/// Mention a newer release, if `updates.check` is enabled
+///
+/// Best effort: the network is asked at most once a day, with a short
+/// timeout, and any failure is ignored. Prints to stderr, and only when it's
+/// a terminal.
+pub async fn check_for_update(path: &Path) {
+    if !std::io::stderr().is_terminal() {
+        return;
+    }
+    match load_config(path) {
+        Ok(config) if config.updates.check => {}
+        _ => return,
+    }
+
+    let Ok(current) = current_version() else {
+        return;
+    };
+    let latest = match load_version_check() {
+        Some(cached) if Utc::now() - cached.checked_at < Duration::hours(CHECK_INTERVAL_HOURS) => {
+            Version::parse(&cached.latest).ok()
+        }
+        _ => {
+            let latest = fetch_latest(&current).await;
+            if let Some(latest) = &latest {
+                save_version_check(latest);
+            }
+            latest
+        }
+    };
+
+    if let Some(latest) = latest
+        && latest > current
+    {
+        eprintln!();
+        eprintln!(
+            "{}",
+            format!("ryu {latest} is available (installed: {current}). Run 'ryu self-update' to install it.")
+                .muted()
+        );
+    }
+}
+
+async fn fetch_latest(current: &Version) -> Option<Version> {
+    let client = http_client(Some(std::time::Duration::from_secs(CHECK_TIMEOUT_SECS))).ok()?;
+    match fetch_releases(&client).await {
+        Ok(releases) => select_latest(&releases, current).map(|(version, _)| version),
+        Err(e) => {
+            debug!(error = %e, "new-version check failed");
+            None
+        }
+    }
+}
+
+fn current_version() -> Result<Version> {
+    let version = env!("CARGO_PKG_VERSION");
+    Version::parse(version)
+        .map_err(|e| Error::SelfUpdate(format!("invalid ryu version {version}: {e}")))
+}
+
+fn http_client(timeout: Option<std::time::Duration>) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder().user_agent("jj-ryu");
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(timeout);
+    }
+    builder
+        .build()
+        .map_err(|e| Error::SelfUpdate(format!("failed to create HTTP client: {e}")))
+}
+
+async fn fetch_releases(client: &reqwest::Client) -> Result<Vec<Release>> {
+    let response = client
+        .get(RELEASES_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(response.json().await?)
+}
+
+async fn download(client: &reqwest::Client, url: &str) -> Result<Vec<u8>> {
+    let response = client.get(url).send().await?.error_for_status()?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// The newest release to update to, with its version
+///
+/// Drafts are skipped, and so are pre-releases unless the installed version
+/// is itself a pre-release.
+fn select_latest<'a>(releases: &'a [Release], current: &Version) -> Option<(Version, &'a Release)> {
+    let allow_prerelease = !current.pre.is_empty();
+    releases
+        .iter()
+        .filter(|r| !r.draft && (allow_prerelease || !r.prerelease))
+        .filter_map(|r| {
+            let version = Version::parse(r.tag_name.trim_start_matches('v')).ok()?;
+            Some((version, r))
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+}
+
+/// Release platform name of this build, as used in archive names
+const fn platform() -> Option<&'static str> {
+    if cfg!(all(target_os = "macos", target_arch = "x86_64")) {
+        Some("darwin-x64")
+    } else if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        Some("darwin-arm64")
+    } else if cfg!(all(
+        target_os = "linux",
+        target_arch = "x86_64",
+        target_env = "musl"
+    )) {
+        Some("linux-x64-musl")
+    } else if cfg!(all(
+        target_os = "linux",
+        target_arch = "aarch64",
+        target_env = "musl"
+    )) {
+        Some("linux-arm64-musl")
+    } else if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+        Some("linux-x64")
+    } else if cfg!(all(target_os = "linux", target_arch = "aarch64")) {
+        Some("linux-arm64")
+    } else if cfg!(all(target_os = "windows", target_arch = "x86_64")) {
+        Some("win32-x64")
+    } else if cfg!(all(target_os = "windows", target_arch = "aarch64")) {
+        Some("win32-arm64")
+    } else {
+        None
+    }
+}
+
+/// Archive name for `platform`
+fn asset_name(platform: &str) -> String {
+    if is_windows_platform(platform) {
+        format!("ryu-{platform}.zip")
+    } else {
+        format!("ryu-{platform}.tar.gz")
+    }
+}
+
+/// Windows releases are zip archives, the rest gzipped tarballs
+fn is_windows_platform(platform: &str) -> bool {
+    platform.starts_with("win32-")
+}
+
+fn asset_url<'a>(release: &'a Release, name: &str) -> Result<&'a str> {
+    release
+        .assets
+        .iter()
+        .find(|a| a.name == name)
+        .map(|a| a.browser_download_url.as_str())
+        .ok_or_else(|| Error::SelfUpdate(format!("release {} has no {name}", release.tag_name)))
+}
+
+/// Checksum for `file` in `sha256sum` output
+fn parse_checksum(checksums: &str, file: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        // `sha256sum` marks files read in binary mode with '*'
+        let name = name.trim_start().trim_start_matches('*');
+        (name == file).then(|| hash.to_string())
+    })
+}
+
+/// Check `data` against a minisign `signature` made with `public_key`
+///
+/// Accepts both legacy (`Ed`) and pre-hashed (`ED`) signatures, and also
+/// checks the global signature so the trusted comment can't be swapped.
+fn verify_signature(data: &[u8], signature: &str, public_key: &str) -> Result<()> {
+    let invalid = |what: &str| Error::SelfUpdate(format!("invalid {SIGNATURE_ASSET}: {what}"));
+    let decode = |text: &str| BASE64.decode(text.trim()).ok();
+
+    let key = decode(public_key)
+        .filter(|key| key.len() == 42 && key.starts_with(b"Ed"))
+        .ok_or_else(|| Error::SelfUpdate("invalid release public key".to_string()))?;
+    let (key_id, key) = key[2..].split_at(8);
+
+    let lines: Vec<&str> = signature.lines().collect();
+    let [_, signature, trusted, global, ..] = lines.as_slice() else {
+        return Err(invalid("expected four lines"));
+    };
+    let signature = decode(signature)
+        .filter(|signature| signature.len() == 74)
+        .ok_or_else(|| invalid("malformed signature"))?;
+    let (algorithm, rest) = signature.split_at(2);
+    let (signature_key_id, signature) = rest.split_at(8);
+    if signature_key_id != key_id {
+        return Err(invalid("signed with a different key"));
+    }
+    let message = match algorithm {
+        b"Ed" => data.to_vec(),
+        b"ED" => Blake2b512::digest(data).to_vec(),
+        _ => return Err(invalid("unsupported signature algorithm")),
+    };
+
+    let key = UnparsedPublicKey::new(&ED25519, key);
+    key.verify(&message, signature)
+        .map_err(|_| Error::SelfUpdate(format!("{CHECKSUMS_ASSET} doesn't match its signature")))?;
+
+    let comment = trusted
+        .strip_prefix("trusted comment: ")
+        .ok_or_else(|| invalid("missing trusted comment"))?;
+    let global = decode(global).ok_or_else(|| invalid("malformed global signature"))?;
+    key.verify(&[signature, comment.as_bytes()].concat(), &global)
+        .map_err(|_| invalid("trusted comment doesn't match its signature"))
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    let mut hex = String::with_capacity(64);
+    for byte in Sha256::digest(data) {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    hex
+}
+
+/// Name of the executable inside release archives
+const fn binary_name() -> &'static str {
+    if cfg!(windows) { "ryu.exe" } else { "ryu" }
+}
+
+/// Read the ryu executable out of a release archive
+fn extract_binary(archive: &[u8], is_zip: bool) -> Result<Vec<u8>> {
+    let mut binary = Vec::new();
+    let read_error = |e: std::io::Error| Error::SelfUpdate(format!("failed to read archive: {e}"));
+
+    if is_zip {
+        let mut zip = zip::ZipArchive::new(Cursor::new(archive))
+            .map_err(|e| Error::SelfUpdate(format!("failed to read archive: {e}")))?;
+        for i in 0..zip.len() {
+            let mut file = zip
+                .by_index(i)
+                .map_err(|e| Error::SelfUpdate(format!("failed to read archive: {e}")))?;
+            if file_name_matches(Path::new(file.name())) {
+                file.read_to_end(&mut binary).map_err(read_error)?;
+                return Ok(binary);
+            }
+        }
+    } else {
+        let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+        for entry in tar.entries().map_err(read_error)? {
+            let mut entry = entry.map_err(read_error)?;
+            if file_name_matches(&entry.path().map_err(read_error)?) {
+                entry.read_to_end(&mut binary).map_err(read_error)?;
+                return Ok(binary);
+            }
+        }
+    }
+
+    Err(Error::SelfUpdate(format!(
+        "archive doesn't contain {}",
+        binary_name()
+    )))
+}
+
+fn file_name_matches(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == binary_name())
+}
+
+/// Swap the running executable `exe` for `binary`
+///
+/// The new binary is staged in a fresh, uniquely named file next to `exe`,
+/// so nobody else can plant or swap it, and it's removed when dropped.
+fn replace_executable(exe: &Path, binary: &[u8]) -> Result<()> {
+    let dir = exe.parent().unwrap_or_else(|| Path::new("."));
+    let write_error = |e: std::io::Error| {
+        Error::SelfUpdate(format!("failed to stage update in {}: {e}", dir.display()))
+    };
+    let mut staged = tempfile::Builder::new()
+        .prefix(".ryu-update-")
+        .tempfile_in(dir)
+        .map_err(write_error)?;
+    staged.write_all(binary).map_err(write_error)?;
+    staged.flush().map_err(write_error)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(staged.path(), fs::Permissions::from_mode(0o755))
+            .map_err(|e| Error::SelfUpdate(format!("failed to make update executable: {e}")))?;
+    }
+
+    self_replace::self_replace(staged.path())
+        .map_err(|e| Error::SelfUpdate(format!("failed to replace ryu executable: {e}")))
+}
+
+/// The package manager that installed `exe`, if it looks managed
+///
+/// Replacing such a binary works, but the package manager would undo it or
+/// get confused on its next upgrade.
+fn package_manager_hint(exe: &Path) -> Option<&'static str> {
+    let path = exe.to_string_lossy().replace('\\', "/");
+    if path.contains("/node_modules/") {
+        Some("npm (run 'npm install -g jj-ryu' to update)")
+    } else if path.contains("/Cellar/") || path.contains("/homebrew/") {
+        Some("Homebrew (run 'brew upgrade jj-ryu' to update)")
+    } else if path.contains("/.cargo/bin/") {
+        Some("cargo (run 'cargo install jj-ryu' to update)")
+    } else {
+        None
+    }
+}
+
+fn version_check_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("ryu").join("version-check.toml"))
+}
+
+fn load_version_check() -> Option<VersionCheck> {
+    let content = fs::read_to_string(version_check_path()?).ok()?;
+    toml::from_str(&content).ok()
+}
+
+fn save_version_check(latest: &Version) {
+    let Some(path) = version_check_path() else {
+        return;
+    };
+    let check = VersionCheck {
+        checked_at: Utc::now(),
+        latest: latest.to_string(),
+    };
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(&path, toml::to_string(&check).unwrap_or_default()));
+    if let Err(e) = written {
+        debug!(error = %e, "failed to save new-version check");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn release(tag: &str, draft: bool, prerelease: bool) -> Release {
+        Release {
+            tag_name: tag.to_string(),
+            draft,
+            prerelease,
+            assets: vec![Asset {
+                name: CHECKSUMS_ASSET.to_string(),
+                browser_download_url: format!("https://example.com/{tag}/{CHECKSUMS_ASSET}"),
+            }],
+        }
+    }
+
+    fn latest_tag(releases: &[Release], current: &str) -> Option<String> {
+        select_latest(releases, &Version::parse(current).unwrap())
+            .map(|(_, release)| release.tag_name.clone())
+    }
+
+    #[test]
+    fn test_select_latest_compares_versions() {
+        let releases = vec![
+            release("v0.9.0", false, false),
+            release("v0.10.0", false, false),
+            release("v0.2.0", false, false),
+            release("not-a-version", false, false),
+        ];
+        assert_eq!(latest_tag(&releases, "0.1.0").as_deref(), Some("v0.10.0"));
+    }
+
+    #[test]
+    fn test_select_latest_skips_drafts_and_prereleases() {
+        let releases = vec![
+            release("v1.0.0", false, false),
+            release("v2.0.0", true, false),
+            release("v1.1.0-alpha.1", false, true),
+        ];
+        assert_eq!(latest_tag(&releases, "1.0.0").as_deref(), Some("v1.0.0"));
+        assert_eq!(
+            latest_tag(&releases, "1.0.0-alpha.1").as_deref(),
+            Some("v1.1.0-alpha.1")
+        );
+        assert_eq!(latest_tag(&[release("v2.0.0", true, false)], "1.0.0"), None);
+    }
+
+    #[test]
+    fn test_asset_selection() {
+        assert_eq!(asset_name("linux-x64-musl"), "ryu-linux-x64-musl.tar.gz");
+        assert_eq!(asset_name("darwin-arm64"), "ryu-darwin-arm64.tar.gz");
+        assert_eq!(asset_name("win32-x64"), "ryu-win32-x64.zip");
+
+        let release = release("v1.0.0", false, false);
+        assert_eq!(
+            asset_url(&release, CHECKSUMS_ASSET).unwrap(),
+            "https://example.com/v1.0.0/checksums-sha256.txt"
+        );
+        assert!(asset_url(&release, "ryu-win32-x64.zip").is_err());
+    }
+
+    #[test]
+    fn test_parse_checksum() {
+        let checksums = "abc123  ryu-linux-x64.tar.gz\ndef456 *ryu-win32-x64.zip\n";
+        assert_eq!(
+            parse_checksum(checksums, "ryu-linux-x64.tar.gz").as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(
+            parse_checksum(checksums, "ryu-win32-x64.zip").as_deref(),
+            Some("def456")
+        );
+        assert_eq!(parse_checksum(checksums, "ryu-linux-x64"), None);
+    }
+
+    /// A minisign key pair: the public key line and a signing function
+    fn minisign_key(key_id: [u8; 8]) -> (String, impl Fn(&[u8], &[u8; 2]) -> String) {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key =
+            BASE64.encode([b"Ed".as_slice(), &key_id, pair.public_key().as_ref()].concat());
+        let sign = move |data: &[u8], algorithm: &[u8; 2]| {
+            let message = if algorithm == b"ED" {
+                Blake2b512::digest(data).to_vec()
+            } else {
+                data.to_vec()
+            };
+            let signature = pair.sign(&message);
+            let comment = "timestamp:0\tfile:checksums-sha256.txt";
+            let global = pair.sign(&[signature.as_ref(), comment.as_bytes()].concat());
+            format!(
+                "untrusted comment: signature from minisign secret key\n{}\ntrusted comment: {comment}\n{}\n",
+                BASE64.encode([algorithm.as_slice(), &key_id, signature.as_ref()].concat()),
+                BASE64.encode(global.as_ref())
+            )
+        };
+        (public_key, sign)
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let data = b"abc123  ryu-linux-x64.tar.gz\n";
+        let (public_key, sign) = minisign_key([1; 8]);
+
+        for algorithm in [b"Ed", b"ED"] {
+            let signature = sign(data, algorithm);
+            assert!(verify_signature(data, &signature, &public_key).is_ok());
+            assert!(
+                verify_signature(b"evil  ryu-linux-x64.tar.gz\n", &signature, &public_key).is_err()
+            );
+        }
+
+        let (other_key, _) = minisign_key([1; 8]);
+        assert!(verify_signature(data, &sign(data, b"ED"), &other_key).is_err());
+        let (_, other_sign) = minisign_key([2; 8]);
+        assert!(verify_signature(data, &other_sign(data, b"ED"), &public_key).is_err());
+
+        let tampered = sign(data, b"ED").replace("timestamp:0", "timestamp:1");
+        assert!(verify_signature(data, &tampered, &public_key).is_err());
+        assert!(verify_signature(data, "", &public_key).is_err());
+    }
+
+    #[test]
+    fn test_release_public_key_is_valid() {
+        if let Some(public_key) = RELEASE_PUBLIC_KEY {
+            let key = BASE64.decode(public_key).unwrap();
+            assert_eq!(key.len(), 42);
+            assert!(key.starts_with(b"Ed"));
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! `ryu send-email` command - mail the stack as a patch series
+
+use crate::cli::style::{Stylize, arrow, bullet, check};
+use anstream::println;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::graph::build_change_graph;
+use jj_ryu::repo::JjWorkspace;
+use jj_ryu::submit::{
+    FormatPatchOptions, SendPatchesOptions, analyze_submission, create_patch_series,
+    format_patches, patches_dir, send_patches,
+};
+use jj_ryu::tracking::load_tracking;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Options for the send-email command
+#[derive(Debug, Clone, Default)]
+pub struct SendEmailOptions {
+    /// Recipients
+    pub to: Vec<String>,
+    /// Carbon-copy recipients
+    pub cc: Vec<String>,
+    /// Reroll count (`[PATCH v2 ...]`)
+    pub version: Option<u32>,
+    /// Subject prefix instead of `PATCH`
+    pub subject_prefix: Option<String>,
+    /// Cover letter subject (default: from the stack)
+    pub subject: Option<String>,
+    /// Write the patches here instead of ryu's patch directory
+    pub output_dir: Option<PathBuf>,
+    /// Write the patches without sending them
+    pub format_only: bool,
+    /// Let `git send-email` print the emails instead of sending them
+    pub dry_run: bool,
+    /// Send all bookmarks in `trunk()`..@ (ignore tracking)
+    pub all: bool,
+}
+
+/// Run the send-email command
+///
+/// Exports the stack up to `bookmark` (default: the top tracked bookmark)
+/// with `git format-patch` and sends it with `git send-email`. No platform
+/// or remote is needed.
+pub async fn run_send_email(
+    path: &Path,
+    bookmark: Option<&str>,
+    options: SendEmailOptions,
+) -> Result<()> {
+    let workspace = JjWorkspace::open(path)?;
+    let workspace_root = workspace.workspace_root().to_path_buf();
+    let tracking = load_tracking(&workspace_root)?;
+
+    let tracked_names = tracking.tracked_names();
+    if tracked_names.is_empty() && !options.all {
+        return Err(Error::Tracking(
+            "No bookmarks tracked. Run 'ryu track' first, or use \
+             'ryu send-email --all' to send all bookmarks."
+                .to_string(),
+        ));
+    }
+
+    let graph = build_change_graph(&workspace)?;
+    if graph.stack.is_none() {
+        println!("{}", "No stack to send".muted());
+        return Ok(());
+    }
+    if let Some(bm) = bookmark
+        && !graph.bookmarks.contains_key(bm)
+    {
+        return Err(Error::BookmarkNotFound(bm.to_string()));
+    }
+
+    let mut analysis = analyze_submission(&graph, bookmark)?;
+    if !options.all {
+        // The series is one commit range, so untracked bookmarks below the
+        // top tracked one are sent too
+        let top = analysis
+            .segments
+            .iter()
+            .rposition(|s| tracked_names.contains(&s.bookmark.name.as_str()));
+        analysis.segments.truncate(top.map_or(0, |i| i + 1));
+        if analysis.segments.is_empty() {
+            return Err(Error::Tracking(
+                "No tracked bookmarks in the stack. Use 'ryu track' to track \
+                 bookmarks, or 'ryu send-email --all'."
+                    .to_string(),
+            ));
+        }
+    }
+
+    let names = analysis.segments.iter().map(|s| s.bookmark.name.as_str());
+    let base_branch = match tracking.stack_base(names) {
+        Some(base) => base.to_string(),
+        None => workspace.default_branch()?,
+    };
+
+    let mut series = create_patch_series(&analysis, &base_branch)?;
+    if let Some(subject) = options.subject {
+        series.subject = subject;
+    }
+
+    // ryu's own patch directory only holds the latest series
+    let output_dir = if let Some(dir) = options.output_dir {
+        dir
+    } else {
+        let dir = patches_dir(&workspace_root);
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        dir
+    };
+
+    let git_dir = workspace.git_dir()?;
+    let patches = format_patches(
+        &git_dir,
+        &series,
+        &output_dir,
+        &FormatPatchOptions {
+            version: options.version,
+            subject_prefix: options.subject_prefix,
+        },
+    )
+    .await?;
+
+    println!(
+        "{} Wrote {} patch{} for {} to {}",
+        check(),
+        series.patch_count.accent(),
+        if series.patch_count == 1 { "" } else { "es" },
+        base_branch.accent(),
+        output_dir.display().to_string().emphasis()
+    );
+    for patch in &patches {
+        let name = patch.file_name().map_or_else(
+            || patch.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        println!("  {} {}", bullet(), name.muted());
+    }
+
+    if options.format_only {
+        return Ok(());
+    }
+
+    println!();
+    println!("{} Sending with git send-email...", arrow());
+    send_patches(
+        &git_dir,
+        &patches,
+        &SendPatchesOptions {
+            to: options.to,
+            cc: options.cc,
+            dry_run: options.dry_run,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+//! `ryu split` command - break a bookmark's segment into several PRs
+
+use crate::cli::style::{Stylize, check};
+use anstream::println;
+use chrono::Utc;
+use dialoguer::MultiSelect;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::graph::build_change_graph;
+use jj_ryu::repo::JjWorkspace;
+use jj_ryu::submit::analyze_submission;
+use jj_ryu::tracking::{TrackedBookmark, load_tracking, save_tracking};
+use jj_ryu::types::LogEntry;
+use std::collections::HashSet;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
+/// Options for the split command
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SplitOptions {
+    /// Give every commit its own bookmark instead of prompting
+    pub each: bool,
+    /// Only show the bookmarks that would be created
+    pub dry_run: bool,
+}
+
+/// Run the split command
+///
+/// Creates bookmarks on commits inside the segment of `bookmark` (the top of
+/// the stack if None), so each group of commits becomes its own PR. The
+/// bookmark itself stays on the top commit, keeping its PR; the new
+/// bookmarks are named `<bookmark>-1`, `<bookmark>-2`, ... from the bottom,
+/// and are tracked like the bookmark if it is tracked.
+pub fn run_split(path: &Path, bookmark: Option<&str>, options: SplitOptions) -> Result<()> {
+    let mut workspace = JjWorkspace::open(path)?;
+    let workspace_root = workspace.workspace_root().to_path_buf();
+
+    let graph = build_change_graph(&workspace)?;
+    if graph.stack.is_none() {
+        return Err(Error::InvalidArgument(
+            "No stack found between trunk and working copy".to_string(),
+        ));
+    }
+    let analysis = analyze_submission(&graph, None)?;
+    let name = bookmark.unwrap_or(&analysis.target_bookmark);
+    let segment = analysis
+        .segments
+        .iter()
+        .find(|s| s.bookmark.name == name)
+        .ok_or_else(|| Error::BookmarkNotFound(name.to_string()))?;
+
+    // Commits below the bookmark's own, newest first
+    let below: Vec<&LogEntry> = segment
+        .changes
+        .iter()
+        .filter(|c| c.commit_id != segment.bookmark.commit_id)
+        .collect();
+    if below.is_empty() {
+        println!(
+            "{}",
+            format!("{name} has a single commit; nothing to split").muted()
+        );
+        return Ok(());
+    }
+
+    let cut: Vec<&LogEntry> = if options.each {
+        below
+    } else if io::stdin().is_terminal() {
+        select_cuts(&below)?
+    } else {
+        return Err(Error::InvalidArgument(
+            "Choosing where to split needs a terminal; pass --each to split every commit"
+                .to_string(),
+        ));
+    };
+    if cut.is_empty() {
+        println!("{}", "No commits selected".muted());
+        return Ok(());
+    }
+
+    // Name the new bookmarks bottom-up, skipping names already in use
+    let mut taken: HashSet<String> = workspace
+        .local_bookmarks()?
+        .into_iter()
+        .map(|b| b.name)
+        .collect();
+    let mut created: Vec<(String, &LogEntry)> = Vec::new();
+    let mut n = 1;
+    for change in cut.into_iter().rev() {
+        let new_name = loop {
+            let candidate = format!("{name}-{n}");
+            n += 1;
+            if taken.insert(candidate.clone()) {
+                break candidate;
+            }
+        };
+        created.push((new_name, change));
+    }
+
+    if options.dry_run {
+        for (new_name, change) in &created {
+            println!(
+                "  {} {}",
+                new_name.accent(),
+                change.description_first_line.muted()
+            );
+        }
+        println!();
+        println!("{}", "Dry run: nothing was changed".muted());
+        return Ok(());
+    }
+
+    let mut tracking = load_tracking(&workspace_root)?;
+    let tracked = tracking.get(name).cloned();
+    for (new_name, change) in &created {
+        workspace.set_bookmark(new_name, &change.commit_id)?;
+        if let Some(tracked) = &tracked {
+            tracking.track(TrackedBookmark {
+                name: new_name.clone(),
+                change_id: change.change_id.clone(),
+                remote: tracked.remote.clone(),
+                base: tracked.base.clone(),
+                tracked_at: Utc::now(),
+            });
+        }
+        println!(
+            "{} Created {} {}",
+            check(),
+            new_name.accent(),
+            change.description_first_line.muted()
+        );
+    }
+    if tracked.is_some() {
+        save_tracking(&workspace_root, &tracking)?;
+    }
+
+    println!();
+    println!(
+        "Run {} to open PRs for the new bookmarks",
+        "ryu submit".accent()
+    );
+    Ok(())
+}
+
+/// Ask which commits should end a PR of their own
+fn select_cuts<'a>(below: &[&'a LogEntry]) -> Result<Vec<&'a LogEntry>> {
+    let items: Vec<String> = below
+        .iter()
+        .map(|c| {
+            format!(
+                "{} {}",
+                &c.change_id[..8.min(c.change_id.len())],
+                c.description_first_line
+            )
+        })
+        .collect();
+
+    let selections = MultiSelect::new()
+        .with_prompt("Select commits to end a new PR at (space to toggle, enter to confirm)")
+        .items(&items)
+        .interact()
+        .map_err(|e| Error::Internal(format!("Failed to read selection: {e}")))?;
+
+    Ok(selections.into_iter().map(|i| below[i]).collect())
+}
@@ -1,19 +1,33 @@
 //! Submit command - submit a bookmark stack as PRs
 
 use crate::cli::context::CommandContext;
-use crate::cli::CliProgress;
-use crate::cli::style::{CHECK, Stylize, arrow, bullet, cross};
-use anstream::{eprintln, println};
+use crate::cli::gerrit::submit_gerrit;
+use crate::cli::output::{SubmissionReport, emit_json, emit_stopped, is_json, println};
+use crate::cli::style::{CHECK, Stylize, arrow, bullet, check, cross};
+use crate::cli::{CliProgress, JournalingProgress};
+use anstream::eprintln;
 use dialoguer::Confirm;
 use jj_ryu::error::{Error, Result};
 use jj_ryu::graph::build_change_graph;
+use jj_ryu::hooks::HookEvent;
 use jj_ryu::platform::PlatformService;
+use jj_ryu::repo::JjWorkspace;
 use jj_ryu::submit::{
-    ExecutionStep, SubmissionAnalysis, SubmissionPlan, analyze_submission, create_submission_plan,
-    execute_submission, select_bookmark_for_segment,
+    ExecutionStep, PrToReopen, ProgressCallback, SubmissionAnalysis, SubmissionPlan,
+    analyze_submission, apply_config_to_plan, apply_pr_template, capture_rollback_snapshot,
+    collapse_to_single_pr, create_as_drafts, create_submission_plan_onto, execute_submission,
+    find_pr_template, generate_changelog_body, record_undo, relink_closed_prs,
+    resume_submission_plan, revset_bookmark_range, rollback_submission,
+    select_bookmark_for_segment, trim_below,
 };
-use jj_ryu::tracking::save_pr_cache;
-use jj_ryu::types::{ChangeGraph, NarrowedBookmarkSegment};
+use jj_ryu::tracking::{
+    PendingOperation, SubmissionJournal, clear_journal, load_journal, load_op_queue, load_tracking,
+    save_journal, save_op_queue,
+};
+use jj_ryu::types::{ChangeGraph, NarrowedBookmarkSegment, Platform, StackCommentMode};
+use jj_ryu::webhooks::ChatSummary;
+use std::collections::HashMap;
+use std::io::IsTerminal;
 use std::path::Path;
 
 /// Scope of bookmark submission (mutually exclusive options)
@@ -53,6 +67,10 @@ pub struct SubmitOptions<'a> {
     pub scope: SubmitScope,
     /// Bookmark name for --upto (only valid when scope == Upto)
     pub upto_bookmark: Option<&'a str>,
+    /// Lowest bookmark to submit; the bookmarks below it are left alone
+    pub from_bookmark: Option<&'a str>,
+    /// Revset whose bookmarks make up the part of the stack to submit
+    pub revisions: Option<&'a str>,
     /// Only update existing PRs, don't create new ones
     pub update_only: bool,
     /// Create new PRs as drafts
@@ -63,10 +81,31 @@ pub struct SubmitOptions<'a> {
     pub select: bool,
     /// Submit all bookmarks in `trunk()`..@ (ignore tracking)
     pub all: bool,
+    /// Queue the submission for `ryu flush` instead of contacting the remote
+    pub offline: bool,
+    /// Resume an interrupted submission from its journal
+    pub resume: bool,
+    /// Roll back this run's changes if any step fails
+    pub atomic: bool,
+    /// Submit the whole stack as one PR from the top bookmark
+    pub single_pr: bool,
+    /// Use the simulated platform and don't push
+    pub simulate: bool,
+    /// Labels to add to every PR, on top of `submit.labels` from config
+    pub labels: Vec<String>,
+    /// Users to assign new PRs to (`self` is the authenticated user)
+    pub assignees: Vec<String>,
+    /// Milestone to put every PR in, instead of `submit.milestone` from config
+    pub milestone: Option<String>,
+    /// Put the stack overview in PR descriptions instead of a comment
+    pub stack_in_body: bool,
+    /// Leave the stack overview off every PR, whatever `submit.stack_comment` says
+    pub no_stack_comment: bool,
+    /// Reopen closed PRs for bookmarks without asking
+    pub reopen: bool,
 }
 
 /// Run the submit command
-#[allow(clippy::too_many_lines)]
 pub async fn run_submit(
     path: &Path,
     bookmark: Option<&str>,
@@ -80,8 +119,60 @@ pub async fn run_submit(
         ));
     }
 
+    if options.offline {
+        return queue_submit(path, bookmark, remote, &options);
+    }
+
+    submit_stack(path, bookmark, remote, options).await?;
+    Ok(())
+}
+
+/// Submit the stack, returning whether every step succeeded
+#[allow(clippy::too_many_lines)]
+pub async fn submit_stack(
+    path: &Path,
+    bookmark: Option<&str>,
+    remote: Option<&str>,
+    options: SubmitOptions<'_>,
+) -> Result<bool> {
     // Create shared context
-    let mut ctx = CommandContext::new(path, remote).await?;
+    let mut ctx = if options.simulate {
+        print_simulation_notice();
+        CommandContext::simulated(path, remote).await?
+    } else {
+        CommandContext::new(path, remote).await?
+    };
+    // `submit.draft` makes drafts the default; `--publish` still wins
+    let options = SubmitOptions {
+        draft: options.draft || (ctx.config.submit.draft && !options.publish),
+        ..options
+    };
+
+    // Load the journal left by an interrupted submission, if any
+    let journal = load_journal(&ctx.workspace_root)?;
+    let resume_journal = if options.resume {
+        let journal = journal.ok_or_else(|| {
+            Error::InvalidArgument("No interrupted submission to continue".to_string())
+        })?;
+        if journal.remote != ctx.remote_name {
+            return Err(Error::InvalidArgument(format!(
+                "Interrupted submission used remote '{}'; pass --remote {} to continue it",
+                journal.remote, journal.remote
+            )));
+        }
+        Some(journal)
+    } else {
+        if journal.is_some() {
+            println!(
+                "{}",
+                "A previous submission was interrupted. Run 'ryu submit --continue' to resume it."
+                    .muted()
+            );
+        }
+        None
+    };
+    let journal_bookmark = resume_journal.as_ref().and_then(|j| j.bookmark.clone());
+    let bookmark = bookmark.or(journal_bookmark.as_deref());
 
     // Check tracking (unless --all bypasses tracking)
     // Collect into owned strings to avoid borrow checker issues with later mutations
@@ -105,14 +196,32 @@ pub async fn run_submit(
             "{}",
             "Create a bookmark with: jj bookmark create <name>".muted()
         );
-        return Ok(());
+        emit_stopped(
+            "submit",
+            true,
+            "No bookmarks found between trunk and working copy",
+        )?;
+        return Ok(true);
     }
 
-    // If bookmark specified, verify it exists in stack
-    if let Some(bm) = bookmark
-        && !graph.bookmarks.contains_key(bm)
-    {
-        return Err(Error::BookmarkNotFound(bm.to_string()));
+    // Resolve --revisions to the bookmarks at the ends of the range it covers
+    let revision_range = match options.revisions {
+        Some(revset) => Some(revset_bookmark_range(
+            &graph,
+            &ctx.workspace.resolve_revset(revset)?,
+        )?),
+        None => None,
+    };
+    let (from, bookmark) = match &revision_range {
+        Some((from, to)) => (Some(from.as_str()), Some(to.as_str())),
+        None => (options.from_bookmark, bookmark),
+    };
+
+    // If bookmarks specified, verify they exist in stack
+    for bm in [bookmark, from].into_iter().flatten() {
+        if !graph.bookmarks.contains_key(bm) {
+            return Err(Error::BookmarkNotFound(bm.to_string()));
+        }
     }
 
     // Analyze submission based on options
@@ -120,9 +229,18 @@ pub async fn run_submit(
 
     // Filter to tracked bookmarks unless --all
     if !options.all && !tracked_names.is_empty() {
-        analysis
-            .segments
-            .retain(|s| tracked_names.contains(&s.bookmark.name));
+        if options.single_pr {
+            // Untracked bookmarks below the top tracked one still ride along
+            let top = analysis
+                .segments
+                .iter()
+                .rposition(|s| tracked_names.contains(&s.bookmark.name));
+            analysis.segments.truncate(top.map_or(0, |i| i + 1));
+        } else {
+            analysis
+                .segments
+                .retain(|s| tracked_names.contains(&s.bookmark.name));
+        }
         if analysis.segments.is_empty() {
             return Err(Error::Tracking(
                 "No tracked bookmarks in submission scope. Use 'ryu track' to track bookmarks, or 'ryu submit --all'.".to_string()
@@ -130,22 +248,97 @@ pub async fn run_submit(
         }
     }
 
+    // Leave the bookmarks below --from alone, basing its PR on the one below
+    let range_base = match from {
+        Some(from) => trim_below(&mut analysis, from)?,
+        None => None,
+    };
+
+    // Gerrit reviews commits, not bookmarks
+    if ctx.platform.config().platform == Platform::Gerrit {
+        if from.is_some() {
+            return Err(Error::Unsupported(
+                "Gerrit reviews every commit below the top of the stack; submit up to a bookmark instead of a range"
+                    .to_string(),
+            ));
+        }
+        if is_json() {
+            return Err(Error::Unsupported(
+                "--json is not supported with Gerrit".to_string(),
+            ));
+        }
+        return submit_gerrit(&mut ctx, &analysis, &options).await;
+    }
+
+    if options.single_pr {
+        collapse_to_single_pr(&mut analysis);
+    }
+
     // Display what will be submitted
     print_submission_summary(&analysis, &options);
 
     // Create submission plan
-    let mut plan =
-        create_submission_plan(&analysis, ctx.platform.as_ref(), &ctx.remote_name, &ctx.default_branch).await?;
+    let base = range_base.unwrap_or_else(|| ctx.stack_base(&analysis));
+    let mut plan = create_submission_plan_onto(
+        &analysis,
+        ctx.platform.as_ref(),
+        &ctx.remote_name,
+        &base,
+        &ctx.default_branch,
+    )
+    .await?;
+    plan.push_remotes = ctx.tracking.push_remotes();
+    plan.labels = submit_labels(&ctx, &options);
+    plan.assignees = resolve_assignees(ctx.platform.as_ref(), &options.assignees).await?;
+    plan.milestone = options
+        .milestone
+        .clone()
+        .or_else(|| ctx.config.submit.milestone.clone());
+    // Drafts are left to apply_plan_options, where --publish wins
+    apply_config_to_plan(&mut plan, &ctx.config.submit, &ctx.pr_cache, false)?;
+    plan.stack_in_body |= options.stack_in_body;
+    if options.no_stack_comment {
+        plan.stack_comment = StackCommentMode::Never;
+    }
+
+    // Reuse PRs created before the interruption instead of creating duplicates
+    if let Some(journal) = &resume_journal {
+        resume_submission_plan(&mut plan, &journal.created_prs);
+    }
+
+    // Reopen closed PRs (e.g. after their branch was deleted) instead of
+    // creating duplicates: with --reopen, or if the user agrees. Otherwise a
+    // closed PR stays closed and a new one is created.
+    let ask = !options.reopen && !options.dry_run && !is_json() && std::io::stdin().is_terminal();
+    if options.reopen || ask {
+        let known_prs: HashMap<String, u64> = ctx
+            .pr_cache
+            .prs
+            .iter()
+            .filter(|cached| cached.remote == ctx.remote_name)
+            .map(|cached| (cached.bookmark.clone(), cached.number))
+            .collect();
+        relink_closed_prs(&mut plan, ctx.platform.as_ref(), &known_prs, |reopen| {
+            options.reopen || confirm_reopen(reopen)
+        })
+        .await;
+    }
 
     // Apply plan modifications based on options
     apply_plan_options(&mut plan, &options);
 
+    // Start new PR bodies with the repo's PR template
+    if let Some(template) = find_pr_template(&ctx.workspace_root, ctx.platform.config().platform) {
+        apply_pr_template(&mut plan, &template);
+    }
+
     // Handle interactive selection
     if options.select {
         let selected = interactive_select(&analysis)?;
         if selected.is_empty() {
             println!("{}", "No bookmarks selected, aborting".muted());
-            return Ok(());
+            emit_stopped("submit", true, "No bookmarks selected")?;
+            return Ok(true);
         }
         filter_plan_to_selection(&mut plan, &selected);
     }
@@ -160,29 +353,138 @@ pub async fn run_submit(
             .map_err(|e| Error::Internal(format!("Failed to read confirmation: {e}")))?
         {
             println!("{}", "Aborted".muted());
-            return Ok(());
+            return Ok(false);
         }
         println!();
     }
 
-    // Execute plan
+    // Record repo and remote state so the run can be undone, or rolled back
+    // under --atomic
+    let (mut undo, snapshot) = if options.dry_run {
+        (None, None)
+    } else {
+        (
+            ctx.begin_undo("submit")?,
+            Some(capture_rollback_snapshot(&plan, &ctx.workspace)?),
+        )
+    };
+
+    // Journal progress as the plan runs, so a failed or interrupted
+    // submission can be resumed with --continue
     let progress = CliProgress::verbose();
-    let result = execute_submission(
+    let journaling = if options.dry_run || ctx.simulated {
+        None
+    } else {
+        let journal = resume_journal.unwrap_or_else(|| {
+            SubmissionJournal::new(bookmark.map(String::from), ctx.remote_name.clone())
+        });
+        Some(JournalingProgress::start(
+            &progress,
+            &ctx.workspace_root,
+            journal,
+        )?)
+    };
+
+    let execution_progress: &dyn ProgressCallback = match &journaling {
+        Some(journaling) => journaling,
+        None => &progress,
+    };
+
+    // Execute plan
+    let executed = execute_submission(
         &plan,
         &mut ctx.workspace,
         ctx.platform.as_ref(),
-        &progress,
+        execution_progress,
         options.dry_run,
     )
-    .await?;
+    .await;
+    let mut journal = journaling.map(JournalingProgress::into_journal);
+    let result = match executed {
+        Ok(result) => result,
+        Err(e) => {
+            if let Some(journal) = &mut journal {
+                journal.errors.push(e.to_string());
+                save_journal(&ctx.workspace_root, journal)?;
+            }
+            return Err(e);
+        }
+    };
+    emit_json(&SubmissionReport {
+        command: "submit",
+        dry_run: options.dry_run,
+        success: result.success,
+        target_bookmark: &analysis.target_bookmark,
+        plan: &plan,
+        result: &result,
+    })?;
+
+    // Undo this run's changes if it failed under --atomic
+    if options.atomic
+        && let Some(snapshot) = &snapshot
+        && !result.success
+    {
+        println!();
+        println!("{}", "Rolling back submission...".emphasis());
+        let rollback = rollback_submission(
+            &plan,
+            snapshot,
+            &result,
+            &mut ctx.workspace,
+            ctx.platform.as_ref(),
+            &progress,
+        )
+        .await;
+
+        eprintln!("{} Submission failed", cross());
+        for err in &result.errors {
+            eprintln!("  {}", err.error());
+        }
+        if rollback.is_complete() {
+            println!("{} Rolled back; remote is in its pre-submit state", check());
+            if !ctx.simulated {
+                clear_journal(&ctx.workspace_root)?;
+            }
+        } else {
+            eprintln!("{} Rollback incomplete:", cross());
+            for err in &rollback.errors {
+                eprintln!("  {}", err.error());
+            }
+        }
+        return Ok(false);
+    }
+
+    if let (Some(undo), Some(snapshot)) = (&mut undo, &snapshot) {
+        record_undo(undo, &plan, snapshot, &result);
+        ctx.save_undo(Some(undo));
+    }
+
+    if !options.dry_run {
+        ctx.save_submitted_prs(&plan, &result);
+    }
 
-    // Update PR cache with results
     if !options.dry_run && result.success {
-        for pr in result.created_prs.iter().chain(result.updated_prs.iter()) {
-            ctx.pr_cache.upsert(&pr.head_ref, pr, &ctx.remote_name);
+        ctx.run_hook(HookEvent::PostSubmit, &result).await;
+        let summary = ChatSummary::submit(
+            ctx.workspace.user_name(),
+            &analysis.target_bookmark,
+            &ctx.repo_name(),
+            &result,
+        );
+        if let Some(summary) = summary {
+            ctx.post_webhooks(summary).await;
+        }
+    }
+
+    // Keep the journal only if the submission failed
+    if let Some(mut journal) = journal {
+        if result.success {
+            clear_journal(&ctx.workspace_root)?;
+        } else {
+            journal.record_created(&result.created_prs);
+            journal.errors.clone_from(&result.errors);
+            save_journal(&ctx.workspace_root, &journal)?;
         }
-        // Best effort - don't fail submit if cache write fails
-        let _ = save_pr_cache(&ctx.workspace_root, &ctx.pr_cache);
     }
 
     // Summary
@@ -200,6 +502,23 @@ pub async fn run_submit(
                 }
             );
 
+            for reopen in plan.execution_steps.iter().filter_map(|step| match step {
+                ExecutionStep::ReopenPr(reopen)
+                    if result
+                        .updated_prs
+                        .iter()
+                        .any(|pr| pr.number == reopen.pr.number) =>
+                {
+                    Some(reopen)
+                }
+                _ => None,
+            }) {
+                println!(
+                    "Reopened PR #{} for {}",
+                    reopen.pr.number.accent(),
+                    reopen.create.bookmark.name.accent()
+                );
+            }
             if !result.created_prs.is_empty() {
                 println!(
                     "Created {} PR{}",
@@ -216,9 +535,125 @@ pub async fn run_submit(
             for err in &result.errors {
                 eprintln!("  {}", err.error());
             }
+            eprintln!(
+                "{}",
+                "Fix the problem, then run 'ryu submit --continue' to resume.".muted()
+            );
         }
     }
 
+    Ok(options.dry_run || result.success)
+}
+
+/// Ask whether to reopen a closed PR instead of creating a new one
+fn confirm_reopen(reopen: &PrToReopen) -> bool {
+    Confirm::new()
+        .with_prompt(format!(
+            "PR #{} for {} was closed. Reopen it instead of creating a new PR?",
+            reopen.pr.number, reopen.create.bookmark.name
+        ))
+        .default(true)
+        .interact()
+        .unwrap_or(false)
+}
+
+/// Labels from config followed by those given with `--label`, without
+/// duplicates
+pub fn submit_labels(ctx: &CommandContext, options: &SubmitOptions<'_>) -> Vec<String> {
+    let mut labels: Vec<String> = Vec::new();
+    for label in ctx.config.submit.labels.iter().chain(&options.labels) {
+        if !labels.contains(label) {
+            labels.push(label.clone());
+        }
+    }
+    labels
+}
+
+/// Assignees with `self` replaced by the authenticated user
+async fn resolve_assignees(
+    platform: &dyn PlatformService,
+    assignees: &[String],
+) -> Result<Vec<String>> {
+    let mut resolved = Vec::with_capacity(assignees.len());
+    for assignee in assignees {
+        let user = if assignee == "self" {
+            platform.current_user().await?
+        } else {
+            assignee.clone()
+        };
+        if !resolved.contains(&user) {
+            resolved.push(user);
+        }
+    }
+    Ok(resolved)
+}
+
+/// Explain what `--simulate` does before it runs
+pub fn print_simulation_notice() {
+    println!(
+        "{}",
+        "Simulating: nothing is pushed and PRs are created on a local fake platform".muted()
+    );
+    println!();
+}
+
+/// Record a submission in the offline queue without contacting the remote
+///
+/// Only local validation runs here: the workspace must open and the target
+/// bookmark must exist and be tracked (unless `--all`).
+fn queue_submit(
+    path: &Path,
+    bookmark: Option<&str>,
+    remote: Option<&str>,
+    options: &SubmitOptions<'_>,
+) -> Result<()> {
+    let workspace = JjWorkspace::open(path)?;
+    let workspace_root = workspace.workspace_root().to_path_buf();
+
+    if let Some(bm) = bookmark {
+        if workspace.get_local_bookmark(bm)?.is_none() {
+            return Err(Error::BookmarkNotFound(bm.to_string()));
+        }
+        if !options.all && !load_tracking(&workspace_root)?.is_tracked(bm) {
+            return Err(Error::Tracking(format!(
+                "Bookmark '{bm}' is not tracked. Run 'ryu track {bm}' first, or use --all."
+            )));
+        }
+    }
+
+    let mut queue = load_op_queue(&workspace_root)?;
+    let operation = PendingOperation::Submit {
+        bookmark: bookmark.map(String::from),
+        remote: remote.map(String::from),
+        upto: options.upto_bookmark.map(String::from),
+        only: options.scope == SubmitScope::Only,
+        stack: options.scope == SubmitScope::Stack,
+        update_only: options.update_only,
+        draft: options.draft,
+        publish: options.publish,
+        all: options.all,
+        labels: options.labels.clone(),
+        assignees: options.assignees.clone(),
+        milestone: options.milestone.clone(),
+        stack_in_body: options.stack_in_body,
+        no_stack_comment: options.no_stack_comment,
+        reopen: options.reopen,
+    };
+    println!(
+        "{} Queued {} ({} pending)",
+        check(),
+        operation.describe().emphasis(),
+        (queue.len() + 1).accent()
+    );
+    let queued = format!("Queued {} for 'ryu flush'", operation.describe());
+    queue.push(operation);
+    save_op_queue(&workspace_root, &queue)?;
+    emit_stopped("submit", true, &queued)?;
+
+    println!(
+        "{}",
+        "Run 'ryu flush' when back online to perform it.".muted()
+    );
     Ok(())
 }
 
@@ -327,11 +762,29 @@ async fn build_analysis(
 
 /// Apply plan modifications based on options
 fn apply_plan_options(plan: &mut SubmissionPlan, options: &SubmitOptions<'_>) {
+    // Handle --single-pr: describe every change in the stack's one PR
+    if options.single_pr {
+        for step in &mut plan.execution_steps {
+            let (name, body) = match step {
+                ExecutionStep::CreatePr(create)
+                | ExecutionStep::ReopenPr(PrToReopen { create, .. }) => {
+                    (&create.bookmark.name, &mut create.body)
+                }
+                ExecutionStep::UpdateMetadata(update) => (&update.bookmark.name, &mut update.body),
+                _ => continue,
+            };
+            if let Some(segment) = plan.segments.iter().find(|s| &s.bookmark.name == name) {
+                *body = generate_changelog_body(segment);
+            }
+        }
+    }
+
     // Handle --update-only: remove PR creation steps and filter to existing PRs
     if options.update_only {
         plan.execution_steps.retain(|step| {
             match step {
-                ExecutionStep::CreatePr(_) => false, // Remove all creates
+                // Remove all creates (reopens revive PRs the plan doesn't know about)
+                ExecutionStep::CreatePr(_) | ExecutionStep::ReopenPr(_) => false,
                 ExecutionStep::Push(bm) => plan.existing_prs.contains_key(&bm.name),
                 _ => true,
             }
@@ -341,11 +794,7 @@ fn apply_plan_options(plan: &mut SubmissionPlan, options: &SubmitOptions<'_>) {
     // Handle --draft: mark new PRs as drafts (unless --publish is also set)
     // When both flags are present, --publish takes precedence and --draft is ignored
     if options.draft && !options.publish {
-        for step in &mut plan.execution_steps {
-            if let ExecutionStep::CreatePr(create) = step {
-                create.draft = true;
-            }
-        }
+        create_as_drafts(plan);
     }
 
     // Handle --publish: publish existing draft PRs
@@ -435,7 +884,7 @@ fn filter_plan_to_selection(plan: &mut SubmissionPlan, selected: &[String]) {
 /// Print submission summary
 fn print_submission_summary(analysis: &SubmissionAnalysis, options: &SubmitOptions<'_>) {
     println!(
-        "{} {} bookmark{}{}:",
+        "{} {} bookmark{}{}{}{}:",
         "Submitting".emphasis(),
         analysis.segments.len().accent(),
         if analysis.segments.len() == 1 {
@@ -443,7 +892,19 @@ fn print_submission_summary(analysis: &SubmissionAnalysis, options: &SubmitOptio
         } else {
             "s"
         },
-        options.scope.to_string().muted()
+        options.scope.to_string().muted(),
+        if options.revisions.is_some() {
+            " (--revisions)".muted().to_string()
+        } else if options.from_bookmark.is_some() {
+            " (--from)".muted().to_string()
+        } else {
+            String::new()
+        },
+        if options.single_pr {
+            " (--single-pr)".muted().to_string()
+        } else {
+            String::new()
+        }
     );
 
     // Display newest (leaf) first, oldest (closest to trunk) last
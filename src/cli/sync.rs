@@ -2,19 +2,42 @@
 
 use crate::cli::context::CommandContext;
 use crate::cli::CliProgress;
+use crate::cli::notify::notify;
+use crate::cli::restack::pause_on_conflicts;
 use crate::cli::style::{CHECK, Stylize, arrow, check, spinner_style};
-use anstream::println;
-use dialoguer::Confirm;
+use crate::cli::output::{SubmissionReport, emit_json, emit_stopped, println};
+use anstream::eprintln;
+use clap::ValueEnum;
+use dialoguer::{Confirm, Select};
 use indicatif::ProgressBar;
 use jj_ryu::error::{Error, Result};
 use jj_ryu::graph::build_change_graph;
+use jj_ryu::platform::get_prs_details;
+use jj_ryu::repo::remove_resolution_changes;
 use jj_ryu::submit::{
-    SubmissionPlan, analyze_submission, create_submission_plan, execute_submission,
+    ExecutionStep, SubmissionPlan, analyze_submission, apply_config_to_plan, bottom_parent_merged,
+    capture_rollback_snapshot, create_submission_plan_onto, execute_submission,
+    publish_bottom_draft, record_undo,
 };
+use jj_ryu::tracking::{clear_restack_state, load_restack_state, save_pr_cache, save_tracking};
+use jj_ryu::types::{ChangeGraph, Platform, PrState};
+use std::io::{self, IsTerminal};
 use std::path::Path;
 use std::time::Duration;
 
+/// What to do with a bookmark whose remote branch was rewritten by someone else
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DivergenceChoice {
+    /// Keep the local commits and force-push them over the remote
+    KeepLocal,
+    /// Replace the local commits with the remote's
+    Reset,
+    /// Leave the bookmark unpushed for this sync
+    Skip,
+}
+
 /// Options for the sync command
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, Default)]
 pub struct SyncOptions {
     /// Dry run - show what would be done without making changes
@@ -23,41 +46,136 @@ pub struct SyncOptions {
     pub confirm: bool,
     /// Sync all bookmarks in `trunk()`..@ (ignore tracking)
     pub all: bool,
+    /// Send a desktop notification when the sync finishes
+    pub notify: bool,
+    /// Resume a sync paused on rebase conflicts
+    pub resume: bool,
+    /// Create new PRs as drafts
+    pub draft: bool,
+    /// Remove tracked bookmarks whose PR was merged or closed, or whose
+    /// branch was deleted from the remote
+    pub prune: bool,
+    /// What to do with diverged bookmarks (None = ask, or skip them when
+    /// there's no terminal)
+    pub on_diverged: Option<DivergenceChoice>,
 }
 
 /// Run the sync command
-#[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_lines, clippy::future_not_send)]
 pub async fn run_sync(path: &Path, remote: Option<&str>, options: SyncOptions) -> Result<()> {
     // Create shared context
     let mut ctx = CommandContext::new(path, remote).await?;
+    if ctx.platform.config().platform == Platform::Gerrit {
+        return Err(Error::Unsupported(
+            "ryu sync is not supported with Gerrit; rebase with jj, then run 'ryu submit' to upload new patch sets".to_string(),
+        ));
+    }
+
+    // A sync paused on conflicts resumes without fetching again
+    let restack = load_restack_state(&ctx.workspace_root)?;
+    if options.resume {
+        let state = restack.as_ref().ok_or_else(|| {
+            Error::InvalidArgument("No sync paused on conflicts to continue".to_string())
+        })?;
+        if state.remote != ctx.remote_name {
+            return Err(Error::InvalidArgument(format!(
+                "Paused sync used remote '{}'; pass --remote {} to continue it",
+                state.remote, state.remote
+            )));
+        }
+    } else if restack.is_some() {
+        println!(
+            "{}",
+            "A sync is paused on conflicts. Resolve them and run 'ryu sync --continue'.".muted()
+        );
+    }
 
     // Check tracking (unless --all bypasses tracking)
     // Collect into owned strings to avoid borrow checker issues with later mutations
-    let tracked_names: Vec<String> = ctx.tracked_names().into_iter().map(String::from).collect();
+    let mut tracked_names: Vec<String> =
+        ctx.tracked_names().into_iter().map(String::from).collect();
     if tracked_names.is_empty() && !options.all {
         return Err(Error::Tracking(
             "No bookmarks tracked. Run 'ryu track' first, or use 'ryu sync --all' to sync all bookmarks.".to_string()
         ));
     }
 
+    // Record where the repo is before fetching and rebasing, for 'ryu undo'
+    let mut undo = if options.dry_run {
+        None
+    } else {
+        ctx.begin_undo("sync")?
+    };
+
     // Fetch from remote with spinner
-    if !options.dry_run {
+    let diverged = if !options.dry_run && !options.resume {
+        // Remember what was last pushed, to spot commits pushed on top by reviewers
+        let heads = pushed_heads(&ctx, &tracked_names, options.all)?;
+
         let spinner = ProgressBar::new_spinner();
         spinner.set_style(spinner_style());
         spinner.set_message(format!("Fetching from {}...", ctx.remote_name.emphasis()));
         spinner.enable_steady_tick(Duration::from_millis(80));
 
-        ctx.workspace.git_fetch(&ctx.remote_name)?;
+        // Bookmarks tracked with their own remote are pushed there
+        let mut remotes = vec![ctx.remote_name.clone()];
+        for remote in ctx.tracking.push_remotes().into_values() {
+            if !remotes.contains(&remote) {
+                remotes.push(remote);
+            }
+        }
+        for remote in &remotes {
+            ctx.workspace.git_fetch(remote)?;
+        }
 
         spinner.finish_with_message(format!(
             "{} Fetched from {}",
             check(),
-            ctx.remote_name.emphasis()
+            remotes.join(", ").emphasis()
         ));
+
+        pull_reviewer_commits(&mut ctx, &heads)?
+    } else {
+        Vec::new()
+    };
+
+    // Merged stubs confuse the next submission; point them out or prune them.
+    // A sync paused while pruning prunes again once the conflicts are resolved.
+    if !options.resume || options.prune {
+        let finished = find_finished(&ctx, &tracked_names).await;
+        if !finished.is_empty() {
+            if options.prune && !options.dry_run {
+                if prune_finished(&mut ctx, &finished, &tracked_names, !options.resume)? {
+                    emit_stopped(
+                        "sync",
+                        false,
+                        "Rebase left conflicts; resolve them and run 'ryu sync --continue --prune'",
+                    )?;
+                    return Ok(());
+                }
+                tracked_names.retain(|name| !finished.iter().any(|f| &f.name == name));
+                if tracked_names.is_empty() && !options.all {
+                    println!("{}", "No tracked bookmarks left to sync".muted());
+                    emit_stopped("sync", true, "No tracked bookmarks left to sync")?;
+                    return Ok(());
+                }
+            } else {
+                print_finished(&finished, options.prune);
+            }
+        }
     }
 
     // Build change graph from working copy
-    let graph = build_change_graph(&ctx.workspace)?;
+    let mut graph = build_change_graph(&ctx.workspace)?;
+
+    // Someone else rewrote these branches; don't force-push over them blindly
+    let skipped = if diverged.is_empty() {
+        Vec::new()
+    } else {
+        let skipped = resolve_divergence(&mut ctx, &graph, &diverged, options.on_diverged)?;
+        graph = build_change_graph(&ctx.workspace)?;
+        skipped
+    };
 
     if graph.stack.is_none() {
         println!("{}", "No stack to sync".muted());
@@ -65,6 +183,7 @@ pub async fn run_sync(path: &Path, remote: Option<&str>, options: SyncOptions) -
             "{}",
             "Create bookmarks between trunk and working copy first.".muted()
         );
+        emit_stopped("sync", true, "No stack to sync")?;
         return Ok(());
     }
 
@@ -85,8 +204,48 @@ pub async fn run_sync(path: &Path, remote: Option<&str>, options: SyncOptions) -
         }
     }
 
-    let plan =
-        create_submission_plan(&analysis, ctx.platform.as_ref(), &ctx.remote_name, &ctx.default_branch).await?;
+    // Don't push conflicted commits; wait for 'ryu sync --continue'
+    let base = ctx.stack_base(&analysis);
+    if !options.dry_run {
+        if pause_on_conflicts(&mut ctx, &analysis.segments, &base)? {
+            emit_stopped(
+                "sync",
+                false,
+                "Rebase left conflicts; resolve them and run 'ryu sync --continue'",
+            )?;
+            return Ok(());
+        }
+        if restack.is_some() {
+            remove_resolution_changes(&mut ctx.workspace)?;
+            clear_restack_state(&ctx.workspace_root)?;
+        }
+    }
+
+    let mut plan = create_submission_plan_onto(
+        &analysis,
+        ctx.platform.as_ref(),
+        &ctx.remote_name,
+        &base,
+        &ctx.default_branch,
+    )
+    .await?;
+    plan.push_remotes = ctx.tracking.push_remotes();
+    apply_config_to_plan(
+        &mut plan,
+        &ctx.config.submit,
+        &ctx.pr_cache,
+        options.draft || ctx.config.submit.draft,
+    )?;
+    plan.execution_steps
+        .retain(|step| !matches!(step, ExecutionStep::Push(b) if skipped.contains(&b.name)));
+
+    // The PR below the bottom merged, so the bottom is ready for review
+    if ctx.config.drafts.auto_publish
+        && bottom_parent_merged(&plan)
+        && let Some(number) = publish_bottom_draft(&mut plan)
+    {
+        println!("{} Publishing draft #{number}", arrow());
+    }
 
     // Show confirmation if requested
     if options.confirm && !options.dry_run {
@@ -110,6 +269,11 @@ pub async fn run_sync(path: &Path, remote: Option<&str>, options: SyncOptions) -
         analysis.target_bookmark.accent()
     );
 
+    let snapshot = if undo.is_some() {
+        Some(capture_rollback_snapshot(&plan, &ctx.workspace)?)
+    } else {
+        None
+    };
     let result = execute_submission(
         &plan,
         &mut ctx.workspace,
@@ -118,6 +282,21 @@ pub async fn run_sync(path: &Path, remote: Option<&str>, options: SyncOptions) -
         options.dry_run,
     )
     .await?;
+    if let (Some(undo), Some(snapshot)) = (&mut undo, &snapshot) {
+        record_undo(undo, &plan, snapshot, &result);
+        ctx.save_undo(Some(undo));
+    }
+    if !options.dry_run {
+        ctx.save_submitted_prs(&plan, &result);
+    }
+    emit_json(&SubmissionReport {
+        command: "sync",
+        dry_run: options.dry_run,
+        success: result.success,
+        target_bookmark: &analysis.target_bookmark,
+        plan: &plan,
+        result: &result,
+    })?;
 
     // Summary
     println!();
@@ -133,9 +312,359 @@ pub async fn run_sync(path: &Path, remote: Option<&str>, options: SyncOptions) -
         );
     }
 
+    if options.notify && !options.dry_run {
+        if result.success {
+            notify(
+                "ryu: sync complete",
+                &format!(
+                    "{} pushed, {} created, {} updated",
+                    result.pushed_bookmarks.len(),
+                    result.created_prs.len(),
+                    result.updated_prs.len()
+                ),
+            );
+        } else {
+            notify("ryu: sync failed", &result.errors.join("\n"));
+        }
+    }
+
     Ok(())
 }
 
+/// A bookmark's local commit and the commit last pushed for it
+struct PushedHead {
+    bookmark: String,
+    remote: String,
+    local: String,
+    pushed: String,
+}
+
+/// Local and last-pushed commits of the bookmarks being synced
+fn pushed_heads(
+    ctx: &CommandContext,
+    tracked_names: &[String],
+    all: bool,
+) -> Result<Vec<PushedHead>> {
+    let mut heads = Vec::new();
+    for bookmark in ctx.workspace.local_bookmarks()? {
+        if !all && !tracked_names.contains(&bookmark.name) {
+            continue;
+        }
+        let remote_name = ctx
+            .tracking
+            .get(&bookmark.name)
+            .and_then(|tracked| tracked.remote.clone())
+            .unwrap_or_else(|| ctx.remote_name.clone());
+        if let Some(remote) = ctx
+            .workspace
+            .get_remote_bookmark(&bookmark.name, &remote_name)?
+        {
+            heads.push(PushedHead {
+                bookmark: bookmark.name,
+                remote: remote_name,
+                local: bookmark.commit_id,
+                pushed: remote.commit_id,
+            });
+        }
+    }
+    Ok(heads)
+}
+
+/// A bookmark whose remote branch was rewritten since it was last pushed
+struct Diverged {
+    bookmark: String,
+    remote: String,
+    /// Commit the remote branch points at now
+    remote_head: String,
+}
+
+/// Fold commits reviewers pushed to PR branches into the local changes
+///
+/// Runs right after fetching, so the force-push that follows doesn't
+/// discard them. Returns the bookmarks whose remote branch was rewritten
+/// rather than added to, which can't be folded in.
+fn pull_reviewer_commits(ctx: &mut CommandContext, heads: &[PushedHead]) -> Result<Vec<Diverged>> {
+    let mut diverged = Vec::new();
+    for head in heads {
+        let Some(remote) = ctx
+            .workspace
+            .get_remote_bookmark(&head.bookmark, &head.remote)?
+        else {
+            continue;
+        };
+        if remote.commit_id == head.pushed
+            || ctx.workspace.is_ancestor(&remote.commit_id, &head.local)?
+        {
+            continue;
+        }
+        if !ctx.workspace.is_ancestor(&head.pushed, &remote.commit_id)? {
+            diverged.push(Diverged {
+                bookmark: head.bookmark.clone(),
+                remote: head.remote.clone(),
+                remote_head: remote.commit_id,
+            });
+            continue;
+        }
+
+        ctx.workspace
+            .absorb_remote_commits(&head.bookmark, &head.local, &head.pushed, &remote.commit_id)
+            .map_err(|e| {
+                Error::RebaseFailed(format!(
+                    "{e}. Squash the commits from {}@{} into your change by hand, then run 'ryu sync' again.",
+                    head.bookmark, head.remote
+                ))
+            })?;
+        println!(
+            "{} Pulled commits pushed to {} into the local change",
+            check(),
+            head.bookmark.accent()
+        );
+    }
+    Ok(diverged)
+}
+
+/// Keep, reset or skip each diverged bookmark, as `choice` says or the user picks
+///
+/// Without a choice and without a terminal to ask on, diverged bookmarks are
+/// skipped. Returns the bookmarks to leave unpushed.
+fn resolve_divergence(
+    ctx: &mut CommandContext,
+    graph: &ChangeGraph,
+    diverged: &[Diverged],
+    choice: Option<DivergenceChoice>,
+) -> Result<Vec<String>> {
+    let mut skipped = Vec::new();
+    for branch in diverged {
+        let name = branch.bookmark.as_str();
+        eprintln!(
+            "{}",
+            format!(
+                "⚠️  {name} was rewritten on {} since it was last pushed",
+                branch.remote
+            )
+            .warn()
+        );
+        let choice = match choice {
+            Some(choice) => choice,
+            None if io::stdin().is_terminal() => {
+                let index = Select::new()
+                    .with_prompt(format!("What should sync do with {name}?"))
+                    .items(&[
+                        "Keep local and force-push",
+                        "Reset to the remote",
+                        "Skip it this time",
+                    ])
+                    .default(2)
+                    .interact()
+                    .map_err(|e| Error::Internal(format!("Failed to read choice: {e}")))?;
+                [
+                    DivergenceChoice::KeepLocal,
+                    DivergenceChoice::Reset,
+                    DivergenceChoice::Skip,
+                ][index]
+            }
+            None => DivergenceChoice::Skip,
+        };
+
+        match choice {
+            DivergenceChoice::KeepLocal => {
+                println!(
+                    "{} Keeping local {}; it will be force-pushed",
+                    arrow(),
+                    name.accent()
+                );
+            }
+            DivergenceChoice::Reset => {
+                let own_commits: Vec<String> = graph
+                    .stack
+                    .iter()
+                    .flat_map(|stack| &stack.segments)
+                    .find(|segment| segment.bookmarks.iter().any(|b| b.name == name))
+                    .map(|segment| {
+                        segment
+                            .changes
+                            .iter()
+                            .map(|c| c.commit_id.clone())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                ctx.workspace
+                    .reset_to_remote(name, &own_commits, &branch.remote_head)?;
+                println!("{} Reset {} to {}", check(), name.accent(), branch.remote);
+            }
+            DivergenceChoice::Skip => {
+                println!("{} Skipping {}; it won't be pushed", arrow(), name.accent());
+                skipped.push(branch.bookmark.clone());
+            }
+        }
+    }
+    Ok(skipped)
+}
+
+/// A tracked bookmark that's done with
+struct Finished {
+    name: String,
+    /// Why, e.g. "PR #12 merged"
+    reason: String,
+}
+
+/// Find tracked bookmarks whose PR was merged or closed, or whose branch was
+/// deleted from the remote
+///
+/// Only bookmarks in the PR cache are checked: a bookmark ryu never opened a
+/// PR for may simply not have been pushed yet. PRs are looked up
+/// concurrently, and a bookmark that can't be checked is reported and left
+/// out rather than failing the sync.
+#[allow(clippy::future_not_send)]
+async fn find_finished(ctx: &CommandContext, tracked_names: &[String]) -> Vec<Finished> {
+    let cached: Vec<(&String, u64)> = tracked_names
+        .iter()
+        .filter_map(|name| ctx.pr_cache.get(name).map(|cached| (name, cached.number)))
+        .collect();
+    let numbers: Vec<u64> = cached.iter().map(|(_, number)| *number).collect();
+    let details = get_prs_details(ctx.platform.as_ref(), &numbers).await;
+
+    let mut finished = Vec::new();
+    for ((name, number), (_, details)) in cached.into_iter().zip(details) {
+        let reason = match details {
+            Ok(details) if details.state != PrState::Open => {
+                format!("PR #{number} {}", details.state)
+            }
+            Ok(_) => {
+                let remote = ctx
+                    .tracking
+                    .get(name)
+                    .and_then(|tracked| tracked.remote.clone())
+                    .unwrap_or_else(|| ctx.remote_name.clone());
+                match ctx.workspace.get_remote_bookmark(name, &remote) {
+                    Ok(Some(_)) => continue,
+                    Ok(None) => format!("branch deleted from {remote}"),
+                    Err(e) => {
+                        warn_unchecked(name, &e);
+                        continue;
+                    }
+                }
+            }
+            Err(e) => {
+                warn_unchecked(name, &e);
+                continue;
+            }
+        };
+        finished.push(Finished {
+            name: name.clone(),
+            reason,
+        });
+    }
+    finished
+}
+
+fn warn_unchecked(name: &str, error: &Error) {
+    eprintln!(
+        "{}",
+        format!("⚠️  Couldn't check whether {name} is done with: {error}").warn()
+    );
+}
+
+/// Rebase the stack onto its base, then remove the `finished` bookmarks
+///
+/// Rebasing first turns changes that already landed on the base (e.g. by a
+/// squash merge) into empty ones, which are abandoned before their
+/// bookmarks are deleted and untracked. Only each finished bookmark's own
+/// changes are abandoned. If the rebase leaves conflicts, nothing is pruned
+/// and the sync pauses like any other (returns true); `rebase` is false when
+/// resuming it.
+fn prune_finished(
+    ctx: &mut CommandContext,
+    finished: &[Finished],
+    tracked_names: &[String],
+    rebase: bool,
+) -> Result<bool> {
+    let base = ctx
+        .tracking
+        .stack_base(tracked_names.iter().map(String::as_str))
+        .unwrap_or(&ctx.default_branch)
+        .to_string();
+    let on_trunk = base == ctx.default_branch;
+    let base_revset = if on_trunk {
+        "trunk()".to_string()
+    } else {
+        format!(
+            "remote_bookmarks(exact:{base:?}, exact:{:?})",
+            ctx.remote_name
+        )
+    };
+
+    let roots = format!("roots({base_revset}..@)");
+    if rebase && !ctx.workspace.resolve_revset(&roots)?.is_empty() {
+        if on_trunk {
+            ctx.workspace.rebase_bookmark_onto_trunk(&roots)?;
+        } else {
+            ctx.workspace
+                .rebase_bookmark_onto_remote(&roots, &base, &ctx.remote_name)?;
+        }
+    }
+
+    // A finished change that didn't land cleanly needs a look before its
+    // bookmark goes
+    let graph = build_change_graph(&ctx.workspace)?;
+    if graph.stack.is_some() {
+        let mut analysis = analyze_submission(&graph, None)?;
+        analysis
+            .segments
+            .retain(|s| tracked_names.contains(&s.bookmark.name));
+        if pause_on_conflicts(ctx, &analysis.segments, &base)? {
+            return Ok(true);
+        }
+    }
+
+    for bookmark in finished {
+        let name = bookmark.name.as_str();
+        // Before the bookmark goes, while it still names the changes
+        let abandoned = ctx.workspace.abandon_empty_segment(&base_revset, name)?;
+        if abandoned > 0 {
+            println!(
+                "{} Abandoned {abandoned} empty change(s) of {name}",
+                check()
+            );
+        }
+
+        ctx.pr_cache.remove(name);
+        ctx.tracking.untrack(name);
+        // Ignore errors - the bookmark may already be gone
+        let _ = ctx.workspace.delete_bookmark(name);
+        println!(
+            "{} Pruned {} {}",
+            check(),
+            name.accent(),
+            format!("({})", bookmark.reason).muted()
+        );
+    }
+
+    save_tracking(&ctx.workspace_root, &ctx.tracking)?;
+    // Best effort - the cache is rebuilt by the next submit
+    let _ = save_pr_cache(&ctx.workspace_root, &ctx.pr_cache);
+    Ok(false)
+}
+
+/// List the finished bookmarks a sync leaves in place
+fn print_finished(finished: &[Finished], dry_run_prune: bool) {
+    if dry_run_prune {
+        println!("Would prune:");
+    } else {
+        println!(
+            "{}",
+            "⚠️  These bookmarks are done with; run 'ryu sync --prune' to remove them:".warn()
+        );
+    }
+    for bookmark in finished {
+        println!(
+            "  {} {}",
+            bookmark.name.accent(),
+            format!("({})", bookmark.reason).muted()
+        );
+    }
+}
+
 /// Print sync preview for --confirm
 fn print_sync_preview(plan: &SubmissionPlan) {
     println!("{}:", "Sync plan".emphasis());
@@ -6,6 +6,7 @@ use chrono::Utc;
 use dialoguer::MultiSelect;
 use jj_ryu::graph::build_change_graph;
 use jj_ryu::repo::JjWorkspace;
+use jj_ryu::submit::branch_pattern_matches;
 use jj_ryu::tracking::{TrackedBookmark, load_tracking, save_tracking};
 use std::io::{self, IsTerminal};
 use std::path::Path;
@@ -16,8 +17,10 @@ pub struct TrackOptions {
     pub all: bool,
     /// Re-track already-tracked bookmarks (update remote)
     pub force: bool,
-    /// Associate with specific remote
+    /// Remote to push these bookmarks to
     pub remote: Option<String>,
+    /// Branch the stack targets instead of the default branch
+    pub base: Option<String>,
 }
 
 /// Run the track command.
@@ -98,24 +101,46 @@ pub async fn run_track(path: &Path, bookmarks: &[String], options: TrackOptions)
             return Ok(());
         }
     } else {
-        // Validate specified bookmarks exist in stack
-        let mut to_track = Vec::new();
+        // Validate specified bookmarks exist in stack; `*` patterns track
+        // every matching bookmark
+        let mut to_track: Vec<&str> = Vec::new();
         for name in bookmarks {
-            if !available_bookmarks.contains(&name.as_str()) {
-                eprintln!(
-                    "{}",
-                    format!("Bookmark '{name}' not found in trunk()..@").error()
-                );
+            let matches: Vec<&str> = if name.contains('*') {
+                available_bookmarks
+                    .iter()
+                    .filter(|&&b| branch_pattern_matches(name, b))
+                    .copied()
+                    .collect()
+            } else {
+                available_bookmarks
+                    .iter()
+                    .filter(|&&b| b == name)
+                    .copied()
+                    .collect()
+            };
+            if matches.is_empty() {
+                let message = if name.contains('*') {
+                    format!("No bookmarks matching '{name}' in trunk()..@")
+                } else {
+                    format!("Bookmark '{name}' not found in trunk()..@")
+                };
+                eprintln!("{}", message.error());
                 continue;
             }
-            if state.is_tracked(name) && !options.force {
-                eprintln!(
-                    "{}",
-                    format!("Bookmark '{name}' already tracked (use --force to re-track)").muted()
-                );
-                continue;
+            for name in matches {
+                if to_track.contains(&name) {
+                    continue;
+                }
+                if state.is_tracked(name) && !options.force {
+                    eprintln!(
+                        "{}",
+                        format!("Bookmark '{name}' already tracked (use --force to re-track)")
+                            .muted()
+                    );
+                    continue;
+                }
+                to_track.push(name);
             }
-            to_track.push(name.as_str());
         }
         to_track
     };
@@ -125,6 +150,12 @@ pub async fn run_track(path: &Path, bookmarks: &[String], options: TrackOptions)
         return Ok(());
     }
 
+    // Without --base, join the base the rest of the stack already declares
+    let base = options
+        .base
+        .clone()
+        .or_else(|| state.stack_base(available_bookmarks.iter().copied()).map(String::from));
+
     // Track the bookmarks
     let mut tracked_names = Vec::new();
     for name in &bookmarks_to_track {
@@ -137,6 +168,7 @@ pub async fn run_track(path: &Path, bookmarks: &[String], options: TrackOptions)
             name: (*name).to_string(),
             change_id,
             remote: options.remote.clone(),
+            base: base.clone(),
             tracked_at: Utc::now(),
         };
 
@@ -164,6 +196,9 @@ pub async fn run_track(path: &Path, bookmarks: &[String], options: TrackOptions)
     for name in &tracked_names {
         eprintln!("  {} {}", check(), name.accent());
     }
+    if let Some(base) = &base {
+        eprintln!("{}", format!("Stack targets {base}").muted());
+    }
 
     Ok(())
 }
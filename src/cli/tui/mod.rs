@@ -0,0 +1,393 @@
+//! `ryu ui` command - interactive terminal UI for the stack
+//!
+//! Shows each bookmark of the stack with its PR, CI and review state, and
+//! runs actions on the selected bookmark with single keys. Submit and merge
+//! suspend the UI and run the same code as `ryu submit` and `ryu merge`;
+//! quicker actions call the platform directly and report in the status line.
+
+mod view;
+
+use crate::cli::context::CommandContext;
+use crate::cli::merge::fetch_all_pr_info;
+use crate::cli::web::open_in_browser;
+use crate::cli::{MergeOptions, SubmitOptions, run_merge, run_submit};
+use jj_ryu::error::{Error, Result};
+use jj_ryu::graph::build_change_graph;
+use jj_ryu::merge::PrInfo;
+use jj_ryu::submit::{analyze_submission, get_base_branch};
+use jj_ryu::tracking::{load_pr_cache, save_pr_cache};
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+/// A bookmark of the stack as shown in the UI
+pub struct Row {
+    /// Bookmark name
+    pub bookmark: String,
+    /// Whether the bookmark is tracked
+    pub tracked: bool,
+    /// Branch the bookmark's PR should be based on
+    pub expected_base: String,
+    /// The bookmark's open PR with its readiness, if it has one
+    pub pr: Option<PrInfo>,
+}
+
+/// State of the UI
+pub struct App {
+    /// Bookmarks from trunk to the top of the stack
+    pub rows: Vec<Row>,
+    /// Index into `rows` of the selected bookmark
+    pub selected: usize,
+    /// Result of the last action
+    pub status: String,
+}
+
+impl App {
+    /// The selected bookmark, if the stack has any
+    fn selected_row(&self) -> Option<&Row> {
+        self.rows.get(self.selected)
+    }
+}
+
+/// What the event loop should do after a key press
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    /// Keep going
+    None,
+    /// Leave the UI
+    Quit,
+    /// Reload the stack from the repo and platform
+    Refresh,
+    /// Suspend the UI and submit the stack up to the selected bookmark
+    Submit,
+    /// Suspend the UI and merge the stack
+    Merge,
+    /// Publish the selected bookmark's draft PR
+    Publish,
+    /// Retarget the selected bookmark's PR
+    Retarget,
+    /// Open the selected bookmark's PR in the browser
+    Open,
+}
+
+/// Run the UI command
+#[allow(clippy::future_not_send)]
+pub async fn run_ui(path: &Path, remote: Option<&str>) -> Result<()> {
+    let ctx = CommandContext::new(path, remote).await?;
+    let mut app = App {
+        rows: Vec::new(),
+        selected: 0,
+        status: String::new(),
+    };
+    load(&ctx, &mut app).await?;
+    if app.rows.is_empty() {
+        return Err(Error::NoStack(
+            "No stack found between trunk and working copy".to_string(),
+        ));
+    }
+    // Start on the top of the stack, like `ryu submit`
+    app.selected = app.rows.len() - 1;
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &ctx, &mut app, path, remote).await;
+    ratatui::restore();
+    result
+}
+
+/// Draw the UI and handle key presses until the user quits
+#[allow(clippy::future_not_send)]
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    ctx: &CommandContext,
+    app: &mut App,
+    path: &Path,
+    remote: Option<&str>,
+) -> Result<()> {
+    loop {
+        terminal.draw(|frame| view::draw(frame, app))?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        let action = handle_key(app, key.code);
+        match action {
+            Action::None => {}
+            Action::Quit => return Ok(()),
+            Action::Refresh => {
+                if let Err(e) = load(ctx, app).await {
+                    app.status = format!("Refresh failed: {e}");
+                }
+            }
+            Action::Publish | Action::Retarget => {
+                let Some(row) = app.selected_row() else {
+                    continue;
+                };
+                app.status = if action == Action::Publish {
+                    publish(ctx, row).await
+                } else {
+                    retarget(ctx, row).await
+                };
+                if let Err(e) = load(ctx, app).await {
+                    app.status = format!("Refresh failed: {e}");
+                }
+            }
+            Action::Open => {
+                if let Some(row) = app.selected_row() {
+                    app.status = open(row);
+                }
+            }
+            Action::Submit => {
+                let Some(row) = app.selected_row() else {
+                    continue;
+                };
+                let bookmark = row.bookmark.clone();
+                ratatui::restore();
+                let result =
+                    run_submit(path, Some(&bookmark), remote, SubmitOptions::default()).await;
+                app.status = outcome("Submit", result);
+                pause()?;
+                *terminal = ratatui::init();
+                if let Err(e) = load(ctx, app).await {
+                    app.status = format!("Refresh failed: {e}");
+                }
+            }
+            Action::Merge => {
+                ratatui::restore();
+                let result = run_merge(path, remote, MergeOptions::default()).await;
+                app.status = outcome("Merge", result);
+                pause()?;
+                *terminal = ratatui::init();
+                if let Err(e) = load(ctx, app).await {
+                    app.status = format!("Refresh failed: {e}");
+                }
+            }
+        }
+    }
+}
+
+/// Update the selection or pick the action for a key press
+///
+/// Keys that act on the selected bookmark do nothing but explain why when
+/// the stack is empty, e.g. after merging all of it from the UI.
+fn handle_key(app: &mut App, code: KeyCode) -> Action {
+    let action = match code {
+        KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.selected = (app.selected + 1).min(app.rows.len().saturating_sub(1));
+            return Action::None;
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            app.selected = app.selected.saturating_sub(1);
+            return Action::None;
+        }
+        KeyCode::Char('g') => return Action::Refresh,
+        KeyCode::Char('m') => return Action::Merge,
+        KeyCode::Char('s') => Action::Submit,
+        KeyCode::Char('p') => Action::Publish,
+        KeyCode::Char('r') => Action::Retarget,
+        KeyCode::Char('o') => Action::Open,
+        _ => return Action::None,
+    };
+
+    if app.selected_row().is_none() {
+        app.status = "No stack between trunk and working copy; g to refresh, q to quit".to_string();
+        return Action::None;
+    }
+    action
+}
+
+/// Load the stack and its PRs into `app`, keeping the selection in range
+#[allow(clippy::future_not_send)]
+async fn load(ctx: &CommandContext, app: &mut App) -> Result<()> {
+    let graph = build_change_graph(&ctx.workspace)?;
+    if graph.stack.is_none() {
+        app.rows.clear();
+        app.selected = 0;
+        return Ok(());
+    }
+    let analysis = analyze_submission(&graph, None)?;
+    let base = ctx.stack_base(&analysis);
+
+    let segments: Vec<_> = analysis.segments.iter().collect();
+    let mut pr_info = fetch_all_pr_info(&segments, ctx.platform.as_ref(), None).await?;
+
+    app.rows = analysis
+        .segments
+        .iter()
+        .map(|segment| {
+            let name = &segment.bookmark.name;
+            Ok(Row {
+                bookmark: name.clone(),
+                tracked: ctx.tracking.is_tracked(name),
+                expected_base: get_base_branch(name, &analysis.segments, &base)?,
+                pr: pr_info.remove(name),
+            })
+        })
+        .collect::<Result<_>>()?;
+    app.selected = app.selected.min(app.rows.len().saturating_sub(1));
+    Ok(())
+}
+
+/// Mark the selected bookmark's draft PR as ready for review
+#[allow(clippy::future_not_send)]
+async fn publish(ctx: &CommandContext, row: &Row) -> String {
+    let Some(info) = &row.pr else {
+        return format!("{} has no PR", row.bookmark);
+    };
+    if !info.details.is_draft {
+        return format!("PR #{} is not a draft", info.details.number);
+    }
+    match ctx.platform.publish_pr(info.details.number).await {
+        Ok(pr) => {
+            // Reload the cache, which submits from the UI have changed since
+            // the context was opened. Best effort - the PR is already published
+            if let Ok(mut pr_cache) = load_pr_cache(&ctx.workspace_root) {
+                pr_cache.upsert(&row.bookmark, &pr, &ctx.remote_name);
+                let _ = save_pr_cache(&ctx.workspace_root, &pr_cache);
+            }
+            format!("Published PR #{}", pr.number)
+        }
+        Err(e) => format!("Publish failed: {e}"),
+    }
+}
+
+/// Point the selected bookmark's PR at the base its place in the stack calls for
+#[allow(clippy::future_not_send)]
+async fn retarget(ctx: &CommandContext, row: &Row) -> String {
+    let Some(info) = &row.pr else {
+        return format!("{} has no PR", row.bookmark);
+    };
+    if info.details.base_ref == row.expected_base {
+        return format!(
+            "PR #{} is already based on {}",
+            info.details.number, row.expected_base
+        );
+    }
+    match ctx
+        .platform
+        .update_pr_base(info.details.number, &row.expected_base)
+        .await
+    {
+        Ok(pr) => format!("Retargeted PR #{} to {}", pr.number, pr.base_ref),
+        Err(e) => format!("Retarget failed: {e}"),
+    }
+}
+
+/// Open the selected bookmark's PR in the browser
+fn open(row: &Row) -> String {
+    let Some(info) = &row.pr else {
+        return format!("{} has no PR", row.bookmark);
+    };
+    if open_in_browser(&info.details.html_url) {
+        format!("Opened PR #{}", info.details.number)
+    } else {
+        info.details.html_url.clone()
+    }
+}
+
+/// Status line for the result of a suspended action
+fn outcome(action: &str, result: Result<()>) -> String {
+    match result {
+        Ok(()) => format!("{action} finished"),
+        Err(e) => format!("{action} failed: {e}"),
+    }
+}
+
+/// Wait for Enter before returning to the UI, so output can be read
+fn pause() -> Result<()> {
+    print!("\nPress Enter to return to ryu ui...");
+    io::stdout().flush()?;
+    io::stdin().lock().read_line(&mut String::new())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn app(bookmarks: &[&str], selected: usize) -> App {
+        App {
+            rows: bookmarks
+                .iter()
+                .map(|name| Row {
+                    bookmark: (*name).to_string(),
+                    tracked: true,
+                    expected_base: "main".to_string(),
+                    pr: None,
+                })
+                .collect(),
+            selected,
+            status: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_navigation_stays_in_range() {
+        let mut app = app(&["a", "b", "c"], 1);
+
+        assert_eq!(handle_key(&mut app, KeyCode::Up), Action::None);
+        assert_eq!(app.selected, 2);
+        assert_eq!(handle_key(&mut app, KeyCode::Char('k')), Action::None);
+        assert_eq!(app.selected, 2);
+
+        handle_key(&mut app, KeyCode::Down);
+        handle_key(&mut app, KeyCode::Char('j'));
+        handle_key(&mut app, KeyCode::Down);
+        assert_eq!(app.selected, 0);
+    }
+
+    #[test]
+    fn test_keys_map_to_actions() {
+        let mut app = app(&["a"], 0);
+
+        assert_eq!(handle_key(&mut app, KeyCode::Char('q')), Action::Quit);
+        assert_eq!(handle_key(&mut app, KeyCode::Esc), Action::Quit);
+        assert_eq!(handle_key(&mut app, KeyCode::Char('g')), Action::Refresh);
+        assert_eq!(handle_key(&mut app, KeyCode::Char('s')), Action::Submit);
+        assert_eq!(handle_key(&mut app, KeyCode::Char('m')), Action::Merge);
+        assert_eq!(handle_key(&mut app, KeyCode::Char('p')), Action::Publish);
+        assert_eq!(handle_key(&mut app, KeyCode::Char('r')), Action::Retarget);
+        assert_eq!(handle_key(&mut app, KeyCode::Char('o')), Action::Open);
+        assert_eq!(handle_key(&mut app, KeyCode::Char('x')), Action::None);
+        assert!(app.status.is_empty());
+    }
+
+    #[test]
+    fn test_empty_stack_ignores_bookmark_actions() {
+        // The whole stack was merged from the UI
+        let mut app = app(&[], 0);
+
+        for key in ['s', 'p', 'r', 'o'] {
+            app.status.clear();
+            assert_eq!(handle_key(&mut app, KeyCode::Char(key)), Action::None);
+            assert!(app.status.contains("No stack"), "{key}: {}", app.status);
+        }
+
+        handle_key(&mut app, KeyCode::Up);
+        handle_key(&mut app, KeyCode::Down);
+        assert_eq!(app.selected, 0);
+        assert_eq!(handle_key(&mut app, KeyCode::Char('g')), Action::Refresh);
+        assert_eq!(handle_key(&mut app, KeyCode::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    fn test_open_without_pr() {
+        let app = app(&["a"], 0);
+        assert_eq!(open(&app.rows[0]), "a has no PR");
+    }
+
+    #[test]
+    fn test_draw_empty_stack() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+        let app = app(&[], 0);
+
+        terminal.draw(|frame| view::draw(frame, &app)).unwrap();
+    }
+}
@@ -0,0 +1,117 @@
+//! Rendering for `ryu ui`
+
+use super::{App, Row};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Paragraph, Row as TableRow, Table, TableState};
+
+/// Key bindings shown at the bottom of the screen
+const HELP: &str =
+    "↑/↓ select  s submit  p publish  m merge  o open  r retarget  g refresh  q quit";
+
+/// Draw the stack table, status line and key help
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [table_area, status_area, help_area] = Layout::vertical([
+        Constraint::Min(1),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    // Top of the stack first, like `jj log`
+    let rows: Vec<TableRow> = app.rows.iter().rev().map(table_row).collect();
+    let header = TableRow::new(["Bookmark", "PR", "CI", "Review", "Base"])
+        .style(Style::new().add_modifier(Modifier::BOLD));
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(30),
+            Constraint::Length(14),
+            Constraint::Percentage(20),
+            Constraint::Percentage(20),
+            Constraint::Percentage(30),
+        ],
+    )
+    .header(header)
+    .block(Block::bordered().title(" ryu "))
+    .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+    let mut state =
+        TableState::default().with_selected(Some(app.rows.len().saturating_sub(1) - app.selected));
+    frame.render_stateful_widget(table, table_area, &mut state);
+
+    frame.render_widget(Paragraph::new(app.status.as_str()), status_area);
+    frame.render_widget(Paragraph::new(HELP.dark_gray()), help_area);
+}
+
+/// One bookmark's line in the table
+fn table_row(row: &Row) -> TableRow<'_> {
+    let name = if row.tracked {
+        Span::raw(row.bookmark.as_str())
+    } else {
+        Span::raw(format!("{} (untracked)", row.bookmark)).dark_gray()
+    };
+
+    let Some(info) = &row.pr else {
+        return TableRow::new([
+            Cell::from(name),
+            Cell::from("no PR".dark_gray()),
+            Cell::default(),
+            Cell::default(),
+            Cell::from(row.expected_base.as_str()),
+        ]);
+    };
+    let readiness = &info.readiness;
+
+    let pr = if info.details.is_draft {
+        Line::from(vec![
+            Span::raw(format!("#{} ", info.details.number)),
+            "draft".dark_gray(),
+        ])
+    } else {
+        Line::from(format!("#{}", info.details.number))
+    };
+
+    let failing = readiness.failing_checks();
+    let pending = readiness.pending_checks();
+    let ci = if !failing.is_empty() {
+        Span::styled(format!("✗ {} failing", failing.len()), Color::Red)
+    } else if !pending.is_empty() {
+        Span::styled(format!("… {} pending", pending.len()), Color::Yellow)
+    } else if readiness.checks.is_empty() {
+        "none".dark_gray()
+    } else {
+        Span::styled("✓ passed", Color::Green)
+    };
+
+    let review = match (
+        readiness.is_approved,
+        readiness.approvals_received,
+        readiness.approvals_required,
+    ) {
+        (true, _, _) => Span::styled("✓ approved", Color::Green),
+        (false, Some(received), Some(required)) => {
+            Span::styled(format!("{received}/{required} approvals"), Color::Yellow)
+        }
+        (false, _, _) => Span::styled("not approved", Color::Yellow),
+    };
+
+    let base = if info.details.base_ref == row.expected_base {
+        Span::raw(info.details.base_ref.as_str())
+    } else {
+        Span::styled(
+            format!("{} → {}", info.details.base_ref, row.expected_base),
+            Color::Yellow,
+        )
+    };
+
+    TableRow::new([
+        Cell::from(name),
+        Cell::from(pr),
+        Cell::from(ci),
+        Cell::from(review),
+        Cell::from(base),
+    ])
+}
@@ -0,0 +1,206 @@
+//! `ryu undo` command - undo the last command that changed the repo
+
+use crate::cli::context::CommandContext;
+use crate::cli::prompt::confirm;
+use crate::cli::style::{Stylize, check, cross};
+use anstream::{eprintln, println};
+use jj_ryu::error::{Error, Result};
+use jj_ryu::repo::JjWorkspace;
+use jj_ryu::tracking::{UndoRecord, UndoStep, clear_undo_record, load_undo_record, save_tracking};
+use std::path::Path;
+
+/// Options for the undo command
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UndoOptions {
+    /// Only show what would be undone
+    pub dry_run: bool,
+    /// Restore without asking when the repo changed after the command
+    pub yes: bool,
+}
+
+/// Run the undo command
+///
+/// Reverts what the last submit, sync, restack, merge, clean, abandon or
+/// backport did: PRs it opened are closed, PRs it closed are reopened,
+/// published drafts go back to draft, changed bases and pushed branches are
+/// restored, and then the repo is restored to the jj operation the command
+/// started from, along with ryu's tracking state. Merges can't be undone and
+/// are only reported.
+///
+/// If the repo was changed after the command (the current jj operation isn't
+/// the one the command left), restoring would throw those changes away too,
+/// so undo asks first.
+pub async fn run_undo(path: &Path, options: UndoOptions) -> Result<()> {
+    let workspace = JjWorkspace::open(path)?;
+    let workspace_root = workspace.workspace_root().to_path_buf();
+    let Some(record) = load_undo_record(&workspace_root)? else {
+        println!("{}", "Nothing to undo".muted());
+        return Ok(());
+    };
+    let current_op = workspace.current_operation_id()?;
+    drop(workspace);
+
+    print_undo_plan(&record);
+    let changed_since = record.finished_operation_id.as_deref() != Some(current_op.as_str());
+    if changed_since {
+        println!();
+        println!(
+            "{}",
+            format!(
+                "⚠️  The repo changed after 'ryu {}'; restoring also discards those changes",
+                record.command
+            )
+            .warn()
+        );
+    }
+    if options.dry_run {
+        println!();
+        println!("{}", "Dry run complete".muted());
+        return Ok(());
+    }
+    if changed_since && !confirm("Undo anyway?", options.yes)? {
+        println!("{}", "Aborted".muted());
+        return Ok(());
+    }
+    println!();
+
+    // Platform and remote first, while the repo still knows where the
+    // pushed branches are
+    let mut errors = Vec::new();
+    if record.has_remote_changes() {
+        let mut ctx = CommandContext::new(path, Some(&record.remote)).await?;
+        revert_remote_changes(&mut ctx, &record, &mut errors).await;
+    }
+
+    JjWorkspace::open(path)?.restore_operation(&record.operation_id)?;
+    save_tracking(&workspace_root, &record.tracking)?;
+    clear_undo_record(&workspace_root)?;
+
+    println!(
+        "{} Restored the repo to before 'ryu {}'",
+        check(),
+        record.command
+    );
+    println!(
+        "{}",
+        format!("To get the repo back, run 'jj op restore {current_op}'").muted()
+    );
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        eprintln!("{} Some changes could not be undone:", cross());
+        for err in &errors {
+            eprintln!("  {err}");
+        }
+        Err(Error::Platform(format!(
+            "{} change(s) could not be undone",
+            errors.len()
+        )))
+    }
+}
+
+/// Print what undoing `record` will do
+fn print_undo_plan(record: &UndoRecord) {
+    println!(
+        "{} {} {}",
+        "Undoing".emphasis(),
+        format!("ryu {}", record.command).accent(),
+        format!("(run {})", record.recorded_at.format("%Y-%m-%d %H:%M")).muted()
+    );
+    for step in record.undo_steps() {
+        match step {
+            UndoStep::ClosePr(number) => println!("  close PR #{number}"),
+            UndoStep::UnpublishPr(number) => println!("  convert PR #{number} back to a draft"),
+            UndoStep::RestoreBase(change) => println!(
+                "  restore base of PR #{} to {}",
+                change.pr, change.previous_base
+            ),
+            UndoStep::RestorePush(push) => match &push.previous {
+                Some(commit) => println!(
+                    "  move {} on {} back to {}",
+                    push.bookmark,
+                    push.remote,
+                    &commit[..commit.len().min(12)]
+                ),
+                None => println!("  delete {} on {}", push.bookmark, push.remote),
+            },
+            UndoStep::ReopenPr(number) => println!("  reopen PR #{number}"),
+        }
+    }
+    println!(
+        "  restore jj operation {}",
+        &record.operation_id[..record.operation_id.len().min(12)]
+    );
+    if !record.merged_bookmarks.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "⚠️  Merged PRs can't be undone: {}",
+                record.merged_bookmarks.join(", ")
+            )
+            .warn()
+        );
+    }
+}
+
+/// Revert the platform and remote changes in `record`, collecting failures
+///
+/// Takes the steps in [`UndoRecord::undo_steps`] order.
+#[allow(clippy::future_not_send)]
+async fn revert_remote_changes(
+    ctx: &mut CommandContext,
+    record: &UndoRecord,
+    errors: &mut Vec<String>,
+) {
+    for step in record.undo_steps() {
+        match step {
+            UndoStep::ClosePr(number) => match ctx.platform.close_pr(number).await {
+                Ok(()) => println!("{} Closed PR #{number}", check()),
+                Err(e) => errors.push(format!("Failed to close PR #{number}: {e}")),
+            },
+            UndoStep::UnpublishPr(number) => match ctx.platform.unpublish_pr(number).await {
+                Ok(_) => println!("{} Converted PR #{number} back to a draft", check()),
+                Err(e) => errors.push(format!("Failed to convert PR #{number} to a draft: {e}")),
+            },
+            UndoStep::RestoreBase(change) => match ctx
+                .platform
+                .update_pr_base(change.pr, &change.previous_base)
+                .await
+            {
+                Ok(_) => println!(
+                    "{} Restored base of PR #{} to {}",
+                    check(),
+                    change.pr,
+                    change.previous_base
+                ),
+                Err(e) => errors.push(format!("Failed to restore base of PR #{}: {e}", change.pr)),
+            },
+            UndoStep::RestorePush(push) => match ctx.workspace.restore_remote_bookmark(
+                &push.bookmark,
+                &push.remote,
+                push.previous.as_deref(),
+            ) {
+                Ok(()) => println!(
+                    "{} {} {} on {}",
+                    check(),
+                    if push.previous.is_some() {
+                        "Restored"
+                    } else {
+                        "Deleted"
+                    },
+                    push.bookmark.accent(),
+                    push.remote
+                ),
+                Err(e) => errors.push(format!(
+                    "Failed to restore {} on {}: {e}",
+                    push.bookmark, push.remote
+                )),
+            },
+            UndoStep::ReopenPr(number) => match ctx.platform.reopen_pr(number).await {
+                Ok(_) => println!("{} Reopened PR #{number}", check()),
+                Err(e) => errors.push(format!("Failed to reopen PR #{number}: {e}")),
+            },
+        }
+    }
+}
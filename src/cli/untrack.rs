@@ -1,10 +1,11 @@
 //! `ryu untrack` command - remove bookmarks from tracking
 
+use crate::cli::context::CommandContext;
 use crate::cli::style::{Stylize, check};
 use anyhow::Result;
 use dialoguer::MultiSelect;
 use jj_ryu::repo::JjWorkspace;
-use jj_ryu::tracking::{load_pr_cache, load_tracking, save_tracking};
+use jj_ryu::tracking::{load_pr_cache, load_tracking, save_pr_cache, save_tracking};
 use std::io::{self, IsTerminal};
 use std::path::Path;
 
@@ -12,9 +13,15 @@ use std::path::Path;
 pub struct UntrackOptions {
     /// Untrack all tracked bookmarks
     pub all: bool,
+    /// Also remove the bookmarks' entries from the PR cache
+    pub forget_pr: bool,
 }
 
 /// Run the untrack command.
+///
+/// Bookmarks with a cached PR are looked up on the platform, with a warning
+/// for each PR that is still open. If the platform can't be reached, the
+/// cached PRs are reported as possibly open instead.
 pub async fn run_untrack(path: &Path, bookmarks: &[String], options: UntrackOptions) -> Result<()> {
     let workspace = JjWorkspace::open(path)?;
     let workspace_root = workspace.workspace_root().to_path_buf();
@@ -28,7 +35,7 @@ pub async fn run_untrack(path: &Path, bookmarks: &[String], options: UntrackOpti
     }
 
     // Load PR cache for notes about open PRs
-    let pr_cache = load_pr_cache(&workspace_root)?;
+    let mut pr_cache = load_pr_cache(&workspace_root)?;
 
     // Determine which bookmarks to untrack
     let bookmarks_to_untrack: Vec<String> = if options.all {
@@ -85,19 +92,24 @@ pub async fn run_untrack(path: &Path, bookmarks: &[String], options: UntrackOpti
 
     // Untrack the bookmarks
     let mut untracked_names = Vec::new();
-    let mut pr_notes = Vec::new();
+    let mut cached_prs = Vec::new();
     for name in &bookmarks_to_untrack {
         if state.untrack(name) {
             untracked_names.push(name.clone());
-            // Note any open PRs
             if let Some(cached) = pr_cache.get(name) {
-                pr_notes.push(format!("PR #{} remains open", cached.number));
+                cached_prs.push((name.clone(), cached.number));
             }
         }
     }
 
     // Save state
     save_tracking(&workspace_root, &state)?;
+    if options.forget_pr {
+        for name in &untracked_names {
+            pr_cache.remove(name);
+        }
+        save_pr_cache(&workspace_root, &pr_cache)?;
+    }
 
     // Print summary
     if untracked_names.len() == 1 {
@@ -109,20 +121,50 @@ pub async fn run_untrack(path: &Path, bookmarks: &[String], options: UntrackOpti
         eprintln!("  {} {}", check(), name.accent());
     }
 
-    // Show PR notes
-    if !pr_notes.is_empty() {
+    // Warn about PRs ryu no longer manages
+    if !cached_prs.is_empty() {
         eprintln!();
-        for note in &pr_notes {
-            eprintln!(
-                "{}",
-                format!("Note: {note}. Close manually if needed.").muted()
-            );
-        }
+        warn_open_prs(path, &cached_prs).await;
     }
 
     Ok(())
 }
 
+/// Warn about the untracked bookmarks' PRs that are still open
+///
+/// Falls back to the cached PR numbers if the platform can't be reached.
+async fn warn_open_prs(path: &Path, cached_prs: &[(String, u64)]) {
+    match CommandContext::new(path, None).await {
+        Ok(ctx) => {
+            for (name, _) in cached_prs {
+                if let Ok(Some(pr)) = ctx.platform.find_existing_pr(name).await {
+                    eprintln!(
+                        "{}",
+                        format!(
+                            "Warning: PR #{} for {name} is still open. \
+                             Close it manually if needed.",
+                            pr.number
+                        )
+                        .warn()
+                    );
+                }
+            }
+        }
+        Err(_) => {
+            for (name, number) in cached_prs {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "Note: PR #{number} for {name} may still be open. \
+                         Close manually if needed."
+                    )
+                    .muted()
+                );
+            }
+        }
+    }
+}
+
 /// Interactive bookmark selection using dialoguer.
 fn interactive_select(bookmarks: &[String]) -> Result<Vec<String>> {
     let selections = MultiSelect::new()
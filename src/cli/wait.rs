@@ -0,0 +1,405 @@
+//! `ryu wait` command - block until the stack's PRs are ready to merge
+
+use crate::cli::context::CommandContext;
+use crate::cli::merge::fetch_all_pr_info;
+use crate::cli::style::{Stylize, check, cross, spinner_style};
+use anstream::println;
+use indicatif::ProgressBar;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::graph::build_change_graph;
+use jj_ryu::merge::PrInfo;
+use jj_ryu::platform::PlatformService;
+use jj_ryu::submit::analyze_submission;
+use jj_ryu::types::NarrowedBookmarkSegment;
+use std::path::Path;
+use std::time::Duration;
+
+/// Options for the wait command
+#[derive(Debug, Clone, Copy)]
+pub struct WaitOptions {
+    /// Time between readiness checks
+    pub interval: Duration,
+    /// Give up after this long (None = wait indefinitely)
+    pub timeout: Option<Duration>,
+}
+
+/// Run the wait command
+///
+/// Polls the merge readiness of every tracked PR in the stack until all of
+/// them are approved, green, published and free of conflicts, so scripts can
+/// run `ryu wait && ryu merge`. Fails right away when a PR is missing, a
+/// required check failed or a PR has conflicts, since waiting won't fix
+/// those, and when `timeout` passes.
+pub async fn run_wait(path: &Path, remote: Option<&str>, options: WaitOptions) -> Result<()> {
+    let ctx = CommandContext::new(path, remote).await?;
+
+    let tracked_names: Vec<String> = ctx.tracked_names().into_iter().map(String::from).collect();
+    if tracked_names.is_empty() {
+        return Err(Error::Tracking(
+            "No bookmarks tracked. Run 'ryu track' first.".to_string(),
+        ));
+    }
+
+    let graph = build_change_graph(&ctx.workspace)?;
+    if graph.stack.is_none() {
+        println!("{}", "No stack found between trunk and working copy.".muted());
+        return Ok(());
+    }
+    let analysis = analyze_submission(&graph, None)?;
+    let tracked_segments: Vec<&NarrowedBookmarkSegment> = analysis
+        .segments
+        .iter()
+        .filter(|s| tracked_names.contains(&s.bookmark.name))
+        .collect();
+    if tracked_segments.is_empty() {
+        println!("{}", "No tracked bookmarks in stack.".muted());
+        return Ok(());
+    }
+
+    let deadline = options
+        .timeout
+        .map(|timeout| tokio::time::Instant::now() + timeout);
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(spinner_style());
+    spinner.set_message(format!(
+        "Checking {} PR(s)...",
+        tracked_segments.len()
+    ));
+    spinner.enable_steady_tick(Duration::from_millis(80));
+
+    loop {
+        let (ready, waiting_on) = match poll_stack(&tracked_segments, ctx.platform.as_ref()).await {
+            Ok(StackPoll::Ready) => {
+                spinner.finish_with_message(format!(
+                    "{} All {} PR(s) are ready to merge",
+                    check(),
+                    tracked_segments.len()
+                ));
+                return Ok(());
+            }
+            Ok(StackPoll::Waiting { ready, waiting_on }) => (ready, waiting_on),
+            Err(Unready::Error(e)) => {
+                spinner.finish_and_clear();
+                return Err(e);
+            }
+            Err(Unready::Stuck(info)) => {
+                spinner.finish_and_clear();
+                println!(
+                    "{} PR #{} ({})",
+                    cross(),
+                    info.details.number,
+                    info.bookmark.accent()
+                );
+                for reason in &info.readiness.blocking_reasons {
+                    println!("    - {}", reason.muted());
+                }
+                return Err(Error::Platform(format!(
+                    "PR #{} can't become mergeable without changes",
+                    info.details.number
+                )));
+            }
+        };
+
+        let now = tokio::time::Instant::now();
+        if deadline.is_some_and(|deadline| now >= deadline) {
+            spinner.finish_and_clear();
+            println!(
+                "{} {ready}/{} ready; still waiting on {waiting_on}",
+                cross(),
+                tracked_segments.len()
+            );
+            return Err(Error::Platform(
+                "timed out waiting for the stack to become mergeable".to_string(),
+            ));
+        }
+
+        spinner.set_message(format!(
+            "{ready}/{} ready; waiting on {}",
+            tracked_segments.len(),
+            waiting_on.muted()
+        ));
+        let sleep = deadline.map_or(options.interval, |deadline| {
+            options.interval.min(deadline - now)
+        });
+        tokio::time::sleep(sleep).await;
+    }
+}
+
+/// Outcome of one readiness check over the stack
+#[derive(Debug)]
+enum StackPoll {
+    /// Every PR is ready to merge
+    Ready,
+    /// `ready` PRs from the bottom are ready; the next one is waiting
+    Waiting { ready: usize, waiting_on: String },
+}
+
+/// Why polling should stop without the stack being ready
+#[derive(Debug)]
+enum Unready {
+    /// A lookup failed or a PR is missing
+    Error(Error),
+    /// A PR can't become mergeable without changes
+    Stuck(Box<PrInfo>),
+}
+
+/// Check every PR in the stack once, from the bottom
+///
+/// Each call fetches fresh readiness from the platform, so repeated calls see
+/// pending CI finish.
+async fn poll_stack(
+    segments: &[&NarrowedBookmarkSegment],
+    platform: &dyn PlatformService,
+) -> std::result::Result<StackPoll, Unready> {
+    let mut pr_info = fetch_all_pr_info(segments, platform, None)
+        .await
+        .map_err(Unready::Error)?;
+
+    for (ready, segment) in segments.iter().enumerate() {
+        let name = &segment.bookmark.name;
+        let Some(info) = pr_info.remove(name) else {
+            return Err(Unready::Error(Error::Platform(format!(
+                "no open PR for {name}; run 'ryu submit' first"
+            ))));
+        };
+        let readiness = &info.readiness;
+
+        if !readiness.failing_checks().is_empty() || readiness.is_mergeable == Some(false) {
+            return Err(Unready::Stuck(Box::new(info)));
+        }
+
+        if readiness.is_blocked() {
+            let reason = readiness
+                .blocking_reasons
+                .first()
+                .cloned()
+                .unwrap_or_else(|| "not ready".to_string());
+            return Ok(StackPoll::Waiting {
+                ready,
+                waiting_on: format!("PR #{} ({name}): {reason}", info.details.number),
+            });
+        }
+    }
+
+    Ok(StackPoll::Ready)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jj_ryu::platform::GitHubService;
+    use jj_ryu::types::Bookmark;
+    use mockito::{Matcher, Mock, Server};
+    use serde_json::json;
+
+    const SHA: &str = "3f2a9c4e8b7d6a5f4e3d2c1b0a9f8e7d6c5b4a39";
+
+    fn segment(name: &str) -> NarrowedBookmarkSegment {
+        NarrowedBookmarkSegment {
+            bookmark: Bookmark {
+                name: name.to_string(),
+                commit_id: SHA.to_string(),
+                change_id: "zzzzzzzz".to_string(),
+                has_remote: true,
+                is_synced: true,
+            },
+            changes: vec![],
+        }
+    }
+
+    fn pull() -> serde_json::Value {
+        json!({
+            "url": "https://api.github.com/repos/o/r/pulls/12",
+            "id": 2001,
+            "number": 12,
+            "state": "open",
+            "title": "Add feature A",
+            "draft": false,
+            "mergeable": true,
+            "html_url": "https://github.com/o/r/pull/12",
+            "head": { "label": "o:feat-a", "ref": "feat-a", "sha": SHA },
+            "base": { "label": "o:main", "ref": "main", "sha": "9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d" }
+        })
+    }
+
+    fn approved_review() -> serde_json::Value {
+        json!([{
+            "id": 301,
+            "node_id": "PRR_301",
+            "user": {
+                "login": "alice",
+                "id": 5001,
+                "node_id": "U_5001",
+                "avatar_url": "https://avatars.githubusercontent.com/u/5001",
+                "gravatar_id": "",
+                "url": "https://api.github.com/users/alice",
+                "html_url": "https://github.com/alice",
+                "followers_url": "https://api.github.com/users/alice/followers",
+                "following_url": "https://api.github.com/users/alice/following{/other_user}",
+                "gists_url": "https://api.github.com/users/alice/gists{/gist_id}",
+                "starred_url": "https://api.github.com/users/alice/starred{/owner}{/repo}",
+                "subscriptions_url": "https://api.github.com/users/alice/subscriptions",
+                "organizations_url": "https://api.github.com/users/alice/orgs",
+                "repos_url": "https://api.github.com/users/alice/repos",
+                "events_url": "https://api.github.com/users/alice/events{/privacy}",
+                "received_events_url": "https://api.github.com/users/alice/received_events",
+                "type": "User",
+                "site_admin": false
+            },
+            "state": "APPROVED",
+            "html_url": "https://github.com/o/r/pull/12#pullrequestreview-301",
+            "pull_request_url": "https://api.github.com/repos/o/r/pulls/12",
+            "commit_id": SHA,
+            "submitted_at": "2025-01-02T10:00:00Z"
+        }])
+    }
+
+    async fn mock_json(server: &mut Server, path: &str, body: serde_json::Value) -> Mock {
+        server
+            .mock("GET", path)
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create_async()
+            .await
+    }
+
+    async fn mock_check_run(server: &mut Server, status: &str, conclusion: Option<&str>) -> Mock {
+        let path = format!("/repos/o/r/commits/{SHA}/check-runs");
+        let body = json!({
+            "total_count": 1,
+            "check_runs": [{
+                "id": 501,
+                "name": "test",
+                "status": status,
+                "conclusion": conclusion,
+                "html_url": "https://github.com/o/r/runs/501"
+            }]
+        });
+        mock_json(server, &path, body).await
+    }
+
+    // The server must outlive the requests, not just the mock setup
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_poll_sees_pending_checks_finish() {
+        let mut server = Server::new_async().await;
+        mock_json(&mut server, "/repos/o/r/pulls", json!([pull()])).await;
+        mock_json(&mut server, "/repos/o/r/pulls/12", pull()).await;
+        mock_json(
+            &mut server,
+            "/repos/o/r/pulls/12/reviews",
+            approved_review(),
+        )
+        .await;
+        let statuses = json!({ "state": "pending", "sha": SHA, "total_count": 0, "statuses": [] });
+        mock_json(
+            &mut server,
+            &format!("/repos/o/r/commits/{SHA}/status"),
+            statuses,
+        )
+        .await;
+        let running = mock_check_run(&mut server, "in_progress", None).await;
+
+        let service =
+            GitHubService::with_api_url("token", "o".to_string(), "r".to_string(), &server.url())
+                .unwrap();
+        let segment = segment("feat-a");
+        let segments = vec![&segment];
+
+        let poll = poll_stack(&segments, &service).await.unwrap();
+        assert!(
+            matches!(&poll, StackPoll::Waiting { ready: 0, waiting_on } if waiting_on.contains("PR #12")),
+            "expected to wait on pending CI, got {poll:?}"
+        );
+
+        // CI finishes between polls; the same service must not replay the pending result
+        running.remove_async().await;
+        mock_check_run(&mut server, "completed", Some("success")).await;
+
+        let poll = poll_stack(&segments, &service).await.unwrap();
+        assert!(
+            matches!(poll, StackPoll::Ready),
+            "expected ready, got {poll:?}"
+        );
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_poll_waits_for_required_check_that_has_not_started() {
+        let mut server = Server::new_async().await;
+        mock_json(&mut server, "/repos/o/r/pulls", json!([pull()])).await;
+        mock_json(&mut server, "/repos/o/r/pulls/12", pull()).await;
+        mock_json(
+            &mut server,
+            "/repos/o/r/pulls/12/reviews",
+            approved_review(),
+        )
+        .await;
+        let protection = json!({ "required_status_checks": { "contexts": ["build"] } });
+        mock_json(
+            &mut server,
+            "/repos/o/r/branches/main/protection",
+            protection,
+        )
+        .await;
+        let statuses = json!({ "state": "pending", "sha": SHA, "total_count": 0, "statuses": [] });
+        mock_json(
+            &mut server,
+            &format!("/repos/o/r/commits/{SHA}/status"),
+            statuses,
+        )
+        .await;
+        // Only an optional check has reported; the required "build" hasn't
+        let optional = mock_check_run(&mut server, "completed", Some("success")).await;
+
+        let service =
+            GitHubService::with_api_url("token", "o".to_string(), "r".to_string(), &server.url())
+                .unwrap();
+        let segment = segment("feat-a");
+        let segments = vec![&segment];
+
+        let poll = poll_stack(&segments, &service).await.unwrap();
+        assert!(
+            matches!(poll, StackPoll::Waiting { ready: 0, .. }),
+            "expected to wait on the required check, got {poll:?}"
+        );
+
+        optional.remove_async().await;
+        let path = format!("/repos/o/r/commits/{SHA}/check-runs");
+        let build = json!({
+            "total_count": 1,
+            "check_runs": [{
+                "id": 502,
+                "name": "build",
+                "status": "completed",
+                "conclusion": "success",
+                "html_url": "https://github.com/o/r/runs/502"
+            }]
+        });
+        mock_json(&mut server, &path, build).await;
+
+        let poll = poll_stack(&segments, &service).await.unwrap();
+        assert!(
+            matches!(poll, StackPoll::Ready),
+            "expected ready, got {poll:?}"
+        );
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_poll_missing_pr_is_an_error() {
+        let mut server = Server::new_async().await;
+        mock_json(&mut server, "/repos/o/r/pulls", json!([])).await;
+
+        let service =
+            GitHubService::with_api_url("token", "o".to_string(), "r".to_string(), &server.url())
+                .unwrap();
+        let segment = segment("feat-a");
+
+        let result = poll_stack(&[&segment], &service).await;
+        assert!(
+            matches!(result, Err(Unready::Error(Error::Platform(msg))) if msg.contains("no open PR"))
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! `ryu web` command - open the stack's PRs in the browser
+
+use crate::cli::context::CommandContext;
+use crate::cli::style::{Stylize, check};
+use anstream::println;
+use jj_ryu::error::{Error, Result};
+use jj_ryu::graph::build_change_graph;
+use jj_ryu::repo::JjWorkspace;
+use jj_ryu::submit::analyze_submission;
+use jj_ryu::tracking::load_pr_cache;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Options for the web command
+#[derive(Debug, Clone, Default)]
+pub struct WebOptions {
+    /// Open every PR in the stack
+    pub all: bool,
+}
+
+/// Run the web command
+///
+/// Opens the PR of `bookmark` (the top of the stack if None), or of every
+/// bookmark in the stack with `--all`. URLs come from the PR cache; bookmarks
+/// missing from it are looked up on the platform.
+pub async fn run_web(
+    path: &Path,
+    bookmark: Option<&str>,
+    remote: Option<&str>,
+    options: WebOptions,
+) -> Result<()> {
+    let workspace = JjWorkspace::open(path)?;
+    let pr_cache = load_pr_cache(workspace.workspace_root()).unwrap_or_default();
+    let graph = build_change_graph(&workspace)?;
+
+    let names: Vec<String> = if options.all {
+        if graph.stack.is_none() {
+            println!("{}", "No stack found between trunk and working copy.".muted());
+            return Ok(());
+        }
+        analyze_submission(&graph, None)?
+            .segments
+            .into_iter()
+            .map(|s| s.bookmark.name)
+            .collect()
+    } else if let Some(name) = bookmark {
+        vec![name.to_string()]
+    } else {
+        vec![analyze_submission(&graph, None)?.target_bookmark]
+    };
+
+    // Look up the PRs the cache doesn't know about
+    let mut urls: Vec<(String, Option<String>)> = names
+        .into_iter()
+        .map(|name| {
+            let url = pr_cache.get(&name).map(|pr| pr.url.clone());
+            (name, url)
+        })
+        .collect();
+    if urls.iter().any(|(_, url)| url.is_none()) {
+        let ctx = CommandContext::new(path, remote).await?;
+        for (name, url) in &mut urls {
+            if url.is_none() {
+                *url = ctx
+                    .platform
+                    .find_existing_pr(name)
+                    .await?
+                    .map(|pr| pr.html_url);
+            }
+        }
+    }
+
+    if let [(name, None)] = urls.as_slice() {
+        return Err(Error::Platform(format!("No PR found for {name}")));
+    }
+    for (name, url) in urls {
+        match url {
+            Some(url) if open_in_browser(&url) => {
+                println!("{} Opened {} ({})", check(), url.accent(), name);
+            }
+            Some(url) => println!("{} {}", name.accent(), url),
+            None => println!("  {} {}", name.accent(), "(no PR)".muted()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Open `url` in the default browser, returning whether a browser was started
+pub fn open_in_browser(url: &str) -> bool {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .is_ok()
+}
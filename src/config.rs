@@ -0,0 +1,689 @@
+//! User and repository configuration.
+//!
+//! Configuration is read from two TOML files and the environment, with later
+//! layers overriding earlier ones key by key:
+//!
+//! 1. User config: `~/.config/ryu/config.toml` (platform config directory)
+//! 2. Repo config: `.jj/repo/ryu/config.toml`
+//! 3. Environment: `RYU_` followed by the key in upper case with dots as
+//!    underscores, e.g. `RYU_SUBMIT_DRAFT=true` for `submit.draft`
+//!
+//! Keys are addressed with dotted names, e.g. `http.max_concurrency` is the
+//! `max_concurrency` key in the `[http]` table. Every key is optional.
+
+use crate::error::{Error, Result};
+use crate::tracking::resolve_repo_path;
+use crate::types::{MergeMethod, StackCommentMode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Filename for config files (both user and repo level).
+const CONFIG_FILE: &str = "config.toml";
+
+/// Default number of concurrent platform API requests.
+pub const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+/// Default number of times a transient platform API failure is retried.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Default delay before the first retry, in milliseconds.
+pub const DEFAULT_RETRY_BACKOFF: u64 = 500;
+
+/// Top-level ryu configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RyuConfig {
+    /// Remote used when `--remote` isn't given.
+    pub remote: Option<String>,
+    /// Platform API request tuning.
+    pub http: HttpConfig,
+    /// Commands run after submit and merge.
+    pub hooks: HooksConfig,
+    /// Chat webhooks posted after submit and merge.
+    pub webhooks: WebhooksConfig,
+    /// Draft PR handling.
+    pub drafts: DraftsConfig,
+    /// Defaults for `ryu submit`.
+    pub submit: SubmitConfig,
+    /// Defaults for `ryu merge`.
+    pub merge: MergeConfig,
+    /// New-version check.
+    pub updates: UpdatesConfig,
+    /// Debug log written to a file.
+    pub log: LogConfig,
+    /// OAuth apps used by `ryu auth <platform> login`.
+    pub auth: AuthConfig,
+    /// Platform of self-hosted instances, keyed by hostname, e.g.
+    /// `"git.corp.com" = "gitlab"`.
+    pub hosts: BTreeMap<String, HostPlatform>,
+}
+
+/// Platform API request tuning.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Maximum number of platform API requests in flight at once.
+    pub max_concurrency: usize,
+    /// Minimum delay between write requests (create, update, merge), in
+    /// milliseconds. Zero disables throttling.
+    pub min_delay_between_writes: u64,
+    /// Times a request is retried after a transient failure (timeout,
+    /// connection error, or 5xx response). Zero disables retries.
+    pub max_retries: u32,
+    /// Delay before the first retry, in milliseconds. Doubles on each
+    /// further retry.
+    pub retry_backoff: u64,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            min_delay_between_writes: 0,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+        }
+    }
+}
+
+/// Commands run after ryu operations.
+///
+/// Each command runs through the shell in the workspace root and receives a
+/// JSON description of the operation on stdin (see [`crate::hooks`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Run after `ryu submit` succeeds.
+    pub post_submit: Option<String>,
+    /// Run after `ryu merge` merges at least one PR.
+    pub post_merge: Option<String>,
+}
+
+/// Chat webhooks posted after submit and merge.
+///
+/// Each URL receives a summary of the stack and its PRs (see
+/// [`crate::webhooks`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhooksConfig {
+    /// Slack incoming webhook URL.
+    pub slack: Option<String>,
+    /// Discord channel webhook URL.
+    pub discord: Option<String>,
+    /// Microsoft Teams incoming webhook URL.
+    pub teams: Option<String>,
+}
+
+/// Draft PR handling.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DraftsConfig {
+    /// Publish a draft PR once the PR below it merges, during `ryu merge`
+    /// or `ryu sync`, so review focuses on the bottom of the stack.
+    pub auto_publish: bool,
+}
+
+/// Defaults for `ryu submit`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct SubmitConfig {
+    /// Create new PRs as drafts, as if `--draft` were given.
+    pub draft: bool,
+    /// Labels added to every submitted PR, along with any `--label` flags.
+    pub labels: Vec<String>,
+    /// Milestone every submitted PR is put in, unless `--milestone` is given.
+    pub milestone: Option<String>,
+    /// Put the stack overview in each PR's description, between
+    /// `<!-- ryu:stack:start -->` and `<!-- ryu:stack:end -->`, instead of
+    /// posting it as a comment.
+    pub stack_in_body: bool,
+    /// When to keep the stack overview on PRs: `always` (default),
+    /// `stacked` (only when the stack has two or more PRs) or `never`.
+    pub stack_comment: StackCommentMode,
+    /// Post a `ryu/stack` commit status on each PR's head commit showing its
+    /// position in the stack and the PR it depends on.
+    pub stack_status: bool,
+    /// After pushing new commits to a PR, ask everyone who already reviewed
+    /// it to review again.
+    pub rerequest_reviews: bool,
+    /// Drop conventional-commit prefixes (`feat:`, `fix(api)!:`) from PR
+    /// titles.
+    pub conventional_titles: bool,
+    /// Label added to a PR by the conventional-commit type of its root
+    /// commit, e.g. `fix = "bug"`.
+    pub type_labels: BTreeMap<String, String>,
+}
+
+/// Defaults for `ryu merge`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MergeConfig {
+    /// How PRs are merged: `squash` (default), `merge` or `rebase`.
+    pub method: MergeMethod,
+}
+
+/// New-version check.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdatesConfig {
+    /// Check for a newer ryu release (at most once a day) and mention it
+    /// after a command finishes.
+    pub check: bool,
+}
+
+/// Debug log written to a file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    /// File the debug log (API calls, fetches and pushes, with timings) is
+    /// appended to, at the level `--verbose` asks for or debug without it.
+    /// `RYU_LOG_FILE` sets it for a single run.
+    pub file: Option<PathBuf>,
+}
+
+/// OAuth apps used by `ryu auth <platform> login`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// Client ID of a GitHub OAuth app with device flow enabled.
+    pub github_client_id: Option<String>,
+    /// Client ID of a GitLab OAuth application (non-confidential, `api`
+    /// scope) with device authorization enabled.
+    pub gitlab_client_id: Option<String>,
+    /// Account to authenticate as on each host, e.g.
+    /// `"ghe.corp.com" = "work"`.
+    pub hosts: BTreeMap<String, String>,
+    /// Accounts named in `hosts`, with where their tokens come from.
+    pub accounts: BTreeMap<String, AccountConfig>,
+}
+
+/// An account in the `[auth.accounts]` table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccountConfig {
+    /// Environment variable holding the account's token.
+    pub token_env: Option<String>,
+    /// Command printing the account's token, e.g. `op read op://Work/GitHub/token`.
+    /// Run through the shell at most once per ryu process; takes precedence
+    /// over `token_env`.
+    pub token_command: Option<String>,
+}
+
+/// Platform a self-hosted instance runs, as named in the `[hosts]` table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HostPlatform {
+    /// GitHub Enterprise Server
+    GitHub,
+    /// Self-managed GitLab
+    GitLab,
+    /// Gerrit
+    Gerrit,
+}
+
+/// Get path to the user config file, if a config directory exists.
+pub fn user_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("ryu").join(CONFIG_FILE))
+}
+
+/// Get path to the repo config file.
+pub fn repo_config_path(workspace_root: &Path) -> PathBuf {
+    resolve_repo_path(workspace_root)
+        .join("ryu")
+        .join(CONFIG_FILE)
+}
+
+/// Load configuration for a workspace.
+///
+/// Missing files are treated as empty. Values in the repo config override
+/// values in the user config, and `RYU_*` environment variables override both.
+pub fn load_config(workspace_root: &Path) -> Result<RyuConfig> {
+    load_config_with_env(&config_paths(workspace_root), |name| env::var(name).ok())
+}
+
+/// Config files for a workspace, lowest precedence first.
+pub fn config_paths(workspace_root: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(user) = user_config_path() {
+        paths.push(user);
+    }
+    paths.push(repo_config_path(workspace_root));
+    paths
+}
+
+/// Load configuration by layering the given files in order.
+pub fn load_config_from(paths: &[PathBuf]) -> Result<RyuConfig> {
+    load_config_with_env(paths, |_| None)
+}
+
+/// Load configuration from the given files, then override keys from the
+/// environment variables `env` returns (see [`env_var_name`]).
+pub fn load_config_with_env(
+    paths: &[PathBuf],
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<RyuConfig> {
+    let mut merged = toml::Table::new();
+
+    for path in paths {
+        merge_tables(&mut merged, read_table(path)?);
+    }
+
+    for key in config_keys() {
+        let name = env_var_name(&key);
+        if let Some(raw) = env(&name) {
+            let value = parse_config_value(&key, &raw)
+                .map_err(|e| Error::Config(format!("{name}: {e}")))?;
+            insert_key(&mut merged, &key, value)?;
+        }
+    }
+
+    toml::Value::Table(merged)
+        .try_into()
+        .map_err(|e| Error::Config(e.to_string()))
+}
+
+/// Dotted names of every setting, e.g. `submit.draft`.
+///
+/// Tables keyed by name, like `[hosts]`, aren't included.
+pub fn config_keys() -> Vec<String> {
+    let mut entries = Vec::new();
+    flatten_json("", &default_json(), &mut entries);
+    entries.into_iter().map(|(key, _)| key).collect()
+}
+
+/// Every setting in `config` with its value, by dotted name.
+///
+/// Unset optional settings are left out; entries of tables keyed by name
+/// are included, e.g. `hosts."git.corp.com"`.
+pub fn config_entries(config: &RyuConfig) -> Vec<(String, serde_json::Value)> {
+    let mut entries = Vec::new();
+    let value = serde_json::to_value(config).unwrap_or_default();
+    flatten_json("", &value, &mut entries);
+    entries.retain(|(_, value)| !value.is_null());
+    entries
+}
+
+/// Value of the dotted `key` in `config`; None if it's unset.
+///
+/// Fails for keys that aren't settings.
+pub fn get_config_value(config: &RyuConfig, key: &str) -> Result<Option<serde_json::Value>> {
+    let path = key_path(key)?;
+    default_at(key, &path)?;
+
+    let mut current = serde_json::to_value(config).unwrap_or_default();
+    for part in &path {
+        match current.get_mut(part) {
+            Some(value) => current = value.take(),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(current).filter(|value| !value.is_null()))
+}
+
+/// Parse `raw` as a value for the dotted `key`, by the setting's type.
+///
+/// Flags take `true` or `false`, numbers are integers, and lists are
+/// comma-separated. Fails for keys that aren't settings.
+pub fn parse_config_value(key: &str, raw: &str) -> Result<toml::Value> {
+    let path = key_path(key)?;
+    match default_at(key, &path)? {
+        Some(serde_json::Value::Bool(_)) => raw.trim().parse().map(toml::Value::Boolean).map_err(
+            |_| Error::Config(format!("{key} must be true or false, not '{raw}'")),
+        ),
+        Some(serde_json::Value::Number(_)) => raw.trim().parse().map(toml::Value::Integer).map_err(
+            |_| Error::Config(format!("{key} must be a number, not '{raw}'")),
+        ),
+        Some(serde_json::Value::Array(_)) => Ok(toml::Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| toml::Value::String(item.to_string()))
+                .collect(),
+        )),
+        _ => Ok(toml::Value::String(raw.to_string())),
+    }
+}
+
+/// Environment variable overriding the dotted `key`, e.g. `RYU_SUBMIT_DRAFT`.
+pub fn env_var_name(key: &str) -> String {
+    format!("RYU_{}", key.replace('.', "_").to_uppercase())
+}
+
+/// Set the dotted `key` (e.g. `submit.draft`) in the config file at `path`.
+///
+/// Key segments containing dots are quoted as in TOML, e.g.
+/// `hosts."git.corp.com"`. The file and any missing tables are created;
+/// other keys are kept, but comments are not. Nothing is written if the
+/// result isn't a valid config.
+pub fn set_config_value(path: &Path, key: &str, value: toml::Value) -> Result<()> {
+    let mut table = read_table(path)?;
+    insert_key(&mut table, key, value)?;
+
+    toml::Value::Table(table.clone())
+        .try_into::<RyuConfig>()
+        .map_err(|e| Error::Config(format!("invalid value for {key}: {e}")))?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| Error::Config(format!("failed to create {}: {e}", parent.display())))?;
+    }
+    let content = toml::to_string_pretty(&table).map_err(|e| Error::Config(e.to_string()))?;
+    fs::write(path, content)
+        .map_err(|e| Error::Config(format!("failed to write {}: {e}", path.display())))
+}
+
+/// Set the dotted `key` in `table`, creating missing tables.
+fn insert_key(table: &mut toml::Table, key: &str, value: toml::Value) -> Result<()> {
+    let mut parts = key_path(key)?;
+    let name = parts.pop().unwrap_or_default();
+    let mut current = table;
+    for part in parts {
+        current = current
+            .entry(part.as_str())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| Error::Config(format!("'{part}' in '{key}' is not a table")))?;
+    }
+    current.insert(name, value);
+    Ok(())
+}
+
+/// Default value of the setting at `path`, or None inside a table keyed by
+/// name (like `[hosts]`), whose entries can be anything.
+fn default_at(key: &str, path: &[String]) -> Result<Option<serde_json::Value>> {
+    let unknown = || Error::Config(format!("unknown config key '{key}'"));
+    let mut current = default_json();
+    for part in path {
+        match current {
+            serde_json::Value::Object(map) if map.is_empty() => return Ok(None),
+            serde_json::Value::Object(mut map) => current = map.remove(part).ok_or_else(unknown)?,
+            _ => return Err(unknown()),
+        }
+    }
+    if current.is_object() {
+        return Err(Error::Config(format!("'{key}' is a table, not a setting")));
+    }
+    Ok(Some(current))
+}
+
+/// The default config as JSON, which (unlike TOML) keeps unset options.
+fn default_json() -> serde_json::Value {
+    serde_json::to_value(RyuConfig::default()).unwrap_or_default()
+}
+
+/// Collect the leaf values of `value` by dotted key, quoting key segments
+/// that aren't bare TOML keys.
+fn flatten_json(
+    prefix: &str,
+    value: &serde_json::Value,
+    entries: &mut Vec<(String, serde_json::Value)>,
+) {
+    let serde_json::Value::Object(map) = value else {
+        entries.push((prefix.to_string(), value.clone()));
+        return;
+    };
+    for (name, value) in map {
+        let bare = name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        let name = if bare { name.clone() } else { format!("\"{name}\"") };
+        let key = if prefix.is_empty() {
+            name
+        } else {
+            format!("{prefix}.{name}")
+        };
+        flatten_json(&key, value, entries);
+    }
+}
+
+/// Split a dotted TOML key into its segments, unquoting quoted ones.
+fn key_path(key: &str) -> Result<Vec<String>> {
+    let invalid = || Error::Config(format!("invalid key '{key}'"));
+    let mut table: toml::Table = toml::from_str(&format!("{key} = 0")).map_err(|_| invalid())?;
+
+    let mut parts = Vec::new();
+    loop {
+        let (part, value) = table.into_iter().next().ok_or_else(invalid)?;
+        parts.push(part);
+        match value {
+            toml::Value::Table(inner) => table = inner,
+            _ => return Ok(parts),
+        }
+    }
+}
+
+/// Read a config file as a table; a missing file is an empty table.
+fn read_table(path: &Path) -> Result<toml::Table> {
+    if !path.exists() {
+        return Ok(toml::Table::new());
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| Error::Config(format!("failed to read {}: {e}", path.display())))?;
+    toml::from_str(&content)
+        .map_err(|e| Error::Config(format!("failed to parse {}: {e}", path.display())))
+}
+
+/// Recursively merge `overlay` into `base`, with `overlay` taking precedence.
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(incoming)) => {
+                merge_tables(existing, incoming);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_defaults_when_no_files() {
+        let temp = TempDir::new().unwrap();
+        let config = load_config_from(&[temp.path().join("missing.toml")]).unwrap();
+        assert_eq!(config, RyuConfig::default());
+        assert_eq!(config.http.max_concurrency, DEFAULT_MAX_CONCURRENCY);
+        assert_eq!(config.http.min_delay_between_writes, 0);
+        assert_eq!(config.http.max_retries, DEFAULT_MAX_RETRIES);
+    }
+
+    #[test]
+    fn test_repo_overrides_user_per_key() {
+        let temp = TempDir::new().unwrap();
+        let user = temp.path().join("user.toml");
+        let repo = temp.path().join("repo.toml");
+        fs::write(
+            &user,
+            "[http]\nmax_concurrency = 8\nmin_delay_between_writes = 100\n",
+        )
+        .unwrap();
+        fs::write(&repo, "[http]\nmax_concurrency = 1\n").unwrap();
+
+        let config = load_config_from(&[user, repo]).unwrap();
+        assert_eq!(config.http.max_concurrency, 1);
+        assert_eq!(config.http.min_delay_between_writes, 100);
+    }
+
+    #[test]
+    fn test_invalid_value_is_config_error() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("config.toml");
+        fs::write(&path, "[http]\nmax_concurrency = \"lots\"\n").unwrap();
+
+        let err = load_config_from(&[path]).unwrap_err();
+        assert!(matches!(err, Error::Config(_)));
+    }
+
+    #[test]
+    fn test_hosts_table() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("config.toml");
+        fs::write(
+            &path,
+            "[hosts]\n\"git.corp.com\" = \"gitlab\"\n\"ghe.corp.com\" = \"github\"\n",
+        )
+        .unwrap();
+
+        let config = load_config_from(&[path]).unwrap();
+        assert_eq!(config.hosts["git.corp.com"], HostPlatform::GitLab);
+        assert_eq!(config.hosts["ghe.corp.com"], HostPlatform::GitHub);
+    }
+
+    #[test]
+    fn test_stack_comment_mode() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("config.toml");
+        fs::write(&path, "[submit]\nstack_comment = \"stacked\"\n").unwrap();
+
+        let config = load_config_from(&[path]).unwrap();
+        assert_eq!(config.submit.stack_comment, StackCommentMode::Stacked);
+        assert!(!config.submit.stack_comment.applies_to(1));
+        assert!(config.submit.stack_comment.applies_to(2));
+    }
+
+    #[test]
+    fn test_auth_accounts() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("config.toml");
+        fs::write(
+            &path,
+            "[auth.hosts]\n\"ghe.corp.com\" = \"work\"\n\n\
+             [auth.accounts.work]\ntoken_env = \"WORK_GITHUB_TOKEN\"\n",
+        )
+        .unwrap();
+
+        let config = load_config_from(&[path]).unwrap();
+        assert_eq!(config.auth.hosts["ghe.corp.com"], "work");
+        assert_eq!(
+            config.auth.accounts["work"].token_env.as_deref(),
+            Some("WORK_GITHUB_TOKEN")
+        );
+    }
+
+    #[test]
+    fn test_repo_config_path() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".jj").join("repo")).unwrap();
+        assert!(repo_config_path(temp.path()).ends_with(".jj/repo/ryu/config.toml"));
+    }
+
+    #[test]
+    fn test_set_config_value_keeps_other_keys() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("ryu").join("config.toml");
+
+        set_config_value(&path, "submit.draft", toml::Value::Boolean(true)).unwrap();
+        set_config_value(&path, "merge.method", toml::Value::String("rebase".into())).unwrap();
+
+        let config = load_config_from(std::slice::from_ref(&path)).unwrap();
+        assert!(config.submit.draft);
+        assert_eq!(config.merge.method, MergeMethod::Rebase);
+
+        let err = set_config_value(&path, "merge.method", toml::Value::String("ff".into()))
+            .unwrap_err();
+        assert!(matches!(err, Error::Config(_)));
+        let config = load_config_from(std::slice::from_ref(&path)).unwrap();
+        assert_eq!(config.merge.method, MergeMethod::Rebase);
+
+        set_config_value(
+            &path,
+            r#"hosts."git.corp.com""#,
+            toml::Value::String("gitlab".into()),
+        )
+        .unwrap();
+        let config = load_config_from(&[path]).unwrap();
+        assert_eq!(config.hosts["git.corp.com"], HostPlatform::GitLab);
+        assert!(config.submit.draft);
+    }
+
+    #[test]
+    fn test_env_overrides_files() {
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path().join("repo.toml");
+        fs::write(&repo, "[submit]\ndraft = false\nlabels = [\"a\"]\n").unwrap();
+
+        let config = load_config_with_env(&[repo], |name| match name {
+            "RYU_SUBMIT_DRAFT" => Some("true".to_string()),
+            "RYU_SUBMIT_LABELS" => Some("stacked, infra".to_string()),
+            "RYU_HTTP_MAX_CONCURRENCY" => Some("2".to_string()),
+            "RYU_REMOTE" => Some("upstream".to_string()),
+            "RYU_LOG_FILE" => Some("/tmp/ryu.log".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        assert!(config.submit.draft);
+        assert_eq!(config.submit.labels, vec!["stacked", "infra"]);
+        assert_eq!(config.http.max_concurrency, 2);
+        assert_eq!(config.remote.as_deref(), Some("upstream"));
+        assert_eq!(config.log.file, Some(PathBuf::from("/tmp/ryu.log")));
+
+        let err = load_config_with_env(&[], |name| {
+            (name == "RYU_SUBMIT_DRAFT").then(|| "maybe".to_string())
+        })
+        .unwrap_err();
+        assert!(matches!(err, Error::Config(ref msg) if msg.contains("RYU_SUBMIT_DRAFT")));
+    }
+
+    #[test]
+    fn test_config_keys_and_values() {
+        let keys = config_keys();
+        assert!(keys.contains(&"submit.draft".to_string()));
+        assert!(keys.contains(&"submit.milestone".to_string()));
+        assert!(keys.contains(&"merge.method".to_string()));
+        assert!(!keys.iter().any(|key| key.starts_with("hosts")));
+
+        let mut config = RyuConfig::default();
+        config
+            .hosts
+            .insert("git.corp.com".to_string(), HostPlatform::GitLab);
+        assert_eq!(
+            get_config_value(&config, "merge.method").unwrap(),
+            Some(serde_json::json!("squash"))
+        );
+        assert_eq!(get_config_value(&config, "submit.milestone").unwrap(), None);
+        assert_eq!(
+            get_config_value(&config, r#"hosts."git.corp.com""#).unwrap(),
+            Some(serde_json::json!("gitlab"))
+        );
+        assert!(get_config_value(&config, "submit.drafts").is_err());
+        assert!(get_config_value(&config, "submit").is_err());
+
+        let entries = config_entries(&config);
+        assert!(entries.contains(&(
+            r#"hosts."git.corp.com""#.to_string(),
+            serde_json::json!("gitlab")
+        )));
+        assert!(!entries.iter().any(|(key, _)| key == "submit.milestone"));
+    }
+
+    #[test]
+    fn test_parse_config_value_by_type() {
+        assert_eq!(
+            parse_config_value("submit.draft", "true").unwrap(),
+            toml::Value::Boolean(true)
+        );
+        assert_eq!(
+            parse_config_value("http.max_retries", "5").unwrap(),
+            toml::Value::Integer(5)
+        );
+        assert_eq!(
+            parse_config_value("submit.milestone", "2024.05").unwrap(),
+            toml::Value::String("2024.05".to_string())
+        );
+        assert_eq!(
+            parse_config_value("auth.accounts.work.token_env", "WORK_TOKEN").unwrap(),
+            toml::Value::String("WORK_TOKEN".to_string())
+        );
+        assert!(parse_config_value("http.max_retries", "many").is_err());
+        assert!(parse_config_value("nope", "1").is_err());
+    }
+}
@@ -44,6 +44,10 @@ pub enum Error {
     #[error("GitLab API error: {0}")]
     GitLabApi(String),
 
+    /// Gerrit API error
+    #[error("Gerrit API error: {0}")]
+    GerritApi(String),
+
     /// Merge commit detected (cannot stack)
     #[error("merge commit detected in bookmark '{0}' history - rebasing required")]
     MergeCommitDetected(String),
@@ -84,6 +88,26 @@ pub enum Error {
     #[error("platform error: {0}")]
     Platform(String),
 
+    /// The platform's API rate limit was hit
+    #[error("{message}")]
+    RateLimited {
+        /// Human-readable description
+        message: String,
+        /// How long the platform asked us to wait, if it said
+        retry_after: Option<std::time::Duration>,
+    },
+
+    /// A request kept failing transiently until retries ran out
+    #[error("{operation} failed after {attempts} attempts: {source}")]
+    RetriesExhausted {
+        /// Platform operation that failed
+        operation: String,
+        /// Number of attempts made
+        attempts: u32,
+        /// Error from the last attempt
+        source: Box<Self>,
+    },
+
     /// Internal error (unexpected state)
     #[error("internal error: {0}")]
     Internal(String),
@@ -105,9 +129,29 @@ pub enum Error {
     #[error("tracking error: {0}")]
     Tracking(String),
 
+    /// Refused to push a bookmark over the default or a protected branch
+    #[error("refusing to push '{0}': it is the default branch or a protected branch")]
+    ProtectedBranch(String),
+
     /// Rebase failed after merge
     #[error("rebase failed: {0}")]
     RebaseFailed(String),
+
+    /// A configured hook script failed
+    #[error("hook error: {0}")]
+    Hook(String),
+
+    /// A chat webhook could not be posted
+    #[error("webhook error: {0}")]
+    Webhook(String),
+
+    /// Downloading or installing a ryu release failed
+    #[error("self-update failed: {0}")]
+    SelfUpdate(String),
+
+    /// The platform server is too old for the requested feature
+    #[error("{0}")]
+    Unsupported(String),
 }
 
 /// Result type alias for jj-ryu operations
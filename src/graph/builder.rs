@@ -20,17 +20,25 @@ use tracing::debug;
 /// - `stack: Some(...)` if there are bookmarked commits between trunk and @
 /// - `stack: None` if working copy is at trunk or no bookmarks exist
 pub fn build_change_graph(workspace: &JjWorkspace) -> Result<ChangeGraph> {
-    debug!("Building change graph from trunk to working copy...");
+    build_change_graph_to(workspace, "@")
+}
+
+/// Build a change graph of the stack from trunk to `head` instead of @
+///
+/// `head` is a revset resolving to a single commit. Used to see the part of
+/// the stack above the working copy, e.g. for stack navigation.
+pub fn build_change_graph_to(workspace: &JjWorkspace, head: &str) -> Result<ChangeGraph> {
+    debug!("Building change graph from trunk to {head}...");
 
-    // Query trunk()..@ to get all commits between trunk and working copy
-    let changes = workspace.resolve_revset("trunk()..@")?;
+    // Query trunk()..head to get all commits between trunk and the head
+    let changes = workspace.resolve_revset(&format!("trunk()..({head})"))?;
 
     if changes.is_empty() {
-        debug!("Working copy is at trunk, no stack to build");
+        debug!("{head} is at trunk, no stack to build");
         return Ok(ChangeGraph::default());
     }
 
-    debug!("Found {} commits between trunk and @", changes.len());
+    debug!("Found {} commits between trunk and {head}", changes.len());
 
     // Check for merge commits - we don't support them
     for change in &changes {
@@ -0,0 +1,227 @@
+//! Machine-readable stack export
+//!
+//! A serializable snapshot of the stack and its PRs for tools that don't want
+//! to talk to jj or the platform themselves. The format is versioned by
+//! [`STACK_EXPORT_VERSION`]; fields are only added within a version.
+
+use crate::types::{ChangeGraph, PrState, PullRequestDetails};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+/// Current version of the export format
+pub const STACK_EXPORT_VERSION: u32 = 1;
+
+/// Exported stack, from trunk to leaf
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StackExport {
+    /// Format version ([`STACK_EXPORT_VERSION`])
+    pub version: u32,
+    /// Trunk branch the stack is based on
+    pub trunk: String,
+    /// Remote PRs are pushed to, if known
+    pub remote: Option<String>,
+    /// Segments from trunk (index 0) to leaf (last index)
+    pub segments: Vec<ExportedSegment>,
+}
+
+/// One segment of the stack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedSegment {
+    /// Bookmarks pointing to the tip of this segment
+    pub bookmarks: Vec<ExportedBookmark>,
+    /// Branch this segment's PR should target (previous segment or trunk)
+    pub base: String,
+    /// Changes in this segment (newest first)
+    pub changes: Vec<ExportedChange>,
+    /// PR for this segment, if one exists
+    pub pr: Option<ExportedPr>,
+}
+
+/// A bookmark in the stack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedBookmark {
+    /// Bookmark name
+    pub name: String,
+    /// Git commit ID (hex)
+    pub commit_id: String,
+    /// jj change ID
+    pub change_id: String,
+    /// Whether this bookmark exists on any remote
+    pub has_remote: bool,
+    /// Whether local and remote are in sync
+    pub is_synced: bool,
+    /// Whether the bookmark is tracked with `ryu track`
+    pub tracked: bool,
+}
+
+/// A change in the stack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedChange {
+    /// Git commit ID (hex)
+    pub commit_id: String,
+    /// jj change ID
+    pub change_id: String,
+    /// First line of the description
+    pub description: String,
+    /// Whether this is the working copy commit
+    pub is_working_copy: bool,
+}
+
+/// A segment's PR
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedPr {
+    /// PR/MR number
+    pub number: u64,
+    /// Web URL
+    pub url: String,
+    /// Head branch
+    pub head: String,
+    /// Current base branch on the platform (None = unknown)
+    pub base: Option<String>,
+    /// Title (None = unknown)
+    pub title: Option<String>,
+    /// State (None = unknown)
+    pub state: Option<PrState>,
+    /// Whether the PR is a draft (None = unknown)
+    pub is_draft: Option<bool>,
+}
+
+impl From<PullRequestDetails> for ExportedPr {
+    fn from(details: PullRequestDetails) -> Self {
+        Self {
+            number: details.number,
+            url: details.html_url,
+            head: details.head_ref,
+            base: Some(details.base_ref),
+            title: Some(details.title),
+            state: Some(details.state),
+            is_draft: Some(details.is_draft),
+        }
+    }
+}
+
+/// Build the export for a change graph
+///
+/// `prs` maps bookmark names to their PRs; a segment takes the PR of its
+/// first bookmark that has one.
+pub fn export_stack<S: BuildHasher>(
+    graph: &ChangeGraph,
+    trunk: &str,
+    remote: Option<&str>,
+    prs: &HashMap<String, ExportedPr, S>,
+    tracked: &[&str],
+) -> StackExport {
+    let mut segments = Vec::new();
+    let mut base = trunk.to_string();
+
+    for segment in graph.stack.iter().flat_map(|stack| &stack.segments) {
+        let bookmarks: Vec<ExportedBookmark> = segment
+            .bookmarks
+            .iter()
+            .map(|b| ExportedBookmark {
+                name: b.name.clone(),
+                commit_id: b.commit_id.clone(),
+                change_id: b.change_id.clone(),
+                has_remote: b.has_remote,
+                is_synced: b.is_synced,
+                tracked: tracked.contains(&b.name.as_str()),
+            })
+            .collect();
+        let changes = segment
+            .changes
+            .iter()
+            .map(|c| ExportedChange {
+                commit_id: c.commit_id.clone(),
+                change_id: c.change_id.clone(),
+                description: c.description_first_line.clone(),
+                is_working_copy: c.is_working_copy,
+            })
+            .collect();
+        let pr = segment
+            .bookmarks
+            .iter()
+            .find_map(|b| prs.get(&b.name))
+            .cloned();
+
+        let next_base = pr.as_ref().map_or_else(
+            || bookmarks.first().map(|b| b.name.clone()),
+            |pr| Some(pr.head.clone()),
+        );
+        segments.push(ExportedSegment {
+            bookmarks,
+            base: base.clone(),
+            changes,
+            pr,
+        });
+        if let Some(next_base) = next_base {
+            base = next_base;
+        }
+    }
+
+    StackExport {
+        version: STACK_EXPORT_VERSION,
+        trunk: trunk.to_string(),
+        remote: remote.map(String::from),
+        segments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Bookmark, BookmarkSegment, BranchStack};
+
+    fn segment(name: &str) -> BookmarkSegment {
+        BookmarkSegment {
+            bookmarks: vec![Bookmark {
+                name: name.to_string(),
+                commit_id: format!("{name}-commit"),
+                change_id: format!("{name}-change"),
+                has_remote: true,
+                is_synced: true,
+            }],
+            changes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_export_stack_bases_and_prs() {
+        let graph = ChangeGraph {
+            stack: Some(BranchStack {
+                segments: vec![segment("feat-a"), segment("feat-b")],
+            }),
+            ..Default::default()
+        };
+        let prs = HashMap::from([(
+            "feat-b".to_string(),
+            ExportedPr {
+                number: 2,
+                url: "https://github.com/o/r/pull/2".to_string(),
+                head: "feat-b".to_string(),
+                base: None,
+                title: None,
+                state: None,
+                is_draft: None,
+            },
+        )]);
+
+        let export = export_stack(&graph, "main", Some("origin"), &prs, &["feat-a"]);
+        assert_eq!(export.version, STACK_EXPORT_VERSION);
+        assert_eq!(export.segments.len(), 2);
+        assert_eq!(export.segments[0].base, "main");
+        assert!(export.segments[0].pr.is_none());
+        assert!(export.segments[0].bookmarks[0].tracked);
+        assert_eq!(export.segments[1].base, "feat-a");
+        assert_eq!(export.segments[1].pr.as_ref().unwrap().number, 2);
+        assert!(!export.segments[1].bookmarks[0].tracked);
+    }
+
+    #[test]
+    fn test_export_round_trips_through_json() {
+        let export = export_stack(&ChangeGraph::default(), "main", None, &HashMap::new(), &[]);
+        let json = serde_json::to_string(&export).unwrap();
+        let parsed: StackExport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, export);
+    }
+}
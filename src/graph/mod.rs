@@ -3,5 +3,10 @@
 //! Analyzes jj bookmarks to build a graph of stacked changes.
 
 mod builder;
+mod export;
 
-pub use builder::build_change_graph;
+pub use builder::{build_change_graph, build_change_graph_to};
+pub use export::{
+    ExportedBookmark, ExportedChange, ExportedPr, ExportedSegment, STACK_EXPORT_VERSION,
+    StackExport, export_stack,
+};
@@ -0,0 +1,181 @@
+//! User hook scripts run after ryu operations
+//!
+//! Hooks are shell commands configured under `[hooks]` (see
+//! [`crate::config::HooksConfig`]). Each runs in the workspace root with a
+//! JSON [`HookPayload`] on stdin and `RYU_HOOK` set to the event name, e.g.:
+//!
+//! ```json
+//! { "event": "post_submit", "remote": "origin", "result": { "success": true, ... } }
+//! ```
+
+use crate::config::HooksConfig;
+use crate::error::{Error, Result};
+use serde::Serialize;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::debug;
+
+/// Event that triggers a hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    /// After `ryu submit` pushed and created/updated PRs
+    PostSubmit,
+    /// After `ryu merge` merged at least one PR
+    PostMerge,
+}
+
+impl HookEvent {
+    /// The configured command for this event, if any
+    pub fn command(self, hooks: &HooksConfig) -> Option<&str> {
+        match self {
+            Self::PostSubmit => hooks.post_submit.as_deref(),
+            Self::PostMerge => hooks.post_merge.as_deref(),
+        }
+        .filter(|command| !command.trim().is_empty())
+    }
+}
+
+impl std::fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PostSubmit => write!(f, "post_submit"),
+            Self::PostMerge => write!(f, "post_merge"),
+        }
+    }
+}
+
+/// JSON written to a hook's stdin
+#[derive(Debug, Serialize)]
+pub struct HookPayload<'a, T> {
+    /// Event that triggered the hook
+    pub event: HookEvent,
+    /// Remote the operation ran against
+    pub remote: &'a str,
+    /// The operation's result (`SubmissionResult` or `MergeExecutionResult`)
+    pub result: &'a T,
+}
+
+/// Run a hook command with `payload` on stdin
+///
+/// Commands run through `sh -c` (`cmd /C` on Windows) in `cwd`. With
+/// `stdout_to_stderr`, whatever the hook prints goes to stderr, keeping
+/// machine-readable output on stdout clean. Returns an error if the command
+/// can't be started or exits unsuccessfully.
+pub async fn run_hook<T: Serialize + Sync>(
+    command: &str,
+    payload: &HookPayload<'_, T>,
+    cwd: &Path,
+    stdout_to_stderr: bool,
+) -> Result<()> {
+    let input = serde_json::to_vec(payload)?;
+    debug!(event = %payload.event, command, "running hook");
+
+    let mut cmd = shell(command);
+    cmd.current_dir(cwd)
+        .env("RYU_HOOK", payload.event.to_string())
+        .stdin(Stdio::piped());
+    if stdout_to_stderr {
+        cmd.stdout(std::io::stderr());
+    }
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| Error::Hook(format!("{} hook failed to start: {e}", payload.event)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores stdin may exit before reading it all
+        if let Err(e) = stdin.write_all(&input).await {
+            debug!(error = %e, "hook did not read its input");
+        }
+    }
+
+    let status = child.wait().await?;
+    if !status.success() {
+        return Err(Error::Hook(format!("{} hook exited with {status}", payload.event)));
+    }
+    Ok(())
+}
+
+/// Command that runs `command` through the platform shell
+#[cfg(not(windows))]
+pub(crate) fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+/// Command that runs `command` through the platform shell
+#[cfg(windows)]
+pub(crate) fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[derive(Serialize)]
+    struct Outcome {
+        success: bool,
+    }
+
+    #[tokio::test]
+    async fn test_hook_receives_payload_on_stdin() {
+        let temp = TempDir::new().unwrap();
+        let payload = HookPayload {
+            event: HookEvent::PostSubmit,
+            remote: "origin",
+            result: &Outcome { success: true },
+        };
+
+        run_hook(
+            "cat > payload.json; echo $RYU_HOOK > event",
+            &payload,
+            temp.path(),
+            false,
+        )
+        .await
+        .unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(temp.path().join("payload.json")).unwrap())
+                .unwrap();
+        assert_eq!(json["event"], "post_submit");
+        assert_eq!(json["remote"], "origin");
+        assert_eq!(json["result"]["success"], true);
+        assert_eq!(
+            fs::read_to_string(temp.path().join("event")).unwrap().trim(),
+            "post_submit"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failing_hook_is_an_error() {
+        let temp = TempDir::new().unwrap();
+        let payload = HookPayload {
+            event: HookEvent::PostMerge,
+            remote: "origin",
+            result: &Outcome { success: true },
+        };
+
+        let err = run_hook("exit 3", &payload, temp.path(), false).await.unwrap_err();
+        assert!(matches!(err, Error::Hook(_)));
+        assert!(err.to_string().contains("post_merge"));
+    }
+
+    #[test]
+    fn test_blank_command_is_not_configured() {
+        let hooks = HooksConfig {
+            post_submit: Some("  ".to_string()),
+            post_merge: Some("./notify.sh".to_string()),
+        };
+        assert_eq!(HookEvent::PostSubmit.command(&hooks), None);
+        assert_eq!(HookEvent::PostMerge.command(&hooks), Some("./notify.sh"));
+    }
+}
@@ -0,0 +1,268 @@
+//! Commit-based stack readers (spr, git-branchless)
+//!
+//! These tools keep no metadata beyond the commits themselves: spr records
+//! each commit's PR in a `Pull Request:` trailer, and git-branchless stacks
+//! are plain branches on top of each other. Both are reconstructed from the
+//! commit graph between trunk and the visible heads.
+
+use super::{ImportedBranch, ImportedPr, sort_parents_first};
+use crate::error::Result;
+use crate::repo::JjWorkspace;
+use crate::types::LogEntry;
+use std::collections::HashMap;
+
+/// Commit message trailer spr uses to link a commit to its PR
+pub const PULL_REQUEST_TRAILER: &str = "Pull Request:";
+
+/// Which commits form the entries of a commit-based stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitStackStyle {
+    /// Every commit with a `Pull Request:` trailer is one PR (spr)
+    Spr,
+    /// Every commit with a local bookmark is one branch (git-branchless)
+    Branchless,
+}
+
+/// A commit that is one entry of a commit-based stack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackCommit {
+    /// Git commit ID (hex)
+    pub commit_id: String,
+    /// Local bookmark on the commit, if any
+    pub bookmark: Option<String>,
+    /// Commit ID of the stack entry below this one (None = on trunk)
+    pub parent: Option<String>,
+    /// PR linked from the commit message, if any
+    pub pr: Option<ImportedPr>,
+}
+
+/// Parse the PR linked by a `Pull Request:` trailer
+///
+/// Accepts a PR/MR URL (`.../pull/12`, `.../merge_requests/12`) or `#12`.
+/// When several trailers are present the last one wins.
+pub fn parse_pull_request_trailer(description: &str) -> Option<ImportedPr> {
+    let value = description
+        .lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix(PULL_REQUEST_TRAILER))?
+        .trim();
+
+    let number = value
+        .trim_start_matches('#')
+        .rsplit('/')
+        .find(|segment| !segment.is_empty())?
+        .parse()
+        .ok()?;
+    let url = value.contains("://").then(|| value.to_string());
+
+    Some(ImportedPr {
+        number,
+        url,
+        title: None,
+        base: None,
+        state: None,
+        is_draft: false,
+    })
+}
+
+/// Pick out the stack entries in `entries` and link each to the one below it
+///
+/// The parent of an entry is its nearest first-parent ancestor that is also
+/// an entry; entries with none are based on trunk.
+pub fn reconstruct_commit_stack(entries: &[LogEntry], style: CommitStackStyle) -> Vec<StackCommit> {
+    let by_id: HashMap<&str, &LogEntry> = entries
+        .iter()
+        .map(|entry| (entry.commit_id.as_str(), entry))
+        .collect();
+    let is_member = |entry: &LogEntry| match style {
+        CommitStackStyle::Spr => parse_pull_request_trailer(&entry.description).is_some(),
+        CommitStackStyle::Branchless => !entry.local_bookmarks.is_empty(),
+    };
+
+    entries
+        .iter()
+        .filter(|entry| is_member(entry))
+        .map(|entry| {
+            let mut parent = None;
+            let mut current = entry;
+            while let Some(next) = current
+                .parents
+                .first()
+                .and_then(|id| by_id.get(id.as_str()))
+            {
+                if is_member(next) {
+                    parent = Some(next.commit_id.clone());
+                    break;
+                }
+                current = next;
+            }
+
+            StackCommit {
+                commit_id: entry.commit_id.clone(),
+                bookmark: entry.local_bookmarks.first().cloned(),
+                parent,
+                pr: parse_pull_request_trailer(&entry.description),
+            }
+        })
+        .collect()
+}
+
+/// Turn stack commits into branches, parents first
+///
+/// Commits without a bookmark take their name from `names` (keyed by commit
+/// ID) and are skipped if it has none. A skipped commit's children are
+/// attached to its nearest named ancestor.
+#[allow(clippy::implicit_hasher)]
+pub fn stack_commits_to_branches(
+    commits: &[StackCommit],
+    names: &HashMap<String, String>,
+) -> Vec<ImportedBranch> {
+    let by_id: HashMap<&str, &StackCommit> = commits
+        .iter()
+        .map(|commit| (commit.commit_id.as_str(), commit))
+        .collect();
+    let name_of = |commit: &StackCommit| {
+        commit
+            .bookmark
+            .clone()
+            .or_else(|| names.get(&commit.commit_id).cloned())
+    };
+
+    let branches = commits
+        .iter()
+        .filter_map(|commit| {
+            let name = name_of(commit)?;
+            let mut parent = None;
+            let mut next = commit.parent.as_deref();
+            while let Some(ancestor) = next.and_then(|id| by_id.get(id)) {
+                if let Some(ancestor_name) = name_of(ancestor) {
+                    parent = Some(ancestor_name);
+                    break;
+                }
+                next = ancestor.parent.as_deref();
+            }
+
+            Some(ImportedBranch {
+                name,
+                parent,
+                pr: commit.pr.clone(),
+            })
+        })
+        .collect();
+
+    sort_parents_first(branches)
+}
+
+/// Read the stack entries between trunk and the visible heads
+pub fn read_commit_stack(
+    workspace: &JjWorkspace,
+    style: CommitStackStyle,
+) -> Result<Vec<StackCommit>> {
+    let entries = workspace.resolve_revset("trunk()..visible_heads()")?;
+    Ok(reconstruct_commit_stack(&entries, style))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn entry(id: &str, parent: &str, bookmark: Option<&str>, description: &str) -> LogEntry {
+        LogEntry {
+            commit_id: id.to_string(),
+            change_id: format!("change-{id}"),
+            author_name: "Test".to_string(),
+            author_email: "test@example.com".to_string(),
+            description_first_line: description.lines().next().unwrap_or_default().to_string(),
+            description: description.to_string(),
+            parents: vec![parent.to_string()],
+            local_bookmarks: bookmark.map(|b| vec![b.to_string()]).unwrap_or_default(),
+            remote_bookmarks: vec![],
+            is_working_copy: false,
+            authored_at: Utc::now(),
+            committed_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_parse_pull_request_trailer() {
+        let pr = parse_pull_request_trailer(
+            "Add feature\n\nDetails.\n\nPull Request: https://github.com/o/r/pull/42\n",
+        )
+        .unwrap();
+        assert_eq!(pr.number, 42);
+        assert_eq!(pr.url.as_deref(), Some("https://github.com/o/r/pull/42"));
+
+        let mr = parse_pull_request_trailer(
+            "Fix\n\nPull Request: https://gitlab.com/g/p/-/merge_requests/7/",
+        )
+        .unwrap();
+        assert_eq!(mr.number, 7);
+
+        let short = parse_pull_request_trailer("Fix\n\nPull Request: #9").unwrap();
+        assert_eq!(short.number, 9);
+        assert!(short.url.is_none());
+
+        assert!(parse_pull_request_trailer("Fix\n\nReviewed-by: someone").is_none());
+    }
+
+    #[test]
+    fn test_reconstruct_spr_stack_skips_unlinked_commits() {
+        let entries = vec![
+            entry("c3", "c2", None, "Third\n\nPull Request: https://github.com/o/r/pull/3"),
+            entry("c2", "c1", None, "Fixup without PR"),
+            entry("c1", "trunk", None, "First\n\nPull Request: https://github.com/o/r/pull/1"),
+        ];
+
+        let stack = reconstruct_commit_stack(&entries, CommitStackStyle::Spr);
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack[0].commit_id, "c3");
+        assert_eq!(stack[0].parent.as_deref(), Some("c1"));
+        assert_eq!(stack[1].parent, None);
+    }
+
+    #[test]
+    fn test_branchless_stack_to_branches() {
+        let entries = vec![
+            entry("c2", "c1", Some("feat-b"), "B"),
+            entry("c1", "trunk", Some("feat-a"), "A"),
+        ];
+
+        let stack = reconstruct_commit_stack(&entries, CommitStackStyle::Branchless);
+        let branches = stack_commits_to_branches(&stack, &HashMap::new());
+        let names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["feat-a", "feat-b"]);
+        assert_eq!(branches[1].parent.as_deref(), Some("feat-a"));
+    }
+
+    #[test]
+    fn test_unnamed_commits_are_skipped() {
+        let stack = vec![
+            StackCommit {
+                commit_id: "c1".to_string(),
+                bookmark: None,
+                parent: None,
+                pr: None,
+            },
+            StackCommit {
+                commit_id: "c2".to_string(),
+                bookmark: None,
+                parent: Some("c1".to_string()),
+                pr: None,
+            },
+            StackCommit {
+                commit_id: "c3".to_string(),
+                bookmark: Some("top".to_string()),
+                parent: Some("c2".to_string()),
+                pr: None,
+            },
+        ];
+        let names = HashMap::from([("c1".to_string(), "spr/main/one".to_string())]);
+
+        let branches = stack_commits_to_branches(&stack, &names);
+        assert_eq!(branches.len(), 2);
+        assert_eq!(branches[0].name, "spr/main/one");
+        assert_eq!(branches[1].name, "top");
+        assert_eq!(branches[1].parent.as_deref(), Some("spr/main/one"));
+    }
+}
@@ -0,0 +1,188 @@
+//! Graphite (`gt`) metadata reader
+//!
+//! Graphite records each branch's parent and PR in a JSON blob referenced by
+//! `refs/branch-metadata/<branch>`. Older versions instead kept a cache file,
+//! `.graphite_cache_persist`, in the git directory; it is used as a fallback
+//! when no metadata refs exist.
+
+use super::{ImportedBranch, ImportedPr, sort_parents_first};
+use crate::error::{Error, Result};
+use crate::repo::JjWorkspace;
+use crate::types::PrState;
+use serde::Deserialize;
+use std::fs;
+
+/// Git ref prefix under which Graphite stores branch metadata
+pub const GRAPHITE_METADATA_PREFIX: &str = "refs/branch-metadata/";
+
+/// Legacy Graphite cache file in the git directory
+pub const GRAPHITE_CACHE_FILE: &str = ".graphite_cache_persist";
+
+/// Contents of a `refs/branch-metadata/<branch>` blob
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BranchMetadata {
+    parent_branch_name: Option<String>,
+    pr_info: Option<PrInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PrInfo {
+    number: Option<u64>,
+    url: Option<String>,
+    title: Option<String>,
+    base: Option<String>,
+    state: Option<String>,
+    #[serde(default)]
+    is_draft: bool,
+}
+
+/// Legacy cache file: `branches` is a list of `[name, info]` pairs
+#[derive(Deserialize)]
+struct GraphiteCache {
+    #[serde(default)]
+    branches: Vec<(String, CachedBranch)>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedBranch {
+    parent_branch_name: Option<String>,
+    validation_result: Option<String>,
+}
+
+/// Parse one branch's metadata blob
+pub fn parse_graphite_metadata(name: &str, content: &[u8]) -> Result<ImportedBranch> {
+    let metadata: BranchMetadata = serde_json::from_slice(content)
+        .map_err(|e| Error::Parse(format!("invalid Graphite metadata for {name}: {e}")))?;
+
+    let pr = metadata.pr_info.and_then(|info| {
+        Some(ImportedPr {
+            number: info.number?,
+            url: info.url,
+            title: info.title,
+            base: info.base,
+            state: info.state.as_deref().and_then(parse_pr_state),
+            is_draft: info.is_draft,
+        })
+    });
+
+    Ok(ImportedBranch {
+        name: name.to_string(),
+        parent: metadata.parent_branch_name,
+        pr,
+    })
+}
+
+/// Parse the legacy `.graphite_cache_persist` file
+pub fn parse_graphite_cache(content: &str) -> Result<Vec<ImportedBranch>> {
+    let cache: GraphiteCache = serde_json::from_str(content)
+        .map_err(|e| Error::Parse(format!("invalid {GRAPHITE_CACHE_FILE}: {e}")))?;
+
+    Ok(cache
+        .branches
+        .into_iter()
+        .filter(|(_, info)| info.validation_result.as_deref() != Some("TRUNK"))
+        .map(|(name, info)| ImportedBranch {
+            name,
+            parent: info.parent_branch_name,
+            pr: None,
+        })
+        .collect())
+}
+
+/// Read all Graphite-managed branches in the repository, parents first
+///
+/// Branches with unreadable metadata are skipped with a debug log.
+pub fn read_graphite_branches(workspace: &JjWorkspace) -> Result<Vec<ImportedBranch>> {
+    let mut branches: Vec<ImportedBranch> = workspace
+        .git_ref_blobs(GRAPHITE_METADATA_PREFIX)?
+        .into_iter()
+        .filter_map(|(name, content)| match parse_graphite_metadata(&name, &content) {
+            Ok(branch) => Some(branch),
+            Err(e) => {
+                tracing::debug!(error = %e, "skipping Graphite branch");
+                None
+            }
+        })
+        .collect();
+
+    if branches.is_empty() {
+        let cache_path = workspace.git_dir()?.join(GRAPHITE_CACHE_FILE);
+        if cache_path.exists() {
+            let content = fs::read_to_string(&cache_path)?;
+            branches = parse_graphite_cache(&content)?;
+        }
+    }
+
+    Ok(sort_parents_first(branches))
+}
+
+/// Map Graphite's PR state strings (`OPEN`, `CLOSED`, `MERGED`)
+fn parse_pr_state(state: &str) -> Option<PrState> {
+    match state.to_ascii_uppercase().as_str() {
+        "OPEN" => Some(PrState::Open),
+        "CLOSED" => Some(PrState::Closed),
+        "MERGED" => Some(PrState::Merged),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_metadata_with_pr() {
+        let content = br#"{
+            "parentBranchName": "feat-a",
+            "parentBranchRevision": "0123abcd",
+            "prInfo": {
+                "number": 42,
+                "url": "https://github.com/o/r/pull/42",
+                "title": "Add B",
+                "base": "feat-a",
+                "state": "OPEN",
+                "isDraft": true
+            }
+        }"#;
+
+        let branch = parse_graphite_metadata("feat-b", content).unwrap();
+        assert_eq!(branch.name, "feat-b");
+        assert_eq!(branch.parent.as_deref(), Some("feat-a"));
+
+        let pr = branch.pr.unwrap();
+        assert_eq!(pr.number, 42);
+        assert_eq!(pr.state, Some(PrState::Open));
+        assert!(pr.is_draft);
+    }
+
+    #[test]
+    fn test_parse_metadata_without_pr_number() {
+        let content = br#"{"parentBranchName": "main", "prInfo": {"title": "WIP"}}"#;
+        let branch = parse_graphite_metadata("feat-a", content).unwrap();
+        assert!(branch.pr.is_none());
+    }
+
+    #[test]
+    fn test_parse_metadata_invalid() {
+        assert!(parse_graphite_metadata("feat-a", b"not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_cache_skips_trunk() {
+        let content = r#"{
+            "sha": "abc",
+            "branches": [
+                ["main", {"validationResult": "TRUNK", "children": ["feat-a"]}],
+                ["feat-a", {"validationResult": "VALID", "parentBranchName": "main"}]
+            ]
+        }"#;
+
+        let branches = parse_graphite_cache(content).unwrap();
+        assert_eq!(branches.len(), 1);
+        assert_eq!(branches[0].name, "feat-a");
+        assert_eq!(branches[0].parent.as_deref(), Some("main"));
+    }
+}
@@ -0,0 +1,153 @@
+//! Import stacks from other stacking tools
+//!
+//! Importers read another tool's metadata and describe the branches it
+//! manages. The CLI turns those into tracked jj bookmarks and cached PRs.
+
+mod commits;
+mod graphite;
+
+pub use commits::{
+    CommitStackStyle, PULL_REQUEST_TRAILER, StackCommit, parse_pull_request_trailer,
+    read_commit_stack, reconstruct_commit_stack, stack_commits_to_branches,
+};
+pub use graphite::{
+    GRAPHITE_CACHE_FILE, GRAPHITE_METADATA_PREFIX, parse_graphite_cache, parse_graphite_metadata,
+    read_graphite_branches,
+};
+
+use crate::types::{PrState, PullRequest};
+use std::collections::HashSet;
+
+/// A branch managed by another stacking tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedBranch {
+    /// Branch (bookmark) name
+    pub name: String,
+    /// Parent branch in the stack (trunk for the bottom of a stack)
+    pub parent: Option<String>,
+    /// PR recorded for the branch, if any
+    pub pr: Option<ImportedPr>,
+}
+
+/// A PR recorded by another stacking tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedPr {
+    /// PR/MR number
+    pub number: u64,
+    /// Web URL, if recorded
+    pub url: Option<String>,
+    /// Title, if recorded
+    pub title: Option<String>,
+    /// Base branch, if recorded
+    pub base: Option<String>,
+    /// Last known state (None = unknown)
+    pub state: Option<PrState>,
+    /// Whether the PR was a draft
+    pub is_draft: bool,
+}
+
+impl ImportedBranch {
+    /// The branch's PR as a [`PullRequest`], if one is recorded and may still be open
+    pub fn open_pull_request(&self) -> Option<PullRequest> {
+        let pr = self.pr.as_ref()?;
+        if matches!(pr.state, Some(PrState::Closed | PrState::Merged)) {
+            return None;
+        }
+
+        Some(PullRequest {
+            number: pr.number,
+            html_url: pr.url.clone().unwrap_or_default(),
+            base_ref: pr
+                .base
+                .clone()
+                .or_else(|| self.parent.clone())
+                .unwrap_or_default(),
+            head_ref: self.name.clone(),
+            title: pr.title.clone().unwrap_or_default(),
+            node_id: None,
+            is_draft: pr.is_draft,
+        })
+    }
+}
+
+/// Order branches so every parent comes before its children
+///
+/// Siblings keep their relative order. Branches whose parent is outside the
+/// set (usually trunk) are roots.
+pub fn sort_parents_first(branches: Vec<ImportedBranch>) -> Vec<ImportedBranch> {
+    let names: HashSet<String> = branches.iter().map(|b| b.name.clone()).collect();
+    let mut placed: HashSet<String> = HashSet::new();
+    let mut remaining = branches;
+    let mut sorted = Vec::with_capacity(remaining.len());
+
+    while !remaining.is_empty() {
+        let (ready, blocked): (Vec<_>, Vec<_>) = remaining.into_iter().partition(|b| {
+            b.parent
+                .as_ref()
+                .is_none_or(|p| !names.contains(p) || placed.contains(p))
+        });
+
+        if ready.is_empty() {
+            // Parent cycle in the metadata; keep the rest in their original order
+            sorted.extend(blocked);
+            break;
+        }
+
+        placed.extend(ready.iter().map(|b| b.name.clone()));
+        sorted.extend(ready);
+        remaining = blocked;
+    }
+
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn branch(name: &str, parent: &str) -> ImportedBranch {
+        ImportedBranch {
+            name: name.to_string(),
+            parent: Some(parent.to_string()),
+            pr: None,
+        }
+    }
+
+    #[test]
+    fn test_sort_parents_first() {
+        let sorted = sort_parents_first(vec![
+            branch("feat-c", "feat-b"),
+            branch("feat-b", "feat-a"),
+            branch("other", "main"),
+            branch("feat-a", "main"),
+        ]);
+        let names: Vec<&str> = sorted.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["other", "feat-a", "feat-b", "feat-c"]);
+    }
+
+    #[test]
+    fn test_sort_parents_first_tolerates_cycles() {
+        let sorted = sort_parents_first(vec![branch("a", "b"), branch("b", "a")]);
+        assert_eq!(sorted.len(), 2);
+    }
+
+    #[test]
+    fn test_open_pull_request_skips_closed() {
+        let mut b = branch("feat-a", "main");
+        b.pr = Some(ImportedPr {
+            number: 7,
+            url: None,
+            title: None,
+            base: None,
+            state: Some(PrState::Merged),
+            is_draft: false,
+        });
+        assert!(b.open_pull_request().is_none());
+
+        b.pr.as_mut().unwrap().state = None;
+        let pr = b.open_pull_request().unwrap();
+        assert_eq!(pr.number, 7);
+        assert_eq!(pr.base_ref, "main");
+        assert_eq!(pr.head_ref, "feat-a");
+    }
+}
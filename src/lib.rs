@@ -12,17 +12,47 @@
 //! - Web servers / REST APIs
 //! - WebSocket servers
 //!
-//! All I/O is async and state is passed explicitly (no globals).
+//! All I/O is async and state is passed explicitly. The one global is the
+//! registry of platform backends added with [`platform::register_platform`].
+//!
+//! # Using the library
+//!
+//! The `ryu` binary is built behind the default `cli` feature. Tools that only
+//! need the library can skip the CLI dependencies:
+//!
+//! ```toml
+//! [dependencies]
+//! jj-ryu = { version = "0.0.1-alpha.11", default-features = false }
+//! ```
+//!
+//! The layers build on each other:
+//! - [`repo`] wraps the jj workspace, and [`graph`] turns its bookmarks into a
+//!   [`ChangeGraph`]
+//! - [`submit`] and [`merge`] analyze a graph, build a plan, then execute it
+//! - [`platform`] implements [`platform::PlatformService`] for GitHub and
+//!   GitLab; executors only talk to the trait, so other implementations can
+//!   be plugged in, and registered so remotes are detected as theirs
+//! - [`tracking`] and [`config`] persist state under `.jj/repo/ryu/`
+//!
+//! # Stability
+//!
+//! The public API of these modules follows semver. While the version is
+//! `0.x`, breaking changes bump the minor version and are listed in the
+//! release notes; the `cli` module of the binary is not part of the API.
 
 pub mod auth;
+pub mod config;
 pub mod error;
 pub mod graph;
+pub mod hooks;
+pub mod import;
 pub mod merge;
 pub mod platform;
 pub mod repo;
 pub mod submit;
 pub mod tracking;
 pub mod types;
+pub mod webhooks;
 
 pub use error::{Error, Result};
 pub use types::*;
@@ -3,9 +3,10 @@
 //! CLI binary for managing stacked pull requests with jj.
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, Parser, Subcommand};
 use jj_ryu::types::Platform;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 mod cli;
 
@@ -18,12 +19,23 @@ struct Cli {
     #[arg(short, long, global = true)]
     path: Option<PathBuf>,
 
+    /// Print a JSON document instead of styled text (status, submit, sync, merge)
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Log debug output to stderr, or to `log.file` if set (-vv for more)
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Set up ryu for this repository interactively
+    Init,
+
     /// Submit current stack as PRs
     Submit {
         /// Bookmark to submit up to (defaults to leaf/top of stack)
@@ -45,6 +57,28 @@ enum Commands {
         #[arg(long, group = "scope")]
         only: bool,
 
+        /// Submit from this bookmark up, leaving the bookmarks below it alone
+        #[arg(
+            long,
+            value_name = "BOOKMARK",
+            conflicts_with_all = ["only", "stack", "offline"]
+        )]
+        from: Option<String>,
+
+        /// Bookmark to submit up to (same as the BOOKMARK argument)
+        #[arg(long, value_name = "BOOKMARK", conflicts_with = "bookmark")]
+        to: Option<String>,
+
+        /// Submit the bookmarks in this revset, e.g. `feat-b::feat-d`
+        #[arg(
+            long,
+            short = 'r',
+            value_name = "REVSET",
+            group = "scope",
+            conflicts_with_all = ["bookmark", "from", "to", "offline"]
+        )]
+        revisions: Option<String>,
+
         /// Only update existing PRs, don't create new ones
         #[arg(long)]
         update_only: bool,
@@ -72,6 +106,58 @@ enum Commands {
         /// Submit all bookmarks in `trunk()`..@ (ignore tracking)
         #[arg(long, short)]
         all: bool,
+
+        /// Queue the submission for `ryu flush` instead of contacting the remote
+        #[arg(long, conflicts_with_all = ["dry_run", "confirm", "select"])]
+        offline: bool,
+
+        /// Resume an interrupted submission, reusing PRs it already created
+        #[arg(long = "continue", conflicts_with = "offline")]
+        resume: bool,
+
+        /// Roll back this run's changes (PRs, bases, pushes) if any step fails
+        #[arg(long, conflicts_with = "offline")]
+        atomic: bool,
+
+        /// Push only the top bookmark and open one PR for the whole stack
+        #[arg(long, conflicts_with_all = ["only", "select", "offline"])]
+        single_pr: bool,
+
+        /// Use a simulated platform: no network, tokens, or pushes
+        #[arg(long, conflicts_with_all = ["offline", "resume"])]
+        simulate: bool,
+
+        /// Add a label to every PR (repeatable; adds to `submit.labels`)
+        #[arg(long = "label", short = 'l', value_name = "LABEL")]
+        labels: Vec<String>,
+
+        /// Assign new PRs to a user (repeatable; `self` for yourself)
+        #[arg(long = "assign", value_name = "USER")]
+        assignees: Vec<String>,
+
+        /// Put every PR in this milestone (overrides `submit.milestone`)
+        #[arg(long)]
+        milestone: Option<String>,
+
+        /// Put the stack overview in PR descriptions instead of a comment
+        #[arg(long)]
+        stack_in_body: bool,
+
+        /// Don't post the stack overview (overrides `submit.stack_comment`)
+        #[arg(long, conflicts_with = "stack_in_body")]
+        no_stack_comment: bool,
+
+        /// Reopen closed PRs for bookmarks without asking (otherwise ryu asks,
+        /// or creates new PRs when it can't)
+        #[arg(long)]
+        reopen: bool,
+    },
+
+    /// Replay operations queued with --offline
+    Flush {
+        /// List queued operations without performing them
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Sync current stack with remote
@@ -91,6 +177,69 @@ enum Commands {
         /// Sync all bookmarks in `trunk()`..@ (ignore tracking)
         #[arg(long, short)]
         all: bool,
+
+        /// Show a desktop notification when the sync finishes
+        #[arg(long)]
+        notify: bool,
+
+        /// Resume a sync paused on rebase conflicts once they are resolved
+        #[arg(long = "continue", conflicts_with = "dry_run")]
+        resume: bool,
+
+        /// Create new PRs as drafts
+        #[arg(long)]
+        draft: bool,
+
+        /// Remove bookmarks whose PRs merged or whose branches were deleted
+        #[arg(long, conflicts_with = "resume")]
+        prune: bool,
+
+        /// What to do with bookmarks someone else rewrote on the remote
+        /// (default: ask, or skip without a terminal)
+        #[arg(long, value_enum, value_name = "CHOICE")]
+        on_diverged: Option<cli::DivergenceChoice>,
+    },
+
+    /// Rebase the tracked stack onto the latest trunk and update its PRs
+    Restack {
+        /// Only rebase locally, without pushing or updating PRs
+        #[arg(long)]
+        no_submit: bool,
+
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
+    },
+
+    /// Add bookmarks inside a bookmark's commits so each part becomes its own PR
+    Split {
+        /// Bookmark to split (default: the top of the stack)
+        bookmark: Option<String>,
+
+        /// Give every commit its own bookmark instead of prompting
+        #[arg(long)]
+        each: bool,
+
+        /// Only show the bookmarks that would be created
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Rename a bookmark, moving its PR and the PRs based on it to the new branch
+    Rename {
+        /// Bookmark to rename
+        old: String,
+
+        /// New name
+        new: String,
+
+        /// Only show what would be done
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
     },
 
     /// Merge approved PRs in the stack
@@ -106,6 +255,351 @@ enum Commands {
         /// Git remote to use
         #[arg(long)]
         remote: Option<String>,
+
+        /// Show a desktop notification when merging finishes or is blocked
+        #[arg(long)]
+        notify: bool,
+
+        /// Update PR branches that are behind their base before merging
+        #[arg(long)]
+        update_branch: bool,
+
+        /// Edit each squash commit message in $EDITOR before merging
+        #[arg(long)]
+        edit_message: bool,
+
+        /// Merge on the simulated platform used by `submit --simulate`
+        #[arg(long)]
+        simulate: bool,
+
+        /// Enable auto-merge so the platform merges once checks pass
+        #[arg(
+            long,
+            visible_alias = "when-ci-passes",
+            conflicts_with_all = ["update_branch", "edit_message"]
+        )]
+        auto: bool,
+
+        /// Wait up to SECS for GitHub to compute whether PRs are mergeable
+        #[arg(
+            long,
+            value_name = "SECS",
+            num_args = 0..=1,
+            default_missing_value = "30"
+        )]
+        wait_mergeable: Option<u64>,
+
+        /// Merge up to and including this bookmark, leaving the rest of the stack
+        #[arg(long, value_name = "BOOKMARK", conflicts_with = "auto")]
+        target: Option<String>,
+
+        /// Merge at most N PRs from the bottom of the stack
+        #[arg(long, value_name = "N", conflicts_with_all = ["target", "auto"])]
+        limit: Option<usize>,
+    },
+
+    /// Wait until every tracked PR in the stack is ready to merge
+    Wait {
+        /// Seconds between readiness checks
+        #[arg(long, value_name = "SECS", default_value_t = 30)]
+        interval: u64,
+
+        /// Give up after SECS (default: wait indefinitely)
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
+
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
+    },
+
+    /// Cherry-pick the stack onto a release branch as a mirrored stack of PRs
+    Backport {
+        /// Branch to backport to (e.g. release/2.3)
+        target: String,
+
+        /// Backport the stack up to this bookmark (default: the whole stack)
+        bookmark: Option<String>,
+
+        /// Dry run - show what would be backported without making changes
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Create the backport PRs as drafts
+        #[arg(long)]
+        draft: bool,
+
+        /// Backport all bookmarks in `trunk()`..@ (ignore tracking)
+        #[arg(long, short)]
+        all: bool,
+
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
+    },
+
+    /// Add jj aliases (`jj submit`, `jj stack`, ...) that run ryu
+    InstallJjAliases {
+        /// Write to this repo's jj config instead of the user config
+        #[arg(long)]
+        repo: bool,
+
+        /// Overwrite existing aliases with the same names
+        #[arg(long, short)]
+        force: bool,
+
+        /// Remove the aliases ryu installed
+        #[arg(long, conflicts_with = "force")]
+        uninstall: bool,
+    },
+
+    /// Mail the stack as a patch series with git send-email
+    SendEmail {
+        /// Send the stack up to this bookmark (default: top tracked bookmark)
+        bookmark: Option<String>,
+
+        /// Recipient (repeatable; defaults to git's sendemail.to)
+        #[arg(long)]
+        to: Vec<String>,
+
+        /// Carbon-copy recipient (repeatable)
+        #[arg(long)]
+        cc: Vec<String>,
+
+        /// Reroll count, for [PATCH vN] subjects
+        #[arg(long)]
+        reroll_count: Option<u32>,
+
+        /// Subject prefix instead of PATCH (e.g. "RFC PATCH")
+        #[arg(long)]
+        subject_prefix: Option<String>,
+
+        /// Cover letter subject (default: from the stack)
+        #[arg(long)]
+        subject: Option<String>,
+
+        /// Write the patches to this directory
+        #[arg(long, short)]
+        output_dir: Option<PathBuf>,
+
+        /// Only write the patches, don't send them
+        #[arg(long)]
+        format_only: bool,
+
+        /// Have git send-email print the emails instead of sending them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Send all bookmarks in `trunk()`..@ (ignore tracking)
+        #[arg(long, short)]
+        all: bool,
+    },
+
+    /// Move the working copy to the next bookmark up the stack
+    Up {
+        /// Number of bookmarks to move further from trunk
+        #[arg(default_value_t = 1)]
+        steps: usize,
+
+        /// Start a new change on top of the bookmark instead of editing it
+        #[arg(long)]
+        new: bool,
+    },
+
+    /// Move the working copy to the next bookmark down the stack
+    Down {
+        /// Number of bookmarks to move closer to trunk
+        #[arg(default_value_t = 1)]
+        steps: usize,
+
+        /// Start a new change on top of the bookmark instead of editing it
+        #[arg(long)]
+        new: bool,
+    },
+
+    /// Move the working copy to the top bookmark of the stack
+    Top {
+        /// Start a new change on top of the bookmark instead of editing it
+        #[arg(long)]
+        new: bool,
+    },
+
+    /// Move the working copy to the bottom bookmark of the stack
+    Bottom {
+        /// Start a new change on top of the bookmark instead of editing it
+        #[arg(long)]
+        new: bool,
+    },
+
+    /// Show the stack as a graph, annotated with each bookmark's PR
+    Log {
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
+
+        /// Show PRs from the local cache without contacting the platform
+        #[arg(long)]
+        offline: bool,
+    },
+
+    /// Show reviewer verdicts and unresolved threads for each PR in the stack
+    Reviews {
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
+    },
+
+    /// Browse the stack and act on its PRs in an interactive terminal UI
+    #[cfg(feature = "tui")]
+    Ui {
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
+    },
+
+    /// Show the CI checks of each PR in the stack
+    Checks {
+        /// Refresh until every check has completed
+        #[arg(long, short)]
+        watch: bool,
+
+        /// Seconds between refreshes with --watch
+        #[arg(long, value_name = "SECS", default_value_t = 15)]
+        interval: u64,
+
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
+    },
+
+    /// Open the PR of the current (or named) bookmark in the browser
+    Web {
+        /// Bookmark whose PR to open (default: the top of the stack)
+        bookmark: Option<String>,
+
+        /// Open every PR in the stack
+        #[arg(long, short)]
+        all: bool,
+
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
+    },
+
+    /// Post a comment on the PR of the current (or named) bookmark
+    Comment {
+        /// Comment text (read from stdin or $EDITOR if omitted or `-`)
+        message: Option<String>,
+
+        /// Bookmark whose PR to comment on (default: the one the working copy is on)
+        #[arg(long, short)]
+        bookmark: Option<String>,
+
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
+
+        /// Queue the comment for `ryu flush` instead of contacting the remote
+        #[arg(long)]
+        offline: bool,
+    },
+
+    /// Convert a bookmark's PR back to a draft
+    Draft {
+        /// Bookmark whose PR to mark as draft
+        bookmark: String,
+
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
+    },
+
+    /// Close the PRs of a bookmark and the stack above it, and delete the bookmarks
+    Abandon {
+        /// Lowest bookmark to abandon (defaults to the whole stack)
+        bookmark: Option<String>,
+
+        /// Also delete the branches on the remote
+        #[arg(long)]
+        delete_remote: bool,
+
+        /// Abandon only this bookmark, moving the PR above it onto its base
+        #[arg(long, requires = "bookmark")]
+        only: bool,
+
+        /// Only list what would be abandoned
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
+    },
+
+    /// Track bookmarks whose PRs were opened outside ryu, and fix the PR bases
+    Adopt {
+        /// Adopt only this PR (defaults to every open PR of a local bookmark)
+        pr: Option<u64>,
+
+        /// Only show what would be adopted
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
+    },
+
+    /// Delete and untrack bookmarks whose PRs were merged or closed
+    Clean {
+        /// Also abandon the bookmarks' empty changes
+        #[arg(long)]
+        abandon: bool,
+
+        /// Only show what would be cleaned up
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
+    },
+
+    /// Undo the last submit, sync, restack, merge, clean, abandon or backport
+    Undo {
+        /// Only show what would be undone
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Don't ask before undoing when the repo changed since the command
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+
+    /// Download and install the latest ryu release
+    #[cfg(feature = "self-update")]
+    SelfUpdate {
+        /// Only check whether a newer release is available
+        #[arg(long)]
+        check: bool,
+
+        /// Reinstall even if up to date or installed by a package manager
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Read and write configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
     },
 
     /// Authentication management
@@ -116,7 +610,7 @@ enum Commands {
 
     /// Track bookmarks for submission
     Track {
-        /// Bookmarks to track (shows available if omitted)
+        /// Bookmarks to track, or patterns like 'feat-*' (shows available if omitted)
         bookmarks: Vec<String>,
 
         /// Track all bookmarks in `trunk()`..@
@@ -127,9 +621,34 @@ enum Commands {
         #[arg(long, short)]
         force: bool,
 
-        /// Associate with specific remote
+        /// Remote to push these bookmarks to
         #[arg(long, short)]
         remote: Option<String>,
+
+        /// Branch the stack targets instead of the default branch (e.g. release/2.3)
+        #[arg(long)]
+        base: Option<String>,
+    },
+
+    /// Print the stack and its PRs as JSON
+    Export {
+        /// Use the local PR cache instead of querying the platform
+        #[arg(long)]
+        cached: bool,
+
+        /// Write to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+
+        /// Git remote to use
+        #[arg(long)]
+        remote: Option<String>,
+    },
+
+    /// Import stacks managed by another tool
+    Import {
+        #[command(subcommand)]
+        source: ImportSource,
     },
 
     /// Stop tracking bookmarks
@@ -140,9 +659,58 @@ enum Commands {
         /// Untrack all tracked bookmarks
         #[arg(long, short)]
         all: bool,
+
+        /// Also forget the bookmarks' PRs from the PR cache
+        #[arg(long)]
+        forget_pr: bool,
     },
 }
 
+#[derive(Subcommand)]
+enum ImportSource {
+    /// Import branches and PRs from Graphite (`gt`) metadata
+    Graphite {
+        /// Show what would be imported without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Import commits linked to PRs by spr `Pull Request:` trailers
+    Spr {
+        /// Show what would be imported without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Import git-branchless-style stacks of bookmarked commits
+    Branchless {
+        /// Show what would be imported without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print the value of a key, e.g. `submit.draft`
+    Get {
+        /// Dotted key name
+        key: String,
+    },
+    /// Save a key in the repo config (or the user config with --user)
+    Set {
+        /// Dotted key name
+        key: String,
+
+        /// Value; lists are comma-separated
+        value: String,
+
+        /// Save in the user config instead of the repo config
+        #[arg(long)]
+        user: bool,
+    },
+    /// Print every key that has a value
+    List,
+}
+
 #[derive(Subcommand)]
 enum AuthPlatform {
     /// GitHub authentication
@@ -155,6 +723,18 @@ enum AuthPlatform {
         #[command(subcommand)]
         action: AuthAction,
     },
+    /// Gerrit authentication
+    Gerrit {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
+    /// Show who the repository's platform token authenticates as, its scopes
+    /// and remaining rate limit
+    Status {
+        /// Git remote to detect the platform from
+        #[arg(long)]
+        remote: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -163,6 +743,38 @@ enum AuthAction {
     Test,
     /// Show authentication setup instructions
     Setup,
+    /// Log in with the OAuth device flow and keep the token in the system keyring
+    Login {
+        /// Host to log in to (default: `GH_HOST`/`GITLAB_HOST` or the public instance)
+        #[arg(long)]
+        host: Option<String>,
+
+        /// Client ID of the OAuth app to log in with (default: from the `[auth]` config)
+        #[arg(long)]
+        client_id: Option<String>,
+    },
+    /// Save a token in the system keyring (GitHub and GitLab)
+    Store {
+        /// Host the token is for (default: `GH_HOST`/`GITLAB_HOST` or the public instance)
+        #[arg(long)]
+        host: Option<String>,
+    },
+}
+
+/// Run an auth action for a platform
+async fn run_auth_action(
+    path: &Path,
+    platform: Platform,
+    action: AuthAction,
+) -> jj_ryu::error::Result<()> {
+    match action {
+        AuthAction::Test => cli::run_auth(platform, "test").await,
+        AuthAction::Setup => cli::run_auth(platform, "setup").await,
+        AuthAction::Login { host, client_id } => {
+            cli::run_auth_login(path, platform, host.as_deref(), client_id.as_deref()).await
+        }
+        AuthAction::Store { host } => cli::run_auth_store(platform, host.as_deref()).await,
+    }
 }
 
 #[tokio::main]
@@ -170,18 +782,49 @@ enum AuthAction {
 async fn main() -> Result<()> {
     let cli = Cli::parse();
     let path = cli.path.unwrap_or_else(|| PathBuf::from("."));
+    cli::init_logging(&path, cli.verbose)?;
+
+    if cli.json {
+        if !matches!(
+            cli.command,
+            None | Some(Commands::Submit { .. } | Commands::Sync { .. } | Commands::Merge { .. })
+        ) {
+            anyhow::bail!(
+                "--json is only supported by status (no subcommand), submit, sync and merge"
+            );
+        }
+        cli::set_json(true);
+    }
 
     match cli.command {
+        None if cli.json => {
+            // The stack as recorded locally, like the text view
+            cli::run_export(
+                &path,
+                None,
+                cli::ExportOptions {
+                    cached: true,
+                    output: None,
+                },
+            )
+            .await?;
+        }
         None => {
             // Default: interactive mode
             cli::run_analyze(&path).await?;
         }
+        Some(Commands::Init) => {
+            cli::run_init(&path).await?;
+        }
         Some(Commands::Submit {
             bookmark,
             dry_run,
             confirm,
             upto,
             only,
+            from,
+            to,
+            revisions,
             update_only,
             stack,
             draft,
@@ -189,6 +832,17 @@ async fn main() -> Result<()> {
             select,
             remote,
             all,
+            offline,
+            resume,
+            atomic,
+            single_pr,
+            simulate,
+            labels,
+            assignees,
+            milestone,
+            stack_in_body,
+            no_stack_comment,
+            reopen,
         }) => {
             // Determine scope from mutually exclusive flags (enforced by clap arg groups)
             #[allow(clippy::option_if_let_else)]
@@ -204,27 +858,48 @@ async fn main() -> Result<()> {
 
             cli::run_submit(
                 &path,
-                bookmark.as_deref(),
+                bookmark.or(to).as_deref(),
                 remote.as_deref(),
                 cli::SubmitOptions {
                     dry_run,
                     confirm,
                     scope,
                     upto_bookmark,
+                    from_bookmark: from.as_deref(),
+                    revisions: revisions.as_deref(),
                     update_only,
                     draft,
                     publish,
                     select,
                     all,
+                    offline,
+                    resume,
+                    atomic,
+                    single_pr,
+                    simulate,
+                    labels,
+                    assignees,
+                    milestone,
+                    stack_in_body,
+                    no_stack_comment,
+                    reopen,
                 },
             )
             .await?;
         }
+        Some(Commands::Flush { dry_run }) => {
+            cli::run_flush(&path, cli::FlushOptions { dry_run }).await?;
+        }
         Some(Commands::Sync {
             dry_run,
             confirm,
             remote,
             all,
+            notify,
+            resume,
+            draft,
+            prune,
+            on_diverged,
         }) => {
             cli::run_sync(
                 &path,
@@ -233,36 +908,305 @@ async fn main() -> Result<()> {
                     dry_run,
                     confirm,
                     all,
+                    notify,
+                    resume,
+                    draft,
+                    prune,
+                    on_diverged,
                 },
             )
             .await?;
         }
+        Some(Commands::Restack { no_submit, remote }) => {
+            cli::run_restack(
+                &path,
+                remote.as_deref(),
+                cli::RestackOptions { no_submit },
+            )
+            .await?;
+        }
+        Some(Commands::Split {
+            bookmark,
+            each,
+            dry_run,
+        }) => {
+            cli::run_split(
+                &path,
+                bookmark.as_deref(),
+                cli::SplitOptions { each, dry_run },
+            )?;
+        }
+        Some(Commands::Rename {
+            old,
+            new,
+            dry_run,
+            remote,
+        }) => {
+            cli::run_rename(
+                &path,
+                &old,
+                &new,
+                remote.as_deref(),
+                cli::RenameOptions { dry_run },
+            )
+            .await?;
+        }
         Some(Commands::Merge {
             dry_run,
             confirm,
             remote,
+            notify,
+            update_branch,
+            edit_message,
+            simulate,
+            auto,
+            wait_mergeable,
+            target,
+            limit,
         }) => {
             cli::run_merge(
                 &path,
                 remote.as_deref(),
-                cli::MergeOptions { dry_run, confirm },
+                cli::MergeOptions {
+                    dry_run,
+                    confirm,
+                    notify,
+                    update_branch,
+                    edit_message,
+                    simulate,
+                    auto,
+                    wait_mergeable: wait_mergeable.map(Duration::from_secs),
+                    target,
+                    limit,
+                },
+            )
+            .await?;
+        }
+        Some(Commands::Wait {
+            interval,
+            timeout,
+            remote,
+        }) => {
+            cli::run_wait(
+                &path,
+                remote.as_deref(),
+                cli::WaitOptions {
+                    interval: Duration::from_secs(interval),
+                    timeout: timeout.map(Duration::from_secs),
+                },
+            )
+            .await?;
+        }
+        Some(Commands::Backport {
+            target,
+            bookmark,
+            dry_run,
+            draft,
+            all,
+            remote,
+        }) => {
+            cli::run_backport(
+                &path,
+                &target,
+                bookmark.as_deref(),
+                remote.as_deref(),
+                cli::BackportOptions {
+                    all,
+                    draft,
+                    dry_run,
+                },
+            )
+            .await?;
+        }
+        Some(Commands::InstallJjAliases {
+            repo,
+            force,
+            uninstall,
+        }) => {
+            cli::run_install_jj_aliases(
+                &path,
+                &cli::AliasOptions {
+                    repo,
+                    force,
+                    uninstall,
+                },
+            )?;
+        }
+        Some(Commands::SendEmail {
+            bookmark,
+            to,
+            cc,
+            reroll_count,
+            subject_prefix,
+            subject,
+            output_dir,
+            format_only,
+            dry_run,
+            all,
+        }) => {
+            cli::run_send_email(
+                &path,
+                bookmark.as_deref(),
+                cli::SendEmailOptions {
+                    to,
+                    cc,
+                    version: reroll_count,
+                    subject_prefix,
+                    subject,
+                    output_dir,
+                    format_only,
+                    dry_run,
+                    all,
+                },
+            )
+            .await?;
+        }
+        Some(Commands::Up { steps, new }) => {
+            cli::run_navigate(&path, cli::Direction::Up(steps), cli::NavigateOptions { new })?;
+        }
+        Some(Commands::Down { steps, new }) => {
+            cli::run_navigate(&path, cli::Direction::Down(steps), cli::NavigateOptions { new })?;
+        }
+        Some(Commands::Top { new }) => {
+            cli::run_navigate(&path, cli::Direction::Top, cli::NavigateOptions { new })?;
+        }
+        Some(Commands::Bottom { new }) => {
+            cli::run_navigate(&path, cli::Direction::Bottom, cli::NavigateOptions { new })?;
+        }
+        Some(Commands::Log { remote, offline }) => {
+            cli::run_log(&path, remote.as_deref(), cli::LogOptions { offline }).await?;
+        }
+        Some(Commands::Reviews { remote }) => {
+            cli::run_reviews(&path, remote.as_deref()).await?;
+        }
+        #[cfg(feature = "tui")]
+        Some(Commands::Ui { remote }) => {
+            cli::run_ui(&path, remote.as_deref()).await?;
+        }
+        Some(Commands::Checks {
+            watch,
+            interval,
+            remote,
+        }) => {
+            cli::run_checks(
+                &path,
+                remote.as_deref(),
+                cli::ChecksOptions {
+                    watch,
+                    interval: Duration::from_secs(interval),
+                },
+            )
+            .await?;
+        }
+        Some(Commands::Web {
+            bookmark,
+            all,
+            remote,
+        }) => {
+            cli::run_web(
+                &path,
+                bookmark.as_deref(),
+                remote.as_deref(),
+                cli::WebOptions { all },
+            )
+            .await?;
+        }
+        Some(Commands::Comment {
+            message,
+            bookmark,
+            remote,
+            offline,
+        }) => {
+            cli::run_comment(
+                &path,
+                bookmark.as_deref(),
+                message.as_deref(),
+                remote.as_deref(),
+                offline,
+            )
+            .await?;
+        }
+        Some(Commands::Draft { bookmark, remote }) => {
+            cli::run_draft(&path, &bookmark, remote.as_deref()).await?;
+        }
+        Some(Commands::Abandon {
+            bookmark,
+            delete_remote,
+            only,
+            dry_run,
+            yes,
+            remote,
+        }) => {
+            cli::run_abandon(
+                &path,
+                bookmark.as_deref(),
+                remote.as_deref(),
+                cli::AbandonOptions {
+                    delete_remote,
+                    only,
+                    dry_run,
+                    yes,
+                },
             )
             .await?;
         }
+        Some(Commands::Adopt {
+            pr,
+            dry_run,
+            remote,
+        }) => {
+            cli::run_adopt(
+                &path,
+                pr,
+                remote.as_deref(),
+                cli::AdoptOptions { dry_run },
+            )
+            .await?;
+        }
+        Some(Commands::Clean {
+            abandon,
+            dry_run,
+            yes,
+            remote,
+        }) => {
+            cli::run_clean(
+                &path,
+                remote.as_deref(),
+                cli::CleanOptions {
+                    abandon,
+                    dry_run,
+                    yes,
+                },
+            )
+            .await?;
+        }
+        Some(Commands::Undo { dry_run, yes }) => {
+            cli::run_undo(&path, cli::UndoOptions { dry_run, yes }).await?;
+        }
+        #[cfg(feature = "self-update")]
+        Some(Commands::SelfUpdate { check, force }) => {
+            cli::run_self_update(cli::SelfUpdateOptions { check, force }).await?;
+            return Ok(());
+        }
+        Some(Commands::Config { action }) => match action {
+            ConfigAction::Get { key } => cli::run_config_get(&path, &key)?,
+            ConfigAction::Set { key, value, user } => {
+                cli::run_config_set(&path, &key, &value, user)?;
+            }
+            ConfigAction::List => cli::run_config_list(&path)?,
+        },
         Some(Commands::Auth { platform }) => match platform {
             AuthPlatform::Github { action } => {
-                let action_str = match action {
-                    AuthAction::Test => "test",
-                    AuthAction::Setup => "setup",
-                };
-                cli::run_auth(Platform::GitHub, action_str).await?;
+                run_auth_action(&path, Platform::GitHub, action).await?;
             }
             AuthPlatform::Gitlab { action } => {
-                let action_str = match action {
-                    AuthAction::Test => "test",
-                    AuthAction::Setup => "setup",
-                };
-                cli::run_auth(Platform::GitLab, action_str).await?;
+                run_auth_action(&path, Platform::GitLab, action).await?;
+            }
+            AuthPlatform::Gerrit { action } => {
+                run_auth_action(&path, Platform::Gerrit, action).await?;
+            }
+            AuthPlatform::Status { remote } => {
+                cli::run_auth_status(&path, remote.as_deref()).await?;
             }
         },
         Some(Commands::Track {
@@ -270,13 +1214,51 @@ async fn main() -> Result<()> {
             all,
             force,
             remote,
+            base,
+        }) => {
+            cli::run_track(
+                &path,
+                &bookmarks,
+                cli::TrackOptions {
+                    all,
+                    force,
+                    remote,
+                    base,
+                },
+            )
+            .await?;
+        }
+        Some(Commands::Export {
+            cached,
+            output,
+            remote,
         }) => {
-            cli::run_track(&path, &bookmarks, cli::TrackOptions { all, force, remote }).await?;
+            cli::run_export(
+                &path,
+                remote.as_deref(),
+                cli::ExportOptions { cached, output },
+            )
+            .await?;
         }
-        Some(Commands::Untrack { bookmarks, all }) => {
-            cli::run_untrack(&path, &bookmarks, cli::UntrackOptions { all }).await?;
+        Some(Commands::Import { source }) => {
+            let (source, dry_run) = match source {
+                ImportSource::Graphite { dry_run } => (cli::ImportSource::Graphite, dry_run),
+                ImportSource::Spr { dry_run } => (cli::ImportSource::Spr, dry_run),
+                ImportSource::Branchless { dry_run } => (cli::ImportSource::Branchless, dry_run),
+            };
+            cli::run_import(&path, source, cli::ImportOptions { dry_run }).await?;
+        }
+        Some(Commands::Untrack {
+            bookmarks,
+            all,
+            forget_pr,
+        }) => {
+            cli::run_untrack(&path, &bookmarks, cli::UntrackOptions { all, forget_pr }).await?;
         }
     }
 
+    #[cfg(feature = "self-update")]
+    cli::check_for_update(&path).await;
+
     Ok(())
 }
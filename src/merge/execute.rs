@@ -8,9 +8,10 @@ use crate::error::Result;
 use crate::merge::plan::{MergeConfidence, MergePlan, MergeStep};
 use crate::platform::PlatformService;
 use crate::submit::ProgressCallback;
+use serde::{Deserialize, Serialize};
 
 /// Result of merge execution
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MergeExecutionResult {
     /// Bookmarks that were successfully merged
     pub merged_bookmarks: Vec<String>,
@@ -58,12 +59,15 @@ impl MergeExecutionResult {
 ///
 /// # Returns
 /// A `MergeExecutionResult` with the outcome of the execution
+#[allow(clippy::too_many_lines)]
 pub async fn execute_merge(
     plan: &MergePlan,
     platform: &dyn PlatformService,
     progress: &dyn ProgressCallback,
 ) -> Result<MergeExecutionResult> {
     let mut result = MergeExecutionResult::default();
+    // Bookmark whose branch was just updated; its checks start over
+    let mut updated: Option<&String> = None;
 
     for step in &plan.steps {
         match step {
@@ -71,6 +75,7 @@ pub async fn execute_merge(
                 bookmark,
                 pr_number,
                 pr_title,
+                pr_body,
                 method,
                 confidence,
             } => {
@@ -78,7 +83,10 @@ pub async fn execute_merge(
                     .on_message(&format!("🔀 Merging PR #{pr_number}: {pr_title}"))
                     .await;
 
-                match platform.merge_pr(*pr_number, *method).await {
+                match platform
+                    .merge_pr(*pr_number, pr_title, pr_body.as_deref(), *method)
+                    .await
+                {
                     Ok(merge_result) if merge_result.merged => {
                         let sha_display = merge_result.sha.as_deref().unwrap_or("(no sha)");
                         progress
@@ -96,7 +104,11 @@ pub async fn execute_merge(
                     }
                     Err(e) => {
                         result.failed_bookmark = Some(bookmark.clone());
-                        result.error_message = Some(e.to_string());
+                        result.error_message = Some(if updated == Some(bookmark) {
+                            format!("{e} (branch was just updated; re-run once CI passes)")
+                        } else {
+                            e.to_string()
+                        });
                         result.was_uncertain =
                             matches!(confidence, MergeConfidence::Uncertain(_));
                         break;
@@ -133,6 +145,24 @@ pub async fn execute_merge(
                     }
                 }
             }
+            MergeStep::UpdateBranch {
+                bookmark,
+                pr_number,
+            } => {
+                progress
+                    .on_message(&format!(
+                        "⬆️ Updating PR #{pr_number} ({bookmark}) from its base"
+                    ))
+                    .await;
+
+                if let Err(e) = platform.update_pr_branch(*pr_number).await {
+                    result.failed_bookmark = Some(bookmark.clone());
+                    result.error_message = Some(format!("Update branch failed: {e}"));
+                    result.was_uncertain = false;
+                    break;
+                }
+                updated = Some(bookmark);
+            }
             MergeStep::Skip {
                 bookmark,
                 pr_number,
@@ -0,0 +1,167 @@
+//! Squash commit messages for `ryu merge --edit-message`
+//!
+//! The message is composed from the PR's title and body plus trailers from
+//! its commits, edited as text, and parsed back into a title and body.
+//! Lines starting with [`COMMENT_PREFIX`] are instructions and are dropped,
+//! like `JJ:` lines in jj's editor (`#` would clash with Markdown headings).
+
+use crate::types::LogEntry;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Prefix of instruction lines removed from an edited message
+pub const COMMENT_PREFIX: &str = "RYU:";
+
+/// A `Token: value` trailer line, e.g. `Co-authored-by: ...`
+static TRAILER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z][A-Za-z0-9-]*: \S").unwrap());
+
+/// Squash commit title and body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SquashMessage {
+    /// Commit title (the platform appends the PR number)
+    pub title: String,
+    /// Commit body
+    pub body: Option<String>,
+}
+
+/// Trailers of the given commits, in order and without duplicates
+///
+/// A trailer is a `Token: value` line in the last paragraph of a commit
+/// description, where every line of that paragraph is a trailer.
+pub fn commit_trailers(changes: &[LogEntry]) -> Vec<String> {
+    let mut trailers: Vec<String> = Vec::new();
+    for change in changes {
+        let description = change.description.trim();
+        let Some((_, last)) = description.rsplit_once("\n\n") else {
+            continue;
+        };
+        let lines: Vec<&str> = last.lines().map(str::trim).collect();
+        if !lines.iter().all(|line| TRAILER_RE.is_match(line)) {
+            continue;
+        }
+        for line in lines {
+            if !trailers.iter().any(|t| t == line) {
+                trailers.push(line.to_string());
+            }
+        }
+    }
+    trailers
+}
+
+/// Text to edit for PR `pr_number`'s squash commit
+///
+/// Trailers already present in the body aren't repeated.
+pub fn compose_squash_message(
+    message: &SquashMessage,
+    pr_number: u64,
+    bookmark: &str,
+    trailers: &[String],
+) -> String {
+    let body = message.body.as_deref().unwrap_or_default().trim();
+    let missing: Vec<&str> = trailers
+        .iter()
+        .map(String::as_str)
+        .filter(|trailer| !body.lines().any(|line| line.trim() == *trailer))
+        .collect();
+
+    let mut sections = vec![message.title.trim().to_string()];
+    if !body.is_empty() {
+        sections.push(body.to_string());
+    }
+    if !missing.is_empty() {
+        sections.push(missing.join("\n"));
+    }
+    sections.push(format!(
+        "{COMMENT_PREFIX} Squash commit message for PR #{pr_number} ({bookmark}).\n\
+         {COMMENT_PREFIX} The first line is the title; \"{COMMENT_PREFIX}\" lines are removed.\n\
+         {COMMENT_PREFIX} Leave the message empty to abort the merge."
+    ));
+    sections.join("\n\n") + "\n"
+}
+
+/// Parse an edited message, or None if it's empty
+pub fn parse_squash_message(text: &str) -> Option<SquashMessage> {
+    let kept: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.starts_with(COMMENT_PREFIX))
+        .collect();
+    let text = kept.join("\n");
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    let (title, body) = text.split_once('\n').unwrap_or((text, ""));
+    let body = body.trim();
+    Some(SquashMessage {
+        title: title.trim().to_string(),
+        body: (!body.is_empty()).then(|| body.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn change(description: &str) -> LogEntry {
+        LogEntry {
+            commit_id: "c".to_string(),
+            change_id: "z".to_string(),
+            author_name: "Alice".to_string(),
+            author_email: "alice@example.com".to_string(),
+            description_first_line: description.lines().next().unwrap_or_default().to_string(),
+            description: description.to_string(),
+            parents: vec![],
+            local_bookmarks: vec![],
+            remote_bookmarks: vec![],
+            is_working_copy: false,
+            authored_at: Utc::now(),
+            committed_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_commit_trailers_only_from_trailer_paragraphs() {
+        let changes = [
+            change("Add auth\n\nCo-authored-by: Bob <bob@example.com>\nFixes: #4"),
+            change("Add login\n\nNote: not a trailer paragraph\nbecause of this line"),
+            change("Add logout\n\nCo-authored-by: Bob <bob@example.com>"),
+            change("Tweak"),
+        ];
+        assert_eq!(
+            commit_trailers(&changes),
+            vec!["Co-authored-by: Bob <bob@example.com>", "Fixes: #4"]
+        );
+    }
+
+    #[test]
+    fn test_compose_and_parse_round_trip() {
+        let message = SquashMessage {
+            title: "Add auth".to_string(),
+            body: Some("## Summary\n\nAdds login.\n\nFixes: #4".to_string()),
+        };
+        let trailers = vec!["Fixes: #4".to_string(), "Co-authored-by: Bob".to_string()];
+
+        let text = compose_squash_message(&message, 12, "feat-a", &trailers);
+        assert!(text.starts_with(
+            "Add auth\n\n## Summary\n\nAdds login.\n\nFixes: #4\n\nCo-authored-by: Bob\n\nRYU: "
+        ));
+
+        assert_eq!(
+            parse_squash_message(&text),
+            Some(SquashMessage {
+                title: "Add auth".to_string(),
+                body: Some(
+                    "## Summary\n\nAdds login.\n\nFixes: #4\n\nCo-authored-by: Bob".to_string()
+                ),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_empty_message_aborts() {
+        assert_eq!(parse_squash_message("\nRYU: nothing here\n\n"), None);
+    }
+}
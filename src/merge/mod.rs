@@ -6,7 +6,14 @@
 //! 3. Execute - perform merges (effectful)
 
 mod execute;
+mod message;
 mod plan;
 
 pub use execute::{execute_merge, MergeExecutionResult};
-pub use plan::{create_merge_plan, MergeConfidence, MergePlan, MergePlanOptions, MergeStep, PrInfo};
+pub use message::{
+    commit_trailers, compose_squash_message, parse_squash_message, SquashMessage, COMMENT_PREFIX,
+};
+pub use plan::{
+    create_auto_merge_plan, create_merge_plan, MergeConfidence, MergePlan, MergePlanOptions,
+    MergeStep, PrInfo,
+};
@@ -5,6 +5,7 @@
 
 use crate::submit::SubmissionAnalysis;
 use crate::types::{MergeMethod, MergeReadiness, PullRequestDetails};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::hash::BuildHasher;
 
@@ -12,7 +13,7 @@ use std::hash::BuildHasher;
 ///
 /// This struct holds all the information needed to plan a merge,
 /// fetched beforehand by the CLI orchestrator.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PrInfo {
     /// Bookmark name this PR is associated with
     pub bookmark: String,
@@ -23,7 +24,8 @@ pub struct PrInfo {
 }
 
 /// Confidence level for a merge attempt
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MergeConfidence {
     /// All conditions verified - merge should succeed
     Certain,
@@ -32,7 +34,8 @@ pub enum MergeConfidence {
 }
 
 /// A single step in the merge plan
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum MergeStep {
     /// Merge this PR
     Merge {
@@ -40,8 +43,10 @@ pub enum MergeStep {
         bookmark: String,
         /// PR number
         pr_number: u64,
-        /// PR title (for display)
+        /// PR title (for display and the squash commit title)
         pr_title: String,
+        /// PR body (for the squash commit message)
+        pr_body: Option<String>,
         /// Merge method to use
         method: MergeMethod,
         /// Confidence level for this merge
@@ -61,6 +66,16 @@ pub enum MergeStep {
         /// New base branch (trunk)
         new_base: String,
     },
+    /// Bring this PR's branch up to date with its base before merging
+    ///
+    /// Needed on repos whose branch protection requires branches to be up to
+    /// date, which reject merging a PR that is behind its base.
+    UpdateBranch {
+        /// Bookmark name (for display)
+        bookmark: String,
+        /// PR number to update
+        pr_number: u64,
+    },
     /// Skip this PR (not ready to merge)
     Skip {
         /// Bookmark name
@@ -78,6 +93,7 @@ impl MergeStep {
         match self {
             Self::Merge { bookmark, .. }
             | Self::RetargetBase { bookmark, .. }
+            | Self::UpdateBranch { bookmark, .. }
             | Self::Skip { bookmark, .. } => bookmark,
         }
     }
@@ -106,6 +122,9 @@ impl std::fmt::Display for MergeStep {
             } => {
                 write!(f, "retarget PR #{pr_number}: {old_base} → {new_base}")
             }
+            Self::UpdateBranch { pr_number, .. } => {
+                write!(f, "update PR #{pr_number} from its base")
+            }
             Self::Skip {
                 pr_number,
                 bookmark,
@@ -122,11 +141,21 @@ impl std::fmt::Display for MergeStep {
 }
 
 /// Options for merge planning
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MergePlanOptions {
     /// Target bookmark (merge up to and including this bookmark)
     /// If None, merge all consecutive mergeable PRs
     pub target_bookmark: Option<String>,
+    /// Merge at most this many PRs, counted from trunk (None = no limit)
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Update PR branches that are behind their base before merging them
+    ///
+    /// PRs retargeted onto trunk during the merge are always updated, since
+    /// they lack the squash commit of the PR merged below them.
+    pub update_branch: bool,
+    /// How each PR is merged
+    pub method: MergeMethod,
 }
 
 /// Merge plan - the functional core output
@@ -134,7 +163,7 @@ pub struct MergePlanOptions {
 /// This is a pure data structure that describes what merge operations
 /// should be performed. Created by `create_merge_plan()` (pure)
 /// and executed by `execute_merge()` (effectful).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MergePlan {
     /// Ordered steps to perform (or skip)
     pub steps: Vec<MergeStep>,
@@ -173,6 +202,8 @@ impl MergePlan {
 /// After each merge (except the last), a `RetargetBase` step is inserted
 /// to retarget the next PR's base to trunk. This is necessary because
 /// GitHub's merge API merges into the PR's current base branch, not trunk.
+/// With `options.update_branch`, an `UpdateBranch` step precedes each merge
+/// of a PR that is behind its base.
 ///
 /// # Arguments
 /// * `analysis` - The submission analysis from `analyze_submission()`
@@ -183,6 +214,7 @@ impl MergePlan {
 /// # Returns
 /// A `MergePlan` describing the merge operations to perform
 #[must_use]
+#[allow(clippy::too_many_lines)]
 pub fn create_merge_plan<S: BuildHasher>(
     analysis: &SubmissionAnalysis,
     pr_info: &HashMap<String, PrInfo, S>,
@@ -237,11 +269,22 @@ pub fn create_merge_plan<S: BuildHasher>(
             continue;
         }
 
+        if options
+            .limit
+            .is_some_and(|limit| mergeable_indices.len() >= limit)
+        {
+            // Merged as many as asked - the rest stays for a later merge
+            if rebase_target.is_none() {
+                rebase_target = Some(bookmark_name.clone());
+            }
+            continue;
+        }
+
         if info.readiness.is_blocked() {
             steps.push(MergeStep::Skip {
                 bookmark: bookmark_name.clone(),
                 pr_number: info.details.number,
-                reasons: info.readiness.blocking_reasons.clone(),
+                reasons: blocking_reasons(&info.readiness),
             });
             hit_blocker = true;
             if rebase_target.is_none() {
@@ -251,18 +294,13 @@ pub fn create_merge_plan<S: BuildHasher>(
             // Track this as mergeable for retarget step insertion
             mergeable_indices.push(idx);
 
-            // Determine confidence based on uncertainty
-            let confidence = info
-                .readiness
-                .uncertainty()
-                .map_or(MergeConfidence::Certain, |reason| {
-                    MergeConfidence::Uncertain(reason.to_string())
-                });
+            let confidence = merge_confidence(info, options);
             steps.push(MergeStep::Merge {
                 bookmark: bookmark_name.clone(),
                 pr_number: info.details.number,
                 pr_title: info.details.title.clone(),
-                method: MergeMethod::Squash,
+                pr_body: info.details.body.clone(),
+                method: options.method,
                 confidence,
             });
             bookmarks_to_clear.push(bookmark_name.clone());
@@ -273,10 +311,24 @@ pub fn create_merge_plan<S: BuildHasher>(
     // We need to do this after collecting all steps because we need lookahead
     let mut final_steps = Vec::new();
     let mut merge_step_count = 0;
+    let mut retargeted: Option<String> = None;
 
     for step in steps {
         match &step {
-            MergeStep::Merge { .. } => {
+            MergeStep::Merge {
+                bookmark,
+                pr_number,
+                ..
+            } => {
+                let is_behind = pr_info.get(bookmark).is_some_and(|i| i.details.is_behind);
+                if options.update_branch
+                    && (is_behind || retargeted.as_ref() == Some(bookmark))
+                {
+                    final_steps.push(MergeStep::UpdateBranch {
+                        bookmark: bookmark.clone(),
+                        pr_number: *pr_number,
+                    });
+                }
                 final_steps.push(step);
                 merge_step_count += 1;
 
@@ -296,6 +348,7 @@ pub fn create_merge_plan<S: BuildHasher>(
                                 old_base: old_base.clone(),
                                 new_base: trunk_branch.to_string(),
                             });
+                            retargeted = Some(next_bookmark.clone());
                         }
                     }
                 }
@@ -303,8 +356,10 @@ pub fn create_merge_plan<S: BuildHasher>(
             MergeStep::Skip { .. } => {
                 final_steps.push(step);
             }
-            // RetargetBase steps are only created in this pass, never in pass 1
-            MergeStep::RetargetBase { .. } => unreachable!("RetargetBase not in initial steps"),
+            // RetargetBase and UpdateBranch steps are only created in this pass
+            MergeStep::RetargetBase { .. } | MergeStep::UpdateBranch { .. } => {
+                unreachable!("RetargetBase/UpdateBranch not in initial steps")
+            }
         }
     }
 
@@ -320,3 +375,111 @@ pub fn create_merge_plan<S: BuildHasher>(
         trunk_branch: trunk_branch.to_string(),
     }
 }
+
+/// Plan for `ryu merge --auto`: the PRs to enable auto-merge on (PURE)
+///
+/// Auto-merge lands a PR into its current base once checks pass, so only
+/// PRs based on trunk qualify; a stacked PR would land in its parent's
+/// branch. Pending checks don't block, since waiting for them is the point.
+/// Walks the stack from trunk and stops at the first PR that is blocked or
+/// stacked on an unmerged PR, which is recorded as a `Skip` step. Each PR
+/// is merged with `method`.
+#[must_use]
+pub fn create_auto_merge_plan<S: BuildHasher>(
+    analysis: &SubmissionAnalysis,
+    pr_info: &HashMap<String, PrInfo, S>,
+    trunk_branch: &str,
+    method: MergeMethod,
+) -> MergePlan {
+    let mut steps = Vec::new();
+    let mut rebase_target = None;
+
+    for segment in &analysis.segments {
+        let bookmark = &segment.bookmark.name;
+        let Some(info) = pr_info.get(bookmark) else {
+            continue;
+        };
+
+        let mut reasons = info.readiness.blocking_reasons.clone();
+        let failing = info.readiness.failing_checks();
+        if !failing.is_empty() {
+            reasons.push(format!("CI failing: {}", failing.join(", ")));
+        }
+        if info.details.base_ref != trunk_branch {
+            reasons.push(format!(
+                "Based on {}; enable auto-merge once it merges",
+                info.details.base_ref
+            ));
+        }
+
+        if !reasons.is_empty() {
+            steps.push(MergeStep::Skip {
+                bookmark: bookmark.clone(),
+                pr_number: info.details.number,
+                reasons,
+            });
+            rebase_target = Some(bookmark.clone());
+            break;
+        }
+
+        let pending = info.readiness.pending_checks();
+        let confidence = if pending.is_empty() {
+            MergeConfidence::Certain
+        } else {
+            MergeConfidence::Uncertain(format!("waiting for {}", pending.join(", ")))
+        };
+        steps.push(MergeStep::Merge {
+            bookmark: bookmark.clone(),
+            pr_number: info.details.number,
+            pr_title: info.details.title.clone(),
+            pr_body: info.details.body.clone(),
+            method,
+            confidence,
+        });
+    }
+
+    let has_actionable = steps.iter().any(|s| matches!(s, MergeStep::Merge { .. }));
+    MergePlan {
+        steps,
+        // Nothing merges right away
+        bookmarks_to_clear: Vec::new(),
+        rebase_target,
+        has_actionable,
+        trunk_branch: trunk_branch.to_string(),
+    }
+}
+
+/// Confidence that merging `info` succeeds, based on its uncertainties
+///
+/// A branch that is behind its base is likely rejected by strict branch
+/// protection unless it gets updated first.
+fn merge_confidence(info: &PrInfo, options: &MergePlanOptions) -> MergeConfidence {
+    if info.details.is_behind && !options.update_branch {
+        return MergeConfidence::Uncertain(format!(
+            "Branch is behind {} (use --update-branch)",
+            info.details.base_ref
+        ));
+    }
+    info.readiness
+        .uncertainty()
+        .map_or(MergeConfidence::Certain, |reason| {
+            MergeConfidence::Uncertain(reason.to_string())
+        })
+}
+
+/// Platform blocking reasons plus the required checks that haven't passed
+///
+/// Failing and still-running checks are each listed by name on one line,
+/// e.g. "CI failing: windows-test, lint".
+fn blocking_reasons(readiness: &MergeReadiness) -> Vec<String> {
+    let mut reasons = readiness.blocking_reasons.clone();
+    for (label, names) in [
+        ("CI failing", readiness.failing_checks()),
+        ("CI still running", readiness.pending_checks()),
+    ] {
+        if !names.is_empty() {
+            reasons.push(format!("{label}: {}", names.join(", ")));
+        }
+    }
+    reasons
+}
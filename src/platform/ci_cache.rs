@@ -0,0 +1,260 @@
+//! Short-lived cache for CI status lookups.
+//!
+//! Checking CI on GitHub takes several API calls per commit, and the same head
+//! commit is usually checked several times within a single command. Settled
+//! results are memoized per head SHA for a short time-to-live, and can
+//! optionally be persisted to disk so that back-to-back commands reuse them.
+//! Results with pending checks are never cached, so callers that poll see CI
+//! finish.
+
+use crate::types::{CheckInfo, CheckState};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tracing::debug;
+
+/// Environment variable holding the cross-command TTL in seconds.
+pub const CI_CACHE_TTL_ENV: &str = "RYU_CI_CACHE_TTL";
+
+/// Filename for the persisted cache within the user cache directory.
+const CI_CACHE_FILE: &str = "ci_status.json";
+
+/// How long in-memory entries stay fresh, in seconds.
+const IN_MEMORY_TTL_SECS: i64 = 30;
+
+/// A cached CI status result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedStatus {
+    checks: Vec<CheckInfo>,
+    checked_at: DateTime<Utc>,
+}
+
+/// Disk persistence settings.
+#[derive(Debug)]
+struct Persistence {
+    path: PathBuf,
+}
+
+/// Memoizes CI checks per commit SHA.
+///
+/// Keys should identify the commit uniquely across repositories, e.g.
+/// `github.com/owner/repo@<sha>`, plus anything else the result depends on.
+#[derive(Debug)]
+pub struct CiStatusCache {
+    entries: Mutex<HashMap<String, CachedStatus>>,
+    ttl: Duration,
+    persistence: Option<Persistence>,
+}
+
+impl CiStatusCache {
+    /// Create a cache that isn't persisted, reusing entries for a short TTL.
+    pub fn in_memory() -> Self {
+        Self::in_memory_with_ttl(Duration::seconds(IN_MEMORY_TTL_SECS))
+    }
+
+    /// Create a cache that isn't persisted, reusing entries younger than `ttl`.
+    pub fn in_memory_with_ttl(ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            ttl,
+            persistence: None,
+        }
+    }
+
+    /// Create a cache backed by a file, reusing entries younger than `ttl`.
+    pub fn persistent(path: PathBuf, ttl: Duration) -> Self {
+        let now = Utc::now();
+        let entries: HashMap<String, CachedStatus> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let entries = entries
+            .into_iter()
+            .filter(|(_, status)| now - status.checked_at < ttl)
+            .collect();
+
+        Self {
+            entries: Mutex::new(entries),
+            ttl,
+            persistence: Some(Persistence { path }),
+        }
+    }
+
+    /// Create a cache configured from the environment.
+    ///
+    /// If `RYU_CI_CACHE_TTL` is set to a positive number of seconds, results
+    /// are persisted in the user cache directory for that long. Otherwise the
+    /// cache is in-memory only, with a short TTL.
+    pub fn from_env() -> Self {
+        let ttl = std::env::var(CI_CACHE_TTL_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|secs| *secs > 0);
+
+        match (ttl, dirs::cache_dir()) {
+            (Some(secs), Some(dir)) => Self::persistent(
+                dir.join("jj-ryu").join(CI_CACHE_FILE),
+                Duration::seconds(secs),
+            ),
+            _ => Self::in_memory(),
+        }
+    }
+
+    /// Look up cached CI checks.
+    pub fn get(&self, key: &str) -> Option<Vec<CheckInfo>> {
+        let status = self.entries.lock().ok()?.get(key)?.clone();
+
+        if Utc::now() - status.checked_at >= self.ttl {
+            return None;
+        }
+
+        Some(status.checks)
+    }
+
+    /// Record CI checks for a commit.
+    ///
+    /// Results with pending checks aren't recorded, since they're about to
+    /// change.
+    pub fn insert(&self, key: &str, checks: Vec<CheckInfo>) {
+        if checks.iter().any(|c| c.state == CheckState::Pending) {
+            return;
+        }
+
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        entries.insert(
+            key.to_string(),
+            CachedStatus {
+                checks,
+                checked_at: Utc::now(),
+            },
+        );
+
+        if let Some(persistence) = &self.persistence {
+            persistence.save(&entries);
+        }
+    }
+}
+
+impl Default for CiStatusCache {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+impl Persistence {
+    /// Write entries to disk. Failures are logged and otherwise ignored,
+    /// since the cache is purely an optimization.
+    fn save(&self, entries: &HashMap<String, CachedStatus>) {
+        if let Some(dir) = self.path.parent()
+            && let Err(e) = fs::create_dir_all(dir)
+        {
+            debug!(path = %dir.display(), error = %e, "failed to create CI cache directory");
+            return;
+        }
+
+        let content = match serde_json::to_string(entries) {
+            Ok(content) => content,
+            Err(e) => {
+                debug!(error = %e, "failed to serialize CI cache");
+                return;
+            }
+        };
+
+        if let Err(e) = fs::write(&self.path, content) {
+            debug!(path = %self.path.display(), error = %e, "failed to write CI cache");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn check(state: CheckState) -> Vec<CheckInfo> {
+        vec![CheckInfo {
+            name: "build".to_string(),
+            state,
+            required: true,
+            url: None,
+        }]
+    }
+
+    #[test]
+    fn test_in_memory_roundtrip() {
+        let cache = CiStatusCache::in_memory();
+        assert_eq!(cache.get("github.com/o/r@abc"), None);
+
+        cache.insert("github.com/o/r@abc", check(CheckState::Success));
+        cache.insert("github.com/o/r@def", check(CheckState::Failure));
+
+        assert_eq!(cache.get("github.com/o/r@abc"), Some(check(CheckState::Success)));
+        assert_eq!(cache.get("github.com/o/r@def"), Some(check(CheckState::Failure)));
+    }
+
+    #[test]
+    fn test_pending_checks_not_cached() {
+        let cache = CiStatusCache::in_memory();
+
+        cache.insert("github.com/o/r@abc", check(CheckState::Pending));
+        assert_eq!(cache.get("github.com/o/r@abc"), None);
+
+        // Once CI finishes, the settled result is cached
+        cache.insert("github.com/o/r@abc", check(CheckState::Success));
+        assert_eq!(cache.get("github.com/o/r@abc"), Some(check(CheckState::Success)));
+    }
+
+    #[test]
+    fn test_in_memory_entries_expire() {
+        let cache = CiStatusCache::in_memory_with_ttl(Duration::zero());
+
+        cache.insert("github.com/o/r@abc", check(CheckState::Success));
+        assert_eq!(cache.get("github.com/o/r@abc"), None);
+    }
+
+    #[test]
+    fn test_persistent_shared_across_instances() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("nested").join(CI_CACHE_FILE);
+
+        let first = CiStatusCache::persistent(path.clone(), Duration::seconds(60));
+        first.insert("github.com/o/r@abc", check(CheckState::Success));
+        assert!(path.exists());
+
+        let second = CiStatusCache::persistent(path, Duration::seconds(60));
+        assert_eq!(second.get("github.com/o/r@abc"), Some(check(CheckState::Success)));
+    }
+
+    #[test]
+    fn test_persistent_expired_entries_ignored() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(CI_CACHE_FILE);
+
+        let stale = HashMap::from([(
+            "github.com/o/r@abc".to_string(),
+            CachedStatus {
+                checks: check(CheckState::Success),
+                checked_at: Utc::now() - Duration::seconds(120),
+            },
+        )]);
+        fs::write(&path, serde_json::to_string(&stale).unwrap()).unwrap();
+
+        let cache = CiStatusCache::persistent(path, Duration::seconds(60));
+        assert_eq!(cache.get("github.com/o/r@abc"), None);
+    }
+
+    #[test]
+    fn test_persistent_corrupt_file_starts_empty() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(CI_CACHE_FILE);
+        fs::write(&path, "not json").unwrap();
+
+        let cache = CiStatusCache::persistent(path, Duration::seconds(60));
+        assert_eq!(cache.get("github.com/o/r@abc"), None);
+    }
+}
@@ -1,8 +1,11 @@
 //! Platform detection from remote URLs
 
+use crate::config::HostPlatform;
 use crate::error::{Error, Result};
+use crate::platform::parse_registered_remote;
 use crate::types::{Platform, PlatformConfig};
 use regex::Regex;
+use std::collections::BTreeMap;
 use std::env;
 use std::sync::LazyLock;
 
@@ -14,13 +17,47 @@ static RE_SSH: LazyLock<Regex> =
 static RE_HTTPS: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"https?://[^/]+/(.+?)(?:\.git)?$").unwrap());
 
-/// Detect platform (GitHub or GitLab) from a remote URL
+/// Regex for SSH URLs with a scheme: `ssh://user@host:port/project`
+static RE_SSH_URL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"ssh://[^/]+/(.+?)(?:\.git)?$").unwrap());
+
+/// Gerrit's default SSH port
+const GERRIT_SSH_PORT: u16 = 29418;
+
+/// Detect platform (GitHub, GitLab or Gerrit) from a remote URL
+///
+/// Gerrit has no well-known host, so it is recognized by `GERRIT_HOST` or
+/// by its default SSH port. Registered backends are checked first.
 pub fn detect_platform(url: &str) -> Option<Platform> {
-    let gh_host = env::var("GH_HOST").ok();
-    let gitlab_host = env::var("GITLAB_HOST").ok();
+    detect_platform_with_hosts(url, &BTreeMap::new())
+}
+
+/// Detect platform from a remote URL, consulting the `[hosts]` config
+///
+/// A configured host takes precedence over the well-known hosts and the
+/// `GH_HOST`/`GITLAB_HOST`/`GERRIT_HOST` environment variables.
+pub fn detect_platform_with_hosts(
+    url: &str,
+    hosts: &BTreeMap<String, HostPlatform>,
+) -> Option<Platform> {
+    if let Some(config) = parse_registered_remote(url) {
+        return Some(config.platform);
+    }
 
     let hostname = extract_hostname(url)?;
 
+    if let Some(platform) = hosts.get(&hostname) {
+        return Some(match platform {
+            HostPlatform::GitHub => Platform::GitHub,
+            HostPlatform::GitLab => Platform::GitLab,
+            HostPlatform::Gerrit => Platform::Gerrit,
+        });
+    }
+
+    let gh_host = env::var("GH_HOST").ok();
+    let gitlab_host = env::var("GITLAB_HOST").ok();
+    let gerrit_host = env::var("GERRIT_HOST").ok();
+
     // Check GitHub
     if hostname == "github.com"
         || hostname.ends_with(".github.com")
@@ -37,27 +74,56 @@ pub fn detect_platform(url: &str) -> Option<Platform> {
         return Some(Platform::GitLab);
     }
 
+    // Check Gerrit
+    let gerrit_port = url::Url::parse(url)
+        .ok()
+        .is_some_and(|u| u.scheme() == "ssh" && u.port() == Some(GERRIT_SSH_PORT));
+    if gerrit_port || gerrit_host.as_ref().is_some_and(|h| hostname == *h) {
+        return Some(Platform::Gerrit);
+    }
+
     None
 }
 
 /// Parse repository info (owner/repo) from a remote URL
 pub fn parse_repo_info(url: &str) -> Result<PlatformConfig> {
+    parse_repo_info_with_hosts(url, &BTreeMap::new())
+}
+
+/// Parse repository info from a remote URL, consulting the `[hosts]` config
+pub fn parse_repo_info_with_hosts(
+    url: &str,
+    hosts: &BTreeMap<String, HostPlatform>,
+) -> Result<PlatformConfig> {
     // Normalize: strip trailing slashes
     let url = url.trim_end_matches('/');
 
-    let platform = detect_platform(url).ok_or(Error::NoSupportedRemotes)?;
+    if let Some(config) = parse_registered_remote(url) {
+        return Ok(config);
+    }
+
+    let platform = detect_platform_with_hosts(url, hosts).ok_or(Error::NoSupportedRemotes)?;
     let hostname = extract_hostname(url);
 
-    let path = RE_SSH
+    let path = RE_SSH_URL
         .captures(url)
+        .or_else(|| RE_SSH.captures(url))
         .or_else(|| RE_HTTPS.captures(url))
         .and_then(|c| c.get(1))
         .map(|m| m.as_str())
         .ok_or_else(|| Error::Parse(format!("cannot parse remote URL: {url}")))?;
 
-    // Split path into owner and repo (GitLab supports nested groups)
+    // Gerrit serves authenticated HTTP under `/a/`, which isn't part of the
+    // project name
+    let path = match platform {
+        Platform::Gerrit => path.strip_prefix("a/").unwrap_or(path),
+        Platform::GitHub | Platform::GitLab | Platform::Custom(_) => path,
+    };
+
+    // Split path into owner and repo (GitLab supports nested groups). Gerrit
+    // project names may be a single segment.
     let parts: Vec<&str> = path.split('/').collect();
-    if parts.len() < 2 {
+    if parts.len() < 2 && platform != Platform::Gerrit {
         return Err(Error::Parse(format!("invalid repo path: {path}")));
     }
 
@@ -80,6 +146,7 @@ pub fn parse_repo_info(url: &str) -> Result<PlatformConfig> {
                 None
             }
         }
+        Platform::Gerrit | Platform::Custom(_) => hostname,
     };
 
     Ok(PlatformConfig {
@@ -90,7 +157,8 @@ pub fn parse_repo_info(url: &str) -> Result<PlatformConfig> {
     })
 }
 
-fn extract_hostname(url: &str) -> Option<String> {
+/// Hostname of a remote URL (SSH `git@host:path` or any URL with a host)
+pub fn extract_hostname(url: &str) -> Option<String> {
     // SSH format
     if url.starts_with("git@") {
         return url
@@ -149,4 +217,31 @@ mod tests {
         assert_eq!(config.owner, "group/subgroup");
         assert_eq!(config.repo, "repo");
     }
+
+    #[test]
+    fn test_detect_gerrit_ssh_port() {
+        let config = parse_repo_info("ssh://alice@review.example.org:29418/tools/ryu").unwrap();
+        assert_eq!(config.platform, Platform::Gerrit);
+        assert_eq!(config.owner, "tools");
+        assert_eq!(config.repo, "ryu");
+        assert_eq!(config.host.as_deref(), Some("review.example.org"));
+    }
+
+    #[test]
+    fn test_configured_host() {
+        let hosts = BTreeMap::from([("git.corp.com".to_string(), HostPlatform::GitLab)]);
+        let config = parse_repo_info_with_hosts("git@git.corp.com:team/app.git", &hosts).unwrap();
+        assert_eq!(config.platform, Platform::GitLab);
+        assert_eq!(config.owner, "team");
+        assert_eq!(config.host.as_deref(), Some("git.corp.com"));
+
+        assert_eq!(detect_platform("git@git.corp.com:team/app.git"), None);
+    }
+
+    #[test]
+    fn test_parse_gerrit_single_segment_project() {
+        let config = parse_repo_info("ssh://review.example.org:29418/ryu.git").unwrap();
+        assert_eq!(config.owner, "");
+        assert_eq!(config.repo, "ryu");
+    }
 }
@@ -1,34 +1,70 @@
 //! Platform service factory
 //!
-//! Creates platform services based on configuration.
+//! Creates platform services based on configuration. Platforms registered
+//! by other crates create their own services.
 
-use crate::auth::{get_github_auth, get_gitlab_auth};
-use crate::error::Result;
-use crate::platform::{GitHubService, GitLabService, PlatformService};
+use crate::auth::{get_gerrit_auth, get_github_auth_for_host, get_gitlab_auth_for_host};
+use crate::config::AuthConfig;
+use crate::error::{Error, Result};
+use crate::platform::{
+    GerritService, GitHubService, GitLabService, PlatformService, registered_platform,
+};
 use crate::types::{Platform, PlatformConfig};
 
 /// Create a platform service from configuration
 ///
-/// Handles authentication and client construction for both GitHub and GitLab.
+/// Handles authentication and client construction for each platform. GitHub
+/// and GitLab tokens are checked for the scopes ryu needs, so a token that
+/// can't manage PRs fails here rather than halfway through a submission.
 pub async fn create_platform_service(config: &PlatformConfig) -> Result<Box<dyn PlatformService>> {
+    create_platform_service_with_auth(config, &AuthConfig::default()).await
+}
+
+/// Create a platform service, authenticating as the account the `[auth]`
+/// config maps the host to, if any
+pub async fn create_platform_service_with_auth(
+    config: &PlatformConfig,
+    auth_config: &AuthConfig,
+) -> Result<Box<dyn PlatformService>> {
     match config.platform {
         Platform::GitHub => {
-            let auth = get_github_auth().await?;
-            Ok(Box::new(GitHubService::new(
+            let auth = get_github_auth_for_host(config.host.as_deref(), auth_config).await?;
+            let service = GitHubService::new(
                 &auth.token,
                 config.owner.clone(),
                 config.repo.clone(),
                 config.host.clone(),
-            )?))
+            )?;
+            service.validate_token().await?;
+            Ok(Box::new(service.detect_ghes_version().await))
         }
         Platform::GitLab => {
-            let auth = get_gitlab_auth(config.host.as_deref()).await?;
-            Ok(Box::new(GitLabService::new(
+            let auth = get_gitlab_auth_for_host(config.host.as_deref(), auth_config).await?;
+            let service = GitLabService::new(
                 auth.token.clone(),
                 config.owner.clone(),
                 config.repo.clone(),
                 Some(auth.host),
+            )?
+            .with_token_kind(auth.kind);
+            service.validate_token().await?;
+            Ok(Box::new(service))
+        }
+        Platform::Gerrit => {
+            let auth = get_gerrit_auth(config.host.as_deref()).await?;
+            Ok(Box::new(GerritService::new(
+                auth.username,
+                auth.password,
+                config.owner.clone(),
+                config.repo.clone(),
+                auth.host,
             )?))
         }
+        Platform::Custom(name) => {
+            let backend = registered_platform(name).ok_or_else(|| {
+                Error::Platform(format!("no platform backend registered as '{name}'"))
+            })?;
+            backend.create_service(config).await
+        }
     }
 }
@@ -0,0 +1,938 @@
+//! Gerrit platform service implementation
+//!
+//! Gerrit reviews commits, not branches: every commit pushed to
+//! `refs/for/<branch>` becomes a change (or a new patch set of the change
+//! named by its `Change-Id` trailer). Here a "PR" is a Gerrit change, its
+//! number is the change number, and its head is the Change-Id. Changes are
+//! created by pushing (see [`crate::submit::create_gerrit_plan`]), not
+//! through the API.
+
+use crate::auth::GERRIT_JSON_PREFIX;
+use crate::error::{Error, Result};
+use crate::platform::{PlatformService, RateLimitCheck, changes_requested_by};
+use crate::types::{
+    CheckInfo, CheckState, MergeMethod, MergeReadiness, MergeResult, Platform, PlatformConfig,
+    PrComment, PrReview, PrState, PullRequest, PullRequestDetails, ReviewState, ReviewThread,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Label carrying review votes
+const CODE_REVIEW_LABEL: &str = "Code-Review";
+
+/// Label CI systems vote on
+const VERIFIED_LABEL: &str = "Verified";
+
+/// Default request timeout in seconds
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Gerrit service using reqwest
+pub struct GerritService {
+    client: Client,
+    username: String,
+    password: String,
+    /// Authenticated REST base URL (`https://{host}/a`)
+    api_url: String,
+    /// Web UI base URL (`https://{host}`)
+    web_url: String,
+    config: PlatformConfig,
+    project: String,
+}
+
+#[derive(Deserialize)]
+struct ChangeInfo {
+    #[serde(rename = "_number")]
+    number: u64,
+    change_id: String,
+    branch: String,
+    subject: String,
+    status: String, // "NEW", "MERGED", "ABANDONED"
+    #[serde(default)]
+    work_in_progress: bool,
+    #[serde(default)]
+    mergeable: Option<bool>,
+    #[serde(default)]
+    submittable: Option<bool>,
+    #[serde(default)]
+    current_revision: Option<String>,
+    #[serde(default)]
+    revisions: HashMap<String, RevisionInfo>,
+    #[serde(default)]
+    labels: HashMap<String, LabelInfo>,
+}
+
+#[derive(Deserialize)]
+struct RevisionInfo {
+    commit: Option<CommitInfo>,
+}
+
+#[derive(Deserialize)]
+struct CommitInfo {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct LabelInfo {
+    #[serde(default)]
+    approved: Option<AccountInfo>,
+    #[serde(default)]
+    rejected: Option<AccountInfo>,
+    #[serde(default)]
+    blocking: bool,
+    #[serde(default)]
+    all: Vec<ApprovalInfo>,
+}
+
+#[derive(Deserialize)]
+struct AccountInfo {
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+impl AccountInfo {
+    fn display_name(self) -> String {
+        self.username
+            .or(self.name)
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+}
+
+#[derive(Deserialize)]
+struct ApprovalInfo {
+    #[serde(flatten)]
+    account: AccountInfo,
+    #[serde(default)]
+    value: Option<i32>,
+    #[serde(default)]
+    date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChangeMessageInfo {
+    message: String,
+    #[serde(default)]
+    author: Option<AccountInfo>,
+}
+
+/// An inline comment (from the `/comments` endpoint)
+#[derive(Deserialize)]
+struct CommentInfo {
+    id: String,
+    #[serde(default)]
+    in_reply_to: Option<String>,
+    #[serde(default)]
+    line: Option<u64>,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    unresolved: bool,
+    #[serde(default)]
+    author: Option<AccountInfo>,
+    #[serde(default)]
+    updated: String,
+}
+
+/// File path Gerrit uses for comments on the change as a whole
+const PATCHSET_LEVEL_PATH: &str = "/PATCHSET_LEVEL";
+
+/// Group a change's inline comments (keyed by file) into threads
+///
+/// Each thread is its file and its comments, oldest first; a thread's ID is
+/// its first comment's.
+fn group_comment_threads(
+    comments: HashMap<String, Vec<CommentInfo>>,
+) -> Vec<(String, Vec<CommentInfo>)> {
+    let mut threads = Vec::new();
+    for (path, comments) in comments {
+        let parents: HashMap<String, Option<String>> = comments
+            .iter()
+            .map(|c| (c.id.clone(), c.in_reply_to.clone()))
+            .collect();
+        let root_of = |id: &str| {
+            let mut root = id.to_string();
+            // Bounded, in case of a reply cycle
+            for _ in 0..parents.len() {
+                match parents.get(&root) {
+                    Some(Some(parent)) if parents.contains_key(parent) => root.clone_from(parent),
+                    _ => break,
+                }
+            }
+            root
+        };
+
+        let mut by_root: HashMap<String, Vec<CommentInfo>> = HashMap::new();
+        for comment in comments {
+            by_root
+                .entry(root_of(&comment.id))
+                .or_default()
+                .push(comment);
+        }
+        for (_, mut thread) in by_root {
+            thread.sort_by(|a, b| a.updated.cmp(&b.updated));
+            threads.push((path.clone(), thread));
+        }
+    }
+    threads
+        .sort_by(|(a_path, a), (b_path, b)| (a_path, &a[0].updated).cmp(&(b_path, &b[0].updated)));
+    threads
+}
+
+/// A comment thread as a [`ReviewThread`] (resolved if its latest comment is)
+fn review_thread(path: String, comments: Vec<CommentInfo>) -> ReviewThread {
+    let is_resolved = comments.last().is_some_and(|c| !c.unresolved);
+    let comment_count = comments.len();
+    let first = comments.into_iter().next();
+    ReviewThread {
+        id: first.as_ref().map(|c| c.id.clone()).unwrap_or_default(),
+        path: (path != PATCHSET_LEVEL_PATH).then_some(path),
+        line: first.as_ref().and_then(|c| c.line),
+        author: first
+            .as_ref()
+            .and_then(|c| c.author.as_ref())
+            .and_then(|a| a.username.clone().or_else(|| a.name.clone()))
+            .unwrap_or_else(|| "unknown".to_string()),
+        body: first.map(|c| c.message).unwrap_or_default(),
+        comment_count,
+        is_resolved,
+    }
+}
+
+/// Parse a Gerrit timestamp ("2024-01-02 03:04:05.000000000", UTC)
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// Map a review vote to a review state (None = no verdict)
+///
+/// +2 approves, +1 is an opinion, and any negative vote asks for changes.
+const fn review_state(value: i32) -> Option<ReviewState> {
+    match value {
+        2.. => Some(ReviewState::Approved),
+        1 => Some(ReviewState::Commented),
+        0 => None,
+        _ => Some(ReviewState::ChangesRequested),
+    }
+}
+
+impl GerritService {
+    /// Create a new Gerrit service
+    ///
+    /// `owner` may be empty for single-segment project names.
+    pub fn new(
+        username: String,
+        password: String,
+        owner: String,
+        repo: String,
+        host: String,
+    ) -> Result<Self> {
+        let web_url = format!("https://{host}");
+        Self::build(username, password, owner, repo, host, web_url)
+    }
+
+    /// Create a Gerrit service that sends every API request to `base_url`
+    ///
+    /// `base_url` is the server root, without the `/a` prefix. Used to point
+    /// the service at a local replay server.
+    pub fn with_base_url(
+        username: String,
+        password: String,
+        owner: String,
+        repo: String,
+        base_url: &str,
+    ) -> Result<Self> {
+        let web_url = base_url.trim_end_matches('/').to_string();
+        let host = url::Url::parse(&web_url)?
+            .host_str()
+            .unwrap_or_default()
+            .to_string();
+        Self::build(username, password, owner, repo, host, web_url)
+    }
+
+    fn build(
+        username: String,
+        password: String,
+        owner: String,
+        repo: String,
+        host: String,
+        web_url: String,
+    ) -> Result<Self> {
+        let project = if owner.is_empty() {
+            repo.clone()
+        } else {
+            format!("{owner}/{repo}")
+        };
+
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| Error::GerritApi(format!("failed to create HTTP client: {e}")))?;
+
+        Ok(Self {
+            client,
+            username,
+            password,
+            api_url: format!("{web_url}/a"),
+            web_url,
+            config: PlatformConfig {
+                platform: Platform::Gerrit,
+                owner,
+                repo,
+                host: Some(host),
+            },
+            project,
+        })
+    }
+
+    /// URL path of a change, identified by project and number
+    fn change_path(&self, number: u64) -> String {
+        format!(
+            "/changes/{}~{number}",
+            urlencoding::encode(&self.project)
+        )
+    }
+
+    fn change_url(&self, number: u64) -> String {
+        format!("{}/c/{}/+/{number}", self.web_url, self.project)
+    }
+
+    fn to_pull_request(&self, change: ChangeInfo) -> PullRequest {
+        PullRequest {
+            number: change.number,
+            html_url: self.change_url(change.number),
+            base_ref: change.branch,
+            head_ref: change.change_id,
+            title: change.subject,
+            node_id: None,
+            is_draft: change.work_in_progress,
+        }
+    }
+
+    /// Send a request, returning the response body
+    ///
+    /// Server errors stay [`Error::Http`] so they are retried (see
+    /// [`super::RetryingPlatformService`]); other failures carry Gerrit's
+    /// plain-text error message.
+    async fn send(&self, request: RequestBuilder) -> Result<String> {
+        let response = request
+            .basic_auth(&self.username, Some(&self.password))
+            .send()
+            .await?
+            .check_rate_limit("Gerrit")?;
+        if let Err(e) = response.error_for_status_ref() {
+            if response.status().is_server_error() {
+                return Err(Error::Http(e));
+            }
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            return Err(Error::GerritApi(format!("{status}: {}", message.trim())));
+        }
+        Ok(response.text().await?)
+    }
+
+    async fn send_json<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        let text = self.send(request).await?;
+        Ok(serde_json::from_str(
+            text.trim_start_matches(GERRIT_JSON_PREFIX),
+        )?)
+    }
+
+    /// A change's inline comment threads (see [`group_comment_threads`])
+    async fn comment_threads(&self, number: u64) -> Result<Vec<(String, Vec<CommentInfo>)>> {
+        let url = format!("{}{}/comments", self.api_url, self.change_path(number));
+        let comments: HashMap<String, Vec<CommentInfo>> =
+            self.send_json(self.client.get(&url)).await?;
+        Ok(group_comment_threads(comments))
+    }
+
+    async fn get_change(&self, number: u64, options: &[&str]) -> Result<ChangeInfo> {
+        let query: Vec<(&str, &str)> = options.iter().map(|o| ("o", *o)).collect();
+        let url = format!("{}{}", self.api_url, self.change_path(number));
+        self.send_json(self.client.get(&url).query(&query)).await
+    }
+
+    async fn query_changes(&self, query: &str, options: &[&str]) -> Result<Vec<ChangeInfo>> {
+        let mut params = vec![("q", query)];
+        params.extend(options.iter().map(|o| ("o", *o)));
+        let url = format!("{}/changes/", self.api_url);
+        self.send_json(self.client.get(&url).query(&params)).await
+    }
+
+    /// Checks from the Verified label (empty if the project doesn't use it)
+    fn verified_checks(&self, change: &ChangeInfo) -> Vec<CheckInfo> {
+        let Some(label) = change.labels.get(VERIFIED_LABEL) else {
+            return Vec::new();
+        };
+        let state = if label.rejected.is_some() {
+            CheckState::Failure
+        } else if label.approved.is_some() {
+            CheckState::Success
+        } else {
+            CheckState::Pending
+        };
+        vec![CheckInfo {
+            name: VERIFIED_LABEL.to_string(),
+            state,
+            required: true,
+            url: Some(self.change_url(change.number)),
+        }]
+    }
+
+    fn unsupported(operation: &str) -> Error {
+        Error::Unsupported(format!("{operation} is not supported on Gerrit"))
+    }
+}
+
+#[async_trait]
+impl PlatformService for GerritService {
+    async fn find_existing_pr(&self, head_branch: &str) -> Result<Option<PullRequest>> {
+        debug!(change_id = head_branch, "finding existing change");
+        let query = format!(
+            "change:{head_branch} project:{} status:open",
+            self.project
+        );
+        let changes = self.query_changes(&query, &[]).await?;
+
+        let result = changes
+            .into_iter()
+            .next()
+            .map(|change| self.to_pull_request(change));
+        if let Some(ref pr) = result {
+            debug!(change = pr.number, "found existing change");
+        } else {
+            debug!("no existing change found");
+        }
+        Ok(result)
+    }
+
+    async fn find_closed_pr(&self, head_branch: &str) -> Result<Option<PullRequest>> {
+        debug!(change_id = head_branch, "finding abandoned change");
+        let query = format!(
+            "change:{head_branch} project:{} status:abandoned",
+            self.project
+        );
+        let changes = self.query_changes(&query, &[]).await?;
+
+        Ok(changes
+            .into_iter()
+            .next()
+            .map(|change| self.to_pull_request(change)))
+    }
+
+    async fn find_pr_by_body(&self, _text: &str) -> Result<Option<PullRequest>> {
+        // Changes are found by their Change-Id, which survives any rename
+        Ok(None)
+    }
+
+    async fn create_pr_with_options(
+        &self,
+        _head: &str,
+        _base: &str,
+        _title: &str,
+        _body: Option<&str>,
+        _draft: bool,
+    ) -> Result<PullRequest> {
+        Err(Error::Unsupported(
+            "Gerrit changes are created by pushing to refs/for/<branch>".to_string(),
+        ))
+    }
+
+    async fn update_pr_base(&self, pr_number: u64, new_base: &str) -> Result<PullRequest> {
+        debug!(change = pr_number, new_base, "moving change");
+        let url = format!("{}{}/move", self.api_url, self.change_path(pr_number));
+        let change: ChangeInfo = self
+            .send_json(
+                self.client
+                    .post(&url)
+                    .json(&serde_json::json!({ "destination_branch": new_base })),
+            )
+            .await?;
+
+        debug!(change = pr_number, "moved change");
+        Ok(self.to_pull_request(change))
+    }
+
+    async fn update_pr_title(&self, _pr_number: u64, _title: &str) -> Result<PullRequest> {
+        // The subject is the commit message's first line and follows each push
+        Err(Error::Unsupported(
+            "Gerrit change subjects are commit messages; edit them with 'jj describe'"
+                .to_string(),
+        ))
+    }
+
+    async fn update_pr_body(&self, _pr_number: u64, _body: &str) -> Result<()> {
+        // The description is the commit message, which would diverge from
+        // the local commit
+        Err(Error::Unsupported(
+            "Gerrit change descriptions are commit messages; edit them with 'jj describe'"
+                .to_string(),
+        ))
+    }
+
+    async fn add_pr_labels(&self, pr_number: u64, labels: &[String]) -> Result<()> {
+        // Gerrit labels are votes; hashtags are the free-form equivalent
+        debug!(change = pr_number, ?labels, "adding change hashtags");
+        let url = format!("{}{}/hashtags", self.api_url, self.change_path(pr_number));
+        self.send(
+            self.client
+                .post(&url)
+                .json(&serde_json::json!({ "add": labels })),
+        )
+        .await?;
+
+        debug!(change = pr_number, "added change hashtags");
+        Ok(())
+    }
+
+    async fn assign_pr(&self, _pr_number: u64, _assignees: &[String]) -> Result<()> {
+        Err(Error::Unsupported(
+            "Gerrit changes have no assignees; add reviewers instead".to_string(),
+        ))
+    }
+
+    async fn request_review(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        debug!(change = pr_number, ?reviewers, "adding change reviewers");
+        let url = format!("{}{}/reviewers", self.api_url, self.change_path(pr_number));
+        for reviewer in reviewers {
+            self.send(
+                self.client
+                    .post(&url)
+                    .json(&serde_json::json!({ "reviewer": reviewer })),
+            )
+            .await?;
+        }
+
+        debug!(change = pr_number, "added change reviewers");
+        Ok(())
+    }
+
+    async fn set_pr_milestone(&self, _pr_number: u64, _milestone: &str) -> Result<()> {
+        Err(Error::Unsupported(
+            "Gerrit has no milestones; use labels (hashtags) instead".to_string(),
+        ))
+    }
+
+    async fn publish_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        debug!(change = pr_number, "marking change ready for review");
+        let url = format!("{}{}/ready", self.api_url, self.change_path(pr_number));
+        self.send(self.client.post(&url)).await?;
+
+        let change = self.get_change(pr_number, &[]).await?;
+        debug!(change = pr_number, "marked change ready for review");
+        Ok(self.to_pull_request(change))
+    }
+
+    async fn unpublish_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        debug!(change = pr_number, "marking change work in progress");
+        let url = format!("{}{}/wip", self.api_url, self.change_path(pr_number));
+        self.send(self.client.post(&url)).await?;
+
+        let change = self.get_change(pr_number, &[]).await?;
+        debug!(change = pr_number, "marked change work in progress");
+        Ok(self.to_pull_request(change))
+    }
+
+    async fn close_pr(&self, pr_number: u64) -> Result<()> {
+        debug!(change = pr_number, "abandoning change");
+        let url = format!("{}{}/abandon", self.api_url, self.change_path(pr_number));
+        self.send(self.client.post(&url)).await?;
+
+        debug!(change = pr_number, "abandoned change");
+        Ok(())
+    }
+
+    async fn reopen_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        debug!(change = pr_number, "restoring change");
+        let url = format!("{}{}/restore", self.api_url, self.change_path(pr_number));
+        let change: ChangeInfo = self.send_json(self.client.post(&url)).await?;
+
+        debug!(change = pr_number, "restored change");
+        Ok(self.to_pull_request(change))
+    }
+
+    async fn list_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>> {
+        debug!(change = pr_number, "listing change messages");
+        let url = format!("{}{}/messages", self.api_url, self.change_path(pr_number));
+        let messages: Vec<ChangeMessageInfo> = self.send_json(self.client.get(&url)).await?;
+
+        // Gerrit message IDs aren't numeric and messages can't be edited,
+        // so number them by position
+        let comments: Vec<PrComment> = messages
+            .into_iter()
+            .filter(|m| m.author.is_some())
+            .zip(1..)
+            .map(|(m, id)| PrComment {
+                id,
+                body: m.message,
+            })
+            .collect();
+        debug!(
+            change = pr_number,
+            count = comments.len(),
+            "listed change messages"
+        );
+        Ok(comments)
+    }
+
+    async fn create_pr_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        debug!(change = pr_number, "posting change message");
+        let url = format!(
+            "{}{}/revisions/current/review",
+            self.api_url,
+            self.change_path(pr_number)
+        );
+        self.send(
+            self.client
+                .post(&url)
+                .json(&serde_json::json!({ "message": body })),
+        )
+        .await?;
+
+        debug!(change = pr_number, "posted change message");
+        Ok(())
+    }
+
+    async fn update_pr_comment(&self, _pr_number: u64, _comment_id: u64, _body: &str) -> Result<()> {
+        Err(Self::unsupported("Editing change messages"))
+    }
+
+    async fn delete_pr_comment(&self, _pr_number: u64, _comment_id: u64) -> Result<()> {
+        Err(Self::unsupported("Deleting change messages"))
+    }
+
+    async fn current_user(&self) -> Result<String> {
+        let url = format!("{}/accounts/self", self.api_url);
+        let account: AccountInfo = self.send_json(self.client.get(&url)).await?;
+        Ok(account.display_name())
+    }
+
+    async fn list_protected_branches(&self) -> Result<Vec<String>> {
+        // Changes are pushed to refs/for/<branch>, never to branches
+        Ok(Vec::new())
+    }
+
+    fn config(&self) -> &PlatformConfig {
+        &self.config
+    }
+
+    // =========================================================================
+    // Merge-related methods
+    // =========================================================================
+
+    async fn get_pr_details(&self, pr_number: u64) -> Result<PullRequestDetails> {
+        debug!(change = pr_number, "getting change details");
+        let mut change = self
+            .get_change(pr_number, &["CURRENT_REVISION", "CURRENT_COMMIT"])
+            .await?;
+
+        let state = match change.status.as_str() {
+            "NEW" => PrState::Open,
+            "MERGED" => PrState::Merged,
+            _ => PrState::Closed,
+        };
+        let head_sha = change.current_revision.clone().unwrap_or_default();
+        // The description is the commit message after its subject line
+        let body = change
+            .revisions
+            .remove(&head_sha)
+            .and_then(|revision| revision.commit)
+            .and_then(|commit| {
+                let (_, rest) = commit.message.split_once('\n')?;
+                let rest = rest.trim();
+                (!rest.is_empty()).then(|| rest.to_string())
+            });
+
+        let details = PullRequestDetails {
+            number: change.number,
+            title: change.subject,
+            body,
+            state,
+            is_draft: change.work_in_progress,
+            mergeable: change.mergeable,
+            head_ref: change.change_id,
+            head_sha,
+            base_ref: change.branch,
+            html_url: self.change_url(change.number),
+            is_behind: false,
+        };
+
+        debug!(change = pr_number, state = ?details.state, "got change details");
+        Ok(details)
+    }
+
+    async fn list_reviews(&self, pr_number: u64) -> Result<Vec<PrReview>> {
+        debug!(change = pr_number, "listing votes");
+        let mut change = self.get_change(pr_number, &["DETAILED_LABELS"]).await?;
+
+        let mut reviews: Vec<PrReview> = change
+            .labels
+            .remove(CODE_REVIEW_LABEL)
+            .map(|label| label.all)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|vote| {
+                let state = review_state(vote.value?)?;
+                Some(PrReview {
+                    reviewer: vote.account.display_name(),
+                    state,
+                    submitted_at: vote.date.as_deref().and_then(parse_timestamp),
+                })
+            })
+            .collect();
+        reviews.sort_by_key(|review| review.submitted_at);
+
+        debug!(change = pr_number, count = reviews.len(), "listed votes");
+        Ok(reviews)
+    }
+
+    async fn list_review_threads(&self, pr_number: u64) -> Result<Vec<ReviewThread>> {
+        debug!(change = pr_number, "listing comment threads");
+        let threads: Vec<ReviewThread> = self
+            .comment_threads(pr_number)
+            .await?
+            .into_iter()
+            .map(|(path, comments)| review_thread(path, comments))
+            .collect();
+        debug!(
+            change = pr_number,
+            count = threads.len(),
+            "listed comment threads"
+        );
+        Ok(threads)
+    }
+
+    async fn resolve_thread(&self, pr_number: u64, thread_id: &str) -> Result<()> {
+        debug!(change = pr_number, thread_id, "resolving comment thread");
+        let (path, comments) = self
+            .comment_threads(pr_number)
+            .await?
+            .into_iter()
+            .find(|(_, comments)| comments.first().is_some_and(|c| c.id == thread_id))
+            .ok_or_else(|| Error::GerritApi(format!("no comment thread {thread_id}")))?;
+        let Some(last) = comments.last() else {
+            return Ok(());
+        };
+
+        // Gerrit resolves a thread with a reply marked resolved
+        let url = format!(
+            "{}{}/revisions/current/review",
+            self.api_url,
+            self.change_path(pr_number)
+        );
+        let reply = serde_json::json!({
+            "in_reply_to": last.id,
+            "line": last.line,
+            "message": "Done",
+            "unresolved": false,
+        });
+        self.send(
+            self.client
+                .post(&url)
+                .json(&serde_json::json!({ "comments": { path: [reply] } })),
+        )
+        .await?;
+
+        debug!(change = pr_number, thread_id, "resolved comment thread");
+        Ok(())
+    }
+
+    async fn list_checks(&self, git_ref: &str) -> Result<Vec<CheckInfo>> {
+        debug!(git_ref, "listing checks");
+        let query = format!("commit:{git_ref} project:{}", self.project);
+        let changes = self.query_changes(&query, &["LABELS"]).await?;
+
+        Ok(changes
+            .first()
+            .map(|change| self.verified_checks(change))
+            .unwrap_or_default())
+    }
+
+    async fn create_commit_status(
+        &self,
+        _sha: &str,
+        _context: &str,
+        _state: CheckState,
+        _description: &str,
+    ) -> Result<()> {
+        Err(Error::Unsupported(
+            "Gerrit has no commit statuses; use labels instead".to_string(),
+        ))
+    }
+
+    async fn check_merge_readiness(&self, details: &PullRequestDetails) -> Result<MergeReadiness> {
+        let pr_number = details.number;
+        debug!(change = pr_number, "checking merge readiness");
+        let change = self
+            .get_change(pr_number, &["LABELS", "SUBMITTABLE"])
+            .await?;
+
+        let reviews = self.list_reviews(pr_number).await.unwrap_or_default();
+        let changes_requested = changes_requested_by(&reviews);
+        let is_approved = change
+            .labels
+            .get(CODE_REVIEW_LABEL)
+            .is_some_and(|label| label.approved.is_some() && label.rejected.is_none());
+        let checks = self.verified_checks(&change);
+
+        let mut blocking_reasons = Vec::new();
+        if details.is_draft {
+            blocking_reasons.push("Change is work in progress".to_string());
+        }
+        if !changes_requested.is_empty() {
+            blocking_reasons.push(format!(
+                "Changes requested by {}",
+                changes_requested.join(", ")
+            ));
+        } else if !is_approved {
+            blocking_reasons.push("Not approved".to_string());
+        }
+        if details.mergeable == Some(false) {
+            blocking_reasons.push("Has merge conflicts".to_string());
+        }
+        let mut blocking_labels: Vec<&str> = change
+            .labels
+            .iter()
+            .filter(|(name, label)| label.blocking && name.as_str() != CODE_REVIEW_LABEL)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        blocking_labels.sort_unstable();
+        if !blocking_labels.is_empty() {
+            blocking_reasons.push(format!("Blocked by {}", blocking_labels.join(", ")));
+        }
+        if blocking_reasons.is_empty() && change.submittable == Some(false) {
+            blocking_reasons.push("Submit requirements not met".to_string());
+        }
+
+        let readiness = MergeReadiness {
+            is_approved,
+            approvals_required: None,
+            approvals_received: None,
+            checks,
+            is_mergeable: details.mergeable,
+            is_draft: details.is_draft,
+            blocking_reasons,
+            uncertainties: vec![],
+        };
+
+        debug!(
+            change = pr_number,
+            is_blocked = readiness.is_blocked(),
+            "checked merge readiness"
+        );
+        Ok(readiness)
+    }
+
+    async fn update_pr_branch(&self, pr_number: u64) -> Result<()> {
+        debug!(change = pr_number, "rebasing change");
+        let url = format!("{}{}/rebase", self.api_url, self.change_path(pr_number));
+        self.send(self.client.post(&url)).await?;
+        Ok(())
+    }
+
+    async fn enable_auto_merge(&self, _pr_number: u64, _method: MergeMethod) -> Result<()> {
+        Err(Error::Unsupported(
+            "Gerrit has no auto-merge; submit changes from the Gerrit web UI".to_string(),
+        ))
+    }
+
+    async fn merge_pr(
+        &self,
+        pr_number: u64,
+        _title: &str,
+        _body: Option<&str>,
+        method: MergeMethod,
+    ) -> Result<MergeResult> {
+        // The submit type is project configuration; the commit message is
+        // the change's own
+        debug!(change = pr_number, %method, "submitting change (method set by project)");
+        let url = format!("{}{}/submit", self.api_url, self.change_path(pr_number));
+        let change: ChangeInfo = self.send_json(self.client.post(&url)).await?;
+
+        let merge_result = MergeResult {
+            merged: change.status == "MERGED",
+            sha: None,
+            message: None,
+        };
+        debug!(change = pr_number, merged = merge_result.merged, "submit complete");
+        Ok(merge_result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_state_from_votes() {
+        assert_eq!(review_state(2), Some(ReviewState::Approved));
+        assert_eq!(review_state(1), Some(ReviewState::Commented));
+        assert_eq!(review_state(0), None);
+        assert_eq!(review_state(-1), Some(ReviewState::ChangesRequested));
+        assert_eq!(review_state(-2), Some(ReviewState::ChangesRequested));
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let parsed = parse_timestamp("2024-01-02 03:04:05.000000000").unwrap();
+        assert_eq!(parsed.to_rfc3339(), "2024-01-02T03:04:05+00:00");
+    }
+
+    #[test]
+    fn test_group_comment_threads() {
+        let comments: HashMap<String, Vec<CommentInfo>> = serde_json::from_value(serde_json::json!({
+            "src/lib.rs": [
+                {"id": "a", "line": 3, "message": "Typo", "unresolved": true,
+                 "author": {"username": "bob"}, "updated": "2024-01-01 10:00:00.000000000"},
+                {"id": "b", "in_reply_to": "a", "message": "Done", "unresolved": false,
+                 "updated": "2024-01-01 11:00:00.000000000"},
+                {"id": "c", "line": 9, "message": "Why?", "unresolved": true,
+                 "author": {"username": "bob"}, "updated": "2024-01-01 12:00:00.000000000"}
+            ],
+            "/PATCHSET_LEVEL": [
+                {"id": "d", "message": "Looks good", "updated": "2024-01-01 09:00:00.000000000"}
+            ]
+        }))
+        .unwrap();
+
+        let threads: Vec<ReviewThread> = group_comment_threads(comments)
+            .into_iter()
+            .map(|(path, comments)| review_thread(path, comments))
+            .collect();
+
+        assert_eq!(threads.len(), 3);
+        assert_eq!(threads[0].id, "d");
+        assert_eq!(threads[0].path, None);
+        assert!(threads[0].is_resolved);
+        assert_eq!(threads[1].id, "a");
+        assert_eq!(threads[1].comment_count, 2);
+        assert!(threads[1].is_resolved);
+        assert_eq!(threads[2].id, "c");
+        assert_eq!(threads[2].line, Some(9));
+        assert!(!threads[2].is_resolved);
+    }
+
+    #[test]
+    fn test_change_paths_encode_project() {
+        let service = GerritService::new(
+            "alice".to_string(),
+            "secret".to_string(),
+            "tools".to_string(),
+            "ryu".to_string(),
+            "review.example.org".to_string(),
+        )
+        .unwrap();
+        assert_eq!(service.change_path(42), "/changes/tools%2Fryu~42");
+        assert_eq!(
+            service.change_url(42),
+            "https://review.example.org/c/tools/ryu/+/42"
+        );
+    }
+}
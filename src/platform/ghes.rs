@@ -0,0 +1,150 @@
+//! GitHub Enterprise Server versions and the features they support
+//!
+//! Endpoints added after a GHES release answer with a bare 404 on older
+//! servers. The service detects the server version once, at creation, and
+//! checks it before using such features, so users get an explicit message.
+
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::fmt;
+
+/// A GitHub Enterprise Server release (`major.minor`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GhesVersion {
+    /// Major version
+    pub major: u32,
+    /// Minor version
+    pub minor: u32,
+}
+
+impl GhesVersion {
+    /// Create a version
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+
+    /// Parse `installed_version` from the `/meta` endpoint, e.g. "3.9.2"
+    ///
+    /// The patch version is ignored.
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.trim().trim_start_matches('v').split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        Some(Self { major, minor })
+    }
+}
+
+impl fmt::Display for GhesVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Features that older GHES releases lack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GhesFeature {
+    /// GraphQL mutations (marking a draft PR ready for review)
+    GraphQlMutations,
+    /// Enabling auto-merge on a PR
+    AutoMerge,
+    /// Adding PRs to a merge queue
+    MergeQueue,
+    /// The Checks API without a preview media type
+    ChecksApi,
+    /// The `X-GitHub-Api-Version` header (calendar-based REST API versions)
+    ApiVersions,
+}
+
+impl GhesFeature {
+    /// First GHES release supporting the feature
+    pub const fn min_version(self) -> GhesVersion {
+        match self {
+            Self::GraphQlMutations | Self::ChecksApi => GhesVersion::new(3, 0),
+            Self::AutoMerge => GhesVersion::new(3, 1),
+            Self::MergeQueue => GhesVersion::new(3, 12),
+            Self::ApiVersions => GhesVersion::new(3, 9),
+        }
+    }
+}
+
+impl fmt::Display for GhesFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GraphQlMutations => write!(f, "marking PRs ready for review (GraphQL)"),
+            Self::AutoMerge => write!(f, "auto-merge"),
+            Self::MergeQueue => write!(f, "merge queues"),
+            Self::ChecksApi => write!(f, "check runs"),
+            Self::ApiVersions => write!(f, "REST API versions"),
+        }
+    }
+}
+
+/// Whether `feature` is available on a server running `version`
+///
+/// `None` means github.com or an undetected version; everything is allowed.
+pub fn supports_feature(version: Option<GhesVersion>, feature: GhesFeature) -> bool {
+    version.is_none_or(|version| version >= feature.min_version())
+}
+
+/// Check that `feature` is available on a server running `version`
+///
+/// Use this for features that can't be worked around; `supports_feature`
+/// is for the ones with a fallback.
+pub fn require_feature(version: Option<GhesVersion>, feature: GhesFeature) -> Result<()> {
+    match version {
+        Some(version) if !supports_feature(Some(version), feature) => {
+            Err(Error::Unsupported(format!(
+                "{feature} is not supported on GHES {version} (requires GHES {} or later)",
+                feature.min_version()
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Response of the GHES `/meta` endpoint (only the fields used)
+#[derive(Debug, Deserialize)]
+pub struct MetaResponse {
+    /// Server version, e.g. "3.9.2" (absent on github.com)
+    pub installed_version: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(GhesVersion::parse("3.9.2"), Some(GhesVersion::new(3, 9)));
+        assert_eq!(GhesVersion::parse("3.12"), Some(GhesVersion::new(3, 12)));
+        assert_eq!(GhesVersion::parse("enterprise"), None);
+        assert_eq!(GhesVersion::parse("3"), None);
+    }
+
+    #[test]
+    fn test_versions_compare_numerically() {
+        assert!(GhesVersion::new(3, 12) > GhesVersion::new(3, 9));
+        assert!(GhesVersion::new(4, 0) > GhesVersion::new(3, 15));
+    }
+
+    #[test]
+    fn test_api_versions_need_ghes_3_9() {
+        assert!(supports_feature(None, GhesFeature::ApiVersions));
+        assert!(supports_feature(Some(GhesVersion::new(3, 9)), GhesFeature::ApiVersions));
+        assert!(!supports_feature(Some(GhesVersion::new(3, 8)), GhesFeature::ApiVersions));
+    }
+
+    #[test]
+    fn test_require_feature() {
+        assert!(require_feature(None, GhesFeature::MergeQueue).is_ok());
+        assert!(require_feature(Some(GhesVersion::new(3, 12)), GhesFeature::MergeQueue).is_ok());
+
+        let err = require_feature(Some(GhesVersion::new(3, 0)), GhesFeature::AutoMerge)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "auto-merge is not supported on GHES 3.0 (requires GHES 3.1 or later)"
+        );
+    }
+}
@@ -1,18 +1,28 @@
 //! GitHub platform service implementation
 
+use crate::auth::validate_github_token;
 use crate::error::{Error, Result};
-use crate::platform::PlatformService;
+use crate::platform::ghes::MetaResponse;
+use crate::platform::{
+    CiStatusCache, GhesFeature, GhesVersion, PlatformService, RateLimitCheck, approval_count,
+    STACK_STATUS_CONTEXT, changes_requested_by, is_approved_by_reviews, require_feature,
+    supports_feature,
+};
 use crate::types::{
-    MergeMethod, MergeReadiness, MergeResult, Platform, PlatformConfig, PrComment, PrState,
-    PullRequest, PullRequestDetails,
+    CheckInfo, CheckState, MergeMethod, MergeReadiness, MergeResult, Platform, PlatformConfig, PrComment, PrReview,
+    PrState, PullRequest, PullRequestDetails, ReviewState as PrReviewState, ReviewThread,
 };
 use async_trait::async_trait;
 use octocrab::Octocrab;
-use reqwest::Client;
+use octocrab::models::pulls::{MergeableState, ReviewState};
+use reqwest::{Client, RequestBuilder};
 use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::fmt::Write;
 use tracing::debug;
 
-// GraphQL response types for publish_pr mutation
+// GraphQL response types for the publish_pr and unpublish_pr mutations
 
 #[derive(Deserialize)]
 struct GraphQlResponse<T> {
@@ -37,6 +47,12 @@ struct MarkReadyPayload {
     pull_request: GraphQlPullRequest,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConvertToDraftData {
+    convert_pull_request_to_draft: MarkReadyPayload,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct GraphQlPullRequest {
@@ -63,6 +79,166 @@ impl From<GraphQlPullRequest> for PullRequest {
     }
 }
 
+// GraphQL response types for the batched find_existing_prs query
+
+#[derive(Deserialize)]
+struct FindPullRequestsData {
+    /// One connection per head branch, keyed by alias (`h0`, `h1`, ...)
+    repository: HashMap<String, PullRequestConnection>,
+}
+
+#[derive(Deserialize)]
+struct PullRequestConnection {
+    nodes: Vec<HeadPullRequest>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HeadPullRequest {
+    #[serde(flatten)]
+    pr: GraphQlPullRequest,
+    head_repository_owner: Option<GraphQlActor>,
+}
+
+#[derive(Deserialize)]
+struct GraphQlActor {
+    login: String,
+}
+
+// GraphQL response types for list_review_threads
+
+#[derive(Deserialize)]
+struct ReviewThreadsData {
+    repository: ReviewThreadsRepository,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewThreadsRepository {
+    pull_request: ReviewThreadsPullRequest,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewThreadsPullRequest {
+    review_threads: ReviewThreadConnection,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewThreadConnection {
+    page_info: PageInfo,
+    nodes: Vec<GraphQlReviewThread>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlReviewThread {
+    id: String,
+    is_resolved: bool,
+    path: Option<String>,
+    line: Option<u64>,
+    comments: ThreadComments,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThreadComments {
+    total_count: usize,
+    nodes: Vec<ThreadComment>,
+}
+
+#[derive(Deserialize)]
+struct ThreadComment {
+    author: Option<GraphQlActor>,
+    body: String,
+}
+
+/// The data of a GraphQL response, or its errors
+fn graphql_data<T>(response: GraphQlResponse<T>) -> Result<T> {
+    if let Some(errors) = response.errors
+        && !errors.is_empty()
+    {
+        let messages: Vec<_> = errors.into_iter().map(|e| e.message).collect();
+        return Err(Error::GitHubApi(format!(
+            "GraphQL error: {}",
+            messages.join(", ")
+        )));
+    }
+    response
+        .data
+        .ok_or_else(|| Error::GitHubApi("No data in GraphQL response".to_string()))
+}
+
+/// Head branches looked up per GraphQL query, to stay well under node limits
+const PR_LOOKUP_BATCH_SIZE: usize = 50;
+
+/// GraphQL query finding the open PRs for `count` head branches
+///
+/// Each branch `$hN` gets its own aliased `pullRequests` connection `hN`.
+fn find_pull_requests_query(count: usize) -> String {
+    let mut variables = String::new();
+    let mut connections = String::new();
+    for i in 0..count {
+        let _ = write!(variables, ", $h{i}: String!");
+        let _ = writeln!(
+            connections,
+            "h{i}: pullRequests(headRefName: $h{i}, states: OPEN, first: 10) {{ nodes {{ ...PrFields }} }}"
+        );
+    }
+    format!(
+        "query FindPullRequests($owner: String!, $repo: String!{variables}) {{
+            repository(owner: $owner, name: $repo) {{
+                {connections}
+            }}
+        }}
+        fragment PrFields on PullRequest {{
+            number
+            url
+            baseRefName
+            headRefName
+            title
+            id
+            isDraft
+            headRepositoryOwner {{ login }}
+        }}"
+    )
+}
+
+/// The `rel="next"` URL in a `Link` header, if there's a next page
+fn next_page_url(link: &str) -> Option<String> {
+    link.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| param.trim() == r#"rel="next""#)
+            .then(|| {
+                url.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
+/// A base branch's protection rules (the parts readiness depends on)
+#[derive(Debug, Default)]
+struct BranchProtection {
+    /// Status checks that must pass (empty = every check counts)
+    required_checks: Vec<String>,
+    /// Approving reviews required (None = rules unknown)
+    required_approvals: Option<u32>,
+    /// Whether every review thread must be resolved before merging
+    require_conversation_resolution: bool,
+}
+
 /// GitHub service using octocrab
 pub struct GitHubService {
     client: Octocrab,
@@ -71,26 +247,44 @@ pub struct GitHubService {
     token: String,
     /// HTTP client for raw requests (CI status checking)
     http_client: Client,
-    /// API host for raw requests
-    api_host: String,
+    /// API base URL for raw requests
+    api_url: String,
+    /// CI status results keyed by head commit
+    ci_cache: CiStatusCache,
+    /// GitHub Enterprise Server version, if detected (None on github.com)
+    ghes_version: Option<GhesVersion>,
 }
 
 impl GitHubService {
     /// Create a new GitHub service
     pub fn new(token: &str, owner: String, repo: String, host: Option<String>) -> Result<Self> {
-        let mut builder = Octocrab::builder().personal_token(token.to_string());
-
-        let api_host = if let Some(ref h) = host {
-            let base_url = format!("https://{h}/api/v3");
-            builder = builder
-                .base_uri(&base_url)
-                .map_err(|e| Error::GitHubApi(e.to_string()))?;
-            format!("{h}/api/v3")
-        } else {
-            "api.github.com".to_string()
-        };
+        let api_url = host.as_ref().map_or_else(
+            || "https://api.github.com".to_string(),
+            |h| format!("https://{h}/api/v3"),
+        );
+        Self::build(token, owner, repo, host, api_url)
+    }
 
-        let client = builder
+    /// Create a GitHub service that sends every API request to `api_url`
+    ///
+    /// Used to point the service at an HTTP proxy or a local replay server;
+    /// the repo is reported as hosted on github.com.
+    pub fn with_api_url(token: &str, owner: String, repo: String, api_url: &str) -> Result<Self> {
+        let api_url = api_url.trim_end_matches('/').to_string();
+        Self::build(token, owner, repo, None, api_url)
+    }
+
+    fn build(
+        token: &str,
+        owner: String,
+        repo: String,
+        host: Option<String>,
+        api_url: String,
+    ) -> Result<Self> {
+        let client = Octocrab::builder()
+            .personal_token(token.to_string())
+            .base_uri(&api_url)
+            .map_err(|e| Error::GitHubApi(e.to_string()))?
             .build()
             .map_err(|e| Error::GitHubApi(e.to_string()))?;
 
@@ -109,57 +303,345 @@ impl GitHubService {
             },
             token: token.to_string(),
             http_client,
-            api_host,
+            api_url,
+            ci_cache: CiStatusCache::from_env(),
+            ghes_version: None,
         })
     }
 
-    /// Check CI status by querying both commit statuses and check runs
+    /// Detect the GitHub Enterprise Server version, to gate newer features
+    ///
+    /// Does nothing for github.com. If the version can't be determined,
+    /// every feature is assumed to be available.
+    pub async fn detect_ghes_version(mut self) -> Self {
+        if self.config.host.is_none() {
+            return self;
+        }
+        self.ghes_version = self.fetch_ghes_version().await;
+        debug!(version = ?self.ghes_version, "detected GHES version");
+        self
+    }
+
+    /// Check up front that the token can manage PRs
+    pub async fn validate_token(&self) -> Result<()> {
+        validate_github_token(&self.token, &self.api_url).await
+    }
+
+    /// The detected GitHub Enterprise Server version
+    pub const fn ghes_version(&self) -> Option<GhesVersion> {
+        self.ghes_version
+    }
+
+    /// GET request for the REST API, with auth and the default media type
+    fn raw_get(&self, url: &str) -> RequestBuilder {
+        self.raw_get_as(url, "application/vnd.github+json")
+    }
+
+    /// GET every page of a REST list endpoint, starting at `url`
+    ///
+    /// Follows the `rel="next"` URL in GitHub's `Link` header until the last
+    /// page.
+    async fn get_all_pages<T: DeserializeOwned>(&self, url: &str) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        let mut url = url.to_string();
+        loop {
+            let response = self
+                .raw_get(&url)
+                .send()
+                .await?
+                .check_rate_limit("GitHub")?
+                .error_for_status()?;
+            let next_url = response
+                .headers()
+                .get(reqwest::header::LINK)
+                .and_then(|v| v.to_str().ok())
+                .and_then(next_page_url);
+            items.extend(response.json::<Vec<T>>().await?);
+
+            match next_url {
+                Some(next) => url = next,
+                None => return Ok(items),
+            }
+        }
+    }
+
+    /// GET request for the REST API, accepting `media_type`
+    ///
+    /// The `X-GitHub-Api-Version` header is only sent to servers that know
+    /// about API versions; older GHES releases reject it.
+    fn raw_get_as(&self, url: &str, media_type: &str) -> RequestBuilder {
+        let request = self
+            .http_client
+            .get(url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", media_type);
+        if supports_feature(self.ghes_version, GhesFeature::ApiVersions) {
+            request.header("X-GitHub-Api-Version", "2022-11-28")
+        } else {
+            request
+        }
+    }
+
+    /// Media type for the check runs endpoint
+    ///
+    /// GHES releases before 3.0 only serve check runs behind a preview.
+    fn check_runs_media_type(&self) -> &'static str {
+        if supports_feature(self.ghes_version, GhesFeature::ChecksApi) {
+            "application/vnd.github+json"
+        } else {
+            "application/vnd.github.antiope-preview+json"
+        }
+    }
+
+    async fn fetch_ghes_version(&self) -> Option<GhesVersion> {
+        let response = self
+            .http_client
+            .get(format!("{}/meta", self.api_url))
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await
+            .ok()?;
+        if !response.status().is_success() {
+            debug!(status = %response.status(), "GHES meta request failed");
+            return None;
+        }
+        let meta: MetaResponse = response.json().await.ok()?;
+        GhesVersion::parse(&meta.installed_version?)
+    }
+
+    /// Collect CI checks by querying both commit statuses and check runs
     ///
     /// GitHub has two CI systems:
     /// 1. Commit Status API (legacy) - used by external CI services
     /// 2. Check Runs API (modern) - used by GitHub Actions
     ///
-    /// We need to check both to properly determine CI status.
-    async fn check_ci_status(&self, ref_name: &str) -> Result<bool> {
-        // Check commit statuses (legacy API)
-        let statuses_passed = self.check_commit_statuses(ref_name).await?;
+    /// We need to check both to properly determine CI status. Checks listed in
+    /// `required` (from the base branch's protection rules) are marked
+    /// required; without such rules every check is treated as required.
+    ///
+    /// Results are cached per head SHA (plus base and required checks), since
+    /// statuses are attached to commits rather than branches.
+    async fn check_ci_status(
+        &self,
+        details: &PullRequestDetails,
+        required: &[String],
+    ) -> Result<Vec<CheckInfo>> {
+        if details.head_sha.is_empty() {
+            return self.fetch_ci_checks(&details.head_ref, required).await;
+        }
+
+        let mut required_key = required.to_vec();
+        required_key.sort();
+        let key = format!(
+            "{}/{}/{}@{}->{}[{}]",
+            self.api_url.trim_start_matches("https://"),
+            self.config.owner,
+            self.config.repo,
+            details.head_sha,
+            details.base_ref,
+            required_key.join(",")
+        );
+        if let Some(checks) = self.ci_cache.get(&key) {
+            debug!(sha = %details.head_sha, count = checks.len(), "CI status cache hit");
+            return Ok(checks);
+        }
 
-        // Check check runs (GitHub Actions API)
-        let check_runs_passed = self.check_check_runs(ref_name).await?;
+        let checks = self.fetch_ci_checks(&details.head_sha, required).await?;
+        self.ci_cache.insert(&key, checks.clone());
+        Ok(checks)
+    }
 
-        // CI passes if both pass (or are not configured)
-        Ok(statuses_passed && check_runs_passed)
+    /// List checks for a commit-ish and mark those in `required` as required
+    ///
+    /// Required checks that haven't reported yet are added as pending, so
+    /// readiness waits for them to start rather than passing without them.
+    async fn fetch_ci_checks(&self, ref_name: &str, required: &[String]) -> Result<Vec<CheckInfo>> {
+        let mut checks = self.list_checks(ref_name).await?;
+
+        if !required.is_empty() {
+            for check in &mut checks {
+                check.required = required.contains(&check.name);
+            }
+            let unreported: Vec<CheckInfo> = required
+                .iter()
+                .filter(|name| !checks.iter().any(|check| &check.name == *name))
+                .map(|name| CheckInfo {
+                    name: name.clone(),
+                    state: CheckState::Pending,
+                    required: true,
+                    url: None,
+                })
+                .collect();
+            checks.extend(unreported);
+        }
+
+        Ok(checks)
     }
 
-    /// Check legacy commit statuses via combined status API
-    async fn check_commit_statuses(&self, ref_name: &str) -> Result<bool> {
+    /// The base branch's protection rules
+    ///
+    /// Reading the full rules needs admin access to the repo. Without it,
+    /// only the required status checks are known (from the branch itself),
+    /// and readiness falls back to "approved by someone".
+    ///
+    /// Best effort: an unprotected branch or a failed lookup gives no rules.
+    async fn branch_protection(&self, branch: &str) -> BranchProtection {
+        #[derive(Deserialize)]
+        #[allow(clippy::struct_field_names)] // Named by the API
+        struct Protection {
+            required_status_checks: Option<RequiredChecks>,
+            required_pull_request_reviews: Option<RequiredReviews>,
+            required_conversation_resolution: Option<Enabled>,
+        }
+
+        #[derive(Deserialize)]
+        struct RequiredChecks {
+            #[serde(default)]
+            contexts: Vec<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct RequiredReviews {
+            #[serde(default)]
+            required_approving_review_count: u32,
+        }
+
+        #[derive(Deserialize)]
+        struct Enabled {
+            enabled: bool,
+        }
+
+        let url = format!(
+            "{}/repos/{}/{}/branches/{}/protection",
+            self.api_url, self.config.owner, self.config.repo, branch
+        );
+
+        let protection = match self
+            .raw_get(&url)
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => {
+                response.json::<Protection>().await.ok()
+            }
+            Ok(response) => {
+                debug!(status = %response.status(), "Branch protection lookup returned non-success");
+                None
+            }
+            Err(e) => {
+                debug!(error = %e, "Failed to fetch branch protection");
+                None
+            }
+        };
+
+        let Some(protection) = protection else {
+            return BranchProtection {
+                required_checks: self.required_check_names(branch).await,
+                ..BranchProtection::default()
+            };
+        };
+
+        BranchProtection {
+            required_checks: protection
+                .required_status_checks
+                .map(|r| r.contexts)
+                .unwrap_or_default(),
+            required_approvals: Some(
+                protection
+                    .required_pull_request_reviews
+                    .map_or(0, |r| r.required_approving_review_count),
+            ),
+            require_conversation_resolution: protection
+                .required_conversation_resolution
+                .is_some_and(|r| r.enabled),
+        }
+    }
+
+    /// Required status check names from the branch's protection summary
+    ///
+    /// Readable without admin access. Best effort: returns an empty list if
+    /// the branch is unprotected or the lookup fails.
+    async fn required_check_names(&self, branch: &str) -> Vec<String> {
+        #[derive(Deserialize)]
+        struct Branch {
+            protection: Option<Protection>,
+        }
+
+        #[derive(Deserialize)]
+        struct Protection {
+            required_status_checks: Option<RequiredChecks>,
+        }
+
+        #[derive(Deserialize)]
+        struct RequiredChecks {
+            #[serde(default)]
+            contexts: Vec<String>,
+        }
+
+        let url = format!(
+            "{}/repos/{}/{}/branches/{}",
+            self.api_url, self.config.owner, self.config.repo, branch
+        );
+
+        let response = match self
+            .raw_get(&url)
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                debug!(status = %response.status(), "Branch lookup returned non-success");
+                return Vec::new();
+            }
+            Err(e) => {
+                debug!(error = %e, "Failed to fetch branch protection");
+                return Vec::new();
+            }
+        };
+
+        response
+            .json::<Branch>()
+            .await
+            .ok()
+            .and_then(|b| b.protection)
+            .and_then(|p| p.required_status_checks)
+            .map(|r| r.contexts)
+            .unwrap_or_default()
+    }
+
+    /// Fetch legacy commit statuses via combined status API
+    async fn fetch_commit_statuses(&self, ref_name: &str) -> Result<Vec<CheckInfo>> {
         #[derive(Deserialize)]
         struct CombinedStatus {
+            statuses: Vec<Status>,
+        }
+
+        #[derive(Deserialize)]
+        struct Status {
+            context: String,
             state: String,
-            total_count: u32,
+            target_url: Option<String>,
         }
 
         let url = format!(
-            "https://{}/repos/{}/{}/commits/{}/status",
-            self.api_host, self.config.owner, self.config.repo, ref_name
+            "{}/repos/{}/{}/commits/{}/status",
+            self.api_url, self.config.owner, self.config.repo, ref_name
         );
 
         let response = self
-            .http_client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.token))
-            .header("Accept", "application/vnd.github+json")
-            .header("X-GitHub-Api-Version", "2022-11-28")
+            .raw_get(&url)
             .send()
             .await
-            .map_err(|e| Error::GitHubApi(format!("Failed to fetch commit status: {e}")))?;
+            .map_err(|e| Error::GitHubApi(format!("Failed to fetch commit status: {e}")))?
+            .check_rate_limit("GitHub")?;
 
         if !response.status().is_success() {
             debug!(
                 status = %response.status(),
                 "Commit status check returned non-success, assuming no statuses configured"
             );
-            return Ok(true);
+            return Ok(Vec::new());
         }
 
         let status: CombinedStatus = response
@@ -167,53 +649,58 @@ impl GitHubService {
             .await
             .map_err(|e| Error::GitHubApi(format!("Failed to parse commit status: {e}")))?;
 
-        // No statuses configured = passing
-        // "success" = all passed
-        // "pending" or "failure" = not passing
-        if status.total_count == 0 {
-            debug!("No commit statuses configured");
-            return Ok(true);
-        }
-
-        debug!(state = %status.state, count = status.total_count, "Commit status result");
-        Ok(status.state == "success")
+        debug!(count = status.statuses.len(), "Commit status result");
+        Ok(status
+            .statuses
+            .into_iter()
+            .filter(|s| s.context != STACK_STATUS_CONTEXT)
+            .map(|s| CheckInfo {
+                state: match s.state.as_str() {
+                    "success" => CheckState::Success,
+                    "pending" => CheckState::Pending,
+                    // "failure" or "error"
+                    _ => CheckState::Failure,
+                },
+                name: s.context,
+                required: true,
+                url: s.target_url,
+            })
+            .collect())
     }
 
-    /// Check GitHub Actions check runs
-    async fn check_check_runs(&self, ref_name: &str) -> Result<bool> {
+    /// Fetch GitHub Actions check runs
+    async fn fetch_check_runs(&self, ref_name: &str) -> Result<Vec<CheckInfo>> {
         #[derive(Deserialize)]
         struct CheckRunsResponse {
-            total_count: u32,
             check_runs: Vec<CheckRun>,
         }
 
         #[derive(Deserialize)]
         struct CheckRun {
+            name: String,
             status: String,
             conclusion: Option<String>,
+            html_url: Option<String>,
         }
 
         let url = format!(
-            "https://{}/repos/{}/{}/commits/{}/check-runs",
-            self.api_host, self.config.owner, self.config.repo, ref_name
+            "{}/repos/{}/{}/commits/{}/check-runs",
+            self.api_url, self.config.owner, self.config.repo, ref_name
         );
 
         let response = self
-            .http_client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.token))
-            .header("Accept", "application/vnd.github+json")
-            .header("X-GitHub-Api-Version", "2022-11-28")
+            .raw_get_as(&url, self.check_runs_media_type())
             .send()
             .await
-            .map_err(|e| Error::GitHubApi(format!("Failed to fetch check runs: {e}")))?;
+            .map_err(|e| Error::GitHubApi(format!("Failed to fetch check runs: {e}")))?
+            .check_rate_limit("GitHub")?;
 
         if !response.status().is_success() {
             debug!(
                 status = %response.status(),
                 "Check runs returned non-success, assuming no checks configured"
             );
-            return Ok(true);
+            return Ok(Vec::new());
         }
 
         let check_runs: CheckRunsResponse = response
@@ -221,39 +708,27 @@ impl GitHubService {
             .await
             .map_err(|e| Error::GitHubApi(format!("Failed to parse check runs: {e}")))?;
 
-        // No check runs configured = passing
-        if check_runs.total_count == 0 {
-            debug!("No check runs configured");
-            return Ok(true);
-        }
-
-        // All check runs must be completed with success/neutral/skipped
-        for run in &check_runs.check_runs {
-            // If any check is still running, CI is not complete
-            if run.status != "completed" {
-                debug!(status = %run.status, "Check run still in progress");
-                return Ok(false);
-            }
-
-            // Check conclusion for completed runs
-            match run.conclusion.as_deref() {
-                Some("success" | "neutral" | "skipped") => {
-                    // These are passing conclusions
-                }
-                Some(conclusion) => {
-                    debug!(conclusion = %conclusion, "Check run failed");
-                    return Ok(false);
-                }
-                None => {
-                    // Completed but no conclusion? Treat as failure
-                    debug!("Check run completed but no conclusion");
-                    return Ok(false);
-                }
-            }
-        }
-
-        debug!(count = check_runs.total_count, "All check runs passed");
-        Ok(true)
+        debug!(count = check_runs.check_runs.len(), "Check runs result");
+        Ok(check_runs
+            .check_runs
+            .into_iter()
+            .map(|run| CheckInfo {
+                // Incomplete runs are pending; completed runs without a
+                // conclusion are treated as failures
+                state: if run.status == "completed" {
+                    match run.conclusion.as_deref() {
+                        Some("success") => CheckState::Success,
+                        Some("neutral" | "skipped") => CheckState::Skipped,
+                        _ => CheckState::Failure,
+                    }
+                } else {
+                    CheckState::Pending
+                },
+                name: run.name,
+                required: true,
+                url: run.html_url,
+            })
+            .collect())
     }
 }
 
@@ -286,10 +761,12 @@ impl PlatformService for GitHubService {
             .list()
             .head(head)
             .state(octocrab::params::State::Open)
+            .per_page(100)
             .send()
             .await?;
+        let prs = self.client.all_pages(prs).await?;
 
-        let result = prs.items.first().map(pr_from_octocrab);
+        let result = prs.first().map(pr_from_octocrab);
         if let Some(ref pr) = result {
             debug!(pr_number = pr.number, "found existing PR");
         } else {
@@ -298,6 +775,134 @@ impl PlatformService for GitHubService {
         Ok(result)
     }
 
+    async fn find_closed_pr(&self, head_branch: &str) -> Result<Option<PullRequest>> {
+        debug!(head_branch, "finding closed PR");
+        let head = format!("{}:{}", &self.config.owner, head_branch);
+
+        let prs = self
+            .client
+            .pulls(&self.config.owner, &self.config.repo)
+            .list()
+            .head(head)
+            .state(octocrab::params::State::Closed)
+            .per_page(100)
+            .send()
+            .await?;
+        let prs = self.client.all_pages(prs).await?;
+
+        // Closed PRs include merged ones, which can't be reopened
+        let result = prs
+            .iter()
+            .filter(|pr| pr.merged_at.is_none())
+            .max_by_key(|pr| pr.number)
+            .map(pr_from_octocrab);
+        debug!(pr_number = result.as_ref().map(|pr| pr.number), "looked up closed PR");
+        Ok(result)
+    }
+
+    async fn find_pr_by_body(&self, text: &str) -> Result<Option<PullRequest>> {
+        let mut prs = self.find_prs_by_body(&[text.to_string()]).await?;
+        Ok(prs.remove(text))
+    }
+
+    async fn find_prs_by_body(&self, texts: &[String]) -> Result<HashMap<String, PullRequest>> {
+        debug!(count = texts.len(), "finding PRs by body");
+        if texts.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        // One listing serves every text; only PRs from this repo count (not forks)
+        let prs = self
+            .client
+            .pulls(&self.config.owner, &self.config.repo)
+            .list()
+            .state(octocrab::params::State::Open)
+            .per_page(100)
+            .send()
+            .await?;
+        let prs: Vec<_> = self
+            .client
+            .all_pages(prs)
+            .await?
+            .into_iter()
+            .filter(|pr| {
+                pr.head
+                    .repo
+                    .as_ref()
+                    .and_then(|repo| repo.owner.as_ref())
+                    .is_some_and(|owner| owner.login.eq_ignore_ascii_case(&self.config.owner))
+            })
+            .collect();
+
+        let result: HashMap<String, PullRequest> = texts
+            .iter()
+            .filter_map(|text| {
+                prs.iter()
+                    .filter(|pr| {
+                        pr.body
+                            .as_deref()
+                            .is_some_and(|body| body.contains(text.as_str()))
+                    })
+                    .max_by_key(|pr| pr.number)
+                    .map(|pr| (text.clone(), pr_from_octocrab(pr)))
+            })
+            .collect();
+        debug!(found = result.len(), "looked up PRs by body");
+        Ok(result)
+    }
+
+    async fn find_existing_prs(
+        &self,
+        head_branches: &[String],
+    ) -> Result<HashMap<String, PullRequest>> {
+        debug!(count = head_branches.len(), "finding existing PRs");
+        let mut result = HashMap::new();
+
+        for batch in head_branches.chunks(PR_LOOKUP_BATCH_SIZE) {
+            let mut variables = serde_json::Map::new();
+            variables.insert("owner".to_string(), self.config.owner.clone().into());
+            variables.insert("repo".to_string(), self.config.repo.clone().into());
+            for (i, branch) in batch.iter().enumerate() {
+                variables.insert(format!("h{i}"), branch.clone().into());
+            }
+
+            let response: GraphQlResponse<FindPullRequestsData> = self
+                .client
+                .graphql(&serde_json::json!({
+                    "query": find_pull_requests_query(batch.len()),
+                    "variables": variables,
+                }))
+                .await
+                .map_err(|e| Error::GitHubApi(format!("GraphQL query failed: {e}")))?;
+
+            let mut data = graphql_data(response)?;
+
+            // Like the REST lookup, only PRs from this repo count (not forks)
+            for (i, branch) in batch.iter().enumerate() {
+                let pr = data
+                    .repository
+                    .remove(&format!("h{i}"))
+                    .into_iter()
+                    .flat_map(|connection| connection.nodes)
+                    .find(|node| {
+                        node.head_repository_owner
+                            .as_ref()
+                            .is_some_and(|o| o.login.eq_ignore_ascii_case(&self.config.owner))
+                    });
+                if let Some(node) = pr {
+                    result.insert(branch.clone(), node.pr.into());
+                }
+            }
+        }
+
+        debug!(found = result.len(), "found existing PRs");
+        Ok(result)
+    }
+
+    fn batches_pr_lookups(&self) -> bool {
+        true
+    }
+
     async fn create_pr_with_options(
         &self,
         head: &str,
@@ -335,8 +940,63 @@ impl PlatformService for GitHubService {
         Ok(pr_from_octocrab(&pr))
     }
 
+    async fn update_pr_title(&self, pr_number: u64, title: &str) -> Result<PullRequest> {
+        debug!(pr_number, title, "updating PR title");
+        let pr = self
+            .client
+            .pulls(&self.config.owner, &self.config.repo)
+            .update(pr_number)
+            .title(title)
+            .send()
+            .await?;
+
+        debug!(pr_number, "updated PR title");
+        Ok(pr_from_octocrab(&pr))
+    }
+
+    async fn update_pr_body(&self, pr_number: u64, body: &str) -> Result<()> {
+        debug!(pr_number, "updating PR body");
+        self.client
+            .pulls(&self.config.owner, &self.config.repo)
+            .update(pr_number)
+            .body(body)
+            .send()
+            .await?;
+
+        debug!(pr_number, "updated PR body");
+        Ok(())
+    }
+
+    async fn close_pr(&self, pr_number: u64) -> Result<()> {
+        debug!(pr_number, "closing PR");
+        self.client
+            .pulls(&self.config.owner, &self.config.repo)
+            .update(pr_number)
+            .state(octocrab::params::pulls::State::Closed)
+            .send()
+            .await?;
+
+        debug!(pr_number, "closed PR");
+        Ok(())
+    }
+
+    async fn reopen_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        debug!(pr_number, "reopening PR");
+        let pr = self
+            .client
+            .pulls(&self.config.owner, &self.config.repo)
+            .update(pr_number)
+            .state(octocrab::params::pulls::State::Open)
+            .send()
+            .await?;
+
+        debug!(pr_number, "reopened PR");
+        Ok(pr_from_octocrab(&pr))
+    }
+
     async fn publish_pr(&self, pr_number: u64) -> Result<PullRequest> {
         debug!(pr_number, "publishing PR");
+        require_feature(self.ghes_version, GhesFeature::GraphQlMutations)?;
         // Fetch PR to get node_id for GraphQL mutation
         let pr = self
             .client
@@ -394,17 +1054,61 @@ impl PlatformService for GitHubService {
         Ok(data.mark_pull_request_ready_for_review.pull_request.into())
     }
 
+    async fn unpublish_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        debug!(pr_number, "converting PR to draft");
+        require_feature(self.ghes_version, GhesFeature::GraphQlMutations)?;
+        let pr = self
+            .client
+            .pulls(&self.config.owner, &self.config.repo)
+            .get(pr_number)
+            .await?;
+
+        let node_id = pr.node_id.as_ref().ok_or_else(|| {
+            Error::GitHubApi("PR missing node_id for GraphQL mutation".to_string())
+        })?;
+
+        let response: GraphQlResponse<ConvertToDraftData> = self
+            .client
+            .graphql(&serde_json::json!({
+                "query": r"
+                    mutation ConvertPullRequestToDraft($pullRequestId: ID!) {
+                        convertPullRequestToDraft(input: { pullRequestId: $pullRequestId }) {
+                            pullRequest {
+                                number
+                                url
+                                baseRefName
+                                headRefName
+                                title
+                                id
+                                isDraft
+                            }
+                        }
+                    }
+                ",
+                "variables": {
+                    "pullRequestId": node_id
+                }
+            }))
+            .await
+            .map_err(|e| Error::GitHubApi(format!("GraphQL mutation failed: {e}")))?;
+        let data = graphql_data(response)?;
+
+        debug!(pr_number, "converted PR to draft");
+        Ok(data.convert_pull_request_to_draft.pull_request.into())
+    }
+
     async fn list_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>> {
         debug!(pr_number, "listing PR comments");
         let comments = self
             .client
             .issues(&self.config.owner, &self.config.repo)
             .list_comments(pr_number)
+            .per_page(100)
             .send()
             .await?;
+        let comments = self.client.all_pages(comments).await?;
 
         let result: Vec<PrComment> = comments
-            .items
             .into_iter()
             .map(|c| PrComment {
                 id: c.id.0,
@@ -415,6 +1119,86 @@ impl PlatformService for GitHubService {
         Ok(result)
     }
 
+    async fn add_pr_labels(&self, pr_number: u64, labels: &[String]) -> Result<()> {
+        debug!(pr_number, ?labels, "adding PR labels");
+        self.client
+            .issues(&self.config.owner, &self.config.repo)
+            .add_labels(pr_number, labels)
+            .await?;
+        debug!(pr_number, "added PR labels");
+        Ok(())
+    }
+
+    async fn assign_pr(&self, pr_number: u64, assignees: &[String]) -> Result<()> {
+        debug!(pr_number, ?assignees, "assigning PR");
+        let assignees: Vec<&str> = assignees.iter().map(String::as_str).collect();
+        self.client
+            .issues(&self.config.owner, &self.config.repo)
+            .add_assignees(pr_number, &assignees)
+            .await?;
+        debug!(pr_number, "assigned PR");
+        Ok(())
+    }
+
+    async fn request_review(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        debug!(pr_number, ?reviewers, "requesting PR review");
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}/requested_reviewers",
+            self.api_url, self.config.owner, self.config.repo, pr_number
+        );
+
+        self.http_client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .json(&serde_json::json!({ "reviewers": reviewers }))
+            .send()
+            .await?
+            .check_rate_limit("GitHub")?
+            .error_for_status()
+            .map_err(|e| Error::GitHubApi(format!("Failed to request reviews: {e}")))?;
+
+        debug!(pr_number, "requested PR review");
+        Ok(())
+    }
+
+    async fn set_pr_milestone(&self, pr_number: u64, milestone: &str) -> Result<()> {
+        #[derive(Deserialize)]
+        struct Milestone {
+            number: u64,
+            title: String,
+        }
+
+        debug!(pr_number, milestone, "setting PR milestone");
+        let url = format!(
+            "{}/repos/{}/{}/milestones?state=open&per_page=100",
+            self.api_url, self.config.owner, self.config.repo
+        );
+        let milestones: Vec<Milestone> = self
+            .raw_get(&url)
+            .send()
+            .await?
+            .check_rate_limit("GitHub")?
+            .error_for_status()
+            .map_err(|e| Error::GitHubApi(format!("Failed to list milestones: {e}")))?
+            .json()
+            .await?;
+        let number = milestones
+            .into_iter()
+            .find(|m| m.title == milestone)
+            .map(|m| m.number)
+            .ok_or_else(|| Error::GitHubApi(format!("no open milestone named '{milestone}'")))?;
+
+        self.client
+            .issues(&self.config.owner, &self.config.repo)
+            .update(pr_number)
+            .milestone(number)
+            .send()
+            .await?;
+        debug!(pr_number, milestone, "set PR milestone");
+        Ok(())
+    }
+
     async fn create_pr_comment(&self, pr_number: u64, body: &str) -> Result<()> {
         debug!(pr_number, "creating PR comment");
         self.client
@@ -435,6 +1219,42 @@ impl PlatformService for GitHubService {
         Ok(())
     }
 
+    async fn delete_pr_comment(&self, _pr_number: u64, comment_id: u64) -> Result<()> {
+        debug!(comment_id, "deleting PR comment");
+        self.client
+            .issues(&self.config.owner, &self.config.repo)
+            .delete_comment(octocrab::models::CommentId(comment_id))
+            .await?;
+        debug!(comment_id, "deleted PR comment");
+        Ok(())
+    }
+
+    async fn current_user(&self) -> Result<String> {
+        let user = self.client.current().user().await?;
+        Ok(user.login)
+    }
+
+    async fn list_protected_branches(&self) -> Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct Branch {
+            name: String,
+        }
+
+        debug!("listing protected branches");
+        let url = format!(
+            "{}/repos/{}/{}/branches?protected=true&per_page=100",
+            self.api_url, self.config.owner, self.config.repo
+        );
+
+        let branches: Vec<Branch> = self
+            .get_all_pages(&url)
+            .await
+            .map_err(|e| Error::GitHubApi(format!("Failed to list protected branches: {e}")))?;
+
+        debug!(count = branches.len(), "listed protected branches");
+        Ok(branches.into_iter().map(|b| b.name).collect())
+    }
+
     fn config(&self) -> &PlatformConfig {
         &self.config
     }
@@ -468,59 +1288,250 @@ impl PlatformService for GitHubService {
             is_draft: pr.draft.unwrap_or(false),
             mergeable: pr.mergeable,
             head_ref: pr.head.ref_field.clone(),
+            head_sha: pr.head.sha.clone(),
             base_ref: pr.base.ref_field.clone(),
             html_url: pr
                 .html_url
                 .as_ref()
                 .map(ToString::to_string)
                 .unwrap_or_default(),
+            is_behind: pr.mergeable_state == Some(MergeableState::Behind),
         };
 
         debug!(pr_number, state = ?details.state, "got PR details");
         Ok(details)
     }
 
-    async fn check_merge_readiness(&self, pr_number: u64) -> Result<MergeReadiness> {
-        debug!(pr_number, "checking merge readiness");
-
-        // Get PR details first
-        let details = self.get_pr_details(pr_number).await?;
+    async fn list_reviews(&self, pr_number: u64) -> Result<Vec<PrReview>> {
+        debug!(pr_number, "listing reviews");
 
-        // Check reviews for approval
         let reviews = self
             .client
             .pulls(&self.config.owner, &self.config.repo)
             .list_reviews(pr_number)
+            .per_page(100)
             .send()
             .await?;
+        let reviews = self.client.all_pages(reviews).await?;
 
-        // Look for at least one APPROVED review
-        let is_approved = reviews.items.iter().any(|r| {
-            r.state
-                .as_ref()
-                .is_some_and(|s| *s == octocrab::models::pulls::ReviewState::Approved)
-        });
+        Ok(reviews
+            .into_iter()
+            .filter_map(|review| {
+                let state = match review.state? {
+                    ReviewState::Approved => PrReviewState::Approved,
+                    ReviewState::ChangesRequested => PrReviewState::ChangesRequested,
+                    ReviewState::Dismissed => PrReviewState::Dismissed,
+                    ReviewState::Pending => PrReviewState::Pending,
+                    // ReviewState is non-exhaustive; treat unknown states as comments
+                    _ => PrReviewState::Commented,
+                };
+                Some(PrReview {
+                    reviewer: review.user.map(|u| u.login).unwrap_or_default(),
+                    state,
+                    submitted_at: review.submitted_at,
+                })
+            })
+            .collect())
+    }
+
+    async fn list_review_threads(&self, pr_number: u64) -> Result<Vec<ReviewThread>> {
+        debug!(pr_number, "listing review threads");
+        let mut threads = Vec::new();
+        let mut after: Option<String> = None;
+
+        loop {
+            let response: GraphQlResponse<ReviewThreadsData> = self
+                .client
+                .graphql(&serde_json::json!({
+                    "query": r"
+                        query ReviewThreads($owner: String!, $repo: String!, $number: Int!, $after: String) {
+                            repository(owner: $owner, name: $repo) {
+                                pullRequest(number: $number) {
+                                    reviewThreads(first: 100, after: $after) {
+                                        pageInfo { hasNextPage endCursor }
+                                        nodes {
+                                            id
+                                            isResolved
+                                            path
+                                            line
+                                            comments(first: 1) {
+                                                totalCount
+                                                nodes { author { login } body }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    ",
+                    "variables": {
+                        "owner": self.config.owner,
+                        "repo": self.config.repo,
+                        "number": pr_number,
+                        "after": after,
+                    }
+                }))
+                .await
+                .map_err(|e| Error::GitHubApi(format!("GraphQL query failed: {e}")))?;
+            let connection = graphql_data(response)?
+                .repository
+                .pull_request
+                .review_threads;
+
+            threads.extend(connection.nodes.into_iter().map(|thread| {
+                let first = thread.comments.nodes.into_iter().next();
+                ReviewThread {
+                    id: thread.id,
+                    path: thread.path,
+                    line: thread.line,
+                    author: first
+                        .as_ref()
+                        .and_then(|c| c.author.as_ref())
+                        .map(|a| a.login.clone())
+                        .unwrap_or_default(),
+                    body: first.map(|c| c.body).unwrap_or_default(),
+                    comment_count: thread.comments.total_count,
+                    is_resolved: thread.is_resolved,
+                }
+            }));
+
+            match connection.page_info.end_cursor {
+                Some(cursor) if connection.page_info.has_next_page => after = Some(cursor),
+                _ => break,
+            }
+        }
+
+        debug!(pr_number, count = threads.len(), "listed review threads");
+        Ok(threads)
+    }
+
+    async fn resolve_thread(&self, pr_number: u64, thread_id: &str) -> Result<()> {
+        debug!(pr_number, thread_id, "resolving review thread");
+        require_feature(self.ghes_version, GhesFeature::GraphQlMutations)?;
+        let response: GraphQlResponse<serde_json::Value> = self
+            .client
+            .graphql(&serde_json::json!({
+                "query": r"
+                    mutation ResolveReviewThread($threadId: ID!) {
+                        resolveReviewThread(input: { threadId: $threadId }) {
+                            thread { id }
+                        }
+                    }
+                ",
+                "variables": {
+                    "threadId": thread_id
+                }
+            }))
+            .await
+            .map_err(|e| Error::GitHubApi(format!("GraphQL mutation failed: {e}")))?;
+        graphql_data(response)?;
+
+        debug!(pr_number, thread_id, "resolved review thread");
+        Ok(())
+    }
+
+    async fn list_checks(&self, git_ref: &str) -> Result<Vec<CheckInfo>> {
+        debug!(git_ref, "listing checks");
+
+        // Both CI systems: commit statuses (legacy API) and check runs (GitHub Actions API)
+        let mut checks = self.fetch_commit_statuses(git_ref).await?;
+        checks.extend(self.fetch_check_runs(git_ref).await?);
+        Ok(checks)
+    }
+
+    async fn create_commit_status(
+        &self,
+        sha: &str,
+        context: &str,
+        state: CheckState,
+        description: &str,
+    ) -> Result<()> {
+        debug!(sha, context, %state, "creating commit status");
+        let url = format!(
+            "{}/repos/{}/{}/statuses/{}",
+            self.api_url, self.config.owner, self.config.repo, sha
+        );
+        let state = match state {
+            CheckState::Pending => "pending",
+            CheckState::Success | CheckState::Skipped => "success",
+            CheckState::Failure => "failure",
+        };
+
+        self.http_client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .json(&serde_json::json!({
+                "state": state,
+                "context": context,
+                "description": description,
+            }))
+            .send()
+            .await?
+            .check_rate_limit("GitHub")?
+            .error_for_status()
+            .map_err(|e| Error::GitHubApi(format!("Failed to create commit status: {e}")))?;
+        Ok(())
+    }
+
+    async fn check_merge_readiness(&self, details: &PullRequestDetails) -> Result<MergeReadiness> {
+        let pr_number = details.number;
+        debug!(pr_number, "checking merge readiness");
+
+        let protection = self.branch_protection(&details.base_ref).await;
+
+        // Approved = enough reviewers' latest verdicts approve (at least one if
+        // the rules are unknown) and none requests changes
+        let reviews = self.list_reviews(pr_number).await?;
+        let changes_requested = changes_requested_by(&reviews);
+        let approvals = approval_count(&reviews);
+        let is_approved = protection.required_approvals.map_or_else(
+            || is_approved_by_reviews(&reviews),
+            |required| changes_requested.is_empty() && approvals >= required as usize,
+        );
 
         // Check CI status
-        let ci_passed = self
-            .check_ci_status(&details.head_ref)
+        let checks = self
+            .check_ci_status(details, &protection.required_checks)
             .await
-            .unwrap_or(true); // If we can't check, assume passing
+            .unwrap_or_default(); // If we can't check, assume no CI
 
         // Build blocking reasons (definitive blockers)
         let mut blocking_reasons = Vec::new();
         if details.is_draft {
             blocking_reasons.push("PR is a draft".to_string());
         }
-        if !is_approved {
-            blocking_reasons.push("Not approved".to_string());
-        }
-        if !ci_passed {
-            blocking_reasons.push("CI not passing".to_string());
+        if !changes_requested.is_empty() {
+            blocking_reasons.push(format!(
+                "Changes requested by {}",
+                changes_requested.join(", ")
+            ));
+        } else if !is_approved {
+            match protection.required_approvals {
+                Some(required) => blocking_reasons.push(format!(
+                    "Needs {required} approval{} (has {approvals})",
+                    if required == 1 { "" } else { "s" }
+                )),
+                None => blocking_reasons.push("Not approved".to_string()),
+            }
         }
         if details.mergeable == Some(false) {
             blocking_reasons.push("Has merge conflicts".to_string());
         }
+        if protection.require_conversation_resolution {
+            let unresolved = self
+                .list_review_threads(pr_number)
+                .await?
+                .iter()
+                .filter(|thread| !thread.is_resolved)
+                .count();
+            if unresolved > 0 {
+                blocking_reasons.push(format!(
+                    "{unresolved} unresolved review thread{}",
+                    if unresolved == 1 { "" } else { "s" }
+                ));
+            }
+        }
 
         // Build uncertainties (unknown states, not definitive blockers)
         let mut uncertainties = Vec::new();
@@ -530,7 +1541,9 @@ impl PlatformService for GitHubService {
 
         let readiness = MergeReadiness {
             is_approved,
-            ci_passed,
+            approvals_required: None,
+            approvals_received: None,
+            checks,
             is_mergeable: details.mergeable,
             is_draft: details.is_draft,
             blocking_reasons,
@@ -546,11 +1559,73 @@ impl PlatformService for GitHubService {
         Ok(readiness)
     }
 
-    async fn merge_pr(&self, pr_number: u64, method: MergeMethod) -> Result<MergeResult> {
-        debug!(pr_number, %method, "merging PR");
+    async fn update_pr_branch(&self, pr_number: u64) -> Result<()> {
+        debug!(pr_number, "updating PR branch");
+        self.client
+            .pulls(&self.config.owner, &self.config.repo)
+            .update_branch(pr_number)
+            .await
+            .map_err(|e| Error::GitHubApi(format!("Failed to update branch: {e}")))?;
+        Ok(())
+    }
 
-        // Get PR details for commit message (squash needs title/body)
-        let details = self.get_pr_details(pr_number).await?;
+    async fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        debug!(pr_number, %method, "enabling auto-merge");
+        require_feature(self.ghes_version, GhesFeature::AutoMerge)?;
+        let pr = self
+            .client
+            .pulls(&self.config.owner, &self.config.repo)
+            .get(pr_number)
+            .await?;
+        let node_id = pr.node_id.as_ref().ok_or_else(|| {
+            Error::GitHubApi("PR missing node_id for GraphQL mutation".to_string())
+        })?;
+
+        let merge_method = match method {
+            MergeMethod::Squash => "SQUASH",
+            MergeMethod::Merge => "MERGE",
+            MergeMethod::Rebase => "REBASE",
+        };
+        let response: GraphQlResponse<serde_json::Value> = self
+            .client
+            .graphql(&serde_json::json!({
+                "query": r"
+                    mutation EnableAutoMerge($pullRequestId: ID!, $mergeMethod: PullRequestMergeMethod!) {
+                        enablePullRequestAutoMerge(input: { pullRequestId: $pullRequestId, mergeMethod: $mergeMethod }) {
+                            clientMutationId
+                        }
+                    }
+                ",
+                "variables": {
+                    "pullRequestId": node_id,
+                    "mergeMethod": merge_method
+                }
+            }))
+            .await
+            .map_err(|e| Error::GitHubApi(format!("GraphQL mutation failed: {e}")))?;
+
+        if let Some(errors) = response.errors
+            && !errors.is_empty()
+        {
+            let messages: Vec<_> = errors.into_iter().map(|e| e.message).collect();
+            return Err(Error::GitHubApi(format!(
+                "GraphQL error: {}",
+                messages.join(", ")
+            )));
+        }
+
+        debug!(pr_number, "enabled auto-merge");
+        Ok(())
+    }
+
+    async fn merge_pr(
+        &self,
+        pr_number: u64,
+        title: &str,
+        body: Option<&str>,
+        method: MergeMethod,
+    ) -> Result<MergeResult> {
+        debug!(pr_number, %method, "merging PR");
 
         let octocrab_method = match method {
             MergeMethod::Squash => octocrab::params::pulls::MergeMethod::Squash,
@@ -564,8 +1639,8 @@ impl PlatformService for GitHubService {
         // For squash, use PR title and body as commit message
         let result = if method == MergeMethod::Squash {
             let mut builder = pulls.merge(pr_number).method(octocrab_method);
-            builder = builder.title(format!("{} (#{})", details.title, pr_number));
-            if let Some(ref body) = details.body {
+            builder = builder.title(format!("{title} (#{pr_number})"));
+            if let Some(body) = body {
                 builder = builder.message(body);
             }
             builder.send().await
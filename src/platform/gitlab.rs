@@ -1,13 +1,19 @@
 //! GitLab platform service implementation
 
+use crate::auth::{GitLabTokenKind, validate_gitlab_token};
 use crate::error::{Error, Result};
-use crate::platform::PlatformService;
+use crate::platform::{
+    PlatformService, RateLimitCheck, STACK_STATUS_CONTEXT, changes_requested_by,
+};
 use crate::types::{
-    MergeMethod, MergeReadiness, MergeResult, Platform, PlatformConfig, PrComment, PrState,
-    PullRequest, PullRequestDetails,
+    CheckInfo, CheckState, MergeMethod, MergeReadiness, MergeResult, Platform, PlatformConfig, PrComment, PrReview,
+    PrState, PullRequest, PullRequestDetails, ReviewState, ReviewThread,
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use reqwest::Client;
+use reqwest::header::HeaderMap;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
@@ -15,7 +21,9 @@ use tracing::debug;
 pub struct GitLabService {
     client: Client,
     token: String,
-    host: String,
+    token_kind: GitLabTokenKind,
+    /// API base URL (`https://{host}/api/v4`)
+    api_url: String,
     config: PlatformConfig,
     project_path: String,
 }
@@ -28,9 +36,16 @@ struct MergeRequest {
     target_branch: String,
     title: String,
     #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
     draft: bool,
 }
 
+#[derive(Deserialize)]
+struct ProtectedBranch {
+    name: String,
+}
+
 #[derive(Deserialize)]
 struct MrNote {
     id: u64,
@@ -38,6 +53,30 @@ struct MrNote {
     system: bool,
 }
 
+#[derive(Deserialize)]
+struct MrDiscussion {
+    id: String,
+    notes: Vec<MrDiscussionNote>,
+}
+
+#[derive(Deserialize)]
+struct MrDiscussionNote {
+    body: String,
+    author: GitLabUser,
+    #[serde(default)]
+    resolvable: bool,
+    #[serde(default)]
+    resolved: bool,
+    position: Option<NotePosition>,
+}
+
+/// Where a diff note is, in the MR's changes
+#[derive(Deserialize)]
+struct NotePosition {
+    new_path: Option<String>,
+    new_line: Option<u64>,
+}
+
 /// Extended MR details for merge operations
 #[derive(Deserialize)]
 struct MergeRequestDetails {
@@ -48,21 +87,126 @@ struct MergeRequestDetails {
     #[serde(default)]
     draft: bool,
     merge_status: String, // "can_be_merged", "cannot_be_merged", etc.
+    #[serde(default)]
+    detailed_merge_status: Option<String>, // "need_rebase", "mergeable", etc.
     web_url: String,
     source_branch: String,
     target_branch: String,
+    #[serde(default)]
+    sha: Option<String>,
 }
 
 /// MR approvals response
 #[derive(Deserialize)]
 struct MrApprovals {
     approved: bool,
+    #[serde(default)]
+    approved_by: Vec<MrApprover>,
+}
+
+#[derive(Deserialize)]
+struct MrApprover {
+    user: GitLabUser,
+}
+
+/// MR approval state response (approval rules and who satisfied them)
+#[derive(Deserialize)]
+struct MrApprovalState {
+    #[serde(default)]
+    rules: Vec<MrApprovalRule>,
+}
+
+#[derive(Deserialize)]
+struct MrApprovalRule {
+    approvals_required: u32,
+    #[serde(default)]
+    approved_by: Vec<GitLabUser>,
+}
+
+/// Total (required, received) approvals across all approval rules
+///
+/// Approvals beyond a rule's requirement don't count towards another rule.
+fn approval_counts(state: &MrApprovalState) -> (u32, u32) {
+    state.rules.iter().fold((0, 0), |(required, received), rule| {
+        let approved = u32::try_from(rule.approved_by.len()).unwrap_or(u32::MAX);
+        (
+            required + rule.approvals_required,
+            received + approved.min(rule.approvals_required),
+        )
+    })
+}
+
+#[derive(Deserialize)]
+struct GitLabUser {
+    username: String,
+}
+
+/// User ID, needed to assign MRs
+#[derive(Deserialize)]
+struct GitLabUserId {
+    id: u64,
+}
+
+/// An MR's current reviewers, kept when adding more
+#[derive(Deserialize)]
+struct MrReviewerIds {
+    #[serde(default)]
+    reviewers: Vec<GitLabUserId>,
+}
+
+/// Milestone ID, needed to set an MR's milestone
+#[derive(Deserialize)]
+struct GitLabMilestone {
+    id: u64,
+}
+
+/// MR reviewer and their review state
+#[derive(Deserialize)]
+struct MrReviewer {
+    user: GitLabUser,
+    state: String, // "unreviewed", "reviewed", "requested_changes", "approved", "unapproved"
+    created_at: Option<DateTime<Utc>>,
 }
 
 /// Pipeline status
 #[derive(Deserialize)]
 struct Pipeline {
+    id: u64,
     status: String, // "success", "failed", "running", "pending"
+    web_url: Option<String>,
+}
+
+/// Pipeline job
+#[derive(Deserialize)]
+struct PipelineJob {
+    name: String,
+    status: String,
+    web_url: Option<String>,
+    #[serde(default)]
+    allow_failure: bool,
+}
+
+/// Map an unsuccessful response status to an error
+///
+/// Server errors stay [`Error::Http`] so they are retried (see
+/// [`super::RetryingPlatformService`]).
+fn status_error(e: reqwest::Error) -> Error {
+    if e.status().is_some_and(|status| status.is_server_error()) {
+        Error::Http(e)
+    } else {
+        Error::GitLabApi(e.to_string())
+    }
+}
+
+/// Map a GitLab pipeline or job status to a check state
+fn check_state(status: &str) -> CheckState {
+    match status {
+        "success" => CheckState::Success,
+        "skipped" | "manual" => CheckState::Skipped,
+        "failed" | "canceled" => CheckState::Failure,
+        // created, pending, running, preparing, scheduled, waiting_for_resource
+        _ => CheckState::Pending,
+    }
 }
 
 /// Merge response
@@ -104,6 +248,28 @@ impl GitLabService {
     /// Create a new GitLab service
     pub fn new(token: String, owner: String, repo: String, host: Option<String>) -> Result<Self> {
         let host = host.unwrap_or_else(|| "gitlab.com".to_string());
+        let api_url = format!("https://{host}/api/v4");
+        let config_host = if host == "gitlab.com" { None } else { Some(host) };
+        Self::build(token, owner, repo, config_host, api_url)
+    }
+
+    /// Create a GitLab service that sends every API request to `api_url`
+    ///
+    /// `api_url` includes the `/api/v4` prefix. Used to point the service at
+    /// an HTTP proxy or a local replay server; the repo is reported as hosted
+    /// on gitlab.com.
+    pub fn with_api_url(token: String, owner: String, repo: String, api_url: &str) -> Result<Self> {
+        let api_url = api_url.trim_end_matches('/').to_string();
+        Self::build(token, owner, repo, None, api_url)
+    }
+
+    fn build(
+        token: String,
+        owner: String,
+        repo: String,
+        host: Option<String>,
+        api_url: String,
+    ) -> Result<Self> {
         let project_path = format!("{owner}/{repo}");
 
         let client = Client::builder()
@@ -111,33 +277,216 @@ impl GitLabService {
             .build()
             .map_err(|e| Error::GitLabApi(format!("failed to create HTTP client: {e}")))?;
 
-        let config_host = if host == "gitlab.com" {
-            None
-        } else {
-            Some(host.clone())
-        };
-
         Ok(Self {
             client,
             token,
-            host,
+            token_kind: GitLabTokenKind::Personal,
+            api_url,
             config: PlatformConfig {
                 platform: Platform::GitLab,
                 owner,
                 repo,
-                host: config_host,
+                host,
             },
             project_path,
         })
     }
 
+    /// Set how the token is sent (a personal access token by default)
+    #[must_use]
+    pub const fn with_token_kind(mut self, kind: GitLabTokenKind) -> Self {
+        self.token_kind = kind;
+        self
+    }
+
+    /// Check up front that the token has the `api` scope
+    pub async fn validate_token(&self) -> Result<()> {
+        validate_gitlab_token(&self.token, self.token_kind, &self.api_url).await
+    }
+
+    /// Headers that authenticate a request with the token
+    fn auth_headers(&self) -> HeaderMap {
+        self.token_kind.headers(&self.token)
+    }
+
     fn api_url(&self, path: &str) -> String {
-        format!("https://{}/api/v4{}", self.host, path)
+        format!("{}{}", self.api_url, path)
     }
 
     fn encoded_project(&self) -> String {
         urlencoding::encode(&self.project_path).into_owned()
     }
+
+    /// GET every page of a list endpoint
+    ///
+    /// Follows GitLab's `X-Next-Page` header, 100 items at a time.
+    async fn get_all_pages<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        let mut page = "1".to_string();
+        loop {
+            let response = self
+                .client
+                .get(url)
+                .headers(self.auth_headers())
+                .query(query)
+                .query(&[("per_page", "100"), ("page", page.as_str())])
+                .send()
+                .await?
+                .check_rate_limit("GitLab")?
+                .error_for_status()
+                .map_err(status_error)?;
+            let next_page = response
+                .headers()
+                .get("X-Next-Page")
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(ToString::to_string);
+            items.extend(response.json::<Vec<T>>().await?);
+
+            match next_page {
+                Some(next) => page = next,
+                None => return Ok(items),
+            }
+        }
+    }
+
+    /// ID of the user with this username
+    async fn user_id(&self, username: &str) -> Result<u64> {
+        let users: Vec<GitLabUserId> = self
+            .client
+            .get(self.api_url("/users"))
+            .headers(self.auth_headers())
+            .query(&[("username", username)])
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?
+            .json()
+            .await?;
+        users
+            .first()
+            .map(|user| user.id)
+            .ok_or_else(|| Error::GitLabApi(format!("no GitLab user named '{username}'")))
+    }
+
+    /// Ask an existing reviewer of an MR to review it again
+    ///
+    /// Only the GraphQL API can re-request a review.
+    async fn rerequest_review(&self, pr_number: u64, user_id: u64) -> Result<()> {
+        const MUTATION: &str = r"
+            mutation RerequestReview($projectPath: ID!, $iid: String!, $userId: UserID!) {
+                mergeRequestReviewerRereview(
+                    input: { projectPath: $projectPath, iid: $iid, userId: $userId }
+                ) {
+                    errors
+                }
+            }";
+
+        let url = format!("{}/graphql", self.api_url.trim_end_matches("/v4"));
+        let response: serde_json::Value = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.token)
+            .json(&serde_json::json!({
+                "query": MUTATION,
+                "variables": {
+                    "projectPath": self.project_path,
+                    "iid": pr_number.to_string(),
+                    "userId": format!("gid://gitlab/User/{user_id}"),
+                },
+            }))
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?
+            .json()
+            .await?;
+
+        let error = response
+            .pointer("/errors/0/message")
+            .or_else(|| response.pointer("/data/mergeRequestReviewerRereview/errors/0"))
+            .and_then(serde_json::Value::as_str);
+        if let Some(message) = error {
+            return Err(Error::GitLabApi(format!(
+                "Re-requesting review failed: {message}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Most recent pipeline from a pipelines listing (None if there are none)
+    async fn latest_pipeline(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Option<Pipeline>> {
+        let pipelines: Vec<Pipeline> = self
+            .client
+            .get(url)
+            .headers(self.auth_headers())
+            .query(query)
+            .query(&[("order_by", "id"), ("sort", "desc"), ("per_page", "1")])
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?
+            .json()
+            .await?;
+        Ok(pipelines.into_iter().next())
+    }
+
+    /// List a pipeline's jobs as checks (jobs allowed to fail are not required)
+    ///
+    /// Falls back to a single check for the whole pipeline if jobs can't be listed.
+    async fn pipeline_checks(&self, pipeline: Pipeline) -> Vec<CheckInfo> {
+        let jobs_url = self.api_url(&format!(
+            "/projects/{}/pipelines/{}/jobs",
+            self.encoded_project(),
+            pipeline.id
+        ));
+
+        let jobs = match self
+            .client
+            .get(&jobs_url)
+            .headers(self.auth_headers())
+            .query(&[("per_page", "100")])
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => {
+                response.json::<Vec<PipelineJob>>().await.ok()
+            }
+            _ => None,
+        };
+
+        let Some(jobs) = jobs else {
+            debug!(pipeline_id = pipeline.id, "could not list pipeline jobs");
+            return vec![CheckInfo {
+                name: "pipeline".to_string(),
+                state: check_state(&pipeline.status),
+                required: true,
+                url: pipeline.web_url,
+            }];
+        };
+
+        jobs.into_iter()
+            .filter(|job| job.name != STACK_STATUS_CONTEXT)
+            .map(|job| CheckInfo {
+                state: check_state(&job.status),
+                name: job.name,
+                required: !job.allow_failure,
+                url: job.web_url,
+            })
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -150,15 +499,7 @@ impl PlatformService for GitLabService {
         ));
 
         let mrs: Vec<MergeRequest> = self
-            .client
-            .get(&url)
-            .header("PRIVATE-TOKEN", &self.token)
-            .query(&[("source_branch", head_branch), ("state", "opened")])
-            .send()
-            .await?
-            .error_for_status()
-            .map_err(|e| Error::GitLabApi(e.to_string()))?
-            .json()
+            .get_all_pages(&url, &[("source_branch", head_branch), ("state", "opened")])
             .await?;
 
         let result: Option<PullRequest> = mrs.into_iter().next().map(Into::into);
@@ -170,6 +511,50 @@ impl PlatformService for GitLabService {
         Ok(result)
     }
 
+    async fn find_closed_pr(&self, head_branch: &str) -> Result<Option<PullRequest>> {
+        debug!(head_branch, "finding closed MR");
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests",
+            self.encoded_project()
+        ));
+
+        // GitLab lists merged MRs separately, so these can all be reopened
+        let mrs: Vec<MergeRequest> = self
+            .get_all_pages(&url, &[("source_branch", head_branch), ("state", "closed")])
+            .await?;
+
+        let result = mrs
+            .into_iter()
+            .map(PullRequest::from)
+            .max_by_key(|pr| pr.number);
+        debug!(mr_iid = result.as_ref().map(|pr| pr.number), "looked up closed MR");
+        Ok(result)
+    }
+
+    async fn find_pr_by_body(&self, text: &str) -> Result<Option<PullRequest>> {
+        debug!(text, "finding MR by description");
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests",
+            self.encoded_project()
+        ));
+
+        // GitLab's search is fuzzy, so the matches are checked again
+        let mrs: Vec<MergeRequest> = self
+            .get_all_pages(
+                &url,
+                &[("state", "opened"), ("search", text), ("in", "description")],
+            )
+            .await?;
+
+        let result = mrs
+            .into_iter()
+            .filter(|mr| mr.description.as_deref().is_some_and(|d| d.contains(text)))
+            .map(PullRequest::from)
+            .max_by_key(|pr| pr.number);
+        debug!(mr_iid = result.as_ref().map(|pr| pr.number), "looked up MR by description");
+        Ok(result)
+    }
+
     async fn create_pr_with_options(
         &self,
         head: &str,
@@ -195,12 +580,13 @@ impl PlatformService for GitLabService {
         let mr: MergeRequest = self
             .client
             .post(&url)
-            .header("PRIVATE-TOKEN", &self.token)
+            .headers(self.auth_headers())
             .json(&payload)
             .send()
             .await?
+            .check_rate_limit("GitLab")?
             .error_for_status()
-            .map_err(|e| Error::GitLabApi(e.to_string()))?
+            .map_err(status_error)?
             .json()
             .await?;
 
@@ -220,12 +606,13 @@ impl PlatformService for GitLabService {
         let mr: MergeRequest = self
             .client
             .put(&url)
-            .header("PRIVATE-TOKEN", &self.token)
+            .headers(self.auth_headers())
             .json(&serde_json::json!({ "target_branch": new_base }))
             .send()
             .await?
+            .check_rate_limit("GitLab")?
             .error_for_status()
-            .map_err(|e| Error::GitLabApi(e.to_string()))?
+            .map_err(status_error)?
             .json()
             .await?;
 
@@ -233,6 +620,237 @@ impl PlatformService for GitLabService {
         Ok(mr.into())
     }
 
+    async fn update_pr_title(&self, pr_number: u64, title: &str) -> Result<PullRequest> {
+        debug!(mr_iid = pr_number, title, "updating MR title");
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}",
+            self.encoded_project(),
+            pr_number
+        ));
+
+        let mr: MergeRequest = self
+            .client
+            .put(&url)
+            .headers(self.auth_headers())
+            .json(&serde_json::json!({ "title": title }))
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?
+            .json()
+            .await?;
+
+        debug!(mr_iid = pr_number, "updated MR title");
+        Ok(mr.into())
+    }
+
+    async fn update_pr_body(&self, pr_number: u64, body: &str) -> Result<()> {
+        debug!(mr_iid = pr_number, "updating MR description");
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}",
+            self.encoded_project(),
+            pr_number
+        ));
+
+        self.client
+            .put(&url)
+            .headers(self.auth_headers())
+            .json(&serde_json::json!({ "description": body }))
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?;
+
+        debug!(mr_iid = pr_number, "updated MR description");
+        Ok(())
+    }
+
+    async fn add_pr_labels(&self, pr_number: u64, labels: &[String]) -> Result<()> {
+        debug!(mr_iid = pr_number, ?labels, "adding MR labels");
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}",
+            self.encoded_project(),
+            pr_number
+        ));
+
+        self.client
+            .put(&url)
+            .headers(self.auth_headers())
+            .json(&serde_json::json!({ "add_labels": labels.join(",") }))
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?;
+
+        debug!(mr_iid = pr_number, "added MR labels");
+        Ok(())
+    }
+
+    async fn assign_pr(&self, pr_number: u64, assignees: &[String]) -> Result<()> {
+        debug!(mr_iid = pr_number, ?assignees, "assigning MR");
+        let mut assignee_ids = Vec::with_capacity(assignees.len());
+        for username in assignees {
+            assignee_ids.push(self.user_id(username).await?);
+        }
+
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}",
+            self.encoded_project(),
+            pr_number
+        ));
+        self.client
+            .put(&url)
+            .headers(self.auth_headers())
+            .json(&serde_json::json!({ "assignee_ids": assignee_ids }))
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?;
+
+        debug!(mr_iid = pr_number, "assigned MR");
+        Ok(())
+    }
+
+    async fn request_review(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        debug!(mr_iid = pr_number, ?reviewers, "requesting MR review");
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}",
+            self.encoded_project(),
+            pr_number
+        ));
+        let mr: MrReviewerIds = self
+            .client
+            .get(&url)
+            .headers(self.auth_headers())
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?
+            .json()
+            .await?;
+
+        // Existing reviewers are asked again; anyone else is added
+        let mut reviewer_ids: Vec<u64> = mr.reviewers.iter().map(|user| user.id).collect();
+        let mut rerequest = Vec::new();
+        for username in reviewers {
+            let id = self.user_id(username).await?;
+            if reviewer_ids.contains(&id) {
+                rerequest.push(id);
+            } else {
+                reviewer_ids.push(id);
+            }
+        }
+
+        if reviewer_ids.len() > mr.reviewers.len() {
+            self.client
+                .put(&url)
+                .headers(self.auth_headers())
+                .json(&serde_json::json!({ "reviewer_ids": reviewer_ids }))
+                .send()
+                .await?
+                .check_rate_limit("GitLab")?
+                .error_for_status()
+                .map_err(status_error)?;
+        }
+        for user_id in rerequest {
+            self.rerequest_review(pr_number, user_id).await?;
+        }
+
+        debug!(mr_iid = pr_number, "requested MR review");
+        Ok(())
+    }
+
+    async fn set_pr_milestone(&self, pr_number: u64, milestone: &str) -> Result<()> {
+        debug!(mr_iid = pr_number, milestone, "setting MR milestone");
+        let url = self.api_url(&format!("/projects/{}/milestones", self.encoded_project()));
+        let milestones: Vec<GitLabMilestone> = self
+            .client
+            .get(&url)
+            .headers(self.auth_headers())
+            .query(&[("title", milestone), ("state", "active")])
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?
+            .json()
+            .await?;
+        let milestone_id = milestones
+            .first()
+            .map(|m| m.id)
+            .ok_or_else(|| Error::GitLabApi(format!("no active milestone named '{milestone}'")))?;
+
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}",
+            self.encoded_project(),
+            pr_number
+        ));
+        self.client
+            .put(&url)
+            .headers(self.auth_headers())
+            .json(&serde_json::json!({ "milestone_id": milestone_id }))
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?;
+
+        debug!(mr_iid = pr_number, milestone, "set MR milestone");
+        Ok(())
+    }
+
+    async fn close_pr(&self, pr_number: u64) -> Result<()> {
+        debug!(mr_iid = pr_number, "closing MR");
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}",
+            self.encoded_project(),
+            pr_number
+        ));
+
+        self.client
+            .put(&url)
+            .headers(self.auth_headers())
+            .json(&serde_json::json!({ "state_event": "close" }))
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?;
+
+        debug!(mr_iid = pr_number, "closed MR");
+        Ok(())
+    }
+
+    async fn reopen_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        debug!(mr_iid = pr_number, "reopening MR");
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}",
+            self.encoded_project(),
+            pr_number
+        ));
+
+        let mr: MergeRequest = self
+            .client
+            .put(&url)
+            .headers(self.auth_headers())
+            .json(&serde_json::json!({ "state_event": "reopen" }))
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?
+            .json()
+            .await?;
+
+        debug!(mr_iid = pr_number, "reopened MR");
+        Ok(mr.into())
+    }
+
     async fn publish_pr(&self, pr_number: u64) -> Result<PullRequest> {
         debug!(mr_iid = pr_number, "publishing MR");
         // GitLab: Use state_event to mark MR as ready
@@ -247,12 +865,13 @@ impl PlatformService for GitLabService {
         let mr: MergeRequest = self
             .client
             .put(&url)
-            .header("PRIVATE-TOKEN", &self.token)
+            .headers(self.auth_headers())
             .json(&serde_json::json!({ "state_event": "ready" }))
             .send()
             .await?
+            .check_rate_limit("GitLab")?
             .error_for_status()
-            .map_err(|e| Error::GitLabApi(e.to_string()))?
+            .map_err(status_error)?
             .json()
             .await?;
 
@@ -260,25 +879,41 @@ impl PlatformService for GitLabService {
         Ok(mr.into())
     }
 
-    async fn list_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>> {
-        debug!(mr_iid = pr_number, "listing MR comments");
+    async fn unpublish_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        debug!(mr_iid = pr_number, "marking MR as draft");
         let url = self.api_url(&format!(
-            "/projects/{}/merge_requests/{}/notes",
+            "/projects/{}/merge_requests/{}",
             self.encoded_project(),
             pr_number
         ));
 
-        let notes: Vec<MrNote> = self
+        let mr: MergeRequest = self
             .client
-            .get(&url)
-            .header("PRIVATE-TOKEN", &self.token)
+            .put(&url)
+            .headers(self.auth_headers())
+            .json(&serde_json::json!({ "state_event": "draft" }))
             .send()
             .await?
+            .check_rate_limit("GitLab")?
             .error_for_status()
-            .map_err(|e| Error::GitLabApi(e.to_string()))?
+            .map_err(status_error)?
             .json()
             .await?;
 
+        debug!(mr_iid = pr_number, "marked MR as draft");
+        Ok(mr.into())
+    }
+
+    async fn list_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>> {
+        debug!(mr_iid = pr_number, "listing MR comments");
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}/notes",
+            self.encoded_project(),
+            pr_number
+        ));
+
+        let notes: Vec<MrNote> = self.get_all_pages(&url, &[]).await?;
+
         let comments: Vec<PrComment> = notes
             .into_iter()
             .filter(|n| !n.system)
@@ -305,12 +940,13 @@ impl PlatformService for GitLabService {
 
         self.client
             .post(&url)
-            .header("PRIVATE-TOKEN", &self.token)
+            .headers(self.auth_headers())
             .json(&serde_json::json!({ "body": body }))
             .send()
             .await?
+            .check_rate_limit("GitLab")?
             .error_for_status()
-            .map_err(|e| Error::GitLabApi(e.to_string()))?;
+            .map_err(status_error)?;
 
         debug!(mr_iid = pr_number, "created MR comment");
         Ok(())
@@ -327,17 +963,79 @@ impl PlatformService for GitLabService {
 
         self.client
             .put(&url)
-            .header("PRIVATE-TOKEN", &self.token)
+            .headers(self.auth_headers())
             .json(&serde_json::json!({ "body": body }))
             .send()
             .await?
+            .check_rate_limit("GitLab")?
             .error_for_status()
-            .map_err(|e| Error::GitLabApi(e.to_string()))?;
+            .map_err(status_error)?;
 
         debug!(mr_iid = pr_number, comment_id, "updated MR comment");
         Ok(())
     }
 
+    async fn delete_pr_comment(&self, pr_number: u64, comment_id: u64) -> Result<()> {
+        debug!(mr_iid = pr_number, comment_id, "deleting MR comment");
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}/notes/{}",
+            self.encoded_project(),
+            pr_number,
+            comment_id
+        ));
+
+        self.client
+            .delete(&url)
+            .headers(self.auth_headers())
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?;
+
+        debug!(mr_iid = pr_number, comment_id, "deleted MR comment");
+        Ok(())
+    }
+
+    async fn current_user(&self) -> Result<String> {
+        let user: GitLabUser = self
+            .client
+            .get(self.api_url("/user"))
+            .headers(self.auth_headers())
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?
+            .json()
+            .await?;
+        Ok(user.username)
+    }
+
+    async fn list_protected_branches(&self) -> Result<Vec<String>> {
+        debug!("listing protected branches");
+        let url = self.api_url(&format!(
+            "/projects/{}/protected_branches",
+            self.encoded_project()
+        ));
+
+        let branches: Vec<ProtectedBranch> = self
+            .client
+            .get(&url)
+            .headers(self.auth_headers())
+            .query(&[("per_page", "100")])
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?
+            .json()
+            .await?;
+
+        debug!(count = branches.len(), "listed protected branches");
+        Ok(branches.into_iter().map(|b| b.name).collect())
+    }
+
     fn config(&self) -> &PlatformConfig {
         &self.config
     }
@@ -358,11 +1056,12 @@ impl PlatformService for GitLabService {
         let mr: MergeRequestDetails = self
             .client
             .get(&url)
-            .header("PRIVATE-TOKEN", &self.token)
+            .headers(self.auth_headers())
             .send()
             .await?
+            .check_rate_limit("GitLab")?
             .error_for_status()
-            .map_err(|e| Error::GitLabApi(e.to_string()))?
+            .map_err(status_error)?
             .json()
             .await?;
 
@@ -380,19 +1079,194 @@ impl PlatformService for GitLabService {
             is_draft: mr.draft,
             mergeable: Some(mr.merge_status == "can_be_merged"),
             head_ref: mr.source_branch,
+            head_sha: mr.sha.unwrap_or_default(),
             base_ref: mr.target_branch,
             html_url: mr.web_url,
+            is_behind: mr.detailed_merge_status.as_deref() == Some("need_rebase"),
         };
 
         debug!(mr_iid = pr_number, state = ?details.state, "got MR details");
         Ok(details)
     }
 
-    async fn check_merge_readiness(&self, pr_number: u64) -> Result<MergeReadiness> {
-        debug!(mr_iid = pr_number, "checking merge readiness");
+    async fn list_reviews(&self, pr_number: u64) -> Result<Vec<PrReview>> {
+        debug!(mr_iid = pr_number, "listing reviews");
 
-        // Get MR details first
-        let details = self.get_pr_details(pr_number).await?;
+        let reviewers_url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}/reviewers",
+            self.encoded_project(),
+            pr_number
+        ));
+        let reviewers: Vec<MrReviewer> = self
+            .client
+            .get(&reviewers_url)
+            .headers(self.auth_headers())
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?
+            .json()
+            .await?;
+
+        let approvals_url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}/approvals",
+            self.encoded_project(),
+            pr_number
+        ));
+        let approvals: MrApprovals = self
+            .client
+            .get(&approvals_url)
+            .headers(self.auth_headers())
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?
+            .json()
+            .await?;
+
+        let mut reviews: Vec<PrReview> = reviewers
+            .into_iter()
+            .filter_map(|reviewer| {
+                let state = match reviewer.state.as_str() {
+                    "requested_changes" => ReviewState::ChangesRequested,
+                    "reviewed" => ReviewState::Commented,
+                    "unapproved" => ReviewState::Dismissed,
+                    // Approvals are taken from the approvals endpoint below
+                    _ => return None,
+                };
+                Some(PrReview {
+                    reviewer: reviewer.user.username,
+                    state,
+                    submitted_at: reviewer.created_at,
+                })
+            })
+            .collect();
+        // Approvals carry no timestamp, so list them first (oldest)
+        let approved: Vec<PrReview> = approvals
+            .approved_by
+            .into_iter()
+            .map(|approver| PrReview {
+                reviewer: approver.user.username,
+                state: ReviewState::Approved,
+                submitted_at: None,
+            })
+            .collect();
+        reviews.splice(0..0, approved);
+
+        debug!(mr_iid = pr_number, count = reviews.len(), "listed reviews");
+        Ok(reviews)
+    }
+
+    async fn list_review_threads(&self, pr_number: u64) -> Result<Vec<ReviewThread>> {
+        debug!(mr_iid = pr_number, "listing MR discussions");
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}/discussions",
+            self.encoded_project(),
+            pr_number
+        ));
+        let discussions: Vec<MrDiscussion> = self.get_all_pages(&url, &[]).await?;
+
+        // Only discussions that can be resolved are review threads
+        let threads: Vec<ReviewThread> = discussions
+            .into_iter()
+            .filter_map(|discussion| {
+                let first = discussion.notes.first().filter(|n| n.resolvable)?;
+                let position = first.position.as_ref();
+                Some(ReviewThread {
+                    path: position.and_then(|p| p.new_path.clone()),
+                    line: position.and_then(|p| p.new_line),
+                    author: first.author.username.clone(),
+                    body: first.body.clone(),
+                    comment_count: discussion.notes.len(),
+                    is_resolved: discussion.notes.iter().all(|n| !n.resolvable || n.resolved),
+                    id: discussion.id,
+                })
+            })
+            .collect();
+        debug!(
+            mr_iid = pr_number,
+            count = threads.len(),
+            "listed MR discussions"
+        );
+        Ok(threads)
+    }
+
+    async fn resolve_thread(&self, pr_number: u64, thread_id: &str) -> Result<()> {
+        debug!(mr_iid = pr_number, thread_id, "resolving MR discussion");
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}/discussions/{}",
+            self.encoded_project(),
+            pr_number,
+            thread_id
+        ));
+        self.client
+            .put(&url)
+            .headers(self.auth_headers())
+            .query(&[("resolved", "true")])
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?;
+
+        debug!(mr_iid = pr_number, thread_id, "resolved MR discussion");
+        Ok(())
+    }
+
+    async fn list_checks(&self, git_ref: &str) -> Result<Vec<CheckInfo>> {
+        debug!(git_ref, "listing checks");
+        let url = self.api_url(&format!("/projects/{}/pipelines", self.encoded_project()));
+
+        // Full commit SHAs are matched by commit, anything else by branch or tag
+        let is_sha = git_ref.len() == 40 && git_ref.chars().all(|c| c.is_ascii_hexdigit());
+        let filter = if is_sha { "sha" } else { "ref" };
+
+        Ok(match self.latest_pipeline(&url, &[(filter, git_ref)]).await? {
+            Some(pipeline) => self.pipeline_checks(pipeline).await,
+            None => Vec::new(),
+        })
+    }
+
+    async fn create_commit_status(
+        &self,
+        sha: &str,
+        context: &str,
+        state: CheckState,
+        description: &str,
+    ) -> Result<()> {
+        debug!(sha, context, %state, "creating commit status");
+        let url = self.api_url(&format!(
+            "/projects/{}/statuses/{}",
+            self.encoded_project(),
+            sha
+        ));
+        let state = match state {
+            CheckState::Pending => "pending",
+            CheckState::Success | CheckState::Skipped => "success",
+            CheckState::Failure => "failed",
+        };
+
+        self.client
+            .post(&url)
+            .headers(self.auth_headers())
+            .json(&serde_json::json!({
+                "state": state,
+                "name": context,
+                "description": description,
+            }))
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?;
+        Ok(())
+    }
+
+    async fn check_merge_readiness(&self, details: &PullRequestDetails) -> Result<MergeReadiness> {
+        let pr_number = details.number;
+        debug!(mr_iid = pr_number, "checking merge readiness");
 
         // Check approvals
         let approvals_url = self.api_url(&format!(
@@ -404,13 +1278,16 @@ impl PlatformService for GitLabService {
         let is_approved = match self
             .client
             .get(&approvals_url)
-            .header("PRIVATE-TOKEN", &self.token)
+            .headers(self.auth_headers())
             .send()
             .await
         {
             Ok(response) => {
                 if response.status().is_success() {
-                    let approvals: MrApprovals = response.json().await.unwrap_or(MrApprovals { approved: false });
+                    let approvals: MrApprovals = response.json().await.unwrap_or(MrApprovals {
+                        approved: false,
+                        approved_by: vec![],
+                    });
                     approvals.approved
                 } else {
                     // If approvals endpoint fails, assume not approved
@@ -420,33 +1297,44 @@ impl PlatformService for GitLabService {
             Err(_) => false,
         };
 
-        // Check pipelines (most recent)
-        let pipelines_url = self.api_url(&format!(
-            "/projects/{}/merge_requests/{}/pipelines",
+        // Approval rules carry the required count; they need GitLab Premium,
+        // so a failed request just leaves the counts unknown
+        let approval_state_url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}/approval_state",
             self.encoded_project(),
             pr_number
         ));
-
-        let ci_passed = match self
+        let approval_counts = match self
             .client
-            .get(&pipelines_url)
-            .header("PRIVATE-TOKEN", &self.token)
+            .get(&approval_state_url)
+            .headers(self.auth_headers())
             .send()
             .await
         {
-            Ok(response) => {
-                if response.status().is_success() {
-                    let pipelines: Vec<Pipeline> = response.json().await.unwrap_or_default();
-                    // No pipeline = not blocking, otherwise check most recent
-                    pipelines
-                        .first()
-                        .is_none_or(|p| p.status == "success")
-                } else {
-                    // If pipelines endpoint fails, assume passing (not blocking)
-                    true
-                }
-            }
-            Err(_) => true,
+            Ok(response) if response.status().is_success() => response
+                .json::<MrApprovalState>()
+                .await
+                .ok()
+                .map(|state| approval_counts(&state))
+                .filter(|&(required, _)| required > 0),
+            _ => None,
+        };
+
+        // A pending change request blocks even when approval rules are met
+        let reviews = self.list_reviews(pr_number).await.unwrap_or_default();
+        let changes_requested = changes_requested_by(&reviews);
+        let is_approved = is_approved && changes_requested.is_empty();
+
+        // Check the MR's most recent pipeline; if the endpoint fails, assume
+        // no checks (not blocking)
+        let pipelines_url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}/pipelines",
+            self.encoded_project(),
+            pr_number
+        ));
+        let checks = match self.latest_pipeline(&pipelines_url, &[]).await {
+            Ok(Some(pipeline)) => self.pipeline_checks(pipeline).await,
+            _ => Vec::new(),
         };
 
         // Build blocking reasons (definitive blockers)
@@ -454,11 +1342,18 @@ impl PlatformService for GitLabService {
         if details.is_draft {
             blocking_reasons.push("MR is a draft".to_string());
         }
-        if !is_approved {
-            blocking_reasons.push("Not approved".to_string());
-        }
-        if !ci_passed {
-            blocking_reasons.push("CI not passing".to_string());
+        if !changes_requested.is_empty() {
+            blocking_reasons.push(format!(
+                "Changes requested by {}",
+                changes_requested.join(", ")
+            ));
+        } else if !is_approved {
+            blocking_reasons.push(match approval_counts {
+                Some((required, received)) => {
+                    format!("Not approved ({received} of {required} required approvals)")
+                }
+                None => "Not approved".to_string(),
+            });
         }
         if details.mergeable == Some(false) {
             blocking_reasons.push("Has merge conflicts".to_string());
@@ -467,7 +1362,9 @@ impl PlatformService for GitLabService {
         // GitLab always computes merge_status synchronously, so uncertainties is always empty
         let readiness = MergeReadiness {
             is_approved,
-            ci_passed,
+            approvals_required: approval_counts.map(|(required, _)| required),
+            approvals_received: approval_counts.map(|(_, received)| received),
+            checks,
             is_mergeable: details.mergeable,
             is_draft: details.is_draft,
             blocking_reasons,
@@ -482,11 +1379,60 @@ impl PlatformService for GitLabService {
         Ok(readiness)
     }
 
-    async fn merge_pr(&self, pr_number: u64, method: MergeMethod) -> Result<MergeResult> {
-        debug!(mr_iid = pr_number, %method, "merging MR");
+    async fn update_pr_branch(&self, pr_number: u64) -> Result<()> {
+        debug!(mr_iid = pr_number, "rebasing MR");
+
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}/rebase",
+            self.encoded_project(),
+            pr_number
+        ));
+
+        self.client
+            .put(&url)
+            .headers(self.auth_headers())
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(status_error)?;
+
+        Ok(())
+    }
+
+    async fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        debug!(mr_iid = pr_number, %method, "setting MR to merge when pipeline succeeds");
+        let url = self.api_url(&format!(
+            "/projects/{}/merge_requests/{}/merge",
+            self.encoded_project(),
+            pr_number
+        ));
+
+        self.client
+            .put(&url)
+            .headers(self.auth_headers())
+            .json(&serde_json::json!({
+                "merge_when_pipeline_succeeds": true,
+                "squash": method == MergeMethod::Squash,
+            }))
+            .send()
+            .await?
+            .check_rate_limit("GitLab")?
+            .error_for_status()
+            .map_err(|e| Error::GitLabApi(format!("Enabling auto-merge failed: {e}")))?;
 
-        // Get MR details for commit message (squash needs title/description)
-        let details = self.get_pr_details(pr_number).await?;
+        debug!(mr_iid = pr_number, "set MR to merge when pipeline succeeds");
+        Ok(())
+    }
+
+    async fn merge_pr(
+        &self,
+        pr_number: u64,
+        title: &str,
+        body: Option<&str>,
+        method: MergeMethod,
+    ) -> Result<MergeResult> {
+        debug!(mr_iid = pr_number, %method, "merging MR");
 
         let url = self.api_url(&format!(
             "/projects/{}/merge_requests/{}/merge",
@@ -498,10 +1444,8 @@ impl PlatformService for GitLabService {
             MergeMethod::Squash => serde_json::json!({
                 "squash": true,
                 "squash_commit_message": format!(
-                    "{} (!{})\n\n{}",
-                    details.title,
-                    pr_number,
-                    details.body.unwrap_or_default()
+                    "{title} (!{pr_number})\n\n{}",
+                    body.unwrap_or_default()
                 )
             }),
             MergeMethod::Merge => serde_json::json!({}),
@@ -513,10 +1457,11 @@ impl PlatformService for GitLabService {
         let response: MergeResponse = self
             .client
             .put(&url)
-            .header("PRIVATE-TOKEN", &self.token)
+            .headers(self.auth_headers())
             .json(&body)
             .send()
             .await?
+            .check_rate_limit("GitLab")?
             .error_for_status()
             .map_err(|e| Error::GitLabApi(format!("Merge failed: {e}")))?
             .json()
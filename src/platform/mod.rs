@@ -1,23 +1,57 @@
-//! Platform services for GitHub and GitLab
+//! Platform services for GitHub, GitLab and Gerrit
 //!
 //! Provides a unified interface for PR/MR operations across platforms.
 
+mod ci_cache;
 mod detection;
 mod factory;
+mod gerrit;
+mod ghes;
 mod github;
 mod gitlab;
+mod retry;
+mod registry;
+mod reviews;
+mod simulated;
+mod throttle;
 
-pub use detection::{detect_platform, parse_repo_info};
-pub use factory::create_platform_service;
+pub use ci_cache::{CI_CACHE_TTL_ENV, CiStatusCache};
+pub use detection::{
+    detect_platform, detect_platform_with_hosts, extract_hostname, parse_repo_info,
+    parse_repo_info_with_hosts,
+};
+pub use factory::{create_platform_service, create_platform_service_with_auth};
+pub use gerrit::GerritService;
+pub use ghes::{GhesFeature, GhesVersion, require_feature, supports_feature};
 pub use github::GitHubService;
 pub use gitlab::GitLabService;
+pub use retry::{RateLimitCheck, RetryingPlatformService, is_transient, is_unsent};
+pub use registry::{
+    PlatformBackend, parse_registered_remote, register_platform, registered_platform,
+};
+pub use reviews::{
+    approval_count, changes_requested_by, is_approved_by_reviews, latest_reviews,
+};
+pub use simulated::{
+    SIMULATED_CHECK, SIMULATED_REVIEWER, SimulatedPlatformService, simulated_state_path,
+};
+pub use throttle::ThrottledPlatformService;
 
-use crate::error::Result;
+use crate::config::DEFAULT_MAX_CONCURRENCY;
+use crate::error::{Error, Result};
 use crate::types::{
-    MergeMethod, MergeReadiness, MergeResult, PlatformConfig, PrComment, PullRequest,
-    PullRequestDetails,
+    CheckInfo, CheckState, MergeMethod, MergeReadiness, MergeResult, PlatformConfig, PrComment, PrReview,
+    PullRequest, PullRequestDetails, ReviewThread,
 };
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+
+/// Context of the commit status ryu posts to show a PR's place in its stack
+///
+/// Platforms leave it out of the checks they report, so it never blocks
+/// ryu's own merges.
+pub const STACK_STATUS_CONTEXT: &str = "ryu/stack";
 
 /// Platform service trait for PR/MR operations
 ///
@@ -28,6 +62,45 @@ pub trait PlatformService: Send + Sync {
     /// Find an existing open PR for a head branch
     async fn find_existing_pr(&self, head_branch: &str) -> Result<Option<PullRequest>>;
 
+    /// Find the existing open PRs for several head branches at once
+    ///
+    /// Returns the PRs found, keyed by head branch. The default looks the
+    /// branches up one request each (see [`find_prs_individually`]); GitHub
+    /// overrides it with a single GraphQL query.
+    async fn find_existing_prs(
+        &self,
+        head_branches: &[String],
+    ) -> Result<HashMap<String, PullRequest>> {
+        find_prs_individually(self, head_branches).await
+    }
+
+    /// Whether [`find_existing_prs`](Self::find_existing_prs) and
+    /// [`find_prs_by_body`](Self::find_prs_by_body) are single lookups
+    ///
+    /// Wrappers use this to throttle and retry each lookup as a whole, rather
+    /// than per branch or body text.
+    fn batches_pr_lookups(&self) -> bool {
+        false
+    }
+
+    /// Find the most recent closed, unmerged PR for a head branch
+    async fn find_closed_pr(&self, head_branch: &str) -> Result<Option<PullRequest>>;
+
+    /// Find an open PR whose body contains `text`, whatever its head branch
+    ///
+    /// Used to find the PR of a renamed bookmark by the change-id marker ryu
+    /// writes into PR bodies.
+    async fn find_pr_by_body(&self, text: &str) -> Result<Option<PullRequest>>;
+
+    /// Find the open PRs whose bodies contain each of several texts at once
+    ///
+    /// Returns the PRs found, keyed by text. The default looks the texts up
+    /// one request each (see [`find_prs_by_body_individually`]); GitHub
+    /// overrides it to list the open PRs once.
+    async fn find_prs_by_body(&self, texts: &[String]) -> Result<HashMap<String, PullRequest>> {
+        find_prs_by_body_individually(self, texts).await
+    }
+
     /// Create a new PR with default options (non-draft, no body).
     ///
     /// This is a convenience method that delegates to [`create_pr_with_options`]
@@ -58,9 +131,46 @@ pub trait PlatformService: Send + Sync {
     /// Update the base branch of an existing PR
     async fn update_pr_base(&self, pr_number: u64, new_base: &str) -> Result<PullRequest>;
 
+    /// Change the title of a PR
+    async fn update_pr_title(&self, pr_number: u64, title: &str) -> Result<PullRequest>;
+
+    /// Replace the body (description) of a PR
+    async fn update_pr_body(&self, pr_number: u64, body: &str) -> Result<()>;
+
+    /// Add labels to a PR, keeping the labels it already has
+    ///
+    /// GitHub: issue labels. GitLab: MR labels. Gerrit: hashtags.
+    async fn add_pr_labels(&self, pr_number: u64, labels: &[String]) -> Result<()>;
+
+    /// Assign users to a PR by username
+    ///
+    /// GitHub adds to the PR's assignees; GitLab replaces the MR's assignees.
+    async fn assign_pr(&self, pr_number: u64, assignees: &[String]) -> Result<()>;
+
+    /// Request reviews on a PR from users by username
+    ///
+    /// Users who already reviewed are asked to review again. GitHub:
+    /// requested reviewers. GitLab: adds new reviewers and re-requests review
+    /// from existing ones. Gerrit: adds the users as reviewers.
+    async fn request_review(&self, pr_number: u64, reviewers: &[String]) -> Result<()>;
+
+    /// Put a PR in the open milestone with this title
+    ///
+    /// GitHub: repository milestones. GitLab: active project milestones.
+    async fn set_pr_milestone(&self, pr_number: u64, milestone: &str) -> Result<()>;
+
     /// Publish a draft PR (convert to ready for review)
     async fn publish_pr(&self, pr_number: u64) -> Result<PullRequest>;
 
+    /// Convert a PR back to a draft
+    async fn unpublish_pr(&self, pr_number: u64) -> Result<PullRequest>;
+
+    /// Close a PR without merging it
+    async fn close_pr(&self, pr_number: u64) -> Result<()>;
+
+    /// Reopen a closed (unmerged) PR
+    async fn reopen_pr(&self, pr_number: u64) -> Result<PullRequest>;
+
     /// List comments on a PR
     async fn list_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>>;
 
@@ -70,9 +180,26 @@ pub trait PlatformService: Send + Sync {
     /// Update an existing comment on a PR
     async fn update_pr_comment(&self, pr_number: u64, comment_id: u64, body: &str) -> Result<()>;
 
+    /// Delete a comment on a PR
+    async fn delete_pr_comment(&self, pr_number: u64, comment_id: u64) -> Result<()>;
+
+    /// List protected branch names (may contain `*` wildcards on GitLab)
+    async fn list_protected_branches(&self) -> Result<Vec<String>>;
+
+    /// Username of the authenticated user
+    async fn current_user(&self) -> Result<String>;
+
     /// Get the platform configuration
     fn config(&self) -> &PlatformConfig;
 
+    /// Maximum number of requests callers should have in flight at once
+    ///
+    /// Used to size concurrent lookups and updates. Overridden by
+    /// [`ThrottledPlatformService`] with the configured `http.max_concurrency`.
+    fn max_concurrency(&self) -> usize {
+        DEFAULT_MAX_CONCURRENCY
+    }
+
     // =========================================================================
     // Merge-related methods (for ryu merge command)
     // =========================================================================
@@ -83,15 +210,166 @@ pub trait PlatformService: Send + Sync {
     /// including the PR body (for commit message) and merge status.
     async fn get_pr_details(&self, pr_number: u64) -> Result<PullRequestDetails>;
 
+    /// List reviews on a PR, oldest first
+    ///
+    /// GitHub: submitted pull request reviews. GitLab: reviewer states and
+    /// approvals (approvals carry no timestamp).
+    async fn list_reviews(&self, pr_number: u64) -> Result<Vec<PrReview>>;
+
+    /// List the review comment threads on a PR, resolved or not
+    ///
+    /// GitHub: review threads. GitLab: resolvable discussions.
+    async fn list_review_threads(&self, pr_number: u64) -> Result<Vec<ReviewThread>>;
+
+    /// Mark a review thread (from [`list_review_threads`]) resolved
+    ///
+    /// GitLab discussions belong to an MR, so the PR number is needed too.
+    ///
+    /// [`list_review_threads`]: Self::list_review_threads
+    async fn resolve_thread(&self, pr_number: u64, thread_id: &str) -> Result<()>;
+
+    /// List the CI checks reported for a commit SHA or branch name
+    ///
+    /// GitHub: check runs and commit status contexts. GitLab: jobs of the
+    /// latest pipeline for the ref. Branch protection isn't consulted here, so
+    /// only jobs allowed to fail are marked as not required.
+    async fn list_checks(&self, git_ref: &str) -> Result<Vec<CheckInfo>>;
+
+    /// Set a commit status on `sha`
+    ///
+    /// GitHub: commit status API. GitLab: external commit status (a job in
+    /// the commit's pipeline).
+    async fn create_commit_status(
+        &self,
+        sha: &str,
+        context: &str,
+        state: CheckState,
+        description: &str,
+    ) -> Result<()>;
+
     /// Check if PR is ready to merge
     ///
     /// Checks approval status, CI status, and merge conflicts.
     /// Returns a `MergeReadiness` struct with all conditions and blocking reasons.
-    async fn check_merge_readiness(&self, pr_number: u64) -> Result<MergeReadiness>;
+    /// Takes details previously fetched via [`get_pr_details`] so they are not
+    /// requested again.
+    ///
+    /// [`get_pr_details`]: Self::get_pr_details
+    async fn check_merge_readiness(&self, details: &PullRequestDetails) -> Result<MergeReadiness>;
+
+    /// Bring a PR's head branch up to date with its base
+    ///
+    /// GitHub: the "Update branch" button (merges the base into the head).
+    /// GitLab: rebases the MR's source branch onto its target. Both complete
+    /// asynchronously on the platform.
+    async fn update_pr_branch(&self, pr_number: u64) -> Result<()>;
 
     /// Merge a PR with the specified method
     ///
-    /// For squash merges, the PR title is used as commit title and
-    /// the PR body is used as commit message.
-    async fn merge_pr(&self, pr_number: u64, method: MergeMethod) -> Result<MergeResult>;
+    /// For squash merges, `title` is used as commit title and `body` as
+    /// commit message. Callers pass these from already-fetched details.
+    async fn merge_pr(
+        &self,
+        pr_number: u64,
+        title: &str,
+        body: Option<&str>,
+        method: MergeMethod,
+    ) -> Result<MergeResult>;
+
+    /// Have the platform merge a PR once its requirements are met
+    ///
+    /// GitHub: auto-merge (`enablePullRequestAutoMerge`). GitLab: merge when
+    /// the pipeline succeeds.
+    async fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()>;
+}
+
+/// Look up the open PR for each head branch with `find_existing_pr`
+///
+/// Lookups are independent, so they run concurrently (up to the platform's
+/// `max_concurrency`).
+pub async fn find_prs_individually<P: PlatformService + ?Sized>(
+    platform: &P,
+    head_branches: &[String],
+) -> Result<HashMap<String, PullRequest>> {
+    let lookups: Vec<_> = stream::iter(head_branches.iter().cloned())
+        .map(|branch| async move {
+            let pr = platform.find_existing_pr(&branch).await?;
+            Ok::<_, Error>((branch, pr))
+        })
+        .buffered(platform.max_concurrency())
+        .collect()
+        .await;
+
+    let mut prs = HashMap::new();
+    for lookup in lookups {
+        if let (branch, Some(pr)) = lookup? {
+            prs.insert(branch, pr);
+        }
+    }
+    Ok(prs)
+}
+
+/// Look up the open PR whose body contains each text with `find_pr_by_body`
+///
+/// Lookups are independent, so they run concurrently (up to the platform's
+/// `max_concurrency`).
+pub async fn find_prs_by_body_individually<P: PlatformService + ?Sized>(
+    platform: &P,
+    texts: &[String],
+) -> Result<HashMap<String, PullRequest>> {
+    let lookups: Vec<_> = stream::iter(texts.iter().cloned())
+        .map(|text| async move {
+            let pr = platform.find_pr_by_body(&text).await?;
+            Ok::<_, Error>((text, pr))
+        })
+        .buffered(platform.max_concurrency())
+        .collect()
+        .await;
+
+    let mut prs = HashMap::new();
+    for lookup in lookups {
+        if let (text, Some(pr)) = lookup? {
+            prs.insert(text, pr);
+        }
+    }
+    Ok(prs)
+}
+
+/// Look up the details of each PR
+///
+/// Lookups run concurrently (up to the platform's `max_concurrency`) and
+/// fail on their own: each PR comes back with its details or the error.
+pub async fn get_prs_details<P: PlatformService + ?Sized>(
+    platform: &P,
+    pr_numbers: &[u64],
+) -> Vec<(u64, Result<PullRequestDetails>)> {
+    stream::iter(pr_numbers.iter().copied())
+        .map(|number| async move { (number, platform.get_pr_details(number).await) })
+        .buffered(platform.max_concurrency())
+        .collect()
+        .await
+}
+
+/// Close the open PR of each head branch, in order
+///
+/// Keeps going past failures, so one bad PR doesn't leave the rest open.
+/// Each branch comes back with the number of the PR it closed (None if it
+/// had no open PR) or the error.
+pub async fn close_prs<P: PlatformService + ?Sized>(
+    platform: &P,
+    head_branches: &[String],
+) -> Vec<(String, Result<Option<u64>>)> {
+    let mut results = Vec::with_capacity(head_branches.len());
+    for branch in head_branches {
+        let result = async {
+            let Some(pr) = platform.find_existing_pr(branch).await? else {
+                return Ok(None);
+            };
+            platform.close_pr(pr.number).await?;
+            Ok(Some(pr.number))
+        }
+        .await;
+        results.push((branch.clone(), result));
+    }
+    results
 }
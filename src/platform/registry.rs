@@ -0,0 +1,124 @@
+//! Registry of platform backends provided by other crates
+//!
+//! Companies with internal forges can implement [`PlatformService`] in their
+//! own crate and register a [`PlatformBackend`] before ryu's commands run.
+//! Registered backends are tried before the built-in platforms, both when
+//! detecting the platform of a remote and in [`create_platform_service`].
+//!
+//! [`create_platform_service`]: crate::platform::create_platform_service
+
+use crate::error::Result;
+use crate::platform::PlatformService;
+use crate::types::{Platform, PlatformConfig};
+use async_trait::async_trait;
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// A platform implemented outside jj-ryu
+#[async_trait]
+pub trait PlatformBackend: Send + Sync {
+    /// Name of the platform, shown to users and used as its
+    /// [`Platform::Custom`] identifier
+    fn name(&self) -> &'static str;
+
+    /// Parse a remote URL into owner, repo and host if it belongs to this
+    /// platform
+    ///
+    /// The returned config's `platform` is replaced with
+    /// `Platform::Custom(self.name())`.
+    fn parse_remote(&self, url: &str) -> Option<PlatformConfig>;
+
+    /// Create the service for a repository, including authentication
+    async fn create_service(&self, config: &PlatformConfig) -> Result<Box<dyn PlatformService>>;
+}
+
+static BACKENDS: RwLock<Vec<Arc<dyn PlatformBackend>>> = RwLock::new(Vec::new());
+
+/// Register a platform backend
+///
+/// Backends registered later take precedence over earlier ones with the
+/// same name.
+pub fn register_platform(backend: impl PlatformBackend + 'static) {
+    let mut backends = BACKENDS.write().unwrap_or_else(PoisonError::into_inner);
+    backends.retain(|b| b.name() != backend.name());
+    backends.push(Arc::new(backend));
+}
+
+/// Registered backend with this name
+pub fn registered_platform(name: &str) -> Option<Arc<dyn PlatformBackend>> {
+    BACKENDS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .rev()
+        .find(|b| b.name() == name)
+        .cloned()
+}
+
+/// Config of the first registered backend that recognizes `url`
+///
+/// Backends registered later are tried first.
+pub fn parse_registered_remote(url: &str) -> Option<PlatformConfig> {
+    let backends = BACKENDS.read().unwrap_or_else(PoisonError::into_inner);
+    backends.iter().rev().find_map(|backend| {
+        let mut config = backend.parse_remote(url)?;
+        config.platform = Platform::Custom(backend.name());
+        Some(config)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::platform::{create_platform_service, detect_platform, parse_repo_info};
+
+    struct ForgeBackend;
+
+    #[async_trait]
+    impl PlatformBackend for ForgeBackend {
+        fn name(&self) -> &'static str {
+            "Test Forge"
+        }
+
+        fn parse_remote(&self, url: &str) -> Option<PlatformConfig> {
+            let path = url.strip_prefix("forge://forge.test.invalid/")?;
+            let (owner, repo) = path.split_once('/')?;
+            Some(PlatformConfig {
+                platform: Platform::GitHub,
+                owner: owner.to_string(),
+                repo: repo.to_string(),
+                host: Some("forge.test.invalid".to_string()),
+            })
+        }
+
+        async fn create_service(
+            &self,
+            _config: &PlatformConfig,
+        ) -> Result<Box<dyn PlatformService>> {
+            Err(Error::Platform("no test forge to connect to".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registered_backend_is_detected() {
+        register_platform(ForgeBackend);
+
+        let url = "forge://forge.test.invalid/team/widgets";
+        assert_eq!(detect_platform(url), Some(Platform::Custom("Test Forge")));
+
+        let config = parse_repo_info(url).unwrap();
+        assert_eq!(config.platform, Platform::Custom("Test Forge"));
+        assert_eq!(config.owner, "team");
+        assert_eq!(config.repo, "widgets");
+
+        // The factory hands the config to the backend
+        let err = create_platform_service(&config).await.err().unwrap();
+        assert!(err.to_string().contains("no test forge"));
+
+        // Other remotes still go to the built-in platforms
+        assert_eq!(
+            detect_platform("https://github.com/owner/repo"),
+            Some(Platform::GitHub)
+        );
+    }
+}
@@ -0,0 +1,529 @@
+//! Retries for transient platform failures.
+//!
+//! Wraps any [`PlatformService`] to retry requests that failed with a timeout,
+//! a connection error, a 5xx response, or a rate limit, backing off
+//! exponentially between attempts (or waiting as long as the platform asked,
+//! for rate limits). Other errors (bad requests, auth failures, 404s) are
+//! returned immediately.
+//!
+//! Only reads and idempotent updates are retried on every transient failure.
+//! Requests that would act twice if repeated (creating PRs and comments,
+//! adding labels and reviewers, merging, posting statuses) are retried only
+//! when the platform can't have acted on them: the connection failed or the
+//! request was rate limited. After a timeout or a 5xx they may have gone
+//! through, so the error is returned for the caller to check.
+
+use crate::config::HttpConfig;
+use crate::error::{Error, Result};
+use crate::platform::{PlatformService, find_prs_by_body_individually, find_prs_individually};
+use crate::types::{
+    CheckInfo, CheckState, MergeMethod, MergeReadiness, MergeResult, PlatformConfig, PrComment,
+    PrReview, PullRequest, PullRequestDetails, ReviewThread,
+};
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use reqwest::{Response, StatusCode};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+/// Longest delay between two attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Longest rate-limit wait worth sitting through; longer ones fail right away.
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(120);
+
+/// Platform service wrapper retrying transient failures per `http.*` config.
+pub struct RetryingPlatformService<T> {
+    inner: T,
+    max_retries: u32,
+    backoff: Duration,
+}
+
+impl<T: PlatformService> RetryingPlatformService<T> {
+    /// Wrap a platform service with the configured retries and backoff.
+    pub const fn new(inner: T, config: &HttpConfig) -> Self {
+        Self {
+            inner,
+            max_retries: config.max_retries,
+            backoff: Duration::from_millis(config.retry_backoff),
+        }
+    }
+
+    /// Run `call`, retrying it while it fails transiently.
+    async fn retry<R, F, Fut>(&self, operation: &str, call: F) -> Result<R>
+    where
+        F: FnMut() -> Fut + Send,
+        Fut: Future<Output = Result<R>> + Send,
+    {
+        self.retry_when(operation, is_transient, call).await
+    }
+
+    /// Run a call that isn't safe to repeat, retrying it only while it
+    /// fails without reaching the platform.
+    async fn retry_unsent<R, F, Fut>(&self, operation: &str, call: F) -> Result<R>
+    where
+        F: FnMut() -> Fut + Send,
+        Fut: Future<Output = Result<R>> + Send,
+    {
+        self.retry_when(operation, is_unsent, call).await
+    }
+
+    /// Run `call`, retrying it while it fails with errors `retryable` accepts.
+    async fn retry_when<R, F, Fut>(
+        &self,
+        operation: &str,
+        retryable: fn(&Error) -> bool,
+        mut call: F,
+    ) -> Result<R>
+    where
+        F: FnMut() -> Fut + Send,
+        Fut: Future<Output = Result<R>> + Send,
+    {
+        let mut attempt = 0;
+        loop {
+            match call().await {
+                Err(e) if attempt < self.max_retries && retryable(&e) => {
+                    let Some(delay) = retry_delay(&e, self.backoff, attempt) else {
+                        return Err(e);
+                    };
+                    attempt += 1;
+                    debug!(operation, attempt, ?delay, error = %e, "retrying request");
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) if attempt > 0 && retryable(&e) => {
+                    return Err(Error::RetriesExhausted {
+                        operation: operation.to_string(),
+                        attempts: attempt + 1,
+                        source: Box::new(e),
+                    });
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Whether a failed request is worth retrying
+///
+/// True for timeouts, connection errors, 5xx responses, and rate limits.
+pub fn is_transient(error: &Error) -> bool {
+    match error {
+        Error::Http(e) => {
+            e.is_timeout()
+                || e.is_connect()
+                || e.status().is_some_and(|status| status.is_server_error())
+                || (e.status().is_none() && (e.is_request() || e.is_body()))
+        }
+        Error::Octocrab(octocrab::Error::GitHub { source, .. }) => {
+            source.status_code.is_server_error()
+                || source.status_code == StatusCode::TOO_MANY_REQUESTS
+                || (source.status_code == StatusCode::FORBIDDEN
+                    && source.message.to_lowercase().contains("rate limit"))
+        }
+        Error::RateLimited { .. }
+        | Error::Octocrab(octocrab::Error::Hyper { .. } | octocrab::Error::Service { .. }) => true,
+        _ => false,
+    }
+}
+
+/// Whether a failed request certainly wasn't acted on by the platform
+///
+/// True for connection errors and rate limits, which are safe to retry even
+/// for requests that aren't idempotent.
+pub fn is_unsent(error: &Error) -> bool {
+    match error {
+        Error::Http(e) => e.is_connect(),
+        Error::Octocrab(octocrab::Error::GitHub { source, .. }) => {
+            source.status_code == StatusCode::TOO_MANY_REQUESTS
+                || (source.status_code == StatusCode::FORBIDDEN
+                    && source.message.to_lowercase().contains("rate limit"))
+        }
+        Error::RateLimited { .. } => true,
+        _ => false,
+    }
+}
+
+/// Delay before retry number `attempt + 1`: `base` doubled per attempt, capped
+fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF)
+}
+
+/// Delay before retrying `error`, or None if the wait is too long to sit out
+///
+/// Rate limits wait as long as the platform asked; everything else backs off.
+fn retry_delay(error: &Error, base: Duration, attempt: u32) -> Option<Duration> {
+    match error {
+        Error::RateLimited {
+            retry_after: Some(wait),
+            ..
+        } => (*wait <= MAX_RATE_LIMIT_WAIT).then_some(*wait),
+        _ => Some(backoff_delay(base, attempt)),
+    }
+}
+
+/// Rate-limit checks for raw HTTP responses
+pub trait RateLimitCheck: Sized {
+    /// Fail with [`Error::RateLimited`] if the response hit `platform`'s rate limit
+    fn check_rate_limit(self, platform: &str) -> Result<Self>;
+}
+
+impl RateLimitCheck for Response {
+    fn check_rate_limit(self, platform: &str) -> Result<Self> {
+        rate_limit_error(self.status(), self.headers(), platform).map_or(Ok(self), Err)
+    }
+}
+
+/// The rate-limit error for a response, if it hit the rate limit
+///
+/// That's a 429, or a 403 with no requests remaining (GitHub answers
+/// primary rate limits that way). The wait comes from `Retry-After`, or
+/// else from the time the limit resets.
+fn rate_limit_error(status: StatusCode, headers: &HeaderMap, platform: &str) -> Option<Error> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+    };
+
+    let exhausted =
+        header("x-ratelimit-remaining").or_else(|| header("ratelimit-remaining")) == Some(0);
+    if status != StatusCode::TOO_MANY_REQUESTS && !(status == StatusCode::FORBIDDEN && exhausted) {
+        return None;
+    }
+
+    let retry_after = header("retry-after").map(Duration::from_secs).or_else(|| {
+        let reset = header("x-ratelimit-reset").or_else(|| header("ratelimit-reset"))?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        Some(Duration::from_secs(reset.saturating_sub(now)))
+    });
+
+    let message = retry_after.map_or_else(
+        || format!("{platform} API rate limit exceeded"),
+        |wait| {
+            format!(
+                "{platform} API rate limit exceeded; it resets in {}s",
+                wait.as_secs()
+            )
+        },
+    );
+    Some(Error::RateLimited {
+        message,
+        retry_after,
+    })
+}
+
+#[async_trait]
+impl<T: PlatformService> PlatformService for RetryingPlatformService<T> {
+    async fn find_existing_pr(&self, head_branch: &str) -> Result<Option<PullRequest>> {
+        self.retry("find_existing_pr", || {
+            self.inner.find_existing_pr(head_branch)
+        })
+        .await
+    }
+
+    async fn find_existing_prs(
+        &self,
+        head_branches: &[String],
+    ) -> Result<HashMap<String, PullRequest>> {
+        if !self.inner.batches_pr_lookups() {
+            return find_prs_individually(self, head_branches).await;
+        }
+        self.retry("find_existing_prs", || {
+            self.inner.find_existing_prs(head_branches)
+        })
+        .await
+    }
+
+    fn batches_pr_lookups(&self) -> bool {
+        self.inner.batches_pr_lookups()
+    }
+
+    async fn find_closed_pr(&self, head_branch: &str) -> Result<Option<PullRequest>> {
+        self.retry("find_closed_pr", || self.inner.find_closed_pr(head_branch))
+            .await
+    }
+
+    async fn find_pr_by_body(&self, text: &str) -> Result<Option<PullRequest>> {
+        self.retry("find_pr_by_body", || self.inner.find_pr_by_body(text))
+            .await
+    }
+
+    async fn find_prs_by_body(&self, texts: &[String]) -> Result<HashMap<String, PullRequest>> {
+        if !self.inner.batches_pr_lookups() {
+            return find_prs_by_body_individually(self, texts).await;
+        }
+        self.retry("find_prs_by_body", || self.inner.find_prs_by_body(texts))
+            .await
+    }
+
+    async fn create_pr_with_options(
+        &self,
+        head: &str,
+        base: &str,
+        title: &str,
+        body: Option<&str>,
+        draft: bool,
+    ) -> Result<PullRequest> {
+        self.retry_unsent("create_pr", || {
+            self.inner
+                .create_pr_with_options(head, base, title, body, draft)
+        })
+        .await
+    }
+
+    async fn update_pr_base(&self, pr_number: u64, new_base: &str) -> Result<PullRequest> {
+        self.retry("update_pr_base", || {
+            self.inner.update_pr_base(pr_number, new_base)
+        })
+        .await
+    }
+
+    async fn update_pr_title(&self, pr_number: u64, title: &str) -> Result<PullRequest> {
+        self.retry("update_pr_title", || self.inner.update_pr_title(pr_number, title))
+            .await
+    }
+
+    async fn update_pr_body(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.retry("update_pr_body", || self.inner.update_pr_body(pr_number, body))
+            .await
+    }
+
+    async fn add_pr_labels(&self, pr_number: u64, labels: &[String]) -> Result<()> {
+        self.retry_unsent("add_pr_labels", || self.inner.add_pr_labels(pr_number, labels))
+            .await
+    }
+
+    async fn assign_pr(&self, pr_number: u64, assignees: &[String]) -> Result<()> {
+        self.retry("assign_pr", || self.inner.assign_pr(pr_number, assignees))
+            .await
+    }
+
+    async fn request_review(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        self.retry_unsent("request_review", || {
+            self.inner.request_review(pr_number, reviewers)
+        })
+        .await
+    }
+
+    async fn set_pr_milestone(&self, pr_number: u64, milestone: &str) -> Result<()> {
+        self.retry("set_pr_milestone", || {
+            self.inner.set_pr_milestone(pr_number, milestone)
+        })
+        .await
+    }
+
+    async fn publish_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        self.retry("publish_pr", || self.inner.publish_pr(pr_number))
+            .await
+    }
+
+    async fn unpublish_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        self.retry("unpublish_pr", || self.inner.unpublish_pr(pr_number))
+            .await
+    }
+
+    async fn close_pr(&self, pr_number: u64) -> Result<()> {
+        self.retry("close_pr", || self.inner.close_pr(pr_number))
+            .await
+    }
+
+    async fn reopen_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        self.retry("reopen_pr", || self.inner.reopen_pr(pr_number))
+            .await
+    }
+
+    async fn list_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>> {
+        self.retry("list_pr_comments", || {
+            self.inner.list_pr_comments(pr_number)
+        })
+        .await
+    }
+
+    async fn create_pr_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.retry_unsent("create_pr_comment", || {
+            self.inner.create_pr_comment(pr_number, body)
+        })
+        .await
+    }
+
+    async fn update_pr_comment(&self, pr_number: u64, comment_id: u64, body: &str) -> Result<()> {
+        self.retry("update_pr_comment", || {
+            self.inner.update_pr_comment(pr_number, comment_id, body)
+        })
+        .await
+    }
+
+    async fn delete_pr_comment(&self, pr_number: u64, comment_id: u64) -> Result<()> {
+        self.retry("delete_pr_comment", || {
+            self.inner.delete_pr_comment(pr_number, comment_id)
+        })
+        .await
+    }
+
+    async fn current_user(&self) -> Result<String> {
+        self.retry("current_user", || self.inner.current_user())
+            .await
+    }
+
+    async fn list_protected_branches(&self) -> Result<Vec<String>> {
+        self.retry("list_protected_branches", || {
+            self.inner.list_protected_branches()
+        })
+        .await
+    }
+
+    fn config(&self) -> &PlatformConfig {
+        self.inner.config()
+    }
+
+    fn max_concurrency(&self) -> usize {
+        self.inner.max_concurrency()
+    }
+
+    async fn get_pr_details(&self, pr_number: u64) -> Result<PullRequestDetails> {
+        self.retry("get_pr_details", || self.inner.get_pr_details(pr_number))
+            .await
+    }
+
+    async fn list_reviews(&self, pr_number: u64) -> Result<Vec<PrReview>> {
+        self.retry("list_reviews", || self.inner.list_reviews(pr_number))
+            .await
+    }
+
+    async fn list_review_threads(&self, pr_number: u64) -> Result<Vec<ReviewThread>> {
+        self.retry("list_review_threads", || {
+            self.inner.list_review_threads(pr_number)
+        })
+        .await
+    }
+
+    async fn resolve_thread(&self, pr_number: u64, thread_id: &str) -> Result<()> {
+        self.retry("resolve_thread", || {
+            self.inner.resolve_thread(pr_number, thread_id)
+        })
+        .await
+    }
+
+    async fn list_checks(&self, git_ref: &str) -> Result<Vec<CheckInfo>> {
+        self.retry("list_checks", || self.inner.list_checks(git_ref))
+            .await
+    }
+
+    async fn create_commit_status(
+        &self,
+        sha: &str,
+        context: &str,
+        state: CheckState,
+        description: &str,
+    ) -> Result<()> {
+        self.retry_unsent("create_commit_status", || {
+            self.inner
+                .create_commit_status(sha, context, state, description)
+        })
+        .await
+    }
+
+    async fn check_merge_readiness(&self, details: &PullRequestDetails) -> Result<MergeReadiness> {
+        self.retry("check_merge_readiness", || {
+            self.inner.check_merge_readiness(details)
+        })
+        .await
+    }
+
+    async fn update_pr_branch(&self, pr_number: u64) -> Result<()> {
+        self.retry("update_pr_branch", || self.inner.update_pr_branch(pr_number))
+            .await
+    }
+
+    async fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        self.retry_unsent("enable_auto_merge", || {
+            self.inner.enable_auto_merge(pr_number, method)
+        })
+        .await
+    }
+
+    async fn merge_pr(
+        &self,
+        pr_number: u64,
+        title: &str,
+        body: Option<&str>,
+        method: MergeMethod,
+    ) -> Result<MergeResult> {
+        self.retry_unsent("merge_pr", || {
+            self.inner.merge_pr(pr_number, title, body, method)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let base = Duration::from_millis(500);
+        assert_eq!(backoff_delay(base, 0), Duration::from_millis(500));
+        assert_eq!(backoff_delay(base, 2), Duration::from_secs(2));
+        assert_eq!(backoff_delay(base, 40), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_non_http_errors_are_not_transient() {
+        assert!(!is_transient(&Error::GitLabApi(
+            "404 Not Found".to_string()
+        )));
+        assert!(!is_transient(&Error::Auth("bad token".to_string())));
+    }
+
+    #[test]
+    fn test_only_rate_limits_are_unsent() {
+        assert!(is_unsent(&Error::RateLimited {
+            message: "rate limited".to_string(),
+            retry_after: None,
+        }));
+        assert!(!is_unsent(&Error::GitLabApi("502 Bad Gateway".to_string())));
+    }
+
+    #[test]
+    fn test_rate_limit_error() {
+        let mut headers = HeaderMap::new();
+        assert!(rate_limit_error(StatusCode::FORBIDDEN, &headers, "GitHub").is_none());
+
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        headers.insert("retry-after", "7".parse().unwrap());
+        let err = rate_limit_error(StatusCode::FORBIDDEN, &headers, "GitHub").unwrap();
+        assert!(is_transient(&err));
+        assert!(matches!(
+            err,
+            Error::RateLimited { retry_after: Some(wait), .. } if wait == Duration::from_secs(7)
+        ));
+
+        let err = rate_limit_error(StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new(), "GitLab");
+        assert!(matches!(
+            err,
+            Some(Error::RateLimited {
+                retry_after: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_long_rate_limit_waits_are_not_retried() {
+        let base = Duration::from_millis(500);
+        let limited = |secs| Error::RateLimited {
+            message: "rate limited".to_string(),
+            retry_after: Some(Duration::from_secs(secs)),
+        };
+        assert_eq!(
+            retry_delay(&limited(5), base, 3),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(retry_delay(&limited(3600), base, 0), None);
+    }
+}
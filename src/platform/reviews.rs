@@ -0,0 +1,122 @@
+//! Review evaluation shared by the platform implementations
+//!
+//! Like GitHub, only each reviewer's most recent decisive review counts: a
+//! comment doesn't cancel an earlier approval, and a dismissal cancels both
+//! approvals and change requests.
+
+use crate::types::{PrReview, ReviewState};
+use std::collections::HashMap;
+
+/// Each reviewer's most recent approval or change request
+///
+/// Reviews without a timestamp are treated as older than dated ones; among
+/// equals, later entries in `reviews` win.
+pub fn latest_reviews(reviews: &[PrReview]) -> Vec<&PrReview> {
+    let mut latest: HashMap<&str, &PrReview> = HashMap::new();
+    for review in reviews {
+        if matches!(review.state, ReviewState::Commented | ReviewState::Pending) {
+            continue;
+        }
+        let newer = latest
+            .get(review.reviewer.as_str())
+            .is_none_or(|current| review.submitted_at >= current.submitted_at);
+        if newer {
+            latest.insert(&review.reviewer, review);
+        }
+    }
+
+    let mut latest: Vec<&PrReview> = latest
+        .into_values()
+        .filter(|r| r.state != ReviewState::Dismissed)
+        .collect();
+    latest.sort_by(|a, b| a.reviewer.cmp(&b.reviewer));
+    latest
+}
+
+/// Reviewers whose latest review requests changes
+pub fn changes_requested_by(reviews: &[PrReview]) -> Vec<&str> {
+    latest_reviews(reviews)
+        .into_iter()
+        .filter(|r| r.state == ReviewState::ChangesRequested)
+        .map(|r| r.reviewer.as_str())
+        .collect()
+}
+
+/// Number of reviewers whose latest verdict is an approval
+pub fn approval_count(reviews: &[PrReview]) -> usize {
+    latest_reviews(reviews)
+        .iter()
+        .filter(|r| r.state == ReviewState::Approved)
+        .count()
+}
+
+/// Whether at least one reviewer approved and nobody requests changes
+pub fn is_approved_by_reviews(reviews: &[PrReview]) -> bool {
+    let latest = latest_reviews(reviews);
+    latest.iter().any(|r| r.state == ReviewState::Approved)
+        && !latest
+            .iter()
+            .any(|r| r.state == ReviewState::ChangesRequested)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn review(reviewer: &str, state: ReviewState, minute: u32) -> PrReview {
+        PrReview {
+            reviewer: reviewer.to_string(),
+            state,
+            submitted_at: Utc.with_ymd_and_hms(2025, 1, 1, 0, minute, 0).single(),
+        }
+    }
+
+    #[test]
+    fn test_later_approval_overrides_change_request() {
+        let reviews = vec![
+            review("alice", ReviewState::ChangesRequested, 1),
+            review("alice", ReviewState::Commented, 2),
+            review("alice", ReviewState::Approved, 3),
+        ];
+        assert!(changes_requested_by(&reviews).is_empty());
+        assert!(is_approved_by_reviews(&reviews));
+    }
+
+    #[test]
+    fn test_change_request_blocks_other_approvals() {
+        let reviews = vec![
+            review("alice", ReviewState::Approved, 1),
+            review("bob", ReviewState::ChangesRequested, 2),
+        ];
+        assert_eq!(changes_requested_by(&reviews), vec!["bob"]);
+        assert!(!is_approved_by_reviews(&reviews));
+    }
+
+    #[test]
+    fn test_comment_does_not_cancel_approval_but_dismissal_does() {
+        let approved = vec![
+            review("alice", ReviewState::Approved, 1),
+            review("alice", ReviewState::Commented, 2),
+        ];
+        assert!(is_approved_by_reviews(&approved));
+
+        let dismissed = vec![
+            review("alice", ReviewState::Approved, 1),
+            review("alice", ReviewState::Dismissed, 2),
+        ];
+        assert!(!is_approved_by_reviews(&dismissed));
+    }
+
+    #[test]
+    fn test_approval_count_uses_latest_verdicts() {
+        let reviews = vec![
+            review("alice", ReviewState::Approved, 1),
+            review("bob", ReviewState::Approved, 2),
+            review("bob", ReviewState::Dismissed, 3),
+            review("carol", ReviewState::Approved, 4),
+            review("alice", ReviewState::Commented, 5),
+        ];
+        assert_eq!(approval_count(&reviews), 2);
+    }
+}
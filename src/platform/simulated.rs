@@ -0,0 +1,575 @@
+//! Simulated platform for `--simulate`
+//!
+//! An in-process fake that behaves like a platform where every PR is
+//! approved and passes CI as soon as it leaves draft. Its PRs are kept in
+//! `.jj/repo/ryu/simulated.toml`, so a simulated `ryu submit` can be
+//! followed by a simulated `ryu merge`. No network or token is needed.
+
+use crate::error::{Error, Result};
+use crate::platform::PlatformService;
+use crate::tracking::resolve_repo_path;
+use crate::types::{
+    CheckInfo, CheckState, MergeMethod, MergeReadiness, MergeResult, PlatformConfig, PrComment,
+    PrReview, PrState, PullRequest, PullRequestDetails, ReviewState, ReviewThread,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Filename for the simulated platform's PRs
+const SIMULATED_FILE: &str = "simulated.toml";
+
+/// Reviewer who approves every simulated PR
+pub const SIMULATED_REVIEWER: &str = "ryu-simulator";
+
+/// Name of the check every simulated PR passes
+pub const SIMULATED_CHECK: &str = "simulated-ci";
+
+/// User the simulated platform is authenticated as
+const SIMULATED_USER: &str = "you";
+
+/// A PR on the simulated platform
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SimulatedPr {
+    number: u64,
+    head: String,
+    base: String,
+    title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    state: PrState,
+    draft: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    assignees: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    milestone: Option<String>,
+    #[serde(default)]
+    comments: Vec<PrComment>,
+}
+
+/// Everything the simulated platform knows
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SimulatedState {
+    #[serde(default)]
+    last_number: u64,
+    #[serde(default)]
+    last_comment_id: u64,
+    #[serde(default)]
+    prs: Vec<SimulatedPr>,
+}
+
+/// Get path to the simulated platform's state file.
+pub fn simulated_state_path(workspace_root: &Path) -> PathBuf {
+    resolve_repo_path(workspace_root)
+        .join("ryu")
+        .join(SIMULATED_FILE)
+}
+
+/// Platform service that simulates PRs locally
+pub struct SimulatedPlatformService {
+    config: PlatformConfig,
+    /// Where PRs are persisted (None = in memory only)
+    path: Option<PathBuf>,
+    state: Mutex<SimulatedState>,
+}
+
+impl SimulatedPlatformService {
+    /// Create a simulated platform with no PRs, kept in memory
+    pub fn new(config: PlatformConfig) -> Self {
+        Self {
+            config,
+            path: None,
+            state: Mutex::new(SimulatedState::default()),
+        }
+    }
+
+    /// Open the simulated platform persisted at `path`
+    ///
+    /// Starts empty if the file doesn't exist yet.
+    pub fn open(config: PlatformConfig, path: PathBuf) -> Result<Self> {
+        let state = if path.exists() {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| Error::Platform(format!("failed to read {}: {e}", path.display())))?;
+            toml::from_str(&content)
+                .map_err(|e| Error::Platform(format!("failed to parse {}: {e}", path.display())))?
+        } else {
+            SimulatedState::default()
+        };
+        Ok(Self {
+            config,
+            path: Some(path),
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Run `f` on the state and persist the result
+    fn update<T>(&self, f: impl FnOnce(&mut SimulatedState) -> Result<T>) -> Result<T> {
+        let mut state = self.state.lock().map_err(|_| lock_error())?;
+        let value = f(&mut state)?;
+        let content = toml::to_string_pretty(&*state)
+            .map_err(|e| Error::Platform(format!("failed to serialize simulated PRs: {e}")))?;
+        drop(state);
+
+        if let Some(path) = &self.path {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(
+                path,
+                format!("# ryu simulated platform - used by --simulate\n\n{content}"),
+            )?;
+        }
+        Ok(value)
+    }
+
+    /// Run `f` on a PR
+    fn with_pr<T>(&self, number: u64, f: impl FnOnce(&SimulatedPr) -> T) -> Result<T> {
+        let state = self.state.lock().map_err(|_| lock_error())?;
+        state
+            .prs
+            .iter()
+            .find(|pr| pr.number == number)
+            .map(f)
+            .ok_or_else(|| not_found(number))
+    }
+
+    /// Change a PR and persist the result
+    fn update_pr<T>(&self, number: u64, f: impl FnOnce(&mut SimulatedPr) -> Result<T>) -> Result<T> {
+        self.update(|state| {
+            let pr = state
+                .prs
+                .iter_mut()
+                .find(|pr| pr.number == number)
+                .ok_or_else(|| not_found(number))?;
+            f(pr)
+        })
+    }
+
+    fn pr_url(&self, number: u64) -> String {
+        format!(
+            "https://simulated.invalid/{}/{}/pull/{number}",
+            self.config.owner, self.config.repo
+        )
+    }
+
+    fn to_pull_request(&self, pr: &SimulatedPr) -> PullRequest {
+        PullRequest {
+            number: pr.number,
+            html_url: self.pr_url(pr.number),
+            base_ref: pr.base.clone(),
+            head_ref: pr.head.clone(),
+            title: pr.title.clone(),
+            node_id: None,
+            is_draft: pr.draft,
+        }
+    }
+}
+
+fn lock_error() -> Error {
+    Error::Internal("simulated platform state lock poisoned".to_string())
+}
+
+fn not_found(number: u64) -> Error {
+    Error::Platform(format!("simulated PR #{number} not found"))
+}
+
+#[async_trait]
+impl PlatformService for SimulatedPlatformService {
+    async fn find_existing_pr(&self, head_branch: &str) -> Result<Option<PullRequest>> {
+        let state = self.state.lock().map_err(|_| lock_error())?;
+        Ok(state
+            .prs
+            .iter()
+            .find(|pr| pr.head == head_branch && pr.state == PrState::Open)
+            .map(|pr| self.to_pull_request(pr)))
+    }
+
+    async fn find_closed_pr(&self, head_branch: &str) -> Result<Option<PullRequest>> {
+        let state = self.state.lock().map_err(|_| lock_error())?;
+        Ok(state
+            .prs
+            .iter()
+            .rev()
+            .find(|pr| pr.head == head_branch && pr.state == PrState::Closed)
+            .map(|pr| self.to_pull_request(pr)))
+    }
+
+    async fn find_pr_by_body(&self, text: &str) -> Result<Option<PullRequest>> {
+        let state = self.state.lock().map_err(|_| lock_error())?;
+        Ok(state
+            .prs
+            .iter()
+            .rev()
+            .find(|pr| {
+                pr.state == PrState::Open && pr.body.as_deref().is_some_and(|b| b.contains(text))
+            })
+            .map(|pr| self.to_pull_request(pr)))
+    }
+
+    async fn create_pr_with_options(
+        &self,
+        head: &str,
+        base: &str,
+        title: &str,
+        body: Option<&str>,
+        draft: bool,
+    ) -> Result<PullRequest> {
+        self.update(|state| {
+            state.last_number += 1;
+            let pr = SimulatedPr {
+                number: state.last_number,
+                head: head.to_string(),
+                base: base.to_string(),
+                title: title.to_string(),
+                body: body.map(String::from),
+                state: PrState::Open,
+                draft,
+                labels: Vec::new(),
+                assignees: Vec::new(),
+                milestone: None,
+                comments: Vec::new(),
+            };
+            let created = self.to_pull_request(&pr);
+            state.prs.push(pr);
+            Ok(created)
+        })
+    }
+
+    async fn update_pr_base(&self, pr_number: u64, new_base: &str) -> Result<PullRequest> {
+        self.update_pr(pr_number, |pr| {
+            pr.base = new_base.to_string();
+            Ok(self.to_pull_request(pr))
+        })
+    }
+
+    async fn update_pr_title(&self, pr_number: u64, title: &str) -> Result<PullRequest> {
+        self.update_pr(pr_number, |pr| {
+            pr.title = title.to_string();
+            Ok(self.to_pull_request(pr))
+        })
+    }
+
+    async fn update_pr_body(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.update_pr(pr_number, |pr| {
+            pr.body = Some(body.to_string());
+            Ok(())
+        })
+    }
+
+    async fn add_pr_labels(&self, pr_number: u64, labels: &[String]) -> Result<()> {
+        self.update_pr(pr_number, |pr| {
+            for label in labels {
+                if !pr.labels.contains(label) {
+                    pr.labels.push(label.clone());
+                }
+            }
+            Ok(())
+        })
+    }
+
+    async fn assign_pr(&self, pr_number: u64, assignees: &[String]) -> Result<()> {
+        self.update_pr(pr_number, |pr| {
+            for assignee in assignees {
+                if !pr.assignees.contains(assignee) {
+                    pr.assignees.push(assignee.clone());
+                }
+            }
+            Ok(())
+        })
+    }
+
+    async fn request_review(&self, pr_number: u64, _reviewers: &[String]) -> Result<()> {
+        // The simulated reviewer approves every PR, so there's no one to ask
+        self.with_pr(pr_number, |_| ())
+    }
+
+    async fn set_pr_milestone(&self, pr_number: u64, milestone: &str) -> Result<()> {
+        self.update_pr(pr_number, |pr| {
+            pr.milestone = Some(milestone.to_string());
+            Ok(())
+        })
+    }
+
+    async fn publish_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        self.update_pr(pr_number, |pr| {
+            pr.draft = false;
+            Ok(self.to_pull_request(pr))
+        })
+    }
+
+    async fn unpublish_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        self.update_pr(pr_number, |pr| {
+            pr.draft = true;
+            Ok(self.to_pull_request(pr))
+        })
+    }
+
+    async fn close_pr(&self, pr_number: u64) -> Result<()> {
+        self.update_pr(pr_number, |pr| {
+            pr.state = PrState::Closed;
+            Ok(())
+        })
+    }
+
+    async fn reopen_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        self.update_pr(pr_number, |pr| {
+            if pr.state == PrState::Merged {
+                return Err(Error::Platform(format!(
+                    "simulated PR #{pr_number} is merged"
+                )));
+            }
+            pr.state = PrState::Open;
+            Ok(self.to_pull_request(pr))
+        })
+    }
+
+    async fn list_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>> {
+        self.with_pr(pr_number, |pr| pr.comments.clone())
+    }
+
+    async fn create_pr_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.update(|state| {
+            state.last_comment_id += 1;
+            let id = state.last_comment_id;
+            let pr = state
+                .prs
+                .iter_mut()
+                .find(|pr| pr.number == pr_number)
+                .ok_or_else(|| not_found(pr_number))?;
+            pr.comments.push(PrComment {
+                id,
+                body: body.to_string(),
+            });
+            Ok(())
+        })
+    }
+
+    async fn update_pr_comment(&self, pr_number: u64, comment_id: u64, body: &str) -> Result<()> {
+        self.update_pr(pr_number, |pr| {
+            let comment = pr
+                .comments
+                .iter_mut()
+                .find(|c| c.id == comment_id)
+                .ok_or_else(|| Error::Platform(format!("simulated comment {comment_id} not found")))?;
+            comment.body = body.to_string();
+            Ok(())
+        })
+    }
+
+    async fn delete_pr_comment(&self, pr_number: u64, comment_id: u64) -> Result<()> {
+        self.update_pr(pr_number, |pr| {
+            pr.comments.retain(|c| c.id != comment_id);
+            Ok(())
+        })
+    }
+
+    async fn current_user(&self) -> Result<String> {
+        Ok(SIMULATED_USER.to_string())
+    }
+
+    async fn list_protected_branches(&self) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    fn config(&self) -> &PlatformConfig {
+        &self.config
+    }
+
+    async fn get_pr_details(&self, pr_number: u64) -> Result<PullRequestDetails> {
+        self.with_pr(pr_number, |pr| PullRequestDetails {
+            number: pr.number,
+            title: pr.title.clone(),
+            body: pr.body.clone(),
+            state: pr.state,
+            is_draft: pr.draft,
+            mergeable: Some(true),
+            head_ref: pr.head.clone(),
+            head_sha: String::new(),
+            base_ref: pr.base.clone(),
+            html_url: self.pr_url(pr.number),
+            is_behind: false,
+        })
+    }
+
+    async fn list_reviews(&self, pr_number: u64) -> Result<Vec<PrReview>> {
+        let draft = self.with_pr(pr_number, |pr| pr.draft)?;
+        if draft {
+            return Ok(Vec::new());
+        }
+        Ok(vec![PrReview {
+            reviewer: SIMULATED_REVIEWER.to_string(),
+            state: ReviewState::Approved,
+            submitted_at: Some(Utc::now()),
+        }])
+    }
+
+    async fn list_review_threads(&self, pr_number: u64) -> Result<Vec<ReviewThread>> {
+        // Simulated reviewers approve without comments
+        self.with_pr(pr_number, |_| Vec::new())
+    }
+
+    async fn resolve_thread(&self, pr_number: u64, thread_id: &str) -> Result<()> {
+        Err(Error::Platform(format!(
+            "simulated PR #{pr_number} has no review thread {thread_id}"
+        )))
+    }
+
+    async fn list_checks(&self, _git_ref: &str) -> Result<Vec<CheckInfo>> {
+        Ok(vec![CheckInfo {
+            name: SIMULATED_CHECK.to_string(),
+            state: CheckState::Success,
+            required: true,
+            url: None,
+        }])
+    }
+
+    async fn create_commit_status(
+        &self,
+        _sha: &str,
+        _context: &str,
+        _state: CheckState,
+        _description: &str,
+    ) -> Result<()> {
+        // The simulated check always passes; other statuses aren't kept
+        Ok(())
+    }
+
+    async fn check_merge_readiness(&self, details: &PullRequestDetails) -> Result<MergeReadiness> {
+        let is_approved = !self.list_reviews(details.number).await?.is_empty();
+        let checks = self.list_checks(&details.head_ref).await?;
+
+        let mut blocking_reasons = Vec::new();
+        if details.is_draft {
+            blocking_reasons.push("PR is a draft".to_string());
+        }
+        if !is_approved {
+            blocking_reasons.push("Not approved".to_string());
+        }
+        if details.state != PrState::Open {
+            blocking_reasons.push(format!("PR is {}", details.state));
+        }
+
+        Ok(MergeReadiness {
+            is_approved,
+            approvals_required: None,
+            approvals_received: None,
+            checks,
+            is_mergeable: Some(details.state == PrState::Open),
+            is_draft: details.is_draft,
+            blocking_reasons,
+            uncertainties: Vec::new(),
+        })
+    }
+
+    async fn update_pr_branch(&self, pr_number: u64) -> Result<()> {
+        self.with_pr(pr_number, |_| ())
+    }
+
+    async fn enable_auto_merge(&self, pr_number: u64, _method: MergeMethod) -> Result<()> {
+        // Simulated checks always pass, so the PR merges right away
+        self.update_pr(pr_number, |pr| {
+            if pr.state == PrState::Open {
+                pr.state = PrState::Merged;
+            }
+            Ok(())
+        })
+    }
+
+    async fn merge_pr(
+        &self,
+        pr_number: u64,
+        _title: &str,
+        _body: Option<&str>,
+        method: MergeMethod,
+    ) -> Result<MergeResult> {
+        self.update_pr(pr_number, |pr| {
+            if pr.state != PrState::Open {
+                return Ok(MergeResult {
+                    merged: false,
+                    sha: None,
+                    message: Some(format!("PR is {}", pr.state)),
+                });
+            }
+            pr.state = PrState::Merged;
+            Ok(MergeResult {
+                merged: true,
+                sha: None,
+                message: Some(format!("Simulated {method} merge")),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Platform;
+    use tempfile::TempDir;
+
+    fn config() -> PlatformConfig {
+        PlatformConfig {
+            platform: Platform::GitHub,
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            host: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_simulated_prs_persist_between_runs() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(SIMULATED_FILE);
+
+        let platform = SimulatedPlatformService::open(config(), path.clone()).unwrap();
+        let pr = platform
+            .create_pr_with_options("feat-a", "main", "Add a", Some("body"), true)
+            .await
+            .unwrap();
+        assert_eq!(pr.number, 1);
+        platform.create_pr_comment(1, "stack").await.unwrap();
+
+        let reopened = SimulatedPlatformService::open(config(), path).unwrap();
+        let found = reopened.find_existing_pr("feat-a").await.unwrap().unwrap();
+        assert!(found.is_draft);
+        assert_eq!(reopened.list_pr_comments(1).await.unwrap()[0].body, "stack");
+    }
+
+    #[tokio::test]
+    async fn test_simulated_prs_merge_once_published() {
+        let platform = SimulatedPlatformService::new(config());
+        platform
+            .create_pr_with_options("feat-a", "main", "Add a", None, true)
+            .await
+            .unwrap();
+
+        let details = platform.get_pr_details(1).await.unwrap();
+        assert!(platform.check_merge_readiness(&details).await.unwrap().is_blocked());
+
+        platform.publish_pr(1).await.unwrap();
+        let details = platform.get_pr_details(1).await.unwrap();
+        assert!(!platform.check_merge_readiness(&details).await.unwrap().is_blocked());
+
+        let result = platform
+            .merge_pr(1, "Add a", None, MergeMethod::Squash)
+            .await
+            .unwrap();
+        assert!(result.merged);
+        assert!(platform.find_existing_pr("feat-a").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_simulated_unpublish_blocks_merge_again() {
+        let platform = SimulatedPlatformService::new(config());
+        platform.create_pr("feat-a", "main", "Add a").await.unwrap();
+
+        let pr = platform.unpublish_pr(1).await.unwrap();
+        assert!(pr.is_draft);
+        let details = platform.get_pr_details(1).await.unwrap();
+        assert!(platform.check_merge_readiness(&details).await.unwrap().is_blocked());
+    }
+}
@@ -0,0 +1,275 @@
+//! Request throttling for platform services.
+//!
+//! Wraps any [`PlatformService`] to bound the number of in-flight API requests
+//! and to space out write requests, for instances with strict rate limits.
+
+use crate::config::HttpConfig;
+use crate::error::{Error, Result};
+use crate::platform::{PlatformService, find_prs_by_body_individually, find_prs_individually};
+use crate::types::{
+    CheckInfo, CheckState, MergeMethod, MergeReadiness, MergeResult, PlatformConfig, PrComment,
+    PrReview, PullRequest, PullRequestDetails, ReviewThread,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore, SemaphorePermit};
+use tokio::time::Instant;
+
+/// Platform service wrapper enforcing `http.*` configuration limits.
+pub struct ThrottledPlatformService {
+    inner: Box<dyn PlatformService>,
+    max_concurrency: usize,
+    permits: Semaphore,
+    min_write_delay: Duration,
+    last_write: Mutex<Option<Instant>>,
+}
+
+impl ThrottledPlatformService {
+    /// Wrap a platform service with the given limits.
+    pub fn new(inner: Box<dyn PlatformService>, config: &HttpConfig) -> Self {
+        let max_concurrency = config.max_concurrency.max(1);
+        Self {
+            inner,
+            max_concurrency,
+            permits: Semaphore::new(max_concurrency),
+            min_write_delay: Duration::from_millis(config.min_delay_between_writes),
+            last_write: Mutex::new(None),
+        }
+    }
+
+    /// Wait for a request slot.
+    async fn acquire(&self) -> Result<SemaphorePermit<'_>> {
+        self.permits
+            .acquire()
+            .await
+            .map_err(|e| Error::Internal(format!("request limiter closed: {e}")))
+    }
+
+    /// Wait for a request slot, then for the minimum delay since the last write.
+    async fn acquire_write(&self) -> Result<SemaphorePermit<'_>> {
+        let permit = self.acquire().await?;
+        if !self.min_write_delay.is_zero() {
+            let mut last_write = self.last_write.lock().await;
+            if let Some(last) = *last_write {
+                tokio::time::sleep_until(last + self.min_write_delay).await;
+            }
+            *last_write = Some(Instant::now());
+        }
+        Ok(permit)
+    }
+}
+
+#[async_trait]
+impl PlatformService for ThrottledPlatformService {
+    async fn find_existing_pr(&self, head_branch: &str) -> Result<Option<PullRequest>> {
+        let _permit = self.acquire().await?;
+        self.inner.find_existing_pr(head_branch).await
+    }
+
+    async fn find_existing_prs(
+        &self,
+        head_branches: &[String],
+    ) -> Result<HashMap<String, PullRequest>> {
+        if !self.inner.batches_pr_lookups() {
+            return find_prs_individually(self, head_branches).await;
+        }
+        let _permit = self.acquire().await?;
+        self.inner.find_existing_prs(head_branches).await
+    }
+
+    fn batches_pr_lookups(&self) -> bool {
+        self.inner.batches_pr_lookups()
+    }
+
+    async fn find_closed_pr(&self, head_branch: &str) -> Result<Option<PullRequest>> {
+        let _permit = self.acquire().await?;
+        self.inner.find_closed_pr(head_branch).await
+    }
+
+    async fn find_pr_by_body(&self, text: &str) -> Result<Option<PullRequest>> {
+        let _permit = self.acquire().await?;
+        self.inner.find_pr_by_body(text).await
+    }
+
+    async fn find_prs_by_body(&self, texts: &[String]) -> Result<HashMap<String, PullRequest>> {
+        if !self.inner.batches_pr_lookups() {
+            return find_prs_by_body_individually(self, texts).await;
+        }
+        let _permit = self.acquire().await?;
+        self.inner.find_prs_by_body(texts).await
+    }
+
+    async fn create_pr_with_options(
+        &self,
+        head: &str,
+        base: &str,
+        title: &str,
+        body: Option<&str>,
+        draft: bool,
+    ) -> Result<PullRequest> {
+        let _permit = self.acquire_write().await?;
+        self.inner
+            .create_pr_with_options(head, base, title, body, draft)
+            .await
+    }
+
+    async fn update_pr_base(&self, pr_number: u64, new_base: &str) -> Result<PullRequest> {
+        let _permit = self.acquire_write().await?;
+        self.inner.update_pr_base(pr_number, new_base).await
+    }
+
+    async fn update_pr_title(&self, pr_number: u64, title: &str) -> Result<PullRequest> {
+        let _permit = self.acquire_write().await?;
+        self.inner.update_pr_title(pr_number, title).await
+    }
+
+    async fn update_pr_body(&self, pr_number: u64, body: &str) -> Result<()> {
+        let _permit = self.acquire_write().await?;
+        self.inner.update_pr_body(pr_number, body).await
+    }
+
+    async fn add_pr_labels(&self, pr_number: u64, labels: &[String]) -> Result<()> {
+        let _permit = self.acquire_write().await?;
+        self.inner.add_pr_labels(pr_number, labels).await
+    }
+
+    async fn assign_pr(&self, pr_number: u64, assignees: &[String]) -> Result<()> {
+        let _permit = self.acquire_write().await?;
+        self.inner.assign_pr(pr_number, assignees).await
+    }
+
+    async fn request_review(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        let _permit = self.acquire_write().await?;
+        self.inner.request_review(pr_number, reviewers).await
+    }
+
+    async fn set_pr_milestone(&self, pr_number: u64, milestone: &str) -> Result<()> {
+        let _permit = self.acquire_write().await?;
+        self.inner.set_pr_milestone(pr_number, milestone).await
+    }
+
+    async fn publish_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        let _permit = self.acquire_write().await?;
+        self.inner.publish_pr(pr_number).await
+    }
+
+    async fn unpublish_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        let _permit = self.acquire_write().await?;
+        self.inner.unpublish_pr(pr_number).await
+    }
+
+    async fn close_pr(&self, pr_number: u64) -> Result<()> {
+        let _permit = self.acquire_write().await?;
+        self.inner.close_pr(pr_number).await
+    }
+
+    async fn reopen_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        let _permit = self.acquire_write().await?;
+        self.inner.reopen_pr(pr_number).await
+    }
+
+    async fn list_pr_comments(&self, pr_number: u64) -> Result<Vec<PrComment>> {
+        let _permit = self.acquire().await?;
+        self.inner.list_pr_comments(pr_number).await
+    }
+
+    async fn create_pr_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        let _permit = self.acquire_write().await?;
+        self.inner.create_pr_comment(pr_number, body).await
+    }
+
+    async fn update_pr_comment(&self, pr_number: u64, comment_id: u64, body: &str) -> Result<()> {
+        let _permit = self.acquire_write().await?;
+        self.inner
+            .update_pr_comment(pr_number, comment_id, body)
+            .await
+    }
+
+    async fn delete_pr_comment(&self, pr_number: u64, comment_id: u64) -> Result<()> {
+        let _permit = self.acquire_write().await?;
+        self.inner.delete_pr_comment(pr_number, comment_id).await
+    }
+
+    async fn current_user(&self) -> Result<String> {
+        let _permit = self.acquire().await?;
+        self.inner.current_user().await
+    }
+
+    async fn list_protected_branches(&self) -> Result<Vec<String>> {
+        let _permit = self.acquire().await?;
+        self.inner.list_protected_branches().await
+    }
+
+    fn config(&self) -> &PlatformConfig {
+        self.inner.config()
+    }
+
+    fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    async fn get_pr_details(&self, pr_number: u64) -> Result<PullRequestDetails> {
+        let _permit = self.acquire().await?;
+        self.inner.get_pr_details(pr_number).await
+    }
+
+    async fn list_reviews(&self, pr_number: u64) -> Result<Vec<PrReview>> {
+        let _permit = self.acquire().await?;
+        self.inner.list_reviews(pr_number).await
+    }
+
+    async fn list_review_threads(&self, pr_number: u64) -> Result<Vec<ReviewThread>> {
+        let _permit = self.acquire().await?;
+        self.inner.list_review_threads(pr_number).await
+    }
+
+    async fn resolve_thread(&self, pr_number: u64, thread_id: &str) -> Result<()> {
+        let _permit = self.acquire_write().await?;
+        self.inner.resolve_thread(pr_number, thread_id).await
+    }
+
+    async fn list_checks(&self, git_ref: &str) -> Result<Vec<CheckInfo>> {
+        let _permit = self.acquire().await?;
+        self.inner.list_checks(git_ref).await
+    }
+
+    async fn create_commit_status(
+        &self,
+        sha: &str,
+        context: &str,
+        state: CheckState,
+        description: &str,
+    ) -> Result<()> {
+        let _permit = self.acquire_write().await?;
+        self.inner
+            .create_commit_status(sha, context, state, description)
+            .await
+    }
+
+    async fn check_merge_readiness(&self, details: &PullRequestDetails) -> Result<MergeReadiness> {
+        let _permit = self.acquire().await?;
+        self.inner.check_merge_readiness(details).await
+    }
+
+    async fn update_pr_branch(&self, pr_number: u64) -> Result<()> {
+        let _permit = self.acquire_write().await?;
+        self.inner.update_pr_branch(pr_number).await
+    }
+
+    async fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        let _permit = self.acquire_write().await?;
+        self.inner.enable_auto_merge(pr_number, method).await
+    }
+
+    async fn merge_pr(
+        &self,
+        pr_number: u64,
+        title: &str,
+        body: Option<&str>,
+        method: MergeMethod,
+    ) -> Result<MergeResult> {
+        let _permit = self.acquire_write().await?;
+        self.inner.merge_pr(pr_number, title, body, method).await
+    }
+}
@@ -0,0 +1,191 @@
+//! jj aliases that invoke ryu
+//!
+//! Installs entries in the `[aliases]` table of a jj config file so that,
+//! e.g., `jj submit` runs `ryu submit`. Aliases go through `jj util exec`,
+//! and any arguments after the alias are passed on to ryu.
+
+use crate::error::{Error, Result};
+use jj_lib::config::{ConfigFile, ConfigSource, ConfigValue};
+use std::path::{Path, PathBuf};
+
+/// Aliases installed by ryu: alias name and the ryu arguments it runs
+pub const RYU_ALIASES: &[(&str, &[&str])] = &[
+    ("ryu", &[]),
+    ("stack", &[]),
+    ("submit", &["submit"]),
+    ("sync", &["sync"]),
+    ("track", &["track"]),
+    ("untrack", &["untrack"]),
+];
+
+/// Outcome of installing or uninstalling aliases
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AliasReport {
+    /// Aliases written (or removed, when uninstalling)
+    pub changed: Vec<String>,
+    /// Aliases already installed with the expected command
+    pub unchanged: Vec<String>,
+    /// Aliases left alone because they run something else
+    pub conflicting: Vec<String>,
+}
+
+/// Path to the user-level jj config file
+///
+/// Honors `JJ_CONFIG` when it names a single file, then prefers
+/// `~/.config/jj/config.toml` if it exists, like jj does.
+pub fn user_jj_config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("JJ_CONFIG").filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    let home_config = dirs::home_dir().map(|home| home.join(".config").join("jj").join("config.toml"));
+    if let Some(path) = home_config.filter(|p| p.exists()) {
+        return Some(path);
+    }
+    dirs::config_dir().map(|dir| dir.join("jj").join("config.toml"))
+}
+
+/// Path to the repo-level jj config file
+pub fn repo_jj_config_path(workspace_root: &Path) -> PathBuf {
+    crate::tracking::resolve_repo_path(workspace_root).join("config.toml")
+}
+
+/// The jj command an alias runs for the given ryu arguments
+pub fn alias_command(ryu_args: &[&str]) -> Vec<String> {
+    ["util", "exec", "--", "ryu"]
+        .iter()
+        .chain(ryu_args)
+        .map(|arg| (*arg).to_string())
+        .collect()
+}
+
+/// Add ryu's aliases to a jj config file
+///
+/// Existing aliases with the same name but a different command are kept
+/// unless `force` is set.
+pub fn install_aliases(config_path: &Path, force: bool) -> Result<AliasReport> {
+    let mut file = load_config_file(config_path)?;
+    let mut report = AliasReport::default();
+
+    for (name, args) in RYU_ALIASES {
+        let expected = alias_command(args);
+        match read_alias(&file, name) {
+            Some(existing) if existing == expected => {
+                report.unchanged.push((*name).to_string());
+                continue;
+            }
+            Some(_) if !force => {
+                report.conflicting.push((*name).to_string());
+                continue;
+            }
+            _ => {}
+        }
+        file.set_value(["aliases", name], ConfigValue::from_iter(expected))
+            .map_err(|e| Error::Config(format!("failed to set alias '{name}': {e}")))?;
+        report.changed.push((*name).to_string());
+    }
+
+    if !report.changed.is_empty() {
+        save_config_file(&file)?;
+    }
+    Ok(report)
+}
+
+/// Remove ryu's aliases from a jj config file
+///
+/// Only aliases that still run the command ryu installed are removed.
+pub fn uninstall_aliases(config_path: &Path) -> Result<AliasReport> {
+    let mut report = AliasReport::default();
+    if !config_path.exists() {
+        return Ok(report);
+    }
+    let mut file = load_config_file(config_path)?;
+
+    for (name, args) in RYU_ALIASES {
+        match read_alias(&file, name) {
+            Some(existing) if existing == alias_command(args) => {
+                file.delete_value(["aliases", name])
+                    .map_err(|e| Error::Config(format!("failed to remove alias '{name}': {e}")))?;
+                report.changed.push((*name).to_string());
+            }
+            Some(_) => report.conflicting.push((*name).to_string()),
+            None => {}
+        }
+    }
+
+    if !report.changed.is_empty() {
+        save_config_file(&file)?;
+    }
+    Ok(report)
+}
+
+fn load_config_file(path: &Path) -> Result<ConfigFile> {
+    ConfigFile::load_or_empty(ConfigSource::User, path)
+        .map_err(|e| Error::Config(format!("failed to load {}: {e}", path.display())))
+}
+
+fn save_config_file(file: &ConfigFile) -> Result<()> {
+    if let Some(parent) = file.path().parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    file.save()
+        .map_err(|e| Error::Config(format!("failed to write {}: {e}", file.path().display())))
+}
+
+/// Read an alias as a list of strings (None if absent or not a string array)
+fn read_alias(file: &ConfigFile, name: &str) -> Option<Vec<String>> {
+    let item = file.layer().look_up_item(["aliases", name]).ok()??;
+    item.as_array()?
+        .iter()
+        .map(|value| value.as_str().map(String::from))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_install_and_uninstall_aliases() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("jj").join("config.toml");
+
+        let report = install_aliases(&path, false).unwrap();
+        assert_eq!(report.changed.len(), RYU_ALIASES.len());
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains(r#"submit = ["util", "exec", "--", "ryu", "submit"]"#));
+
+        let again = install_aliases(&path, false).unwrap();
+        assert!(again.changed.is_empty());
+        assert_eq!(again.unchanged.len(), RYU_ALIASES.len());
+
+        let removed = uninstall_aliases(&path).unwrap();
+        assert_eq!(removed.changed.len(), RYU_ALIASES.len());
+        assert!(!fs::read_to_string(&path).unwrap().contains("ryu"));
+    }
+
+    #[test]
+    fn test_install_keeps_user_aliases_unless_forced() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("config.toml");
+        fs::write(
+            &path,
+            "# my config\n[aliases]\nsync = [\"git\", \"fetch\"]\n",
+        )
+        .unwrap();
+
+        let report = install_aliases(&path, false).unwrap();
+        assert_eq!(report.conflicting, vec!["sync".to_string()]);
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("# my config"));
+        assert!(content.contains(r#"sync = ["git", "fetch"]"#));
+
+        let removed = uninstall_aliases(&path).unwrap();
+        assert_eq!(removed.conflicting, vec!["sync".to_string()]);
+        assert!(fs::read_to_string(&path).unwrap().contains("sync"));
+
+        let forced = install_aliases(&path, true).unwrap();
+        assert!(forced.changed.contains(&"sync".to_string()));
+    }
+}
@@ -0,0 +1,109 @@
+//! Conflicts left in a stack by a rebase
+//!
+//! jj records conflicts in the rebased commits instead of stopping, so after
+//! restacking, ryu looks for them before pushing anything. Each conflicted
+//! segment gets an empty "resolution change" on top of its oldest conflicted
+//! commit, to resolve in and squash down (the `jj new` + `jj squash` flow).
+
+use crate::error::Result;
+use crate::repo::JjWorkspace;
+use crate::types::NarrowedBookmarkSegment;
+
+/// Start of the description of resolution changes
+pub const RESOLUTION_CHANGE_PREFIX: &str = "ryu: resolve conflicts in ";
+
+/// A commit with unresolved conflicts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictedChange {
+    /// Commit ID (hex)
+    pub commit_id: String,
+    /// First line of the commit description
+    pub description_first_line: String,
+    /// Conflicted file paths
+    pub paths: Vec<String>,
+}
+
+/// Conflicts in one bookmark's segment of the stack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackConflict {
+    /// Bookmark whose segment has conflicts
+    pub bookmark: String,
+    /// Conflicted commits, oldest first
+    pub changes: Vec<ConflictedChange>,
+    /// Commit ID of the change to resolve them in, once created
+    pub resolution_change: Option<String>,
+}
+
+/// Description of the resolution change for `bookmark`
+pub fn resolution_change_description(bookmark: &str) -> String {
+    format!("{RESOLUTION_CHANGE_PREFIX}{bookmark}\n")
+}
+
+/// Find the conflicted commits of each segment, bottom of the stack first
+///
+/// Segments without conflicts are left out.
+pub fn find_stack_conflicts(
+    workspace: &JjWorkspace,
+    segments: &[NarrowedBookmarkSegment],
+) -> Result<Vec<StackConflict>> {
+    let mut conflicts = Vec::new();
+    for segment in segments {
+        let mut changes = Vec::new();
+        // Segment changes are newest first
+        for change in segment.changes.iter().rev() {
+            let paths = workspace.conflicted_paths(&change.commit_id)?;
+            if !paths.is_empty() {
+                changes.push(ConflictedChange {
+                    commit_id: change.commit_id.clone(),
+                    description_first_line: change.description_first_line.clone(),
+                    paths,
+                });
+            }
+        }
+        if !changes.is_empty() {
+            conflicts.push(StackConflict {
+                bookmark: segment.bookmark.name.clone(),
+                changes,
+                resolution_change: None,
+            });
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Create a resolution change on each segment's oldest conflicted commit
+///
+/// Reuses a resolution change left by an earlier run. The working copy
+/// doesn't move.
+pub fn create_resolution_changes(
+    workspace: &mut JjWorkspace,
+    conflicts: &mut [StackConflict],
+) -> Result<()> {
+    for conflict in conflicts {
+        let Some(oldest) = conflict.changes.first() else {
+            continue;
+        };
+        let description = resolution_change_description(&conflict.bookmark);
+        let existing = workspace.resolve_revset(&format!(
+            "children({}) & description(exact:{description:?})",
+            oldest.commit_id
+        ))?;
+        let commit_id = match existing.first() {
+            Some(entry) => entry.commit_id.clone(),
+            None => workspace.new_change(&oldest.commit_id, &description)?,
+        };
+        conflict.resolution_change = Some(commit_id);
+    }
+    Ok(())
+}
+
+/// Abandon resolution changes that were left empty
+///
+/// Changes that were squashed into the conflicted commit are gone already;
+/// this cleans up the ones resolved some other way. Returns how many were
+/// abandoned.
+pub fn remove_resolution_changes(workspace: &mut JjWorkspace) -> Result<usize> {
+    workspace.abandon_empty(&format!(
+        "description(substring:{RESOLUTION_CHANGE_PREFIX:?})"
+    ))
+}
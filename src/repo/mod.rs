@@ -2,6 +2,16 @@
 //!
 //! Provides a high-level interface to jj repository operations.
 
+mod aliases;
+mod conflicts;
 mod workspace;
 
+pub use aliases::{
+    AliasReport, RYU_ALIASES, alias_command, install_aliases, repo_jj_config_path,
+    uninstall_aliases, user_jj_config_path,
+};
+pub use conflicts::{
+    ConflictedChange, RESOLUTION_CHANGE_PREFIX, StackConflict, create_resolution_changes,
+    find_stack_conflicts, remove_resolution_changes, resolution_change_description,
+};
 pub use workspace::{JjWorkspace, select_remote};
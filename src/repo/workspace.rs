@@ -7,12 +7,16 @@ use chrono::{DateTime, TimeZone, Utc};
 use jj_lib::backend::Timestamp;
 use jj_lib::commit::Commit;
 use jj_lib::config::{ConfigLayer, ConfigSource, StackedConfig};
+use jj_lib::merge::Merge;
+use jj_lib::merged_tree::MergedTree;
 use jj_lib::git::{
     self, GitFetch, GitImportOptions, GitRefUpdate, GitSettings, RemoteCallbacks,
     expand_fetch_refspecs,
 };
 use jj_lib::op_store::RefTarget;
-use jj_lib::rewrite::{MoveCommitsLocation, MoveCommitsTarget, RebaseOptions, move_commits};
+use jj_lib::rewrite::{
+    MoveCommitsLocation, MoveCommitsTarget, RebaseOptions, duplicate_commits, move_commits,
+};
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_store::{RemoteRef, RemoteRefState};
 use jj_lib::ref_name::{RefName, RemoteName};
@@ -24,13 +28,18 @@ use jj_lib::revset::{
 use jj_lib::settings::UserSettings;
 use jj_lib::str_util::{StringExpression, StringMatcher, StringPattern};
 use jj_lib::workspace::{Workspace, default_working_copy_factories};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
+use tracing::debug;
 
 /// Wrapper around jj-lib workspace and repository
 pub struct JjWorkspace {
     workspace: Workspace,
     settings: UserSettings,
+    /// Skip fetches and pushes (`--simulate`)
+    simulate_remote: bool,
 }
 
 /// Create `UserSettings` with defaults for read operations
@@ -90,9 +99,18 @@ impl JjWorkspace {
         Ok(Self {
             workspace,
             settings,
+            simulate_remote: false,
         })
     }
 
+    /// Pretend remote operations succeed, for `--simulate`
+    ///
+    /// Fetches and pushes return immediately without contacting the remote,
+    /// leaving it and its remote-tracking bookmarks untouched.
+    pub const fn simulate_remote(&mut self) {
+        self.simulate_remote = true;
+    }
+
     /// Get the readonly repo at head operation
     fn repo(&self) -> Result<Arc<jj_lib::repo::ReadonlyRepo>> {
         self.workspace
@@ -101,6 +119,35 @@ impl JjWorkspace {
             .map_err(|e| Error::Workspace(format!("Failed to load repo: {e}")))
     }
 
+    /// Get the ID of the operation the repo is at
+    ///
+    /// Recorded before ryu changes the repo, so `ryu undo` can restore it.
+    pub fn current_operation_id(&self) -> Result<String> {
+        Ok(self.repo()?.operation().id().hex())
+    }
+
+    /// Restore the repo to how it was at an earlier operation
+    ///
+    /// Like `jj op restore`: a new operation brings back the bookmarks,
+    /// heads and working-copy commits of `operation_id`, which may be a
+    /// prefix of the full ID. The working copy on disk is updated by the
+    /// next jj command.
+    pub fn restore_operation(&mut self, operation_id: &str) -> Result<()> {
+        let repo = self.repo()?;
+        let target = jj_lib::op_walk::resolve_op_with_repo(&repo, operation_id)
+            .map_err(|e| Error::Workspace(format!("Unknown operation '{operation_id}': {e}")))?;
+        let view = target
+            .view()
+            .map_err(|e| Error::Workspace(format!("Failed to load operation: {e}")))?;
+
+        let mut tx = repo.start_transaction();
+        tx.repo_mut().set_view(view.store_view().clone());
+        tx.commit(format!("restore to operation {}", target.id().hex()))
+            .map_err(|e| Error::Workspace(format!("Failed to restore operation: {e}")))?;
+
+        Ok(())
+    }
+
     /// Get git settings from user settings
     fn git_settings(&self) -> Result<GitSettings> {
         GitSettings::from_settings(&self.settings)
@@ -459,8 +506,85 @@ impl JjWorkspace {
         Ok(remotes)
     }
 
+    /// Get the path of the backing git directory
+    pub fn git_dir(&self) -> Result<PathBuf> {
+        let repo = self.repo()?;
+        let git_repo = git::get_git_repo(repo.store())
+            .map_err(|_| Error::Git("Not a git-backed repo".to_string()))?;
+        Ok(git_repo.common_dir().to_path_buf())
+    }
+
+    /// Read the blobs that git refs under `prefix` point to
+    ///
+    /// Returns `(name, content)` pairs with `prefix` stripped from each ref
+    /// name. Refs that don't point at a blob are skipped.
+    pub fn git_ref_blobs(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        let repo = self.repo()?;
+        let git_repo = git::get_git_repo(repo.store())
+            .map_err(|_| Error::Git("Not a git-backed repo".to_string()))?;
+
+        let references = git_repo
+            .references()
+            .map_err(|e| Error::Git(format!("Failed to read refs: {e}")))?;
+        let refs = references
+            .prefixed(prefix)
+            .map_err(|e| Error::Git(format!("Failed to read refs under {prefix}: {e}")))?;
+
+        let mut blobs = Vec::new();
+        for reference in refs.flatten() {
+            let full_name = reference.name().as_bstr().to_string();
+            let Some(name) = full_name.strip_prefix(prefix) else {
+                continue;
+            };
+            let Some(id) = reference.try_id() else {
+                continue;
+            };
+            if let Ok(blob) = git_repo.find_blob(id) {
+                blobs.push((name.to_string(), blob.data.clone()));
+            }
+        }
+
+        Ok(blobs)
+    }
+
+    /// Import local git branches as jj bookmarks
+    ///
+    /// Only the named branches are imported; other git refs are left as is.
+    pub fn import_git_branches(&mut self, names: &[String]) -> Result<()> {
+        let repo = self.repo()?;
+        let git_settings = self.git_settings()?;
+
+        let mut tx = repo.start_transaction();
+
+        let import_options = GitImportOptions {
+            auto_local_bookmark: git_settings.auto_local_bookmark,
+            abandon_unreachable_commits: git_settings.abandon_unreachable_commits,
+            remote_auto_track_bookmarks: std::collections::HashMap::new(),
+        };
+        git::import_some_refs(tx.repo_mut(), &import_options, |kind, symbol| {
+            kind == git::GitRefKind::Bookmark
+                && symbol.remote == git::REMOTE_NAME_FOR_LOCAL_GIT_REPO
+                && names.iter().any(|name| name == symbol.name.as_str())
+        })
+        .map_err(|e| Error::Git(format!("Failed to import git branches: {e}")))?;
+
+        if tx.repo().has_rewrites() {
+            tx.repo_mut()
+                .rebase_descendants()
+                .map_err(|e| Error::Git(format!("Failed to rebase descendants: {e}")))?;
+        }
+
+        tx.commit(format!("import git branches {}", names.join(", ")))
+            .map_err(|e| Error::Git(format!("Failed to commit import: {e}")))?;
+
+        Ok(())
+    }
+
     /// Fetch from a git remote
     pub fn git_fetch(&mut self, remote: &str) -> Result<()> {
+        if self.simulate_remote {
+            return Ok(());
+        }
         let repo = self.repo()?;
         let git_settings = self.git_settings()?;
 
@@ -484,6 +608,7 @@ impl JjWorkspace {
         .map_err(|e| Error::Git(format!("Failed to create fetch: {e}")))?;
 
         let remote_name = RemoteName::new(remote);
+        let started = Instant::now();
         let refspecs = expand_fetch_refspecs(remote_name, StringExpression::all())
             .map_err(|e| Error::Git(format!("Failed to expand refspecs: {e}")))?;
         fetch
@@ -495,6 +620,7 @@ impl JjWorkspace {
                 None,
             )
             .map_err(|e| Error::Git(format!("Failed to fetch: {e}")))?;
+        debug!(remote, elapsed = ?started.elapsed(), "fetched");
 
         // Import the fetched refs
         fetch
@@ -519,6 +645,9 @@ impl JjWorkspace {
 
     /// Push a bookmark to a remote
     pub fn git_push(&mut self, bookmark: &str, remote: &str) -> Result<()> {
+        if self.simulate_remote {
+            return Ok(());
+        }
         let repo = self.repo()?;
         let git_settings = self.git_settings()?;
 
@@ -565,6 +694,7 @@ impl JjWorkspace {
             new_target,
         };
 
+        let started = Instant::now();
         git::push_updates(
             tx.repo_mut().base_repo().as_ref(),
             git_settings.to_subprocess_options(),
@@ -573,6 +703,7 @@ impl JjWorkspace {
             RemoteCallbacks::default(),
         )
         .map_err(|e| Error::Git(format!("Failed to push: {e}")))?;
+        debug!(bookmark, remote, elapsed = ?started.elapsed(), "pushed");
 
         // Update the remote tracking ref to match what we just pushed
         // This ensures the bookmark shows as "synced" after push
@@ -588,6 +719,108 @@ impl JjWorkspace {
         Ok(())
     }
 
+    /// Push `commit_id` and its ancestors to a Gerrit magic ref
+    ///
+    /// `target` is e.g. `refs/for/main` or `refs/for/main%wip`. Gerrit turns
+    /// each new commit into a change or a new patch set. Returns false if
+    /// Gerrit rejected the push because nothing changed.
+    pub fn push_for_review(&mut self, remote: &str, commit_id: &str, target: &str) -> Result<bool> {
+        if self.simulate_remote {
+            return Ok(true);
+        }
+        let repo = self.repo()?;
+        let git_settings = self.git_settings()?;
+        let id = CommitId::try_from_hex(commit_id)
+            .ok_or_else(|| Error::Git(format!("invalid commit id '{commit_id}'")))?;
+
+        let update = GitRefUpdate {
+            qualified_name: target.into(),
+            expected_current_target: None,
+            new_target: Some(id),
+        };
+        let stats = git::push_updates(
+            repo.as_ref(),
+            git_settings.to_subprocess_options(),
+            RemoteName::new(remote),
+            &[update],
+            RemoteCallbacks::default(),
+        )
+        .map_err(|e| Error::Git(format!("Failed to push: {e}")))?;
+
+        match stats.remote_rejected.first() {
+            None => Ok(true),
+            Some((_, Some(reason))) if reason.contains("no new changes") => Ok(false),
+            Some((_, reason)) => Err(Error::Git(format!(
+                "{remote} rejected the push to {target}: {}",
+                reason.as_deref().unwrap_or("no reason given")
+            ))),
+        }
+    }
+
+    /// Move a bookmark on the remote to `commit_id`, or delete it if `None`
+    ///
+    /// Used to undo a push, and to move the head branch of a renamed
+    /// bookmark's PR along with it. The push is leased against the current
+    /// remote-tracking target, so it fails rather than clobbering commits
+    /// someone else pushed in the meantime.
+    pub fn restore_remote_bookmark(
+        &mut self,
+        bookmark: &str,
+        remote: &str,
+        commit_id: Option<&str>,
+    ) -> Result<()> {
+        if self.simulate_remote {
+            return Ok(());
+        }
+        let repo = self.repo()?;
+        let git_settings = self.git_settings()?;
+
+        let new_target = commit_id
+            .map(|hex| {
+                CommitId::try_from_hex(hex)
+                    .ok_or_else(|| Error::Git(format!("invalid commit id '{hex}'")))
+            })
+            .transpose()?;
+
+        let ref_name = RefName::new(bookmark);
+        let remote_name = RemoteName::new(remote);
+        let remote_symbol = ref_name.to_remote_symbol(remote_name);
+        let expected_current_target = repo
+            .view()
+            .get_remote_bookmark(remote_symbol)
+            .target
+            .as_normal()
+            .cloned();
+
+        let update = GitRefUpdate {
+            qualified_name: format!("refs/heads/{bookmark}").into(),
+            expected_current_target,
+            new_target: new_target.clone(),
+        };
+
+        let mut tx = repo.start_transaction();
+
+        git::push_updates(
+            tx.repo_mut().base_repo().as_ref(),
+            git_settings.to_subprocess_options(),
+            remote_name,
+            &[update],
+            RemoteCallbacks::default(),
+        )
+        .map_err(|e| Error::Git(format!("Failed to push: {e}")))?;
+
+        let remote_ref = new_target.map_or_else(RemoteRef::absent, |id| RemoteRef {
+            target: RefTarget::normal(id),
+            state: RemoteRefState::Tracked,
+        });
+        tx.repo_mut().set_remote_bookmark(remote_symbol, remote_ref);
+
+        tx.commit(format!("restore {bookmark} on {remote}"))
+            .map_err(|e| Error::Git(format!("Failed to commit push: {e}")))?;
+
+        Ok(())
+    }
+
     /// Get the default branch name by checking remote HEAD first, then common names
     pub fn default_branch(&self) -> Result<String> {
         let repo = self.repo()?;
@@ -617,20 +850,44 @@ impl JjWorkspace {
         self.workspace.workspace_root()
     }
 
+    /// Get the configured jj user name
+    pub fn user_name(&self) -> &str {
+        self.settings.user_name()
+    }
+
     /// Rebase a bookmark and its descendants onto trunk
     ///
     /// After a merge, the bottom of the stack is now in trunk.
     /// This rebases the next bookmark (and everything above it) onto the new trunk.
     pub fn rebase_bookmark_onto_trunk(&mut self, bookmark: &str) -> Result<()> {
+        self.rebase_bookmark_onto(bookmark, "trunk()", "trunk")
+    }
+
+    /// Rebase a bookmark and its descendants onto a remote branch
+    ///
+    /// Like [`Self::rebase_bookmark_onto_trunk`], for stacks that target a
+    /// branch other than the default (e.g. `release/2.3@origin`).
+    pub fn rebase_bookmark_onto_remote(
+        &mut self,
+        bookmark: &str,
+        branch: &str,
+        remote: &str,
+    ) -> Result<()> {
+        let revset = format!("remote_bookmarks(exact:{branch:?}, exact:{remote:?})");
+        self.rebase_bookmark_onto(bookmark, &revset, &format!("{branch}@{remote}"))
+    }
+
+    /// Rebase a bookmark and its descendants onto the commit `destination` resolves to
+    fn rebase_bookmark_onto(&self, bookmark: &str, destination: &str, label: &str) -> Result<()> {
         let repo = self.repo()?;
 
-        // Resolve trunk() to get destination commit
-        let trunk_commits = self.resolve_revset("trunk()")?;
-        let trunk_entry = trunk_commits
-            .first()
-            .ok_or_else(|| Error::RebaseFailed("trunk() resolved to empty set".to_string()))?;
-        let trunk_commit_id = CommitId::try_from_hex(&trunk_entry.commit_id)
-            .ok_or_else(|| Error::RebaseFailed("invalid trunk commit id".to_string()))?;
+        // Resolve the destination commit
+        let destination_commits = self.resolve_revset(destination)?;
+        let destination_entry = destination_commits.first().ok_or_else(|| {
+            Error::RebaseFailed(format!("{destination} resolved to empty set"))
+        })?;
+        let destination_commit_id = CommitId::try_from_hex(&destination_entry.commit_id)
+            .ok_or_else(|| Error::RebaseFailed(format!("invalid {label} commit id")))?;
 
         // Resolve the bookmark to get the commit to rebase
         let bookmark_commits = self.resolve_revset(bookmark)?;
@@ -645,9 +902,9 @@ impl JjWorkspace {
         // Start a transaction for the rebase
         let mut tx = repo.start_transaction();
 
-        // Set up the move: rebase bookmark and all descendants onto trunk
+        // Set up the move: rebase bookmark and all descendants onto the destination
         let location = MoveCommitsLocation {
-            new_parent_ids: vec![trunk_commit_id],
+            new_parent_ids: vec![destination_commit_id],
             new_child_ids: vec![],
             target: MoveCommitsTarget::Roots(vec![bookmark_commit_id]),
         };
@@ -658,12 +915,327 @@ impl JjWorkspace {
             .map_err(|e| Error::RebaseFailed(format!("Failed to rebase: {e}")))?;
 
         // Commit the transaction
-        tx.commit(format!("rebase {bookmark} onto trunk"))
+        tx.commit(format!("rebase {bookmark} onto {label}"))
             .map_err(|e| Error::RebaseFailed(format!("Failed to commit rebase: {e}")))?;
 
         Ok(())
     }
 
+    /// Whether `ancestor` is an ancestor of (or the same commit as) `descendant`
+    pub fn is_ancestor(&self, ancestor: &str, descendant: &str) -> Result<bool> {
+        let common = self.resolve_revset(&format!("{ancestor} & ::{descendant}"))?;
+        Ok(!common.is_empty())
+    }
+
+    /// Fold commits someone else pushed on top of a bookmark into its change
+    ///
+    /// `pushed` is the commit last pushed for `bookmark`, and `remote_head`
+    /// where the remote branch is now (a descendant of `pushed`). The changes
+    /// between them are applied to `local`, the bookmark's own commit, whose
+    /// descendants are rebased onto the result; the commits from the remote
+    /// are abandoned and the bookmark is moved to the rewritten commit.
+    /// Returns the rewritten commit's ID. Nothing is changed if the changes
+    /// conflict with `local`.
+    pub fn absorb_remote_commits(
+        &mut self,
+        bookmark: &str,
+        local: &str,
+        pushed: &str,
+        remote_head: &str,
+    ) -> Result<String> {
+        let repo = self.repo()?;
+
+        let get = |hex: &str| {
+            let id = CommitId::try_from_hex(hex)
+                .ok_or_else(|| Error::Workspace(format!("invalid commit id '{hex}'")))?;
+            repo.store()
+                .get_commit(&id)
+                .map_err(|e| Error::Workspace(format!("Failed to get commit: {e}")))
+        };
+        let local_commit = get(local)?;
+        let pushed_commit = get(pushed)?;
+        let remote_commit = get(remote_head)?;
+        let remote_only = self
+            .resolve_revset(&format!("{pushed}..{remote_head}"))?
+            .iter()
+            .map(|entry| get(&entry.commit_id))
+            .collect::<Result<Vec<_>>>()?;
+
+        let tree = futures::executor::block_on(MergedTree::merge(Merge::from_vec(vec![
+            (local_commit.tree(), format!("{bookmark} (local)")),
+            (pushed_commit.tree(), format!("{bookmark} (last pushed)")),
+            (remote_commit.tree(), format!("{bookmark} (remote)")),
+        ])))
+        .map_err(|e| Error::Workspace(format!("Failed to merge trees: {e}")))?;
+        if tree.has_conflict() {
+            return Err(Error::RebaseFailed(format!(
+                "commits pushed to {bookmark} conflict with the local change"
+            )));
+        }
+
+        let mut tx = repo.start_transaction();
+        let rewritten = tx
+            .repo_mut()
+            .rewrite_commit(&local_commit)
+            .set_tree(tree)
+            .write()
+            .map_err(|e| Error::Workspace(format!("Failed to rewrite commit: {e}")))?;
+        for commit in &remote_only {
+            tx.repo_mut()
+                .record_abandoned_commit_with_parents(commit.id().clone(), [rewritten.id().clone()]);
+        }
+        tx.repo_mut()
+            .rebase_descendants()
+            .map_err(|e| Error::RebaseFailed(format!("Failed to rebase descendants: {e}")))?;
+        tx.repo_mut().set_local_bookmark_target(
+            RefName::new(bookmark),
+            RefTarget::normal(rewritten.id().clone()),
+        );
+
+        tx.commit(format!(
+            "absorb {} remote commit(s) into {bookmark}",
+            remote_only.len()
+        ))
+        .map_err(|e| Error::Workspace(format!("Failed to commit absorb: {e}")))?;
+
+        Ok(rewritten.id().hex())
+    }
+
+    /// Replace a bookmark's own commits with where it is on a remote
+    ///
+    /// `own_commits` are the commits of the bookmark's segment. Those the
+    /// remote doesn't share are abandoned, their descendants are rebased onto
+    /// `remote_head`, and the bookmark is moved there, like resetting a branch
+    /// to its remote.
+    pub fn reset_to_remote(
+        &mut self,
+        bookmark: &str,
+        own_commits: &[String],
+        remote_head: &str,
+    ) -> Result<()> {
+        let parse = |hex: &str| {
+            CommitId::try_from_hex(hex)
+                .ok_or_else(|| Error::Workspace(format!("invalid commit id '{hex}'")))
+        };
+        let remote_id = parse(remote_head)?;
+        let mut abandoned = Vec::new();
+        for hex in own_commits {
+            if !self.is_ancestor(hex, remote_head)? {
+                abandoned.push(parse(hex)?);
+            }
+        }
+
+        let repo = self.repo()?;
+        let mut tx = repo.start_transaction();
+        for id in abandoned {
+            tx.repo_mut()
+                .record_abandoned_commit_with_parents(id, [remote_id.clone()]);
+        }
+        tx.repo_mut()
+            .rebase_descendants()
+            .map_err(|e| Error::RebaseFailed(format!("Failed to rebase descendants: {e}")))?;
+        tx.repo_mut()
+            .set_local_bookmark_target(RefName::new(bookmark), RefTarget::normal(remote_id));
+
+        tx.commit(format!("reset {bookmark} to its remote"))
+            .map_err(|e| Error::Workspace(format!("Failed to commit reset: {e}")))?;
+
+        Ok(())
+    }
+
+    /// Paths with unresolved conflicts in a commit (empty if it has none)
+    pub fn conflicted_paths(&self, commit_id: &str) -> Result<Vec<String>> {
+        let repo = self.repo()?;
+        let id = CommitId::try_from_hex(commit_id)
+            .ok_or_else(|| Error::Workspace(format!("invalid commit id '{commit_id}'")))?;
+        let commit = repo
+            .store()
+            .get_commit(&id)
+            .map_err(|e| Error::Workspace(format!("Failed to get commit: {e}")))?;
+
+        Ok(commit
+            .tree()
+            .conflicts()
+            .map(|(path, _)| path.as_internal_file_string().to_string())
+            .collect())
+    }
+
+    /// Create an empty change on top of `parent`, like `jj new --no-edit`
+    ///
+    /// The working copy stays where it is. Returns the new commit's ID.
+    pub fn new_change(&mut self, parent: &str, description: &str) -> Result<String> {
+        let repo = self.repo()?;
+        let id = CommitId::try_from_hex(parent)
+            .ok_or_else(|| Error::Workspace(format!("invalid commit id '{parent}'")))?;
+        let parent_commit = repo
+            .store()
+            .get_commit(&id)
+            .map_err(|e| Error::Workspace(format!("Failed to get commit: {e}")))?;
+
+        let mut tx = repo.start_transaction();
+        let commit = tx
+            .repo_mut()
+            .new_commit(vec![id], parent_commit.tree())
+            .set_description(description)
+            .write()
+            .map_err(|e| Error::Workspace(format!("Failed to create change: {e}")))?;
+        tx.commit(format!("new empty change on {}", &parent[..12.min(parent.len())]))
+            .map_err(|e| Error::Workspace(format!("Failed to commit new change: {e}")))?;
+
+        Ok(commit.id().hex())
+    }
+
+    /// Abandon the commits `revset` resolves to that don't change anything
+    ///
+    /// Descendants are rebased onto their parents. Returns how many commits
+    /// were abandoned.
+    pub fn abandon_empty(&mut self, revset: &str) -> Result<usize> {
+        let repo = self.repo()?;
+        let mut commits = Vec::new();
+        for entry in self.resolve_revset(revset)? {
+            let id = CommitId::try_from_hex(&entry.commit_id).ok_or_else(|| {
+                Error::Workspace(format!("invalid commit id '{}'", entry.commit_id))
+            })?;
+            let commit = repo
+                .store()
+                .get_commit(&id)
+                .map_err(|e| Error::Workspace(format!("Failed to get commit: {e}")))?;
+            let is_empty = commit
+                .is_empty(repo.as_ref())
+                .map_err(|e| Error::Workspace(format!("Failed to diff commit: {e}")))?;
+            if is_empty {
+                commits.push(commit);
+            }
+        }
+        if commits.is_empty() {
+            return Ok(0);
+        }
+
+        let mut tx = repo.start_transaction();
+        for commit in &commits {
+            tx.repo_mut().record_abandoned_commit(commit);
+        }
+        tx.repo_mut()
+            .rebase_descendants()
+            .map_err(|e| Error::RebaseFailed(format!("Failed to rebase descendants: {e}")))?;
+        tx.commit(format!("abandon {} empty commit(s)", commits.len()))
+            .map_err(|e| Error::Workspace(format!("Failed to commit abandon: {e}")))?;
+
+        Ok(commits.len())
+    }
+
+    /// Abandon the empty changes of bookmark `name`'s own segment
+    ///
+    /// That's the changes between `base_revset` and `name` that aren't
+    /// under another local bookmark, so the changes of lower bookmarks are
+    /// left alone. Returns how many commits were abandoned.
+    pub fn abandon_empty_segment(&mut self, base_revset: &str, name: &str) -> Result<usize> {
+        self.abandon_empty(&format!(
+            "({base_revset}..{name:?}) ~ ::((::{name:?} & bookmarks()) ~ {name:?})"
+        ))
+    }
+
+    /// Change the descriptions of commits, like `jj describe`
+    ///
+    /// `descriptions` maps commit IDs to their new description. Descendants
+    /// are rebased onto the rewritten commits. Returns a map from the old ID
+    /// of every rewritten commit (described or rebased) to its new ID.
+    pub fn set_descriptions(
+        &mut self,
+        descriptions: &HashMap<String, String>,
+    ) -> Result<HashMap<String, String>> {
+        if descriptions.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let repo = self.repo()?;
+        let roots = descriptions
+            .keys()
+            .map(|hex| {
+                CommitId::try_from_hex(hex)
+                    .ok_or_else(|| Error::Workspace(format!("invalid commit id '{hex}'")))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut tx = repo.start_transaction();
+        let mut rewritten = HashMap::new();
+        tx.repo_mut()
+            .transform_descendants(roots, async |rewriter| {
+                let old_id = rewriter.old_commit().id().hex();
+                if let Some(description) = descriptions.get(&old_id) {
+                    let commit = rewriter.reparent().set_description(description).write()?;
+                    rewritten.insert(old_id, commit.id().hex());
+                } else if rewriter.parents_changed() {
+                    let commit = rewriter.reparent().write()?;
+                    rewritten.insert(old_id, commit.id().hex());
+                }
+                Ok(())
+            })
+            .map_err(|e| Error::Workspace(format!("Failed to rewrite commits: {e}")))?;
+        tx.commit(format!("describe {} commit(s)", descriptions.len()))
+            .map_err(|e| Error::Workspace(format!("Failed to commit describe: {e}")))?;
+
+        Ok(rewritten)
+    }
+
+    /// Duplicate commits onto another commit, like `jj duplicate -d`
+    ///
+    /// `commit_ids` are given newest first; the oldest is duplicated onto
+    /// `destination` and the rest onto each other's duplicates. Returns a map
+    /// from each original commit ID to its duplicate. Nothing is changed if
+    /// any duplicate would have conflicts.
+    pub fn duplicate_onto(
+        &mut self,
+        commit_ids: &[String],
+        destination: &str,
+    ) -> Result<HashMap<String, String>> {
+        let repo = self.repo()?;
+
+        let parse = |hex: &str| {
+            CommitId::try_from_hex(hex)
+                .ok_or_else(|| Error::Workspace(format!("invalid commit id '{hex}'")))
+        };
+        let destination_id = parse(destination)?;
+        let target_ids = commit_ids
+            .iter()
+            .map(|id| parse(id))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut tx = repo.start_transaction();
+        let stats = futures::executor::block_on(duplicate_commits(
+            tx.repo_mut(),
+            &target_ids,
+            &HashMap::new(),
+            &[destination_id],
+            &[],
+        ))
+        .map_err(|e| Error::Workspace(format!("Failed to duplicate commits: {e}")))?;
+
+        if let Some((original, _)) = stats
+            .duplicated_commits
+            .iter()
+            .find(|(_, duplicate)| duplicate.has_conflict())
+        {
+            return Err(Error::RebaseFailed(format!(
+                "commit {} conflicts with {destination}",
+                &original.hex()[..12]
+            )));
+        }
+
+        let duplicates = stats
+            .duplicated_commits
+            .iter()
+            .map(|(original, duplicate)| (original.hex(), duplicate.id().hex()))
+            .collect();
+
+        tx.commit(format!(
+            "duplicate {} commit(s) onto {destination}",
+            target_ids.len()
+        ))
+        .map_err(|e| Error::Workspace(format!("Failed to commit duplicates: {e}")))?;
+
+        Ok(duplicates)
+    }
+
     /// Delete a local bookmark
     ///
     /// Used after merge to clean up the merged bookmark.
@@ -686,6 +1258,23 @@ impl JjWorkspace {
 
         Ok(())
     }
+
+    /// Create or move a local bookmark to point at a commit
+    pub fn set_bookmark(&mut self, bookmark: &str, commit_id: &str) -> Result<()> {
+        let repo = self.repo()?;
+
+        let id = CommitId::try_from_hex(commit_id)
+            .ok_or_else(|| Error::Workspace(format!("invalid commit id '{commit_id}'")))?;
+
+        let mut tx = repo.start_transaction();
+        tx.repo_mut()
+            .set_local_bookmark_target(RefName::new(bookmark), RefTarget::normal(id));
+
+        tx.commit(format!("set bookmark {bookmark}"))
+            .map_err(|e| Error::Workspace(format!("Failed to commit bookmark update: {e}")))?;
+
+        Ok(())
+    }
 }
 
 /// Select a remote from a list of available remotes
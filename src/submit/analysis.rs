@@ -3,10 +3,11 @@
 //! Identifies what needs to be submitted for a given target bookmark.
 
 use crate::error::{Error, Result};
-use crate::types::{Bookmark, BookmarkSegment, ChangeGraph, NarrowedBookmarkSegment};
+use crate::types::{Bookmark, BookmarkSegment, ChangeGraph, LogEntry, NarrowedBookmarkSegment};
+use serde::{Deserialize, Serialize};
 
 /// Result of submission analysis
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmissionAnalysis {
     /// Target bookmark name
     pub target_bookmark: String,
@@ -129,6 +130,73 @@ fn is_temporary_bookmark(name: &str) -> bool {
         || lower.starts_with("wip/")
 }
 
+/// Drop the segments below the one whose bookmark is `from`
+///
+/// Narrows the analysis to a contiguous part of the stack, leaving the
+/// bookmarks under `from` alone. Returns the bookmark directly below `from`,
+/// which the PR for `from` should be based on, or None if `from` is already
+/// at the bottom.
+pub fn trim_below(analysis: &mut SubmissionAnalysis, from: &str) -> Result<Option<String>> {
+    let index = analysis
+        .segments
+        .iter()
+        .position(|s| s.bookmark.name == from)
+        .ok_or_else(|| {
+            Error::InvalidArgument(format!(
+                "Bookmark '{from}' is not in the submission, which ends at '{}'",
+                analysis.target_bookmark
+            ))
+        })?;
+
+    let base = index
+        .checked_sub(1)
+        .map(|below| analysis.segments[below].bookmark.name.clone());
+    analysis.segments.drain(..index);
+    Ok(base)
+}
+
+/// Find the bookmarks at the bottom and top of the stack `commits` covers
+///
+/// A segment is covered when the commit its bookmarks point at is one of
+/// `commits`, so `feat-b::feat-d` covers feat-b, feat-d and everything in
+/// between. The covered segments must be contiguous.
+pub fn revset_bookmark_range(
+    graph: &ChangeGraph,
+    commits: &[LogEntry],
+) -> Result<(String, String)> {
+    let stack = graph.stack.as_ref().ok_or_else(|| {
+        Error::NoStack("No bookmarks found between trunk and working copy".to_string())
+    })?;
+
+    let covered: Vec<usize> = stack
+        .segments
+        .iter()
+        .enumerate()
+        .filter(|(_, segment)| {
+            segment
+                .bookmarks
+                .first()
+                .is_some_and(|b| commits.iter().any(|c| c.commit_id == b.commit_id))
+        })
+        .map(|(i, _)| i)
+        .collect();
+
+    let (Some(&first), Some(&last)) = (covered.first(), covered.last()) else {
+        return Err(Error::InvalidArgument(
+            "Revisions don't include any bookmark in the stack".to_string(),
+        ));
+    };
+    if last - first + 1 != covered.len() {
+        return Err(Error::InvalidArgument(
+            "Revisions must cover a contiguous part of the stack".to_string(),
+        ));
+    }
+
+    let from = select_bookmark_for_segment(&stack.segments[first], None).name;
+    let to = select_bookmark_for_segment(&stack.segments[last], None).name;
+    Ok((from, to))
+}
+
 /// Get the expected base branch for a bookmark in a submission
 ///
 /// Returns the bookmark name that this bookmark should be based on,
@@ -170,8 +238,9 @@ pub fn generate_pr_title(
 /// - **Title**: Uses the oldest (root) commit's first line, since that
 ///   typically represents the primary intent of the change. Falls back to
 ///   bookmark name if no description is available.
-/// - **Body**: Concatenates the body portions of all commits in root-to-tip
-///   order, separated by blank lines. Returns `None` if no commits have bodies.
+/// - **Body**: The commit's body for a single commit (`None` if it has none).
+///   With several commits, a bullet per commit in root-to-tip order, each
+///   with its first line and, indented below it, its body.
 pub fn generate_pr_content(
     bookmark_name: &str,
     segments: &[NarrowedBookmarkSegment],
@@ -197,22 +266,153 @@ pub fn generate_pr_content(
         root_commit.description_first_line.clone()
     };
 
-    // Collect bodies from all commits in root-to-tip order
+    let body = if segment.changes.len() == 1 {
+        extract_body(&root_commit.description).map(str::to_string)
+    } else {
+        Some(commit_summary(&segment.changes))
+    };
+
+    Ok((title, body))
+}
+
+/// Bulleted summary of a segment's commits, root first
+///
+/// Each commit's body is indented under its bullet, so it renders as part
+/// of the list item.
+fn commit_summary(changes: &[LogEntry]) -> String {
     // changes is newest-first, so reverse for root-to-tip
-    let bodies: Vec<&str> = segment
-        .changes
+    let items: Vec<String> = changes
         .iter()
         .rev()
-        .filter_map(|c| extract_body(&c.description))
+        .map(|change| {
+            let title = format!("- {}", change_title(change));
+            let Some(body) = extract_body(&change.description) else {
+                return title;
+            };
+            let indented: Vec<String> = body
+                .lines()
+                .map(|line| {
+                    if line.trim().is_empty() {
+                        String::new()
+                    } else {
+                        format!("  {line}")
+                    }
+                })
+                .collect();
+            format!("{title}\n\n{}", indented.join("\n"))
+        })
         .collect();
 
-    let body = if bodies.is_empty() {
-        None
+    let loose = changes
+        .iter()
+        .any(|c| extract_body(&c.description).is_some());
+    items.join(if loose { "\n\n" } else { "\n" })
+}
+
+/// First line of a change's description, or a placeholder if it has none
+fn change_title(change: &LogEntry) -> &str {
+    if change.description_first_line.is_empty() {
+        "(no description)"
     } else {
-        Some(bodies.join("\n\n"))
+        &change.description_first_line
+    }
+}
+
+/// Conventional-commit prefix of a title, as in `feat(api)!: add login`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConventionalPrefix<'a> {
+    /// Type, e.g. `feat` or `fix`
+    pub kind: &'a str,
+    /// Scope in parentheses, e.g. `api`
+    pub scope: Option<&'a str>,
+    /// Whether `!` marks the change as breaking
+    pub breaking: bool,
+}
+
+/// Split a conventional-commit prefix off a title
+///
+/// Returns the prefix and the rest of the title, or `None` if the title
+/// doesn't start with `type:`, `type(scope):` or either followed by `!`,
+/// then a space and some text. Only lowercase types count, so titles like
+/// `Note: ...` are left alone.
+pub fn parse_conventional_title(title: &str) -> Option<(ConventionalPrefix<'_>, &str)> {
+    let (head, rest) = title.split_once(':')?;
+    if !rest.starts_with(char::is_whitespace) || rest.trim().is_empty() {
+        return None;
+    }
+
+    let (head, breaking) = head
+        .strip_suffix('!')
+        .map_or((head, false), |head| (head, true));
+    let (kind, scope) = match head.split_once('(') {
+        Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?)),
+        None => (head, None),
     };
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_lowercase()) {
+        return None;
+    }
+    if scope.is_some_and(|scope| scope.trim().is_empty() || scope.contains(['(', ')'])) {
+        return None;
+    }
 
-    Ok((title, body))
+    Some((
+        ConventionalPrefix {
+            kind,
+            scope,
+            breaking,
+        },
+        rest.trim(),
+    ))
+}
+
+/// Collapse a submission into a single PR for the whole stack
+///
+/// The target bookmark's segment absorbs the changes of every segment below
+/// it, so only that bookmark is pushed and one PR targets the base branch.
+pub fn collapse_to_single_pr(analysis: &mut SubmissionAnalysis) {
+    let Some(mut top) = analysis.segments.pop() else {
+        return;
+    };
+    // Segments are trunk-first and changes newest-first, so append downward
+    for segment in analysis.segments.drain(..).rev() {
+        top.changes.extend(segment.changes);
+    }
+    analysis.target_bookmark.clone_from(&top.bookmark.name);
+    analysis.segments.push(top);
+}
+
+/// Generate the body of a single-PR submission
+///
+/// Lists every change in the segment, root first, followed by the commit
+/// bodies separated by blank lines.
+pub fn generate_changelog_body(segment: &NarrowedBookmarkSegment) -> Option<String> {
+    if segment.changes.is_empty() {
+        return None;
+    }
+
+    let changelog: Vec<String> = segment
+        .changes
+        .iter()
+        .rev()
+        .map(|change| {
+            let short_id = change.commit_id.get(..8).unwrap_or(&change.commit_id);
+            format!("- {} (`{short_id}`)", change_title(change))
+        })
+        .collect();
+    let mut body = format!("## Changes\n\n{}", changelog.join("\n"));
+
+    let bodies: Vec<&str> = segment
+        .changes
+        .iter()
+        .rev()
+        .filter_map(|c| extract_body(&c.description))
+        .collect();
+    if !bodies.is_empty() {
+        body.push_str("\n\n");
+        body.push_str(&bodies.join("\n\n"));
+    }
+
+    Some(body)
 }
 
 /// Extract the body portion from a commit description.
@@ -257,7 +457,7 @@ pub fn create_narrowed_segments(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{BookmarkSegment, BranchStack, LogEntry};
+    use crate::types::{BookmarkSegment, BranchStack};
     use chrono::Utc;
 
     fn make_bookmark(name: &str) -> Bookmark {
@@ -633,6 +833,34 @@ mod tests {
         assert_eq!(body, None);
     }
 
+    #[test]
+    fn test_parse_conventional_title() {
+        let (prefix, rest) = parse_conventional_title("feat(api)!: add login").unwrap();
+        assert_eq!(prefix.kind, "feat");
+        assert_eq!(prefix.scope, Some("api"));
+        assert!(prefix.breaking);
+        assert_eq!(rest, "add login");
+
+        let (prefix, rest) = parse_conventional_title("fix: handle empty stacks").unwrap();
+        assert_eq!(
+            (prefix.kind, prefix.scope, prefix.breaking),
+            ("fix", None, false)
+        );
+        assert_eq!(rest, "handle empty stacks");
+
+        for title in [
+            "Add login",
+            "Note: this is a title",
+            "feat:no space",
+            "feat: ",
+            "feat(): empty scope",
+            "feat(api: unclosed",
+            "fix bug: in parser",
+        ] {
+            assert_eq!(parse_conventional_title(title), None, "{title}");
+        }
+    }
+
     // === generate_pr_content tests ===
 
     #[test]
@@ -667,7 +895,7 @@ mod tests {
     }
 
     #[test]
-    fn test_generate_pr_content_multiple_commits_concatenates_bodies() {
+    fn test_generate_pr_content_multiple_commits_lists_commits() {
         // changes[0] is newest, changes[last] is oldest (root)
         let segments = vec![NarrowedBookmarkSegment {
             bookmark: make_bookmark("feat-a"),
@@ -681,28 +909,34 @@ mod tests {
         let (title, body) = generate_pr_content("feat-a", &segments).unwrap();
         // Title comes from root commit
         assert_eq!(title, "Implement feature");
-        // Body concatenates all bodies in root-to-tip order
+        // Body lists every commit in root-to-tip order, with its body
         assert_eq!(
-            body,
-            Some("Initial implementation.\n\nAdded unit tests.\n\nFixed a typo in docs.".to_string())
+            body.unwrap(),
+            "- Implement feature\n\n  Initial implementation.\n\n\
+             - Add tests\n\n  Added unit tests.\n\n\
+             - Fix typo\n\n  Fixed a typo in docs."
         );
     }
 
     #[test]
-    fn test_generate_pr_content_skips_commits_without_body() {
+    fn test_generate_pr_content_indents_multi_paragraph_bodies() {
         let segments = vec![NarrowedBookmarkSegment {
             bookmark: make_bookmark("feat-a"),
             changes: vec![
-                make_log_entry("Fix typo", &["feat-a"]),                                    // no body
-                make_log_entry_with_body("Add tests", "Added unit tests.", &[]),           // has body
-                make_log_entry("Implement feature", &[]),                                  // no body
+                make_log_entry("Fix typo", &["feat-a"]), // no body
+                make_log_entry_with_body("Add tests", "Unit tests.\n\nAnd a fixture.", &[]),
+                make_log_entry("Implement feature", &[]), // no body
             ],
         }];
 
         let (title, body) = generate_pr_content("feat-a", &segments).unwrap();
         assert_eq!(title, "Implement feature");
-        // Only the commit with a body is included
-        assert_eq!(body, Some("Added unit tests.".to_string()));
+        assert_eq!(
+            body.unwrap(),
+            "- Implement feature\n\n\
+             - Add tests\n\n  Unit tests.\n\n  And a fixture.\n\n\
+             - Fix typo"
+        );
     }
 
     #[test]
@@ -718,7 +952,10 @@ mod tests {
 
         let (title, body) = generate_pr_content("feat-a", &segments).unwrap();
         assert_eq!(title, "Implement feature");
-        assert_eq!(body, None);
+        assert_eq!(
+            body.unwrap(),
+            "- Implement feature\n- Add tests\n- Fix typo"
+        );
     }
 
     #[test]
@@ -732,4 +969,111 @@ mod tests {
         assert_eq!(title, "feat-a");
         assert_eq!(body, None);
     }
+
+    #[test]
+    fn test_collapse_to_single_pr_merges_stack_into_top_segment() {
+        let mut analysis = SubmissionAnalysis {
+            target_bookmark: "feat-b".to_string(),
+            segments: vec![
+                NarrowedBookmarkSegment {
+                    bookmark: make_bookmark("feat-a"),
+                    changes: vec![
+                        make_log_entry("Add tests", &["feat-a"]),
+                        make_log_entry("Add auth", &[]),
+                    ],
+                },
+                NarrowedBookmarkSegment {
+                    bookmark: make_bookmark("feat-b"),
+                    changes: vec![make_log_entry_with_body(
+                        "Add sessions",
+                        "Sessions expire after a day.",
+                        &["feat-b"],
+                    )],
+                },
+            ],
+        };
+
+        collapse_to_single_pr(&mut analysis);
+
+        assert_eq!(analysis.target_bookmark, "feat-b");
+        assert_eq!(analysis.segments.len(), 1);
+        let segment = &analysis.segments[0];
+        assert_eq!(segment.bookmark.name, "feat-b");
+        let titles: Vec<_> = segment
+            .changes
+            .iter()
+            .map(|c| c.description_first_line.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Add sessions", "Add tests", "Add auth"]);
+
+        assert_eq!(
+            generate_changelog_body(segment).unwrap(),
+            "## Changes\n\
+             \n- Add auth (`Add auth`)\
+             \n- Add tests (`Add test`)\
+             \n- Add sessions (`Add sess`)\
+             \n\nSessions expire after a day."
+        );
+    }
+
+    fn make_three_bookmark_graph() -> ChangeGraph {
+        let names = ["feat-a", "feat-b", "feat-c"];
+        let segments = names
+            .iter()
+            .map(|name| BookmarkSegment {
+                bookmarks: vec![make_bookmark(name)],
+                changes: vec![make_log_entry(name, &[name])],
+            })
+            .collect();
+        ChangeGraph {
+            bookmarks: names
+                .iter()
+                .map(|name| ((*name).to_string(), make_bookmark(name)))
+                .collect(),
+            stack: Some(BranchStack { segments }),
+            excluded_bookmark_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_trim_below_returns_base_for_new_bottom() {
+        let graph = make_three_bookmark_graph();
+        let mut analysis = analyze_submission(&graph, None).unwrap();
+
+        let base = trim_below(&mut analysis, "feat-b").unwrap();
+        assert_eq!(base.as_deref(), Some("feat-a"));
+        let names: Vec<_> = analysis
+            .segments
+            .iter()
+            .map(|s| s.bookmark.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["feat-b", "feat-c"]);
+
+        assert_eq!(trim_below(&mut analysis, "feat-b").unwrap(), None);
+        assert!(matches!(
+            trim_below(&mut analysis, "feat-a"),
+            Err(Error::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_revset_bookmark_range_requires_contiguous_bookmarks() {
+        let graph = make_three_bookmark_graph();
+        let commit = |name: &str| LogEntry {
+            commit_id: format!("{name}_commit"),
+            ..make_log_entry(name, &[])
+        };
+
+        let range = revset_bookmark_range(&graph, &[commit("feat-c"), commit("feat-b")]).unwrap();
+        assert_eq!(range, ("feat-b".to_string(), "feat-c".to_string()));
+
+        assert!(matches!(
+            revset_bookmark_range(&graph, &[commit("feat-a"), commit("feat-c")]),
+            Err(Error::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            revset_bookmark_range(&graph, &[commit("other")]),
+            Err(Error::InvalidArgument(_))
+        ));
+    }
 }
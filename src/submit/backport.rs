@@ -0,0 +1,204 @@
+//! Backporting a stack to another branch
+//!
+//! A backport duplicates a stack's commits onto the target branch (like
+//! `jj duplicate -d <target>@<remote>`), puts `backport/<target>/<bookmark>`
+//! bookmarks on the duplicates, and submits them as a mirrored stack whose
+//! PRs link back to the originals.
+
+use crate::error::{Error, Result};
+use crate::repo::JjWorkspace;
+use crate::submit::plan::{ExecutionStep, PrToReopen, SubmissionPlan};
+use crate::types::{NarrowedBookmarkSegment, PullRequest};
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+/// Prefix of bookmarks created for backports
+pub const BACKPORT_PREFIX: &str = "backport/";
+
+/// Name of the bookmark backporting `bookmark` to `target`
+pub fn backport_bookmark_name(target: &str, bookmark: &str) -> String {
+    format!("{BACKPORT_PREFIX}{target}/{bookmark}")
+}
+
+/// Duplicate a stack onto `target@remote` and bookmark the duplicates
+///
+/// `segments` are the stack to backport, trunk first. Returns the mirrored
+/// segments in the same order, ready for [`super::create_submission_plan`]
+/// with `target` as the base branch. Fails without changing anything if the
+/// target branch is missing, a backport bookmark already exists, or a commit
+/// doesn't apply cleanly.
+pub fn create_backport_stack(
+    workspace: &mut JjWorkspace,
+    segments: &[NarrowedBookmarkSegment],
+    target: &str,
+    remote: &str,
+) -> Result<Vec<NarrowedBookmarkSegment>> {
+    let destination = workspace
+        .get_remote_bookmark(target, remote)?
+        .ok_or_else(|| Error::BookmarkNotFound(format!("{target}@{remote}")))?;
+
+    let names: Vec<String> = segments
+        .iter()
+        .map(|s| backport_bookmark_name(target, &s.bookmark.name))
+        .collect();
+    for name in &names {
+        if workspace.get_local_bookmark(name)?.is_some() {
+            return Err(Error::InvalidArgument(format!(
+                "bookmark '{name}' already exists; delete it to backport again"
+            )));
+        }
+    }
+
+    // Newest first, as jj-lib expects
+    let commit_ids: Vec<String> = segments
+        .iter()
+        .rev()
+        .flat_map(|s| s.changes.iter().map(|c| c.commit_id.clone()))
+        .collect();
+    let duplicates = workspace.duplicate_onto(&commit_ids, &destination.commit_id)?;
+
+    for (segment, name) in segments.iter().zip(&names) {
+        let head = duplicates.get(&segment.bookmark.commit_id).ok_or_else(|| {
+            Error::Internal(format!("{} was not duplicated", segment.bookmark.name))
+        })?;
+        workspace.set_bookmark(name, head)?;
+    }
+
+    let mut backported = Vec::with_capacity(segments.len());
+    let mut parent = destination.commit_id;
+    for name in names {
+        let bookmark = workspace
+            .get_local_bookmark(&name)?
+            .ok_or_else(|| Error::BookmarkNotFound(name.clone()))?;
+        let changes = workspace.resolve_revset(&format!("{parent:?}..{name:?}"))?;
+        parent = name;
+        backported.push(NarrowedBookmarkSegment { bookmark, changes });
+    }
+
+    Ok(backported)
+}
+
+/// Mark the PRs a backport plan creates as backports
+///
+/// Titles get a `[<target>]` prefix, and bodies open with a link to the
+/// original PR. `originals` maps backport bookmark names to the PRs they
+/// were backported from.
+pub fn link_backport_prs<S: BuildHasher>(
+    plan: &mut SubmissionPlan,
+    target: &str,
+    originals: &HashMap<String, PullRequest, S>,
+) {
+    for step in &mut plan.execution_steps {
+        let (ExecutionStep::CreatePr(create) | ExecutionStep::ReopenPr(PrToReopen { create, .. })) =
+            step
+        else {
+            continue;
+        };
+
+        create.title = format!("[{target}] {}", create.title);
+        let note = originals.get(&create.bookmark.name).map_or_else(
+            || format!("Backport to `{target}`."),
+            |pr| format!("Backport of #{} to `{target}`.", pr.number),
+        );
+        create.body = Some(match create.body.take() {
+            Some(body) => format!("{note}\n\n{body}"),
+            None => note,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Bookmark, StackCommentMode};
+
+    fn bookmark(name: &str) -> Bookmark {
+        Bookmark {
+            name: name.to_string(),
+            commit_id: format!("{name}_commit"),
+            change_id: format!("{name}_change"),
+            has_remote: false,
+            is_synced: false,
+        }
+    }
+
+    fn create_step(name: &str, body: Option<&str>) -> ExecutionStep {
+        ExecutionStep::CreatePr(crate::submit::PrToCreate {
+            bookmark: bookmark(name),
+            base_branch: "release/2.3".to_string(),
+            title: "Add auth".to_string(),
+            body: body.map(String::from),
+            draft: false,
+        })
+    }
+
+    #[test]
+    fn test_backport_bookmark_name() {
+        assert_eq!(
+            backport_bookmark_name("release/2.3", "feat-a"),
+            "backport/release/2.3/feat-a"
+        );
+    }
+
+    #[test]
+    fn test_link_backport_prs_prefixes_title_and_links_original() {
+        let linked = backport_bookmark_name("release/2.3", "feat-a");
+        let unlinked = backport_bookmark_name("release/2.3", "feat-b");
+        let mut plan = SubmissionPlan {
+            segments: vec![],
+            constraints: vec![],
+            execution_steps: vec![
+                ExecutionStep::Push(bookmark(&linked)),
+                create_step(&linked, Some("Adds login.")),
+                create_step(&unlinked, None),
+            ],
+            existing_prs: HashMap::new(),
+            remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
+            default_branch: "release/2.3".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
+        };
+        let originals = HashMap::from([(
+            linked,
+            PullRequest {
+                number: 12,
+                html_url: "https://github.com/acme/widgets/pull/12".to_string(),
+                base_ref: "main".to_string(),
+                head_ref: "feat-a".to_string(),
+                title: "Add auth".to_string(),
+                node_id: None,
+                is_draft: false,
+            },
+        )]);
+
+        link_backport_prs(&mut plan, "release/2.3", &originals);
+
+        let creates: Vec<_> = plan
+            .execution_steps
+            .iter()
+            .filter_map(|step| match step {
+                ExecutionStep::CreatePr(create) => {
+                    Some((create.title.as_str(), create.body.as_deref()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            creates,
+            vec![
+                (
+                    "[release/2.3] Add auth",
+                    Some("Backport of #12 to `release/2.3`.\n\nAdds login.")
+                ),
+                ("[release/2.3] Add auth", Some("Backport to `release/2.3`.")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,310 @@
+//! Email patch-series submission
+//!
+//! For mailing-list projects without a code review platform: the stack is
+//! exported with `git format-patch` as one series (a patch per commit) and
+//! sent with `git send-email`. The cover letter lays out the stack the way
+//! the stack comment does on PRs, naming which patches belong to which
+//! bookmark.
+
+use crate::error::{Error, Result};
+use crate::submit::SubmissionAnalysis;
+use crate::submit::analysis::generate_pr_content;
+use crate::tracking::resolve_repo_path;
+use crate::types::NarrowedBookmarkSegment;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+use tracing::debug;
+
+/// Placeholder `git format-patch` puts in the cover letter's subject
+const COVER_SUBJECT_PLACEHOLDER: &str = "*** SUBJECT HERE ***";
+
+/// Placeholder `git format-patch` puts in the cover letter's body
+const COVER_BLURB_PLACEHOLDER: &str = "*** BLURB HERE ***";
+
+/// A stack exported as a patch series
+#[derive(Debug, Clone)]
+pub struct PatchSeries {
+    /// Commit the series applies on (parent of the oldest commit)
+    pub base_commit: String,
+    /// Newest commit of the series
+    pub tip_commit: String,
+    /// Branch the series targets, named in the cover letter
+    pub base_branch: String,
+    /// Number of patches (one per commit)
+    pub patch_count: usize,
+    /// Cover letter subject
+    pub subject: String,
+    /// Cover letter body
+    pub cover_letter: String,
+}
+
+impl PatchSeries {
+    /// Whether the series gets a cover letter (single patches don't)
+    pub const fn has_cover_letter(&self) -> bool {
+        self.patch_count > 1
+    }
+}
+
+/// Options for formatting a patch series
+#[derive(Debug, Clone, Default)]
+pub struct FormatPatchOptions {
+    /// Reroll count, producing `[PATCH v2 1/3]` subjects
+    pub version: Option<u32>,
+    /// Subject prefix instead of `PATCH` (e.g. `RFC PATCH`)
+    pub subject_prefix: Option<String>,
+}
+
+/// Describe the analyzed stack as a patch series on top of `base_branch`
+pub fn create_patch_series(analysis: &SubmissionAnalysis, base_branch: &str) -> Result<PatchSeries> {
+    let segments = &analysis.segments;
+    let (Some(bottom), Some(top)) = (segments.first(), segments.last()) else {
+        return Err(Error::NoStack("Nothing to send".to_string()));
+    };
+    let oldest = bottom
+        .changes
+        .last()
+        .ok_or_else(|| Error::NoStack(format!("'{}' has no commits", bottom.bookmark.name)))?;
+    let base_commit = oldest.parents.first().cloned().ok_or_else(|| {
+        Error::InvalidArgument(format!(
+            "Commit {} has no parent to base the series on",
+            oldest.commit_id
+        ))
+    })?;
+
+    let subject = if segments.len() == 1 {
+        bottom
+            .changes
+            .last()
+            .map(|c| c.description_first_line.clone())
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| bottom.bookmark.name.clone())
+    } else {
+        segments
+            .iter()
+            .map(|s| s.bookmark.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    Ok(PatchSeries {
+        base_commit,
+        tip_commit: top.bookmark.commit_id.clone(),
+        base_branch: base_branch.to_string(),
+        patch_count: segments.iter().map(|s| s.changes.len()).sum(),
+        subject,
+        cover_letter: format_cover_letter(segments, base_branch),
+    })
+}
+
+/// Cover letter body: the stack's bookmarks with their patch numbers
+///
+/// Lists the stack root first, since that's the order the patches apply
+/// in, followed by each bookmark's commit bodies (as in its PR body). The
+/// shortlog and diffstat are added by `git format-patch`.
+pub fn format_cover_letter(segments: &[NarrowedBookmarkSegment], base_branch: &str) -> String {
+    let mut body = format!("This series applies on top of {base_branch}:\n\n");
+
+    let mut next = 1;
+    for segment in segments {
+        let count = segment.changes.len();
+        let range = match count {
+            0 => String::new(),
+            1 => format!(" (patch {next})"),
+            _ => format!(" (patches {next}-{})", next + count - 1),
+        };
+        let _ = writeln!(body, "* {}{range}", segment.bookmark.name);
+        next += count;
+    }
+
+    for segment in segments {
+        if let Ok((_, Some(details))) = generate_pr_content(&segment.bookmark.name, segments) {
+            let _ = write!(body, "\n{}:\n\n{}\n", segment.bookmark.name, details.trim_end());
+        }
+    }
+
+    let _ = write!(
+        body,
+        "\n--\nThis stack is managed by jj-ryu (https://github.com/dmmulroy/jj-ryu)."
+    );
+    body
+}
+
+/// Directory `ryu send-email` writes patch series to by default
+pub fn patches_dir(workspace_root: &Path) -> PathBuf {
+    resolve_repo_path(workspace_root).join("ryu").join("patches")
+}
+
+/// Write the series to `output_dir` with `git format-patch`
+///
+/// Fills in the cover letter's subject and body. Returns the patch files in
+/// sending order (cover letter first).
+pub async fn format_patches(
+    git_dir: &Path,
+    series: &PatchSeries,
+    output_dir: &Path,
+    options: &FormatPatchOptions,
+) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(output_dir)?;
+
+    let mut cmd = Command::new("git");
+    cmd.arg("--git-dir")
+        .arg(git_dir)
+        .args(["format-patch", "--thread=shallow", "-o"])
+        .arg(output_dir);
+    if series.has_cover_letter() {
+        cmd.arg("--cover-letter");
+    }
+    if let Some(version) = options.version {
+        cmd.arg(format!("--reroll-count={version}"));
+    }
+    if let Some(prefix) = &options.subject_prefix {
+        cmd.arg(format!("--subject-prefix={prefix}"));
+    }
+    cmd.arg(format!("{}..{}", series.base_commit, series.tip_commit));
+
+    debug!(?cmd, "running git format-patch");
+    let output = cmd.stderr(Stdio::piped()).output().await?;
+    if !output.status.success() {
+        return Err(Error::Git(format!(
+            "git format-patch failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let files: Vec<PathBuf> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(PathBuf::from)
+        .collect();
+
+    if series.has_cover_letter()
+        && let Some(cover) = files.first()
+    {
+        let letter = fs::read_to_string(cover)?;
+        let letter = letter
+            .replacen(COVER_SUBJECT_PLACEHOLDER, &series.subject, 1)
+            .replacen(COVER_BLURB_PLACEHOLDER, &series.cover_letter, 1);
+        fs::write(cover, letter)?;
+    }
+
+    Ok(files)
+}
+
+/// Options for sending a patch series
+#[derive(Debug, Clone, Default)]
+pub struct SendPatchesOptions {
+    /// Recipients (falls back to git's `sendemail.to`)
+    pub to: Vec<String>,
+    /// Carbon-copy recipients
+    pub cc: Vec<String>,
+    /// Let `git send-email` print the emails instead of sending them
+    pub dry_run: bool,
+}
+
+/// Send patch files with `git send-email`
+///
+/// Runs interactively: `git send-email` asks for confirmation (per its
+/// `sendemail.confirm` setting) and uses the SMTP settings from git config.
+pub async fn send_patches(
+    git_dir: &Path,
+    patches: &[PathBuf],
+    options: &SendPatchesOptions,
+) -> Result<()> {
+    let mut cmd = Command::new("git");
+    cmd.arg("--git-dir").arg(git_dir).arg("send-email");
+    for to in &options.to {
+        cmd.arg(format!("--to={to}"));
+    }
+    for cc in &options.cc {
+        cmd.arg(format!("--cc={cc}"));
+    }
+    if options.dry_run {
+        cmd.arg("--dry-run");
+    }
+    cmd.arg("--").args(patches);
+
+    debug!(?cmd, "running git send-email");
+    let status = cmd.status().await.map_err(|e| {
+        Error::Git(format!(
+            "failed to run git send-email (is it installed?): {e}"
+        ))
+    })?;
+    if !status.success() {
+        return Err(Error::Git(format!("git send-email exited with {status}")));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Bookmark, LogEntry};
+    use chrono::Utc;
+
+    fn segment(name: &str, titles: &[&str]) -> NarrowedBookmarkSegment {
+        let changes = titles
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, title)| LogEntry {
+                commit_id: format!("{name}{i}"),
+                change_id: format!("change{i}"),
+                author_name: "Test".to_string(),
+                author_email: "test@example.com".to_string(),
+                description_first_line: (*title).to_string(),
+                description: format!("{title}\n\nWhy {title}.\n"),
+                parents: vec![format!("{name}-parent{i}")],
+                local_bookmarks: vec![],
+                remote_bookmarks: vec![],
+                is_working_copy: false,
+                authored_at: Utc::now(),
+                committed_at: Utc::now(),
+            })
+            .collect();
+        NarrowedBookmarkSegment {
+            bookmark: Bookmark {
+                name: name.to_string(),
+                commit_id: format!("{name}0"),
+                change_id: "change0".to_string(),
+                has_remote: false,
+                is_synced: false,
+            },
+            changes,
+        }
+    }
+
+    #[test]
+    fn test_cover_letter_numbers_patches_per_bookmark() {
+        let segments = [
+            segment("feat-auth", &["Add login", "Add logout"]),
+            segment("feat-session", &["Add sessions"]),
+        ];
+        let letter = format_cover_letter(&segments, "main");
+
+        assert!(letter.starts_with("This series applies on top of main:\n\n"));
+        assert!(letter.contains("* feat-auth (patches 1-2)\n* feat-session (patch 3)\n"));
+        assert!(letter.contains("feat-auth:\n\n"));
+    }
+
+    #[test]
+    fn test_create_patch_series() {
+        let analysis = SubmissionAnalysis {
+            target_bookmark: "feat-session".to_string(),
+            segments: vec![
+                segment("feat-auth", &["Add login", "Add logout"]),
+                segment("feat-session", &["Add sessions"]),
+            ],
+        };
+        let series = create_patch_series(&analysis, "main").unwrap();
+
+        // The oldest commit is last in the bottom segment
+        assert_eq!(series.base_commit, "feat-auth-parent1");
+        assert_eq!(series.tip_commit, "feat-session0");
+        assert_eq!(series.patch_count, 3);
+        assert_eq!(series.subject, "feat-auth, feat-session");
+        assert!(series.has_cover_letter());
+    }
+}
@@ -3,18 +3,19 @@
 //! Executes the submission plan: push, create PRs, update bases, add comments.
 
 use crate::error::{Error, Result};
-use crate::platform::PlatformService;
+use crate::platform::{PlatformService, STACK_STATUS_CONTEXT, is_approved_by_reviews};
 use crate::repo::JjWorkspace;
-use crate::submit::plan::{PrBaseUpdate, PrToCreate};
+use crate::submit::plan::{PrBaseUpdate, PrMetadataUpdate, PrToCreate, PrToReopen};
 use crate::submit::{ExecutionStep, Phase, ProgressCallback, PushStatus, SubmissionPlan};
-use crate::types::{Bookmark, Platform, PullRequest};
+use crate::types::{Bookmark, CheckState, Platform, PrComment, PullRequest, ReviewState};
 use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::Write;
 
 /// Result of submission execution
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SubmissionResult {
     /// Whether execution succeeded
     pub success: bool,
@@ -24,6 +25,9 @@ pub struct SubmissionResult {
     pub updated_prs: Vec<PullRequest>,
     /// Bookmarks that were pushed
     pub pushed_bookmarks: Vec<String>,
+    /// Bookmarks whose PR title and body were updated from their commits
+    #[serde(default)]
+    pub described_prs: Vec<String>,
     /// Errors encountered (non-fatal)
     pub errors: Vec<String>,
 }
@@ -82,6 +86,35 @@ pub struct StackItem {
     pub pr_number: u64,
     /// PR title
     pub pr_title: String,
+    /// Review status when the comment was written
+    #[serde(default)]
+    pub status: StackItemStatus,
+}
+
+/// Review status of a PR in the stack, shown as badges in the stack comment
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StackItemStatus {
+    /// CI checks ran and all required ones passed
+    pub ci_passed: bool,
+    /// Approved, with no outstanding change requests
+    pub approved: bool,
+    /// PR is a draft
+    pub draft: bool,
+}
+
+impl StackItemStatus {
+    /// Badges for this status, each preceded by a space (empty if none apply)
+    pub fn badges(self) -> String {
+        [
+            (self.ci_passed, STACK_BADGE_CI_PASSED),
+            (self.approved, STACK_BADGE_APPROVED),
+            (self.draft, STACK_BADGE_DRAFT),
+        ]
+        .into_iter()
+        .filter(|(applies, _)| *applies)
+        .flat_map(|(_, badge)| [" ", badge])
+        .collect()
+    }
 }
 
 /// Prefix for stack comment data
@@ -91,6 +124,24 @@ const COMMENT_DATA_PREFIX_OLD: &str = "<!--- JJ-STACK_INFO: ";
 pub const COMMENT_DATA_POSTFIX: &str = " --->";
 /// Marker for the current PR in stack comments
 pub const STACK_COMMENT_THIS_PR: &str = "👈";
+/// Start of the line at the top of a stacked PR's body naming its parent PR
+pub const DEPENDS_ON_PREFIX: &str = "Depends on ";
+/// Start of the stack overview in a PR body (with `submit.stack_in_body`)
+pub const STACK_BODY_START: &str = "<!-- ryu:stack:start -->";
+/// End of the stack overview in a PR body
+pub const STACK_BODY_END: &str = "<!-- ryu:stack:end -->";
+/// Start of the description generated from the commits in a PR body
+pub const DESCRIPTION_BODY_START: &str = "<!-- ryu:description:start -->";
+/// End of the description generated from the commits in a PR body
+pub const DESCRIPTION_BODY_END: &str = "<!-- ryu:description:end -->";
+/// Start of the marker in a PR body naming the jj change the PR was created for
+pub const CHANGE_ID_MARKER_PREFIX: &str = "<!-- ryu:change-id:";
+/// Stack comment badge for a PR whose CI passed
+pub const STACK_BADGE_CI_PASSED: &str = "✅";
+/// Stack comment badge for an approved PR
+pub const STACK_BADGE_APPROVED: &str = "👍";
+/// Stack comment badge for a draft PR
+pub const STACK_BADGE_DRAFT: &str = "🚧";
 
 // =============================================================================
 // Step Execution Functions (testable in isolation)
@@ -104,6 +155,24 @@ pub fn execute_push(workspace: &mut JjWorkspace, bookmark: &Bookmark, remote: &s
     }
 }
 
+/// Push a renamed bookmark's commit to the head branch of its existing PR
+///
+/// A PR's head branch can't be changed, so the PR found for the bookmark
+/// under its old name keeps following the bookmark this way.
+pub fn execute_push_to_head(
+    workspace: &mut JjWorkspace,
+    bookmark: &Bookmark,
+    head: &str,
+    remote: &str,
+) -> StepOutcome {
+    match workspace.restore_remote_bookmark(head, remote, Some(&bookmark.commit_id)) {
+        Ok(()) => StepOutcome::Success(None),
+        Err(e) => {
+            StepOutcome::FatalError(format!("Failed to push {} to {head}: {e}", bookmark.name))
+        }
+    }
+}
+
 /// Execute an update base step
 pub async fn execute_update_base(
     platform: &dyn PlatformService,
@@ -123,19 +192,93 @@ pub async fn execute_update_base(
 
 /// Execute a create PR step
 pub async fn execute_create_pr(platform: &dyn PlatformService, create: &PrToCreate) -> StepOutcome {
+    // The body is the generated description unless a template was added
+    let body = create.body.as_deref().unwrap_or_default();
+    let body = if body.contains(DESCRIPTION_BODY_START) {
+        body.to_string()
+    } else {
+        description_block(body)
+    };
+    let body = with_change_id_marker(&body, &create.bookmark.change_id);
     match platform
         .create_pr_with_options(
             &create.bookmark.name,
             &create.base_branch,
             &create.title,
-            create.body.as_deref(),
+            Some(&body),
             create.draft,
         )
         .await
     {
         Ok(pr) => StepOutcome::Success(Some((create.bookmark.name.clone(), pr))),
+        Err(e) => {
+            // The request may have gone through even though we saw an error (e.g. a
+            // timeout); adopt that PR rather than leaving it orphaned
+            if let Some(pr) = find_created_pr(platform, create).await {
+                tracing::debug!(
+                    pr_number = pr.number,
+                    error = %e,
+                    "create request failed but PR exists"
+                );
+                return StepOutcome::Success(Some((create.bookmark.name.clone(), pr)));
+            }
+            StepOutcome::FatalError(format!(
+                "Failed to create PR for {}: {e}",
+                create.bookmark.name
+            ))
+        }
+    }
+}
+
+/// Find an open PR matching a create step (same head, base and title)
+///
+/// Used after a failed create request to detect a PR the platform created
+/// anyway, so it isn't created a second time.
+pub async fn find_created_pr(
+    platform: &dyn PlatformService,
+    create: &PrToCreate,
+) -> Option<PullRequest> {
+    let pr = platform
+        .find_existing_pr(&create.bookmark.name)
+        .await
+        .ok()
+        .flatten()?;
+
+    (pr.head_ref == create.bookmark.name
+        && pr.base_ref == create.base_branch
+        && pr.title == create.title)
+        .then_some(pr)
+}
+
+/// Execute a reopen PR step
+///
+/// Falls back to creating a new PR if the platform refuses to reopen, e.g.
+/// GitHub rejects reopening once the head branch was recreated with new history.
+pub async fn execute_reopen_pr(platform: &dyn PlatformService, reopen: &PrToReopen) -> StepOutcome {
+    let create = &reopen.create;
+    let pr = match platform.reopen_pr(reopen.pr.number).await {
+        Ok(pr) => pr,
+        Err(e) => {
+            tracing::debug!(
+                pr_number = reopen.pr.number,
+                error = %e,
+                "reopen refused, creating new PR"
+            );
+            return execute_create_pr(platform, create).await;
+        }
+    };
+
+    if pr.base_ref == create.base_branch {
+        return StepOutcome::Success(Some((create.bookmark.name.clone(), pr)));
+    }
+
+    match platform
+        .update_pr_base(pr.number, &create.base_branch)
+        .await
+    {
+        Ok(updated_pr) => StepOutcome::Success(Some((create.bookmark.name.clone(), updated_pr))),
         Err(e) => StepOutcome::FatalError(format!(
-            "Failed to create PR for {}: {e}",
+            "Failed to update PR base for {}: {e}",
             create.bookmark.name
         )),
     }
@@ -149,6 +292,36 @@ pub async fn execute_publish_pr(platform: &dyn PlatformService, pr: &PullRequest
     }
 }
 
+/// Execute an update metadata step (soft fail on error)
+///
+/// Sets the new title, then replaces the description ryu generated in the PR
+/// body with the new one, leaving the rest of the body as it is.
+pub async fn execute_update_metadata(
+    platform: &dyn PlatformService,
+    update: &PrMetadataUpdate,
+) -> StepOutcome {
+    let pr = match platform
+        .update_pr_title(update.pr.number, &update.title)
+        .await
+    {
+        Ok(pr) => pr,
+        Err(e) => {
+            return StepOutcome::SoftError(format!(
+                "Failed to update PR title for {}: {e}",
+                update.bookmark.name
+            ));
+        }
+    };
+
+    match update_description(platform, update.pr.number, update.body.as_deref()).await {
+        Ok(()) => StepOutcome::Success(Some((update.bookmark.name.clone(), pr))),
+        Err(e) => StepOutcome::SoftError(format!(
+            "Failed to update PR body for {}: {e}",
+            update.bookmark.name
+        )),
+    }
+}
+
 // =============================================================================
 // Main Execution Orchestrator
 // =============================================================================
@@ -159,8 +332,15 @@ pub async fn execute_publish_pr(platform: &dyn PlatformService, pr: &PullRequest
 /// 1. Push bookmarks to remote
 /// 2. Update PR bases
 /// 3. Create new PRs
-/// 4. Publish draft PRs
-/// 5. Add/update stack comments
+/// 4. Publish draft PRs and update stale PR titles
+/// 5. Assign new PRs, and set the milestone and labels of every PR
+/// 6. Re-request review on pushed PRs (with `rerequest_reviews`)
+/// 7. Keep each PR body's "Depends on" line pointing at its parent PR
+/// 8. Add/update stack comments (per `stack_comment`)
+/// 9. Post the `ryu/stack` commit status on each PR head (with `stack_status`)
+///
+/// Steps 5 to 9 run concurrently across PRs; their failures are non-fatal.
+#[allow(clippy::too_many_lines)]
 pub async fn execute_submission(
     plan: &SubmissionPlan,
     workspace: &mut JjWorkspace,
@@ -185,13 +365,22 @@ pub async fn execute_submission(
     progress.on_phase(Phase::Executing).await;
 
     for step in &plan.execution_steps {
-        let outcome = execute_step(step, workspace, platform, &plan.remote, progress).await;
+        let outcome = execute_step(step, workspace, platform, plan, progress).await;
 
         match outcome {
             StepOutcome::Success(Some((bookmark, pr))) => {
                 // Track the PR for comment generation
                 match step {
                     ExecutionStep::CreatePr(_) => result.created_prs.push(pr.clone()),
+                    // A refused reopen falls back to creating a new PR
+                    ExecutionStep::ReopenPr(reopen) if reopen.pr.number != pr.number => {
+                        result.created_prs.push(pr.clone());
+                    }
+                    ExecutionStep::ReopenPr(_) => result.updated_prs.push(pr.clone()),
+                    ExecutionStep::UpdateMetadata(update) => {
+                        result.described_prs.push(update.bookmark.name.clone());
+                        result.updated_prs.push(pr.clone());
+                    }
                     ExecutionStep::UpdateBase(_) | ExecutionStep::PublishPr(_) => {
                         result.updated_prs.push(pr.clone());
                     }
@@ -220,21 +409,72 @@ pub async fn execute_submission(
     // Phase: Adding stack comments
     progress.on_phase(Phase::AddingComments).await;
 
+    let mut metadata_errors = assign_created_prs(plan, &result.created_prs, platform).await;
+    metadata_errors.extend(set_milestone(plan, &bookmark_to_pr, platform).await);
+    metadata_errors.extend(add_labels(plan, &bookmark_to_pr, platform).await);
+    metadata_errors.extend(rerequest_reviews(plan, &result.pushed_bookmarks, platform).await);
+    for msg in metadata_errors {
+        progress.on_error(&Error::Platform(msg.clone())).await;
+        result.soft_fail(msg);
+    }
+
     if !bookmark_to_pr.is_empty() {
-        let stack_data = build_stack_comment_data(plan, &bookmark_to_pr);
+        let parents = dependency_parents(plan, &bookmark_to_pr);
+        if plan.stack_status {
+            for msg in post_stack_statuses(plan, platform, &parents).await {
+                progress.on_error(&Error::Platform(msg.clone())).await;
+                result.soft_fail(msg);
+            }
+        }
 
-        for (idx, item) in stack_data.stack.iter().enumerate() {
-            if let Err(e) =
-                create_or_update_stack_comment(platform, &stack_data, idx, item.pr_number).await
-            {
-                let msg = format!(
-                    "Failed to update stack comment for {}: {e}",
-                    item.bookmark_name
-                );
+        let outcomes: Vec<_> = stream::iter(parents)
+            .map(|(bookmark, pr_number, parent)| async move {
+                let outcome = update_dependency_line(platform, pr_number, parent).await;
+                (bookmark, outcome)
+            })
+            .buffered(platform.max_concurrency())
+            .collect()
+            .await;
+
+        for (bookmark, outcome) in outcomes {
+            if let Err(e) = outcome {
+                let msg = format!("Failed to update dependency line for {bookmark}: {e}");
                 progress.on_error(&Error::Platform(msg.clone())).await;
                 result.soft_fail(msg);
             }
         }
+
+        let mut stack_data = build_stack_comment_data(plan, &bookmark_to_pr);
+        if plan.stack_comment.applies_to(stack_data.stack.len()) {
+            fetch_stack_status(platform, &mut stack_data).await;
+
+            // Each PR's comment is independent, so run them concurrently.
+            // Failures are collected in stack order and reported as soft errors.
+            let outcomes: Vec<_> = stream::iter(stack_data.stack.iter().enumerate())
+                .map(|(idx, item)| {
+                    let stack_data = &stack_data;
+                    async move {
+                        let outcome =
+                            update_stack_overview(plan, platform, stack_data, idx, item.pr_number)
+                                .await;
+                        (item, outcome)
+                    }
+                })
+                .buffered(platform.max_concurrency())
+                .collect()
+                .await;
+
+            for (item, outcome) in outcomes {
+                if let Err(e) = outcome {
+                    let msg = format!(
+                        "Failed to update stack comment for {}: {e}",
+                        item.bookmark_name
+                    );
+                    progress.on_error(&Error::Platform(msg.clone())).await;
+                    result.soft_fail(msg);
+                }
+            }
+        }
     }
 
     progress.on_phase(Phase::Complete).await;
@@ -242,12 +482,162 @@ pub async fn execute_submission(
     Ok(result)
 }
 
+/// Assign the plan's assignees to newly created PRs, returning failures
+async fn assign_created_prs(
+    plan: &SubmissionPlan,
+    created_prs: &[PullRequest],
+    platform: &dyn PlatformService,
+) -> Vec<String> {
+    if plan.assignees.is_empty() {
+        return Vec::new();
+    }
+
+    stream::iter(created_prs)
+        .map(|pr| async move {
+            platform
+                .assign_pr(pr.number, &plan.assignees)
+                .await
+                .err()
+                .map(|e| format!("Failed to assign {}: {e}", pr.head_ref))
+        })
+        .buffered(platform.max_concurrency())
+        .filter_map(|msg| async move { msg })
+        .collect()
+        .await
+}
+
+/// Put every PR in the stack in the plan's milestone, returning failures
+async fn set_milestone(
+    plan: &SubmissionPlan,
+    bookmark_to_pr: &HashMap<String, PullRequest>,
+    platform: &dyn PlatformService,
+) -> Vec<String> {
+    let Some(milestone) = &plan.milestone else {
+        return Vec::new();
+    };
+
+    let prs = plan
+        .segments
+        .iter()
+        .filter_map(|segment| bookmark_to_pr.get(&segment.bookmark.name));
+    stream::iter(prs)
+        .map(|pr| async move {
+            platform
+                .set_pr_milestone(pr.number, milestone)
+                .await
+                .err()
+                .map(|e| format!("Failed to set milestone of {}: {e}", pr.head_ref))
+        })
+        .buffered(platform.max_concurrency())
+        .filter_map(|msg| async move { msg })
+        .collect()
+        .await
+}
+
+/// Add the plan's labels to every PR in the stack, returning failures
+///
+/// Existing PRs are labeled too, so PRs created before the labels were
+/// configured pick them up. Each PR also gets its own labels from
+/// `pr_labels`.
+async fn add_labels(
+    plan: &SubmissionPlan,
+    bookmark_to_pr: &HashMap<String, PullRequest>,
+    platform: &dyn PlatformService,
+) -> Vec<String> {
+    if plan.labels.is_empty() && plan.pr_labels.is_empty() {
+        return Vec::new();
+    }
+
+    let prs = plan.segments.iter().filter_map(|segment| {
+        let name = &segment.bookmark.name;
+        let pr = bookmark_to_pr.get(name)?;
+        let mut labels = plan.labels.clone();
+        for label in plan.pr_labels.get(name).into_iter().flatten() {
+            if !labels.contains(label) {
+                labels.push(label.clone());
+            }
+        }
+        (!labels.is_empty()).then_some((pr, labels))
+    });
+    stream::iter(prs)
+        .map(|(pr, labels)| async move {
+            platform
+                .add_pr_labels(pr.number, &labels)
+                .await
+                .err()
+                .map(|e| format!("Failed to add labels to {}: {e}", pr.head_ref))
+        })
+        .buffered(platform.max_concurrency())
+        .filter_map(|msg| async move { msg })
+        .collect()
+        .await
+}
+
+/// Re-request review on the existing PRs whose bookmarks were pushed,
+/// returning failures
+///
+/// Pushing new commits doesn't notify anyone who already reviewed, so each
+/// of them is asked again. The current user is left out, since platforms
+/// refuse review requests from a PR's author.
+async fn rerequest_reviews(
+    plan: &SubmissionPlan,
+    pushed_bookmarks: &[String],
+    platform: &dyn PlatformService,
+) -> Vec<String> {
+    if !plan.rerequest_reviews {
+        return Vec::new();
+    }
+    let prs: Vec<&PullRequest> = pushed_bookmarks
+        .iter()
+        .filter_map(|bookmark| plan.existing_prs.get(bookmark))
+        .collect();
+    if prs.is_empty() {
+        return Vec::new();
+    }
+
+    let me = platform.current_user().await.ok();
+    let me = me.as_deref();
+    stream::iter(prs)
+        .map(|pr| async move {
+            rerequest_review(platform, pr.number, me)
+                .await
+                .err()
+                .map(|e| format!("Failed to re-request review on {}: {e}", pr.head_ref))
+        })
+        .buffered(platform.max_concurrency())
+        .filter_map(|msg| async move { msg })
+        .collect()
+        .await
+}
+
+/// Ask everyone who reviewed a PR, other than `me`, to review it again
+async fn rerequest_review(
+    platform: &dyn PlatformService,
+    pr_number: u64,
+    me: Option<&str>,
+) -> Result<()> {
+    let mut reviewers: Vec<String> = Vec::new();
+    for review in platform.list_reviews(pr_number).await? {
+        if review.state != ReviewState::Pending
+            && me != Some(review.reviewer.as_str())
+            && !reviewers.contains(&review.reviewer)
+        {
+            reviewers.push(review.reviewer);
+        }
+    }
+
+    if reviewers.is_empty() {
+        return Ok(());
+    }
+    platform.request_review(pr_number, &reviewers).await
+}
+
 /// Execute a single step with progress reporting
 async fn execute_step(
     step: &ExecutionStep,
     workspace: &mut JjWorkspace,
     platform: &dyn PlatformService,
-    remote: &str,
+    plan: &SubmissionPlan,
     progress: &dyn ProgressCallback,
 ) -> StepOutcome {
     match step {
@@ -256,7 +646,22 @@ async fn execute_step(
                 .on_bookmark_push(&bookmark.name, PushStatus::Started)
                 .await;
 
-            let outcome = execute_push(workspace, bookmark, remote);
+            let remote = plan.remote_for(&bookmark.name);
+            let mut outcome = execute_push(workspace, bookmark, remote);
+
+            // The PR of a renamed bookmark still has the old name as its head
+            if matches!(outcome, StepOutcome::Success(_))
+                && let Some(pr) = plan.existing_prs.get(&bookmark.name)
+                && pr.head_ref != bookmark.name
+            {
+                progress
+                    .on_message(&format!(
+                        "Pushing {} to {}, the head of PR #{}",
+                        bookmark.name, pr.head_ref, pr.number
+                    ))
+                    .await;
+                outcome = execute_push_to_head(workspace, bookmark, &pr.head_ref, remote);
+            }
 
             match &outcome {
                 StepOutcome::Success(_) => {
@@ -309,6 +714,27 @@ async fn execute_step(
             outcome
         }
 
+        ExecutionStep::ReopenPr(reopen) => {
+            progress
+                .on_message(&format!(
+                    "Reopening PR #{} for {} (base: {})",
+                    reopen.pr.number, reopen.create.bookmark.name, reopen.create.base_branch
+                ))
+                .await;
+
+            let outcome = execute_reopen_pr(platform, reopen).await;
+
+            if let StepOutcome::Success(Some((bookmark, pr))) = &outcome {
+                if pr.number == reopen.pr.number {
+                    progress.on_pr_updated(bookmark, pr).await;
+                } else {
+                    progress.on_pr_created(bookmark, pr).await;
+                }
+            }
+
+            outcome
+        }
+
         ExecutionStep::PublishPr(pr) => {
             progress
                 .on_message(&format!("Publishing PR #{} ({})", pr.number, pr.head_ref))
@@ -316,6 +742,23 @@ async fn execute_step(
 
             execute_publish_pr(platform, pr).await
         }
+
+        ExecutionStep::UpdateMetadata(update) => {
+            progress
+                .on_message(&format!(
+                    "Updating PR #{} title: {}",
+                    update.pr.number, update.title
+                ))
+                .await;
+
+            let outcome = execute_update_metadata(platform, update).await;
+
+            if let StepOutcome::Success(Some((bookmark, pr))) = &outcome {
+                progress.on_pr_updated(bookmark, pr).await;
+            }
+
+            outcome
+        }
     }
 }
 
@@ -332,7 +775,23 @@ async fn report_dry_run(plan: &SubmissionPlan, progress: &dyn ProgressCallback)
 
     progress.on_message("Would execute:").await;
     for step in &plan.execution_steps {
-        let msg = format_step_for_dry_run(step, &plan.remote);
+        let remote = match step {
+            ExecutionStep::Push(bm) => plan.remote_for(&bm.name),
+            _ => &plan.remote,
+        };
+        let msg = format_step_for_dry_run(step, remote);
+        progress.on_message(&msg).await;
+    }
+    if !plan.assignees.is_empty() {
+        let msg = format!("  → assign new PRs to {}", plan.assignees.join(", "));
+        progress.on_message(&msg).await;
+    }
+    if let Some(milestone) = &plan.milestone {
+        let msg = format!("  → put PRs in milestone {milestone}");
+        progress.on_message(&msg).await;
+    }
+    if !plan.labels.is_empty() {
+        let msg = format!("  → label PRs with {}", plan.labels.join(", "));
         progress.on_message(&msg).await;
     }
 }
@@ -347,6 +806,220 @@ pub fn format_step_for_dry_run(step: &ExecutionStep, remote: &str) -> String {
     }
 }
 
+// =============================================================================
+// Dependency Line Functions
+// =============================================================================
+
+/// Parent PR of each submitted PR, as (bookmark, PR number, parent PR number)
+///
+/// The parent is the PR whose head is this PR's base, or None when the base
+/// is the stack's base branch. PRs based on a branch with no known PR are
+/// left out, so their bodies aren't touched.
+fn dependency_parents(
+    plan: &SubmissionPlan,
+    bookmark_to_pr: &HashMap<String, PullRequest>,
+) -> Vec<(String, u64, Option<u64>)> {
+    plan.segments
+        .iter()
+        .filter_map(|seg| {
+            let pr = bookmark_to_pr.get(&seg.bookmark.name)?;
+            let parent = if pr.base_ref == plan.default_branch {
+                None
+            } else {
+                Some(bookmark_to_pr.get(&pr.base_ref)?.number)
+            };
+            Some((seg.bookmark.name.clone(), pr.number, parent))
+        })
+        .collect()
+}
+
+/// Post the `ryu/stack` commit status on each PR's head, returning failures
+///
+/// `parents` is in stack order, as returned by [`dependency_parents`]. A PR
+/// whose parent is still open gets a pending status, so the platform shows
+/// it shouldn't be merged yet.
+async fn post_stack_statuses(
+    plan: &SubmissionPlan,
+    platform: &dyn PlatformService,
+    parents: &[(String, u64, Option<u64>)],
+) -> Vec<String> {
+    let total = parents.len();
+    let platform_kind = platform.config().platform;
+
+    let outcomes: Vec<_> = stream::iter(parents.iter().enumerate())
+        .map(|(idx, (bookmark, _, parent))| async move {
+            let sha = plan
+                .segments
+                .iter()
+                .find(|s| s.bookmark.name == *bookmark)
+                .map(|s| s.bookmark.commit_id.as_str())?;
+            let state = if parent.is_some() {
+                CheckState::Pending
+            } else {
+                CheckState::Success
+            };
+            let description = stack_status_description(idx + 1, total, *parent, platform_kind);
+            let outcome = platform
+                .create_commit_status(sha, STACK_STATUS_CONTEXT, state, &description)
+                .await;
+            Some((bookmark, outcome))
+        })
+        .buffered(platform.max_concurrency())
+        .collect()
+        .await;
+
+    outcomes
+        .into_iter()
+        .flatten()
+        .filter_map(|(bookmark, outcome)| {
+            outcome
+                .err()
+                .map(|e| format!("Failed to post stack status for {bookmark}: {e}"))
+        })
+        .collect()
+}
+
+/// Description of the `ryu/stack` commit status, e.g. "2/4 – depends on #101"
+pub fn stack_status_description(
+    position: usize,
+    total: usize,
+    parent: Option<u64>,
+    platform: Platform,
+) -> String {
+    parent.map_or_else(
+        || format!("{position}/{total} – bottom of the stack"),
+        |number| format!("{position}/{total} – depends on {}{number}", pr_sigil(platform)),
+    )
+}
+
+/// The character PR references start with on `platform`
+///
+/// GitHub references PRs as `#N`, GitLab references MRs as `!N`. Gerrit
+/// and registered platforms have no known syntax, so `#N` is used there too.
+pub const fn pr_sigil(platform: Platform) -> char {
+    match platform {
+        Platform::GitHub | Platform::Gerrit | Platform::Custom(_) => '#',
+        Platform::GitLab => '!',
+    }
+}
+
+/// Set or remove the "Depends on" line at the top of a PR body
+///
+/// `parent` is the PR below this one in the stack, or None once it merged.
+pub fn with_dependency_line(body: &str, parent: Option<u64>, platform: Platform) -> String {
+    let rest = strip_dependency_line(body);
+    let Some(number) = parent else {
+        return rest.to_string();
+    };
+
+    let line = format!("{DEPENDS_ON_PREFIX}{}{number}", pr_sigil(platform));
+    if rest.is_empty() {
+        line
+    } else {
+        format!("{line}\n\n{rest}")
+    }
+}
+
+/// `body` without a leading "Depends on #N" line and the blank lines after it
+fn strip_dependency_line(body: &str) -> &str {
+    let (first, rest) = body.split_once('\n').unwrap_or((body, ""));
+    let is_dependency_line = first
+        .trim_end()
+        .strip_prefix(DEPENDS_ON_PREFIX)
+        .and_then(|reference| reference.strip_prefix(['#', '!']))
+        .is_some_and(|number| !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()));
+    if is_dependency_line {
+        rest.trim_start_matches(['\r', '\n'])
+    } else {
+        body
+    }
+}
+
+/// The marker naming the jj change a PR was created for
+pub fn change_id_marker(change_id: &str) -> String {
+    format!("{CHANGE_ID_MARKER_PREFIX}{change_id} -->")
+}
+
+/// Append the change-id marker to a PR body
+///
+/// The marker is an HTML comment, so it doesn't show in the rendered
+/// description. It lets a PR be found again after its bookmark is renamed.
+pub fn with_change_id_marker(body: &str, change_id: &str) -> String {
+    let marker = change_id_marker(change_id);
+    let body = body.trim_end();
+    if body.is_empty() {
+        marker
+    } else {
+        format!("{body}\n\n{marker}")
+    }
+}
+
+/// A generated description between the ryu description markers
+pub fn description_block(description: &str) -> String {
+    let description = description.trim();
+    if description.is_empty() {
+        format!("{DESCRIPTION_BODY_START}\n{DESCRIPTION_BODY_END}")
+    } else {
+        format!("{DESCRIPTION_BODY_START}\n{description}\n{DESCRIPTION_BODY_END}")
+    }
+}
+
+/// Replace the generated description in a PR body
+///
+/// Only the part between the ryu description markers is replaced, so
+/// templates, notes and edits made on the platform around it stay. Bodies
+/// without the markers (PRs ryu didn't create) are left as they are.
+pub fn with_description(body: &str, description: &str) -> String {
+    let Some(start) = body.find(DESCRIPTION_BODY_START) else {
+        return body.to_string();
+    };
+    let Some(end) = body[start..].find(DESCRIPTION_BODY_END) else {
+        return body.to_string();
+    };
+    let end = start + end + DESCRIPTION_BODY_END.len();
+    format!(
+        "{}{}{}",
+        &body[..start],
+        description_block(description),
+        &body[end..]
+    )
+}
+
+/// Replace the generated description in a PR's body if it changed
+///
+/// Without a generated description the body is left alone.
+async fn update_description(
+    platform: &dyn PlatformService,
+    pr_number: u64,
+    description: Option<&str>,
+) -> Result<()> {
+    let Some(description) = description else {
+        return Ok(());
+    };
+    let details = platform.get_pr_details(pr_number).await?;
+    let body = details.body.unwrap_or_default();
+    let updated = with_description(&body, description);
+    if updated != body {
+        platform.update_pr_body(pr_number, &updated).await?;
+    }
+    Ok(())
+}
+
+/// Update a PR's "Depends on" line if it doesn't already match `parent`
+async fn update_dependency_line(
+    platform: &dyn PlatformService,
+    pr_number: u64,
+    parent: Option<u64>,
+) -> Result<()> {
+    let details = platform.get_pr_details(pr_number).await?;
+    let body = details.body.unwrap_or_default();
+    let updated = with_dependency_line(&body, parent, platform.config().platform);
+    if updated != body {
+        platform.update_pr_body(pr_number, &updated).await?;
+    }
+    Ok(())
+}
+
 // =============================================================================
 // Stack Comment Functions
 // =============================================================================
@@ -366,6 +1039,10 @@ pub fn build_stack_comment_data(
                 pr_url: pr.html_url.clone(),
                 pr_number: pr.number,
                 pr_title: pr.title.clone(),
+                status: StackItemStatus {
+                    draft: pr.is_draft,
+                    ..StackItemStatus::default()
+                },
             })
         })
         .collect();
@@ -377,6 +1054,34 @@ pub fn build_stack_comment_data(
     }
 }
 
+/// Refresh the CI and approval status of every PR in the stack
+///
+/// Best effort: a PR whose checks or reviews can't be fetched just doesn't
+/// get those badges.
+async fn fetch_stack_status(platform: &dyn PlatformService, data: &mut StackCommentData) {
+    let statuses: Vec<(bool, bool)> = stream::iter(data.stack.iter())
+        .map(|item| async move {
+            let checks = platform.list_checks(&item.bookmark_name).await;
+            let reviews = platform.list_reviews(item.pr_number).await;
+            let ci_passed = checks.is_ok_and(|checks| {
+                !checks.is_empty()
+                    && checks
+                        .iter()
+                        .all(|check| !check.required || check.state.is_passing())
+            });
+            let approved = reviews.is_ok_and(|reviews| is_approved_by_reviews(&reviews));
+            (ci_passed, approved)
+        })
+        .buffered(platform.max_concurrency())
+        .collect()
+        .await;
+
+    for (item, (ci_passed, approved)) in data.stack.iter_mut().zip(statuses) {
+        item.status.ci_passed = ci_passed;
+        item.status.approved = approved;
+    }
+}
+
 /// Format the stack comment body for a PR (defaults to GitHub format)
 ///
 /// For platform-specific formatting, use internal `format_stack_comment_for_platform`.
@@ -404,31 +1109,32 @@ fn format_stack_comment_for_platform(
     let reversed_idx = data.stack.len() - 1 - current_idx;
     for (i, item) in data.stack.iter().rev().enumerate() {
         let is_current = i == reversed_idx;
+        let badges = item.status.badges();
         match platform {
             Platform::GitHub => {
                 // GitHub: "* PR title #N" - #N auto-links to PRs
                 if is_current {
                     let _ = writeln!(
                         body,
-                        "* **{} #{} {STACK_COMMENT_THIS_PR}**",
+                        "* **{} #{}{badges} {STACK_COMMENT_THIS_PR}**",
                         item.pr_title, item.pr_number
                     );
                 } else {
-                    let _ = writeln!(body, "* {} #{}", item.pr_title, item.pr_number);
+                    let _ = writeln!(body, "* {} #{}{badges}", item.pr_title, item.pr_number);
                 }
             }
-            Platform::GitLab => {
+            Platform::GitLab | Platform::Gerrit | Platform::Custom(_) => {
                 // GitLab: "* [PR title !N](url)" - !N is MR reference, full link for clickability
                 if is_current {
                     let _ = writeln!(
                         body,
-                        "* **[{} !{}]({}) {STACK_COMMENT_THIS_PR}**",
+                        "* **[{} !{}]({}){badges} {STACK_COMMENT_THIS_PR}**",
                         item.pr_title, item.pr_number, item.pr_url
                     );
                 } else {
                     let _ = writeln!(
                         body,
-                        "* [{} !{}]({})",
+                        "* [{} !{}]({}){badges}",
                         item.pr_title, item.pr_number, item.pr_url
                     );
                 }
@@ -447,6 +1153,21 @@ fn format_stack_comment_for_platform(
     Ok(body)
 }
 
+/// Update a PR's stack overview, in its body or its stack comment per the plan
+async fn update_stack_overview(
+    plan: &SubmissionPlan,
+    platform: &dyn PlatformService,
+    data: &StackCommentData,
+    current_idx: usize,
+    pr_number: u64,
+) -> Result<()> {
+    if plan.stack_in_body {
+        update_stack_in_body(platform, data, current_idx, pr_number).await
+    } else {
+        create_or_update_stack_comment(platform, data, current_idx, pr_number).await
+    }
+}
+
 /// Create or update the stack comment on a PR
 async fn create_or_update_stack_comment(
     platform: &dyn PlatformService,
@@ -456,16 +1177,17 @@ async fn create_or_update_stack_comment(
 ) -> Result<()> {
     let body = format_stack_comment_for_platform(data, current_idx, platform.config().platform)?;
 
-    // Find existing comment by looking for our data prefix (check both old and new)
     let comments = platform.list_pr_comments(pr_number).await?;
-    let existing = comments
-        .iter()
-        .find(|c| c.body.contains(COMMENT_DATA_PREFIX) || c.body.contains(COMMENT_DATA_PREFIX_OLD));
+    let mut existing = find_stack_comments(&comments).into_iter();
 
-    if let Some(comment) = existing {
+    if let Some(comment) = existing.next() {
         platform
             .update_pr_comment(pr_number, comment.id, &body)
             .await?;
+        // Earlier versions or racing runs may have left duplicates behind
+        for duplicate in existing {
+            platform.delete_pr_comment(pr_number, duplicate.id).await?;
+        }
     } else {
         platform.create_pr_comment(pr_number, &body).await?;
     }
@@ -473,6 +1195,64 @@ async fn create_or_update_stack_comment(
     Ok(())
 }
 
+/// Put the stack overview in a PR's body, replacing the stack comment
+async fn update_stack_in_body(
+    platform: &dyn PlatformService,
+    data: &StackCommentData,
+    current_idx: usize,
+    pr_number: u64,
+) -> Result<()> {
+    let section = format_stack_comment_for_platform(data, current_idx, platform.config().platform)?;
+
+    let details = platform.get_pr_details(pr_number).await?;
+    let body = details.body.unwrap_or_default();
+    let updated = with_stack_section(&body, &section);
+    if updated != body {
+        platform.update_pr_body(pr_number, &updated).await?;
+    }
+
+    // A comment from before the switch would now be a stale duplicate
+    let comments = platform.list_pr_comments(pr_number).await?;
+    for comment in find_stack_comments(&comments) {
+        platform.delete_pr_comment(pr_number, comment.id).await?;
+    }
+
+    Ok(())
+}
+
+/// Replace the stack overview between the ryu markers in a PR body
+///
+/// Appends it (with the markers) if the body has none yet. Everything outside
+/// the markers is left alone.
+pub fn with_stack_section(body: &str, section: &str) -> String {
+    let block = format!("{STACK_BODY_START}\n{}\n{STACK_BODY_END}", section.trim_end());
+
+    if let Some(start) = body.find(STACK_BODY_START)
+        && let Some(end) = body[start..].find(STACK_BODY_END)
+    {
+        let end = start + end + STACK_BODY_END.len();
+        return format!("{}{block}{}", &body[..start], &body[end..]);
+    }
+
+    let body = body.trim_end();
+    if body.is_empty() {
+        block
+    } else {
+        format!("{body}\n\n{block}")
+    }
+}
+
+/// Find ryu stack comments (old and new format), oldest first
+fn find_stack_comments(comments: &[PrComment]) -> Vec<&PrComment> {
+    let mut found: Vec<&PrComment> = comments
+        .iter()
+        .filter(|c| c.body.contains(COMMENT_DATA_PREFIX) || c.body.contains(COMMENT_DATA_PREFIX_OLD))
+        .collect();
+    // Comment IDs increase monotonically on both GitHub and GitLab
+    found.sort_by_key(|c| c.id);
+    found
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -480,7 +1260,7 @@ async fn create_or_update_stack_comment(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::NarrowedBookmarkSegment;
+    use crate::types::{NarrowedBookmarkSegment, StackCommentMode};
 
     fn make_pr(number: u64, bookmark: &str) -> PullRequest {
         PullRequest {
@@ -641,7 +1421,16 @@ mod tests {
             execution_steps: vec![],
             existing_prs: HashMap::new(),
             remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
             default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
         };
 
         let mut bookmark_to_pr = HashMap::new();
@@ -677,7 +1466,16 @@ mod tests {
             execution_steps: vec![],
             existing_prs: HashMap::new(),
             remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
             default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
         };
 
         // Only feat-a has a PR
@@ -700,12 +1498,14 @@ mod tests {
                     pr_url: "https://example.com/1".to_string(),
                     pr_number: 1,
                     pr_title: "feat: add auth".to_string(),
+                    status: StackItemStatus::default(),
                 },
                 StackItem {
                     bookmark_name: "feat-b".to_string(),
                     pr_url: "https://example.com/2".to_string(),
                     pr_number: 2,
                     pr_title: "feat: add sessions".to_string(),
+                    status: StackItemStatus::default(),
                 },
             ],
             base_branch: "main".to_string(),
@@ -726,6 +1526,7 @@ mod tests {
                 pr_url: "https://example.com/1".to_string(),
                 pr_number: 1,
                 pr_title: "feat: add auth".to_string(),
+                status: StackItemStatus::default(),
             }],
             base_branch: "main".to_string(),
         };
@@ -745,12 +1546,14 @@ mod tests {
                     pr_url: "https://gitlab.com/test/test/-/merge_requests/1".to_string(),
                     pr_number: 1,
                     pr_title: "feat: add auth".to_string(),
+                    status: StackItemStatus::default(),
                 },
                 StackItem {
                     bookmark_name: "feat-b".to_string(),
                     pr_url: "https://gitlab.com/test/test/-/merge_requests/2".to_string(),
                     pr_number: 2,
                     pr_title: "feat: add sessions".to_string(),
+                    status: StackItemStatus::default(),
                 },
             ],
             base_branch: "main".to_string(),
@@ -790,6 +1593,7 @@ mod tests {
                 pr_url: "https://github.com/test/test/pull/1".to_string(),
                 pr_number: 1,
                 pr_title: "feat: add auth".to_string(),
+                status: StackItemStatus::default(),
             }],
             base_branch: "main".to_string(),
         };
@@ -806,8 +1610,133 @@ mod tests {
         );
     }
 
+    // === Dependency line tests ===
+
+    #[test]
+    fn test_with_dependency_line_adds_and_replaces() {
+        assert_eq!(
+            with_dependency_line("Adds login.", Some(12), Platform::GitHub),
+            "Depends on #12\n\nAdds login."
+        );
+        assert_eq!(
+            with_dependency_line("Depends on #12\n\nAdds login.", Some(14), Platform::GitLab),
+            "Depends on !14\n\nAdds login."
+        );
+        assert_eq!(with_dependency_line("", Some(3), Platform::GitHub), "Depends on #3");
+    }
+
+    #[test]
+    fn test_with_dependency_line_removes_when_parent_merged() {
+        assert_eq!(
+            with_dependency_line("Depends on #12\n\nAdds login.", None, Platform::GitHub),
+            "Adds login."
+        );
+        // Only a leading "Depends on" line with a PR reference is ours
+        let body = "Depends on the new session API.";
+        assert_eq!(with_dependency_line(body, None, Platform::GitHub), body);
+    }
+
+    #[test]
+    fn test_with_description_replaces_only_the_generated_description() {
+        let body = format!(
+            "Depends on #12\n\n{}\n\n## Testing\nManual.\n\n{STACK_BODY_START}\n* #12\n{STACK_BODY_END}",
+            description_block("Old description.")
+        );
+        assert_eq!(
+            with_description(&body, "New description.\n"),
+            format!(
+                "Depends on #12\n\n{DESCRIPTION_BODY_START}\nNew description.\n{DESCRIPTION_BODY_END}\n\n## Testing\nManual.\n\n{STACK_BODY_START}\n* #12\n{STACK_BODY_END}"
+            )
+        );
+        // Bodies without the markers weren't generated by ryu
+        assert_eq!(
+            with_description("Written by hand.", "New description."),
+            "Written by hand."
+        );
+    }
+
+    #[test]
+    fn test_change_id_marker_survives_new_description() {
+        let body = with_change_id_marker(
+            &format!(
+                "Depends on #12\n\n{}",
+                description_block("Old description.")
+            ),
+            "kxqzpt",
+        );
+        assert!(body.ends_with(
+            "Old description.\n<!-- ryu:description:end -->\n\n<!-- ryu:change-id:kxqzpt -->"
+        ));
+        assert_eq!(
+            with_description(&body, "New description."),
+            "Depends on #12\n\n<!-- ryu:description:start -->\nNew description.\n<!-- ryu:description:end -->\n\n<!-- ryu:change-id:kxqzpt -->"
+        );
+        assert_eq!(
+            with_change_id_marker("", "kxqzpt"),
+            "<!-- ryu:change-id:kxqzpt -->"
+        );
+    }
+
+    #[test]
+    fn test_stack_status_description() {
+        assert_eq!(
+            stack_status_description(2, 4, Some(101), Platform::GitHub),
+            "2/4 – depends on #101"
+        );
+        assert_eq!(
+            stack_status_description(2, 4, Some(101), Platform::GitLab),
+            "2/4 – depends on !101"
+        );
+        assert_eq!(
+            stack_status_description(1, 4, None, Platform::GitHub),
+            "1/4 – bottom of the stack"
+        );
+    }
+
+    #[test]
+    fn test_with_stack_section_appends_then_replaces() {
+        let body = with_stack_section("Adds login.", "* old #1\n");
+        assert_eq!(
+            body,
+            format!("Adds login.\n\n{STACK_BODY_START}\n* old #1\n{STACK_BODY_END}")
+        );
+
+        let edited = format!("{body}\n\nReviewer notes.");
+        assert_eq!(
+            with_stack_section(&edited, "* new #2"),
+            format!(
+                "Adds login.\n\n{STACK_BODY_START}\n* new #2\n{STACK_BODY_END}\n\nReviewer notes."
+            )
+        );
+        assert_eq!(
+            with_stack_section("", "* new #2"),
+            format!("{STACK_BODY_START}\n* new #2\n{STACK_BODY_END}")
+        );
+    }
+
     // === Plan helper tests ===
 
+    #[test]
+    fn test_find_stack_comments_oldest_first() {
+        let comments = vec![
+            PrComment {
+                id: 30,
+                body: format!("{COMMENT_DATA_PREFIX}e30={COMMENT_DATA_POSTFIX}"),
+            },
+            PrComment {
+                id: 5,
+                body: "LGTM".to_string(),
+            },
+            PrComment {
+                id: 12,
+                body: format!("{COMMENT_DATA_PREFIX_OLD}e30={COMMENT_DATA_POSTFIX}"),
+            },
+        ];
+
+        let found: Vec<u64> = find_stack_comments(&comments).iter().map(|c| c.id).collect();
+        assert_eq!(found, vec![12, 30]);
+    }
+
     #[test]
     fn test_plan_is_empty() {
         let plan = SubmissionPlan {
@@ -816,7 +1745,16 @@ mod tests {
             execution_steps: vec![],
             existing_prs: HashMap::new(),
             remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
             default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
         };
 
         assert!(plan.is_empty());
@@ -843,7 +1781,16 @@ mod tests {
             ],
             existing_prs: HashMap::new(),
             remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
             default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
         };
 
         assert!(!plan.is_empty());
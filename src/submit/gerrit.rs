@@ -0,0 +1,308 @@
+//! Gerrit submission: one change per commit
+//!
+//! Gerrit reviews individual commits rather than branches. The whole stack
+//! is pushed to `refs/for/<branch>` in one go, and Gerrit creates a change
+//! (or a new patch set) for every commit, matched by the commit's
+//! `Change-Id` trailer. Bookmarks only select which commits are submitted.
+
+use crate::error::{Error, Result};
+use crate::platform::PlatformService;
+use crate::repo::JjWorkspace;
+use crate::submit::SubmissionAnalysis;
+use crate::types::PullRequest;
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+
+/// Trailer naming the Gerrit change a commit belongs to
+pub const CHANGE_ID_TRAILER: &str = "Change-Id";
+
+/// Gerrit Change-Id derived from a jj change ID
+///
+/// Uses the same format as jj's own Gerrit support, so changes keep their
+/// Gerrit identity whichever tool pushes them.
+pub fn gerrit_change_id(jj_change_id: &str) -> String {
+    format!("I{jj_change_id}6a6a6964")
+}
+
+/// The `Change-Id` trailer of a commit description, if it has one
+pub fn change_id_trailer(description: &str) -> Option<String> {
+    let paragraph = trailer_paragraph(description)?;
+    paragraph
+        .lines()
+        .rev()
+        .find_map(|line| line.strip_prefix(CHANGE_ID_TRAILER)?.strip_prefix(':'))
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// Add a `Change-Id` trailer to a commit description
+///
+/// The trailer joins an existing trailer paragraph (e.g. `Signed-off-by`),
+/// otherwise it starts a new paragraph.
+pub fn with_change_id_trailer(description: &str, change_id: &str) -> String {
+    let body = description.trim_end();
+    let separator = if trailer_paragraph(body).is_some() {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    format!("{body}{separator}{CHANGE_ID_TRAILER}: {change_id}\n")
+}
+
+/// Last paragraph of a description, if it consists only of trailers
+///
+/// The subject line is never a trailer paragraph.
+fn trailer_paragraph(description: &str) -> Option<&str> {
+    let body = description.trim_end();
+    let (_, paragraph) = body.rsplit_once("\n\n")?;
+    let is_trailer = |line: &str| {
+        line.split_once(':').is_some_and(|(token, value)| {
+            !token.is_empty()
+                && !value.trim().is_empty()
+                && token
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+    };
+    paragraph.lines().all(is_trailer).then_some(paragraph)
+}
+
+/// A commit to submit as a Gerrit change
+#[derive(Debug, Clone)]
+pub struct GerritChange {
+    /// Bookmark whose segment contains the commit
+    pub bookmark: String,
+    /// Commit ID before submission
+    pub commit_id: String,
+    /// First line of the commit description
+    pub title: String,
+    /// Gerrit Change-Id of the commit
+    pub change_id: String,
+    /// Description with the Change-Id trailer added, if the commit lacks one
+    pub new_description: Option<String>,
+    /// The open change with this Change-Id, if any
+    pub existing: Option<PullRequest>,
+}
+
+/// Plan for pushing a stack to Gerrit
+#[derive(Debug, Clone)]
+pub struct GerritSubmissionPlan {
+    /// Remote to push to
+    pub remote: String,
+    /// Branch the changes are for
+    pub target_branch: String,
+    /// Commits to submit, bottom of the stack first
+    pub changes: Vec<GerritChange>,
+    /// Mark the changes as work in progress
+    pub wip: bool,
+    /// Mark the changes as ready for review
+    pub ready: bool,
+    /// Hashtags added to the pushed changes
+    pub hashtags: Vec<String>,
+}
+
+impl GerritSubmissionPlan {
+    /// Magic ref to push to, e.g. `refs/for/main%wip,hashtag=infra`
+    pub fn push_target(&self) -> String {
+        let mut options = Vec::new();
+        if self.wip {
+            options.push("wip".to_string());
+        } else if self.ready {
+            options.push("ready".to_string());
+        }
+        options.extend(self.hashtags.iter().map(|tag| format!("hashtag={tag}")));
+
+        let mut target = format!("refs/for/{}", self.target_branch);
+        if !options.is_empty() {
+            target.push('%');
+            target.push_str(&options.join(","));
+        }
+        target
+    }
+
+    /// Number of commits that will become new changes
+    pub fn count_new(&self) -> usize {
+        self.changes.iter().filter(|c| c.existing.is_none()).count()
+    }
+}
+
+/// Plan a Gerrit submission of the analyzed stack
+///
+/// Every commit of every segment becomes a change. Commits without a
+/// `Change-Id` trailer get one derived from their jj change ID.
+pub async fn create_gerrit_plan(
+    analysis: &SubmissionAnalysis,
+    platform: &dyn PlatformService,
+    remote: &str,
+    target_branch: &str,
+) -> Result<GerritSubmissionPlan> {
+    let mut changes = Vec::new();
+    for segment in &analysis.segments {
+        // Segment changes are newest first
+        for entry in segment.changes.iter().rev() {
+            if entry.description.trim().is_empty() {
+                return Err(Error::InvalidArgument(format!(
+                    "Commit {} in '{}' has no description; Gerrit changes need a commit message",
+                    &entry.commit_id[..12.min(entry.commit_id.len())],
+                    segment.bookmark.name
+                )));
+            }
+
+            let (change_id, new_description) = change_id_trailer(&entry.description)
+                .map_or_else(
+                    || {
+                        let id = gerrit_change_id(&entry.change_id);
+                        let description = with_change_id_trailer(&entry.description, &id);
+                        (id, Some(description))
+                    },
+                    |id| (id, None),
+                );
+
+            changes.push(GerritChange {
+                bookmark: segment.bookmark.name.clone(),
+                commit_id: entry.commit_id.clone(),
+                title: entry.description_first_line.clone(),
+                change_id,
+                new_description,
+                existing: None,
+            });
+        }
+    }
+
+    let existing = find_changes(platform, &changes).await?;
+    for change in &mut changes {
+        change.existing = existing.get(&change.change_id).cloned();
+    }
+
+    Ok(GerritSubmissionPlan {
+        remote: remote.to_string(),
+        target_branch: target_branch.to_string(),
+        changes,
+        wip: false,
+        ready: false,
+        hashtags: Vec::new(),
+    })
+}
+
+/// Result of a Gerrit submission
+#[derive(Debug, Clone, Default)]
+pub struct GerritSubmissionResult {
+    /// Whether Gerrit accepted the push (false if nothing changed)
+    pub pushed: bool,
+    /// Submitted commits with their change after the push, bottom first
+    pub changes: Vec<(GerritChange, Option<PullRequest>)>,
+}
+
+/// Execute a Gerrit submission plan
+///
+/// Adds missing Change-Id trailers (rewriting the stack like `jj describe`),
+/// pushes the top commit to the plan's magic ref, and looks up the
+/// resulting changes.
+pub async fn execute_gerrit_submission(
+    plan: &GerritSubmissionPlan,
+    workspace: &mut JjWorkspace,
+    platform: &dyn PlatformService,
+) -> Result<GerritSubmissionResult> {
+    let Some(top) = plan.changes.last() else {
+        return Ok(GerritSubmissionResult::default());
+    };
+
+    let descriptions: HashMap<String, String> = plan
+        .changes
+        .iter()
+        .filter_map(|c| Some((c.commit_id.clone(), c.new_description.clone()?)))
+        .collect();
+    let rewritten = workspace.set_descriptions(&descriptions)?;
+    let top_commit = rewritten.get(&top.commit_id).unwrap_or(&top.commit_id);
+
+    let pushed = workspace.push_for_review(&plan.remote, top_commit, &plan.push_target())?;
+
+    let found = find_changes(platform, &plan.changes).await?;
+    let changes = plan
+        .changes
+        .iter()
+        .map(|change| (change.clone(), found.get(&change.change_id).cloned()))
+        .collect();
+
+    Ok(GerritSubmissionResult { pushed, changes })
+}
+
+/// Look up the open change for each Change-Id
+async fn find_changes(
+    platform: &dyn PlatformService,
+    changes: &[GerritChange],
+) -> Result<HashMap<String, PullRequest>> {
+    let lookups: Vec<_> = stream::iter(changes)
+        .map(|change| async move {
+            let pr = platform.find_existing_pr(&change.change_id).await?;
+            Ok::<_, Error>((change.change_id.clone(), pr))
+        })
+        .buffered(platform.max_concurrency())
+        .collect()
+        .await;
+
+    let mut found = HashMap::new();
+    for lookup in lookups {
+        if let (change_id, Some(pr)) = lookup? {
+            found.insert(change_id, pr);
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_id_trailer() {
+        let description = "Add login\n\nBody text.\n\nChange-Id: Iabc123\nSigned-off-by: A <a@b.c>\n";
+        assert_eq!(change_id_trailer(description), Some("Iabc123".to_string()));
+
+        // Only the last paragraph holds trailers
+        assert_eq!(change_id_trailer("Change-Id: Iabc123\n"), None);
+        assert_eq!(
+            change_id_trailer("Add login\n\nChange-Id: Iabc123\n\nMore text.\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_with_change_id_trailer() {
+        assert_eq!(
+            with_change_id_trailer("Add login\n", "Iabc"),
+            "Add login\n\nChange-Id: Iabc\n"
+        );
+        assert_eq!(
+            with_change_id_trailer("Add login\n\nSigned-off-by: A <a@b.c>\n", "Iabc"),
+            "Add login\n\nSigned-off-by: A <a@b.c>\nChange-Id: Iabc\n"
+        );
+        assert_eq!(
+            with_change_id_trailer("Add login\n\nNote: this is prose, really.\nMore prose.", "Iabc"),
+            "Add login\n\nNote: this is prose, really.\nMore prose.\n\nChange-Id: Iabc\n"
+        );
+    }
+
+    #[test]
+    fn test_push_target() {
+        let mut plan = GerritSubmissionPlan {
+            remote: "origin".to_string(),
+            target_branch: "main".to_string(),
+            changes: vec![],
+            wip: false,
+            ready: false,
+            hashtags: vec![],
+        };
+        assert_eq!(plan.push_target(), "refs/for/main");
+        plan.wip = true;
+        assert_eq!(plan.push_target(), "refs/for/main%wip");
+        plan.wip = false;
+        plan.ready = true;
+        assert_eq!(plan.push_target(), "refs/for/main%ready");
+        plan.hashtags = vec!["infra".to_string(), "q3".to_string()];
+        assert_eq!(
+            plan.push_target(),
+            "refs/for/main%ready,hashtag=infra,hashtag=q3"
+        );
+    }
+}
@@ -4,27 +4,60 @@
 //! 1. Analysis - understand what needs to be submitted
 //! 2. Planning - determine what PRs to create/update
 //! 3. Execution - perform the actual operations
+//!
+//! Gerrit and email patch series have their own submission paths, since
+//! they submit commits rather than bookmarks.
 
 mod analysis;
+mod backport;
+mod email;
 mod execute;
+mod gerrit;
 mod plan;
 mod progress;
+mod rollback;
+mod template;
 
 pub use analysis::{
-    SubmissionAnalysis, analyze_submission, create_narrowed_segments, generate_pr_title,
-    get_base_branch, select_bookmark_for_segment,
+    ConventionalPrefix, SubmissionAnalysis, analyze_submission, collapse_to_single_pr,
+    create_narrowed_segments, generate_changelog_body, generate_pr_title, get_base_branch,
+    parse_conventional_title, revset_bookmark_range, select_bookmark_for_segment, trim_below,
+};
+pub use backport::{
+    BACKPORT_PREFIX, backport_bookmark_name, create_backport_stack, link_backport_prs,
+};
+pub use email::{
+    FormatPatchOptions, PatchSeries, SendPatchesOptions, create_patch_series, format_cover_letter,
+    format_patches, patches_dir, send_patches,
 };
 pub use execute::{
-    STACK_COMMENT_THIS_PR, SubmissionResult, execute_submission, format_stack_comment,
+    CHANGE_ID_MARKER_PREFIX, DEPENDS_ON_PREFIX, DESCRIPTION_BODY_END, DESCRIPTION_BODY_START,
+    STACK_BODY_END, STACK_BODY_START, STACK_COMMENT_THIS_PR, SubmissionResult, change_id_marker,
+    description_block, execute_submission,
+    find_created_pr, format_stack_comment, pr_sigil, with_change_id_marker, with_dependency_line,
+    with_description, with_stack_section,
 };
 
 // Exports for testing stack comment formatting (used by integration tests)
 pub use execute::{
-    COMMENT_DATA_POSTFIX, COMMENT_DATA_PREFIX, StackCommentData, StackItem,
-    build_stack_comment_data,
+    COMMENT_DATA_POSTFIX, COMMENT_DATA_PREFIX, STACK_BADGE_APPROVED, STACK_BADGE_CI_PASSED,
+    STACK_BADGE_DRAFT, StackCommentData, StackItem, StackItemStatus, build_stack_comment_data,
+};
+pub use gerrit::{
+    CHANGE_ID_TRAILER, GerritChange, GerritSubmissionPlan, GerritSubmissionResult,
+    change_id_trailer, create_gerrit_plan, execute_gerrit_submission, gerrit_change_id,
+    with_change_id_trailer,
 };
 pub use plan::{
-    ExecutionConstraint, ExecutionStep, PrBaseUpdate, PrToCreate, SubmissionPlan,
-    create_submission_plan,
+    ExecutionConstraint, ExecutionStep, PrBaseUpdate, PrMetadataUpdate, PrToCreate, PrToReopen,
+    SubmissionPlan, add_type_labels, apply_config_to_plan, bottom_parent_merged,
+    branch_pattern_matches, create_as_drafts, create_submission_plan, create_submission_plan_onto,
+    plan_metadata_updates, publish_bottom_draft, record_descriptions, relink_closed_prs,
+    resume_submission_plan, strip_conventional_titles,
 };
 pub use progress::{NoopProgress, Phase, ProgressCallback, PushStatus};
+pub use rollback::{
+    ROLLBACK_CLOSE_NOTE, RollbackResult, RollbackSnapshot, capture_rollback_snapshot,
+    record_undo, rollback_submission,
+};
+pub use template::{apply_pr_template, find_pr_template, with_pr_template};
@@ -2,16 +2,21 @@
 //!
 //! Determines what operations need to be performed to submit a stack.
 
+use crate::config::SubmitConfig;
 use crate::error::{Error, Result};
 use crate::platform::PlatformService;
 use crate::submit::SubmissionAnalysis;
-use crate::submit::analysis::{generate_pr_content, get_base_branch};
-use crate::types::{Bookmark, NarrowedBookmarkSegment, PullRequest};
+use crate::submit::analysis::{generate_pr_content, get_base_branch, parse_conventional_title};
+use crate::submit::execute::{SubmissionResult, change_id_marker};
+use crate::tracking::PrCache;
+use crate::types::{Bookmark, NarrowedBookmarkSegment, PrState, PullRequest, StackCommentMode};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
 use std::cmp::Reverse;
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
 
 /// Information about a PR that needs to be created
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PrToCreate {
     /// Bookmark for this PR
     pub bookmark: Bookmark,
@@ -25,8 +30,17 @@ pub struct PrToCreate {
     pub draft: bool,
 }
 
+/// Information about a closed PR to reopen instead of creating a new one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrToReopen {
+    /// Closed PR previously associated with the bookmark
+    pub pr: PullRequest,
+    /// PR to create instead if the platform refuses to reopen
+    pub create: PrToCreate,
+}
+
 /// Information about a PR that needs its base updated
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PrBaseUpdate {
     /// Bookmark for this PR
     pub bookmark: Bookmark,
@@ -38,8 +52,24 @@ pub struct PrBaseUpdate {
     pub pr: PullRequest,
 }
 
+/// Information about a PR whose commits were redescribed since ryu last
+/// set its title and body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrMetadataUpdate {
+    /// Bookmark for this PR
+    pub bookmark: Bookmark,
+    /// Existing PR
+    pub pr: PullRequest,
+    /// Title generated from the root commit's description
+    pub title: String,
+    /// Body generated from the commit descriptions (`None` leaves the PR's
+    /// body alone)
+    pub body: Option<String>,
+}
+
 /// Ordered execution step for a submission plan
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum ExecutionStep {
     /// Push bookmark to remote
     Push(Bookmark),
@@ -47,8 +77,12 @@ pub enum ExecutionStep {
     UpdateBase(PrBaseUpdate),
     /// Create a new PR
     CreatePr(PrToCreate),
+    /// Reopen a PR that was closed when its head branch was deleted
+    ReopenPr(PrToReopen),
     /// Publish a draft PR
     PublishPr(PullRequest),
+    /// Update the title and body of an existing PR
+    UpdateMetadata(PrMetadataUpdate),
 }
 
 impl ExecutionStep {
@@ -57,7 +91,10 @@ impl ExecutionStep {
         match self {
             Self::Push(bm) => &bm.name,
             Self::UpdateBase(update) => &update.bookmark.name,
-            Self::CreatePr(create) => &create.bookmark.name,
+            Self::UpdateMetadata(update) => &update.bookmark.name,
+            Self::CreatePr(create) | Self::ReopenPr(PrToReopen { create, .. }) => {
+                &create.bookmark.name
+            }
             Self::PublishPr(pr) => &pr.head_ref,
         }
     }
@@ -86,7 +123,23 @@ impl std::fmt::Display for ExecutionStep {
                 }
                 Ok(())
             }
+            Self::ReopenPr(reopen) => write!(
+                f,
+                "reopen PR #{} {} → {}",
+                reopen.pr.number, reopen.create.bookmark.name, reopen.create.base_branch
+            ),
             Self::PublishPr(pr) => write!(f, "publish PR #{} ({})", pr.number, pr.head_ref),
+            Self::UpdateMetadata(update) => {
+                write!(
+                    f,
+                    "retitle PR #{} {} ({})",
+                    update.pr.number, update.bookmark.name, update.title
+                )?;
+                if update.body.is_some() {
+                    write!(f, " [+body]")?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -97,15 +150,15 @@ impl std::fmt::Display for ExecutionStep {
 
 /// Typed reference to a Push operation by bookmark name.
 /// Distinct from [`UpdateRef`]/[`CreateRef`] to prevent mixing constraint endpoints.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PushRef(pub String);
 
 /// Typed reference to an `UpdateBase` operation by bookmark name.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct UpdateRef(pub String);
 
 /// Typed reference to a `CreatePr` operation by bookmark name.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct CreateRef(pub String);
 
 /// Dependency constraint between execution operations.
@@ -116,7 +169,8 @@ pub struct CreateRef(pub String);
 /// Constraints may reference operations that don't exist in the current plan
 /// (e.g., a bookmark that's already synced has no `Push` node). Resolution
 /// returns `None` for such constraints, which is expected behavior.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum ExecutionConstraint {
     /// Push parent branch before child branch.
     /// Ensures commits are pushed in stack order (ancestors before descendants).
@@ -267,7 +321,7 @@ struct ExecutionNode {
 }
 
 /// Submission plan
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmissionPlan {
     /// Segments to submit (used for stack comment generation)
     pub segments: Vec<NarrowedBookmarkSegment>,
@@ -279,11 +333,46 @@ pub struct SubmissionPlan {
     pub existing_prs: HashMap<String, PullRequest>,
     /// Remote name to push to
     pub remote: String,
+    /// Remotes to push individual bookmarks to instead of `remote`
+    pub push_remotes: HashMap<String, String>,
     /// Default branch name (main/master)
     pub default_branch: String,
+    /// Labels added to every PR in the stack, new and existing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    /// Labels added to one bookmark's PR on top of `labels`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub pr_labels: HashMap<String, Vec<String>>,
+    /// Users assigned to the PRs this submission creates
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assignees: Vec<String>,
+    /// Milestone every PR in the stack is put in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestone: Option<String>,
+    /// Put the stack overview in each PR's description instead of a comment
+    #[serde(default)]
+    pub stack_in_body: bool,
+    /// When the stack overview is kept on the PRs
+    #[serde(default)]
+    pub stack_comment: StackCommentMode,
+    /// Post a `ryu/stack` commit status on each PR head naming its place in
+    /// the stack
+    #[serde(default)]
+    pub stack_status: bool,
+    /// Re-request review from everyone who already reviewed a PR whose
+    /// bookmark was pushed
+    #[serde(default)]
+    pub rerequest_reviews: bool,
 }
 
 impl SubmissionPlan {
+    /// Remote a bookmark is pushed to
+    pub fn remote_for(&self, bookmark: &str) -> &str {
+        self.push_remotes
+            .get(bookmark)
+            .map_or(&self.remote, String::as_str)
+    }
+
     /// Check if there's nothing to do
     pub const fn is_empty(&self) -> bool {
         self.execution_steps.is_empty()
@@ -305,6 +394,14 @@ impl SubmissionPlan {
             .count()
     }
 
+    /// Count reopen PR steps
+    pub fn count_reopens(&self) -> usize {
+        self.execution_steps
+            .iter()
+            .filter(|s| matches!(s, ExecutionStep::ReopenPr(_)))
+            .count()
+    }
+
     /// Count update base steps
     pub fn count_updates(&self) -> usize {
         self.execution_steps
@@ -320,6 +417,14 @@ impl SubmissionPlan {
             .filter(|s| matches!(s, ExecutionStep::PublishPr(_)))
             .count()
     }
+
+    /// Count update metadata steps
+    pub fn count_metadata_updates(&self) -> usize {
+        self.execution_steps
+            .iter()
+            .filter(|s| matches!(s, ExecutionStep::UpdateMetadata(_)))
+            .count()
+    }
 }
 
 /// Create a submission plan
@@ -328,22 +433,38 @@ impl SubmissionPlan {
 /// - Which bookmarks need pushing
 /// - Which PRs need to be created
 /// - Which PR bases need updating
+///
+/// Titles and bodies of existing PRs are left to `plan_metadata_updates`.
+/// For a stack based on a branch other than the repository's default branch,
+/// use [`create_submission_plan_onto`].
 pub async fn create_submission_plan(
     analysis: &SubmissionAnalysis,
     platform: &dyn PlatformService,
     remote: &str,
     default_branch: &str,
+) -> Result<SubmissionPlan> {
+    create_submission_plan_onto(analysis, platform, remote, default_branch, default_branch).await
+}
+
+/// Create a submission plan for a stack based on `default_branch`
+///
+/// `trunk` is the repository's default branch. Like the stack's base, it's
+/// never pushed to, even when the stack targets another branch.
+pub async fn create_submission_plan_onto(
+    analysis: &SubmissionAnalysis,
+    platform: &dyn PlatformService,
+    remote: &str,
+    default_branch: &str,
+    trunk: &str,
 ) -> Result<SubmissionPlan> {
     let segments = &analysis.segments;
     let bookmarks: Vec<&Bookmark> = segments.iter().map(|s| &s.bookmark).collect();
 
-    // Check for existing PRs
-    let mut existing_prs = HashMap::new();
-    for bookmark in &bookmarks {
-        if let Some(pr) = platform.find_existing_pr(&bookmark.name).await? {
-            existing_prs.insert(bookmark.name.clone(), pr);
-        }
-    }
+    // Check for existing PRs (in one query where the platform supports it)
+    let names: Vec<String> = bookmarks.iter().map(|b| b.name.clone()).collect();
+    let mut existing_prs = platform.find_existing_prs(&names).await?;
+    let renamed_prs = find_renamed_prs(&bookmarks, &existing_prs, platform).await;
+    existing_prs.extend(renamed_prs);
 
     // Collect raw operations (unordered)
     let mut bookmarks_needing_push = Vec::new();
@@ -351,8 +472,12 @@ pub async fn create_submission_plan(
     let mut prs_to_update_base = Vec::new();
 
     for bookmark in &bookmarks {
-        // Check if needs push
-        if !bookmark.has_remote || !bookmark.is_synced {
+        // Check if needs push (a renamed bookmark's push also updates its
+        // PR's head branch)
+        let renamed = existing_prs
+            .get(&bookmark.name)
+            .is_some_and(|pr| pr.head_ref != bookmark.name);
+        if !bookmark.has_remote || !bookmark.is_synced || renamed {
             bookmarks_needing_push.push((*bookmark).clone());
         }
 
@@ -384,6 +509,9 @@ pub async fn create_submission_plan(
         }
     }
 
+    // Never push over trunk or a protected branch
+    check_protected_pushes(&bookmarks_needing_push, platform, &[default_branch, trunk]).await?;
+
     // Build ordered execution steps
     let (constraints, execution_steps) = build_execution_steps(
         segments,
@@ -399,10 +527,475 @@ pub async fn create_submission_plan(
         execution_steps,
         existing_prs,
         remote: remote.to_string(),
+        push_remotes: HashMap::new(),
         default_branch: default_branch.to_string(),
+        labels: Vec::new(),
+        pr_labels: HashMap::new(),
+        assignees: Vec::new(),
+        milestone: None,
+        stack_in_body: false,
+        stack_comment: StackCommentMode::default(),
+        stack_status: false,
+        rerequest_reviews: false,
     })
 }
 
+/// Find the open PRs of renamed bookmarks, keyed by their new name
+///
+/// A PR is looked up by head branch, which misses it once the bookmark is
+/// renamed. PRs ryu created carry the bookmark's change id in their body, so
+/// bookmarks without a PR are looked up by that instead. Lookups are best
+/// effort, and a PR already matched by head branch isn't claimed twice.
+async fn find_renamed_prs(
+    bookmarks: &[&Bookmark],
+    existing_prs: &HashMap<String, PullRequest>,
+    platform: &dyn PlatformService,
+) -> HashMap<String, PullRequest> {
+    let candidates: Vec<&Bookmark> = bookmarks
+        .iter()
+        .copied()
+        .filter(|b| !existing_prs.contains_key(&b.name))
+        .collect();
+
+    if candidates.is_empty() {
+        return HashMap::new();
+    }
+
+    let markers: Vec<String> = candidates
+        .iter()
+        .map(|bookmark| change_id_marker(&bookmark.change_id))
+        .collect();
+    let mut found = match platform.find_prs_by_body(&markers).await {
+        Ok(found) => found,
+        Err(e) => {
+            tracing::debug!(error = %e, "could not look up PRs by change id");
+            return HashMap::new();
+        }
+    };
+
+    let mut claimed: HashSet<u64> = existing_prs.values().map(|pr| pr.number).collect();
+    candidates
+        .into_iter()
+        .zip(&markers)
+        .filter_map(|(bookmark, marker)| Some((bookmark.name.clone(), found.remove(marker)?)))
+        .filter(|(_, pr)| claimed.insert(pr.number))
+        .collect()
+}
+
+/// Plan title and body updates for PRs whose commits were redescribed
+///
+/// A PR is updated only when the commit descriptions of its segment changed
+/// since ryu last described it (as recorded in the PR cache by
+/// `record_descriptions`), so a title or body edited on the platform stays
+/// until the commits are redescribed. PRs ryu has no record for are left
+/// alone, and so are segments whose root commit has no description.
+pub fn plan_metadata_updates(plan: &mut SubmissionPlan, pr_cache: &PrCache) -> Result<()> {
+    for segment in &plan.segments {
+        let name = &segment.bookmark.name;
+        let Some(pr) = plan.existing_prs.get(name) else {
+            continue;
+        };
+        let submitted = pr_cache
+            .get(name)
+            .filter(|cached| cached.number == pr.number)
+            .and_then(|cached| cached.description.as_deref());
+        let redescribed =
+            submitted.is_some_and(|submitted| submitted != segment_description(segment));
+        if !redescribed || !has_description(name, &plan.segments) {
+            continue;
+        }
+
+        let (title, body) = generate_pr_content(name, &plan.segments)?;
+        plan.execution_steps
+            .push(ExecutionStep::UpdateMetadata(PrMetadataUpdate {
+                bookmark: segment.bookmark.clone(),
+                pr: pr.clone(),
+                title,
+                body,
+            }));
+    }
+    Ok(())
+}
+
+/// Record the commit descriptions a submitted plan's PRs were described from
+///
+/// Run once the created and updated PRs are in the cache. The description
+/// is recorded for PRs ryu had no record for (including the ones it just
+/// created) and for PRs whose title and body it updated; an update that
+/// didn't happen is planned again next time.
+pub fn record_descriptions(
+    plan: &SubmissionPlan,
+    result: &SubmissionResult,
+    pr_cache: &mut PrCache,
+) {
+    for segment in &plan.segments {
+        let name = &segment.bookmark.name;
+        let Some(cached) = pr_cache.get(name) else {
+            continue;
+        };
+        if cached.description.is_none() || result.described_prs.contains(name) {
+            pr_cache.set_description(name, &segment_description(segment));
+        }
+    }
+}
+
+/// The commit descriptions a segment's PR title and body are generated from
+fn segment_description(segment: &NarrowedBookmarkSegment) -> String {
+    segment
+        .changes
+        .iter()
+        .rev()
+        .map(|change| change.description.trim())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Whether the root commit of a bookmark's segment has a description
+///
+/// Without one the generated title is just the bookmark name, which
+/// shouldn't replace a title written on the platform.
+fn has_description(bookmark_name: &str, segments: &[NarrowedBookmarkSegment]) -> bool {
+    segments
+        .iter()
+        .find(|s| s.bookmark.name == bookmark_name)
+        .and_then(|s| s.changes.last())
+        .is_some_and(|root| !root.description_first_line.is_empty())
+}
+
+/// Refuse to push bookmarks named like `never_pushed` or a protected branch
+///
+/// `never_pushed` holds the stack's base and the repository's default branch.
+/// Protected branches are looked up only when there is something to push. The
+/// lookup is best effort (it may need permissions the token lacks); the
+/// `never_pushed` check always applies.
+async fn check_protected_pushes(
+    bookmarks: &[Bookmark],
+    platform: &dyn PlatformService,
+    never_pushed: &[&str],
+) -> Result<()> {
+    if let Some(bookmark) = bookmarks
+        .iter()
+        .find(|b| never_pushed.contains(&b.name.as_str()))
+    {
+        return Err(Error::ProtectedBranch(bookmark.name.clone()));
+    }
+    if bookmarks.is_empty() {
+        return Ok(());
+    }
+
+    let protected = match platform.list_protected_branches().await {
+        Ok(protected) => protected,
+        Err(e) => {
+            tracing::debug!(error = %e, "could not list protected branches");
+            return Ok(());
+        }
+    };
+
+    for bookmark in bookmarks {
+        if protected
+            .iter()
+            .any(|pattern| branch_pattern_matches(pattern, &bookmark.name))
+        {
+            return Err(Error::ProtectedBranch(bookmark.name.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Match a branch name against a pattern (`*` matches any run of characters)
+///
+/// Used for branch protection rules and `ryu track` bookmark globs.
+pub fn branch_pattern_matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return false;
+    };
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard: exact match
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Fold PRs created by an interrupted submission back into a plan
+///
+/// Platforms can take a moment to report freshly created PRs, so a re-plan
+/// may not see them. Any `CreatePr` step for a bookmark in `created_prs` is
+/// dropped (or turned into a base update if the expected base has changed)
+/// and the PR is registered as existing for stack comment generation.
+pub fn resume_submission_plan(plan: &mut SubmissionPlan, created_prs: &[PullRequest]) {
+    for pr in created_prs {
+        plan.existing_prs
+            .entry(pr.head_ref.clone())
+            .or_insert_with(|| pr.clone());
+    }
+
+    let steps = std::mem::take(&mut plan.execution_steps);
+    plan.execution_steps = steps
+        .into_iter()
+        .filter_map(|step| {
+            let ExecutionStep::CreatePr(create) = step else {
+                return Some(step);
+            };
+            match created_prs
+                .iter()
+                .find(|pr| pr.head_ref == create.bookmark.name)
+            {
+                None => Some(ExecutionStep::CreatePr(create)),
+                Some(pr) if pr.base_ref == create.base_branch => None,
+                Some(pr) => Some(ExecutionStep::UpdateBase(PrBaseUpdate {
+                    bookmark: create.bookmark,
+                    current_base: pr.base_ref.clone(),
+                    expected_base: create.base_branch,
+                    pr: pr.clone(),
+                })),
+            }
+        })
+        .collect();
+}
+
+/// Relink closed PRs instead of creating duplicates
+///
+/// GitHub closes a PR when its head branch is deleted, so a fresh plan finds
+/// no open PR and would create a duplicate; a PR may also have been closed by
+/// hand. For each `CreatePr` step, a closed, unmerged PR for the bookmark is
+/// looked up: by its previously known number (`known_prs`, bookmark → number)
+/// if the bookmark is missing from the remote, otherwise by head branch. The
+/// step then becomes a `ReopenPr` if `reopen` agrees. The bookmark's push is
+/// already ordered before it, so the branch exists again by the time the PR is
+/// reopened.
+#[allow(clippy::implicit_hasher)]
+pub async fn relink_closed_prs(
+    plan: &mut SubmissionPlan,
+    platform: &dyn PlatformService,
+    known_prs: &HashMap<String, u64>,
+    mut reopen: impl FnMut(&PrToReopen) -> bool,
+) {
+    let candidates: Vec<(String, Option<u64>)> = plan
+        .execution_steps
+        .iter()
+        .filter_map(|step| match step {
+            ExecutionStep::CreatePr(create) => {
+                let known = if create.bookmark.has_remote {
+                    None
+                } else {
+                    known_prs.get(&create.bookmark.name).copied()
+                };
+                Some((create.bookmark.name.clone(), known))
+            }
+            _ => None,
+        })
+        .collect();
+
+    if candidates.is_empty() {
+        return;
+    }
+
+    let lookups: Vec<_> = stream::iter(candidates)
+        .map(|(bookmark, known)| async move {
+            let lookup = match known {
+                Some(number) => closed_pr_by_number(platform, number).await,
+                None => platform.find_closed_pr(&bookmark).await,
+            };
+            (bookmark, lookup)
+        })
+        .buffered(platform.max_concurrency())
+        .collect()
+        .await;
+
+    // Lookups are best effort: a failed one just means a new PR is created
+    let closed: HashMap<String, PullRequest> = lookups
+        .into_iter()
+        .filter_map(|(bookmark, lookup)| match lookup {
+            Ok(pr) => Some((bookmark, pr?)),
+            Err(e) => {
+                tracing::debug!(error = %e, bookmark, "could not look up closed PR");
+                None
+            }
+        })
+        .collect();
+
+    for step in &mut plan.execution_steps {
+        if let ExecutionStep::CreatePr(create) = step
+            && let Some(pr) = closed.get(&create.bookmark.name)
+        {
+            let candidate = PrToReopen {
+                pr: pr.clone(),
+                create: create.clone(),
+            };
+            if reopen(&candidate) {
+                *step = ExecutionStep::ReopenPr(candidate);
+            }
+        }
+    }
+}
+
+/// The PR with this number, if it's closed without being merged
+async fn closed_pr_by_number(
+    platform: &dyn PlatformService,
+    number: u64,
+) -> Result<Option<PullRequest>> {
+    let details = platform.get_pr_details(number).await?;
+    if details.state != PrState::Closed {
+        return Ok(None);
+    }
+    Ok(Some(PullRequest {
+        number: details.number,
+        html_url: details.html_url,
+        base_ref: details.base_ref,
+        head_ref: details.head_ref,
+        title: details.title,
+        node_id: None,
+        is_draft: details.is_draft,
+    }))
+}
+
+/// Whether the PR below the stack's bottom PR has merged
+///
+/// Detected as the bottom PR being retargeted from another branch onto the
+/// base branch, which happens once the branch it was stacked on is gone.
+pub fn bottom_parent_merged(plan: &SubmissionPlan) -> bool {
+    let Some(bottom) = plan.segments.first() else {
+        return false;
+    };
+    let base = plan.default_branch.as_str();
+    plan.execution_steps.iter().any(|step| {
+        matches!(step, ExecutionStep::UpdateBase(update)
+            if update.bookmark.name == bottom.bookmark.name && update.expected_base == base)
+    })
+}
+
+/// Publish the stack's bottom PR if it's a draft
+///
+/// Used once the PR below it has merged, so review moves to the new bottom
+/// of the stack. Returns the number of the PR that will be published.
+pub fn publish_bottom_draft(plan: &mut SubmissionPlan) -> Option<u64> {
+    let bottom = plan.segments.first()?;
+    let pr = plan
+        .existing_prs
+        .get(&bottom.bookmark.name)
+        .filter(|pr| pr.is_draft)?
+        .clone();
+
+    let number = pr.number;
+    let queued = plan
+        .execution_steps
+        .iter()
+        .any(|step| matches!(step, ExecutionStep::PublishPr(p) if p.number == number));
+    if !queued {
+        plan.execution_steps.push(ExecutionStep::PublishPr(pr));
+    }
+    Some(number)
+}
+
+/// Create the PRs the plan opens as drafts
+///
+/// Only PRs created by the plan become drafts. Existing PRs, including
+/// closed ones it reopens, keep their draft or ready-for-review state.
+/// Returns the number of PRs that will be created as drafts.
+pub fn create_as_drafts(plan: &mut SubmissionPlan) -> usize {
+    let mut count = 0;
+    for step in &mut plan.execution_steps {
+        if let ExecutionStep::CreatePr(create)
+        | ExecutionStep::ReopenPr(PrToReopen { create, .. }) = step
+        {
+            create.draft = true;
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Drop conventional-commit prefixes from the titles the plan sets
+///
+/// `feat(api): add login` becomes `add login`. Only titles the plan sets
+/// change: created and reopened PRs, and PRs re-titled because their
+/// commits were redescribed.
+pub fn strip_conventional_titles(plan: &mut SubmissionPlan) {
+    let strip = |title: &mut String| {
+        if let Some((_, rest)) = parse_conventional_title(title) {
+            *title = rest.to_string();
+        }
+    };
+
+    for step in &mut plan.execution_steps {
+        match step {
+            ExecutionStep::CreatePr(create)
+            | ExecutionStep::ReopenPr(PrToReopen { create, .. }) => strip(&mut create.title),
+            ExecutionStep::UpdateMetadata(update) => strip(&mut update.title),
+            _ => {}
+        }
+    }
+}
+
+/// Label each PR by the conventional-commit type of its root commit
+///
+/// `type_labels` maps types to labels, e.g. `fix` to `bug`. Segments whose
+/// root commit has no conventional prefix, or a type without a label, get
+/// none.
+pub fn add_type_labels(plan: &mut SubmissionPlan, type_labels: &BTreeMap<String, String>) {
+    if type_labels.is_empty() {
+        return;
+    }
+    for segment in &plan.segments {
+        let label = segment
+            .changes
+            .last()
+            .and_then(|root| parse_conventional_title(&root.description_first_line))
+            .and_then(|(prefix, _)| type_labels.get(prefix.kind));
+        if let Some(label) = label {
+            let labels = plan
+                .pr_labels
+                .entry(segment.bookmark.name.clone())
+                .or_default();
+            if !labels.contains(label) {
+                labels.push(label.clone());
+            }
+        }
+    }
+}
+
+/// Apply the `[submit]` settings shared by every command that submits
+///
+/// Sets how the stack overview and `ryu/stack` status are kept and whether
+/// reviews are re-requested, labels PRs by commit type with `type_labels`,
+/// re-describes PRs whose commits were redescribed since `pr_cache` recorded
+/// them, drops conventional-commit prefixes from titles with
+/// `conventional_titles`, and creates new PRs as drafts when `draft` is set
+/// (each command decides how `submit.draft` combines with its flags).
+pub fn apply_config_to_plan(
+    plan: &mut SubmissionPlan,
+    config: &SubmitConfig,
+    pr_cache: &PrCache,
+    draft: bool,
+) -> Result<()> {
+    plan.stack_in_body = config.stack_in_body;
+    plan.stack_comment = config.stack_comment;
+    plan.stack_status = config.stack_status;
+    plan.rerequest_reviews = config.rerequest_reviews;
+    add_type_labels(plan, &config.type_labels);
+    plan_metadata_updates(plan, pr_cache)?;
+    if config.conventional_titles {
+        strip_conventional_titles(plan);
+    }
+    if draft {
+        create_as_drafts(plan);
+    }
+    Ok(())
+}
+
 /// Build dependency-ordered execution steps.
 ///
 /// Returns both the constraints (for debugging/display) and the sorted execution steps.
@@ -685,6 +1278,8 @@ fn topo_sort_steps(nodes: &[ExecutionNode], edges: &[Vec<usize>]) -> Result<Vec<
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::LogEntry;
+    use chrono::Utc;
 
     fn make_bookmark(name: &str, has_remote: bool, is_synced: bool) -> Bookmark {
         Bookmark {
@@ -898,7 +1493,16 @@ mod tests {
             execution_steps: vec![],
             existing_prs: HashMap::new(),
             remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
             default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
         };
 
         assert!(plan.is_empty());
@@ -918,7 +1522,16 @@ mod tests {
             ],
             existing_prs: HashMap::new(),
             remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
             default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
         };
 
         assert!(!plan.is_empty());
@@ -927,4 +1540,346 @@ mod tests {
         assert_eq!(plan.count_updates(), 0);
         assert_eq!(plan.count_publishes(), 0);
     }
+
+    #[test]
+    fn test_remote_for_prefers_bookmark_remote() {
+        let plan = SubmissionPlan {
+            segments: vec![],
+            constraints: vec![],
+            execution_steps: vec![],
+            existing_prs: HashMap::new(),
+            remote: "origin".to_string(),
+            push_remotes: HashMap::from([("vendor".to_string(), "mirror".to_string())]),
+            default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
+        };
+
+        assert_eq!(plan.remote_for("vendor"), "mirror");
+        assert_eq!(plan.remote_for("feat-a"), "origin");
+    }
+
+    #[test]
+    fn test_publish_bottom_draft_after_parent_merged() {
+        let bm_b = make_bookmark("b", true, true);
+        let mut draft = make_pr(2, "b", "a");
+        draft.is_draft = true;
+        let mut plan = SubmissionPlan {
+            segments: vec![make_segment("b"), make_segment("c")],
+            constraints: vec![],
+            execution_steps: vec![ExecutionStep::UpdateBase(make_update(&bm_b, "a", "main", 2))],
+            existing_prs: HashMap::from([("b".to_string(), draft)]),
+            remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
+            default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
+        };
+
+        assert!(bottom_parent_merged(&plan));
+        assert_eq!(publish_bottom_draft(&mut plan), Some(2));
+        // Publishing again doesn't queue a second step
+        assert_eq!(publish_bottom_draft(&mut plan), Some(2));
+        assert_eq!(plan.count_publishes(), 1);
+    }
+
+    #[test]
+    fn test_create_as_drafts_leaves_existing_prs_alone() {
+        let bm_a = make_bookmark("a", true, false);
+        let bm_b = make_bookmark("b", false, false);
+        let mut plan = SubmissionPlan {
+            segments: vec![make_segment("a"), make_segment("b")],
+            constraints: vec![],
+            execution_steps: vec![
+                ExecutionStep::Push(bm_a.clone()),
+                ExecutionStep::UpdateBase(make_update(&bm_a, "old", "main", 1)),
+                ExecutionStep::CreatePr(make_create(&bm_b, "a")),
+            ],
+            existing_prs: HashMap::from([("a".to_string(), make_pr(1, "a", "old"))]),
+            remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
+            default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
+        };
+
+        assert_eq!(create_as_drafts(&mut plan), 1);
+        assert!(matches!(
+            &plan.execution_steps[2],
+            ExecutionStep::CreatePr(create) if create.draft
+        ));
+        assert!(!plan.existing_prs["a"].is_draft);
+        assert_eq!(plan.count_publishes(), 0);
+    }
+
+    fn make_described_segment(name: &str, title: &str) -> NarrowedBookmarkSegment {
+        NarrowedBookmarkSegment {
+            bookmark: make_bookmark(name, true, true),
+            changes: vec![LogEntry {
+                commit_id: format!("{name}_commit"),
+                change_id: format!("{name}_change"),
+                author_name: "Test".to_string(),
+                author_email: "test@example.com".to_string(),
+                description_first_line: title.to_string(),
+                description: title.to_string(),
+                parents: vec![],
+                local_bookmarks: vec![name.to_string()],
+                remote_bookmarks: vec![],
+                is_working_copy: false,
+                authored_at: Utc::now(),
+                committed_at: Utc::now(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_strip_conventional_titles_and_type_labels() {
+        let bm_c = make_bookmark("c", false, false);
+        let mut create = make_create(&bm_c, "b");
+        create.title = "fix(api): handle timeouts".to_string();
+        let mut stale = make_pr(1, "a", "main");
+        stale.title = "feat: add login".to_string();
+        let mut clean = make_pr(2, "b", "a");
+        clean.title = "add sessions".to_string();
+        let mut plan = SubmissionPlan {
+            segments: vec![
+                make_described_segment("a", "feat: add login"),
+                make_described_segment("b", "feat!: add sessions"),
+                make_described_segment("c", "fix(api): handle timeouts"),
+            ],
+            constraints: vec![],
+            execution_steps: vec![
+                ExecutionStep::CreatePr(create),
+                // Planned because the commit was redescribed
+                ExecutionStep::UpdateMetadata(PrMetadataUpdate {
+                    bookmark: make_bookmark("b", true, true),
+                    pr: clean.clone(),
+                    title: "feat!: add sessions".to_string(),
+                    body: None,
+                }),
+            ],
+            existing_prs: HashMap::from([("a".to_string(), stale), ("b".to_string(), clean)]),
+            remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
+            default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
+        };
+
+        strip_conventional_titles(&mut plan);
+        let titles: Vec<_> = plan
+            .execution_steps
+            .iter()
+            .map(|step| {
+                let title = match step {
+                    ExecutionStep::CreatePr(create) => &create.title,
+                    ExecutionStep::UpdateMetadata(update) => &update.title,
+                    _ => unreachable!(),
+                };
+                (step.bookmark_name(), title.as_str())
+            })
+            .collect();
+        // The prefixed title of "a" stays until its commit is redescribed
+        assert_eq!(
+            titles,
+            vec![("c", "handle timeouts"), ("b", "add sessions")]
+        );
+
+        let type_labels = BTreeMap::from([
+            ("fix".to_string(), "bug".to_string()),
+            ("feat".to_string(), "enhancement".to_string()),
+        ]);
+        add_type_labels(&mut plan, &type_labels);
+        assert_eq!(plan.pr_labels["a"], vec!["enhancement"]);
+        assert_eq!(plan.pr_labels["b"], vec!["enhancement"]);
+        assert_eq!(plan.pr_labels["c"], vec!["bug"]);
+    }
+
+    #[test]
+    fn test_metadata_updates_follow_redescribed_commits() {
+        let mut edited = make_pr(1, "a", "main");
+        edited.title = "Title edited on the platform".to_string();
+        let mut plan = SubmissionPlan {
+            segments: vec![
+                make_described_segment("a", "Add login"),
+                make_described_segment("b", "Add sessions"),
+                make_described_segment("c", "Add logout"),
+            ],
+            constraints: vec![],
+            execution_steps: vec![],
+            existing_prs: HashMap::from([
+                ("a".to_string(), edited),
+                ("b".to_string(), make_pr(2, "b", "a")),
+                ("c".to_string(), make_pr(3, "c", "b")),
+            ]),
+            remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
+            default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
+        };
+        let mut pr_cache = PrCache::new();
+        for (name, pr) in &plan.existing_prs {
+            pr_cache.upsert(name, pr, "origin");
+        }
+        // "a" is unchanged since ryu described it, "b" was redescribed and
+        // "c" was never described by ryu
+        pr_cache.set_description("a", "Add login");
+        pr_cache.set_description("b", "Add sesions");
+
+        plan_metadata_updates(&mut plan, &pr_cache).unwrap();
+        assert_eq!(plan.count_metadata_updates(), 1);
+        let ExecutionStep::UpdateMetadata(update) = &plan.execution_steps[0] else {
+            unreachable!()
+        };
+        assert_eq!(update.bookmark.name, "b");
+        assert_eq!(update.title, "Add sessions");
+
+        // The redescription isn't recorded until the update goes through
+        let mut result = SubmissionResult::new();
+        record_descriptions(&plan, &result, &mut pr_cache);
+        assert_eq!(
+            pr_cache.get("b").unwrap().description.as_deref(),
+            Some("Add sesions")
+        );
+        assert_eq!(
+            pr_cache.get("c").unwrap().description.as_deref(),
+            Some("Add logout")
+        );
+
+        result.described_prs.push("b".to_string());
+        record_descriptions(&plan, &result, &mut pr_cache);
+        assert_eq!(
+            pr_cache.get("b").unwrap().description.as_deref(),
+            Some("Add sessions")
+        );
+
+        plan.execution_steps.clear();
+        plan_metadata_updates(&mut plan, &pr_cache).unwrap();
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn test_apply_config_to_plan() {
+        let bm_a = make_bookmark("a", false, false);
+        let mut create = make_create(&bm_a, "main");
+        create.title = "feat: add login".to_string();
+        let mut plan = SubmissionPlan {
+            segments: vec![make_described_segment("a", "feat: add login")],
+            constraints: vec![],
+            execution_steps: vec![ExecutionStep::CreatePr(create)],
+            existing_prs: HashMap::new(),
+            remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
+            default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
+        };
+        let config = SubmitConfig {
+            stack_in_body: true,
+            stack_comment: StackCommentMode::Stacked,
+            stack_status: true,
+            rerequest_reviews: true,
+            conventional_titles: true,
+            type_labels: BTreeMap::from([("feat".to_string(), "enhancement".to_string())]),
+            ..SubmitConfig::default()
+        };
+
+        apply_config_to_plan(&mut plan, &config, &PrCache::new(), true).unwrap();
+        assert!(plan.stack_in_body);
+        assert_eq!(plan.stack_comment, StackCommentMode::Stacked);
+        assert!(plan.stack_status);
+        assert!(plan.rerequest_reviews);
+        assert_eq!(plan.pr_labels["a"], vec!["enhancement"]);
+        let ExecutionStep::CreatePr(create) = &plan.execution_steps[0] else {
+            unreachable!()
+        };
+        assert_eq!(create.title, "add login");
+        assert!(create.draft);
+
+        // Nothing is stripped or made a draft unless asked for
+        let mut create = make_create(&bm_a, "main");
+        create.title = "feat: add login".to_string();
+        plan.execution_steps = vec![ExecutionStep::CreatePr(create)];
+        apply_config_to_plan(&mut plan, &SubmitConfig::default(), &PrCache::new(), false).unwrap();
+        assert!(!plan.stack_in_body);
+        let ExecutionStep::CreatePr(create) = &plan.execution_steps[0] else {
+            unreachable!()
+        };
+        assert_eq!(create.title, "feat: add login");
+        assert!(!create.draft);
+    }
+
+    #[test]
+    fn test_publish_bottom_draft_skips_ready_prs() {
+        let mut plan = SubmissionPlan {
+            segments: vec![make_segment("a")],
+            constraints: vec![],
+            execution_steps: vec![],
+            existing_prs: HashMap::from([("a".to_string(), make_pr(1, "a", "main"))]),
+            remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
+            default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
+        };
+
+        assert!(!bottom_parent_merged(&plan));
+        assert_eq!(publish_bottom_draft(&mut plan), None);
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn test_branch_pattern_matches() {
+        assert!(branch_pattern_matches("main", "main"));
+        assert!(!branch_pattern_matches("main", "main-fix"));
+        assert!(branch_pattern_matches("release/*", "release/1.2"));
+        assert!(!branch_pattern_matches("release/*", "feat/release"));
+        assert!(branch_pattern_matches("*-stable", "2.0-stable"));
+        assert!(branch_pattern_matches("v*.x", "v1.x"));
+        assert!(!branch_pattern_matches("a*a", "a"));
+        assert!(branch_pattern_matches("feat-*", "feat-auth"));
+    }
 }
@@ -0,0 +1,321 @@
+//! Rollback for partially executed submissions
+//!
+//! With `ryu submit --atomic`, a failed submission undoes what it did in this
+//! run, in reverse dependency order:
+//! 1. Close PRs created in this run (with an explanatory comment) and re-close
+//!    PRs it reopened
+//! 2. Restore PR bases that were changed
+//! 3. Move pushed bookmarks back to their previous remote position
+//!
+//! Published drafts are left published, since not every platform can turn a
+//! PR back into a draft.
+
+use crate::error::Result;
+use crate::platform::PlatformService;
+use crate::repo::JjWorkspace;
+use crate::submit::{ExecutionStep, ProgressCallback, SubmissionPlan, SubmissionResult};
+use crate::tracking::UndoRecord;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Comment posted on PRs closed by a rollback
+pub const ROLLBACK_CLOSE_NOTE: &str =
+    "Closed automatically: this PR was created by a `ryu submit --atomic` run that failed and was rolled back.";
+
+/// Remote state captured before execution
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RollbackSnapshot {
+    /// Remote commit ID for each bookmark the plan pushes (None = not on remote)
+    pub remote_targets: HashMap<String, Option<String>>,
+}
+
+/// Outcome of a rollback
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RollbackResult {
+    /// PRs that were closed
+    pub closed_prs: Vec<u64>,
+    /// PRs whose base was restored
+    pub restored_bases: Vec<u64>,
+    /// Bookmarks moved back (or deleted) on the remote
+    pub reverted_pushes: Vec<String>,
+    /// Operations that could not be undone
+    pub errors: Vec<String>,
+}
+
+impl RollbackResult {
+    /// Check if everything was undone
+    pub const fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Record where each bookmark the plan will push currently sits on the remote
+pub fn capture_rollback_snapshot(
+    plan: &SubmissionPlan,
+    workspace: &JjWorkspace,
+) -> Result<RollbackSnapshot> {
+    let mut remote_targets = HashMap::new();
+    for step in &plan.execution_steps {
+        if let ExecutionStep::Push(bookmark) = step {
+            let current = workspace
+                .get_remote_bookmark(&bookmark.name, plan.remote_for(&bookmark.name))?
+                .map(|b| b.commit_id);
+            remote_targets.insert(bookmark.name.clone(), current);
+        }
+    }
+    Ok(RollbackSnapshot { remote_targets })
+}
+
+/// Undo the effects of a failed submission (EFFECTFUL)
+///
+/// Best effort: each undo is attempted even if earlier ones fail, and
+/// failures are collected in [`RollbackResult::errors`].
+pub async fn rollback_submission(
+    plan: &SubmissionPlan,
+    snapshot: &RollbackSnapshot,
+    result: &SubmissionResult,
+    workspace: &mut JjWorkspace,
+    platform: &dyn PlatformService,
+    progress: &dyn ProgressCallback,
+) -> RollbackResult {
+    let mut rollback = RollbackResult::default();
+
+    // 1. Close PRs created in this run
+    for pr in result.created_prs.iter().rev() {
+        progress
+            .on_message(&format!("Closing PR #{} ({})", pr.number, pr.head_ref))
+            .await;
+        // The note is informational; still close the PR if commenting fails
+        let _ = platform
+            .create_pr_comment(pr.number, ROLLBACK_CLOSE_NOTE)
+            .await;
+        match platform.close_pr(pr.number).await {
+            Ok(()) => rollback.closed_prs.push(pr.number),
+            Err(e) => rollback
+                .errors
+                .push(format!("Failed to close PR #{}: {e}", pr.number)),
+        }
+    }
+
+    let updated: Vec<u64> = result.updated_prs.iter().map(|pr| pr.number).collect();
+    for step in plan.execution_steps.iter().rev() {
+        let ExecutionStep::ReopenPr(reopen) = step else {
+            continue;
+        };
+        if !updated.contains(&reopen.pr.number) {
+            continue;
+        }
+        progress
+            .on_message(&format!("Re-closing PR #{}", reopen.pr.number))
+            .await;
+        match platform.close_pr(reopen.pr.number).await {
+            Ok(()) => rollback.closed_prs.push(reopen.pr.number),
+            Err(e) => rollback
+                .errors
+                .push(format!("Failed to close PR #{}: {e}", reopen.pr.number)),
+        }
+    }
+
+    // 2. Restore bases changed in this run
+    for step in plan.execution_steps.iter().rev() {
+        let ExecutionStep::UpdateBase(update) = step else {
+            continue;
+        };
+        if !updated.contains(&update.pr.number) {
+            continue;
+        }
+        progress
+            .on_message(&format!(
+                "Restoring PR #{} base to {}",
+                update.pr.number, update.current_base
+            ))
+            .await;
+        match platform
+            .update_pr_base(update.pr.number, &update.current_base)
+            .await
+        {
+            Ok(_) => rollback.restored_bases.push(update.pr.number),
+            Err(e) => rollback.errors.push(format!(
+                "Failed to restore base of PR #{}: {e}",
+                update.pr.number
+            )),
+        }
+    }
+
+    // 3. Revert pushes, most recent first
+    for name in result.pushed_bookmarks.iter().rev() {
+        let Some(previous) = snapshot.remote_targets.get(name) else {
+            rollback
+                .errors
+                .push(format!("No previous remote state recorded for {name}"));
+            continue;
+        };
+        let action = if previous.is_some() {
+            "Restoring"
+        } else {
+            "Deleting"
+        };
+        let remote = plan.remote_for(name);
+        progress
+            .on_message(&format!("{action} {name} on {remote}"))
+            .await;
+        match workspace.restore_remote_bookmark(name, remote, previous.as_deref()) {
+            Ok(()) => rollback.reverted_pushes.push(name.clone()),
+            Err(e) => rollback
+                .errors
+                .push(format!("Failed to revert push of {name}: {e}")),
+        }
+    }
+
+    rollback
+}
+
+/// Add what a submission changed on the remote and platform to `record`, so
+/// `ryu undo` can revert it
+///
+/// `snapshot` must have been captured before the plan was executed.
+pub fn record_undo(
+    record: &mut UndoRecord,
+    plan: &SubmissionPlan,
+    snapshot: &RollbackSnapshot,
+    result: &SubmissionResult,
+) {
+    for name in &result.pushed_bookmarks {
+        if let Some(previous) = snapshot.remote_targets.get(name) {
+            record.record_push(name, plan.remote_for(name), previous.clone());
+        }
+    }
+    record
+        .opened_prs
+        .extend(result.created_prs.iter().map(|pr| pr.number));
+
+    let updated: Vec<u64> = result.updated_prs.iter().map(|pr| pr.number).collect();
+    for step in &plan.execution_steps {
+        match step {
+            ExecutionStep::ReopenPr(reopen) if updated.contains(&reopen.pr.number) => {
+                record.opened_prs.push(reopen.pr.number);
+            }
+            ExecutionStep::UpdateBase(update) if updated.contains(&update.pr.number) => {
+                record.record_base_change(update.pr.number, &update.current_base);
+            }
+            ExecutionStep::PublishPr(pr) if updated.contains(&pr.number) => {
+                record.published_prs.push(pr.number);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::submit::{PrBaseUpdate, PrToCreate, PrToReopen};
+    use crate::tracking::{TrackingState, UndoBaseChange, UndoPush};
+    use crate::types::{Bookmark, PullRequest, StackCommentMode};
+
+    fn make_pr(number: u64, bookmark: &str) -> PullRequest {
+        PullRequest {
+            number,
+            html_url: format!("https://github.com/test/test/pull/{number}"),
+            base_ref: "main".to_string(),
+            head_ref: bookmark.to_string(),
+            title: format!("PR for {bookmark}"),
+            node_id: None,
+            is_draft: false,
+        }
+    }
+
+    fn make_bookmark(name: &str) -> Bookmark {
+        Bookmark {
+            name: name.to_string(),
+            commit_id: format!("{name}_commit"),
+            change_id: format!("{name}_change"),
+            has_remote: true,
+            is_synced: false,
+        }
+    }
+
+    fn make_base_update(number: u64, name: &str, current_base: &str) -> ExecutionStep {
+        ExecutionStep::UpdateBase(PrBaseUpdate {
+            bookmark: make_bookmark(name),
+            current_base: current_base.to_string(),
+            expected_base: "main".to_string(),
+            pr: make_pr(number, name),
+        })
+    }
+
+    #[test]
+    fn test_record_undo_records_only_completed_steps() {
+        let reopened = make_pr(4, "feat-d");
+        let plan = SubmissionPlan {
+            segments: vec![],
+            constraints: vec![],
+            execution_steps: vec![
+                ExecutionStep::Push(make_bookmark("feat-a")),
+                ExecutionStep::Push(make_bookmark("feat-b")),
+                make_base_update(1, "feat-a", "old-base"),
+                make_base_update(2, "feat-b", "feat-x"),
+                ExecutionStep::ReopenPr(PrToReopen {
+                    pr: reopened.clone(),
+                    create: PrToCreate {
+                        bookmark: make_bookmark("feat-d"),
+                        base_branch: "main".to_string(),
+                        title: reopened.title.clone(),
+                        body: None,
+                        draft: false,
+                    },
+                }),
+                ExecutionStep::PublishPr(make_pr(5, "feat-e")),
+            ],
+            existing_prs: HashMap::new(),
+            remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
+            default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
+        };
+        let snapshot = RollbackSnapshot {
+            remote_targets: HashMap::from([
+                ("feat-a".to_string(), Some("abc".to_string())),
+                ("feat-b".to_string(), None),
+            ]),
+        };
+        // feat-b's push and PR #2's base update never happened
+        let mut result = SubmissionResult::new();
+        result.pushed_bookmarks = vec!["feat-a".to_string()];
+        result.created_prs = vec![make_pr(3, "feat-c")];
+        result.updated_prs = vec![make_pr(1, "feat-a"), reopened, make_pr(5, "feat-e")];
+
+        let mut record = UndoRecord::new(
+            "submit",
+            "op".to_string(),
+            "origin".to_string(),
+            TrackingState::new(),
+        );
+        record_undo(&mut record, &plan, &snapshot, &result);
+
+        assert_eq!(
+            record.pushes,
+            vec![UndoPush {
+                bookmark: "feat-a".to_string(),
+                remote: "origin".to_string(),
+                previous: Some("abc".to_string()),
+            }]
+        );
+        assert_eq!(record.opened_prs, vec![3, 4]);
+        assert_eq!(
+            record.base_changes,
+            vec![UndoBaseChange {
+                pr: 1,
+                previous_base: "old-base".to_string(),
+            }]
+        );
+        assert_eq!(record.published_prs, vec![5]);
+    }
+}
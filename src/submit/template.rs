@@ -0,0 +1,146 @@
+//! Repository PR templates
+//!
+//! Repos with a pull request (or merge request) template get it in the body
+//! of every PR ryu creates, below the commit descriptions, so bots checking
+//! for the template's sections don't flag ryu's PRs.
+
+use crate::submit::execute::description_block;
+use crate::submit::plan::{ExecutionStep, PrToReopen, SubmissionPlan};
+use crate::types::Platform;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where GitHub looks for a pull request template, in order
+const GITHUB_TEMPLATE_PATHS: &[&str] = &[
+    ".github/PULL_REQUEST_TEMPLATE.md",
+    ".github/pull_request_template.md",
+    "PULL_REQUEST_TEMPLATE.md",
+    "pull_request_template.md",
+    "docs/PULL_REQUEST_TEMPLATE.md",
+    "docs/pull_request_template.md",
+];
+
+/// Directory of GitLab merge request templates
+const GITLAB_TEMPLATE_DIR: &str = ".gitlab/merge_request_templates";
+
+/// GitLab template used when a repo has several
+const GITLAB_DEFAULT_TEMPLATE: &str = "Default.md";
+
+/// Find the repo's PR template in the working copy
+///
+/// Looks in the platform's own locations first. GitLab repos use
+/// `Default.md` from `.gitlab/merge_request_templates/`, or the first
+/// template by name if there is no default. Empty templates are ignored.
+pub fn find_pr_template(workspace_root: &Path, platform: Platform) -> Option<String> {
+    let github = GITHUB_TEMPLATE_PATHS
+        .iter()
+        .map(|path| workspace_root.join(path));
+    let gitlab = gitlab_template(&workspace_root.join(GITLAB_TEMPLATE_DIR));
+
+    let candidates: Vec<PathBuf> = if platform == Platform::GitLab {
+        gitlab.into_iter().chain(github).collect()
+    } else {
+        github.chain(gitlab).collect()
+    };
+
+    candidates.iter().find_map(|path| {
+        let template = fs::read_to_string(path).ok()?;
+        let template = template.trim();
+        (!template.is_empty()).then(|| template.to_string())
+    })
+}
+
+/// The GitLab template to use from `dir`, if it has any
+fn gitlab_template(dir: &Path) -> Option<PathBuf> {
+    let default = dir.join(GITLAB_DEFAULT_TEMPLATE);
+    if default.is_file() {
+        return Some(default);
+    }
+
+    let mut templates: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    templates.sort();
+    templates.into_iter().next()
+}
+
+/// Body of a new PR: the commit descriptions followed by the template
+///
+/// The descriptions are kept between ryu's markers, so redescribing the
+/// commits later replaces them without touching the filled-in template.
+pub fn with_pr_template(body: Option<&str>, template: &str) -> String {
+    format!(
+        "{}\n\n{template}",
+        description_block(body.unwrap_or_default())
+    )
+}
+
+/// Add the template to the body of every PR the plan creates
+///
+/// Existing PRs keep their bodies; only their generated description is
+/// replaced when their commits are redescribed.
+pub fn apply_pr_template(plan: &mut SubmissionPlan, template: &str) {
+    for step in &mut plan.execution_steps {
+        if let ExecutionStep::CreatePr(create)
+        | ExecutionStep::ReopenPr(PrToReopen { create, .. }) = step
+        {
+            create.body = Some(with_pr_template(create.body.as_deref(), template));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_find_pr_template_prefers_platform_location() {
+        let temp = TempDir::new().unwrap();
+        write(temp.path(), ".github/pull_request_template.md", "## GitHub\n");
+        write(temp.path(), ".gitlab/merge_request_templates/Bug.md", "## Bug\n");
+        write(temp.path(), ".gitlab/merge_request_templates/Default.md", "## GitLab\n");
+
+        assert_eq!(
+            find_pr_template(temp.path(), Platform::GitHub).as_deref(),
+            Some("## GitHub")
+        );
+        assert_eq!(
+            find_pr_template(temp.path(), Platform::GitLab).as_deref(),
+            Some("## GitLab")
+        );
+    }
+
+    #[test]
+    fn test_find_pr_template_skips_empty_and_missing() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(find_pr_template(temp.path(), Platform::GitHub), None);
+
+        write(temp.path(), ".github/PULL_REQUEST_TEMPLATE.md", "  \n");
+        write(temp.path(), "docs/pull_request_template.md", "## Testing\n");
+        assert_eq!(
+            find_pr_template(temp.path(), Platform::GitHub).as_deref(),
+            Some("## Testing")
+        );
+    }
+
+    #[test]
+    fn test_with_pr_template() {
+        assert_eq!(
+            with_pr_template(None, "## Testing"),
+            "<!-- ryu:description:start -->\n<!-- ryu:description:end -->\n\n## Testing"
+        );
+        assert_eq!(
+            with_pr_template(Some("Why this change.\n"), "## Testing"),
+            "<!-- ryu:description:start -->\nWhy this change.\n<!-- ryu:description:end -->\n\n## Testing"
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! Submission journal for resuming interrupted submits.
+//!
+//! When a submission fails part-way, the PRs it already created are recorded
+//! in `.jj/repo/ryu/submit_journal.toml`. `ryu submit --continue` feeds them
+//! back into the next plan so they are reused rather than created again.
+
+use super::storage::resolve_repo_path;
+use crate::error::{Error, Result};
+use crate::types::PullRequest;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Current version of the submission journal file format.
+pub const JOURNAL_VERSION: u32 = 1;
+
+/// Filename for the submission journal.
+const JOURNAL_FILE: &str = "submit_journal.toml";
+
+/// Progress of an interrupted submission.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionJournal {
+    /// File format version.
+    pub version: u32,
+    /// Bookmark the submission targeted (None = top of stack).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bookmark: Option<String>,
+    /// Remote the submission pushed to.
+    pub remote: String,
+    /// PRs created before the failure.
+    #[serde(default)]
+    pub created_prs: Vec<PullRequest>,
+    /// Errors that stopped the submission.
+    #[serde(default)]
+    pub errors: Vec<String>,
+    /// When the journal was last written.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SubmissionJournal {
+    /// Create a journal for a submission targeting `bookmark` on `remote`.
+    pub fn new(bookmark: Option<String>, remote: String) -> Self {
+        Self {
+            version: JOURNAL_VERSION,
+            bookmark,
+            remote,
+            created_prs: Vec::new(),
+            errors: Vec::new(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Record created PRs, replacing earlier entries for the same head branch.
+    pub fn record_created(&mut self, prs: &[PullRequest]) {
+        for pr in prs {
+            self.created_prs.retain(|p| p.head_ref != pr.head_ref);
+            self.created_prs.push(pr.clone());
+        }
+        self.updated_at = Utc::now();
+    }
+}
+
+/// Get path to the submission journal file.
+pub fn journal_path(workspace_root: &Path) -> PathBuf {
+    resolve_repo_path(workspace_root)
+        .join("ryu")
+        .join(JOURNAL_FILE)
+}
+
+/// Load the submission journal, if an interrupted submission left one.
+pub fn load_journal(workspace_root: &Path) -> Result<Option<SubmissionJournal>> {
+    let path = journal_path(workspace_root);
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| Error::Tracking(format!("failed to read {}: {e}", path.display())))?;
+
+    let journal: SubmissionJournal = toml::from_str(&content)
+        .map_err(|e| Error::Tracking(format!("failed to parse {}: {e}", path.display())))?;
+
+    Ok(Some(journal))
+}
+
+/// Save the submission journal to disk.
+pub fn save_journal(workspace_root: &Path, journal: &SubmissionJournal) -> Result<()> {
+    let path = journal_path(workspace_root);
+
+    if let Some(dir) = path.parent()
+        && !dir.exists()
+    {
+        fs::create_dir_all(dir)
+            .map_err(|e| Error::Tracking(format!("failed to create {}: {e}", dir.display())))?;
+    }
+
+    let mut journal_to_save = journal.clone();
+    journal_to_save.version = JOURNAL_VERSION;
+
+    let content = toml::to_string_pretty(&journal_to_save)
+        .map_err(|e| Error::Tracking(format!("failed to serialize submission journal: {e}")))?;
+
+    let content_with_header = format!(
+        "# ryu submission journal - used by 'ryu submit --continue'\n\
+         # Removed automatically once a submission completes\n\n{content}"
+    );
+
+    fs::write(&path, content_with_header)
+        .map_err(|e| Error::Tracking(format!("failed to write {}: {e}", path.display())))?;
+
+    Ok(())
+}
+
+/// Remove the submission journal (no-op if absent).
+pub fn clear_journal(workspace_root: &Path) -> Result<()> {
+    let path = journal_path(workspace_root);
+    if path.exists() {
+        fs::remove_file(&path)
+            .map_err(|e| Error::Tracking(format!("failed to remove {}: {e}", path.display())))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup_fake_jj_workspace() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".jj").join("repo")).unwrap();
+        temp
+    }
+
+    fn make_pr(number: u64, head: &str) -> PullRequest {
+        PullRequest {
+            number,
+            html_url: format!("https://github.com/owner/repo/pull/{number}"),
+            base_ref: "main".to_string(),
+            head_ref: head.to_string(),
+            title: "Test PR".to_string(),
+            node_id: None,
+            is_draft: false,
+        }
+    }
+
+    #[test]
+    fn test_load_missing_returns_none() {
+        let temp = setup_fake_jj_workspace();
+        assert!(load_journal(temp.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_roundtrip_and_clear() {
+        let temp = setup_fake_jj_workspace();
+
+        let mut journal = SubmissionJournal::new(Some("feat-c".to_string()), "origin".into());
+        journal.record_created(&[make_pr(1, "feat-a"), make_pr(2, "feat-b")]);
+        journal.errors.push("rate limited".to_string());
+        save_journal(temp.path(), &journal).unwrap();
+
+        let loaded = load_journal(temp.path()).unwrap().unwrap();
+        assert_eq!(loaded.bookmark.as_deref(), Some("feat-c"));
+        assert_eq!(loaded.created_prs.len(), 2);
+        assert_eq!(loaded.errors, vec!["rate limited"]);
+
+        clear_journal(temp.path()).unwrap();
+        assert!(load_journal(temp.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_record_created_replaces_same_head() {
+        let mut journal = SubmissionJournal::new(None, "origin".to_string());
+        journal.record_created(&[make_pr(1, "feat-a")]);
+        journal.record_created(&[make_pr(5, "feat-a")]);
+
+        assert_eq!(journal.created_prs.len(), 1);
+        assert_eq!(journal.created_prs[0].number, 5);
+    }
+}
@@ -1,18 +1,40 @@
 //! Bookmark tracking for explicit submission management.
 //!
 //! This module provides persistence for tracking which bookmarks should be
-//! submitted to the remote platform. It stores metadata in `.jj/repo/ryu/`.
+//! submitted to the remote platform. It stores metadata in `.jj/repo/ryu/`,
+//! including the offline operation queue.
 
+mod journal;
+mod op_queue;
 mod pr_cache;
+mod restack;
 mod storage;
+mod undo;
 
+pub use journal::{
+    JOURNAL_VERSION, SubmissionJournal, clear_journal, journal_path, load_journal, save_journal,
+};
+pub use op_queue::{
+    OP_QUEUE_VERSION, OperationQueue, PendingOperation, QueuedOperation, load_op_queue,
+    op_queue_path, save_op_queue,
+};
 pub use pr_cache::{
     CachedPr, PR_CACHE_VERSION, PrCache, load_pr_cache, pr_cache_path, save_pr_cache,
 };
+pub use restack::{
+    RESTACK_VERSION, RestackState, clear_restack_state, load_restack_state, restack_state_path,
+    save_restack_state,
+};
+pub(crate) use storage::resolve_repo_path;
 pub use storage::{load_tracking, save_tracking, tracking_path};
+pub use undo::{
+    UNDO_VERSION, UndoBaseChange, UndoPush, UndoRecord, UndoStep, clear_undo_record,
+    load_undo_record, save_undo_record, undo_record_path,
+};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Current version of the tracking file format.
 pub const TRACKING_VERSION: u32 = 1;
@@ -27,6 +49,9 @@ pub struct TrackedBookmark {
     /// Optional remote to submit to (defaults to auto-detect).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub remote: Option<String>,
+    /// Branch the stack targets instead of the default branch (e.g. `release/2.3`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
     /// When this bookmark was tracked.
     pub tracked_at: DateTime<Utc>,
 }
@@ -38,6 +63,7 @@ impl TrackedBookmark {
             name,
             change_id,
             remote: None,
+            base: None,
             tracked_at: Utc::now(),
         }
     }
@@ -48,6 +74,7 @@ impl TrackedBookmark {
             name,
             change_id,
             remote: Some(remote),
+            base: None,
             tracked_at: Utc::now(),
         }
     }
@@ -100,6 +127,24 @@ impl TrackingState {
     pub fn tracked_names(&self) -> Vec<&str> {
         self.bookmarks.iter().map(|b| b.name.as_str()).collect()
     }
+
+    /// Get the base branch declared for a stack, if any.
+    ///
+    /// `names` are the stack's bookmarks from trunk to leaf; the first tracked
+    /// bookmark with a base wins.
+    pub fn stack_base<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Option<&str> {
+        names
+            .into_iter()
+            .find_map(|name| self.get(name).and_then(|b| b.base.as_deref()))
+    }
+
+    /// Remotes recorded for individual bookmarks, by bookmark name.
+    pub fn push_remotes(&self) -> HashMap<String, String> {
+        self.bookmarks
+            .iter()
+            .filter_map(|b| Some((b.name.clone(), b.remote.clone()?)))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -148,6 +193,40 @@ mod tests {
         assert!(!state.untrack("feat-auth")); // Already removed
     }
 
+    #[test]
+    fn test_stack_base_uses_lowest_declared_base() {
+        let mut state = TrackingState::new();
+        state.track(TrackedBookmark::new("feat-a".to_string(), "a".to_string()));
+        for (name, base) in [("feat-b", "release/2.3"), ("feat-c", "release/2.4")] {
+            let mut bookmark = TrackedBookmark::new(name.to_string(), name.to_string());
+            bookmark.base = Some(base.to_string());
+            state.track(bookmark);
+        }
+
+        assert_eq!(
+            state.stack_base(["feat-a", "feat-b", "feat-c"]),
+            Some("release/2.3")
+        );
+        assert_eq!(state.stack_base(["feat-a"]), None);
+        assert_eq!(state.stack_base(["untracked", "feat-c"]), Some("release/2.4"));
+    }
+
+    #[test]
+    fn test_push_remotes_only_lists_bookmarks_with_a_remote() {
+        let mut state = TrackingState::new();
+        state.track(TrackedBookmark::new("feat-a".to_string(), "a".to_string()));
+        state.track(TrackedBookmark::with_remote(
+            "feat-b".to_string(),
+            "b".to_string(),
+            "mirror".to_string(),
+        ));
+
+        assert_eq!(
+            state.push_remotes(),
+            HashMap::from([("feat-b".to_string(), "mirror".to_string())])
+        );
+    }
+
     #[test]
     fn test_tracking_state_serialization() {
         let mut state = TrackingState::new();
@@ -0,0 +1,333 @@
+//! Offline operation queue.
+//!
+//! Remote operations recorded while the network is unavailable (`ryu submit
+//! --offline` and `ryu comment --offline`) are stored in
+//! `.jj/repo/ryu/queue.toml` and replayed in order by `ryu flush`.
+
+use super::storage::resolve_repo_path;
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Current version of the operation queue file format.
+pub const OP_QUEUE_VERSION: u32 = 1;
+
+/// Filename for the operation queue.
+const OP_QUEUE_FILE: &str = "queue.toml";
+
+/// A remote operation deferred until the network is available.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PendingOperation {
+    /// Submit a stack (arguments mirror `ryu submit`).
+    Submit {
+        /// Target bookmark (defaults to top of stack).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bookmark: Option<String>,
+        /// Git remote to push to.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        remote: Option<String>,
+        /// Submit only up to this bookmark.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        upto: Option<String>,
+        /// Submit only the target bookmark.
+        #[serde(default)]
+        only: bool,
+        /// Include descendants.
+        #[serde(default)]
+        stack: bool,
+        /// Only update existing PRs.
+        #[serde(default)]
+        update_only: bool,
+        /// Create new PRs as drafts.
+        #[serde(default)]
+        draft: bool,
+        /// Publish draft PRs.
+        #[serde(default)]
+        publish: bool,
+        /// Ignore tracking.
+        #[serde(default)]
+        all: bool,
+        /// Labels to add to every PR.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        labels: Vec<String>,
+        /// Users to assign new PRs to.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        assignees: Vec<String>,
+        /// Milestone to put every PR in.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        milestone: Option<String>,
+        /// Put the stack overview in PR descriptions.
+        #[serde(default)]
+        stack_in_body: bool,
+        /// Leave the stack overview off every PR.
+        #[serde(default)]
+        no_stack_comment: bool,
+        /// Reopen closed PRs for bookmarks without asking.
+        #[serde(default)]
+        reopen: bool,
+    },
+    /// Post a comment on a bookmark's PR.
+    Comment {
+        /// Bookmark whose PR receives the comment.
+        bookmark: String,
+        /// Comment body (markdown).
+        body: String,
+        /// Git remote the PR belongs to.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        remote: Option<String>,
+    },
+}
+
+impl PendingOperation {
+    /// Short human-readable description for listings.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Submit { bookmark, .. } => bookmark
+                .as_ref()
+                .map_or_else(|| "submit".to_string(), |name| format!("submit {name}")),
+            Self::Comment { bookmark, .. } => format!("comment on {bookmark}"),
+        }
+    }
+}
+
+/// A queued operation with metadata.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QueuedOperation {
+    /// The deferred operation.
+    #[serde(flatten)]
+    pub operation: PendingOperation,
+    /// When the operation was queued.
+    pub queued_at: DateTime<Utc>,
+}
+
+/// Offline operation queue state.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OperationQueue {
+    /// File format version.
+    pub version: u32,
+    /// Operations in the order they were queued.
+    #[serde(default)]
+    pub operations: Vec<QueuedOperation>,
+}
+
+impl OperationQueue {
+    /// Create a new empty queue.
+    pub const fn new() -> Self {
+        Self {
+            version: OP_QUEUE_VERSION,
+            operations: Vec::new(),
+        }
+    }
+
+    /// Append an operation to the end of the queue.
+    pub fn push(&mut self, operation: PendingOperation) {
+        self.operations.push(QueuedOperation {
+            operation,
+            queued_at: Utc::now(),
+        });
+    }
+
+    /// Remove and return the oldest operation.
+    pub fn pop_front(&mut self) -> Option<QueuedOperation> {
+        if self.operations.is_empty() {
+            None
+        } else {
+            Some(self.operations.remove(0))
+        }
+    }
+
+    /// Check if the queue has no pending operations.
+    pub const fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Number of pending operations.
+    pub const fn len(&self) -> usize {
+        self.operations.len()
+    }
+}
+
+/// Get path to the operation queue file.
+pub fn op_queue_path(workspace_root: &Path) -> PathBuf {
+    resolve_repo_path(workspace_root)
+        .join("ryu")
+        .join(OP_QUEUE_FILE)
+}
+
+/// Load the operation queue from disk.
+///
+/// Returns an empty `OperationQueue` if the file doesn't exist.
+pub fn load_op_queue(workspace_root: &Path) -> Result<OperationQueue> {
+    let path = op_queue_path(workspace_root);
+
+    if !path.exists() {
+        return Ok(OperationQueue::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| Error::Tracking(format!("failed to read {}: {e}", path.display())))?;
+
+    let queue: OperationQueue = toml::from_str(&content)
+        .map_err(|e| Error::Tracking(format!("failed to parse {}: {e}", path.display())))?;
+
+    Ok(queue)
+}
+
+/// Save the operation queue to disk.
+///
+/// Removes the file when the queue is empty.
+pub fn save_op_queue(workspace_root: &Path, queue: &OperationQueue) -> Result<()> {
+    let path = op_queue_path(workspace_root);
+
+    if queue.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| Error::Tracking(format!("failed to remove {}: {e}", path.display())))?;
+        }
+        return Ok(());
+    }
+
+    if let Some(dir) = path.parent()
+        && !dir.exists()
+    {
+        fs::create_dir_all(dir)
+            .map_err(|e| Error::Tracking(format!("failed to create {}: {e}", dir.display())))?;
+    }
+
+    let mut queue_to_save = queue.clone();
+    queue_to_save.version = OP_QUEUE_VERSION;
+
+    let content = toml::to_string_pretty(&queue_to_save)
+        .map_err(|e| Error::Tracking(format!("failed to serialize operation queue: {e}")))?;
+
+    let content_with_header = format!(
+        "# ryu offline operation queue - replayed by 'ryu flush'\n\
+         # Deleting this file discards pending operations\n\n{content}"
+    );
+
+    fs::write(&path, content_with_header)
+        .map_err(|e| Error::Tracking(format!("failed to write {}: {e}", path.display())))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup_fake_jj_workspace() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".jj").join("repo")).unwrap();
+        temp
+    }
+
+    fn submit_op(bookmark: &str) -> PendingOperation {
+        PendingOperation::Submit {
+            bookmark: Some(bookmark.to_string()),
+            remote: None,
+            upto: None,
+            only: false,
+            stack: false,
+            update_only: false,
+            draft: true,
+            publish: false,
+            all: false,
+            labels: vec![],
+            assignees: vec![],
+            milestone: None,
+            stack_in_body: false,
+            no_stack_comment: false,
+            reopen: false,
+        }
+    }
+
+    #[test]
+    fn test_load_missing_file_returns_empty() {
+        let temp = setup_fake_jj_workspace();
+        let queue = load_op_queue(temp.path()).unwrap();
+        assert!(queue.is_empty());
+        assert_eq!(queue.version, OP_QUEUE_VERSION);
+    }
+
+    #[test]
+    fn test_roundtrip_preserves_order() {
+        let temp = setup_fake_jj_workspace();
+
+        let mut queue = OperationQueue::new();
+        queue.push(submit_op("feat-a"));
+        queue.push(PendingOperation::Comment {
+            bookmark: "feat-a".to_string(),
+            body: "LGTM after rebase".to_string(),
+            remote: Some("upstream".to_string()),
+        });
+
+        save_op_queue(temp.path(), &queue).unwrap();
+        let loaded = load_op_queue(temp.path()).unwrap();
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.operations[0].operation, submit_op("feat-a"));
+        assert_eq!(loaded.operations[1].operation.describe(), "comment on feat-a");
+    }
+
+    #[test]
+    fn test_submit_reopen_survives_roundtrip() {
+        let temp = setup_fake_jj_workspace();
+
+        let mut op = submit_op("feat-a");
+        if let PendingOperation::Submit { reopen, .. } = &mut op {
+            *reopen = true;
+        }
+        let mut queue = OperationQueue::new();
+        queue.push(op.clone());
+        save_op_queue(temp.path(), &queue).unwrap();
+
+        let loaded = load_op_queue(temp.path()).unwrap();
+        assert_eq!(loaded.operations[0].operation, op);
+    }
+
+    #[test]
+    fn test_submit_without_reopen_field_defaults_to_false() {
+        let content = r#"
+            version = 1
+
+            [[operations]]
+            kind = "submit"
+            bookmark = "feat-a"
+            queued_at = "2026-01-01T00:00:00Z"
+        "#;
+        let queue: OperationQueue = toml::from_str(content).unwrap();
+        assert!(matches!(
+            queue.operations[0].operation,
+            PendingOperation::Submit { reopen: false, .. }
+        ));
+    }
+
+    #[test]
+    fn test_pop_front() {
+        let mut queue = OperationQueue::new();
+        queue.push(submit_op("feat-a"));
+        queue.push(submit_op("feat-b"));
+
+        let first = queue.pop_front().unwrap();
+        assert_eq!(first.operation.describe(), "submit feat-a");
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[test]
+    fn test_save_empty_removes_file() {
+        let temp = setup_fake_jj_workspace();
+
+        let mut queue = OperationQueue::new();
+        queue.push(submit_op("feat-a"));
+        save_op_queue(temp.path(), &queue).unwrap();
+        assert!(op_queue_path(temp.path()).exists());
+
+        queue.pop_front();
+        save_op_queue(temp.path(), &queue).unwrap();
+        assert!(!op_queue_path(temp.path()).exists());
+    }
+}
@@ -26,8 +26,22 @@ pub struct CachedPr {
     pub number: u64,
     /// Web URL for the PR.
     pub url: String,
+    /// PR title when last seen (empty in caches written before it was kept).
+    #[serde(default)]
+    pub title: String,
+    /// Base branch when last seen (empty in caches written before it was kept).
+    #[serde(default)]
+    pub base: String,
     /// Remote this PR was pushed to.
     pub remote: String,
+    /// Gerrit Change-Id, for entries tracking a single change of the
+    /// bookmark's segment rather than the bookmark's PR.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_id: Option<String>,
+    /// Commit descriptions of the bookmark's segment when ryu last set the
+    /// PR's title and body from them, to tell when they're redescribed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     /// When this cache entry was last updated.
     pub updated_at: DateTime<Utc>,
 }
@@ -53,7 +67,9 @@ impl PrCache {
 
     /// Get cached PR for a bookmark.
     pub fn get(&self, bookmark: &str) -> Option<&CachedPr> {
-        self.prs.iter().find(|p| p.bookmark == bookmark)
+        self.prs
+            .iter()
+            .find(|p| p.bookmark == bookmark && p.change_id.is_none())
     }
 
     /// Update or insert a PR cache entry.
@@ -62,17 +78,78 @@ impl PrCache {
             bookmark: bookmark.to_string(),
             number: pr.number,
             url: pr.html_url.clone(),
+            title: pr.title.clone(),
+            base: pr.base_ref.clone(),
             remote: remote.to_string(),
+            change_id: None,
+            description: None,
             updated_at: Utc::now(),
         };
 
-        if let Some(existing) = self.prs.iter_mut().find(|p| p.bookmark == bookmark) {
+        if let Some(existing) = self
+            .prs
+            .iter_mut()
+            .find(|p| p.bookmark == bookmark && p.change_id.is_none())
+        {
+            // The same PR keeps the description it was submitted with
+            let description = existing
+                .description
+                .take()
+                .filter(|_| existing.number == pr.number);
+            *existing = CachedPr {
+                description,
+                ..entry
+            };
+        } else {
+            self.prs.push(entry);
+        }
+    }
+
+    /// Get the cached Gerrit change with a Change-Id.
+    pub fn get_change(&self, change_id: &str) -> Option<&CachedPr> {
+        self.prs
+            .iter()
+            .find(|p| p.change_id.as_deref() == Some(change_id))
+    }
+
+    /// Update or insert the entry for a Gerrit change in `bookmark`'s segment.
+    pub fn upsert_change(&mut self, bookmark: &str, change_id: &str, pr: &PullRequest, remote: &str) {
+        let entry = CachedPr {
+            bookmark: bookmark.to_string(),
+            number: pr.number,
+            url: pr.html_url.clone(),
+            title: pr.title.clone(),
+            base: pr.base_ref.clone(),
+            remote: remote.to_string(),
+            change_id: Some(change_id.to_string()),
+            description: None,
+            updated_at: Utc::now(),
+        };
+
+        if let Some(existing) = self
+            .prs
+            .iter_mut()
+            .find(|p| p.change_id.as_deref() == Some(change_id))
+        {
             *existing = entry;
         } else {
             self.prs.push(entry);
         }
     }
 
+    /// Record the commit descriptions a bookmark's PR was last described from.
+    ///
+    /// Does nothing if the bookmark has no cached PR.
+    pub fn set_description(&mut self, bookmark: &str, description: &str) {
+        if let Some(entry) = self
+            .prs
+            .iter_mut()
+            .find(|p| p.bookmark == bookmark && p.change_id.is_none())
+        {
+            entry.description = Some(description.to_string());
+        }
+    }
+
     /// Remove a bookmark's PR cache entry.
     pub fn remove(&mut self, bookmark: &str) -> bool {
         let len_before = self.prs.len();
@@ -204,6 +281,42 @@ mod tests {
         assert_eq!(cached.remote, "upstream");
     }
 
+    #[test]
+    fn test_description_survives_upsert_of_same_pr() {
+        let mut cache = PrCache::new();
+        cache.set_description("feat-auth", "Add login");
+        assert!(cache.get("feat-auth").is_none());
+
+        cache.upsert("feat-auth", &make_test_pr(123), "origin");
+        cache.set_description("feat-auth", "Add login");
+        cache.upsert("feat-auth", &make_test_pr(123), "origin");
+        assert_eq!(
+            cache.get("feat-auth").unwrap().description.as_deref(),
+            Some("Add login")
+        );
+
+        // A new PR for the bookmark wasn't described yet
+        cache.upsert("feat-auth", &make_test_pr(456), "origin");
+        assert_eq!(cache.get("feat-auth").unwrap().description, None);
+    }
+
+    #[test]
+    fn test_gerrit_changes_are_kept_apart_from_bookmark_prs() {
+        let mut cache = PrCache::new();
+        cache.upsert_change("feat-auth", "Iabc", &make_test_pr(7), "origin");
+        assert!(cache.get("feat-auth").is_none());
+
+        cache.upsert("feat-auth", &make_test_pr(8), "origin");
+        cache.upsert_change("feat-auth", "Iabc", &make_test_pr(9), "origin");
+
+        assert_eq!(cache.get("feat-auth").unwrap().number, 8);
+        assert_eq!(cache.get_change("Iabc").unwrap().number, 9);
+        assert_eq!(cache.prs.len(), 2);
+
+        assert!(cache.remove("feat-auth"));
+        assert!(cache.prs.is_empty());
+    }
+
     #[test]
     fn test_remove() {
         let mut cache = PrCache::new();
@@ -251,6 +364,34 @@ mod tests {
         let db = loaded.get("feat-db").unwrap();
         assert_eq!(db.number, 124);
         assert_eq!(db.remote, "upstream");
+        assert_eq!(db.title, "Test PR");
+        assert_eq!(db.base, "main");
+    }
+
+    #[test]
+    fn test_load_cache_without_title_and_base() {
+        let temp = setup_fake_jj_workspace();
+        let path = pr_cache_path(temp.path());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            r#"version = 1
+
+[[prs]]
+bookmark = "feat-auth"
+number = 123
+url = "https://github.com/owner/repo/pull/123"
+remote = "origin"
+updated_at = "2024-01-01T00:00:00Z"
+"#,
+        )
+        .unwrap();
+
+        let cached = load_pr_cache(temp.path()).unwrap();
+        let auth = cached.get("feat-auth").unwrap();
+        assert_eq!(auth.number, 123);
+        assert!(auth.title.is_empty());
+        assert!(auth.base.is_empty());
     }
 
     #[test]
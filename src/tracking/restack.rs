@@ -0,0 +1,137 @@
+//! State of a sync paused on rebase conflicts.
+//!
+//! When rebasing the stack (after a merge, or while pulling reviewer
+//! commits) leaves conflicts, nothing is pushed and the conflicted bookmarks
+//! are recorded in `.jj/repo/ryu/restack.toml`. `ryu sync --continue` picks
+//! up from there once they are resolved.
+
+use super::storage::resolve_repo_path;
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Current version of the restack state file format.
+pub const RESTACK_VERSION: u32 = 1;
+
+/// Filename for the restack state.
+const RESTACK_FILE: &str = "restack.toml";
+
+/// A sync waiting for conflicts to be resolved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestackState {
+    /// File format version.
+    pub version: u32,
+    /// Remote the stack syncs with.
+    pub remote: String,
+    /// Branch the stack was rebased onto.
+    pub base: String,
+    /// Bookmarks whose changes had conflicts, bottom of the stack first.
+    #[serde(default)]
+    pub conflicted: Vec<String>,
+    /// When the sync was paused.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RestackState {
+    /// Record a sync with `remote` paused on conflicts in `conflicted`.
+    pub fn new(remote: String, base: String, conflicted: Vec<String>) -> Self {
+        Self {
+            version: RESTACK_VERSION,
+            remote,
+            base,
+            conflicted,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Get path to the restack state file.
+pub fn restack_state_path(workspace_root: &Path) -> PathBuf {
+    resolve_repo_path(workspace_root)
+        .join("ryu")
+        .join(RESTACK_FILE)
+}
+
+/// Load the restack state, if a sync is paused on conflicts.
+pub fn load_restack_state(workspace_root: &Path) -> Result<Option<RestackState>> {
+    let path = restack_state_path(workspace_root);
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| Error::Tracking(format!("failed to read {}: {e}", path.display())))?;
+
+    let state: RestackState = toml::from_str(&content)
+        .map_err(|e| Error::Tracking(format!("failed to parse {}: {e}", path.display())))?;
+
+    Ok(Some(state))
+}
+
+/// Save the restack state to disk.
+pub fn save_restack_state(workspace_root: &Path, state: &RestackState) -> Result<()> {
+    let path = restack_state_path(workspace_root);
+
+    if let Some(dir) = path.parent()
+        && !dir.exists()
+    {
+        fs::create_dir_all(dir)
+            .map_err(|e| Error::Tracking(format!("failed to create {}: {e}", dir.display())))?;
+    }
+
+    let mut state_to_save = state.clone();
+    state_to_save.version = RESTACK_VERSION;
+
+    let content = toml::to_string_pretty(&state_to_save)
+        .map_err(|e| Error::Tracking(format!("failed to serialize restack state: {e}")))?;
+
+    let content_with_header = format!(
+        "# ryu restack state - used by 'ryu sync --continue'\n\
+         # Removed automatically once the conflicts are resolved and synced\n\n{content}"
+    );
+
+    fs::write(&path, content_with_header)
+        .map_err(|e| Error::Tracking(format!("failed to write {}: {e}", path.display())))?;
+
+    Ok(())
+}
+
+/// Remove the restack state (no-op if absent).
+pub fn clear_restack_state(workspace_root: &Path) -> Result<()> {
+    let path = restack_state_path(workspace_root);
+    if path.exists() {
+        fs::remove_file(&path)
+            .map_err(|e| Error::Tracking(format!("failed to remove {}: {e}", path.display())))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_roundtrip_and_clear() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".jj").join("repo")).unwrap();
+        assert!(load_restack_state(temp.path()).unwrap().is_none());
+
+        let state = RestackState::new(
+            "origin".to_string(),
+            "main".to_string(),
+            vec!["feat-b".to_string()],
+        );
+        save_restack_state(temp.path(), &state).unwrap();
+
+        let loaded = load_restack_state(temp.path()).unwrap().unwrap();
+        assert_eq!(loaded.remote, "origin");
+        assert_eq!(loaded.conflicted, vec!["feat-b"]);
+
+        clear_restack_state(temp.path()).unwrap();
+        assert!(load_restack_state(temp.path()).unwrap().is_none());
+    }
+}
@@ -19,7 +19,7 @@ const TRACKING_FILE: &str = "tracked.toml";
 /// and use its contents as the actual repo path.
 ///
 /// Falls back to the original path if resolution fails.
-pub(super) fn resolve_repo_path(workspace_root: &Path) -> PathBuf {
+pub fn resolve_repo_path(workspace_root: &Path) -> PathBuf {
     let repo_path = workspace_root.join(".jj").join("repo");
 
     // In jj workspaces, .jj/repo may be a file containing the path to the real repo
@@ -0,0 +1,361 @@
+//! Undo record for the last ryu command that changed the repo.
+//!
+//! Before a command fetches, rebases, pushes or deletes bookmarks, the jj
+//! operation it started from is recorded in `.jj/repo/ryu/undo.toml`, along
+//! with the tracking state. The platform changes it makes are added as it
+//! runs, so `ryu undo` can restore the operation and revert those changes
+//! where the platform allows it.
+
+use super::TrackingState;
+use super::storage::resolve_repo_path;
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Current version of the undo record file format.
+pub const UNDO_VERSION: u32 = 1;
+
+/// Filename for the undo record.
+const UNDO_FILE: &str = "undo.toml";
+
+/// A bookmark the command moved or deleted on a remote.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UndoPush {
+    /// Bookmark name.
+    pub bookmark: String,
+    /// Remote the bookmark was pushed to.
+    pub remote: String,
+    /// Commit the bookmark was at on the remote before (None = not on remote).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<String>,
+}
+
+/// A PR whose base branch the command changed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UndoBaseChange {
+    /// PR number.
+    pub pr: u64,
+    /// Base branch before the command.
+    pub previous_base: String,
+}
+
+/// One platform or remote change `ryu undo` reverts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UndoStep {
+    /// Close a PR the command created or reopened.
+    ClosePr(u64),
+    /// Turn a PR the command published back into a draft.
+    UnpublishPr(u64),
+    /// Point a PR back at its previous base.
+    RestoreBase(UndoBaseChange),
+    /// Move a bookmark on a remote back, or delete it.
+    RestorePush(UndoPush),
+    /// Reopen a PR the command closed.
+    ReopenPr(u64),
+}
+
+/// What the last ryu command changed, and where the repo was before it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoRecord {
+    /// File format version.
+    pub version: u32,
+    /// Command that made the changes (e.g. "sync").
+    pub command: String,
+    /// jj operation the repo was at before the command.
+    pub operation_id: String,
+    /// jj operation the command left the repo at (None until it finishes).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_operation_id: Option<String>,
+    /// Remote whose platform the command changed.
+    pub remote: String,
+    /// Tracking state before the command.
+    #[serde(default)]
+    pub tracking: TrackingState,
+    /// Bookmarks pushed or deleted on a remote.
+    #[serde(default)]
+    pub pushes: Vec<UndoPush>,
+    /// PRs created or reopened.
+    #[serde(default)]
+    pub opened_prs: Vec<u64>,
+    /// PRs closed.
+    #[serde(default)]
+    pub closed_prs: Vec<u64>,
+    /// Draft PRs published.
+    #[serde(default)]
+    pub published_prs: Vec<u64>,
+    /// PRs whose base branch changed.
+    #[serde(default)]
+    pub base_changes: Vec<UndoBaseChange>,
+    /// Bookmarks whose PRs were merged (can't be undone).
+    #[serde(default)]
+    pub merged_bookmarks: Vec<String>,
+    /// When the command started.
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl UndoRecord {
+    /// Start a record for `command` on `remote`, run from `operation_id`
+    /// with `tracking`.
+    pub fn new(
+        command: &str,
+        operation_id: String,
+        remote: String,
+        tracking: TrackingState,
+    ) -> Self {
+        Self {
+            version: UNDO_VERSION,
+            command: command.to_string(),
+            operation_id,
+            finished_operation_id: None,
+            remote,
+            tracking,
+            pushes: Vec::new(),
+            opened_prs: Vec::new(),
+            closed_prs: Vec::new(),
+            published_prs: Vec::new(),
+            base_changes: Vec::new(),
+            merged_bookmarks: Vec::new(),
+            recorded_at: Utc::now(),
+        }
+    }
+
+    /// Record a push of `bookmark` to `remote`, which was at `previous`.
+    ///
+    /// A bookmark pushed twice keeps its first previous position.
+    pub fn record_push(&mut self, bookmark: &str, remote: &str, previous: Option<String>) {
+        if self
+            .pushes
+            .iter()
+            .any(|p| p.bookmark == bookmark && p.remote == remote)
+        {
+            return;
+        }
+        self.pushes.push(UndoPush {
+            bookmark: bookmark.to_string(),
+            remote: remote.to_string(),
+            previous,
+        });
+    }
+
+    /// Record a change of the base of PR `pr` from `previous_base`.
+    ///
+    /// A base changed twice keeps its first previous branch.
+    pub fn record_base_change(&mut self, pr: u64, previous_base: &str) {
+        if self.base_changes.iter().any(|c| c.pr == pr) {
+            return;
+        }
+        self.base_changes.push(UndoBaseChange {
+            pr,
+            previous_base: previous_base.to_string(),
+        });
+    }
+
+    /// The steps that revert the command's platform and remote changes, in
+    /// the order to take them.
+    ///
+    /// PRs are closed before their branches are deleted, and branches are
+    /// restored before their PRs are reopened. Opened PRs, bases and pushes
+    /// are reverted latest first.
+    pub fn undo_steps(&self) -> Vec<UndoStep> {
+        let opened = self.opened_prs.iter().rev().map(|&n| UndoStep::ClosePr(n));
+        let published = self.published_prs.iter().map(|&n| UndoStep::UnpublishPr(n));
+        let bases = self
+            .base_changes
+            .iter()
+            .rev()
+            .cloned()
+            .map(UndoStep::RestoreBase);
+        let pushes = self.pushes.iter().rev().cloned().map(UndoStep::RestorePush);
+        let closed = self.closed_prs.iter().map(|&n| UndoStep::ReopenPr(n));
+        opened
+            .chain(published)
+            .chain(bases)
+            .chain(pushes)
+            .chain(closed)
+            .collect()
+    }
+
+    /// Check if the command changed anything on the platform or a remote.
+    pub const fn has_remote_changes(&self) -> bool {
+        !(self.pushes.is_empty()
+            && self.opened_prs.is_empty()
+            && self.closed_prs.is_empty()
+            && self.published_prs.is_empty()
+            && self.base_changes.is_empty()
+            && self.merged_bookmarks.is_empty())
+    }
+}
+
+/// Get path to the undo record file.
+pub fn undo_record_path(workspace_root: &Path) -> PathBuf {
+    resolve_repo_path(workspace_root)
+        .join("ryu")
+        .join(UNDO_FILE)
+}
+
+/// Load the undo record, if a command left one.
+pub fn load_undo_record(workspace_root: &Path) -> Result<Option<UndoRecord>> {
+    let path = undo_record_path(workspace_root);
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| Error::Tracking(format!("failed to read {}: {e}", path.display())))?;
+
+    let record: UndoRecord = toml::from_str(&content)
+        .map_err(|e| Error::Tracking(format!("failed to parse {}: {e}", path.display())))?;
+
+    Ok(Some(record))
+}
+
+/// Save the undo record to disk.
+pub fn save_undo_record(workspace_root: &Path, record: &UndoRecord) -> Result<()> {
+    let path = undo_record_path(workspace_root);
+
+    if let Some(dir) = path.parent()
+        && !dir.exists()
+    {
+        fs::create_dir_all(dir)
+            .map_err(|e| Error::Tracking(format!("failed to create {}: {e}", dir.display())))?;
+    }
+
+    let mut record_to_save = record.clone();
+    record_to_save.version = UNDO_VERSION;
+
+    let content = toml::to_string_pretty(&record_to_save)
+        .map_err(|e| Error::Tracking(format!("failed to serialize undo record: {e}")))?;
+
+    let content_with_header = format!(
+        "# ryu undo record - used by 'ryu undo'\n\
+         # Replaced by each command that changes the repo\n\n{content}"
+    );
+
+    fs::write(&path, content_with_header)
+        .map_err(|e| Error::Tracking(format!("failed to write {}: {e}", path.display())))?;
+
+    Ok(())
+}
+
+/// Remove the undo record (no-op if absent).
+pub fn clear_undo_record(workspace_root: &Path) -> Result<()> {
+    let path = undo_record_path(workspace_root);
+    if path.exists() {
+        fs::remove_file(&path)
+            .map_err(|e| Error::Tracking(format!("failed to remove {}: {e}", path.display())))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracking::TrackedBookmark;
+    use tempfile::TempDir;
+
+    fn setup_fake_jj_workspace() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".jj").join("repo")).unwrap();
+        temp
+    }
+
+    #[test]
+    fn test_roundtrip_and_clear() {
+        let temp = setup_fake_jj_workspace();
+        assert!(load_undo_record(temp.path()).unwrap().is_none());
+
+        let mut tracking = TrackingState::new();
+        tracking.track(TrackedBookmark::new("feat-a".to_string(), "a".to_string()));
+        let mut record = UndoRecord::new(
+            "sync",
+            "0123abcd".to_string(),
+            "origin".to_string(),
+            tracking,
+        );
+        record.record_push("feat-a", "origin", Some("deadbeef".to_string()));
+        record.record_push("feat-b", "origin", None);
+        record.opened_prs.push(7);
+        record.record_base_change(6, "main");
+        save_undo_record(temp.path(), &record).unwrap();
+
+        let loaded = load_undo_record(temp.path()).unwrap().unwrap();
+        assert_eq!(loaded.command, "sync");
+        assert_eq!(loaded.operation_id, "0123abcd");
+        assert!(loaded.tracking.is_tracked("feat-a"));
+        assert_eq!(loaded.pushes, record.pushes);
+        assert_eq!(loaded.opened_prs, vec![7]);
+        assert_eq!(loaded.base_changes, record.base_changes);
+        assert!(loaded.has_remote_changes());
+
+        clear_undo_record(temp.path()).unwrap();
+        assert!(load_undo_record(temp.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_undo_steps_order() {
+        let mut record = UndoRecord::new(
+            "submit",
+            "op".to_string(),
+            "origin".to_string(),
+            TrackingState::new(),
+        );
+        record.closed_prs.push(9);
+        record.record_push("feat-a", "origin", None);
+        record.record_push("feat-b", "origin", Some("abc".to_string()));
+        record.record_base_change(5, "main");
+        record.record_base_change(6, "feat-a");
+        record.published_prs.push(4);
+        record.opened_prs.extend([1, 2]);
+
+        let push = |bookmark: &str, previous: Option<&str>| {
+            UndoStep::RestorePush(UndoPush {
+                bookmark: bookmark.to_string(),
+                remote: "origin".to_string(),
+                previous: previous.map(String::from),
+            })
+        };
+        let base = |pr, previous_base: &str| {
+            UndoStep::RestoreBase(UndoBaseChange {
+                pr,
+                previous_base: previous_base.to_string(),
+            })
+        };
+        assert_eq!(
+            record.undo_steps(),
+            vec![
+                UndoStep::ClosePr(2),
+                UndoStep::ClosePr(1),
+                UndoStep::UnpublishPr(4),
+                base(6, "feat-a"),
+                base(5, "main"),
+                push("feat-b", Some("abc")),
+                push("feat-a", None),
+                UndoStep::ReopenPr(9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_repeated_changes_keep_first_previous_state() {
+        let mut record = UndoRecord::new(
+            "merge",
+            "op".to_string(),
+            "origin".to_string(),
+            TrackingState::new(),
+        );
+        assert!(!record.has_remote_changes());
+
+        record.record_push("feat-a", "origin", Some("first".to_string()));
+        record.record_push("feat-a", "origin", Some("second".to_string()));
+        record.record_base_change(3, "feat-a");
+        record.record_base_change(3, "main");
+
+        assert_eq!(record.pushes.len(), 1);
+        assert_eq!(record.pushes[0].previous.as_deref(), Some("first"));
+        assert_eq!(record.base_changes.len(), 1);
+        assert_eq!(record.base_changes[0].previous_base, "feat-a");
+    }
+}
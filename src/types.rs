@@ -49,7 +49,7 @@ pub struct LogEntry {
 }
 
 /// A segment of changes belonging to one or more bookmarks
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BookmarkSegment {
     /// Bookmarks pointing to the tip of this segment
     pub bookmarks: Vec<Bookmark>,
@@ -58,7 +58,7 @@ pub struct BookmarkSegment {
 }
 
 /// A segment narrowed to a single bookmark (after user selection)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NarrowedBookmarkSegment {
     /// The selected bookmark for this segment
     pub bookmark: Bookmark,
@@ -67,7 +67,7 @@ pub struct NarrowedBookmarkSegment {
 }
 
 /// A stack of bookmarks from trunk to a leaf
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BranchStack {
     /// Segments from trunk (index 0) to leaf (last index)
     pub segments: Vec<BookmarkSegment>,
@@ -77,7 +77,7 @@ pub struct BranchStack {
 ///
 /// Represents the single linear stack from trunk to working copy.
 /// Only bookmarks between trunk and working copy are included.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ChangeGraph {
     /// All bookmarks in the stack by name
     pub bookmarks: HashMap<String, Bookmark>,
@@ -131,6 +131,12 @@ pub enum Platform {
     GitHub,
     /// GitLab or self-hosted GitLab
     GitLab,
+    /// Gerrit Code Review (one change per commit)
+    Gerrit,
+    /// A platform registered by another crate, by name (see
+    /// [`crate::platform::register_platform`])
+    #[serde(skip_deserializing)]
+    Custom(&'static str),
 }
 
 impl std::fmt::Display for Platform {
@@ -138,6 +144,8 @@ impl std::fmt::Display for Platform {
         match self {
             Self::GitHub => write!(f, "GitHub"),
             Self::GitLab => write!(f, "GitLab"),
+            Self::Gerrit => write!(f, "Gerrit"),
+            Self::Custom(name) => write!(f, "{name}"),
         }
     }
 }
@@ -200,22 +208,140 @@ pub struct PullRequestDetails {
     pub mergeable: Option<bool>,
     /// Head branch name
     pub head_ref: String,
+    /// Commit SHA at the tip of the head branch
+    #[serde(default)]
+    pub head_sha: String,
     /// Base branch name
     pub base_ref: String,
     /// Web URL for the PR/MR
     pub html_url: String,
+    /// Whether the head branch must be brought up to date with the base
+    /// before the platform allows merging (strict branch protection)
+    #[serde(default)]
+    pub is_behind: bool,
+}
+
+/// State of a CI check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckState {
+    /// Queued or still running
+    Pending,
+    /// Completed successfully
+    Success,
+    /// Completed without a verdict (skipped, neutral, manual)
+    Skipped,
+    /// Failed, errored, cancelled or timed out
+    Failure,
+}
+
+impl CheckState {
+    /// Whether this state counts as passing for merge purposes
+    pub const fn is_passing(self) -> bool {
+        matches!(self, Self::Success | Self::Skipped)
+    }
+}
+
+impl std::fmt::Display for CheckState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pending => write!(f, "pending"),
+            Self::Success => write!(f, "success"),
+            Self::Skipped => write!(f, "skipped"),
+            Self::Failure => write!(f, "failure"),
+        }
+    }
+}
+
+/// A single CI check on a PR's head commit
+///
+/// GitHub: a check run or commit status context. GitLab: a pipeline job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckInfo {
+    /// Check name (check run name, status context or job name)
+    pub name: String,
+    /// Current state
+    pub state: CheckState,
+    /// Whether the check must pass before merging
+    pub required: bool,
+    /// Link to the check's details, if available
+    pub url: Option<String>,
+}
+
+/// State of a PR review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewState {
+    /// Reviewer approved the PR
+    Approved,
+    /// Reviewer requested changes
+    ChangesRequested,
+    /// Reviewer left comments without a verdict
+    Commented,
+    /// An earlier approval or change request was dismissed
+    Dismissed,
+    /// Review started but not submitted yet
+    Pending,
+}
+
+impl std::fmt::Display for ReviewState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Approved => write!(f, "approved"),
+            Self::ChangesRequested => write!(f, "changes requested"),
+            Self::Commented => write!(f, "commented"),
+            Self::Dismissed => write!(f, "dismissed"),
+            Self::Pending => write!(f, "pending"),
+        }
+    }
+}
+
+/// A review on a PR
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrReview {
+    /// Reviewer's username
+    pub reviewer: String,
+    /// Review verdict
+    pub state: ReviewState,
+    /// When the review was submitted (None = unknown or not submitted)
+    pub submitted_at: Option<DateTime<Utc>>,
+}
+
+/// A review comment thread on a PR
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewThread {
+    /// Platform ID of the thread (GraphQL node ID on GitHub, discussion ID on GitLab)
+    pub id: String,
+    /// File the thread is on (None = a general comment)
+    pub path: Option<String>,
+    /// Line the thread is on, in the new version of the file
+    pub line: Option<u64>,
+    /// Username of whoever started the thread
+    pub author: String,
+    /// Body of the thread's first comment
+    pub body: String,
+    /// Number of comments in the thread
+    pub comment_count: usize,
+    /// Whether the thread has been resolved
+    pub is_resolved: bool,
 }
 
 /// Merge readiness check result
 ///
 /// Captures all the conditions that must be met for a PR to be merged.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct MergeReadiness {
     /// Whether the PR has been approved by reviewers
     pub is_approved: bool,
-    /// Whether CI checks have passed
-    pub ci_passed: bool,
+    /// Approvals required by the platform's approval rules (`None` = unknown)
+    #[serde(default)]
+    pub approvals_required: Option<u32>,
+    /// Approvals received that count towards `approvals_required`
+    #[serde(default)]
+    pub approvals_received: Option<u32>,
+    /// CI checks on the head commit (empty = no CI configured)
+    pub checks: Vec<CheckInfo>,
     /// Whether the PR can be merged (no conflicts)
     /// - `Some(true)` = mergeable
     /// - `Some(false)` = has conflicts
@@ -230,20 +356,54 @@ pub struct MergeReadiness {
 }
 
 impl MergeReadiness {
+    /// Whether every required check has passed (no checks = passing)
+    pub fn ci_passed(&self) -> bool {
+        self.checks
+            .iter()
+            .filter(|c| c.required)
+            .all(|c| c.state.is_passing())
+    }
+
+    /// Required checks that have not passed yet
+    pub fn blocking_checks(&self) -> impl Iterator<Item = &CheckInfo> {
+        self.checks
+            .iter()
+            .filter(|c| c.required && !c.state.is_passing())
+    }
+
+    /// Names of required checks that failed
+    pub fn failing_checks(&self) -> Vec<&str> {
+        self.blocking_checks()
+            .filter(|c| c.state != CheckState::Pending)
+            .map(|c| c.name.as_str())
+            .collect()
+    }
+
+    /// Names of required checks that are still running
+    pub fn pending_checks(&self) -> Vec<&str> {
+        self.blocking_checks()
+            .filter(|c| c.state == CheckState::Pending)
+            .map(|c| c.name.as_str())
+            .collect()
+    }
+
     /// Check if there are definitive blockers preventing merge.
     ///
     /// Returns `true` if the PR definitely cannot be merged:
     /// - Not approved
-    /// - CI failing
+    /// - A required check failing or pending
     /// - Is a draft
     /// - Has confirmed merge conflicts (`is_mergeable == Some(false)`)
+    /// - Any other blocker in `blocking_reasons` (e.g. unresolved review
+    ///   threads)
     ///
     /// Returns `false` if the PR might be mergeable (including unknown status).
-    pub const fn is_blocked(&self) -> bool {
+    pub fn is_blocked(&self) -> bool {
         !self.is_approved
-            || !self.ci_passed
+            || !self.ci_passed()
             || self.is_draft
             || matches!(self.is_mergeable, Some(false))
+            || !self.blocking_reasons.is_empty()
     }
 
     /// Returns the first uncertainty reason, if any.
@@ -256,7 +416,7 @@ impl MergeReadiness {
 }
 
 /// Result of a merge operation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MergeResult {
     /// Whether the merge was successful
     pub merged: bool,
@@ -266,10 +426,36 @@ pub struct MergeResult {
     pub message: Option<String>,
 }
 
+/// When the stack overview is kept on each PR
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StackCommentMode {
+    /// On every PR
+    #[default]
+    Always,
+    /// Only when the stack has at least two PRs
+    Stacked,
+    /// Never
+    Never,
+}
+
+impl StackCommentMode {
+    /// Whether a stack of `pr_count` PRs gets the overview
+    pub const fn applies_to(self, pr_count: usize) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Stacked => pr_count >= 2,
+            Self::Never => false,
+        }
+    }
+}
+
 /// Merge strategy/method
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MergeMethod {
     /// Squash all commits into one
+    #[default]
     Squash,
     /// Create a merge commit
     Merge,
@@ -0,0 +1,316 @@
+//! Chat webhook notifications after submit and merge
+//!
+//! Webhook URLs are configured under `[webhooks]` (see
+//! [`crate::config::WebhooksConfig`]). A [`ChatSummary`] is built from the
+//! operation's result and rendered in each chat service's markup: Slack
+//! `mrkdwn`, or Markdown for Discord and Microsoft Teams.
+
+use crate::config::WebhooksConfig;
+use crate::error::{Error, Result};
+use crate::merge::{MergeExecutionResult, PrInfo};
+use crate::submit::SubmissionResult;
+use reqwest::Client;
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::debug;
+
+/// Request timeout for webhook posts, in seconds
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+/// Chat service a webhook posts to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookKind {
+    /// Slack incoming webhook
+    Slack,
+    /// Discord channel webhook
+    Discord,
+    /// Microsoft Teams incoming webhook
+    Teams,
+}
+
+impl WebhookKind {
+    /// Configured webhooks, skipping blank URLs
+    pub fn configured(config: &WebhooksConfig) -> Vec<(Self, &str)> {
+        [
+            (Self::Slack, config.slack.as_deref()),
+            (Self::Discord, config.discord.as_deref()),
+            (Self::Teams, config.teams.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(kind, url)| Some((kind, url?.trim())))
+        .filter(|(_, url)| !url.is_empty())
+        .collect()
+    }
+
+    /// JSON body posting `text` to this service
+    fn payload(self, text: &str) -> serde_json::Value {
+        match self {
+            Self::Slack | Self::Teams => json!({ "text": text }),
+            Self::Discord => json!({ "content": text }),
+        }
+    }
+}
+
+impl std::fmt::Display for WebhookKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Slack => write!(f, "Slack"),
+            Self::Discord => write!(f, "Discord"),
+            Self::Teams => write!(f, "Teams"),
+        }
+    }
+}
+
+/// A PR line in a chat summary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummaryPr {
+    /// PR/MR number
+    pub number: u64,
+    /// Web URL for the PR/MR
+    pub url: String,
+    /// Text after the link, e.g. the bookmark and what happened to it
+    pub text: String,
+}
+
+/// Summary of an operation, posted to chat
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatSummary {
+    /// First line, e.g. "alice submitted stack feat-c to acme/widgets"
+    pub headline: String,
+    /// One line per PR
+    pub prs: Vec<SummaryPr>,
+    /// Trailing note, e.g. why a merge stopped
+    pub note: Option<String>,
+}
+
+impl ChatSummary {
+    /// Summarize a submission, or None if it changed nothing
+    pub fn submit(user: &str, stack: &str, repo: &str, result: &SubmissionResult) -> Option<Self> {
+        if result.pushed_bookmarks.is_empty()
+            && result.created_prs.is_empty()
+            && result.updated_prs.is_empty()
+        {
+            return None;
+        }
+
+        let created = result.created_prs.iter().map(|pr| (pr, "created"));
+        let updated = result.updated_prs.iter().map(|pr| (pr, "updated"));
+        let prs = created
+            .chain(updated)
+            .map(|(pr, action)| SummaryPr {
+                number: pr.number,
+                url: pr.html_url.clone(),
+                text: format!("{} ({action})", pr.head_ref),
+            })
+            .collect();
+
+        Some(Self {
+            headline: format!("{user} submitted stack {stack} to {repo}"),
+            prs,
+            note: None,
+        })
+    }
+
+    /// Summarize a merge, using `pr_info` for the merged PRs' titles and links
+    pub fn merge<S: std::hash::BuildHasher>(
+        user: &str,
+        stack: &str,
+        repo: &str,
+        result: &MergeExecutionResult,
+        pr_info: &HashMap<String, PrInfo, S>,
+    ) -> Self {
+        let prs = result
+            .merged_bookmarks
+            .iter()
+            .filter_map(|bookmark| pr_info.get(bookmark))
+            .map(|info| SummaryPr {
+                number: info.details.number,
+                url: info.details.html_url.clone(),
+                text: format!("{} ({})", info.details.title, info.bookmark),
+            })
+            .collect();
+
+        let note = result.failed_bookmark.as_ref().map(|bookmark| {
+            let reason = result.error_message.as_deref().unwrap_or("unknown error");
+            format!("Stopped at {bookmark}: {reason}")
+        });
+
+        Self {
+            headline: format!(
+                "{user} merged {} PR(s) from stack {stack} in {repo}",
+                result.merged_bookmarks.len()
+            ),
+            prs,
+            note,
+        }
+    }
+
+    /// Render the summary in `kind`'s markup
+    pub fn render(&self, kind: WebhookKind) -> String {
+        let mut lines = Vec::with_capacity(self.prs.len() + 2);
+        match kind {
+            WebhookKind::Slack => {
+                lines.push(format!("*{}*", self.headline));
+                lines.extend(
+                    self.prs
+                        .iter()
+                        .map(|pr| format!("• <{}|#{}> {}", pr.url, pr.number, pr.text)),
+                );
+            }
+            WebhookKind::Discord | WebhookKind::Teams => {
+                lines.push(format!("**{}**", self.headline));
+                lines.extend(
+                    self.prs
+                        .iter()
+                        .map(|pr| format!("- [#{}]({}) {}", pr.number, pr.url, pr.text)),
+                );
+            }
+        }
+        if let Some(note) = &self.note {
+            lines.push(format!("_{note}_"));
+        }
+
+        // Teams collapses single newlines
+        let separator = if kind == WebhookKind::Teams { "\n\n" } else { "\n" };
+        lines.join(separator)
+    }
+}
+
+/// Post `summary` to every configured webhook
+///
+/// Every webhook is attempted; the errors of those that failed are returned.
+pub async fn post_summary(config: &WebhooksConfig, summary: &ChatSummary) -> Vec<Error> {
+    let webhooks = WebhookKind::configured(config);
+    if webhooks.is_empty() {
+        return Vec::new();
+    }
+
+    let client = match Client::builder()
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => return vec![Error::Webhook(format!("failed to create HTTP client: {e}"))],
+    };
+
+    let mut errors = Vec::new();
+    for (kind, url) in webhooks {
+        if let Err(e) = post(&client, kind, url, &summary.render(kind)).await {
+            errors.push(e);
+        }
+    }
+    errors
+}
+
+/// Post `text` to a single webhook
+async fn post(client: &Client, kind: WebhookKind, url: &str, text: &str) -> Result<()> {
+    debug!(%kind, "posting webhook");
+    let response = client
+        .post(url)
+        .json(&kind.payload(text))
+        .send()
+        .await
+        .map_err(|e| Error::Webhook(format!("{kind} webhook failed: {e}")))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(Error::Webhook(format!("{kind} webhook returned {status}")));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PullRequest;
+
+    fn pr(number: u64, head_ref: &str) -> PullRequest {
+        PullRequest {
+            number,
+            html_url: format!("https://github.com/acme/widgets/pull/{number}"),
+            base_ref: "main".to_string(),
+            head_ref: head_ref.to_string(),
+            title: head_ref.to_string(),
+            node_id: None,
+            is_draft: false,
+        }
+    }
+
+    fn summary() -> ChatSummary {
+        let mut result = SubmissionResult::new();
+        result.pushed_bookmarks = vec!["feat-a".to_string(), "feat-b".to_string()];
+        result.created_prs = vec![pr(2, "feat-b")];
+        result.updated_prs = vec![pr(1, "feat-a")];
+        ChatSummary::submit("alice", "feat-b", "acme/widgets", &result).unwrap()
+    }
+
+    #[test]
+    fn test_submit_summary_renders_per_service() {
+        let summary = summary();
+        assert_eq!(
+            summary.render(WebhookKind::Slack),
+            "*alice submitted stack feat-b to acme/widgets*\n\
+             • <https://github.com/acme/widgets/pull/2|#2> feat-b (created)\n\
+             • <https://github.com/acme/widgets/pull/1|#1> feat-a (updated)"
+        );
+        assert_eq!(
+            summary.render(WebhookKind::Discord),
+            "**alice submitted stack feat-b to acme/widgets**\n\
+             - [#2](https://github.com/acme/widgets/pull/2) feat-b (created)\n\
+             - [#1](https://github.com/acme/widgets/pull/1) feat-a (updated)"
+        );
+        assert!(summary.render(WebhookKind::Teams).contains(")\n\n- [#1]"));
+    }
+
+    #[test]
+    fn test_unchanged_submission_has_no_summary() {
+        let result = SubmissionResult::new();
+        assert!(ChatSummary::submit("alice", "feat-b", "acme/widgets", &result).is_none());
+    }
+
+    #[test]
+    fn test_blank_urls_are_not_configured() {
+        let config = WebhooksConfig {
+            slack: Some(" ".to_string()),
+            discord: None,
+            teams: Some("https://example.com/teams".to_string()),
+        };
+        assert_eq!(
+            WebhookKind::configured(&config),
+            vec![(WebhookKind::Teams, "https://example.com/teams")]
+        );
+    }
+
+    // The server must outlive the requests, not just the mock setup
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_post_summary_reports_failed_webhooks() {
+        let mut server = mockito::Server::new_async().await;
+        let discord = server
+            .mock("POST", "/discord")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "content": summary().render(WebhookKind::Discord)
+            })))
+            .with_status(204)
+            .create_async()
+            .await;
+        let slack = server
+            .mock("POST", "/slack")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let config = WebhooksConfig {
+            slack: Some(format!("{}/slack", server.url())),
+            discord: Some(format!("{}/discord", server.url())),
+            teams: None,
+        };
+        let errors = post_summary(&config, &summary()).await;
+
+        discord.assert_async().await;
+        slack.assert_async().await;
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().contains("Slack webhook returned 404"));
+    }
+}
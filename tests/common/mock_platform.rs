@@ -9,8 +9,8 @@ use async_trait::async_trait;
 use jj_ryu::error::{Error, Result};
 use jj_ryu::platform::PlatformService;
 use jj_ryu::types::{
-    MergeMethod, MergeReadiness, MergeResult, PlatformConfig, PrComment, PrState, PullRequest,
-    PullRequestDetails,
+    CheckInfo, CheckState, MergeMethod, MergeReadiness, MergeResult, PlatformConfig, PrComment,
+    PrReview, PrState, PullRequest, PullRequestDetails, ReviewThread,
 };
 use std::collections::HashMap;
 use std::sync::Mutex;
@@ -39,6 +39,14 @@ pub struct CreateCommentCall {
     pub body: String,
 }
 
+/// Call record for `update_pr_comment`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateCommentCall {
+    pub pr_number: u64,
+    pub comment_id: u64,
+    pub body: String,
+}
+
 /// Call record for `merge_pr`
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MergePrCall {
@@ -60,17 +68,39 @@ pub struct MockPlatformService {
     config: PlatformConfig,
     next_pr_number: AtomicU64,
     find_pr_responses: Mutex<HashMap<String, Option<PullRequest>>>,
+    closed_prs: Mutex<HashMap<String, PullRequest>>,
+    prs_by_body: Mutex<HashMap<String, PullRequest>>,
     list_comments_responses: Mutex<HashMap<u64, Vec<PrComment>>>,
+    protected_branches: Mutex<Vec<String>>,
+    reviews: Mutex<HashMap<u64, Vec<PrReview>>>,
+    review_threads: Mutex<HashMap<u64, Vec<ReviewThread>>>,
+    checks: Mutex<HashMap<String, Vec<CheckInfo>>>,
     // Call tracking
     find_pr_calls: Mutex<Vec<String>>,
     create_pr_calls: Mutex<Vec<CreatePrCall>>,
     update_base_calls: Mutex<Vec<UpdateBaseCall>>,
     create_comment_calls: Mutex<Vec<CreateCommentCall>>,
     list_comments_calls: Mutex<Vec<u64>>,
+    update_comment_calls: Mutex<Vec<UpdateCommentCall>>,
+    delete_comment_calls: Mutex<Vec<(u64, u64)>>,
+    close_pr_calls: Mutex<Vec<u64>>,
+    update_title_calls: Mutex<Vec<(u64, String)>>,
+    commit_status_calls: Mutex<Vec<(String, CheckState, String)>>,
+    unpublish_calls: Mutex<Vec<u64>>,
+    update_body_calls: Mutex<Vec<(u64, String)>>,
+    add_labels_calls: Mutex<Vec<(u64, Vec<String>)>>,
+    assign_calls: Mutex<Vec<(u64, Vec<String>)>>,
+    request_review_calls: Mutex<Vec<(u64, Vec<String>)>>,
+    milestone_calls: Mutex<Vec<(u64, String)>>,
+    auto_merge_calls: Mutex<Vec<MergePrCall>>,
+    resolve_thread_calls: Mutex<Vec<(u64, String)>>,
+    reopen_pr_calls: Mutex<Vec<u64>>,
     // Error injection
     error_on_find_pr: Mutex<Option<String>>,
     error_on_create_pr: Mutex<Option<String>>,
     error_on_update_base: Mutex<Option<String>>,
+    error_on_reopen_pr: Mutex<Option<String>>,
+    error_on_close_pr: Mutex<HashMap<u64, String>>,
     // Merge-related response maps
     pr_details_responses: Mutex<HashMap<u64, PullRequestDetails>>,
     merge_readiness_responses: Mutex<HashMap<u64, MergeReadiness>>,
@@ -79,6 +109,7 @@ pub struct MockPlatformService {
     get_pr_details_calls: Mutex<Vec<u64>>,
     check_merge_readiness_calls: Mutex<Vec<u64>>,
     merge_pr_calls: Mutex<Vec<MergePrCall>>,
+    update_branch_calls: Mutex<Vec<u64>>,
     // Merge-related error injection
     error_on_merge_pr: Mutex<Option<String>>,
 }
@@ -90,21 +121,44 @@ impl MockPlatformService {
             config,
             next_pr_number: AtomicU64::new(1),
             find_pr_responses: Mutex::new(HashMap::new()),
+            closed_prs: Mutex::new(HashMap::new()),
+            prs_by_body: Mutex::new(HashMap::new()),
             list_comments_responses: Mutex::new(HashMap::new()),
+            protected_branches: Mutex::new(Vec::new()),
+            reviews: Mutex::new(HashMap::new()),
+            review_threads: Mutex::new(HashMap::new()),
+            checks: Mutex::new(HashMap::new()),
             find_pr_calls: Mutex::new(Vec::new()),
             create_pr_calls: Mutex::new(Vec::new()),
             update_base_calls: Mutex::new(Vec::new()),
             create_comment_calls: Mutex::new(Vec::new()),
             list_comments_calls: Mutex::new(Vec::new()),
+            update_comment_calls: Mutex::new(Vec::new()),
+            delete_comment_calls: Mutex::new(Vec::new()),
+            close_pr_calls: Mutex::new(Vec::new()),
+            update_title_calls: Mutex::new(Vec::new()),
+            commit_status_calls: Mutex::new(Vec::new()),
+            unpublish_calls: Mutex::new(Vec::new()),
+            update_body_calls: Mutex::new(Vec::new()),
+            add_labels_calls: Mutex::new(Vec::new()),
+            assign_calls: Mutex::new(Vec::new()),
+            request_review_calls: Mutex::new(Vec::new()),
+            milestone_calls: Mutex::new(Vec::new()),
+            auto_merge_calls: Mutex::new(Vec::new()),
+            resolve_thread_calls: Mutex::new(Vec::new()),
+            reopen_pr_calls: Mutex::new(Vec::new()),
             error_on_find_pr: Mutex::new(None),
             error_on_create_pr: Mutex::new(None),
             error_on_update_base: Mutex::new(None),
+            error_on_reopen_pr: Mutex::new(None),
+            error_on_close_pr: Mutex::new(HashMap::new()),
             pr_details_responses: Mutex::new(HashMap::new()),
             merge_readiness_responses: Mutex::new(HashMap::new()),
             merge_responses: Mutex::new(HashMap::new()),
             get_pr_details_calls: Mutex::new(Vec::new()),
             check_merge_readiness_calls: Mutex::new(Vec::new()),
             merge_pr_calls: Mutex::new(Vec::new()),
+            update_branch_calls: Mutex::new(Vec::new()),
             error_on_merge_pr: Mutex::new(None),
         }
     }
@@ -126,11 +180,45 @@ impl MockPlatformService {
         *self.error_on_update_base.lock().unwrap() = Some(msg.to_string());
     }
 
+    /// Make `reopen_pr` return an error
+    pub fn fail_reopen_pr(&self, msg: &str) {
+        *self.error_on_reopen_pr.lock().unwrap() = Some(msg.to_string());
+    }
+
+    /// Make `close_pr` return an error for one PR
+    pub fn fail_close_pr(&self, pr_number: u64, msg: &str) {
+        self.error_on_close_pr
+            .lock()
+            .unwrap()
+            .insert(pr_number, msg.to_string());
+    }
+
     /// Make `merge_pr` return an error
     pub fn fail_merge_pr(&self, msg: &str) {
         *self.error_on_merge_pr.lock().unwrap() = Some(msg.to_string());
     }
 
+    /// Set the branches reported by `list_protected_branches`
+    pub fn set_protected_branches(&self, branches: &[&str]) {
+        *self.protected_branches.lock().unwrap() =
+            branches.iter().map(ToString::to_string).collect();
+    }
+
+    /// Set the reviews returned by `list_reviews` for a PR
+    pub fn set_reviews(&self, pr_number: u64, reviews: Vec<PrReview>) {
+        self.reviews.lock().unwrap().insert(pr_number, reviews);
+    }
+
+    /// Set the threads returned by `list_review_threads` for a PR
+    pub fn set_review_threads(&self, pr_number: u64, threads: Vec<ReviewThread>) {
+        self.review_threads.lock().unwrap().insert(pr_number, threads);
+    }
+
+    /// Set the checks returned by `list_checks` for a ref
+    pub fn set_checks(&self, git_ref: &str, checks: Vec<CheckInfo>) {
+        self.checks.lock().unwrap().insert(git_ref.to_string(), checks);
+    }
+
     /// Set the response for `find_existing_pr` for a specific branch
     pub fn set_find_pr_response(&self, branch: &str, pr: Option<PullRequest>) {
         self.find_pr_responses
@@ -139,6 +227,22 @@ impl MockPlatformService {
             .insert(branch.to_string(), pr);
     }
 
+    /// Set the closed PR `find_closed_pr` returns for a branch
+    pub fn set_closed_pr(&self, branch: &str, pr: PullRequest) {
+        self.closed_prs
+            .lock()
+            .unwrap()
+            .insert(branch.to_string(), pr);
+    }
+
+    /// Set the PR `find_pr_by_body` returns for a body text
+    pub fn set_pr_by_body(&self, text: &str, pr: PullRequest) {
+        self.prs_by_body
+            .lock()
+            .unwrap()
+            .insert(text.to_string(), pr);
+    }
+
     /// Set the response for `list_pr_comments` for a specific PR
     pub fn set_list_comments_response(&self, pr_number: u64, comments: Vec<PrComment>) {
         self.list_comments_responses
@@ -198,8 +302,10 @@ impl MockPlatformService {
                 is_draft: false,
                 mergeable: Some(true),
                 head_ref: bookmark.to_string(),
+                head_sha: format!("sha-{bookmark}"),
                 base_ref: "main".to_string(),
                 html_url: format!("https://github.com/test/repo/pull/{pr_number}"),
+                is_behind: false,
             },
         );
 
@@ -208,7 +314,9 @@ impl MockPlatformService {
             pr_number,
             MergeReadiness {
                 is_approved: true,
-                ci_passed: true,
+                approvals_required: None,
+                approvals_received: None,
+                checks: vec![],
                 is_mergeable: Some(true),
                 is_draft: false,
                 blocking_reasons: vec![],
@@ -254,8 +362,10 @@ impl MockPlatformService {
                 is_draft: false,
                 mergeable: Some(true),
                 head_ref: bookmark.to_string(),
+                head_sha: format!("sha-{bookmark}"),
                 base_ref: "main".to_string(),
                 html_url: format!("https://github.com/test/repo/pull/{pr_number}"),
+                is_behind: false,
             },
         );
 
@@ -264,7 +374,9 @@ impl MockPlatformService {
             pr_number,
             MergeReadiness {
                 is_approved: false,
-                ci_passed: true,
+                approvals_required: None,
+                approvals_received: None,
+                checks: vec![],
                 is_mergeable: Some(true),
                 is_draft: false,
                 blocking_reasons: reasons,
@@ -300,8 +412,10 @@ impl MockPlatformService {
                 is_draft: false,
                 mergeable: None, // Unknown - GitHub still computing
                 head_ref: bookmark.to_string(),
+                head_sha: format!("sha-{bookmark}"),
                 base_ref: "main".to_string(),
                 html_url: format!("https://github.com/test/repo/pull/{pr_number}"),
+                is_behind: false,
             },
         );
 
@@ -310,7 +424,9 @@ impl MockPlatformService {
             pr_number,
             MergeReadiness {
                 is_approved: true,
-                ci_passed: true,
+                approvals_required: None,
+                approvals_received: None,
+                checks: vec![],
                 is_mergeable: None, // Must match details.mergeable
                 is_draft: false,
                 blocking_reasons: vec![],
@@ -356,6 +472,76 @@ impl MockPlatformService {
         self.list_comments_calls.lock().unwrap().clone()
     }
 
+    /// Get all `update_pr_comment` calls
+    pub fn get_update_comment_calls(&self) -> Vec<UpdateCommentCall> {
+        self.update_comment_calls.lock().unwrap().clone()
+    }
+
+    /// Get all `delete_pr_comment` calls as `(pr_number, comment_id)`
+    pub fn get_delete_comment_calls(&self) -> Vec<(u64, u64)> {
+        self.delete_comment_calls.lock().unwrap().clone()
+    }
+
+    /// Get all `close_pr` calls
+    pub fn get_close_pr_calls(&self) -> Vec<u64> {
+        self.close_pr_calls.lock().unwrap().clone()
+    }
+
+    /// Get all `unpublish_pr` calls (PR numbers)
+    pub fn get_unpublish_calls(&self) -> Vec<u64> {
+        self.unpublish_calls.lock().unwrap().clone()
+    }
+
+    /// Get all `create_commit_status` calls as (sha, state, description)
+    pub fn get_commit_status_calls(&self) -> Vec<(String, CheckState, String)> {
+        self.commit_status_calls.lock().unwrap().clone()
+    }
+
+    /// Get all `update_pr_title` calls as (PR number, title)
+    pub fn get_update_title_calls(&self) -> Vec<(u64, String)> {
+        self.update_title_calls.lock().unwrap().clone()
+    }
+
+    /// Get all `update_pr_body` calls as (PR number, body)
+    pub fn get_update_body_calls(&self) -> Vec<(u64, String)> {
+        self.update_body_calls.lock().unwrap().clone()
+    }
+
+    /// Get all `add_pr_labels` calls as (PR number, labels)
+    pub fn get_add_labels_calls(&self) -> Vec<(u64, Vec<String>)> {
+        self.add_labels_calls.lock().unwrap().clone()
+    }
+
+    /// Get all `assign_pr` calls as (PR number, assignees)
+    pub fn get_assign_calls(&self) -> Vec<(u64, Vec<String>)> {
+        self.assign_calls.lock().unwrap().clone()
+    }
+
+    /// Get all `request_review` calls as (PR number, reviewers)
+    pub fn get_request_review_calls(&self) -> Vec<(u64, Vec<String>)> {
+        self.request_review_calls.lock().unwrap().clone()
+    }
+
+    /// Get all `set_pr_milestone` calls as (PR number, milestone)
+    pub fn get_milestone_calls(&self) -> Vec<(u64, String)> {
+        self.milestone_calls.lock().unwrap().clone()
+    }
+
+    /// Get all `enable_auto_merge` calls
+    pub fn get_auto_merge_calls(&self) -> Vec<MergePrCall> {
+        self.auto_merge_calls.lock().unwrap().clone()
+    }
+
+    /// Get all `resolve_thread` calls as (PR number, thread ID)
+    pub fn get_resolve_thread_calls(&self) -> Vec<(u64, String)> {
+        self.resolve_thread_calls.lock().unwrap().clone()
+    }
+
+    /// Get all `reopen_pr` calls
+    pub fn get_reopen_pr_calls(&self) -> Vec<u64> {
+        self.reopen_pr_calls.lock().unwrap().clone()
+    }
+
     /// Get all `get_pr_details` calls
     pub fn get_pr_details_calls(&self) -> Vec<u64> {
         self.get_pr_details_calls.lock().unwrap().clone()
@@ -371,6 +557,11 @@ impl MockPlatformService {
         self.merge_pr_calls.lock().unwrap().clone()
     }
 
+    /// Get all `update_pr_branch` calls
+    pub fn get_update_branch_calls(&self) -> Vec<u64> {
+        self.update_branch_calls.lock().unwrap().clone()
+    }
+
     /// Assert that `create_pr` was called with specific head and base
     pub fn assert_create_pr_called(&self, head: &str, base: &str) {
         let calls = self.get_create_pr_calls();
@@ -429,7 +620,7 @@ impl MockPlatformService {
         );
     }
 
-    /// Get count of merge_pr calls
+    /// Get count of `merge_pr` calls
     pub fn merge_call_count(&self) -> usize {
         self.merge_pr_calls.lock().unwrap().len()
     }
@@ -452,6 +643,14 @@ impl PlatformService for MockPlatformService {
         Ok(responses.get(head_branch).cloned().flatten())
     }
 
+    async fn find_closed_pr(&self, head_branch: &str) -> Result<Option<PullRequest>> {
+        Ok(self.closed_prs.lock().unwrap().get(head_branch).cloned())
+    }
+
+    async fn find_pr_by_body(&self, text: &str) -> Result<Option<PullRequest>> {
+        Ok(self.prs_by_body.lock().unwrap().get(text).cloned())
+    }
+
     async fn create_pr_with_options(
         &self,
         head: &str,
@@ -524,12 +723,23 @@ impl PlatformService for MockPlatformService {
         Ok(())
     }
 
-    async fn update_pr_comment(
-        &self,
-        _pr_number: u64,
-        _comment_id: u64,
-        _body: &str,
-    ) -> Result<()> {
+    async fn update_pr_comment(&self, pr_number: u64, comment_id: u64, body: &str) -> Result<()> {
+        self.update_comment_calls
+            .lock()
+            .unwrap()
+            .push(UpdateCommentCall {
+                pr_number,
+                comment_id,
+                body: body.to_string(),
+            });
+        Ok(())
+    }
+
+    async fn delete_pr_comment(&self, pr_number: u64, comment_id: u64) -> Result<()> {
+        self.delete_comment_calls
+            .lock()
+            .unwrap()
+            .push((pr_number, comment_id));
         Ok(())
     }
 
@@ -545,6 +755,117 @@ impl PlatformService for MockPlatformService {
         })
     }
 
+    async fn unpublish_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        self.unpublish_calls.lock().unwrap().push(pr_number);
+        Ok(PullRequest {
+            number: pr_number,
+            html_url: format!("https://github.com/test/repo/pull/{pr_number}"),
+            base_ref: "main".to_string(),
+            head_ref: "unpublished".to_string(),
+            title: "Unpublished PR".to_string(),
+            node_id: Some(format!("PR_node_{pr_number}")),
+            is_draft: true,
+        })
+    }
+
+    async fn update_pr_title(&self, pr_number: u64, title: &str) -> Result<PullRequest> {
+        self.update_title_calls
+            .lock()
+            .unwrap()
+            .push((pr_number, title.to_string()));
+        Ok(PullRequest {
+            number: pr_number,
+            html_url: format!("https://github.com/test/repo/pull/{pr_number}"),
+            base_ref: "main".to_string(),
+            head_ref: "updated".to_string(),
+            title: title.to_string(),
+            node_id: Some(format!("PR_node_{pr_number}")),
+            is_draft: false,
+        })
+    }
+
+    async fn update_pr_body(&self, pr_number: u64, body: &str) -> Result<()> {
+        self.update_body_calls
+            .lock()
+            .unwrap()
+            .push((pr_number, body.to_string()));
+        Ok(())
+    }
+
+    async fn add_pr_labels(&self, pr_number: u64, labels: &[String]) -> Result<()> {
+        self.add_labels_calls
+            .lock()
+            .unwrap()
+            .push((pr_number, labels.to_vec()));
+        Ok(())
+    }
+
+    async fn assign_pr(&self, pr_number: u64, assignees: &[String]) -> Result<()> {
+        self.assign_calls
+            .lock()
+            .unwrap()
+            .push((pr_number, assignees.to_vec()));
+        Ok(())
+    }
+
+    async fn request_review(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        self.request_review_calls
+            .lock()
+            .unwrap()
+            .push((pr_number, reviewers.to_vec()));
+        Ok(())
+    }
+
+    async fn set_pr_milestone(&self, pr_number: u64, milestone: &str) -> Result<()> {
+        self.milestone_calls
+            .lock()
+            .unwrap()
+            .push((pr_number, milestone.to_string()));
+        Ok(())
+    }
+
+    async fn close_pr(&self, pr_number: u64) -> Result<()> {
+        self.close_pr_calls.lock().unwrap().push(pr_number);
+        if let Some(msg) = self.error_on_close_pr.lock().unwrap().get(&pr_number) {
+            return Err(Error::Platform(msg.clone()));
+        }
+        Ok(())
+    }
+
+    async fn reopen_pr(&self, pr_number: u64) -> Result<PullRequest> {
+        self.reopen_pr_calls.lock().unwrap().push(pr_number);
+
+        if let Some(msg) = self.error_on_reopen_pr.lock().unwrap().as_ref() {
+            return Err(Error::Platform(msg.clone()));
+        }
+
+        // Report the base from configured details so callers can detect retargeting
+        let base_ref = self
+            .pr_details_responses
+            .lock()
+            .unwrap()
+            .get(&pr_number)
+            .map_or_else(|| "main".to_string(), |d| d.base_ref.clone());
+
+        Ok(PullRequest {
+            number: pr_number,
+            html_url: format!("https://github.com/test/repo/pull/{pr_number}"),
+            base_ref,
+            head_ref: "reopened".to_string(),
+            title: "Reopened PR".to_string(),
+            node_id: Some(format!("PR_node_{pr_number}")),
+            is_draft: false,
+        })
+    }
+
+    async fn current_user(&self) -> Result<String> {
+        Ok("mock-user".to_string())
+    }
+
+    async fn list_protected_branches(&self) -> Result<Vec<String>> {
+        Ok(self.protected_branches.lock().unwrap().clone())
+    }
+
     fn config(&self) -> &PlatformConfig {
         &self.config
     }
@@ -564,7 +885,61 @@ impl PlatformService for MockPlatformService {
         })
     }
 
-    async fn check_merge_readiness(&self, pr_number: u64) -> Result<MergeReadiness> {
+    async fn list_reviews(&self, pr_number: u64) -> Result<Vec<PrReview>> {
+        Ok(self
+            .reviews
+            .lock()
+            .unwrap()
+            .get(&pr_number)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn list_review_threads(&self, pr_number: u64) -> Result<Vec<ReviewThread>> {
+        Ok(self
+            .review_threads
+            .lock()
+            .unwrap()
+            .get(&pr_number)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn resolve_thread(&self, pr_number: u64, thread_id: &str) -> Result<()> {
+        self.resolve_thread_calls
+            .lock()
+            .unwrap()
+            .push((pr_number, thread_id.to_string()));
+        Ok(())
+    }
+
+    async fn list_checks(&self, git_ref: &str) -> Result<Vec<CheckInfo>> {
+        Ok(self
+            .checks
+            .lock()
+            .unwrap()
+            .get(git_ref)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn create_commit_status(
+        &self,
+        sha: &str,
+        _context: &str,
+        state: CheckState,
+        description: &str,
+    ) -> Result<()> {
+        self.commit_status_calls.lock().unwrap().push((
+            sha.to_string(),
+            state,
+            description.to_string(),
+        ));
+        Ok(())
+    }
+
+    async fn check_merge_readiness(&self, details: &PullRequestDetails) -> Result<MergeReadiness> {
+        let pr_number = details.number;
         self.check_merge_readiness_calls
             .lock()
             .unwrap()
@@ -578,7 +953,26 @@ impl PlatformService for MockPlatformService {
         })
     }
 
-    async fn merge_pr(&self, pr_number: u64, method: MergeMethod) -> Result<MergeResult> {
+    async fn update_pr_branch(&self, pr_number: u64) -> Result<()> {
+        self.update_branch_calls.lock().unwrap().push(pr_number);
+        Ok(())
+    }
+
+    async fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        self.auto_merge_calls
+            .lock()
+            .unwrap()
+            .push(MergePrCall { pr_number, method });
+        Ok(())
+    }
+
+    async fn merge_pr(
+        &self,
+        pr_number: u64,
+        _title: &str,
+        _body: Option<&str>,
+        method: MergeMethod,
+    ) -> Result<MergeResult> {
         self.merge_pr_calls
             .lock()
             .unwrap()
@@ -3,6 +3,7 @@
 pub mod fixtures;
 pub mod mock_platform;
 pub mod temp_repo;
+pub mod vcr;
 
 // Re-exports for convenience - not all test binaries use all exports
 #[allow(unused_imports)]
@@ -0,0 +1,167 @@
+//! Record/replay of real GitHub and GitLab HTTP traffic
+//!
+//! A cassette is a YAML file under `tests/fixtures/cassettes/` in httpmock's
+//! playback format: one `when`/`then` document per request. By default the
+//! cassette is served from a local mock server, so platform services run
+//! against real API responses without network access or tokens.
+//!
+//! Set `JJ_RYU_VCR_RECORD=1` to re-record instead: requests are forwarded to
+//! the real API and the exchange is written over the cassette when the test
+//! calls [`Cassette::finish`]. Recording needs:
+//! - `JJ_RYU_VCR_TOKEN` - token for the platform being recorded
+//! - `JJ_RYU_VCR_REPO` - `owner/repo` to record against
+//!
+//! The real owner and repo are replaced with [`FIXTURE_OWNER`] and
+//! [`FIXTURE_REPO`] before saving. Request headers (and so the token) are
+//! never recorded, but review responses before committing them.
+
+#![allow(dead_code)]
+
+use httpmock::{MockServer, Recording};
+use jj_ryu::platform::{GitHubService, GitLabService};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Owner used in cassettes and by services replaying them
+pub const FIXTURE_OWNER: &str = "jj-ryu";
+/// Repo used in cassettes and by services replaying them
+pub const FIXTURE_REPO: &str = "fixtures";
+
+/// API a cassette records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Api {
+    /// api.github.com
+    GitHub,
+    /// gitlab.com
+    GitLab,
+}
+
+impl Api {
+    /// Host requests are forwarded to when recording
+    const fn upstream(self) -> &'static str {
+        match self {
+            Self::GitHub => "https://api.github.com",
+            Self::GitLab => "https://gitlab.com",
+        }
+    }
+}
+
+/// A cassette being replayed (or recorded) for one test
+pub struct Cassette {
+    api: Api,
+    path: PathBuf,
+    server: MockServer,
+    /// Id of the active recording, if recording
+    recording: Option<usize>,
+    /// Real `(owner, repo)` when recording
+    target: Option<(String, String)>,
+}
+
+impl Cassette {
+    /// Serve the named cassette, or start recording it if `JJ_RYU_VCR_RECORD` is set
+    // httpmock's rule builders aren't Send; tests await this on their own task
+    #[allow(clippy::future_not_send)]
+    pub async fn open(api: Api, name: &str) -> Self {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/cassettes")
+            .join(format!("{name}.yaml"));
+        let server = MockServer::start_async().await;
+
+        if env::var("JJ_RYU_VCR_RECORD").is_ok() {
+            let repo = env::var("JJ_RYU_VCR_REPO").expect("JJ_RYU_VCR_REPO must be set to record");
+            let (owner, repo) = repo
+                .split_once('/')
+                .expect("JJ_RYU_VCR_REPO must be owner/repo");
+            server
+                .forward_to_async(api.upstream(), |rule| {
+                    rule.filter(|when| {
+                        when.any_request();
+                    });
+                })
+                .await;
+            let recording = server
+                .record_async(|rule| {
+                    rule.filter(|when| {
+                        when.any_request();
+                    });
+                })
+                .await
+                .id;
+            return Self {
+                api,
+                path,
+                server,
+                recording: Some(recording),
+                target: Some((owner.to_string(), repo.to_string())),
+            };
+        }
+
+        let yaml = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("failed to read cassette {}: {e}", path.display()));
+        server.playback_from_yaml_async(yaml).await;
+        Self {
+            api,
+            path,
+            server,
+            recording: None,
+            target: None,
+        }
+    }
+
+    /// Owner and repo the services should use
+    fn owner_repo(&self) -> (String, String) {
+        self.target.clone().unwrap_or_else(|| {
+            (FIXTURE_OWNER.to_string(), FIXTURE_REPO.to_string())
+        })
+    }
+
+    fn token() -> String {
+        env::var("JJ_RYU_VCR_TOKEN").unwrap_or_else(|_| "replay-token".to_string())
+    }
+
+    /// A GitHub service talking to this cassette
+    pub fn github(&self) -> GitHubService {
+        assert_eq!(self.api, Api::GitHub, "cassette was not opened for GitHub");
+        let (owner, repo) = self.owner_repo();
+        GitHubService::with_api_url(&Self::token(), owner, repo, &self.server.base_url())
+            .expect("failed to create GitHub service")
+    }
+
+    /// A GitLab service talking to this cassette
+    pub fn gitlab(&self) -> GitLabService {
+        assert_eq!(self.api, Api::GitLab, "cassette was not opened for GitLab");
+        let (owner, repo) = self.owner_repo();
+        let api_url = format!("{}/api/v4", self.server.base_url());
+        GitLabService::with_api_url(Self::token(), owner, repo, &api_url)
+            .expect("failed to create GitLab service")
+    }
+
+    /// Save the cassette if recording; a no-op when replaying
+    pub async fn finish(self) {
+        let (Some(id), Some((owner, repo))) = (self.recording, &self.target) else {
+            return;
+        };
+        let bytes = Recording::new(id, &self.server)
+            .export_async()
+            .await
+            .expect("failed to export recording")
+            .expect("nothing was recorded");
+        let yaml = scrub(&String::from_utf8_lossy(&bytes), owner, repo);
+
+        fs::create_dir_all(self.path.parent().expect("cassette path has a parent"))
+            .expect("failed to create cassette directory");
+        fs::write(&self.path, yaml).expect("failed to write cassette");
+    }
+}
+
+/// Replace the recorded owner and repo with the fixture names
+fn scrub(yaml: &str, owner: &str, repo: &str) -> String {
+    let fixture = format!("{FIXTURE_OWNER}/{FIXTURE_REPO}");
+    yaml.replace(&format!("{owner}/{repo}"), &fixture)
+        .replace(
+            &format!("{owner}%2F{repo}"),
+            &format!("{FIXTURE_OWNER}%2F{FIXTURE_REPO}"),
+        )
+        .replace(owner, FIXTURE_OWNER)
+}
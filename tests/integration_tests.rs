@@ -7,7 +7,11 @@ mod common;
 use assert_cmd::Command;
 use common::{MockPlatformService, TempJjRepo, github_config, make_pr};
 use jj_ryu::graph::build_change_graph;
-use jj_ryu::submit::{ExecutionStep, analyze_submission, create_submission_plan};
+use jj_ryu::submit::{
+    ExecutionStep, NoopProgress, RollbackSnapshot, SubmissionResult, analyze_submission,
+    create_submission_plan, rollback_submission,
+};
+use jj_ryu::tracking::{PendingOperation, load_op_queue};
 use predicates::prelude::*;
 
 // =============================================================================
@@ -54,6 +58,122 @@ fn test_sync_help() {
         .stdout(predicate::str::contains("Sync current stack"));
 }
 
+#[test]
+fn test_flush_help() {
+    let mut cmd = Command::cargo_bin("ryu").unwrap();
+    cmd.args(["flush", "--help"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Replay operations queued"));
+}
+
+#[test]
+fn test_comment_offline_queues_for_flush() {
+    let repo = TempJjRepo::new();
+    repo.build_stack(&[("feat-a", "Add A")]);
+
+    let mut cmd = Command::cargo_bin("ryu").unwrap();
+    cmd.current_dir(repo.path())
+        .args(["comment", "-b", "feat-a", "--offline", "Rebased, please re-review"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("comment on feat-a"));
+
+    let queue = load_op_queue(repo.path()).expect("load queue");
+    assert_eq!(queue.len(), 1);
+    assert_eq!(
+        queue.operations[0].operation,
+        PendingOperation::Comment {
+            bookmark: "feat-a".to_string(),
+            body: "Rebased, please re-review".to_string(),
+            remote: None,
+        }
+    );
+
+    // Unknown bookmarks are rejected without queueing anything
+    let mut cmd = Command::cargo_bin("ryu").unwrap();
+    cmd.current_dir(repo.path())
+        .args(["comment", "-b", "nope", "--offline", "hello"]);
+    cmd.assert().failure();
+    assert_eq!(load_op_queue(repo.path()).expect("load queue").len(), 1);
+}
+
+#[test]
+fn test_import_help() {
+    let mut cmd = Command::cargo_bin("ryu").unwrap();
+    cmd.args(["import", "--help"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("graphite"))
+        .stdout(predicate::str::contains("spr"))
+        .stdout(predicate::str::contains("branchless"));
+}
+
+#[test]
+fn test_export_help() {
+    let mut cmd = Command::cargo_bin("ryu").unwrap();
+    cmd.args(["export", "--help"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("--cached"));
+}
+
+#[test]
+fn test_install_jj_aliases_help() {
+    let mut cmd = Command::cargo_bin("ryu").unwrap();
+    cmd.args(["install-jj-aliases", "--help"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("--uninstall"));
+}
+
+#[test]
+fn test_reviews_help() {
+    let mut cmd = Command::cargo_bin("ryu").unwrap();
+    cmd.args(["reviews", "--help"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("reviewer verdicts"));
+}
+
+#[test]
+fn test_backport_help() {
+    let mut cmd = Command::cargo_bin("ryu").unwrap();
+    cmd.args(["backport", "--help"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("release branch"));
+}
+
+#[cfg(feature = "self-update")]
+#[test]
+fn test_self_update_help() {
+    let mut cmd = Command::cargo_bin("ryu").unwrap();
+    cmd.args(["self-update", "--help"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("latest ryu release"))
+        .stdout(predicate::str::contains("--check"));
+}
+
+#[test]
+fn test_send_email_help() {
+    let mut cmd = Command::cargo_bin("ryu").unwrap();
+    cmd.args(["send-email", "--help"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("patch series"))
+        .stdout(predicate::str::contains("--reroll-count"));
+}
+
 #[test]
 fn test_auth_help() {
     let mut cmd = Command::cargo_bin("ryu").unwrap();
@@ -337,6 +457,46 @@ async fn test_plan_pr_numbers_increment() {
     assert_eq!(creates[1].bookmark.name, "feat-b");
 }
 
+#[tokio::test]
+async fn test_atomic_rollback_closes_created_prs_and_restores_bases() {
+    let repo = TempJjRepo::new();
+    repo.build_stack(&[("feat-a", "Add A"), ("feat-b", "Add B")]);
+
+    let mut workspace = repo.workspace();
+    let graph = build_change_graph(&workspace).expect("build graph");
+    let analysis = analyze_submission(&graph, Some("feat-b")).expect("analyze");
+
+    // feat-b has a PR targeting main that the plan retargets onto feat-a
+    let mock = MockPlatformService::with_config(github_config());
+    mock.set_find_pr_response("feat-b", Some(make_pr(2, "feat-b", "main")));
+
+    let plan = create_submission_plan(&analysis, &mock, "origin", "main")
+        .await
+        .expect("create plan");
+
+    // Simulate a run that created feat-a's PR and retargeted feat-b, then failed
+    let result = SubmissionResult {
+        success: false,
+        created_prs: vec![make_pr(1, "feat-a", "main")],
+        updated_prs: vec![make_pr(2, "feat-b", "feat-a")],
+        ..SubmissionResult::default()
+    };
+
+    let rollback = rollback_submission(
+        &plan,
+        &RollbackSnapshot::default(),
+        &result,
+        &mut workspace,
+        &mock,
+        &NoopProgress,
+    )
+    .await;
+
+    assert!(rollback.is_complete(), "errors: {:?}", rollback.errors);
+    assert_eq!(mock.get_close_pr_calls(), vec![1]);
+    mock.assert_update_base_called(2, "main");
+}
+
 // =============================================================================
 // Git Fetch Tests (Issue #8)
 // =============================================================================
@@ -469,5 +629,81 @@ fn test_git_fetch_handles_rebased_commits() {
     );
 }
 
+/// Rebasing a change onto a commit it doesn't apply to leaves jj conflicts
+/// that must be found before anything is pushed
+#[test]
+fn test_find_stack_conflicts_and_resolution_changes() {
+    use jj_ryu::repo::{create_resolution_changes, find_stack_conflicts};
+
+    let repo = TempJjRepo::new();
+    let file = repo.path().join("f.txt");
+    std::fs::write(&file, "base\n").expect("write file");
+    repo.commit("Add f");
+    std::fs::write(&file, "a\n").expect("write file");
+    repo.commit("Change f to a");
+    std::fs::write(&file, "b\n").expect("write file");
+    repo.commit("Change f to b");
+    repo.create_bookmark("feat-a");
+
+    // Apply "a -> b" directly onto "base"
+    repo.rebase_before("@-", "@--");
+
+    let graph = build_change_graph(&repo.workspace()).expect("build graph");
+    let analysis = analyze_submission(&graph, None).expect("analyze");
+    let mut workspace = repo.workspace();
+    let mut conflicts = find_stack_conflicts(&workspace, &analysis.segments).expect("conflicts");
+
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].bookmark, "feat-a");
+    assert_eq!(conflicts[0].changes[0].description_first_line, "Change f to b");
+    assert_eq!(conflicts[0].changes[0].paths, vec!["f.txt"]);
+
+    create_resolution_changes(&mut workspace, &mut conflicts).expect("create");
+    let resolution = conflicts[0].resolution_change.clone().expect("resolution change");
+
+    // A second run reuses the change instead of adding another
+    create_resolution_changes(&mut workspace, &mut conflicts).expect("reuse");
+    assert_eq!(conflicts[0].resolution_change, Some(resolution));
+}
+
+/// Abandoning a bookmark's empty changes leaves those of the bookmarks
+/// below it alone
+#[test]
+fn test_abandon_empty_segment_stops_at_lower_bookmarks() {
+    let repo = TempJjRepo::new();
+    repo.build_stack(&[("feat-a", "Empty a"), ("feat-b", "Empty b")]);
+    let a = repo.change_id("feat-a");
+
+    let mut workspace = repo.workspace();
+    let abandoned = workspace
+        .abandon_empty_segment("trunk()", "feat-b")
+        .expect("abandon");
+
+    assert_eq!(abandoned, 1);
+    assert_eq!(repo.change_id("feat-a"), a);
+    assert_eq!(repo.change_id("feat-b"), a);
+}
+
+/// Pruning a finished bookmark in the middle of a stack abandons only its
+/// own empty changes, and the bookmark above moves onto the one below
+#[test]
+fn test_abandon_empty_segment_in_middle_of_stack() {
+    let repo = TempJjRepo::new();
+    repo.build_stack(&[("feat-a", "Empty a"), ("feat-b", "Landed b")]);
+    std::fs::write(repo.path().join("c.txt"), "c\n").expect("write file");
+    repo.commit("Add c");
+    repo.create_bookmark("feat-c");
+    let a = repo.change_id("feat-a");
+
+    let mut workspace = repo.workspace();
+    let abandoned = workspace
+        .abandon_empty_segment("trunk()", "feat-b")
+        .expect("abandon");
+
+    assert_eq!(abandoned, 1);
+    assert_eq!(repo.change_id("feat-a"), a);
+    assert_eq!(repo.change_id("parents(feat-c)"), a);
+}
+
 use std::process::Command as StdCommand;
 use tempfile::TempDir;
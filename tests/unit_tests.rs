@@ -262,7 +262,31 @@ mod detection_test {
 
 mod plan_test {
     use crate::common::{MockPlatformService, github_config, make_linear_stack, make_pr};
-    use jj_ryu::submit::{ExecutionStep, analyze_submission, create_submission_plan};
+    use jj_ryu::submit::{
+        ExecutionStep, analyze_submission, change_id_marker, create_submission_plan,
+        create_submission_plan_onto, find_created_pr, plan_metadata_updates, relink_closed_prs,
+        resume_submission_plan,
+    };
+    use jj_ryu::error::Error;
+    use jj_ryu::tracking::PrCache;
+    use jj_ryu::types::{PrState, PullRequestDetails};
+    use std::collections::HashMap;
+
+    fn closed_pr_details(number: u64, bookmark: &str, state: PrState) -> PullRequestDetails {
+        PullRequestDetails {
+            number,
+            title: format!("PR for {bookmark}"),
+            body: None,
+            state,
+            is_draft: false,
+            mergeable: None,
+            head_ref: bookmark.to_string(),
+            head_sha: format!("sha-{bookmark}"),
+            base_ref: "main".to_string(),
+            html_url: format!("https://github.com/test/repo/pull/{number}"),
+            is_behind: false,
+        }
+    }
 
     #[tokio::test]
     async fn test_plan_new_stack_no_existing_prs() {
@@ -298,6 +322,26 @@ mod plan_test {
         assert_eq!(creates[1].base_branch, "feat-a");
     }
 
+    #[tokio::test]
+    async fn test_plan_serializes_with_tagged_steps() {
+        let graph = make_linear_stack(&["feat-a", "feat-b"]);
+        let analysis = analyze_submission(&graph, Some("feat-b")).unwrap();
+        let mock = MockPlatformService::with_config(github_config());
+
+        let plan = create_submission_plan(&analysis, &mock, "origin", "main")
+            .await
+            .unwrap();
+
+        let json = serde_json::to_value(&plan).unwrap();
+        assert_eq!(json["remote"], "origin");
+        assert_eq!(json["execution_steps"][0]["type"], "push");
+        assert_eq!(json["execution_steps"][0]["name"], "feat-a");
+
+        let parsed: jj_ryu::submit::SubmissionPlan = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.count_creates(), plan.count_creates());
+        assert_eq!(parsed.count_pushes(), plan.count_pushes());
+    }
+
     #[tokio::test]
     async fn test_plan_update_existing_pr_base() {
         let graph = make_linear_stack(&["feat-a", "feat-b"]);
@@ -329,6 +373,233 @@ mod plan_test {
         assert_eq!(update.expected_base, "feat-a");
     }
 
+    #[tokio::test]
+    async fn test_resume_plan_reuses_journaled_prs() {
+        let graph = make_linear_stack(&["feat-a", "feat-b", "feat-c"]);
+        let analysis = analyze_submission(&graph, Some("feat-c")).unwrap();
+
+        // Platform hasn't caught up yet: no PRs visible
+        let mock = MockPlatformService::with_config(github_config());
+        let mut plan = create_submission_plan(&analysis, &mock, "origin", "main")
+            .await
+            .unwrap();
+        assert_eq!(plan.count_creates(), 3);
+
+        // Journal: feat-a created correctly, feat-b created against a stale base
+        resume_submission_plan(
+            &mut plan,
+            &[
+                make_pr(1, "feat-a", "main"),
+                make_pr(2, "feat-b", "main"),
+            ],
+        );
+
+        assert_eq!(plan.count_creates(), 1);
+        assert_eq!(plan.count_updates(), 1);
+        assert!(plan.existing_prs.contains_key("feat-a"));
+        assert!(plan.existing_prs.contains_key("feat-b"));
+
+        let update = plan
+            .execution_steps
+            .iter()
+            .find_map(|s| match s {
+                ExecutionStep::UpdateBase(u) => Some(u),
+                _ => None,
+            })
+            .expect("should have update step");
+        assert_eq!(update.pr.number, 2);
+        assert_eq!(update.expected_base, "feat-a");
+    }
+
+    #[tokio::test]
+    async fn test_relink_reopens_closed_prs_for_deleted_branches() {
+        let graph = make_linear_stack(&["feat-a", "feat-b", "feat-c"]);
+        let analysis = analyze_submission(&graph, Some("feat-c")).unwrap();
+
+        // All branches were deleted remotely, so no open PRs are found
+        let mock = MockPlatformService::with_config(github_config());
+        mock.set_pr_details_response(7, closed_pr_details(7, "feat-a", PrState::Closed));
+        mock.set_pr_details_response(8, closed_pr_details(8, "feat-b", PrState::Merged));
+
+        let mut plan = create_submission_plan(&analysis, &mock, "origin", "main")
+            .await
+            .unwrap();
+        // feat-c's cached PR no longer exists on the platform
+        let known_prs = HashMap::from([
+            ("feat-a".to_string(), 7),
+            ("feat-b".to_string(), 8),
+            ("feat-c".to_string(), 9),
+        ]);
+        relink_closed_prs(&mut plan, &mock, &known_prs, |_| true).await;
+
+        assert_eq!(plan.count_reopens(), 1);
+        assert_eq!(plan.count_creates(), 2);
+
+        let (reopen_idx, reopen) = plan
+            .execution_steps
+            .iter()
+            .enumerate()
+            .find_map(|(i, s)| match s {
+                ExecutionStep::ReopenPr(r) => Some((i, r)),
+                _ => None,
+            })
+            .expect("should have reopen step");
+        assert_eq!(reopen.pr.number, 7);
+        assert_eq!(reopen.create.bookmark.name, "feat-a");
+
+        // Branch must be pushed again before the PR can be reopened
+        let push_idx = plan
+            .execution_steps
+            .iter()
+            .position(|s| matches!(s, ExecutionStep::Push(b) if b.name == "feat-a"))
+            .expect("should push feat-a");
+        assert!(push_idx < reopen_idx);
+    }
+
+    #[tokio::test]
+    async fn test_relink_reopens_closed_pr_found_by_branch() {
+        let graph = make_linear_stack(&["feat-a", "feat-b"]);
+        let analysis = analyze_submission(&graph, Some("feat-b")).unwrap();
+
+        // feat-b's PR was closed by hand and isn't in the PR cache
+        let mock = MockPlatformService::with_config(github_config());
+        mock.set_closed_pr("feat-b", make_pr(5, "feat-b", "feat-a"));
+
+        let mut plan = create_submission_plan(&analysis, &mock, "origin", "main")
+            .await
+            .unwrap();
+        relink_closed_prs(&mut plan, &mock, &HashMap::new(), |_| true).await;
+
+        assert_eq!(plan.count_reopens(), 1);
+        assert_eq!(plan.count_creates(), 1);
+        assert!(plan.execution_steps.iter().any(|s| matches!(
+            s,
+            ExecutionStep::ReopenPr(r) if r.pr.number == 5 && r.create.bookmark.name == "feat-b"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_relink_creates_new_pr_when_reopen_declined() {
+        let graph = make_linear_stack(&["feat-a", "feat-b"]);
+        let analysis = analyze_submission(&graph, Some("feat-b")).unwrap();
+
+        let mock = MockPlatformService::with_config(github_config());
+        mock.set_closed_pr("feat-a", make_pr(4, "feat-a", "main"));
+        mock.set_closed_pr("feat-b", make_pr(5, "feat-b", "feat-a"));
+
+        let mut plan = create_submission_plan(&analysis, &mock, "origin", "main")
+            .await
+            .unwrap();
+        let mut offered = Vec::new();
+        relink_closed_prs(&mut plan, &mock, &HashMap::new(), |reopen| {
+            offered.push(reopen.pr.number);
+            reopen.pr.number == 5
+        })
+        .await;
+
+        assert_eq!(offered, vec![4, 5]);
+        assert_eq!(plan.count_reopens(), 1);
+        assert_eq!(plan.count_creates(), 1);
+        assert!(plan.execution_steps.iter().any(|s| matches!(
+            s,
+            ExecutionStep::CreatePr(c) if c.bookmark.name == "feat-a"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_plan_reuses_pr_of_renamed_bookmark() {
+        let graph = make_linear_stack(&["feat-a", "feat-b"]);
+        let analysis = analyze_submission(&graph, Some("feat-b")).unwrap();
+
+        // feat-b was renamed from feat-old after its PR was created
+        let mock = MockPlatformService::with_config(github_config());
+        mock.set_pr_by_body(
+            &change_id_marker("feat-b_change"),
+            make_pr(7, "feat-old", "feat-a"),
+        );
+
+        let plan = create_submission_plan(&analysis, &mock, "origin", "main")
+            .await
+            .unwrap();
+
+        assert_eq!(plan.count_creates(), 1);
+        assert_eq!(plan.existing_prs["feat-b"].number, 7);
+        assert!(plan.execution_steps.iter().any(|s| matches!(
+            s,
+            ExecutionStep::Push(b) if b.name == "feat-b"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_find_created_pr_matches_head_base_and_title() {
+        let graph = make_linear_stack(&["feat-a"]);
+        let analysis = analyze_submission(&graph, Some("feat-a")).unwrap();
+        let mock = MockPlatformService::with_config(github_config());
+        let plan = create_submission_plan(&analysis, &mock, "origin", "main")
+            .await
+            .unwrap();
+        let create = plan
+            .execution_steps
+            .iter()
+            .find_map(|s| match s {
+                ExecutionStep::CreatePr(c) => Some(c),
+                _ => None,
+            })
+            .expect("should have create step");
+
+        // Nothing was created
+        assert!(find_created_pr(&mock, create).await.is_none());
+
+        // The create request timed out but the PR was opened
+        let mut orphan = make_pr(4, "feat-a", "main");
+        orphan.title.clone_from(&create.title);
+        mock.set_find_pr_response("feat-a", Some(orphan));
+        assert_eq!(find_created_pr(&mock, create).await.map(|pr| pr.number), Some(4));
+
+        // A PR against a different base is not the one we asked for
+        let mut other = make_pr(5, "feat-a", "develop");
+        other.title.clone_from(&create.title);
+        mock.set_find_pr_response("feat-a", Some(other));
+        assert!(find_created_pr(&mock, create).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_plan_refuses_to_push_protected_branches() {
+        let graph = make_linear_stack(&["feat-a", "release/1.0"]);
+        let analysis = analyze_submission(&graph, Some("release/1.0")).unwrap();
+
+        let mock = MockPlatformService::with_config(github_config());
+        mock.set_protected_branches(&["main", "release/*"]);
+
+        let err = create_submission_plan(&analysis, &mock, "origin", "main")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ProtectedBranch(ref name) if name == "release/1.0"));
+
+        // A bookmark named after the default branch is refused even without a lookup
+        let graph = make_linear_stack(&["trunk"]);
+        let analysis = analyze_submission(&graph, Some("trunk")).unwrap();
+        let mock = MockPlatformService::with_config(github_config());
+
+        let err = create_submission_plan(&analysis, &mock, "origin", "trunk")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ProtectedBranch(ref name) if name == "trunk"));
+    }
+
+    #[tokio::test]
+    async fn test_plan_onto_other_base_still_refuses_default_branch() {
+        // A stack tracked onto release/2.x, with a bookmark named like trunk
+        let graph = make_linear_stack(&["main"]);
+        let analysis = analyze_submission(&graph, Some("main")).unwrap();
+        let mock = MockPlatformService::with_config(github_config());
+
+        let err = create_submission_plan_onto(&analysis, &mock, "origin", "release/2.x", "main")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ProtectedBranch(ref name) if name == "main"));
+    }
+
     #[tokio::test]
     async fn test_plan_all_prs_exist_correct_base() {
         let graph = make_linear_stack(&["feat-a", "feat-b"]);
@@ -351,6 +622,44 @@ mod plan_test {
         assert_eq!(plan.existing_prs.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_plan_retitles_prs_with_redescribed_commits() {
+        let graph = make_linear_stack(&["feat-a", "feat-b"]);
+        let analysis = analyze_submission(&graph, Some("feat-b")).unwrap();
+
+        let mock = MockPlatformService::with_config(github_config());
+        let feat_a = make_pr(1, "feat-a", "main");
+        let feat_b = make_pr(2, "feat-b", "feat-a");
+        mock.set_find_pr_response("feat-a", Some(feat_a.clone()));
+        mock.set_find_pr_response("feat-b", Some(feat_b.clone()));
+
+        // feat-b's commit was described differently when it was submitted,
+        // and feat-a's PR title differs but its commit wasn't redescribed
+        let mut pr_cache = PrCache::new();
+        pr_cache.upsert("feat-a", &feat_a, "origin");
+        pr_cache.set_description("feat-a", "Commit for feat-a");
+        pr_cache.upsert("feat-b", &feat_b, "origin");
+        pr_cache.set_description("feat-b", "WIP");
+
+        let mut plan = create_submission_plan(&analysis, &mock, "origin", "main")
+            .await
+            .unwrap();
+        assert_eq!(plan.count_metadata_updates(), 0);
+        plan_metadata_updates(&mut plan, &pr_cache).unwrap();
+
+        assert_eq!(plan.count_metadata_updates(), 1);
+        let update = plan
+            .execution_steps
+            .iter()
+            .find_map(|s| match s {
+                ExecutionStep::UpdateMetadata(u) => Some(u),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(update.pr.number, 2);
+        assert_eq!(update.title, "Commit for feat-b");
+    }
+
     #[tokio::test]
     async fn test_plan_synced_bookmark_not_in_push_list() {
         let mut graph = make_linear_stack(&["feat-a"]);
@@ -543,10 +852,10 @@ mod plan_test {
 
 mod stack_comment_test {
     use jj_ryu::submit::{
-        COMMENT_DATA_PREFIX, STACK_COMMENT_THIS_PR, StackCommentData, StackItem, SubmissionPlan,
-        build_stack_comment_data, format_stack_comment,
+        COMMENT_DATA_PREFIX, STACK_COMMENT_THIS_PR, StackCommentData, StackItem, StackItemStatus,
+        SubmissionPlan, build_stack_comment_data, format_stack_comment,
     };
-    use jj_ryu::types::{Bookmark, NarrowedBookmarkSegment, PullRequest};
+    use jj_ryu::types::{Bookmark, NarrowedBookmarkSegment, PullRequest, StackCommentMode};
     use std::collections::HashMap;
 
     fn make_bookmark(name: &str) -> Bookmark {
@@ -577,6 +886,7 @@ mod stack_comment_test {
             pr_url: format!("https://github.com/test/test/pull/{number}"),
             pr_number: number,
             pr_title: format!("feat: {name}"),
+            status: StackItemStatus::default(),
         }
     }
 
@@ -591,7 +901,16 @@ mod stack_comment_test {
             execution_steps: vec![],
             existing_prs: HashMap::new(),
             remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
             default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
         };
 
         let mut bookmark_to_pr = HashMap::new();
@@ -627,7 +946,16 @@ mod stack_comment_test {
             execution_steps: vec![],
             existing_prs: HashMap::new(),
             remote: "origin".to_string(),
+            push_remotes: HashMap::new(),
             default_branch: "main".to_string(),
+            labels: Vec::new(),
+            pr_labels: HashMap::new(),
+            assignees: Vec::new(),
+            milestone: None,
+            stack_in_body: false,
+            stack_comment: StackCommentMode::default(),
+            stack_status: false,
+            rerequest_reviews: false,
         };
 
         let mut bookmark_to_pr = HashMap::new();
@@ -723,6 +1051,31 @@ mod stack_comment_test {
         );
     }
 
+    #[test]
+    fn test_format_body_shows_status_badges() {
+        let mut ready = make_stack_item("feat-a", 1);
+        ready.status = StackItemStatus {
+            ci_passed: true,
+            approved: true,
+            draft: false,
+        };
+        let mut draft = make_stack_item("feat-b", 2);
+        draft.status.draft = true;
+        let data = StackCommentData {
+            version: 1,
+            stack: vec![ready, draft],
+            base_branch: "main".to_string(),
+        };
+
+        let body = format_stack_comment(&data, 1).unwrap();
+
+        assert!(body.contains("* feat: feat-a #1 ✅ 👍\n"), "{body}");
+        assert!(
+            body.contains(&format!("* **feat: feat-b #2 🚧 {STACK_COMMENT_THIS_PR}**")),
+            "{body}"
+        );
+    }
+
     #[test]
     fn test_format_body_contains_pr_title() {
         let data = StackCommentData {
@@ -807,12 +1160,26 @@ mod sync_test {
 
 mod merge_plan_test {
     use crate::common::make_linear_stack;
-    use jj_ryu::merge::{create_merge_plan, MergeConfidence, MergePlanOptions, MergeStep, PrInfo};
+    use jj_ryu::merge::{
+        create_auto_merge_plan, create_merge_plan, MergeConfidence, MergePlanOptions, MergeStep,
+        PrInfo,
+    };
     use jj_ryu::submit::analyze_submission;
-    use jj_ryu::types::{MergeMethod, MergeReadiness, PrState, PullRequestDetails};
+    use jj_ryu::types::{
+        CheckInfo, CheckState, MergeMethod, MergeReadiness, PrState, PullRequestDetails,
+    };
     use std::collections::HashMap;
 
-    /// Helper to create a mergeable PrInfo with base_ref set to "main".
+    fn make_check(name: &str, state: CheckState, required: bool) -> CheckInfo {
+        CheckInfo {
+            name: name.to_string(),
+            state,
+            required,
+            url: None,
+        }
+    }
+
+    /// Helper to create a mergeable `PrInfo` with `base_ref` set to "main".
     ///
     /// NOTE: This creates a "flat" PR where all PRs target main directly.
     /// For realistic stacked PR scenarios where PRs target their parent's branch,
@@ -828,12 +1195,16 @@ mod merge_plan_test {
                 is_draft: false,
                 mergeable: Some(true),
                 head_ref: bookmark.to_string(),
+                head_sha: format!("sha-{bookmark}"),
                 base_ref: "main".to_string(),
                 html_url: format!("https://github.com/test/repo/pull/{pr_number}"),
+                is_behind: false,
             },
             readiness: MergeReadiness {
                 is_approved: true,
-                ci_passed: true,
+                approvals_required: None,
+                approvals_received: None,
+                checks: vec![],
                 is_mergeable: Some(true),
                 is_draft: false,
                 blocking_reasons: vec![],
@@ -842,7 +1213,7 @@ mod merge_plan_test {
         }
     }
 
-    /// Helper to create a blocked PrInfo
+    /// Helper to create a blocked `PrInfo`
     fn make_blocked_pr_info(
         bookmark: &str,
         pr_number: u64,
@@ -859,12 +1230,16 @@ mod merge_plan_test {
                 is_draft: false,
                 mergeable: Some(true),
                 head_ref: bookmark.to_string(),
+                head_sha: format!("sha-{bookmark}"),
                 base_ref: "main".to_string(),
                 html_url: format!("https://github.com/test/repo/pull/{pr_number}"),
+                is_behind: false,
             },
             readiness: MergeReadiness {
                 is_approved: false,
-                ci_passed: true,
+                approvals_required: None,
+                approvals_received: None,
+                checks: vec![],
                 is_mergeable: Some(true),
                 is_draft: false,
                 blocking_reasons: reasons,
@@ -873,7 +1248,7 @@ mod merge_plan_test {
         }
     }
 
-    /// Helper to create a PrInfo with uncertain merge status (GitHub still computing)
+    /// Helper to create a `PrInfo` with uncertain merge status (GitHub still computing)
     fn make_uncertain_pr_info(bookmark: &str, pr_number: u64, title: &str) -> PrInfo {
         PrInfo {
             bookmark: bookmark.to_string(),
@@ -885,12 +1260,16 @@ mod merge_plan_test {
                 is_draft: false,
                 mergeable: None, // Unknown - GitHub still computing
                 head_ref: bookmark.to_string(),
+                head_sha: format!("sha-{bookmark}"),
                 base_ref: "main".to_string(),
                 html_url: format!("https://github.com/test/repo/pull/{pr_number}"),
+                is_behind: false,
             },
             readiness: MergeReadiness {
                 is_approved: true,
-                ci_passed: true,
+                approvals_required: None,
+                approvals_received: None,
+                checks: vec![],
                 is_mergeable: None, // Must match details.mergeable
                 is_draft: false,
                 blocking_reasons: vec![],
@@ -899,6 +1278,27 @@ mod merge_plan_test {
         }
     }
 
+    #[test]
+    fn test_merge_plan_serializes_with_tagged_steps() {
+        let graph = make_linear_stack(&["feat-a"]);
+        let analysis = analyze_submission(&graph, Some("feat-a")).unwrap();
+        let pr_info = HashMap::from([(
+            "feat-a".to_string(),
+            make_mergeable_pr_info("feat-a", 1, "Add feature A"),
+        )]);
+
+        let plan = create_merge_plan(&analysis, &pr_info, &MergePlanOptions::default(), "main");
+
+        let json = serde_json::to_value(&plan).unwrap();
+        assert_eq!(json["steps"][0]["type"], "merge");
+        assert_eq!(json["steps"][0]["method"], "squash");
+        assert_eq!(json["steps"][0]["confidence"], "certain");
+        assert_eq!(json["trunk_branch"], "main");
+
+        let parsed: jj_ryu::merge::MergePlan = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.merge_count(), 1);
+    }
+
     #[test]
     fn test_create_merge_plan_single_mergeable() {
         let graph = make_linear_stack(&["feat-a"]);
@@ -926,6 +1326,7 @@ mod merge_plan_test {
                 pr_title,
                 method,
                 confidence,
+                ..
             } => {
                 assert_eq!(bookmark, "feat-a");
                 assert_eq!(*pr_number, 1);
@@ -935,6 +1336,7 @@ mod merge_plan_test {
             }
             MergeStep::Skip { .. } => panic!("Expected Merge step, got Skip"),
             MergeStep::RetargetBase { .. } => panic!("Expected Merge step, got RetargetBase"),
+            MergeStep::UpdateBranch { .. } => panic!("Expected Merge step, got UpdateBranch"),
         }
     }
 
@@ -981,6 +1383,59 @@ mod merge_plan_test {
         assert!(matches!(&plan.steps[4], MergeStep::Merge { pr_number: 3, .. }));
     }
 
+    #[test]
+    fn test_create_merge_plan_updates_behind_and_retargeted_branches() {
+        let graph = make_linear_stack(&["feat-a", "feat-b"]);
+        let analysis = analyze_submission(&graph, Some("feat-b")).unwrap();
+
+        let mut behind = make_mergeable_pr_info_with_base("feat-a", 1, "Add feature A", "main");
+        behind.details.is_behind = true;
+        let pr_info = HashMap::from([
+            ("feat-a".to_string(), behind),
+            (
+                "feat-b".to_string(),
+                make_mergeable_pr_info_with_base("feat-b", 2, "Add feature B", "feat-a"),
+            ),
+        ]);
+        let options = MergePlanOptions {
+            update_branch: true,
+            ..MergePlanOptions::default()
+        };
+
+        let plan = create_merge_plan(&analysis, &pr_info, &options, "main");
+
+        let steps: Vec<String> = plan.steps.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            steps,
+            vec![
+                "update PR #1 from its base",
+                "merge PR #1: Add feature A",
+                "retarget PR #2: feat-a → main",
+                "update PR #2 from its base",
+                "merge PR #2: Add feature B",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_create_merge_plan_behind_branch_is_uncertain_without_update() {
+        let graph = make_linear_stack(&["feat-a"]);
+        let analysis = analyze_submission(&graph, Some("feat-a")).unwrap();
+
+        let mut behind = make_mergeable_pr_info("feat-a", 1, "Add feature A");
+        behind.details.is_behind = true;
+        let pr_info = HashMap::from([("feat-a".to_string(), behind)]);
+
+        let plan = create_merge_plan(&analysis, &pr_info, &MergePlanOptions::default(), "main");
+
+        assert_eq!(plan.steps.len(), 1);
+        assert!(matches!(
+            &plan.steps[0],
+            MergeStep::Merge { confidence: MergeConfidence::Uncertain(reason), .. }
+                if reason.contains("--update-branch")
+        ));
+    }
+
     #[test]
     fn test_create_merge_plan_blocked_pr_stops_chain() {
         let graph = make_linear_stack(&["feat-a", "feat-b", "feat-c"]);
@@ -1073,6 +1528,7 @@ mod merge_plan_test {
         // Only merge up to feat-b
         let options = MergePlanOptions {
             target_bookmark: Some("feat-b".to_string()),
+            ..MergePlanOptions::default()
         };
         let plan = create_merge_plan(&analysis, &pr_info, &options, "main");
 
@@ -1082,6 +1538,33 @@ mod merge_plan_test {
         assert_eq!(plan.rebase_target, Some("feat-c".to_string()));
     }
 
+    #[test]
+    fn test_create_merge_plan_with_limit() {
+        let graph = make_linear_stack(&["feat-a", "feat-b", "feat-c"]);
+        let analysis = analyze_submission(&graph, Some("feat-c")).unwrap();
+
+        let mut pr_info = HashMap::new();
+        // No PR for feat-a, so the limit counts from feat-b
+        pr_info.insert(
+            "feat-b".to_string(),
+            make_mergeable_pr_info("feat-b", 2, "Add feature B"),
+        );
+        pr_info.insert(
+            "feat-c".to_string(),
+            make_mergeable_pr_info("feat-c", 3, "Add feature C"),
+        );
+
+        let options = MergePlanOptions {
+            limit: Some(1),
+            ..MergePlanOptions::default()
+        };
+        let plan = create_merge_plan(&analysis, &pr_info, &options, "main");
+
+        assert_eq!(plan.merge_count(), 1);
+        assert_eq!(plan.bookmarks_to_clear, vec!["feat-b"]);
+        assert_eq!(plan.rebase_target, Some("feat-c".to_string()));
+    }
+
     #[test]
     fn test_create_merge_plan_empty_when_no_prs() {
         let graph = make_linear_stack(&["feat-a", "feat-b"]);
@@ -1139,6 +1622,24 @@ mod merge_plan_test {
         assert!(matches!(&plan.steps[0], MergeStep::Skip { reasons, .. } if reasons.contains(&"PR is a draft".to_string())));
     }
 
+    #[test]
+    fn test_create_merge_plan_unresolved_thread_blocks() {
+        let graph = make_linear_stack(&["feat-a"]);
+        let analysis = analyze_submission(&graph, Some("feat-a")).unwrap();
+
+        let mut pr_info = HashMap::new();
+        // Approved and green, but the base branch requires resolved conversations
+        let mut info = make_mergeable_pr_info("feat-a", 1, "Add feature A");
+        info.readiness.blocking_reasons = vec!["1 unresolved review thread".to_string()];
+        pr_info.insert("feat-a".to_string(), info);
+
+        let plan = create_merge_plan(&analysis, &pr_info, &MergePlanOptions::default(), "main");
+
+        assert!(plan.is_empty());
+        assert!(!plan.has_actionable);
+        assert!(matches!(&plan.steps[0], MergeStep::Skip { reasons, .. } if reasons.contains(&"1 unresolved review thread".to_string())));
+    }
+
     #[test]
     fn test_create_merge_plan_not_approved_blocks() {
         let graph = make_linear_stack(&["feat-a"]);
@@ -1163,14 +1664,28 @@ mod merge_plan_test {
 
         let mut pr_info = HashMap::new();
         let mut info = make_mergeable_pr_info("feat-a", 1, "Add feature A");
-        info.readiness.ci_passed = false;
-        info.readiness.blocking_reasons = vec!["CI not passing".to_string()];
+        info.readiness.checks = vec![
+            make_check("build", CheckState::Failure, true),
+            make_check("windows-test", CheckState::Failure, true),
+            make_check("lint", CheckState::Pending, true),
+            // Optional checks don't block
+            make_check("coverage", CheckState::Failure, false),
+        ];
         pr_info.insert("feat-a".to_string(), info);
 
         let plan = create_merge_plan(&analysis, &pr_info, &MergePlanOptions::default(), "main");
 
         assert!(plan.is_empty());
-        assert!(matches!(&plan.steps[0], MergeStep::Skip { reasons, .. } if reasons.contains(&"CI not passing".to_string())));
+        let MergeStep::Skip { reasons, .. } = &plan.steps[0] else {
+            panic!("expected skip step");
+        };
+        assert_eq!(
+            reasons,
+            &vec![
+                "CI failing: build, windows-test".to_string(),
+                "CI still running: lint".to_string(),
+            ]
+        );
     }
 
     #[test]
@@ -1287,7 +1802,9 @@ mod merge_plan_test {
         // Unit tests for is_blocked() with various combinations
         let base = MergeReadiness {
             is_approved: true,
-            ci_passed: true,
+            approvals_required: None,
+            approvals_received: None,
+            checks: vec![],
             is_mergeable: Some(true),
             is_draft: false,
             blocking_reasons: vec![],
@@ -1300,14 +1817,27 @@ mod merge_plan_test {
         r.is_approved = false;
         assert!(r.is_blocked());
 
-        // CI failing blocks
+        // Required check failing blocks
         let mut r = base.clone();
-        r.ci_passed = false;
+        r.checks = vec![make_check("build", CheckState::Failure, true)];
         assert!(r.is_blocked());
 
-        // Conflicts block
+        // Required check still running blocks
         let mut r = base.clone();
-        r.is_mergeable = Some(false);
+        r.checks = vec![make_check("build", CheckState::Pending, true)];
+        assert!(r.is_blocked());
+
+        // Optional check failing and skipped checks do not block
+        let mut r = base.clone();
+        r.checks = vec![
+            make_check("coverage", CheckState::Failure, false),
+            make_check("deploy", CheckState::Skipped, true),
+        ];
+        assert!(!r.is_blocked());
+
+        // Conflicts block
+        let mut r = base.clone();
+        r.is_mergeable = Some(false);
         assert!(r.is_blocked());
 
         // Unknown does NOT block
@@ -1326,7 +1856,9 @@ mod merge_plan_test {
         // Unit tests for uncertainty() method
         let mut r = MergeReadiness {
             is_approved: true,
-            ci_passed: true,
+            approvals_required: None,
+            approvals_received: None,
+            checks: vec![],
             is_mergeable: None,
             is_draft: false,
             blocking_reasons: vec![],
@@ -1345,7 +1877,7 @@ mod merge_plan_test {
     // Retarget step generation tests
     // =========================================================================
 
-    /// Helper to create a PrInfo with a specific base_ref (for retarget testing)
+    /// Helper to create a `PrInfo` with a specific `base_ref` (for retarget testing)
     fn make_mergeable_pr_info_with_base(
         bookmark: &str,
         pr_number: u64,
@@ -1362,12 +1894,16 @@ mod merge_plan_test {
                 is_draft: false,
                 mergeable: Some(true),
                 head_ref: bookmark.to_string(),
+                head_sha: format!("sha-{bookmark}"),
                 base_ref: base_ref.to_string(),
                 html_url: format!("https://github.com/test/repo/pull/{pr_number}"),
+                is_behind: false,
             },
             readiness: MergeReadiness {
                 is_approved: true,
-                ci_passed: true,
+                approvals_required: None,
+                approvals_received: None,
+                checks: vec![],
                 is_mergeable: Some(true),
                 is_draft: false,
                 blocking_reasons: vec![],
@@ -1525,6 +2061,77 @@ mod merge_plan_test {
         assert!(matches!(&plan.steps[0], MergeStep::Merge { pr_number: 1, .. }));
         assert!(matches!(&plan.steps[1], MergeStep::Merge { pr_number: 2, .. }));
     }
+
+    // =========================================================================
+    // Auto-merge plan tests
+    // =========================================================================
+
+    #[test]
+    fn test_auto_merge_plan_waits_for_pending_checks() {
+        let graph = make_linear_stack(&["feat-a"]);
+        let analysis = analyze_submission(&graph, Some("feat-a")).unwrap();
+
+        let mut info = make_mergeable_pr_info("feat-a", 1, "Add feature A");
+        info.readiness.checks = vec![make_check("build", CheckState::Pending, true)];
+        let pr_info = HashMap::from([("feat-a".to_string(), info)]);
+
+        let plan = create_auto_merge_plan(&analysis, &pr_info, "main", MergeMethod::Squash);
+        assert_eq!(plan.merge_count(), 1);
+        match &plan.steps[0] {
+            MergeStep::Merge { confidence, .. } => assert_eq!(
+                confidence,
+                &MergeConfidence::Uncertain("waiting for build".to_string())
+            ),
+            _ => panic!("Expected Merge step"),
+        }
+        // Nothing is merged yet, so nothing is cleaned up
+        assert!(plan.bookmarks_to_clear.is_empty());
+    }
+
+    #[test]
+    fn test_auto_merge_plan_stops_at_stacked_pr() {
+        let graph = make_linear_stack(&["feat-a", "feat-b"]);
+        let analysis = analyze_submission(&graph, Some("feat-b")).unwrap();
+
+        let pr_info = HashMap::from([
+            (
+                "feat-a".to_string(),
+                make_mergeable_pr_info_with_base("feat-a", 1, "Add feature A", "main"),
+            ),
+            (
+                "feat-b".to_string(),
+                make_mergeable_pr_info_with_base("feat-b", 2, "Add feature B", "feat-a"),
+            ),
+        ]);
+
+        let plan = create_auto_merge_plan(&analysis, &pr_info, "main", MergeMethod::Squash);
+        assert_eq!(plan.steps.len(), 2);
+        assert!(matches!(&plan.steps[0], MergeStep::Merge { pr_number: 1, .. }));
+        match &plan.steps[1] {
+            MergeStep::Skip {
+                pr_number, reasons, ..
+            } => {
+                assert_eq!(*pr_number, 2);
+                assert!(reasons[0].contains("Based on feat-a"));
+            }
+            _ => panic!("Expected Skip step"),
+        }
+        assert_eq!(plan.rebase_target.as_deref(), Some("feat-b"));
+    }
+
+    #[test]
+    fn test_auto_merge_plan_skips_failing_checks() {
+        let graph = make_linear_stack(&["feat-a"]);
+        let analysis = analyze_submission(&graph, Some("feat-a")).unwrap();
+
+        let mut info = make_mergeable_pr_info("feat-a", 1, "Add feature A");
+        info.readiness.checks = vec![make_check("build", CheckState::Failure, true)];
+        let pr_info = HashMap::from([("feat-a".to_string(), info)]);
+
+        let plan = create_auto_merge_plan(&analysis, &pr_info, "main", MergeMethod::Squash);
+        assert!(plan.is_empty());
+        assert!(matches!(&plan.steps[0], MergeStep::Skip { .. }));
+    }
 }
 
 mod merge_execution_test {
@@ -1545,6 +2152,7 @@ mod merge_execution_test {
                 bookmark: "feat-a".to_string(),
                 pr_number: 1,
                 pr_title: "Feature A".to_string(),
+                pr_body: None,
                 method: MergeMethod::Squash,
                 confidence: MergeConfidence::Uncertain(
                     "Merge status unknown (GitHub still computing)".to_string(),
@@ -1565,6 +2173,33 @@ mod merge_execution_test {
         assert!(!result.was_uncertain); // Only set on failure
     }
 
+    #[tokio::test]
+    async fn test_merge_uses_planned_details_without_refetch() {
+        let mock = MockPlatformService::with_config(github_config());
+        mock.setup_mergeable_pr(1, "feat-a", "Feature A");
+
+        let plan = MergePlan {
+            steps: vec![MergeStep::Merge {
+                bookmark: "feat-a".to_string(),
+                pr_number: 1,
+                pr_title: "Feature A".to_string(),
+                pr_body: Some("PR body".to_string()),
+                method: MergeMethod::Squash,
+                confidence: MergeConfidence::Certain,
+            }],
+            bookmarks_to_clear: vec!["feat-a".to_string()],
+            rebase_target: None,
+            has_actionable: true,
+            trunk_branch: "main".to_string(),
+        };
+
+        let result = execute_merge(&plan, &mock, &NoopProgress).await.unwrap();
+
+        assert!(result.is_success());
+        mock.assert_merge_called(1);
+        assert!(mock.get_pr_details_calls().is_empty());
+    }
+
     #[tokio::test]
     async fn test_merge_uncertain_pr_fails_sets_was_uncertain() {
         let mock = MockPlatformService::with_config(github_config());
@@ -1584,6 +2219,7 @@ mod merge_execution_test {
                 bookmark: "feat-a".to_string(),
                 pr_number: 1,
                 pr_title: "Feature A".to_string(),
+                pr_body: None,
                 method: MergeMethod::Squash,
                 confidence: MergeConfidence::Uncertain(
                     "Merge status unknown".to_string(),
@@ -1624,6 +2260,7 @@ mod merge_execution_test {
                 bookmark: "feat-a".to_string(),
                 pr_number: 1,
                 pr_title: "Feature A".to_string(),
+                pr_body: None,
                 method: MergeMethod::Squash,
                 confidence: MergeConfidence::Certain, // Certain, not uncertain
             }],
@@ -1655,6 +2292,7 @@ mod merge_execution_test {
                     bookmark: "feat-a".to_string(),
                     pr_number: 1,
                     pr_title: "Feature A".to_string(),
+                    pr_body: None,
                     method: MergeMethod::Squash,
                     confidence: MergeConfidence::Certain,
                 },
@@ -1668,6 +2306,7 @@ mod merge_execution_test {
                     bookmark: "feat-b".to_string(),
                     pr_number: 2,
                     pr_title: "Feature B".to_string(),
+                    pr_body: None,
                     method: MergeMethod::Squash,
                     confidence: MergeConfidence::Certain,
                 },
@@ -1689,6 +2328,39 @@ mod merge_execution_test {
         mock.assert_update_base_called(2, "main");
     }
 
+    #[tokio::test]
+    async fn test_execute_merge_updates_branch_before_merging() {
+        let mock = MockPlatformService::with_config(github_config());
+        mock.setup_mergeable_pr(1, "feat-a", "Feature A");
+
+        let plan = MergePlan {
+            steps: vec![
+                MergeStep::UpdateBranch {
+                    bookmark: "feat-a".to_string(),
+                    pr_number: 1,
+                },
+                MergeStep::Merge {
+                    bookmark: "feat-a".to_string(),
+                    pr_number: 1,
+                    pr_title: "Feature A".to_string(),
+                    pr_body: None,
+                    method: MergeMethod::Squash,
+                    confidence: MergeConfidence::Certain,
+                },
+            ],
+            bookmarks_to_clear: vec!["feat-a".to_string()],
+            rebase_target: None,
+            has_actionable: true,
+            trunk_branch: "main".to_string(),
+        };
+
+        let result = execute_merge(&plan, &mock, &NoopProgress).await.unwrap();
+
+        assert!(result.is_success());
+        assert_eq!(mock.get_update_branch_calls(), vec![1]);
+        mock.assert_merge_called(1);
+    }
+
     #[tokio::test]
     async fn test_execute_merge_stops_on_retarget_failure() {
         // Test that retarget failure stops execution
@@ -1704,6 +2376,7 @@ mod merge_execution_test {
                     bookmark: "feat-a".to_string(),
                     pr_number: 1,
                     pr_title: "Feature A".to_string(),
+                    pr_body: None,
                     method: MergeMethod::Squash,
                     confidence: MergeConfidence::Certain,
                 },
@@ -1717,6 +2390,7 @@ mod merge_execution_test {
                     bookmark: "feat-b".to_string(),
                     pr_number: 2,
                     pr_title: "Feature B".to_string(),
+                    pr_body: None,
                     method: MergeMethod::Squash,
                     confidence: MergeConfidence::Certain,
                 },
@@ -1741,3 +2415,849 @@ mod merge_execution_test {
         assert_eq!(mock.merge_call_count(), 1);
     }
 }
+
+mod throttle_test {
+    use crate::common::{github_config, make_pr, MockPlatformService};
+    use jj_ryu::config::HttpConfig;
+    use jj_ryu::platform::{PlatformService, ThrottledPlatformService};
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_throttle_reports_configured_concurrency() {
+        let mock = MockPlatformService::with_config(github_config());
+        let config = HttpConfig {
+            max_concurrency: 0,
+            min_delay_between_writes: 0,
+            ..HttpConfig::default()
+        };
+        let throttled = ThrottledPlatformService::new(Box::new(mock), &config);
+
+        // Zero is clamped so requests can still make progress
+        assert_eq!(throttled.max_concurrency(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_throttle_spaces_out_writes() {
+        let mock = MockPlatformService::with_config(github_config());
+        let config = HttpConfig {
+            max_concurrency: 4,
+            min_delay_between_writes: 30,
+            ..HttpConfig::default()
+        };
+        let throttled = ThrottledPlatformService::new(Box::new(mock), &config);
+
+        let start = Instant::now();
+        throttled.create_pr_comment(1, "a").await.unwrap();
+        throttled.create_pr_comment(2, "b").await.unwrap();
+        throttled.create_pr_comment(3, "c").await.unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(60));
+    }
+
+    #[tokio::test]
+    async fn test_throttle_finds_prs_per_branch_without_batching() {
+        let mock = MockPlatformService::with_config(github_config());
+        mock.set_find_pr_response("feat-b", Some(make_pr(2, "feat-b", "feat-a")));
+        let throttled = ThrottledPlatformService::new(Box::new(mock), &HttpConfig::default());
+
+        let branches = vec!["feat-a".to_string(), "feat-b".to_string()];
+        let prs = throttled.find_existing_prs(&branches).await.unwrap();
+
+        assert!(!throttled.batches_pr_lookups());
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs["feat-b"].number, 2);
+    }
+}
+
+mod retry_test {
+    use jj_ryu::config::HttpConfig;
+    use jj_ryu::error::Error;
+    use jj_ryu::platform::{GitLabService, PlatformService, RetryingPlatformService};
+    use mockito::Matcher;
+
+    fn retrying(server: &mockito::Server, max_retries: u32) -> impl PlatformService {
+        let gitlab = GitLabService::with_api_url(
+            "token".to_string(),
+            "acme".to_string(),
+            "widgets".to_string(),
+            &format!("{}/api/v4", server.url()),
+        )
+        .unwrap();
+        let config = HttpConfig {
+            max_retries,
+            retry_backoff: 1,
+            ..HttpConfig::default()
+        };
+        RetryingPlatformService::new(gitlab, &config)
+    }
+
+    // The server must outlive the requests, not just the mock setup
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_retries_server_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let failing = server
+            .mock("GET", Matcher::Any)
+            .with_status(502)
+            .expect(2)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("GET", Matcher::Any)
+            .with_body("[]")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let pr = retrying(&server, 3).find_existing_pr("feat-a").await.unwrap();
+
+        assert!(pr.is_none());
+        failing.assert_async().await;
+        ok.assert_async().await;
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let mut server = mockito::Server::new_async().await;
+        let failing = server
+            .mock("GET", Matcher::Any)
+            .with_status(503)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let err = retrying(&server, 2)
+            .find_existing_pr("feat-a")
+            .await
+            .unwrap_err();
+
+        match err {
+            Error::RetriesExhausted {
+                attempts, source, ..
+            } => {
+                assert_eq!(attempts, 3);
+                assert!(matches!(*source, Error::Http(_)));
+            }
+            other => panic!("expected RetriesExhausted, got {other:?}"),
+        }
+        failing.assert_async().await;
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_waits_out_rate_limits() {
+        let mut server = mockito::Server::new_async().await;
+        let limited = server
+            .mock("GET", Matcher::Any)
+            .with_status(429)
+            .with_header("Retry-After", "0")
+            .expect(1)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("GET", Matcher::Any)
+            .with_body("[]")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let pr = retrying(&server, 3).find_existing_pr("feat-a").await.unwrap();
+
+        assert!(pr.is_none());
+        limited.assert_async().await;
+        ok.assert_async().await;
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_does_not_retry_client_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let not_found = server
+            .mock("GET", Matcher::Any)
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let err = retrying(&server, 3)
+            .find_existing_pr("feat-a")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::GitLabApi(_)));
+        not_found.assert_async().await;
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_does_not_resend_create_after_ambiguous_failure() {
+        let mut server = mockito::Server::new_async().await;
+        // The MR may have been created before the gateway gave up
+        let failing = server
+            .mock("POST", Matcher::Any)
+            .with_status(502)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let err = retrying(&server, 3)
+            .create_pr("feat-a", "main", "Add feature")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::Http(_)));
+        failing.assert_async().await;
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_retries_rate_limited_create() {
+        let mut server = mockito::Server::new_async().await;
+        let limited = server
+            .mock("POST", Matcher::Any)
+            .with_status(429)
+            .with_header("Retry-After", "0")
+            .expect(1)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("POST", Matcher::Any)
+            .with_body(
+                r#"{"iid": 7, "web_url": "https://gitlab.com/acme/widgets/-/merge_requests/7",
+                    "source_branch": "feat-a", "target_branch": "main", "title": "Add feature"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let pr = retrying(&server, 3)
+            .create_pr("feat-a", "main", "Add feature")
+            .await
+            .unwrap();
+
+        assert_eq!(pr.number, 7);
+        limited.assert_async().await;
+        ok.assert_async().await;
+    }
+}
+
+mod token_validation_test {
+    use jj_ryu::auth::{
+        GitLabTokenKind, github_token_status, gitlab_token_status, validate_github_token,
+        validate_gitlab_token,
+    };
+    use jj_ryu::error::Error;
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_github_token_needs_repo_scope() {
+        let mut server = mockito::Server::new_async().await;
+        let _user = server
+            .mock("GET", "/user")
+            .with_header("x-oauth-scopes", "read:org, gist")
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let err = validate_github_token("token", &server.url())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::Auth(ref msg) if msg.contains("read:org, gist")));
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_github_token_without_scopes_passes() {
+        let mut server = mockito::Server::new_async().await;
+        let _user = server
+            .mock("GET", "/user")
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        validate_github_token("token", &server.url()).await.unwrap();
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_gitlab_token_needs_api_scope() {
+        let mut server = mockito::Server::new_async().await;
+        let _token = server
+            .mock("GET", "/api/v4/personal_access_tokens/self")
+            .with_body(r#"{"scopes": ["read_api", "read_repository"]}"#)
+            .create_async()
+            .await;
+
+        let api_url = format!("{}/api/v4", server.url());
+        let err = validate_gitlab_token("token", GitLabTokenKind::Personal, &api_url)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::Auth(ref msg) if msg.contains("`api` scope")));
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_github_token_status() {
+        let mut server = mockito::Server::new_async().await;
+        let _user = server
+            .mock("GET", "/user")
+            .with_header("x-oauth-scopes", "repo, read:org")
+            .with_header("x-ratelimit-remaining", "4990")
+            .with_header("x-ratelimit-limit", "5000")
+            .with_body(r#"{"login": "octocat"}"#)
+            .create_async()
+            .await;
+
+        let status = github_token_status("token", &server.url()).await.unwrap();
+
+        assert_eq!(status.login, "octocat");
+        assert_eq!(
+            status.scopes,
+            Some(vec!["repo".to_string(), "read:org".to_string()])
+        );
+        assert_eq!(status.rate_limit_remaining, Some(4990));
+        assert_eq!(status.rate_limit, Some(5000));
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_gitlab_token_status_without_inspectable_token() {
+        let mut server = mockito::Server::new_async().await;
+        let _user = server
+            .mock("GET", "/api/v4/user")
+            .with_header("ratelimit-remaining", "1999")
+            .with_header("ratelimit-limit", "2000")
+            .with_body(r#"{"username": "tanuki"}"#)
+            .create_async()
+            .await;
+        let _token = server
+            .mock("GET", "/api/v4/personal_access_tokens/self")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let api_url = format!("{}/api/v4", server.url());
+        let status = gitlab_token_status("token", GitLabTokenKind::Personal, &api_url)
+            .await
+            .unwrap();
+
+        assert_eq!(status.login, "tanuki");
+        assert_eq!(status.scopes, None);
+        assert_eq!(status.rate_limit_remaining, Some(1999));
+        assert_eq!(status.rate_limit, Some(2000));
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_gitlab_oauth_token_sent_as_bearer() {
+        let mut server = mockito::Server::new_async().await;
+        let user = server
+            .mock("GET", "/api/v4/user")
+            .match_header("authorization", "Bearer oauth-token")
+            .match_header("private-token", mockito::Matcher::Missing)
+            .with_body(r#"{"username": "tanuki"}"#)
+            .create_async()
+            .await;
+
+        let api_url = format!("{}/api/v4", server.url());
+        validate_gitlab_token("oauth-token", GitLabTokenKind::OAuth, &api_url)
+            .await
+            .unwrap();
+        let status = gitlab_token_status("oauth-token", GitLabTokenKind::OAuth, &api_url)
+            .await
+            .unwrap();
+
+        user.assert_async().await;
+        assert_eq!(status.login, "tanuki");
+        assert_eq!(status.scopes, None);
+    }
+}
+
+mod gitlab_approval_rules_test {
+    use jj_ryu::platform::{GitLabService, PlatformService};
+    use jj_ryu::types::{PrState, PullRequestDetails};
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_readiness_reports_required_approval_count() {
+        let mut server = mockito::Server::new_async().await;
+        let _approvals = server
+            .mock("GET", "/api/v4/projects/acme%2Fwidgets/merge_requests/7/approvals")
+            .with_body(r#"{"approved": false, "approved_by": []}"#)
+            .create_async()
+            .await;
+        let _approval_state = server
+            .mock(
+                "GET",
+                "/api/v4/projects/acme%2Fwidgets/merge_requests/7/approval_state",
+            )
+            .with_body(
+                r#"{"rules": [
+                    {"approvals_required": 2, "approved_by": [{"username": "alice"}, {"username": "bob"}, {"username": "carol"}]},
+                    {"approvals_required": 1, "approved_by": []}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let gitlab = GitLabService::with_api_url(
+            "token".to_string(),
+            "acme".to_string(),
+            "widgets".to_string(),
+            &format!("{}/api/v4", server.url()),
+        )
+        .unwrap();
+        let details = PullRequestDetails {
+            number: 7,
+            title: "MR for feat-a".to_string(),
+            body: None,
+            state: PrState::Open,
+            is_draft: false,
+            mergeable: Some(true),
+            head_ref: "feat-a".to_string(),
+            head_sha: "sha-feat-a".to_string(),
+            base_ref: "main".to_string(),
+            html_url: "https://gitlab.com/acme/widgets/-/merge_requests/7".to_string(),
+            is_behind: false,
+        };
+
+        let readiness = gitlab.check_merge_readiness(&details).await.unwrap();
+
+        assert!(!readiness.is_approved);
+        assert_eq!(readiness.approvals_required, Some(3));
+        assert_eq!(readiness.approvals_received, Some(2));
+        assert_eq!(
+            readiness.blocking_reasons,
+            vec!["Not approved (2 of 3 required approvals)"]
+        );
+    }
+}
+
+mod gitlab_request_review_test {
+    use jj_ryu::auth::GitLabTokenKind;
+    use jj_ryu::platform::{GitLabService, PlatformService};
+    use mockito::Matcher;
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_request_review_adds_new_and_rerequests_existing_reviewers() {
+        let mut server = mockito::Server::new_async().await;
+        let _mr = server
+            .mock("GET", "/api/v4/projects/acme%2Fwidgets/merge_requests/7")
+            .with_body(r#"{"reviewers": [{"id": 11, "username": "alice"}]}"#)
+            .create_async()
+            .await;
+        let _alice = server
+            .mock("GET", "/api/v4/users")
+            .match_query(Matcher::UrlEncoded("username".into(), "alice".into()))
+            .with_body(r#"[{"id": 11}]"#)
+            .create_async()
+            .await;
+        let _bob = server
+            .mock("GET", "/api/v4/users")
+            .match_query(Matcher::UrlEncoded("username".into(), "bob".into()))
+            .with_body(r#"[{"id": 12}]"#)
+            .create_async()
+            .await;
+        let add_bob = server
+            .mock("PUT", "/api/v4/projects/acme%2Fwidgets/merge_requests/7")
+            .match_body(Matcher::Json(serde_json::json!({ "reviewer_ids": [11, 12] })))
+            .with_body("{}")
+            .create_async()
+            .await;
+        let rerequest_alice = server
+            .mock("POST", "/api/graphql")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "variables": { "iid": "7", "userId": "gid://gitlab/User/11" }
+            })))
+            .with_body(r#"{"data": {"mergeRequestReviewerRereview": {"errors": []}}}"#)
+            .create_async()
+            .await;
+
+        let gitlab = GitLabService::with_api_url(
+            "token".to_string(),
+            "acme".to_string(),
+            "widgets".to_string(),
+            &format!("{}/api/v4", server.url()),
+        )
+        .unwrap();
+
+        gitlab
+            .request_review(7, &["alice".to_string(), "bob".to_string()])
+            .await
+            .unwrap();
+
+        add_bob.assert_async().await;
+        rerequest_alice.assert_async().await;
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_oauth_token_sent_as_bearer() {
+        let mut server = mockito::Server::new_async().await;
+        let mr = server
+            .mock("GET", "/api/v4/projects/acme%2Fwidgets/merge_requests/7")
+            .match_header("authorization", "Bearer oauth-token")
+            .match_header("private-token", Matcher::Missing)
+            .with_body(r#"{"reviewers": []}"#)
+            .create_async()
+            .await;
+        let _users = server
+            .mock("GET", "/api/v4/users")
+            .match_query(Matcher::Any)
+            .match_header("authorization", "Bearer oauth-token")
+            .with_body(r#"[{"id": 11}]"#)
+            .create_async()
+            .await;
+        let add = server
+            .mock("PUT", "/api/v4/projects/acme%2Fwidgets/merge_requests/7")
+            .match_header("authorization", "Bearer oauth-token")
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let gitlab = GitLabService::with_api_url(
+            "oauth-token".to_string(),
+            "acme".to_string(),
+            "widgets".to_string(),
+            &format!("{}/api/v4", server.url()),
+        )
+        .unwrap()
+        .with_token_kind(GitLabTokenKind::OAuth);
+
+        gitlab.request_review(7, &["alice".to_string()]).await.unwrap();
+
+        mr.assert_async().await;
+        add.assert_async().await;
+    }
+}
+
+mod device_flow_test {
+    use chrono::{Duration, Utc};
+    use jj_ryu::auth::{DeviceFlow, OAuthToken};
+    use jj_ryu::error::Error;
+    use mockito::Matcher;
+
+    const DEVICE_CODE: &str = r#"{
+        "device_code": "dev-123",
+        "user_code": "ABCD-1234",
+        "verification_uri": "https://github.com/login/device",
+        "expires_in": 900,
+        "interval": 0
+    }"#;
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_github_device_flow_returns_token() {
+        let mut server = mockito::Server::new_async().await;
+        let _code = server
+            .mock("POST", "/login/device/code")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("client_id".into(), "app-id".into()),
+                Matcher::UrlEncoded("scope".into(), "repo".into()),
+            ]))
+            .with_body(DEVICE_CODE)
+            .create_async()
+            .await;
+        let _token = server
+            .mock("POST", "/login/oauth/access_token")
+            .match_body(Matcher::UrlEncoded("device_code".into(), "dev-123".into()))
+            .with_body(r#"{"access_token": "gho_token", "token_type": "bearer"}"#)
+            .create_async()
+            .await;
+
+        let flow = DeviceFlow::github(&server.url(), "app-id").unwrap();
+        let code = flow.request_code().await.unwrap();
+        assert_eq!(code.user_code, "ABCD-1234");
+
+        let token = flow.poll_token(&code).await.unwrap();
+        assert_eq!(token.access_token, "gho_token");
+        assert_eq!(token.refresh_token, None);
+        assert_eq!(token.expires_at, None);
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_gitlab_device_flow_keeps_refresh_token() {
+        let mut server = mockito::Server::new_async().await;
+        let _code = server
+            .mock("POST", "/oauth/authorize_device")
+            .with_body(DEVICE_CODE)
+            .create_async()
+            .await;
+        let _token = server
+            .mock("POST", "/oauth/token")
+            .match_body(Matcher::UrlEncoded("device_code".into(), "dev-123".into()))
+            .with_body(
+                r#"{"access_token": "glat", "refresh_token": "glrt", "expires_in": 7200, "token_type": "Bearer"}"#,
+            )
+            .create_async()
+            .await;
+
+        let flow = DeviceFlow::gitlab(&server.url(), "app-id").unwrap();
+        let code = flow.request_code().await.unwrap();
+        let token = flow.poll_token(&code).await.unwrap();
+
+        assert_eq!(token.access_token, "glat");
+        assert_eq!(token.refresh_token.as_deref(), Some("glrt"));
+        assert_eq!(token.client_id, "app-id");
+        assert!(!token.expires_soon());
+        assert_eq!(OAuthToken::from_stored(&token.to_stored()), Some(token));
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_gitlab_refresh_replaces_token() {
+        let mut server = mockito::Server::new_async().await;
+        let refresh = server
+            .mock("POST", "/oauth/token")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("grant_type".into(), "refresh_token".into()),
+                Matcher::UrlEncoded("refresh_token".into(), "glrt-old".into()),
+                Matcher::UrlEncoded("client_id".into(), "app-id".into()),
+            ]))
+            .with_body(r#"{"access_token": "glat-new", "refresh_token": "glrt-new", "expires_in": 7200}"#)
+            .create_async()
+            .await;
+
+        let expired = OAuthToken {
+            access_token: "glat-old".to_string(),
+            refresh_token: Some("glrt-old".to_string()),
+            expires_at: Some(Utc::now() - Duration::minutes(1)),
+            client_id: "app-id".to_string(),
+        };
+        assert!(expired.expires_soon());
+
+        let flow = DeviceFlow::gitlab(&server.url(), "app-id").unwrap();
+        let token = flow.refresh(&expired).await.unwrap();
+
+        refresh.assert_async().await;
+        assert_eq!(token.access_token, "glat-new");
+        assert_eq!(token.refresh_token.as_deref(), Some("glrt-new"));
+        assert!(!token.expires_soon());
+    }
+
+    #[test]
+    fn test_personal_token_is_not_an_oauth_token() {
+        assert_eq!(OAuthToken::from_stored("glpat-abc123"), None);
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_gitlab_device_flow_denied() {
+        let mut server = mockito::Server::new_async().await;
+        let _code = server
+            .mock("POST", "/oauth/authorize_device")
+            .match_body(Matcher::UrlEncoded("scope".into(), "api".into()))
+            .with_body(DEVICE_CODE)
+            .create_async()
+            .await;
+        let _token = server
+            .mock("POST", "/oauth/token")
+            .with_status(400)
+            .with_body(r#"{"error": "access_denied"}"#)
+            .create_async()
+            .await;
+
+        let flow = DeviceFlow::gitlab(&server.url(), "app-id").unwrap();
+        let code = flow.request_code().await.unwrap();
+        let err = flow.poll_token(&code).await.unwrap_err();
+
+        assert!(matches!(err, Error::Auth(ref msg) if msg.contains("denied")));
+    }
+}
+
+mod close_prs_test {
+    use crate::common::{MockPlatformService, github_config, make_pr};
+    use jj_ryu::platform::close_prs;
+
+    #[tokio::test]
+    async fn test_close_prs_continues_past_failures() {
+        let mock = MockPlatformService::with_config(github_config());
+        mock.set_find_pr_response("feat-c", Some(make_pr(3, "feat-c", "feat-b")));
+        mock.set_find_pr_response("feat-b", Some(make_pr(2, "feat-b", "feat-a")));
+        mock.set_find_pr_response("feat-a", Some(make_pr(1, "feat-a", "main")));
+        mock.fail_close_pr(2, "forbidden");
+
+        let branches = vec![
+            "feat-c".to_string(),
+            "feat-b".to_string(),
+            "feat-a".to_string(),
+            "feat-x".to_string(),
+        ];
+        let results = close_prs(&mock, &branches).await;
+
+        // Top of the stack first, and the failure doesn't stop the rest
+        assert_eq!(mock.get_close_pr_calls(), vec![3, 2, 1]);
+        let names: Vec<&str> = results.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["feat-c", "feat-b", "feat-a", "feat-x"]);
+        assert_eq!(results[0].1.as_ref().unwrap(), &Some(3));
+        assert!(
+            results[1]
+                .1
+                .as_ref()
+                .is_err_and(|e| e.to_string().contains("forbidden"))
+        );
+        assert_eq!(results[2].1.as_ref().unwrap(), &Some(1));
+        assert_eq!(results[3].1.as_ref().unwrap(), &None);
+    }
+}
+
+mod prs_details_test {
+    use crate::common::{MockPlatformService, github_config};
+    use jj_ryu::platform::get_prs_details;
+
+    #[tokio::test]
+    async fn test_get_prs_details_fails_per_pr() {
+        let mock = MockPlatformService::with_config(github_config());
+        mock.setup_mergeable_pr(1, "feat-a", "Add a");
+        mock.setup_mergeable_pr(3, "feat-c", "Add c");
+
+        let results = get_prs_details(&mock, &[1, 2, 3]).await;
+
+        // PR #2 has no details configured, which doesn't stop the others
+        let numbers: Vec<u64> = results.iter().map(|(number, _)| *number).collect();
+        assert_eq!(numbers, [1, 2, 3]);
+        assert_eq!(results[0].1.as_ref().unwrap().head_ref, "feat-a");
+        assert!(results[1].1.is_err());
+        assert_eq!(results[2].1.as_ref().unwrap().head_ref, "feat-c");
+    }
+}
+
+mod github_protected_branches_test {
+    use jj_ryu::platform::{GitHubService, PlatformService};
+    use mockito::Matcher;
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_protected_branches_follow_link_header() {
+        let mut server = mockito::Server::new_async().await;
+        let next = format!(
+            "{}/repos/acme/widgets/branches?protected=true&per_page=100&page=2",
+            server.url()
+        );
+        let first = server
+            .mock("GET", "/repos/acme/widgets/branches")
+            .match_query(Matcher::Exact("protected=true&per_page=100".into()))
+            .with_header(
+                "link",
+                &format!(r#"<{next}>; rel="next", <{next}>; rel="last""#),
+            )
+            .with_body(r#"[{"name": "main"}, {"name": "release/1.x"}]"#)
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/repos/acme/widgets/branches")
+            .match_query(Matcher::UrlEncoded("page".into(), "2".into()))
+            .with_body(r#"[{"name": "release/2.x"}]"#)
+            .create_async()
+            .await;
+
+        let service = GitHubService::with_api_url(
+            "token",
+            "acme".to_string(),
+            "widgets".to_string(),
+            &server.url(),
+        )
+        .unwrap();
+        let branches = service.list_protected_branches().await.unwrap();
+
+        first.assert_async().await;
+        second.assert_async().await;
+        assert_eq!(branches, ["main", "release/1.x", "release/2.x"]);
+    }
+}
+
+mod github_find_prs_by_body_test {
+    use jj_ryu::platform::{GitHubService, PlatformService};
+    use serde_json::{Value, json};
+
+    fn user(login: &str) -> Value {
+        let url = format!("https://api.github.com/users/{login}");
+        json!({
+            "login": login,
+            "id": 5001,
+            "node_id": "U_5001",
+            "avatar_url": "https://avatars.githubusercontent.com/u/5001",
+            "gravatar_id": "",
+            "url": url,
+            "html_url": format!("https://github.com/{login}"),
+            "followers_url": format!("{url}/followers"),
+            "following_url": format!("{url}/following{{/other_user}}"),
+            "gists_url": format!("{url}/gists{{/gist_id}}"),
+            "starred_url": format!("{url}/starred{{/owner}}{{/repo}}"),
+            "subscriptions_url": format!("{url}/subscriptions"),
+            "organizations_url": format!("{url}/orgs"),
+            "repos_url": format!("{url}/repos"),
+            "events_url": format!("{url}/events{{/privacy}}"),
+            "received_events_url": format!("{url}/received_events"),
+            "type": "User",
+            "site_admin": false
+        })
+    }
+
+    /// An open PR from `head_owner`'s copy of the repo
+    fn pull(number: u64, head_owner: &str, body: &str) -> Value {
+        json!({
+            "url": format!("https://api.github.com/repos/acme/widgets/pulls/{number}"),
+            "id": 2000 + number,
+            "number": number,
+            "state": "open",
+            "title": format!("PR {number}"),
+            "body": body,
+            "html_url": format!("https://github.com/acme/widgets/pull/{number}"),
+            "head": {
+                "label": format!("{head_owner}:feat-{number}"),
+                "ref": format!("feat-{number}"),
+                "sha": "3f2a9c4e8b7d6a5f4e3d2c1b0a9f8e7d6c5b4a39",
+                "repo": {
+                    "id": 3000 + number,
+                    "name": "widgets",
+                    "url": format!("https://api.github.com/repos/{head_owner}/widgets"),
+                    "owner": user(head_owner)
+                }
+            },
+            "base": { "label": "acme:main", "ref": "main", "sha": "9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d" }
+        })
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_find_prs_by_body_lists_once_and_skips_forks() {
+        let mut server = mockito::Server::new_async().await;
+        let prs = json!([
+            pull(3, "acme", "Adds a\n\n<!-- ryu:change-id:aaa -->"),
+            pull(4, "mallory", "Copied\n\n<!-- ryu:change-id:bbb -->"),
+            pull(5, "acme", "No marker"),
+        ]);
+        let list = server
+            .mock("GET", "/repos/acme/widgets/pulls")
+            .match_query(mockito::Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(prs.to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let service = GitHubService::with_api_url(
+            "token",
+            "acme".to_string(),
+            "widgets".to_string(),
+            &server.url(),
+        )
+        .unwrap();
+        let markers = vec![
+            "<!-- ryu:change-id:aaa -->".to_string(),
+            "<!-- ryu:change-id:bbb -->".to_string(),
+            "<!-- ryu:change-id:ccc -->".to_string(),
+        ];
+        let found = service.find_prs_by_body(&markers).await.unwrap();
+
+        list.assert_async().await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found["<!-- ryu:change-id:aaa -->"].number, 3);
+    }
+}
@@ -0,0 +1,203 @@
+//! Platform services against recorded GitHub/GitLab HTTP traffic
+//!
+//! Each test replays a cassette from `tests/fixtures/cassettes/` through a
+//! local server (see `common::vcr`). To re-record against a real repo:
+//!
+//! ```text
+//! JJ_RYU_VCR_RECORD=1 JJ_RYU_VCR_TOKEN=... JJ_RYU_VCR_REPO=owner/repo \
+//!     cargo test --test vcr_tests github_
+//! ```
+//!
+//! The repo must contain the PRs/MRs and branches each test expects.
+
+mod common;
+
+use common::vcr::{Api, Cassette};
+use jj_ryu::error::Error;
+use jj_ryu::platform::PlatformService;
+use jj_ryu::types::{CheckState, PrReview, PrState, ReviewState};
+
+fn review_states(reviews: &[PrReview]) -> Vec<(&str, ReviewState)> {
+    reviews
+        .iter()
+        .map(|r| (r.reviewer.as_str(), r.state))
+        .collect()
+}
+
+// =============================================================================
+// GitHub
+// =============================================================================
+
+#[tokio::test]
+async fn github_find_existing_pr() {
+    let cassette = Cassette::open(Api::GitHub, "github_find_existing_pr").await;
+    let service = cassette.github();
+
+    let pr = service
+        .find_existing_pr("feat-a")
+        .await
+        .unwrap()
+        .expect("PR should be found");
+    assert_eq!(pr.number, 12);
+    assert_eq!(pr.head_ref, "feat-a");
+    assert_eq!(pr.base_ref, "main");
+    assert_eq!(pr.html_url, "https://github.com/jj-ryu/fixtures/pull/12");
+    assert!(!pr.is_draft);
+    assert!(pr.node_id.is_some());
+
+    assert!(service.find_existing_pr("feat-missing").await.unwrap().is_none());
+
+    cassette.finish().await;
+}
+
+#[tokio::test]
+async fn github_merge_readiness_combines_reviews_statuses_and_check_runs() {
+    let cassette = Cassette::open(Api::GitHub, "github_merge_readiness").await;
+    let service = cassette.github();
+
+    let details = service.get_pr_details(12).await.unwrap();
+    assert_eq!(details.state, PrState::Open);
+    assert_eq!(details.head_sha, "3f2a9c4e8b7d6a5f4e3d2c1b0a9f8e7d6c5b4a39");
+    assert_eq!(details.mergeable, Some(true));
+
+    let readiness = service.check_merge_readiness(&details).await.unwrap();
+    assert!(!readiness.is_approved);
+    assert!(
+        readiness
+            .blocking_reasons
+            .contains(&"Changes requested by bob".to_string())
+    );
+
+    // Commit status "ci/lint" isn't in the branch protection rules
+    let checks: Vec<_> = readiness
+        .checks
+        .iter()
+        .map(|c| (c.name.as_str(), c.state, c.required))
+        .collect();
+    assert_eq!(
+        checks,
+        vec![
+            ("ci/lint", CheckState::Success, false),
+            ("test", CheckState::Failure, true),
+            ("docs", CheckState::Pending, true),
+        ]
+    );
+
+    cassette.finish().await;
+}
+
+#[tokio::test]
+async fn github_publish_pr_uses_graphql_mutation() {
+    let cassette = Cassette::open(Api::GitHub, "github_publish_pr").await;
+    let service = cassette.github();
+
+    let pr = service.publish_pr(12).await.unwrap();
+    assert_eq!(pr.number, 12);
+    assert!(!pr.is_draft);
+    assert_eq!(pr.node_id.as_deref(), Some("PR_kwDOLhF3ks5rAbCd"));
+
+    cassette.finish().await;
+}
+
+#[tokio::test]
+async fn github_create_pr_validation_error() {
+    let cassette = Cassette::open(Api::GitHub, "github_create_pr_conflict").await;
+    let service = cassette.github();
+
+    let err = service
+        .create_pr_with_options("feat-a", "main", "Add feature A", None, false)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Octocrab(_)), "unexpected error: {err}");
+
+    cassette.finish().await;
+}
+
+#[tokio::test]
+async fn github_protected_branches_forbidden() {
+    let cassette = Cassette::open(Api::GitHub, "github_protected_branches_forbidden").await;
+    let service = cassette.github();
+
+    let err = service.list_protected_branches().await.unwrap_err();
+    assert!(err.to_string().contains("403"), "unexpected error: {err}");
+
+    cassette.finish().await;
+}
+
+// =============================================================================
+// GitLab
+// =============================================================================
+
+#[tokio::test]
+async fn gitlab_find_existing_mr() {
+    let cassette = Cassette::open(Api::GitLab, "gitlab_find_existing_mr").await;
+    let service = cassette.gitlab();
+
+    let mr = service
+        .find_existing_pr("feat-a")
+        .await
+        .unwrap()
+        .expect("MR should be found");
+    assert_eq!(mr.number, 7);
+    assert_eq!(mr.base_ref, "main");
+    assert_eq!(
+        mr.html_url,
+        "https://gitlab.com/jj-ryu/fixtures/-/merge_requests/7"
+    );
+
+    assert!(service.find_existing_pr("feat-missing").await.unwrap().is_none());
+
+    cassette.finish().await;
+}
+
+#[tokio::test]
+async fn gitlab_list_reviews_merges_reviewers_and_approvals() {
+    let cassette = Cassette::open(Api::GitLab, "gitlab_list_reviews").await;
+    let service = cassette.gitlab();
+
+    let reviews = service.list_reviews(7).await.unwrap();
+    assert_eq!(
+        review_states(&reviews),
+        vec![
+            ("erin", ReviewState::Approved),
+            ("carol", ReviewState::ChangesRequested),
+        ]
+    );
+
+    cassette.finish().await;
+}
+
+#[tokio::test]
+async fn gitlab_list_checks_reports_pipeline_jobs() {
+    let cassette = Cassette::open(Api::GitLab, "gitlab_list_checks").await;
+    let service = cassette.gitlab();
+
+    let checks = service.list_checks("feat-a").await.unwrap();
+    let checks: Vec<_> = checks
+        .iter()
+        .map(|c| (c.name.as_str(), c.state, c.required))
+        .collect();
+    assert_eq!(
+        checks,
+        vec![
+            ("build", CheckState::Success, true),
+            ("lint", CheckState::Failure, false),
+        ]
+    );
+
+    cassette.finish().await;
+}
+
+#[tokio::test]
+async fn gitlab_unauthorized() {
+    let cassette = Cassette::open(Api::GitLab, "gitlab_unauthorized").await;
+    let service = cassette.gitlab();
+
+    let err = service.find_existing_pr("feat-a").await.unwrap_err();
+    assert!(
+        matches!(&err, Error::GitLabApi(msg) if msg.contains("401")),
+        "unexpected error: {err}"
+    );
+
+    cassette.finish().await;
+}